@@ -0,0 +1,11 @@
+fn main() {
+    // The header of the C interface is only written when it is enabled, cbindgen is an optional dependency
+    #[cfg(feature = "capi")]
+    {
+        println!("cargo:rerun-if-changed=src/capi.rs");
+        let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        cbindgen::generate(&dir)
+            .expect("Could not generate the C header")
+            .write_to_file(std::path::Path::new(&dir).join("include/computational_geometry.h"));
+    }
+}
@@ -0,0 +1,9 @@
+use computational_geometry::math::Vec2;
+
+/// Turns every 2 bytes of the input into a point on a small grid,
+/// so that duplicate and collinear points are generated often.
+pub fn points(data: &[u8]) -> Vec<Vec2> {
+    data.chunks_exact(2)
+        .map(|c| Vec2::new((c[0] as i8) as f32 / 16.0, (c[1] as i8) as f32 / 16.0))
+        .collect()
+}
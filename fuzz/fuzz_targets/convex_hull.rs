@@ -0,0 +1,20 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use computational_geometry::algorithms::{ JarvisMarch, GrahamScan };
+
+mod common;
+
+fuzz_target!(|data: &[u8]| {
+    let points = common::points(data);
+
+    let hull = JarvisMarch::march(points.iter());
+    assert!(hull.len() <= points.len());
+    assert!(hull.iter().all(|&idx| idx < points.len()));
+    for (i, idx) in hull.iter().enumerate() {
+        assert!(!hull[i + 1..].contains(idx), "{} is on the hull twice", idx);
+    }
+
+    let hull = GrahamScan::scan(&points);
+    assert!(hull.len() <= points.len());
+    assert!(hull.iter().all(|&idx| idx < points.len()));
+});
@@ -0,0 +1,51 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use computational_geometry::{
+    algorithms::{ GrahamScan, Operation, hull_positions, polygon_boolean },
+    math::Vec2,
+};
+
+mod common;
+
+/// Sum of the signed areas of the polygons, the holes of the results are clockwise so their areas are negative
+fn area(polygons: &[Vec<Vec2>]) -> f32 {
+    polygons.iter().map(|p| signed_area(p)).sum()
+}
+
+fn signed_area(polygon: &[Vec2]) -> f32 {
+    (0..polygon.len())
+        .map(|i| {
+            let (a, b) = (polygon[i], polygon[(i + 1) % polygon.len()]);
+            a.x * b.y - a.y * b.x
+        })
+        .sum::<f32>() / 2.0
+}
+
+fuzz_target!(|data: &[u8]| {
+    let split = data.first().map(|&b| b as usize).unwrap_or(0);
+    let points = common::points(data.get(1..).unwrap_or_default());
+    let (a, b) = points.split_at(split.min(points.len()));
+
+    // Any polygons, even self-intersecting, must not make the operations panic or loop
+    for &operation in [ Operation::Union, Operation::Intersection, Operation::Difference, Operation::Xor ].iter() {
+        for result in polygon_boolean(a, b, operation) {
+            assert!(result.len() >= 3);
+            assert!(result.iter().all(|p| p.x.is_finite() && p.y.is_finite()));
+        }
+    }
+
+    // The areas of the results on the convex hulls of the points match
+    let (a, b) = (hull_positions(a, &GrahamScan::scan(a)), hull_positions(b, &GrahamScan::scan(b)));
+    let (area_a, area_b) = (signed_area(&a).abs(), signed_area(&b).abs());
+    if area_a < 0.01 || area_b < 0.01 {
+        return;
+    }
+    let union = area(&polygon_boolean(&a, &b, Operation::Union));
+    let intersection = area(&polygon_boolean(&a, &b, Operation::Intersection));
+    let difference = area(&polygon_boolean(&a, &b, Operation::Difference));
+    let xor = area(&polygon_boolean(&a, &b, Operation::Xor));
+    let tolerance = 1e-3 * (area_a + area_b);
+    assert!((union + intersection - area_a - area_b).abs() <= tolerance, "{:?} {:?}", a, b);
+    assert!((difference + intersection - area_a).abs() <= tolerance, "{:?} {:?}", a, b);
+    assert!((xor + intersection - union).abs() <= tolerance, "{:?} {:?}", a, b);
+});
@@ -0,0 +1,17 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use computational_geometry::math::Segment2;
+
+mod common;
+
+fuzz_target!(|data: &[u8]| {
+    let points = common::points(data);
+    if points.len() < 4 {
+        return;
+    }
+    let s1 = Segment2::new(points[0], points[1]);
+    let s2 = Segment2::new(points[2], points[3]);
+
+    // Intersecting is symmetric
+    assert_eq!(s1.intersects(&s2), s2.intersects(&s1), "{:?} {:?}", s1, s2);
+});
@@ -0,0 +1,18 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use computational_geometry::{ algorithms::Incremental2dTriangulation, task::Progress };
+
+mod common;
+
+fuzz_target!(|data: &[u8]| {
+    let flip_edges = data.first().map(|b| b & 1 == 1).unwrap_or(false);
+    let points = common::points(data.get(1..).unwrap_or_default());
+    let count = points.len();
+
+    let (points, indices) = Incremental2dTriangulation::compute(points, flip_edges, &Progress::default())
+                                .expect("The computation was not cancelled");
+    // Coincident points are only kept once
+    assert!(points.len() <= count);
+    assert_eq!(indices.len() % 3, 0);
+    assert!(indices.iter().all(|&idx| idx < points.len()));
+});
@@ -0,0 +1,148 @@
+#ifndef COMPUTATIONAL_GEOMETRY_H
+#define COMPUTATIONAL_GEOMETRY_H
+
+/* Generated by cbindgen from src/capi.rs, do not edit */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Parameters sampled per curve by the arc-length table of `resample_path`
+ */
+#define ARC_LENGTH_SAMPLES 64
+
+/**
+ * Smallest target edge length, so that a field painted down to zero still lets the refinement end
+ */
+#define MIN_SIZE 1e-3
+
+/**
+ * Points closer than this are merged into a single vertex
+ */
+#define Arrangement_EPSILON 1e-6
+
+#define POINT_SIZE 8.0
+
+/**
+ * Maximum number of viewports displayed at the same time.
+ */
+#define MAX_VIEWPORTS 4
+
+/**
+ * Boolean operation between two polygons, the difference is the first polygon without the second one.
+ */
+typedef enum CgOperation {
+  CgOperation_Union,
+  CgOperation_Intersection,
+  CgOperation_Difference,
+  CgOperation_Xor,
+} CgOperation;
+
+/**
+ * A 2D point, laid out as two consecutive floats.
+ */
+typedef struct CgPoint {
+  float x;
+  float y;
+} CgPoint;
+
+/**
+ * A triangulation allocated by [`delaunay_2d`], to release with [`triangulation_free`].
+ */
+typedef struct CgTriangulation {
+  /**
+   * Input points in the order used by the indices, without the duplicates
+   */
+  struct CgPoint *points;
+  size_t points_len;
+  /**
+   * Indices of the points that form the triangles, 3 per triangle
+   */
+  size_t *indices;
+  size_t indices_len;
+} CgTriangulation;
+
+/**
+ * Polygons allocated by [`polygon_boolean`], to release with [`polygons_free`].
+ */
+typedef struct CgPolygons {
+  /**
+   * Vertices of all the polygons, one polygon after the other
+   */
+  struct CgPoint *points;
+  size_t points_len;
+  /**
+   * Number of vertices of each polygon
+   */
+  size_t *sizes;
+  size_t sizes_len;
+} CgPolygons;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Computes the convex hull of `len` points with the Graham scan and writes its vertices
+ * in counter-clockwise order to `out`, which must have room for `len` points.
+ * Returns the number of vertices of the hull.
+ *
+ * # Safety
+ *
+ * `points` must point to `len` readable points and `out` to `len` writable points.
+ */
+size_t convex_hull_2d(const struct CgPoint *points, size_t len, struct CgPoint *out);
+
+/**
+ * Triangulates `len` points, the triangulation is a Delaunay triangulation if `delaunay` is true.
+ * Coincident points are only kept once, so the result may have fewer points than the input.
+ * The result must be released with [`triangulation_free`].
+ *
+ * # Safety
+ *
+ * `points` must point to `len` readable points.
+ */
+struct CgTriangulation delaunay_2d(const struct CgPoint *points, size_t len, bool delaunay);
+
+/**
+ * Releases a triangulation returned by [`delaunay_2d`], its pointers are set to null.
+ *
+ * # Safety
+ *
+ * `triangulation` must be null or have been returned by [`delaunay_2d`] and not modified since.
+ */
+void triangulation_free(struct CgTriangulation *triangulation);
+
+/**
+ * Computes the union, intersection, difference or exclusive or of two simple polygons of `a_len` and `b_len` points,
+ * clockwise or counter-clockwise. The result is made of counter-clockwise outer boundaries and clockwise holes,
+ * and must be released with [`polygons_free`].
+ *
+ * # Safety
+ *
+ * `a` must point to `a_len` readable points, `b` to `b_len` readable points,
+ * and `operation` must be one of the values of [`CgOperation`].
+ */
+struct CgPolygons polygon_boolean(const struct CgPoint *a,
+                                  size_t a_len,
+                                  const struct CgPoint *b,
+                                  size_t b_len,
+                                  enum CgOperation operation);
+
+/**
+ * Releases polygons returned by [`polygon_boolean`], their pointers are set to null.
+ *
+ * # Safety
+ *
+ * `polygons` must be null or have been returned by [`polygon_boolean`] and not modified since.
+ */
+void polygons_free(struct CgPolygons *polygons);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* COMPUTATIONAL_GEOMETRY_H */
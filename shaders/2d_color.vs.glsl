@@ -0,0 +1,11 @@
+#version 330 core
+
+in vec2 position;
+in vec3 color;
+
+out vec3 vColor;
+
+void main() {
+    gl_Position = vec4(position.xy, 0.0, 1.0);
+    vColor = color;
+}
@@ -14,6 +14,8 @@ out vec4 colorSpecular;
 uniform mat4 model;
 uniform mat4 view;
 uniform mat4 projection;
+// Offset added to the normalized depth, to push surfaces back behind the lines drawn over them
+uniform float depthBias;
 
 void main() {
     vertNormal = mat3(transpose(inverse(model))) * normal;
@@ -23,4 +25,5 @@ void main() {
     colorSpecular = color_specular;
 
     gl_Position = projection * view * vec4(vertPos.xyz, 1.0);
+    gl_Position.z += depthBias * gl_Position.w;
 }
@@ -0,0 +1,300 @@
+use crate::math::{ Vec2, Segment2, float, polygon };
+
+use alloc::{ vec, vec::Vec };
+
+/// Half of an edge of the arrangement, going from `origin` to the origin of `twin`.
+/// The face it bounds is on its left.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct HalfEdge {
+    pub origin: usize,
+    pub twin: usize,
+    /// Following half-edge on the boundary of `face`
+    pub next: usize,
+    pub prev: usize,
+    pub face: usize,
+}
+
+/// A face of the arrangement: a connected region of the plane that no segment crosses.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Face {
+    /// A half-edge of the outer boundary, counter-clockwise, `None` for the unbounded face
+    pub outer: Option<usize>,
+    /// A half-edge of each hole in the face, clockwise
+    pub inner: Vec<usize>,
+}
+
+/// Arrangement of a set of segments, the subdivision of the plane they induce,
+/// stored as a doubly connected edge list (DCEL).
+/// The segments are split where they intersect, overlapping parts are merged into a single edge.
+#[derive(Clone, Default)]
+pub struct Arrangement {
+    vertices: Vec<Vec2>,
+    half_edges: Vec<HalfEdge>,
+    /// The first face is the unbounded one
+    faces: Vec<Face>,
+    /// Number of connected components of the segments
+    components: usize,
+}
+
+impl Arrangement {
+    /// Points closer than this are merged into a single vertex
+    pub const EPSILON: f32 = 1e-6;
+
+    /// Builds the arrangement of `segments`, testing every pair of segments for intersections.
+    /// Segments without length are ignored.
+    pub fn new(segments: &[Segment2]) -> Self {
+        let _span = span!("Arrangement");
+        let segments = segments.iter()
+                            .filter(|s| s.as_vec2().sqr_length() > 0.0)
+                            .cloned()
+                            .collect::<Vec<_>>();
+        let mut arrangement = Self::default();
+        let edges = arrangement.split(&segments);
+        arrangement.link(&edges);
+        arrangement.build_faces();
+        arrangement
+    }
+
+    pub fn vertices(&self) -> &[Vec2] {
+        &self.vertices
+    }
+
+    pub fn half_edges(&self) -> &[HalfEdge] {
+        &self.half_edges
+    }
+
+    pub fn faces(&self) -> &[Face] {
+        &self.faces
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.half_edges.len() / 2
+    }
+
+    pub fn component_count(&self) -> usize {
+        self.components
+    }
+
+    /// Returns the half-edges of the boundary cycle that contains `half_edge`, starting with it.
+    pub fn cycle(&self, half_edge: usize) -> impl Iterator<Item = usize> + '_ {
+        let mut current = Some(half_edge);
+        core::iter::from_fn(move || {
+            let edge = current?;
+            let next = self.half_edges[edge].next;
+            current = if next == half_edge { None } else { Some(next) };
+            Some(edge)
+        })
+    }
+
+    /// Returns the positions of the vertices of the boundary cycle that contains `half_edge`.
+    pub fn cycle_positions(&self, half_edge: usize) -> Vec<Vec2> {
+        self.cycle(half_edge)
+            .map(|edge| self.vertices[self.half_edges[edge].origin])
+            .collect()
+    }
+
+    /// Returns the boundary cycles of a face, the outer one first.
+    pub fn face_boundaries(&self, face: usize) -> Vec<Vec<Vec2>> {
+        let face = &self.faces[face];
+        face.outer.iter()
+            .chain(face.inner.iter())
+            .map(|&edge| self.cycle_positions(edge))
+            .collect()
+    }
+
+    /// Returns the index of the face containing `p`: the smallest bounded face whose outer boundary contains it,
+    /// or the unbounded face.
+    pub fn locate(&self, p: Vec2) -> usize {
+        self.faces.iter()
+            .enumerate()
+            .filter_map(|(idx, face)| {
+                let boundary = self.cycle_positions(face.outer?);
+                if polygon::contains(&boundary, p) { Some((idx, polygon::signed_area(&boundary))) } else { None }
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map_or(0, |(idx, _)| idx)
+    }
+
+    /// Returns the index of the vertex at `p`, adding it if there is none.
+    fn vertex(&mut self, p: Vec2) -> usize {
+        match self.vertices.iter().position(|v| (v - &p).length() <= Self::EPSILON) {
+            Some(idx) => idx,
+            None => {
+                self.vertices.push(p);
+                self.vertices.len() - 1
+            },
+        }
+    }
+
+    /// Splits the segments at their intersections and returns the resulting edges, as pairs of vertex indices.
+    fn split(&mut self, segments: &[Segment2]) -> Vec<(usize, usize)> {
+        let cross = |a: Vec2, b: Vec2| a.x * b.y - a.y * b.x;
+
+        // Points on each segment where it has to be split, with their parameter along the segment
+        let mut splits = segments.iter()
+                            .map(|s| vec![ (0.0, s.a), (1.0, s.b) ])
+                            .collect::<Vec<_>>();
+        for (i, s1) in segments.iter().enumerate() {
+            for (j, s2) in segments.iter().enumerate().skip(i + 1) {
+                let (r, s) = (s1.as_vec2(), s2.as_vec2());
+                let (sqr_r, sqr_s) = (r.sqr_length(), s.sqr_length());
+                let qp = &s2.a - &s1.a;
+                let denom = cross(r, s);
+                let eps = 1e-6;
+
+                if float::abs(denom) > eps * float::sqrt(sqr_r * sqr_s) {
+                    let t = cross(qp, s) / denom;
+                    let u = cross(qp, r) / denom;
+                    if t >= -eps && t <= 1.0 + eps && u >= -eps && u <= 1.0 + eps {
+                        let p = &s1.a + &(&r * t);
+                        splits[i].push((t, p));
+                        splits[j].push((u, p));
+                    }
+                } else if float::abs(cross(qp, r)) <= eps * float::sqrt(sqr_r * qp.sqr_length()) {
+                    // Collinear, the ends of each segment split the other one
+                    for &p in &[ s2.a, s2.b ] {
+                        let t = (&p - &s1.a).dot(r) / sqr_r;
+                        if t > 0.0 && t < 1.0 {
+                            splits[i].push((t, p));
+                        }
+                    }
+                    for &p in &[ s1.a, s1.b ] {
+                        let u = (&p - &s2.a).dot(s) / sqr_s;
+                        if u > 0.0 && u < 1.0 {
+                            splits[j].push((u, p));
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut edges = Vec::new();
+        for mut points in splits {
+            points.sort_by(|a, b| a.0.total_cmp(&b.0));
+            let indices = points.iter().map(|&(_, p)| self.vertex(p)).collect::<Vec<_>>();
+            for pair in indices.windows(2) {
+                let edge = (pair[0].min(pair[1]), pair[0].max(pair[1]));
+                if edge.0 != edge.1 && !edges.contains(&edge) {
+                    edges.push(edge);
+                }
+            }
+        }
+        edges
+    }
+
+    /// Creates the twin half-edges of `edges` and links each one to the next half-edge around its face:
+    /// arriving at a vertex, the boundary turns into the next edge clockwise.
+    fn link(&mut self, edges: &[(usize, usize)]) {
+        for (idx, &(a, b)) in edges.iter().enumerate() {
+            self.half_edges.push(HalfEdge { origin: a, twin: 2 * idx + 1, next: 0, prev: 0, face: 0 });
+            self.half_edges.push(HalfEdge { origin: b, twin: 2 * idx, next: 0, prev: 0, face: 0 });
+        }
+
+        // Outgoing half-edges of each vertex, in counter-clockwise order
+        let mut outgoing = vec![ Vec::new(); self.vertices.len() ];
+        for (idx, edge) in self.half_edges.iter().enumerate() {
+            outgoing[edge.origin].push(idx);
+        }
+        let angle = |edge: usize| {
+            let from = self.vertices[self.half_edges[edge].origin];
+            let to = self.vertices[self.half_edges[self.half_edges[edge].twin].origin];
+            float::atan2(to.y - from.y, to.x - from.x)
+        };
+        for around in &mut outgoing {
+            around.sort_by(|&a, &b| angle(a).total_cmp(&angle(b)));
+        }
+
+        for around in &outgoing {
+            for (pos, &out) in around.iter().enumerate() {
+                // The half-edges arriving through `out` leave through the previous edge counter-clockwise
+                let incoming = self.half_edges[out].twin;
+                let next = around[(pos + around.len() - 1) % around.len()];
+                self.half_edges[incoming].next = next;
+                self.half_edges[next].prev = incoming;
+            }
+        }
+    }
+
+    /// Creates a face for each counter-clockwise boundary cycle, and attaches the outer boundaries
+    /// of the connected components as holes of the smallest face that contains them.
+    fn build_faces(&mut self) {
+        self.faces = vec![ Face::default() ];
+
+        // Connected components, to find which cycles bound the same piece of the arrangement
+        let mut component = (0..self.vertices.len()).collect::<Vec<_>>();
+        fn find(component: &mut [usize], v: usize) -> usize {
+            let mut root = v;
+            while component[root] != root {
+                root = component[root];
+            }
+            component[v] = root;
+            root
+        }
+        for edge in self.half_edges.iter().step_by(2) {
+            let (a, b) = (find(&mut component, edge.origin), find(&mut component, self.half_edges[edge.twin].origin));
+            component[a] = b;
+        }
+
+        // Boundary cycles, with their signed area and component
+        let mut cycles = Vec::new();
+        let mut visited = vec![ false; self.half_edges.len() ];
+        for start in 0..self.half_edges.len() {
+            if visited[start] {
+                continue;
+            }
+            let edges = self.cycle(start).collect::<Vec<_>>();
+            for &edge in &edges {
+                visited[edge] = true;
+            }
+            let area = polygon::signed_area(&self.cycle_positions(start));
+            let root = find(&mut component, self.half_edges[start].origin);
+            cycles.push((edges, area, root));
+        }
+        self.components = (0..self.vertices.len()).filter(|&v| find(&mut component, v) == v).count();
+
+        // Each component has a single clockwise cycle around it, the one with the smallest area.
+        // Comparing the areas is more robust than their signs: the cycles around a tree have no area
+        // but rounding errors can make it slightly positive.
+        let mut is_hole = vec![ false; cycles.len() ];
+        for (idx, (_, area, root)) in cycles.iter().enumerate() {
+            let smallest = cycles.iter()
+                            .enumerate()
+                            .filter(|(_, (_, _, other))| other == root)
+                            .all(|(other_idx, (_, other_area, _))| area < other_area || area == other_area && idx <= other_idx);
+            is_hole[idx] = smallest;
+        }
+
+        // The other cycles are the outer boundaries of the bounded faces, counter-clockwise
+        let mut outer_cycles = Vec::new();
+        for (idx, (edges, area, root)) in cycles.iter().enumerate() {
+            if !is_hole[idx] {
+                self.faces.push(Face { outer: Some(edges[0]), inner: Vec::new() });
+                for &edge in edges {
+                    self.half_edges[edge].face = self.faces.len() - 1;
+                }
+                outer_cycles.push((self.faces.len() - 1, *area, *root));
+            }
+        }
+
+        // The other cycles are the outer boundaries of the components, in the smallest face of another component
+        // that contains them, or in the unbounded face
+        for (idx, (edges, _, root)) in cycles.iter().enumerate() {
+            if !is_hole[idx] {
+                continue;
+            }
+            let leftmost = edges.iter()
+                                .map(|&edge| self.vertices[self.half_edges[edge].origin])
+                                .min_by(|a, b| a.x.total_cmp(&b.x))
+                                .unwrap();
+            let face = outer_cycles.iter()
+                            .filter(|(_, _, other)| other != root)
+                            .filter(|(face, _, _)| polygon::contains(&self.cycle_positions(self.faces[*face].outer.unwrap()), leftmost))
+                            .min_by(|a, b| a.1.total_cmp(&b.1))
+                            .map_or(0, |&(face, _, _)| face);
+            self.faces[face].inner.push(edges[0]);
+            for &edge in edges {
+                self.half_edges[edge].face = face;
+            }
+        }
+    }
+}
@@ -0,0 +1,448 @@
+//! Boolean operations on shapes bounded by segments and circular arcs, e.g. polygons and the round offsets of polylines.
+//! The edges of both shapes are cut where they cross or touch, each piece is kept or dropped depending on whether it lies
+//! inside the other shape, outside of it or along its boundary, and the kept pieces are linked back into closed contours.
+//! The crossings are found by testing every edge of a shape against every edge of the other one. The contours of a shape
+//! must not cross each other or themselves.
+
+use crate::math::{ Vec2, Segment2, Arc, clamp, float, arc::normalized_angle };
+
+use alloc::{ vec, vec::Vec };
+
+use core::{ cmp::Ordering, f32::consts::PI, slice };
+
+/// Tolerance on the distances, relative to the coordinates of the shapes
+const TOLERANCE: f32 = 1e-5;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Operation {
+    Union,
+    Intersection,
+    /// The first shape without the second one
+    Difference,
+    /// The parts covered by only one of the shapes
+    Xor,
+}
+
+/// Edge of a contour, from its first point to its last one.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Edge {
+    Segment(Segment2),
+    Arc(Arc),
+}
+
+/// Closed loop of edges, each one starting where the previous one ends. The inside of a shape is on the left
+/// of its contours: outer boundaries go counter-clockwise and holes clockwise.
+pub type Contour = Vec<Edge>;
+
+/// Position of a piece of edge of a shape relative to the other shape
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum Side {
+    Inside,
+    Outside,
+    /// Along the boundary of the other shape, in the same direction
+    Same,
+    /// Along the boundary of the other shape, in the opposite direction
+    Opposite,
+}
+
+/// Part of an edge between two cuts, and the nodes at its ends
+struct Piece {
+    edge: Edge,
+    start: usize,
+    end: usize,
+}
+
+fn cross(u: Vec2, v: Vec2) -> f32 {
+    u.x * v.y - u.y * v.x
+}
+
+/// Returns the parameter of the projection of `p` on the line of the segment, 0 at its first point and 1 at the last one
+fn project(segment: &Segment2, p: Vec2) -> f32 {
+    let direction = segment.as_vec2();
+    let sqr_length = direction.sqr_length();
+    if sqr_length == 0.0 {
+        return 0.0;
+    }
+    (&p - &segment.a).dot(direction) / sqr_length
+}
+
+/// Returns the point where two segments cross, none if they are parallel
+fn segment_intersection(a: &Segment2, b: &Segment2) -> Option<Vec2> {
+    let (r, s) = (a.as_vec2(), b.as_vec2());
+    let denominator = cross(r, s);
+    if denominator == 0.0 {
+        return None;
+    }
+    let offset = &b.a - &a.a;
+    let (t, u) = (cross(offset, s) / denominator, cross(offset, r) / denominator);
+    if (0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u) {
+        Some(&a.a + &(&r * t))
+    } else {
+        None
+    }
+}
+
+impl Edge {
+    /// Returns the point at the parameter `t`, from the first point at 0 to the last one at 1
+    pub fn point(&self, t: f32) -> Vec2 {
+        match self {
+            Edge::Segment(segment) if t == 1.0 => segment.b,
+            Edge::Segment(segment) => &segment.a + &(&segment.as_vec2() * t),
+            Edge::Arc(arc) => arc.point(t),
+        }
+    }
+
+    pub fn start(&self) -> Vec2 {
+        self.point(0.0)
+    }
+
+    pub fn end(&self) -> Vec2 {
+        self.point(1.0)
+    }
+
+    pub fn length(&self) -> f32 {
+        match self {
+            Edge::Segment(segment) => segment.as_vec2().length(),
+            Edge::Arc(arc) => arc.length(),
+        }
+    }
+
+    /// Returns the same edge from its last point to its first one
+    pub fn reversed(&self) -> Self {
+        match self {
+            Edge::Segment(segment) => Edge::Segment(Segment2::new(segment.b, segment.a)),
+            Edge::Arc(arc) => Edge::Arc(Arc::new(arc.circle, arc.start + arc.sweep, -arc.sweep)),
+        }
+    }
+
+    /// Returns the unit direction of the edge at the parameter `t`
+    pub fn tangent(&self, t: f32) -> Vec2 {
+        match self {
+            Edge::Segment(segment) => segment.as_vec2().normalized(),
+            Edge::Arc(arc) => {
+                let angle = arc.start + t * arc.sweep;
+                let sign = if arc.sweep >= 0.0 { 1.0 } else { -1.0 };
+                Vec2::new(-sign * float::sin(angle), sign * float::cos(angle))
+            }
+        }
+    }
+
+    /// Returns the parameter of the point of the edge closest to `p`
+    fn parameter(&self, p: Vec2) -> f32 {
+        match self {
+            Edge::Segment(segment) => clamp(project(segment, p), 0.0, 1.0),
+            Edge::Arc(arc) => {
+                let angle = float::atan2(p.y - arc.circle.center.y, p.x - arc.circle.center.x);
+                let relative = normalized_angle(if arc.sweep >= 0.0 { angle - arc.start } else { arc.start - angle });
+                let sweep = float::abs(arc.sweep);
+                if relative <= sweep {
+                    relative / sweep
+                } else if relative - sweep < 2.0 * PI - relative {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+
+    fn distance(&self, p: Vec2) -> f32 {
+        (&p - &self.point(self.parameter(p))).length()
+    }
+
+    /// Returns the part of the edge between the parameters `t0` and `t1`
+    fn part(&self, t0: f32, t1: f32) -> Self {
+        match self {
+            Edge::Segment(_) => Edge::Segment(Segment2::new(self.point(t0), self.point(t1))),
+            Edge::Arc(arc) => Edge::Arc(Arc::new(arc.circle, arc.start + t0 * arc.sweep, (t1 - t0) * arc.sweep)),
+        }
+    }
+
+    /// Returns the points where the edges cross, and the ends of each one that touch the other,
+    /// e.g. where they overlap or where rounding misses a crossing at an end
+    fn crossings(&self, other: &Self, tolerance: f32) -> Vec<Vec2> {
+        let mut points = match (self, other) {
+            (Edge::Segment(a), Edge::Segment(b)) => segment_intersection(a, b).into_iter().collect::<Vec<_>>(),
+            (Edge::Segment(segment), Edge::Arc(arc)) | (Edge::Arc(arc), Edge::Segment(segment)) => arc.segment_intersections(segment),
+            (Edge::Arc(a), Edge::Arc(b)) => a.intersections(b),
+        };
+        for (edge, p) in [ (other, self.start()), (other, self.end()), (self, other.start()), (self, other.end()) ].iter() {
+            if edge.distance(*p) <= tolerance {
+                points.push(*p);
+            }
+        }
+        points
+    }
+
+    /// Returns the angle swept by the direction from `p` to a point running along the edge
+    fn winding_angle(&self, p: Vec2) -> f32 {
+        let (start, end) = (self.start(), self.end());
+        let chord = (&start - &p).signed_angle(&end - &p);
+        let arc = match self {
+            Edge::Segment(_) => return chord,
+            Edge::Arc(arc) => arc,
+        };
+        if float::abs(arc.sweep) > PI {
+            return self.part(0.0, 0.5).winding_angle(p) + self.part(0.5, 1.0).winding_angle(p);
+        }
+        if (&p - &arc.circle.center).length() >= arc.circle.radius {
+            return chord;
+        }
+        // Between the arc and its chord, the direction turns once more than along the chord
+        let turn = if arc.sweep >= 0.0 { 2.0 * PI } else { -2.0 * PI };
+        let side = Vec2::orientation(start, end, p);
+        if side == 0.0 {
+            turn / 2.0
+        } else if (side > 0.0) == (Vec2::orientation(start, end, arc.point(0.5)) > 0.0) {
+            chord + turn
+        } else {
+            chord
+        }
+    }
+}
+
+/// Returns the number of times the edges turn around `p`, counter-clockwise
+fn winding<'a>(edges: impl Iterator<Item = &'a Edge>, p: Vec2) -> i32 {
+    float::round(edges.map(|edge| edge.winding_angle(p)).sum::<f32>() / (2.0 * PI)) as i32
+}
+
+/// Checks whether `p` is inside the shape bounded by the contours
+pub fn contains(contours: &[Contour], p: Vec2) -> bool {
+    winding(contours.iter().flatten(), p) != 0
+}
+
+/// Returns the area of the shape bounded by the contours, negative if it is on their right
+pub fn area(contours: &[Contour]) -> f32 {
+    contours
+        .iter()
+        .flatten()
+        .map(|edge| {
+            let chord = cross(edge.start(), edge.end()) / 2.0;
+            match edge {
+                Edge::Segment(_) => chord,
+                // Plus the circular segment between the arc and its chord
+                Edge::Arc(arc) => chord + arc.circle.radius * arc.circle.radius * (arc.sweep - float::sin(arc.sweep)) / 2.0,
+            }
+        })
+        .sum()
+}
+
+/// Returns the contour of a polygon, counter-clockwise whichever way its vertices go, or none if it has less than 3 vertices
+pub fn polygon_contour(polygon: &[Vec2]) -> Contour {
+    if polygon.len() < 3 {
+        return Vec::new();
+    }
+    let contour = (0..polygon.len())
+        .map(|i| Edge::Segment(Segment2::new(polygon[i], polygon[(i + 1) % polygon.len()])))
+        .collect::<Contour>();
+    if area(slice::from_ref(&contour)) < 0.0 {
+        contour.iter().rev().map(Edge::reversed).collect()
+    } else {
+        contour
+    }
+}
+
+/// Returns the result of the operation between two simple polygons, clockwise or counter-clockwise, as polygons:
+/// counter-clockwise outer boundaries and clockwise holes.
+pub fn polygon_boolean(a: &[Vec2], b: &[Vec2], operation: Operation) -> Vec<Vec<Vec2>> {
+    boolean(&[ polygon_contour(a) ], &[ polygon_contour(b) ], operation)
+        .into_iter()
+        .map(|contour| contour.iter().map(Edge::start).collect::<Vec<_>>())
+        .filter(|polygon| polygon.len() >= 3)
+        .collect()
+}
+
+/// Returns the contours of the result of the operation between the shapes bounded by the contours `a` and `b`.
+/// Where the result touches itself at a point, its contours are kept apart.
+pub fn boolean(a: &[Contour], b: &[Contour], operation: Operation) -> Vec<Contour> {
+    let extent = a.iter().chain(b).flatten()
+        .flat_map(|edge| [ edge.start(), edge.end() ].to_vec())
+        .fold(1.0f32, |extent, p| extent.max(float::abs(p.x)).max(float::abs(p.y)));
+    let tolerance = TOLERANCE * extent;
+
+    // The ends of the edges are nodes, merged with the union-find `parents` where they meet
+    let mut parents = Vec::new();
+    let mut edges = Vec::new();
+    for (shape, contours) in [ a, b ].iter().enumerate() {
+        for contour in contours.iter() {
+            let first = parents.len();
+            parents.extend(first..first + contour.len());
+            for (i, edge) in contour.iter().enumerate() {
+                let (start, end) = (first + i, first + (i + 1) % contour.len());
+                if edge.length() <= tolerance {
+                    union(&mut parents, start, end);
+                } else {
+                    edges.push((shape, *edge, vec![ (0.0, start), (1.0, end) ]));
+                }
+            }
+        }
+    }
+
+    // Cuts where the edges of both shapes meet, each cut of an edge shares its node with the one of the other edge
+    for i in 0..edges.len() {
+        for j in i + 1..edges.len() {
+            if edges[i].0 == edges[j].0 {
+                continue;
+            }
+            for p in edges[i].1.crossings(&edges[j].1, tolerance) {
+                let node = parents.len();
+                parents.push(node);
+                let (ti, tj) = (edges[i].1.parameter(p), edges[j].1.parameter(p));
+                edges[i].2.push((ti, node));
+                edges[j].2.push((tj, node));
+            }
+        }
+    }
+
+    let mut pieces = Vec::new();
+    for (shape, edge, cuts) in edges.iter_mut() {
+        cuts.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+        // Cuts closer than the tolerance along the edge are the same
+        let length = edge.length();
+        let mut kept: Vec<(f32, usize)> = Vec::new();
+        for &(t, node) in cuts.iter() {
+            match kept.last_mut() {
+                Some(last) if (t - last.0) * length <= tolerance => {
+                    union(&mut parents, last.1, node);
+                    if t == 1.0 {
+                        last.0 = 1.0;
+                    }
+                }
+                _ => kept.push((t, node)),
+            }
+        }
+        for pair in kept.windows(2) {
+            pieces.push((*shape, Piece { edge: edge.part(pair[0].0, pair[1].0), start: pair[0].1, end: pair[1].1 }));
+        }
+    }
+
+    let selected = pieces
+        .into_iter()
+        .filter_map(|(shape, piece)| {
+            let others = edges.iter().filter(|edge| edge.0 != shape).map(|edge| &edge.1);
+            let reversed = selection(operation, shape == 0, side(&piece.edge, others, tolerance))?;
+            Some(Piece {
+                edge: if reversed { piece.edge.reversed() } else { piece.edge },
+                start: find(&mut parents, if reversed { piece.end } else { piece.start }),
+                end: find(&mut parents, if reversed { piece.start } else { piece.end }),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    link(&selected, parents.len())
+}
+
+fn find(parents: &mut [usize], mut i: usize) -> usize {
+    while parents[i] != i {
+        parents[i] = parents[parents[i]];
+        i = parents[i];
+    }
+    i
+}
+
+fn union(parents: &mut [usize], i: usize, j: usize) {
+    let (i, j) = (find(parents, i), find(parents, j));
+    parents[i] = j;
+}
+
+/// Returns where the middle of a piece of edge lies relative to the shape bounded by `others`
+fn side<'a>(edge: &Edge, others: impl Iterator<Item = &'a Edge> + Clone, tolerance: f32) -> Side {
+    let middle = edge.point(0.5);
+    match others.clone().find(|other| other.distance(middle) <= tolerance) {
+        Some(other) if edge.tangent(0.5).dot(other.tangent(other.parameter(middle))) > 0.0 => Side::Same,
+        Some(_) => Side::Opposite,
+        None if winding(others, middle) != 0 => Side::Inside,
+        None => Side::Outside,
+    }
+}
+
+/// Returns whether a piece of the first shape or of the second one is part of the result,
+/// reversed or not, and `None` if it is not. The pieces along both boundaries are taken from the first shape.
+fn selection(operation: Operation, first: bool, side: Side) -> Option<bool> {
+    use Operation::*;
+    match (operation, first, side) {
+        (Union, _, Side::Outside) | (Union, true, Side::Same) => Some(false),
+        (Intersection, _, Side::Inside) | (Intersection, true, Side::Same) => Some(false),
+        (Difference, true, Side::Outside) | (Difference, true, Side::Opposite) => Some(false),
+        (Difference, false, Side::Inside) => Some(true),
+        (Xor, _, Side::Outside) => Some(false),
+        (Xor, _, Side::Inside) => Some(true),
+        _ => None,
+    }
+}
+
+/// Links the pieces into closed contours. At the nodes with several ways out, the contours turn as far left as they can,
+/// so that the parts of the result that touch at a point get their own contours. Chains that cannot be closed,
+/// e.g. because of rounding, are dropped.
+fn link(pieces: &[Piece], nodes: usize) -> Vec<Contour> {
+    let mut outgoing = vec![ Vec::new(); nodes ];
+    for (i, piece) in pieces.iter().enumerate() {
+        outgoing[piece.start].push(i);
+    }
+    let mut used = vec![ false; pieces.len() ];
+    let mut contours = Vec::new();
+    for first in 0..pieces.len() {
+        if used[first] {
+            continue;
+        }
+        used[first] = true;
+        let mut chain = vec![ first ];
+        loop {
+            let last = &pieces[chain[chain.len() - 1]];
+            if last.end == pieces[first].start {
+                contours.push(joined(chain.iter().map(|&i| pieces[i].edge)));
+                break;
+            }
+            let incoming = last.edge.tangent(1.0);
+            let turn = |i: &usize| incoming.signed_angle(pieces[*i].edge.tangent(0.0));
+            let next = outgoing[last.end]
+                .iter()
+                .filter(|&&i| !used[i])
+                .max_by(|i, j| turn(i).partial_cmp(&turn(j)).unwrap_or(Ordering::Equal));
+            match next {
+                Some(&i) => {
+                    used[i] = true;
+                    chain.push(i);
+                }
+                None => break,
+            }
+        }
+    }
+    contours
+}
+
+/// Returns the contour of the edges, with the consecutive segments that go the same way and the consecutive arcs
+/// of the same circle made into one edge
+fn joined(edges: impl Iterator<Item = Edge>) -> Contour {
+    let mut contour: Contour = Vec::new();
+    for edge in edges {
+        match contour.last().and_then(|last| join(last, &edge)) {
+            Some(both) => *contour.last_mut().unwrap() = both,
+            None => contour.push(edge),
+        }
+    }
+    while contour.len() > 1 {
+        match join(&contour[contour.len() - 1], &contour[0]) {
+            Some(both) => {
+                contour[0] = both;
+                contour.pop();
+            }
+            None => break,
+        }
+    }
+    contour
+}
+
+fn join(a: &Edge, b: &Edge) -> Option<Edge> {
+    match (a, b) {
+        (Edge::Segment(s), Edge::Segment(t)) => {
+            let (u, v) = (s.as_vec2(), t.as_vec2());
+            let aligned = float::abs(cross(u, v)) <= TOLERANCE * u.length() * v.length() && u.dot(v) > 0.0;
+            if aligned { Some(Edge::Segment(Segment2::new(s.a, t.b))) } else { None }
+        }
+        (Edge::Arc(x), Edge::Arc(y)) if x.circle == y.circle && (x.sweep >= 0.0) == (y.sweep >= 0.0) => {
+            Some(Edge::Arc(Arc::new(x.circle, x.start, x.sweep + y.sweep)))
+        }
+        _ => None,
+    }
+}
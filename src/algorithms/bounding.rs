@@ -0,0 +1,139 @@
+//! Bounding geometry of point sets, polygons and meshes: their axis-aligned bounding rectangle,
+//! their bounding circle and their oriented bounding box, through the same [`Bounded`](trait.Bounded.html) trait.
+//! The 2D shapes are the smallest ones, from the farthest-point Voronoi diagram and the rotating calipers.
+
+use super::{ GrahamScan, FarthestPointVoronoi, RotatingCalipers };
+use crate::math::{ Vec2, Rect, Circle };
+#[cfg(feature = "std")]
+use crate::math::{ Vec3, pca };
+
+use alloc::vec::Vec;
+
+/// Shapes enclosing a geometry, `None` for an empty one.
+/// The 2D geometries are bounded by rectangles and circles, the 3D ones by boxes and spheres.
+pub trait Bounded {
+    type Rect;
+    type Circle;
+    type OrientedBox;
+
+    /// Returns the smallest box whose sides are parallel to the axes that contains the geometry.
+    fn bounding_rect(&self) -> Option<Self::Rect>;
+
+    /// Returns a circle, or a sphere, that contains the geometry.
+    fn bounding_circle(&self) -> Option<Self::Circle>;
+
+    /// Returns a box in any orientation that contains the geometry.
+    fn oriented_bounding_box(&self) -> Option<Self::OrientedBox>;
+}
+
+/// A point set, or the vertices of a polygon.
+impl Bounded for [Vec2] {
+    type Rect = Rect;
+    type Circle = Circle;
+    /// Corners in counter-clockwise order
+    type OrientedBox = [Vec2; 4];
+
+    fn bounding_rect(&self) -> Option<Rect> {
+        Rect::from_points(self)
+    }
+
+    /// Returns the smallest enclosing circle, centered on the farthest-point Voronoi diagram.
+    fn bounding_circle(&self) -> Option<Circle> {
+        let mut diagram = FarthestPointVoronoi::new();
+        diagram.set_points(self.to_vec());
+        diagram.minimum_enclosing_circle()
+    }
+
+    /// Returns the enclosing rectangle of smallest area, which has a side flush with an edge of the convex hull.
+    fn oriented_bounding_box(&self) -> Option<[Vec2; 4]> {
+        let hull = GrahamScan::scan(self).iter().map(|&idx| self[idx]).collect::<Vec<_>>();
+        match hull[..] {
+            [] => None,
+            [ p ] => Some([ p; 4 ]),
+            _ => RotatingCalipers::rotate(&hull)
+                    .iter()
+                    .min_by(|a, b| a.area().total_cmp(&b.area()))
+                    .map(|caliper| caliper.rectangle),
+        }
+    }
+}
+
+/// Box whose sides are parallel to the axes.
+#[cfg(feature = "std")]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Box3 {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+#[cfg(feature = "std")]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Sphere {
+    pub center: Vec3,
+    pub radius: f32,
+}
+
+/// Box in any orientation.
+#[cfg(feature = "std")]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct OrientedBox3 {
+    pub center: Vec3,
+    /// Unit vectors along the sides, forming a right-handed frame
+    pub axes: [Vec3; 3],
+    /// Half of the length of the sides along each axis
+    pub half_extents: [f32; 3],
+}
+
+/// A point cloud, or the vertices of a mesh.
+#[cfg(feature = "std")]
+impl Bounded for [Vec3] {
+    type Rect = Box3;
+    type Circle = Sphere;
+    type OrientedBox = OrientedBox3;
+
+    fn bounding_rect(&self) -> Option<Box3> {
+        let (first, rest) = self.split_first()?;
+        Some(rest.iter().fold(Box3 { min: *first, max: *first }, |bounds, p| Box3 {
+            min: Vec3::new(bounds.min.0.x.min(p.0.x), bounds.min.0.y.min(p.0.y), bounds.min.0.z.min(p.0.z)),
+            max: Vec3::new(bounds.max.0.x.max(p.0.x), bounds.max.0.y.max(p.0.y), bounds.max.0.z.max(p.0.z)),
+        }))
+    }
+
+    /// Returns the sphere of Ritter's algorithm, at most a few percents larger than the smallest one:
+    /// the sphere between two points far apart, grown to reach each point left outside.
+    fn bounding_circle(&self) -> Option<Sphere> {
+        use cgmath::{ InnerSpace, Vector3 };
+
+        let farthest = |from: Vector3<f32>| self.iter()
+                                            .map(|p| p.0)
+                                            .max_by(|a, b| (a - from).magnitude2().total_cmp(&(b - from).magnitude2()));
+        let a = farthest(self.first()?.0)?;
+        let b = farthest(a)?;
+        let (mut center, mut radius) = ((a + b) / 2.0, (b - a).magnitude() / 2.0);
+        for p in self {
+            let distance = (p.0 - center).magnitude();
+            if distance > radius {
+                // The new sphere touches `p` and the opposite side of the previous one
+                let new_radius = (radius + distance) / 2.0;
+                center += (p.0 - center) * ((new_radius - radius) / distance);
+                radius = new_radius;
+            }
+        }
+        Some(Sphere { center: center.into(), radius })
+    }
+
+    /// Returns the box aligned with the principal axes of the points, which fits elongated clouds closely.
+    fn oriented_bounding_box(&self) -> Option<OrientedBox3> {
+        let axes = pca::principal_axes_3d(self)?;
+        let local = self.iter().map(|&p| axes.to_local(p)).collect::<Vec<_>>();
+        let bounds = local.bounding_rect()?;
+        let (min, max) = (bounds.min.0, bounds.max.0);
+        let middle = (min + max) / 2.0;
+        let center = axes.centroid.0 + axes.axes[0].0 * middle.x + axes.axes[1].0 * middle.y + axes.axes[2].0 * middle.z;
+        Some(OrientedBox3 {
+            center: center.into(),
+            axes: axes.axes,
+            half_extents: [ (max.x - min.x) / 2.0, (max.y - min.y) / 2.0, (max.z - min.z) / 2.0 ],
+        })
+    }
+}
@@ -0,0 +1,70 @@
+use super::largest_empty_circle;
+use crate::math::{ Vec2, Circle, float, polygon };
+
+use alloc::vec::Vec;
+
+/// Greedy packing of non-overlapping circles inside a simple polygon:
+/// each new circle is the largest one that fits in the room left by the previous ones.
+#[derive(Default)]
+pub struct CirclePacking {
+    polygon: Vec<Vec2>,
+    circles: Vec<Circle>,
+    /// Area of the polygon
+    area: f32,
+    /// The circles are searched with this precision, relative to the size of the polygon
+    precision: f32,
+}
+
+impl CirclePacking {
+    /// Starts an empty packing of `polygon`.
+    pub fn new(polygon: Vec<Vec2>) -> Self {
+        let area = float::abs(polygon::signed_area(&polygon));
+        let precision = polygon::bounding_rect(&polygon)
+                            .map_or(0.0, |rect| rect.width().max(rect.height()) * 1e-4);
+        Self {
+            polygon,
+            circles: Vec::new(),
+            area,
+            precision,
+        }
+    }
+
+    pub fn polygon(&self) -> &[Vec2] {
+        &self.polygon
+    }
+
+    /// Returns the circles, from the largest to the smallest.
+    pub fn circles(&self) -> &[Circle] {
+        &self.circles
+    }
+
+    /// Returns the fraction of the area of the polygon covered by the circles.
+    pub fn coverage(&self) -> f32 {
+        if self.area == 0.0 {
+            return 0.0;
+        }
+        self.circles.iter().map(Circle::area).sum::<f32>() / self.area
+    }
+
+    /// Adds the largest circle that fits in the polygon without overlapping the others.
+    /// Returns `None` if its radius would be less than `min_radius`.
+    pub fn step(&mut self, min_radius: f32) -> Option<Circle> {
+        let circle = largest_empty_circle(&self.polygon, &self.circles, self.precision)?.circle;
+        if circle.radius < min_radius.max(self.precision) {
+            return None;
+        }
+        self.circles.push(circle);
+        Some(circle)
+    }
+
+    /// Adds circles until there are `max_count` of them, they cover `target_coverage` of the area,
+    /// or the next one would be smaller than `min_radius`.
+    pub fn pack(&mut self, max_count: usize, target_coverage: f32, min_radius: f32) {
+        let _span = span!("Circle packing");
+        while self.circles.len() < max_count && self.coverage() < target_coverage {
+            if self.step(min_radius).is_none() {
+                break;
+            }
+        }
+    }
+}
@@ -0,0 +1,207 @@
+//! Partitions of sets of points into clusters.
+//! DBSCAN grows clusters from the points with enough neighbours within a radius, and leaves the isolated points out as noise.
+//! k-means splits the points into a given number of clusters, each point belonging to the cluster of the closest center.
+
+use crate::{
+    algorithms::{ GrahamScan, RotatingCalipers },
+    math::{ Vec2, polygon },
+    spatial::KdTree,
+};
+
+use alloc::{ vec, vec::Vec, collections::VecDeque };
+
+/// Cluster of each point of a set, as returned by [`dbscan`] and [`k_means`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct Clustering {
+    /// Cluster of each point, `None` for the noise
+    pub labels: Vec<Option<usize>>,
+    /// Number of clusters, the labels are below it
+    pub count: usize,
+}
+
+impl Clustering {
+    /// Returns the indices of the points of each cluster, in increasing order.
+    pub fn clusters(&self) -> Vec<Vec<usize>> {
+        let mut clusters = vec![ Vec::new(); self.count ];
+        for (idx, label) in self.labels.iter().enumerate() {
+            if let Some(cluster) = label {
+                clusters[*cluster].push(idx);
+            }
+        }
+        clusters
+    }
+
+    /// Returns the indices of the points that belong to no cluster.
+    pub fn noise(&self) -> Vec<usize> {
+        self.labels.iter().enumerate().filter(|(_, label)| label.is_none()).map(|(idx, _)| idx).collect()
+    }
+
+    /// Returns the convex hull of each cluster, as indices of `points` counter-clockwise.
+    pub fn hulls(&self, points: &[Vec2]) -> Vec<Vec<usize>> {
+        self.clusters()
+            .into_iter()
+            .map(|cluster| {
+                let positions = cluster.iter().map(|&idx| points[idx]).collect::<Vec<_>>();
+                GrahamScan::scan(&positions).into_iter().map(|i| cluster[i]).collect()
+            })
+            .collect()
+    }
+
+    /// Returns the statistics of each cluster. The hulls and the diameters are found with rotating calipers.
+    pub fn statistics(&self, points: &[Vec2]) -> Vec<ClusterStatistics> {
+        let mut calipers = RotatingCalipers::new();
+        self.clusters()
+            .into_iter()
+            .map(|cluster| {
+                let positions = cluster.iter().map(|&idx| points[idx]).collect::<Vec<_>>();
+                let centroid = if positions.is_empty() {
+                    None
+                } else {
+                    let sum = positions.iter().fold(Vec2::new(0.0, 0.0), |sum, p| &sum + p);
+                    Some(&sum * (1.0 / positions.len() as f32))
+                };
+
+                calipers.set_points(positions);
+                let hull = calipers.hull().iter().map(|&i| cluster[i]).collect::<Vec<_>>();
+                let area = polygon::signed_area(&hull.iter().map(|&idx| points[idx]).collect::<Vec<_>>()).abs();
+                let diameter = calipers.diameter()
+                                .map(|((a, b), distance)| ((hull[a], hull[b]), distance))
+                                .filter(|&(_, distance)| distance > 0.0);
+                ClusterStatistics {
+                    size: cluster.len(),
+                    hull,
+                    area,
+                    centroid,
+                    diameter,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Summary of a cluster, as returned by [`Clustering::statistics`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct ClusterStatistics {
+    /// Number of points of the cluster
+    pub size: usize,
+    /// Indices of the points on the convex hull of the cluster, counter-clockwise
+    pub hull: Vec<usize>,
+    /// Area of the convex hull, zero when the points are collinear
+    pub area: f32,
+    /// Mean of the points, `None` for an empty cluster
+    pub centroid: Option<Vec2>,
+    /// Indices of the two points that are the farthest apart and their distance, `None` for fewer than two distinct points
+    pub diameter: Option<((usize, usize), f32)>,
+}
+
+/// Clusters the points with DBSCAN: a point with at least `min_points` points within `radius`, itself included, is a core point.
+/// The core points closer than `radius` to each other are in the same cluster, with the other points around them.
+/// The points that are not close to any core point are noise. The neighbours are found with a k-d tree.
+pub fn dbscan(points: &[Vec2], radius: f32, min_points: usize) -> Clustering {
+    let tree = KdTree::new(points);
+    let mut labels = vec![ None; points.len() ];
+    let mut visited = vec![ false; points.len() ];
+    let mut count = 0;
+
+    for start in 0..points.len() {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+        let neighbours = tree.within(points[start], radius);
+        if neighbours.len() < min_points {
+            continue; // Noise, unless a core point reaches it later
+        }
+
+        // Breadth-first search through the core points
+        labels[start] = Some(count);
+        let mut queue = neighbours.into_iter().collect::<VecDeque<_>>();
+        while let Some(idx) = queue.pop_front() {
+            if labels[idx].is_none() {
+                labels[idx] = Some(count);
+            }
+            if visited[idx] {
+                continue;
+            }
+            visited[idx] = true;
+            let neighbours = tree.within(points[idx], radius);
+            if neighbours.len() >= min_points {
+                queue.extend(neighbours);
+            }
+        }
+        count += 1;
+    }
+
+    Clustering {
+        labels,
+        count,
+    }
+}
+
+/// Clusters the points with Lloyd's k-means: every iteration assigns each point to the closest center,
+/// found with a k-d tree over the centers, then moves each center to the centroid of its points.
+/// The first center is the first point and each next one is the point farthest from the centers so far,
+/// so the result is deterministic. Stops when no point changes cluster, or after `max_iterations` iterations.
+/// Every point belongs to a cluster, there are fewer than `k` clusters when there are fewer distinct points.
+pub fn k_means(points: &[Vec2], k: usize, max_iterations: usize) -> Clustering {
+    let mut centers = initial_centers(points, k);
+    let mut labels = vec![ None; points.len() ];
+
+    for _ in 0..max_iterations {
+        let tree = KdTree::new(&centers);
+        let mut changed = false;
+        for (label, &p) in labels.iter_mut().zip(points) {
+            let closest = tree.nearest(p);
+            changed |= *label != closest;
+            *label = closest;
+        }
+        if !changed {
+            break;
+        }
+
+        let mut sums = vec![ (Vec2::new(0.0, 0.0), 0); centers.len() ];
+        for (label, p) in labels.iter().zip(points) {
+            if let Some(cluster) = label {
+                let (sum, n) = &mut sums[*cluster];
+                *sum += p;
+                *n += 1;
+            }
+        }
+        for (center, (sum, n)) in centers.iter_mut().zip(sums) {
+            if n > 0 {
+                *center = &sum * (1.0 / n as f32);
+            }
+        }
+    }
+
+    Clustering {
+        labels,
+        count: centers.len(),
+    }
+}
+
+/// Returns up to `k` distinct points, each one the farthest from the previous ones.
+fn initial_centers(points: &[Vec2], k: usize) -> Vec<Vec2> {
+    let first = match points.first() {
+        Some(&first) if k > 0 => first,
+        _ => return Vec::new(),
+    };
+    let mut centers = vec![ first ];
+    let mut distances = points.iter().map(|p| (p - &first).sqr_length()).collect::<Vec<_>>();
+    while centers.len() < k {
+        let (farthest, &distance) = distances
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .expect("No point");
+        if distance == 0.0 {
+            break; // Every point is on a center
+        }
+        let center = points[farthest];
+        centers.push(center);
+        for (d, p) in distances.iter_mut().zip(points) {
+            *d = d.min((p - &center).sqr_length());
+        }
+    }
+    centers
+}
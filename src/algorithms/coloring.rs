@@ -0,0 +1,34 @@
+//! Greedy coloring of the triangles of a mesh, so that two triangles sharing an edge never have the same color.
+//! The triangles are the nodes of the dual graph, linked when they share an edge, so each has at most 3 neighbours
+//! and the greedy coloring never needs more than 4 colors.
+
+use super::DualGraph;
+
+use alloc::{ vec, vec::Vec };
+
+/// Colors the nodes of a graph given by the neighbours of each node, and returns the color of each node, from 0.
+/// The nodes are colored by decreasing degree (Welsh-Powell order), each with the smallest color none of its neighbours has,
+/// so at most one more color than the largest degree is used.
+pub fn greedy_coloring(neighbours: &[Vec<usize>]) -> Vec<usize> {
+    let mut order = (0..neighbours.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&node| core::cmp::Reverse(neighbours[node].len()));
+
+    let mut colors = vec![ usize::MAX; neighbours.len() ];
+    let mut used = Vec::new();
+    for node in order {
+        used.clear();
+        used.extend(neighbours[node].iter().map(|&other| colors[other]).filter(|&color| color != usize::MAX));
+        colors[node] = (0..).find(|color| !used.contains(color)).unwrap();
+    }
+    colors
+}
+
+/// Returns the color of each triangle, adjacent triangles have different colors.
+pub fn color_triangles(indices: &[usize]) -> Vec<usize> {
+    greedy_coloring(DualGraph::new(indices).adjacency())
+}
+
+/// Returns the number of colors of a coloring.
+pub fn color_count(colors: &[usize]) -> usize {
+    colors.iter().max().map_or(0, |&max| max + 1)
+}
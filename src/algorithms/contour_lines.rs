@@ -0,0 +1,108 @@
+//! Contour lines of a terrain given as a triangulated irregular network (TIN): 2D points with a height each,
+//! triangulated, and interpolated linearly over each triangle. A contour line crosses a triangle along a segment
+//! between two of its edges, the segments of neighbour triangles meet on their shared edge.
+//! The vertices at the height of a contour count as above it, so that lines never go through them.
+
+use crate::math::{ Vec2, float };
+
+use alloc::{ vec, vec::Vec, collections::BTreeMap };
+
+/// Part of the contour of a terrain at a height.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ContourLine {
+    pub level: f32,
+    pub points: Vec<Vec2>,
+    /// Whether the last point is linked to the first one, the line is open if it ends on the boundary of the terrain
+    pub closed: bool,
+}
+
+/// Returns the multiples of `interval` between `min` and `max`, included, or none if `interval` is not positive.
+pub fn contour_levels(min: f32, max: f32, interval: f32) -> Vec<f32> {
+    if interval <= 0.0 || min > max {
+        return Vec::new();
+    }
+    let mut first = float::round(min / interval);
+    if first * interval < min {
+        first += 1.0;
+    }
+    (0..)
+        .map(|k| (first + k as f32) * interval)
+        .take_while(|&level| level <= max)
+        .collect()
+}
+
+/// Returns the height at `p` of the terrain of the points with their `heights`, triangulated by `indices`,
+/// interpolated linearly in the triangle containing `p`, or `None` if `p` is outside the triangulation.
+pub fn interpolate(points: &[Vec2], heights: &[f32], indices: &[usize], p: Vec2) -> Option<f32> {
+    indices.chunks(3).find_map(|t| {
+        let (a, b, c) = (points[t[0]], points[t[1]], points[t[2]]);
+        let area = Vec2::orientation(a, b, c);
+        if area == 0.0 {
+            return None;
+        }
+        // Barycentric coordinates, all positive inside the triangle whatever its orientation
+        let weights = [ Vec2::orientation(p, b, c) / area, Vec2::orientation(a, p, c) / area, Vec2::orientation(a, b, p) / area ];
+        if weights.iter().any(|&w| w < 0.0) {
+            return None;
+        }
+        Some(weights.iter().zip(t).map(|(&w, &idx)| w as f32 * heights[idx]).sum())
+    })
+}
+
+/// Returns the contour lines at `level` of the terrain of the points with their `heights`,
+/// triangulated by `indices`, 3 per triangle.
+pub fn contour_lines(points: &[Vec2], heights: &[f32], indices: &[usize], level: f32) -> Vec<ContourLine> {
+    let above = |idx: usize| heights[idx] >= level;
+    // Where the contour crosses the edge between a point above and a point below
+    let crossing = |(a, b): (usize, usize)| {
+        let t = (level - heights[a]) / (heights[b] - heights[a]);
+        &points[a] + &(&(&points[b] - &points[a]) * t)
+    };
+
+    // Each crossed triangle has a segment between two crossed edges, each crossed edge is shared by one or two segments
+    let mut segments: Vec<[(usize, usize); 2]> = Vec::new();
+    let mut segments_of_edge = BTreeMap::new();
+    for t in indices.chunks(3) {
+        let crossed = [ (t[0], t[1]), (t[1], t[2]), (t[2], t[0]) ]
+            .iter()
+            .filter(|&&(a, b)| above(a) != above(b))
+            .map(|&(a, b)| (a.min(b), a.max(b)))
+            .collect::<Vec<_>>();
+        if let [ first, second ] = crossed[..] {
+            for &edge in &[ first, second ] {
+                segments_of_edge.entry(edge).or_insert_with(Vec::new).push(segments.len());
+            }
+            segments.push([ first, second ]);
+        }
+    }
+
+    // The lines that end on the boundary start from an edge with a single segment, the others are loops
+    let mut used = vec![ false; segments.len() ];
+    let mut lines = Vec::new();
+    let starts = segments_of_edge
+        .iter()
+        .filter(|(_, segments)| segments.len() == 1)
+        .map(|(&edge, segments)| (edge, segments[0]))
+        .chain(segments.iter().enumerate().map(|(idx, segment)| (segment[0], idx)))
+        .collect::<Vec<_>>();
+    for (start, first) in starts {
+        if used[first] {
+            continue;
+        }
+        let mut edge = start;
+        let mut points = vec![ crossing(edge) ];
+        let mut current = Some(first);
+        while let Some(idx) = current {
+            used[idx] = true;
+            edge = if segments[idx][0] == edge { segments[idx][1] } else { segments[idx][0] };
+            points.push(crossing(edge));
+            current = segments_of_edge[&edge].iter().copied().find(|&next| !used[next]);
+        }
+        let closed = points.len() > 3 && edge == start;
+        if closed {
+            points.pop();
+        }
+        lines.push(ContourLine { level, points, closed });
+    }
+    lines
+}
@@ -0,0 +1,175 @@
+//! Boundaries of the regions of a binary image, traced with the Moore neighbour algorithm.
+//! The tracing walks around a region from its topmost leftmost pixel, each step searching clockwise
+//! among the 8 neighbours of the current pixel for the next pixel of the region, starting next to the pixel it came from.
+//! It stops when it leaves the first pixel again in the same direction as at the start, Jacob's criterion,
+//! so that regions whose boundary goes through the first pixel twice are traced completely.
+
+use crate::math::{ Vec2, polygon::signed_area };
+
+use alloc::{ vec, vec::Vec };
+
+/// Offsets of the 8 neighbours of a pixel, clockwise from the left one, with the rows going down
+const NEIGHBOURS: [(isize, isize); 8] = [ (-1, 0), (-1, -1), (0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 1) ];
+
+/// Region of a binary image, 8-connected, with the 4-connected background inside it.
+#[derive(Clone, PartialEq, Debug)]
+pub struct TracedRegion {
+    /// Boundary of the region through the centers of its border pixels, counter-clockwise
+    pub outer: Vec<Vec2>,
+    /// Boundaries of the holes of the region through the centers of their border pixels, clockwise
+    pub holes: Vec<Vec<Vec2>>,
+}
+
+/// Traces the boundaries of the regions of the pixels set in `mask`, which holds `width` pixels per row
+/// from the top left corner. The boundaries are in pixels from the bottom left corner of the image, y pointing up,
+/// without the vertices in the middle of straight runs. The regions and the holes without area,
+/// a single pixel or a line one pixel wide, are left out, and the boundaries touch themselves where the regions
+/// are only one pixel wide.
+pub fn trace_contours(mask: &[bool], width: usize, height: usize) -> Vec<TracedRegion> {
+    let _span = span!("Contour tracing");
+    assert_eq!(mask.len(), width * height);
+
+    // The background between the regions is 4-connected, so that it does not leak between diagonal pixels
+    let (regions, region_count) = label(mask, width, height, true);
+    let (background, background_count) = label(&mask.iter().map(|&set| !set).collect::<Vec<_>>(), width, height, false);
+
+    // The background touching the sides of the image surrounds the regions, the rest is in holes
+    let mut outside = vec![ false; background_count ];
+    for (idx, &component) in background.iter().enumerate() {
+        let (x, y) = (idx % width, idx / width);
+        if component != 0 && (x == 0 || y == 0 || x == width - 1 || y == height - 1) {
+            outside[component - 1] = true;
+        }
+    }
+
+    let mut traced = vec![ None; region_count ];
+    let mut holes = vec![ Vec::new(); region_count ];
+    let mut seen = vec![ false; region_count + background_count ];
+    for idx in 0..mask.len() {
+        let (x, y) = (idx % width, idx / width);
+        if regions[idx] != 0 {
+            let region = regions[idx] - 1;
+            if !seen[region] {
+                seen[region] = true;
+                traced[region] = boundary(&regions, width, height, (x, y));
+            }
+        } else {
+            let component = background[idx] - 1;
+            if !seen[region_count + component] {
+                seen[region_count + component] = true;
+                if !outside[component] {
+                    // The first pixel of a hole is on the right of a pixel of the region around it
+                    let region = regions[idx - 1] - 1;
+                    if let Some(hole) = boundary(&background, width, height, (x, y)) {
+                        holes[region].push(hole);
+                    }
+                }
+            }
+        }
+    }
+
+    traced
+        .into_iter()
+        .zip(holes)
+        .filter_map(|(outer, holes)| outer.map(|outer| TracedRegion { outer, holes }))
+        .map(|mut region| {
+            if signed_area(&region.outer) < 0.0 {
+                region.outer.reverse();
+            }
+            for hole in &mut region.holes {
+                if signed_area(hole) > 0.0 {
+                    hole.reverse();
+                }
+            }
+            region
+        })
+        .collect()
+}
+
+/// Labels the connected components of the set pixels from 1, 0 for the others, and returns the labels and their count.
+fn label(mask: &[bool], width: usize, height: usize, eight_connected: bool) -> (Vec<usize>, usize) {
+    let mut labels = vec![ 0; mask.len() ];
+    let mut count = 0;
+    let mut stack = Vec::new();
+    for start in 0..mask.len() {
+        if !mask[start] || labels[start] != 0 {
+            continue;
+        }
+        count += 1;
+        labels[start] = count;
+        stack.push(start);
+        while let Some(idx) = stack.pop() {
+            for (k, &(dx, dy)) in NEIGHBOURS.iter().enumerate() {
+                // The even offsets are the 4 sides, the odd ones the corners
+                if !eight_connected && k % 2 == 1 {
+                    continue;
+                }
+                if let Some(neighbour) = offset(width, height, idx % width, idx / width, dx, dy) {
+                    if mask[neighbour] && labels[neighbour] == 0 {
+                        labels[neighbour] = count;
+                        stack.push(neighbour);
+                    }
+                }
+            }
+        }
+    }
+    (labels, count)
+}
+
+/// Returns the index of the pixel at (`x + dx`, `y + dy`), or `None` out of the image.
+fn offset(width: usize, height: usize, x: usize, y: usize, dx: isize, dy: isize) -> Option<usize> {
+    let (x, y) = (x as isize + dx, y as isize + dy);
+    if x < 0 || y < 0 || x >= width as isize || y >= height as isize {
+        return None;
+    }
+    Some(y as usize * width + x as usize)
+}
+
+/// Traces the boundary of the component of `labels` whose topmost leftmost pixel is `start`,
+/// or returns `None` if it has no area.
+fn boundary(labels: &[usize], width: usize, height: usize, start: (usize, usize)) -> Option<Vec<Vec2>> {
+    let component = labels[start.1 * width + start.0];
+    let inside = |(x, y): (usize, usize), (dx, dy): (isize, isize)| {
+        offset(width, height, x, y, dx, dy).filter(|&idx| labels[idx] == component).map(|idx| (idx % width, idx / width))
+    };
+
+    // The pixel on the left of the first one is out of the component, the search starts from it
+    let mut pixels = Vec::new();
+    let (mut p, mut back, mut first_move) = (start, 0, None);
+    loop {
+        let next = (1..=8).map(|k| (back + k) % 8).find_map(|k| inside(p, NEIGHBOURS[k]).map(|q| (k, q)));
+        let (k, q) = next?;
+        if p == start {
+            match first_move {
+                Some(first) if first == k => break,
+                Some(_) => {},
+                None => first_move = Some(k),
+            }
+        }
+        pixels.push(p);
+
+        // The next search starts from the last neighbour found out of the component, seen from q
+        let (before, after) = (NEIGHBOURS[(k + 7) % 8], NEIGHBOURS[k]);
+        let from_q = (before.0 - after.0, before.1 - after.1);
+        back = NEIGHBOURS.iter().position(|&d| d == from_q).expect("Consecutive neighbours are adjacent");
+        p = q;
+    }
+
+    // Pixel centers, y up, without the pixels in the middle of straight runs
+    let points = pixels
+        .iter()
+        .map(|&(x, y)| Vec2::new(x as f32 + 0.5, (height - y) as f32 - 0.5))
+        .collect::<Vec<_>>();
+    let n = points.len();
+    let corners = (0..n)
+        .filter(|&i| {
+            let (prev, p, next) = (points[(i + n - 1) % n], points[i], points[(i + 1) % n]);
+            Vec2::orientation(prev, p, next) != 0.0 || (&p - &prev).dot(&next - &p) <= 0.0
+        })
+        .map(|i| points[i])
+        .collect::<Vec<_>>();
+    if signed_area(&corners) == 0.0 {
+        return None;
+    }
+    Some(corners)
+}
@@ -0,0 +1,162 @@
+//! The inputs of the 2D hull algorithms, and checks that a polygon is the convex hull of a set of points,
+//! whatever the algorithm that computed it.
+
+use crate::math::{ Vec2, Segment2, float, polygon };
+
+use alloc::{ vec, vec::Vec, collections::BTreeSet };
+
+/// Relative tolerance of the orientation tests, points closer to an edge are considered on it
+const EPSILON: f32 = 1e-5;
+
+/// A geometry whose convex hull can be computed. The hull of a segment or of a polygon,
+/// convex or not, is the hull of its vertices, so the hull algorithms only use them.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Primitive {
+    Point(Vec2),
+    Segment(Segment2),
+    /// The vertices of a polygon, in any order
+    Polygon(Vec<Vec2>),
+}
+
+impl Primitive {
+    pub fn vertices(&self) -> impl Iterator<Item = Vec2> + '_ {
+        let (a, b, others) = match self {
+            Primitive::Point(point) => (Some(*point), None, &[][..]),
+            Primitive::Segment(segment) => (Some(segment.a), Some(segment.b), &[][..]),
+            Primitive::Polygon(vertices) => (None, None, &vertices[..]),
+        };
+        a.into_iter().chain(b).chain(others.iter().cloned())
+    }
+}
+
+impl From<Vec2> for Primitive {
+    fn from(point: Vec2) -> Self {
+        Primitive::Point(point)
+    }
+}
+
+impl From<Segment2> for Primitive {
+    fn from(segment: Segment2) -> Self {
+        Primitive::Segment(segment)
+    }
+}
+
+impl From<Vec<Vec2>> for Primitive {
+    fn from(vertices: Vec<Vec2>) -> Self {
+        Primitive::Polygon(vertices)
+    }
+}
+
+/// Returns the vertices of the primitives in their order, without duplicates:
+/// the vertices shared by several segments or polygons are only returned once.
+pub fn primitive_vertices(primitives: &[Primitive]) -> Vec<Vec2> {
+    let mut seen = BTreeSet::new();
+    primitives.iter()
+        .flat_map(Primitive::vertices)
+        .filter(|p| seen.insert(((p.x + 0.0).to_bits(), (p.y + 0.0).to_bits()))) // + 0.0 turns -0 into 0
+        .collect()
+}
+
+/// A reason why a polygon is not the convex hull of a set of points.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum HullViolation {
+    /// The index at this position of the hull does not refer to an input point
+    InvalidIndex(usize),
+    /// The vertex at this position of the hull is at the same position as a previous one
+    Duplicate(usize),
+    /// The hull turns clockwise or goes exactly straight at the vertex at this position
+    NotConvex(usize),
+    /// The vertices of the hull are in clockwise order
+    Clockwise,
+    /// The input point with this index is outside of the hull
+    Outside(usize),
+}
+
+/// Checks that `hull`, the indices of some of the `points`, is their convex hull as computed
+/// by [`JarvisMarch`](super::JarvisMarch) and [`GrahamScan`](super::GrahamScan): a counter-clockwise
+/// convex polygon, without points in the middle of its edges, that contains all the points.
+/// Fewer than 3 points have a degenerate hull: nothing, a single point or the two ends of a segment.
+/// Returns all the violations found, none if the hull is valid.
+pub fn validate_hull(points: &[Vec2], hull: &[usize]) -> Vec<HullViolation> {
+    let _span = span!("Hull validation");
+    let mut violations = Vec::new();
+
+    let mut seen = vec![ false; points.len() ];
+    for (pos, &idx) in hull.iter().enumerate() {
+        if idx >= points.len() {
+            violations.push(HullViolation::InvalidIndex(pos));
+        } else if seen[idx] || hull[..pos].iter().any(|&other| other < points.len() && points[other] == points[idx]) {
+            violations.push(HullViolation::Duplicate(pos));
+        } else {
+            seen[idx] = true;
+        }
+    }
+    if !violations.is_empty() {
+        return violations;
+    }
+    let vertices = hull.iter().map(|&idx| points[idx]).collect::<Vec<_>>();
+
+    // A polygon turning the other way has every point on the wrong side, it is only reported once
+    let n = vertices.len();
+    if n >= 3 {
+        let area = (0..n).map(|i| cross(vertices[i], vertices[(i + 1) % n], Vec2::default())).sum::<f32>();
+        if area <= 0.0 {
+            return vec![ HullViolation::Clockwise ];
+        }
+        for i in 0..n {
+            let (prev, vertex, next) = (vertices[(i + n - 1) % n], vertices[i], vertices[(i + 1) % n]);
+            if is_right(prev, vertex, next) || Vec2::orientation(prev, vertex, next) == 0.0 {
+                violations.push(HullViolation::NotConvex(i));
+            }
+        }
+    }
+
+    for (idx, &p) in points.iter().enumerate() {
+        let inside = match n {
+            0 => false,
+            1 => p == vertices[0],
+            2 => is_on_segment(vertices[0], vertices[1], p),
+            _ => (0..n).all(|i| !is_right(vertices[i], vertices[(i + 1) % n], p)),
+        };
+        if !inside {
+            violations.push(HullViolation::Outside(idx));
+        }
+    }
+    violations
+}
+
+/// Returns the points at the indices of `hull`, e.g. to compare hulls computed from different point sets.
+pub fn hull_positions(points: &[Vec2], hull: &[usize]) -> Vec<Vec2> {
+    hull.iter().map(|&idx| points[idx]).collect()
+}
+
+/// Returns the area enclosed by `hull`, the indices of some of the `points` in counter-clockwise order.
+pub fn hull_area(points: &[Vec2], hull: &[usize]) -> f32 {
+    polygon::signed_area(&hull_positions(points, hull))
+}
+
+/// Returns the length of the boundary of `hull`, twice the length of a hull made of the two ends of a segment.
+pub fn hull_perimeter(points: &[Vec2], hull: &[usize]) -> f32 {
+    polygon::edges(&hull_positions(points, hull)).map(|(a, b)| (&b - &a).length()).sum()
+}
+
+/// Returns the cross product of `a - o` and `b - o`, positive if (o, a, b) is counter-clockwise.
+fn cross(a: Vec2, b: Vec2, o: Vec2) -> f32 {
+    (a.x - o.x) * (b.y - o.y) - (a.y - o.y) * (b.x - o.x)
+}
+
+/// Returns the tolerance of the orientation test of `p` against the line (a, b).
+fn tolerance(a: Vec2, b: Vec2, p: Vec2) -> f32 {
+    EPSILON * (&b - &a).length() * (&p - &a).length().max((&p - &b).length())
+}
+
+/// Returns true if `p` is strictly on the right of the line (a, b), beyond the tolerance.
+fn is_right(a: Vec2, b: Vec2, p: Vec2) -> bool {
+    cross(b, p, a) < -tolerance(a, b, p)
+}
+
+fn is_on_segment(a: Vec2, b: Vec2, p: Vec2) -> bool {
+    let ab = &b - &a;
+    let t = (&p - &a).dot(ab) / ab.sqr_length();
+    float::abs(cross(b, p, a)) <= tolerance(a, b, p) && (-EPSILON..=1.0 + EPSILON).contains(&t)
+}
@@ -1,39 +1,39 @@
-use crate::{
-    graphics,
-    math::{ Vec3, ToArray },
-    ui::window::algorithms::{ Drawable, Configurable },
-};
-
-use std::time::{ Duration, /*Instant*/ };
-
-use glium::{
-    index, Surface, Frame, Program, VertexBuffer, IndexBuffer, DrawParameters, Depth, DepthTest,
-    backend::Facade,
-};
-use cgmath::{ SquareMatrix, InnerSpace };
-
-#[derive(Copy, Clone, Debug)]
-pub struct Vertex {
-    position: Vec3,
-    normal: Vec3,
-}
+//! Convex hull of 3D points, built incrementally with a conflict graph: each point not inserted yet
+//! knows the faces it sees, and each face the points that see it. Inserting a point removes its visible faces,
+//! found from its conflicts without testing the whole hull, and joins the point to the horizon of these faces.
+//! A point that sees a new face saw one of the two faces around its horizon edge, so only their conflicts are tested again.
+//! With the points in random order, the construction takes O(n log n) expected time.
 
-impl Vertex {
-    pub fn new(position: Vec3, normal: Vec3) -> Self {
-        Self {
-            position,
-            normal,
-        }
-    }
-}
+use crate::math::{ Vec3, XorShift };
+
+use alloc::{ vec, vec::Vec, collections::BTreeMap };
+use cgmath::InnerSpace;
+
+/// Relative tolerance of the visibility tests, scaled by the extent of the points
+const TOLERANCE: f64 = 1e-9;
+/// Distance under which a point is on the boundary of a hull, in single precision
+const BOUNDARY_TOLERANCE: f32 = 1e-5;
 
+/// Classification of the elements of a hull under construction by the next point to insert.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum Color {
+    /// Not classified, in a complete hull
     White,
+    /// Seen by the next point, removed when it is inserted
     Red,
+    /// Hidden from the next point, kept
     Blue,
+    /// Between red and blue faces: the horizon of the next point, joined to it when it is inserted
     Purple,
 }
 
+impl Color {
+    /// Returns the color of an element shared by elements of the colors `self` and `other`.
+    fn mix(self, other: Color) -> Color {
+        if self == other { self } else { Color::Purple }
+    }
+}
+
 pub struct GraphVertex {
     pub idx: u32,
     pub color: Color,
@@ -50,10 +50,10 @@ pub struct GraphFace {
 }
 
 impl GraphFace {
-    pub fn normal(&self, vertices: &[Vertex]) -> Vec3 {
-        let p1 = vertices[self.idx.0 as usize].position.0;
-        let p2 = vertices[self.idx.1 as usize].position.0;
-        let p3 = vertices[self.idx.2 as usize].position.0;
+    pub fn normal(&self, vertices: &[Vec3]) -> Vec3 {
+        let p1 = vertices[self.idx.0 as usize].0;
+        let p2 = vertices[self.idx.1 as usize].0;
+        let p3 = vertices[self.idx.2 as usize].0;
         (p2 - p1).cross(p3 - p1).into()
     }
 }
@@ -65,233 +65,416 @@ pub struct Graph {
     pub faces: Vec<GraphFace>,
 }
 
-implement_vertex!(Vertex, position, normal);
+impl Graph {
+    /// Returns the volume enclosed by the faces, a closed surface of `vertices` counterclockwise seen from outside.
+    pub fn volume(&self, vertices: &[Vec3]) -> f32 {
+        self.tetrahedra(vertices).map(|(volume, _)| volume).sum()
+    }
+
+    /// Returns the total area of the faces.
+    pub fn surface_area(&self, vertices: &[Vec3]) -> f32 {
+        self.faces.iter().map(|face| face.normal(vertices).0.magnitude() / 2.0).sum()
+    }
+
+    /// Returns the center of mass of the solid enclosed by the faces, or `None` if it has no volume.
+    pub fn centroid(&self, vertices: &[Vec3]) -> Option<Vec3> {
+        let (volume, moment) = self.tetrahedra(vertices)
+            .fold((0.0, cgmath::vec3(0.0, 0.0, 0.0)), |(total, moment), (volume, centroid)| (total + volume, moment + centroid * volume));
+        if volume > 0.0 {
+            Some((moment / volume).into())
+        } else {
+            None
+        }
+    }
 
-pub struct ConvexHull<'f> {
-    facade: &'f dyn Facade,
-    cam: graphics::OrbitCamera,
-    axes: graphics::axes::Axes,
+    /// Returns the signed volume and the centroid of the tetrahedron joining each face to the first vertex,
+    /// they add up to the volume of the solid and weight its centroid.
+    fn tetrahedra<'a>(&'a self, vertices: &'a [Vec3]) -> impl Iterator<Item = (f32, cgmath::Vector3<f32>)> + 'a {
+        let apex = self.vertices.first().map_or(cgmath::vec3(0.0, 0.0, 0.0), |v| vertices[v.idx as usize].0);
+        self.faces.iter().map(move |face| {
+            let (a, b, c) = (vertices[face.idx.0 as usize].0, vertices[face.idx.1 as usize].0, vertices[face.idx.2 as usize].0);
+            let volume = (a - apex).dot((b - apex).cross(c - apex)) / 6.0;
+            (volume, (apex + a + b + c) / 4.0)
+        })
+    }
+}
+
+/// Convex hull of a set of 3D points.
+#[derive(Default)]
+pub struct ConvexHull {
     /// Input points that will be wrapped in the generated hull
-    points: Vec<Vertex>,
-    points_program: Program,
-    faces_program: Program,
-    /// Buffer object that stores all the points
-    points_buffer: VertexBuffer<Vertex>,
-    faces_indices: IndexBuffer<u32>,
-    exec_time: Option<Duration>,
-    rotate_model: bool,
-    is_rotating: bool,
-    model_matrix: cgmath::Matrix4<f32>,
+    points: Vec<Vec3>,
+    graph: Graph,
 }
 
-impl<'f> Drawable for ConvexHull<'f> {
-    fn draw(&self, target: &mut Frame) {
-        self.draw_points(target);
-        self.draw_faces(target);
-        self.axes.draw(target);
+impl ConvexHull {
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    fn handle_events(&mut self, window: &winit::Window, event: &winit::Event, io: &imgui::Io) {
-        self.cam.handle_events(window, event, io);
-        if !self.rotate_model {
-            self.axes.handle_events(window, event, io);
-        }
+    pub fn points(&self) -> &[Vec3] {
+        &self.points
+    }
 
-        if let winit::Event::WindowEvent { event, .. } = event {
-            if let winit::WindowEvent::MouseInput { button, state, .. } = event {
-                if !io.want_capture_mouse && // Ignore clicks when the cursor is over an ImGui window
-                    button == &winit::MouseButton::Left && self.rotate_model {
+    /// Returns the vertices, edges and faces of the hull.
+    pub fn graph(&self) -> &Graph {
+        &self.graph
+    }
 
-                    self.is_rotating = state == &winit::ElementState::Pressed;
-                }
-            }
-        }
+    /// Replaces the input points and builds their hull.
+    pub fn set_points(&mut self, points: Vec<Vec3>) {
+        self.graph = Self::hull(&points);
+        self.points = points;
+    }
 
-        if let winit::Event::DeviceEvent { event, .. } = event {
-            if let winit::DeviceEvent::MouseMotion { delta } = event {
-                if self.rotate_model && self.is_rotating {
-                    self.model_matrix = self.model_matrix * cgmath::Matrix4::<f32>::from_angle_y(cgmath::Rad { 0: delta.0 as f32 * 0.01 });
-                    self.model_matrix = self.model_matrix * cgmath::Matrix4::<f32>::from_angle_x(cgmath::Rad { 0: delta.1 as f32 * 0.01 });
-                }
-            }
+    pub fn clear(&mut self) {
+        self.points.clear();
+        self.graph = Graph::default();
+    }
+
+    /// Moves the point at `idx` and updates the hull. It is only rebuilt if the point is one of its vertices or leaves it,
+    /// a point moving inside of the hull leaves it unchanged. Returns true if the hull was rebuilt.
+    pub fn move_point(&mut self, idx: usize, point: Vec3) -> bool {
+        self.points[idx] = point;
+        let is_vertex = self.graph.vertices.iter().any(|vertex| vertex.idx as usize == idx);
+        if is_vertex || !self.contains(point) {
+            self.graph = Self::hull(&self.points);
+            return true;
         }
+        false
+    }
+
+    /// Returns true if the point is inside of the hull or on its boundary.
+    pub fn contains(&self, point: Vec3) -> bool {
+        !self.graph.faces.is_empty() && self.graph.faces.iter().all(|face| {
+            let normal = face.normal(&self.points).0;
+            normal.dot(point.0 - self.points[face.idx.0 as usize].0) <= BOUNDARY_TOLERANCE * normal.magnitude()
+        })
     }
-}
 
-impl<'f> Configurable for ConvexHull<'f> {
-    fn name(&self) -> &'static str {
-        "3D Convex hull"
+    /// Returns the volume of the hull.
+    pub fn volume(&self) -> f32 {
+        self.graph.volume(&self.points)
     }
 
-    fn configure(&mut self, ui: &imgui::Ui) {
-        ui.text(imgui::im_str!("{} vertices", self.points.len()));
+    /// Returns the area of the boundary of the hull.
+    pub fn surface_area(&self) -> f32 {
+        self.graph.surface_area(&self.points)
+    }
 
-        if ui.checkbox(imgui::im_str!("Rotate model"), &mut self.rotate_model) {
-            self.cam.zoom = !self.rotate_model;
-            self.cam.rotate = !self.rotate_model;
-        }
+    /// Returns the center of mass of the hull, or `None` if it is empty.
+    pub fn centroid(&self) -> Option<Vec3> {
+        self.graph.centroid(&self.points)
+    }
 
-        if let Some(exec_time) = self.exec_time {
-            ui.text(imgui::im_str!("Execution time: {} µs", exec_time.as_micros()));
+    /// Builds the hull of the points, its faces are counterclockwise seen from outside.
+    /// The hull is empty if there are less than 4 points or if they all lie in a plane.
+    pub fn hull(points: &[Vec3]) -> Graph {
+        let n = points.len();
+        if n < 4 {
+            log::warn!("At least 4 points are needed to build a 3D convex hull, got {}", n);
+            return Graph::default();
+        }
+        let _span = span!("3D convex hull");
+        match IncrementalHull::new(points) {
+            Some(mut hull) => {
+                while hull.step().is_some() {}
+                hull.graph()
+            },
+            None => {
+                log::warn!("The points of the 3D convex hull are coplanar");
+                Graph::default()
+            },
         }
     }
 }
 
-impl<'f> ConvexHull<'f> {
-    pub fn new(facade: &'f dyn Facade) -> Self {
-        let vs = graphics::SHADERS._3d_vs;
-        let fs = graphics::SHADERS.basic_fs;
-        let points_program = Program::from_source(facade, vs, fs, None)
-                                    .expect("Could not compile shaders");
-
-        let vs = graphics::SHADERS._3d_vs;
-        let fs = graphics::SHADERS.phong_fs;
-        let faces_program = Program::from_source(facade, vs, fs, None)
-                                    .expect("Could not compile shaders");
-
-        let mut points = vec! [
-            Vertex {position: Vec3::new(-0.5, 0.0, 0.0,), normal: Vec3::new(0.0, 0.0, 0.0)},
-            Vertex {position: Vec3::new(0.0, 0.0, 0.5,), normal: Vec3::new(0.0, 0.0, 0.0)},
-            Vertex {position: Vec3::new(0.5, 0.0, 0.0,), normal: Vec3::new(0.0, 0.0, 0.0)},
-            Vertex {position: Vec3::new(0.0, 0.5, 0.0,), normal: Vec3::new(0.0, 0.0, 0.0)},
-            Vertex {position: Vec3::new(0.9, 0.6, 0.2,), normal: Vec3::new(0.0, 0.0, 0.0)},
-        ];
-        let graph = Self::hull(&points);
-        let faces = graph.faces.iter().fold(vec![], |mut vec, indices| {
-            vec.push(indices.idx.0);
-            vec.push(indices.idx.1);
-            vec.push(indices.idx.2);
-            vec
-        });
-        Self::compute_normals(&mut points, &graph);
-        let points_buffer = VertexBuffer::new(facade, &points).unwrap();
-        let faces_indices = IndexBuffer::new(facade, glium::index::PrimitiveType::TrianglesList, &faces).unwrap();
-
-        Self {
-            facade,
-            cam: graphics::OrbitCamera::new(10.0, Vec3::new(0.0, 0.0, 0.0), 45.0, 1024.0 / 768.0, 0.1, 100.0),
-            axes: graphics::axes::Axes::new(facade),
-            points,
-            points_program,
-            faces_program,
-            points_buffer,
-            faces_indices,
-            exec_time: None,
-            rotate_model: false,
-            is_rotating: false,
-            model_matrix: cgmath::Matrix4::<f32>::identity(),
+/// Face of a hull under construction, counterclockwise seen from outside.
+#[derive(Clone, Debug)]
+struct Face {
+    vertices: [usize; 3],
+    /// Face across the edge from `vertices[i]` to `vertices[(i + 1) % 3]`
+    neighbours: [usize; 3],
+    /// Outward unit normal, the points of the plane satisfy `normal · x = offset`
+    normal: [f64; 3],
+    offset: f64,
+    /// Points not inserted yet that see the face
+    conflicts: Vec<usize>,
+    /// Faces seen by an inserted point are removed, their slots are not reused
+    alive: bool,
+}
+
+/// Randomized incremental construction of the convex hull of 3D points, one point per step,
+/// with the conflict graph between the points not inserted yet and the faces they see.
+#[derive(Clone, Debug)]
+pub struct IncrementalHull {
+    points: Vec<[f64; 3]>,
+    faces: Vec<Face>,
+    /// Faces seen by each point, empty once it is inserted
+    conflicts: Vec<Vec<usize>>,
+    /// Last face each point was tested against, so that a point in the conflicts of both faces around an edge is tested once
+    tested: Vec<usize>,
+    /// Insertion order, the vertices of the first tetrahedron and then the other points shuffled
+    order: Vec<usize>,
+    /// Position in `order` of the next point to insert
+    next: usize,
+    /// Distance under which a point is on the plane of a face, from the extent of the points
+    epsilon: f64,
+}
+
+impl IncrementalHull {
+    /// Starts the hull with a tetrahedron of the points and finds the faces seen by the others.
+    /// Returns `None` if there are less than 4 points or if they all lie in a plane.
+    pub fn new(points: &[Vec3]) -> Option<Self> {
+        let points = points.iter().map(|p| [ p.0.x as f64, p.0.y as f64, p.0.z as f64 ]).collect::<Vec<_>>();
+        let extent = points.iter().flatten().fold(0.0f64, |extent, &x| extent.max(x.abs()));
+        let epsilon = TOLERANCE * extent.max(1.0);
+
+        // Fixed xorshift shuffle, so that the construction is reproducible
+        let mut order = (0..points.len()).collect::<Vec<_>>();
+        XorShift::default().shuffle(&mut order);
+
+        let [ a, mut b, mut c, d ] = initial_tetrahedron(&points, &order, epsilon)?;
+        order.retain(|idx| ![ a, b, c, d ].contains(idx));
+        order.splice(0..0, [ a, b, c, d ].iter().copied());
+        if dot(&plane(&points, [ a, b, c ]).0, &sub(&points[d], &points[a])) > 0.0 {
+            core::mem::swap(&mut b, &mut c);
         }
-    }
 
-    fn draw_points(&self, target: &mut Frame) {
-        let indices = index::NoIndices(index::PrimitiveType::Points);
-        let uniforms = uniform! {
-            model: cgmath::Matrix4::<f32>::identity().to_array(),
-            view: self.cam.get_view_matrix().to_array(),
-            projection: self.cam.get_projection_matrix().to_array(),
-            color: [ 0.0_f32, 0.2_f32, 1.0_f32 ],
-        };
-        let mut draw_params = DrawParameters::default();
-        draw_params.point_size = Some(8.0);
-        draw_params.depth = Depth {
-            write: true,
-            test: DepthTest::IfLess,
-            .. Default::default()
+        let mut hull = Self {
+            conflicts: vec![ Vec::new(); points.len() ],
+            tested: vec![ usize::MAX; points.len() ],
+            points,
+            faces: Vec::new(),
+            order,
+            next: 4,
+            epsilon,
         };
-        target.draw(&self.points_buffer, &indices, &self.points_program, &uniforms, &draw_params).expect("Draw failure");
-    }
+        let faces = [ [ a, b, c ], [ a, d, b ], [ b, d, c ], [ c, d, a ] ];
+        let mut edges = BTreeMap::new();
+        for (idx, &vertices) in faces.iter().enumerate() {
+            for i in 0..3 {
+                edges.insert((vertices[i], vertices[(i + 1) % 3]), idx);
+            }
+            hull.add_face(vertices);
+        }
+        for face in &mut hull.faces {
+            let v = face.vertices;
+            for i in 0..3 {
+                face.neighbours[i] = edges[&(v[(i + 1) % 3], v[i])];
+            }
+        }
 
-    fn draw_faces(&self, target: &mut Frame) {
-        let uniforms = uniform! {
-            model: self.model_matrix.to_array(),
-            view: self.cam.get_view_matrix().to_array(),
-            projection: self.cam.get_projection_matrix().to_array(),
-            viewPosition: self.cam.get_position().to_array(),
-            materialAmbient: [ 1.0_f32, 1.0_f32, 1.0_f32 ],
-            materialDiffuse: [ 1.0_f32, 1.0_f32, 1.0_f32 ],
-            materialSpecular: [ 0.5_f32, 0.5_f32, 0.5_f32 ],
-            materialShininess: 32.0_f32,
-            lightPosition: [ 5.0_f32, 1.0_f32, 0.0_f32 ],
-            lightAmbient: [ 0.2_f32, 0.2_f32, 0.2_f32 ],
-            lightDiffuse: [ 0.75_f32, 0.75_f32, 0.75_f32 ],
-            lightSpecular: [ 0.6_f32, 0.6_f32, 0.6_f32 ],
-        };
-        let mut draw_params = DrawParameters::default();
-        draw_params.depth = Depth {
-            write: true,
-            test: DepthTest::IfLess,
-            .. Default::default()
-        };
-        target.draw(&self.points_buffer, &self.faces_indices, &self.faces_program, &uniforms, &draw_params).expect("Draw failure");
+        for idx in 4..hull.order.len() {
+            for face in 0..4 {
+                hull.add_conflict(face, hull.order[idx]);
+            }
+        }
+        Some(hull)
     }
 
-    pub fn hull(points: &[Vertex]) -> Graph {
-        let mut graph = Graph::default();
+    /// Inserts the next point and returns its index, or `None` once all the points are inserted.
+    /// A point inside the hull sees no face and leaves it unchanged.
+    pub fn step(&mut self) -> Option<usize> {
+        let p = *self.order.get(self.next)?;
+        self.next += 1;
+        let visible = core::mem::take(&mut self.conflicts[p]);
+        if visible.is_empty() {
+            return Some(p);
+        }
+        for &face in &visible {
+            self.faces[face].alive = false;
+        }
 
-        let n = points.len();
-        if n < 4 {
-            return graph;
+        // Each horizon edge, from a visible face to a hidden one, becomes the base of a new face joined to p
+        let mut horizon = Vec::new();
+        for &face in &visible {
+            let Face { vertices, neighbours, .. } = self.faces[face];
+            for i in 0..3 {
+                if self.faces[neighbours[i]].alive {
+                    horizon.push((vertices[i], vertices[(i + 1) % 3], face, neighbours[i]));
+                }
+            }
         }
-        let iter = points.iter().skip(4);
-
-        // Initial tetrahedron
-        graph.vertices.push(GraphVertex { idx: 0, color: Color::White });
-        graph.vertices.push(GraphVertex { idx: 1, color: Color::White });
-        graph.vertices.push(GraphVertex { idx: 2, color: Color::White });
-        graph.vertices.push(GraphVertex { idx: 3, color: Color::White });
-        graph.edges.push(GraphEdge { idx: (0, 1), color: Color::White });
-        graph.edges.push(GraphEdge { idx: (0, 2), color: Color::White });
-        graph.edges.push(GraphEdge { idx: (0, 3), color: Color::White });
-        graph.edges.push(GraphEdge { idx: (1, 2), color: Color::White });
-        graph.edges.push(GraphEdge { idx: (1, 3), color: Color::White });
-        graph.edges.push(GraphEdge { idx: (2, 3), color: Color::White });
-        graph.faces.push(GraphFace { idx:(0, 1, 2), color: Color::White });
-        graph.faces.push(GraphFace { idx:(0, 1, 3), color: Color::White });
-        graph.faces.push(GraphFace { idx:(0, 2, 3), color: Color::White });
-        graph.faces.push(GraphFace { idx:(1, 2, 3), color: Color::White });
-        let b = points[graph.vertices[0].idx as usize].position.0;
-
-        for _point_to_add in iter {
-            for face in graph.faces.iter() {
-                let mut normal = face.normal(points);
-                let p1 = points[face.idx.0 as usize].position.0;
-                if normal.0.dot(b - p1) > 0.0 {
-                    normal.0 *= -1.0;
+
+        // New faces by the first and last vertex of their horizon edge
+        let mut starting = BTreeMap::new();
+        let mut ending = BTreeMap::new();
+        for &(u, v, visible_face, hidden_face) in &horizon {
+            let face = self.add_face([ u, v, p ]);
+            starting.insert(u, face);
+            ending.insert(v, face);
+            self.faces[face].neighbours[0] = hidden_face;
+            let hidden = &mut self.faces[hidden_face];
+            let i = (0..3).find(|&i| hidden.vertices[i] == v).expect("Broken horizon");
+            hidden.neighbours[i] = face;
+
+            for &other in &[ visible_face, hidden_face ] {
+                for i in 0..self.faces[other].conflicts.len() {
+                    let q = self.faces[other].conflicts[i];
+                    if q != p && self.tested[q] != face {
+                        self.tested[q] = face;
+                        self.add_conflict(face, q);
+                    }
                 }
             }
         }
+        for &face in starting.values() {
+            let [ u, v, _ ] = self.faces[face].vertices;
+            self.faces[face].neighbours[1] = starting[&v];
+            self.faces[face].neighbours[2] = ending[&u];
+        }
 
-        graph
+        for &face in &visible {
+            for q in core::mem::take(&mut self.faces[face].conflicts) {
+                self.conflicts[q].retain(|&other| other != face);
+            }
+        }
+        Some(p)
     }
 
-    fn compute_normals(vertices: &mut Vec<Vertex>, graph: &Graph) {
-        for v in vertices.iter_mut() {
-            v.normal = Vec3::new(0.0, 0.0, 0.0);
-        }
+    /// Returns true once all the points are inserted.
+    pub fn is_done(&self) -> bool {
+        self.next >= self.order.len()
+    }
+
+    /// Returns the number of points not inserted yet.
+    pub fn remaining(&self) -> usize {
+        self.order.len() - self.next
+    }
+
+    /// Returns the vertices of the faces of the hull built so far, counterclockwise seen from outside.
+    pub fn faces(&self) -> impl Iterator<Item = [usize; 3]> + '_ {
+        self.faces.iter().filter(|face| face.alive).map(|face| face.vertices)
+    }
+
+    /// Returns the edges of the conflict graph: each point not inserted yet with the vertices of each face it sees.
+    pub fn conflicts(&self) -> impl Iterator<Item = (usize, [usize; 3])> + '_ {
+        self.conflicts.iter()
+            .enumerate()
+            .flat_map(move |(p, faces)| faces.iter().map(move |&face| (p, self.faces[face].vertices)))
+    }
+
+    /// Returns the point inserted by the next step, or `None` once all the points are inserted.
+    pub fn next_point(&self) -> Option<usize> {
+        self.order.get(self.next).copied()
+    }
 
-        for tri in graph.faces.iter() {
-            let tri = vec![tri.idx.0, tri.idx.1, tri.idx.2];
-            let p1 = vertices[tri[0] as usize].position.0;
-            let p2 = vertices[tri[1] as usize].position.0;
-            let p3 = vertices[tri[2] as usize].position.0;
-            for vert_idx in tri {
-                let vert = &mut vertices[vert_idx as usize];
-                let face_normal = (p2 - p1).cross(p3 - p1);
-                vert.normal = (vert.normal.0 + face_normal).into();
+    /// Returns the horizon of the next point to insert: the loop of the edges between the faces it sees and the others,
+    /// as its vertices in order, counterclockwise seen from the point. It is empty if the point sees no face,
+    /// or once all the points are inserted.
+    pub fn horizon(&self) -> Vec<usize> {
+        let visible = self.next_point().map_or(&[][..], |p| &self.conflicts[p][..]);
+        // Each horizon edge, in the direction of its visible face, leads to the next one
+        let mut edges = BTreeMap::new();
+        for &face in visible {
+            let Face { vertices, neighbours, .. } = self.faces[face];
+            for i in 0..3 {
+                if !visible.contains(&neighbours[i]) {
+                    edges.insert(vertices[i], vertices[(i + 1) % 3]);
+                }
+            }
+        }
+        let mut horizon = Vec::with_capacity(edges.len());
+        if let Some(&start) = edges.keys().next() {
+            let mut v = start;
+            while let Some(&next) = edges.get(&v) {
+                horizon.push(v);
+                v = next;
+                if v == start || horizon.len() == edges.len() {
+                    break;
+                }
             }
         }
+        horizon
+    }
 
-        for v in vertices.iter_mut() {
-            if v.normal.0 == Vec3::new(0.0, 0.0, 0.0).0 {
-                continue;
+    /// Returns the vertices, edges and faces of the hull built so far, colored by the next point to insert:
+    /// the faces it sees are red and the others blue, the edges and vertices are red or blue if all their faces are,
+    /// purple on the horizon. They are all white once the hull is complete.
+    pub fn graph(&self) -> Graph {
+        let visible = self.next_point().map_or(&[][..], |p| &self.conflicts[p][..]);
+        let mut graph = Graph::default();
+        let mut vertices = BTreeMap::new();
+        let mut edges = BTreeMap::new();
+        for (idx, face) in self.faces.iter().enumerate().filter(|(_, face)| face.alive) {
+            let color = match self.next_point() {
+                None => Color::White,
+                Some(_) if visible.contains(&idx) => Color::Red,
+                Some(_) => Color::Blue,
+            };
+            let [ a, b, c ] = face.vertices;
+            for &v in &[ a, b, c ] {
+                let mixed = vertices.get(&v).map_or(color, |&other: &Color| other.mix(color));
+                vertices.insert(v, mixed);
+            }
+            for &(u, v) in &[ (a, b), (b, c), (c, a) ] {
+                let edge = (u.min(v), u.max(v));
+                let mixed = edges.get(&edge).map_or(color, |&other: &Color| other.mix(color));
+                edges.insert(edge, mixed);
             }
-            v.normal = v.normal.0.normalize().into();
+            graph.faces.push(GraphFace { idx: (a as u32, b as u32, c as u32), color });
         }
+        graph.vertices = vertices.into_iter().map(|(idx, color)| GraphVertex { idx: idx as u32, color }).collect();
+        graph.edges = edges.into_iter().map(|((u, v), color)| GraphEdge { idx: (u as u32, v as u32), color }).collect();
+        graph
     }
 
-    pub fn clear(&mut self) {
-        self.points.clear();
-        self.points_buffer = VertexBuffer::empty(self.facade, 0).unwrap();
-        self.faces_indices = IndexBuffer::empty(self.facade, glium::index::PrimitiveType::TrianglesList, 0).unwrap();
+    /// Adds a face without neighbours or conflicts and returns its index.
+    fn add_face(&mut self, vertices: [usize; 3]) -> usize {
+        let (normal, offset) = plane(&self.points, vertices);
+        self.faces.push(Face {
+            vertices,
+            neighbours: [ usize::MAX; 3 ],
+            normal,
+            offset,
+            conflicts: Vec::new(),
+            alive: true,
+        });
+        self.faces.len() - 1
     }
+
+    /// Links the face and the point if the point sees the face.
+    fn add_conflict(&mut self, face: usize, q: usize) {
+        let f = &self.faces[face];
+        if dot(&f.normal, &self.points[q]) - f.offset > self.epsilon {
+            self.faces[face].conflicts.push(q);
+            self.conflicts[q].push(face);
+        }
+    }
+}
+
+/// Returns the first 4 points in `order` that are not coplanar, or `None` if they all lie in a plane.
+fn initial_tetrahedron(points: &[[f64; 3]], order: &[usize], epsilon: f64) -> Option<[usize; 4]> {
+    let a = *order.first()?;
+    let b = *order.iter().find(|&&idx| length(&sub(&points[idx], &points[a])) > epsilon)?;
+    let ab = sub(&points[b], &points[a]);
+    let c = *order.iter().find(|&&idx| length(&cross(&ab, &sub(&points[idx], &points[a]))) > epsilon * length(&ab))?;
+    let (normal, offset) = plane(points, [ a, b, c ]);
+    let d = *order.iter().find(|&&idx| (dot(&normal, &points[idx]) - offset).abs() > epsilon)?;
+    Some([ a, b, c, d ])
+}
+
+/// Returns the unit normal of the plane of the triangle, counterclockwise around it, and its offset from the origin.
+fn plane(points: &[[f64; 3]], [ a, b, c ]: [usize; 3]) -> ([f64; 3], f64) {
+    let normal = cross(&sub(&points[b], &points[a]), &sub(&points[c], &points[a]));
+    let length = length(&normal);
+    let normal = if length > 0.0 { [ normal[0] / length, normal[1] / length, normal[2] / length ] } else { normal };
+    (normal, dot(&normal, &points[a]))
+}
+
+fn sub(a: &[f64; 3], b: &[f64; 3]) -> [f64; 3] {
+    [ a[0] - b[0], a[1] - b[1], a[2] - b[2] ]
+}
+
+fn dot(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: &[f64; 3], b: &[f64; 3]) -> [f64; 3] {
+    [ a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0] ]
+}
+
+fn length(a: &[f64; 3]) -> f64 {
+    dot(a, a).sqrt()
 }
@@ -0,0 +1,271 @@
+//! Convex hull of points in any dimension, built with the beneath-beyond method:
+//! the hull starts as a simplex, then each point outside of it removes the facets it sees
+//! and is joined to the ridges on the horizon of these facets.
+//! The facets are simplices, so the flat cells of hulls with coplanar points are triangulated.
+
+use alloc::{ vec, vec::Vec, collections::{ BTreeMap, BTreeSet } };
+
+/// Relative tolerance of the orientation tests, scaled by the extent of the points
+const TOLERANCE: f64 = 1e-9;
+
+/// Facet of a hull in d dimensions, a (d - 1)-simplex on a supporting hyperplane.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Facet {
+    /// Indices of the d vertices of the facet in the input points
+    pub vertices: Vec<usize>,
+    /// Outward normal of the hyperplane of the facet, not normalized
+    pub normal: Vec<f64>,
+    /// The points of the hull satisfy `normal · x <= offset`, the vertices of the facet with equality
+    pub offset: f64,
+}
+
+impl Facet {
+    /// Returns how far the point is above the hyperplane of the facet, in units of the length of the normal.
+    /// It is positive for the points that see the facet from outside of the hull.
+    pub fn height(&self, point: &[f64]) -> f64 {
+        dot(&self.normal, point) - self.offset
+    }
+}
+
+/// Convex hull of a set of points in d >= 2 dimensions.
+/// The facets form its H-representation, the half-spaces `normal · x <= offset` whose intersection is the hull.
+#[derive(Clone, Debug)]
+pub struct ConvexHullNd {
+    points: Vec<Vec<f64>>,
+    facets: Vec<Facet>,
+    /// Distance under which a point is on a hyperplane, from the extent of the points
+    epsilon: f64,
+}
+
+impl ConvexHullNd {
+    /// Computes the hull of the points, which must all have the same dimension.
+    /// Returns `None` if there are less than d + 1 points or if they all lie in a hyperplane,
+    /// in which case the hull has no interior.
+    pub fn new(points: &[Vec<f64>]) -> Option<Self> {
+        let dimension = points.first()?.len();
+        assert!(points.iter().all(|p| p.len() == dimension), "Points of different dimensions");
+        if dimension < 2 || points.len() <= dimension {
+            return None;
+        }
+
+        let extent = points.iter().flatten().fold(0.0f64, |extent, &x| extent.max(x.abs()));
+        let epsilon = TOLERANCE * extent.max(1.0);
+        let simplex = initial_simplex(points, epsilon)?;
+
+        // The centroid of the simplex stays inside the hull and orients the facets
+        let mut interior = vec![ 0.0; dimension ];
+        for &idx in &simplex {
+            for (c, x) in interior.iter_mut().zip(&points[idx]) {
+                *c += x / simplex.len() as f64;
+            }
+        }
+
+        let mut hull = Self {
+            points: points.to_vec(),
+            facets: Vec::new(),
+            epsilon,
+        };
+        for skipped in 0..simplex.len() {
+            let vertices = simplex.iter().enumerate().filter(|&(i, _)| i != skipped).map(|(_, &idx)| idx).collect();
+            hull.facets.push(hull.facet(vertices, &interior)?);
+        }
+
+        for idx in (0..points.len()).filter(|idx| !simplex.contains(idx)) {
+            hull.insert(idx, &interior)?;
+        }
+        Some(hull)
+    }
+
+    pub fn dimension(&self) -> usize {
+        self.points[0].len()
+    }
+
+    pub fn points(&self) -> &[Vec<f64>] {
+        &self.points
+    }
+
+    pub fn facets(&self) -> &[Facet] {
+        &self.facets
+    }
+
+    /// Returns the sorted indices of the points that are vertices of a facet.
+    pub fn vertices(&self) -> Vec<usize> {
+        let vertices = self.facets.iter().flat_map(|f| f.vertices.iter().copied()).collect::<BTreeSet<_>>();
+        vertices.into_iter().collect()
+    }
+
+    /// Returns the edges of the hull as sorted pairs of indices, each edge once.
+    /// The diagonals that split the flat cells of the hull into simplices are left out:
+    /// a segment between two vertices is an edge when the facets around it span d - 1 independent hyperplanes.
+    pub fn edges(&self) -> Vec<(usize, usize)> {
+        let mut facets_around = BTreeMap::<(usize, usize), Vec<Vec<f64>>>::new();
+        for facet in &self.facets {
+            for (i, &a) in facet.vertices.iter().enumerate() {
+                for &b in &facet.vertices[i + 1..] {
+                    facets_around.entry((a.min(b), a.max(b))).or_default().push(facet.normal.clone());
+                }
+            }
+        }
+        let dimension = self.dimension();
+        facets_around
+            .into_iter()
+            .filter(|(_, normals)| rank(normals.clone(), dimension) + 1 >= dimension)
+            .map(|(edge, _)| edge)
+            .collect()
+    }
+
+    /// Returns whether the point is inside the hull, borders included.
+    pub fn contains(&self, point: &[f64]) -> bool {
+        self.facets.iter().all(|facet| !self.is_above(facet, point))
+    }
+
+    /// Returns whether the point is above the hyperplane of the facet by more than the tolerance.
+    fn is_above(&self, facet: &Facet, point: &[f64]) -> bool {
+        // height / |normal| > epsilon without the square root
+        let height = facet.height(point);
+        height > 0.0 && height * height > self.epsilon * self.epsilon * dot(&facet.normal, &facet.normal)
+    }
+
+    /// Adds a point to the hull, or does nothing if it is already inside.
+    fn insert(&mut self, idx: usize, interior: &[f64]) -> Option<()> {
+        let (visible, hidden): (Vec<_>, Vec<_>) = core::mem::take(&mut self.facets)
+            .into_iter()
+            .partition(|f| self.is_above(f, &self.points[idx]));
+        self.facets = hidden;
+        if visible.is_empty() {
+            return Some(());
+        }
+
+        // The ridges of the horizon belong to a single visible facet, the other one being hidden
+        let mut ridges = BTreeMap::<Vec<usize>, usize>::new();
+        for facet in &visible {
+            for skipped in 0..facet.vertices.len() {
+                let mut ridge = facet.vertices.clone();
+                ridge.remove(skipped);
+                ridge.sort_unstable();
+                *ridges.entry(ridge).or_insert(0) += 1;
+            }
+        }
+        for (mut ridge, _) in ridges.into_iter().filter(|&(_, count)| count == 1) {
+            ridge.push(idx);
+            let facet = self.facet(ridge, interior)?;
+            self.facets.push(facet);
+        }
+        Some(())
+    }
+
+    /// Returns the facet through the vertices with its normal pointing away from the interior point,
+    /// or `None` if the vertices do not span a hyperplane.
+    fn facet(&self, vertices: Vec<usize>, interior: &[f64]) -> Option<Facet> {
+        let origin = &self.points[vertices[0]];
+        let rows = vertices[1..]
+            .iter()
+            .map(|&idx| self.points[idx].iter().zip(origin).map(|(x, o)| x - o).collect())
+            .collect();
+        let mut normal = null_vector(rows, self.dimension())?;
+        let mut offset = dot(&normal, origin);
+        if dot(&normal, interior) > offset {
+            normal.iter_mut().for_each(|x| *x = -*x);
+            offset = -offset;
+        }
+        Some(Facet {
+            vertices,
+            normal,
+            offset,
+        })
+    }
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// Returns the indices of d + 1 affinely independent points, picking at each step
+/// the point farthest from the span of the previous ones, or `None` if the points lie in a hyperplane.
+fn initial_simplex(points: &[Vec<f64>], epsilon: f64) -> Option<Vec<usize>> {
+    let dimension = points[0].len();
+    let mut simplex = vec![ 0 ];
+    // Orthogonal basis of the directions spanned by the simplex, not normalized
+    let mut basis: Vec<Vec<f64>> = Vec::with_capacity(dimension);
+    while simplex.len() <= dimension {
+        let (idx, residual) = points
+            .iter()
+            .enumerate()
+            .map(|(idx, p)| {
+                let mut residual = p.iter().zip(&points[0]).map(|(x, o)| x - o).collect::<Vec<_>>();
+                for axis in &basis {
+                    let scale = dot(&residual, axis) / dot(axis, axis);
+                    residual.iter_mut().zip(axis).for_each(|(r, a)| *r -= scale * a);
+                }
+                (idx, residual)
+            })
+            .max_by(|a, b| dot(&a.1, &a.1).total_cmp(&dot(&b.1, &b.1)))?;
+        if dot(&residual, &residual) <= epsilon * epsilon {
+            return None;
+        }
+        simplex.push(idx);
+        basis.push(residual);
+    }
+    Some(simplex)
+}
+
+/// Returns a non-zero vector orthogonal to the d - 1 rows, or `None` if they are not linearly independent.
+fn null_vector(rows: Vec<Vec<f64>>, dimension: usize) -> Option<Vec<f64>> {
+    let count = rows.len();
+    let (rows, pivots) = row_echelon(rows, dimension, 0.0);
+    if pivots.len() < count {
+        return None;
+    }
+
+    // The columns without a pivot are free, a single one for rows of full rank
+    let free = (0..dimension).find(|col| !pivots.contains(col))?;
+    let mut normal = vec![ 0.0; dimension ];
+    normal[free] = 1.0;
+    for (row, &col) in pivots.iter().enumerate() {
+        normal[col] = -rows[row][free];
+    }
+    Some(normal)
+}
+
+/// Returns the number of linearly independent rows, up to the tolerance.
+fn rank(mut rows: Vec<Vec<f64>>, dimension: usize) -> usize {
+    // Normals of different facets have different lengths
+    for row in &mut rows {
+        let scale = row.iter().fold(0.0f64, |scale, x| scale.max(x.abs()));
+        if scale > 0.0 {
+            row.iter_mut().for_each(|x| *x /= scale);
+        }
+    }
+    row_echelon(rows, dimension, TOLERANCE).1.len()
+}
+
+/// Reduces the rows to reduced row echelon form with partial pivoting, skipping the pivots up to `tolerance`,
+/// and returns them with the columns of their pivots.
+fn row_echelon(mut rows: Vec<Vec<f64>>, dimension: usize, tolerance: f64) -> (Vec<Vec<f64>>, Vec<usize>) {
+    let mut pivots = Vec::with_capacity(rows.len());
+    let mut row = 0;
+    for col in 0..dimension {
+        if row == rows.len() {
+            break;
+        }
+        let pivot = (row..rows.len())
+            .max_by(|&a, &b| rows[a][col].abs().total_cmp(&rows[b][col].abs()))
+            .expect("No rows left");
+        if rows[pivot][col].abs() <= tolerance {
+            continue;
+        }
+        rows.swap(row, pivot);
+        let scale = rows[row][col];
+        rows[row].iter_mut().for_each(|x| *x /= scale);
+        let pivot_row = rows[row].clone();
+        for (other, values) in rows.iter_mut().enumerate() {
+            if other != row {
+                let factor = values[col];
+                values.iter_mut().zip(&pivot_row).skip(col).for_each(|(x, p)| *x -= factor * p);
+            }
+        }
+        pivots.push(col);
+        row += 1;
+    }
+    (rows, pivots)
+}
@@ -0,0 +1,135 @@
+//! Depth of points in a set of points, how central they are, the multivariate counterpart of the rank of a value.
+//! Hull peeling removes the convex hull of the points over and over, the depth of a point being the layer it is removed with.
+//! The Tukey depth of a position is the smallest number of points in a closed half-plane containing it,
+//! and the positions of depth at least k form a convex region, nested in the region of depth k - 1.
+
+use super::GrahamScan;
+use crate::math::{ Vec2, Rect, HalfPlane };
+
+use alloc::{ vec, vec::Vec };
+
+/// Convex layers of a set of points, as returned by [`ConvexLayers::new`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct ConvexLayers {
+    /// Corners of each layer, as indices of the points counter-clockwise, from the outermost layer inwards
+    pub layers: Vec<Vec<usize>>,
+    /// Layer of each point, 0 for the points on the convex hull
+    pub depths: Vec<usize>,
+}
+
+impl ConvexLayers {
+    /// Peels the points, removing the points on the convex hull of the remaining ones until none is left.
+    /// The points in the middle of the edges of a layer, and the duplicates of its corners, belong to it.
+    pub fn new(points: &[Vec2]) -> Self {
+        let _span = span!("Hull peeling");
+        let mut remaining = (0..points.len()).collect::<Vec<_>>();
+        let mut layers = Vec::new();
+        let mut depths = vec![ 0; points.len() ];
+        while !remaining.is_empty() {
+            let positions = remaining.iter().map(|&idx| points[idx]).collect::<Vec<_>>();
+            let layer = GrahamScan::scan(&positions).into_iter().map(|i| remaining[i]).collect::<Vec<_>>();
+
+            // The corners are on the boundary too, so each layer removes at least one point
+            let depth = layers.len();
+            remaining.retain(|&idx| {
+                let on_boundary = (0..layer.len()).any(|i| {
+                    let (a, b, p) = (points[layer[i]], points[layer[(i + 1) % layer.len()]], points[idx]);
+                    Vec2::orientation(a, b, p) == 0.0 && (&p - &a).dot(&p - &b) <= 0.0
+                });
+                if on_boundary {
+                    depths[idx] = depth;
+                }
+                !on_boundary
+            });
+            layers.push(layer);
+        }
+        Self {
+            layers,
+            depths,
+        }
+    }
+
+    /// Returns the number of layers, one more than the largest depth.
+    pub fn count(&self) -> usize {
+        self.layers.len()
+    }
+}
+
+/// Returns the Tukey depth of `q`, the smallest number of points in a closed half-plane containing `q`.
+/// It is 0 out of the convex hull of the points, and at least a third of the points at their deepest position.
+pub fn tukey_depth(points: &[Vec2], q: Vec2) -> usize {
+    // The smallest half-planes are bounded by a line through q turned slightly away from a line through q and a point,
+    // the points of that line are then on either side depending on the ray from q they are on
+    let coincident = points.iter().filter(|&&p| p == q).count();
+    let mut depth = points.len() - coincident;
+    for &p in points.iter().filter(|&&p| p != q) {
+        let (mut left, mut right, mut forward, mut backward) = (0, 0, 0, 0);
+        let direction = &p - &q;
+        for &other in points.iter().filter(|&&other| other != q) {
+            let orientation = Vec2::orientation(q, p, other);
+            if orientation > 0.0 {
+                left += 1;
+            } else if orientation < 0.0 {
+                right += 1;
+            } else if direction.dot(&other - &q) > 0.0 {
+                forward += 1;
+            } else {
+                backward += 1;
+            }
+        }
+        depth = depth.min((left + forward).min(left + backward)).min((right + forward).min(right + backward));
+    }
+    depth + coincident
+}
+
+/// Returns the Tukey depth regions of the points, counter-clockwise: the `k - 1`th one is the convex polygon
+/// of the positions with a depth of at least `k`. The first one is the convex hull of the points.
+/// Stops at the first region without area, e.g. when the deepest positions are a single point,
+/// and returns nothing if the points are collinear.
+pub fn tukey_regions(points: &[Vec2]) -> Vec<Vec<Vec2>> {
+    let _span = span!("Tukey depth regions");
+    let bounds = match Rect::from_points(points) {
+        Some(bounds) => bounds,
+        None => return Vec::new(),
+    };
+
+    // The region of depth k is bounded by the lines through two points with less than k points strictly on one side,
+    // and lies on their other side. The half-planes are sorted by the depth from which they bound the regions
+    let mut constraints = Vec::new();
+    for (i, &a) in points.iter().enumerate() {
+        for &b in points[i + 1..].iter().filter(|&&b| b != a) {
+            let (mut left, mut right) = (0, 0);
+            for &p in points {
+                let orientation = Vec2::orientation(a, b, p);
+                if orientation > 0.0 {
+                    left += 1;
+                } else if orientation < 0.0 {
+                    right += 1;
+                }
+            }
+            constraints.push((left, HalfPlane::left_of(b, a)));
+            constraints.push((right, HalfPlane::left_of(a, b)));
+        }
+    }
+    constraints.sort_by_key(|&(count, _)| count);
+
+    // Each region is clipped from the previous one with the half-planes that start bounding it
+    let mut regions = Vec::new();
+    let mut region = bounds.to_polygon();
+    let mut next = 0;
+    for depth in 1.. {
+        while next < constraints.len() && constraints[next].0 < depth {
+            region = constraints[next].1.clip(&region);
+            next += 1;
+        }
+        if region.is_empty() {
+            break;
+        }
+        regions.push(region.clone());
+        if next == constraints.len() {
+            // No half-plane bounds the deeper regions, there are too few points
+            break;
+        }
+    }
+    regions
+}
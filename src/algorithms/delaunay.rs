@@ -0,0 +1,200 @@
+//! Checks whether a triangulation satisfies the Delaunay criterion, and repairs it by flipping edges.
+
+use crate::math::Vec2;
+
+use alloc::{ vec, vec::Vec, collections::BTreeMap };
+
+/// Relative tolerance of the in-circle test, points closer to the circumcircle are considered on it
+/// so that cocircular points do not make the edges illegal
+const EPSILON: f64 = 1e-6;
+
+/// Returns the edges of the triangulation that are not locally Delaunay: those shared by two triangles
+/// where the circumcircle of one triangle contains the opposite vertex of the other.
+/// `indices` contains 3 indices into `points` per triangle, in any orientation.
+/// Each edge is returned once, as a pair of point indices with the smallest one first.
+pub fn validate_delaunay(points: &[Vec2], indices: &[usize]) -> Vec<(usize, usize)> {
+    let _span = span!("Delaunay validation");
+    let triangles = oriented_triangles(points, indices);
+    adjacency(&triangles)
+        .into_iter()
+        .filter(|(_, adjacent)| adjacent.len() == 2 && is_illegal(points, &triangles, adjacent[0], adjacent[1]))
+        .map(|(edge, _)| edge)
+        .collect()
+}
+
+/// Flips the illegal edges of the triangulation until it is a Delaunay triangulation, and returns the number of flips.
+/// The triangles are rewritten counter-clockwise.
+pub fn repair_delaunay(points: &[Vec2], indices: &mut Vec<usize>) -> usize {
+    let _span = span!("Delaunay repair");
+    let mut triangles = oriented_triangles(points, indices);
+    let mut adjacent = adjacency(&triangles);
+
+    // Every edge is checked once, then the edges around each flip are checked again
+    let mut stack = adjacent.keys().cloned().collect::<Vec<_>>();
+    let mut flips = 0;
+    while let Some(edge) = stack.pop() {
+        let (t1, t2) = match adjacent.get(&edge) {
+            Some(triangles) if triangles.len() == 2 => (triangles[0], triangles[1]),
+            _ => continue,
+        };
+        if !is_illegal(points, &triangles, t1, t2) {
+            continue;
+        }
+
+        // t1 is (a, b, c) and t2 is (b, a, d), both counter-clockwise,
+        // they are replaced with (a, d, c) and (d, b, c)
+        let (a, b, c) = rotated(triangles[t1], edge);
+        let d = opposite(triangles[t2], edge);
+        triangles[t1] = [ a, d, c ];
+        triangles[t2] = [ d, b, c ];
+
+        adjacent.remove(&edge);
+        adjacent.insert(key(c, d), vec![ t1, t2 ]);
+        replace(&mut adjacent, key(a, d), t2, t1);
+        replace(&mut adjacent, key(b, c), t1, t2);
+
+        stack.extend_from_slice(&[ key(a, d), key(d, b), key(b, c), key(c, a) ]);
+        flips += 1;
+    }
+
+    log::debug!("Delaunay repair: {} flips", flips);
+    *indices = triangles.iter().flatten().cloned().collect();
+    flips
+}
+
+/// Updates the Delaunay triangulation of points that moved since it was computed, by flipping its illegal edges,
+/// and returns the number of flips. Returns `None` and leaves the triangles unchanged if the triangulation cannot be repaired:
+/// when one of its triangles turned over, or when its boundary stopped being convex so that it no longer covers the hull.
+/// The triangles must be counter-clockwise, like [`repair_delaunay`](fn.repair_delaunay.html) writes them.
+pub fn update_delaunay(points: &[Vec2], indices: &mut Vec<usize>) -> Option<usize> {
+    let triangles = indices.chunks_exact(3).map(|t| [ t[0], t[1], t[2] ]).collect::<Vec<_>>();
+    if triangles.iter().any(|t| Vec2::orientation(points[t[0]], points[t[1]], points[t[2]]) <= 0.0) {
+        return None;
+    }
+
+    // The boundary edges have a single triangle, they follow its counter-clockwise order and turn left at each vertex
+    let adjacent = adjacency(&triangles);
+    let next = triangles
+        .iter()
+        .flat_map(|t| (0..3).map(move |i| (t[i], t[(i + 1) % 3])))
+        .filter(|&(a, b)| adjacent[&key(a, b)].len() == 1)
+        .collect::<BTreeMap<_, _>>();
+    let is_convex = next.iter().all(|(&a, &b)| next.get(&b).is_none_or(|&c| Vec2::orientation(points[a], points[b], points[c]) >= 0.0));
+    if !is_convex {
+        return None;
+    }
+    Some(repair_delaunay(points, indices))
+}
+
+/// Returns the edge as a pair of indices with the smallest one first.
+fn key(a: usize, b: usize) -> (usize, usize) {
+    (a.min(b), a.max(b))
+}
+
+/// Returns the triangles of `indices`, counter-clockwise. Degenerate triangles keep their orientation.
+fn oriented_triangles(points: &[Vec2], indices: &[usize]) -> Vec<[usize; 3]> {
+    indices.chunks_exact(3)
+        .map(|t| if Vec2::orientation(points[t[0]], points[t[1]], points[t[2]]) < 0.0 { [ t[0], t[2], t[1] ] } else { [ t[0], t[1], t[2] ] })
+        .collect()
+}
+
+/// Returns the triangles on each side of every edge.
+fn adjacency(triangles: &[[usize; 3]]) -> BTreeMap<(usize, usize), Vec<usize>> {
+    let mut adjacent = BTreeMap::new();
+    for (idx, t) in triangles.iter().enumerate() {
+        for i in 0..3 {
+            adjacent.entry(key(t[i], t[(i + 1) % 3])).or_insert_with(Vec::new).push(idx);
+        }
+    }
+    adjacent
+}
+
+fn replace(adjacent: &mut BTreeMap<(usize, usize), Vec<usize>>, edge: (usize, usize), from: usize, to: usize) {
+    if let Some(triangles) = adjacent.get_mut(&edge) {
+        for t in triangles.iter_mut().filter(|t| **t == from) {
+            *t = to;
+        }
+    }
+}
+
+/// Rotates the triangle so that it starts with the edge, in its own orientation.
+fn rotated(t: [usize; 3], edge: (usize, usize)) -> (usize, usize, usize) {
+    let (a, b, c) = (t[0], t[1], t[2]);
+    if key(a, b) == edge {
+        (a, b, c)
+    } else if key(b, c) == edge {
+        (b, c, a)
+    } else {
+        (c, a, b)
+    }
+}
+
+/// Returns the vertex of the triangle that is not on the edge.
+fn opposite(t: [usize; 3], edge: (usize, usize)) -> usize {
+    t.iter().cloned().find(|&v| v != edge.0 && v != edge.1).expect("Edge not in the triangle")
+}
+
+/// Returns true if the circumcircle of one of the two triangles contains the vertex of the other one
+/// that is opposite to their shared edge. The triangles are counter-clockwise.
+fn is_illegal(points: &[Vec2], triangles: &[[usize; 3]], t1: usize, t2: usize) -> bool {
+    let t = triangles[t1];
+    let shared = t.iter().cloned().filter(|v| triangles[t2].contains(v)).collect::<Vec<_>>();
+    if shared.len() != 2 {
+        return false;
+    }
+    let d = opposite(triangles[t2], (shared[0], shared[1]));
+    let (a, b, c) = (points[t[0]], points[t[1]], points[t[2]]);
+    if Vec2::orientation(a, b, c) <= 0.0 {
+        return false;
+    }
+    in_circle(a, b, c, points[d])
+}
+
+/// Returns true if `d` is strictly inside the circumcircle of the triangle (a, b, c), in either orientation,
+/// beyond the tolerance. Degenerate triangles have no circumcircle.
+pub(crate) fn in_circumcircle(a: Vec2, b: Vec2, c: Vec2, d: Vec2) -> bool {
+    let area = Vec2::orientation(a, b, c);
+    if area > 0.0 {
+        in_circle(a, b, c, d)
+    } else if area < 0.0 {
+        in_circle(a, c, b, d)
+    } else {
+        false
+    }
+}
+
+/// Returns true if `d` is on the circumcircle of the triangle (a, b, c), up to the tolerance of the in-circle test.
+/// Degenerate triangles have no circumcircle.
+pub(crate) fn on_circumcircle(a: Vec2, b: Vec2, c: Vec2, d: Vec2) -> bool {
+    if Vec2::orientation(a, b, c) == 0.0 {
+        return false;
+    }
+    let (det, tolerance) = in_circle_determinant(a, b, c, d);
+    -tolerance <= det && det <= tolerance
+}
+
+/// Returns true if `d` is strictly inside the circumcircle of the counter-clockwise triangle (a, b, c),
+/// beyond the tolerance.
+fn in_circle(a: Vec2, b: Vec2, c: Vec2, d: Vec2) -> bool {
+    let (det, tolerance) = in_circle_determinant(a, b, c, d);
+    det > tolerance
+}
+
+/// Returns the in-circle determinant, positive if `d` is inside the circumcircle of the counter-clockwise
+/// triangle (a, b, c), and the tolerance below which its absolute value means that `d` is on the circle.
+fn in_circle_determinant(a: Vec2, b: Vec2, c: Vec2, d: Vec2) -> (f64, f64) {
+    // Determinant of the rows (x, y, x² + y²) of the points relative to d, computed in f64
+    let row = |p: Vec2| {
+        let (x, y) = (p.x as f64 - d.x as f64, p.y as f64 - d.y as f64);
+        (x, y, x * x + y * y)
+    };
+    let (a, b, c) = (row(a), row(b), row(c));
+    let det = a.0 * (b.1 * c.2 - b.2 * c.1)
+            - a.1 * (b.0 * c.2 - b.2 * c.0)
+            + a.2 * (b.0 * c.1 - b.1 * c.0);
+
+    // The scale covers all the sides of the quadrilateral, so that both triangles sharing an edge agree on it
+    let sqr_distance = |p: (f64, f64, f64), q: (f64, f64, f64)| (p.0 - q.0) * (p.0 - q.0) + (p.1 - q.1) * (p.1 - q.1);
+    let scale = a.2.max(b.2).max(c.2).max(sqr_distance(a, b)).max(sqr_distance(b, c)).max(sqr_distance(c, a));
+    (det, EPSILON * scale * scale)
+}
@@ -0,0 +1,145 @@
+//! Delaunay tetrahedralization of 3D points, and the shape quality of its tetrahedra.
+//! Lifted onto the paraboloid w = x² + y² + z², the points whose sphere is empty form the lower facets of a 4D hull,
+//! so the tetrahedralization is the lower hull of the lifted points projected back.
+
+use crate::math::{ Vec3, XorShift };
+use super::ConvexHullNd;
+
+use alloc::{ vec, vec::Vec };
+use cgmath::InnerSpace;
+
+/// Relative tolerance under which a facet of the lifted hull is vertical, its projection is flat
+const TOLERANCE: f64 = 1e-9;
+/// Largest offset of the lifted points, relative to the extent of the points squared.
+/// Above the rounding of single precision coordinates, and small enough to only reorder the points that are nearly cospherical
+const PERTURBATION: f64 = 1e-5;
+
+/// Returns the tetrahedra of the Delaunay tetrahedralization of the points, 4 indices into `points` each,
+/// with a positive volume. Cospherical points, up to a small tolerance, are split into tetrahedra in any way.
+/// Returns nothing if there are less than 4 points or if they are coplanar.
+pub fn delaunay_3d(points: &[Vec3]) -> Vec<[usize; 4]> {
+    let _span = span!("3D Delaunay");
+    if points.len() < 4 {
+        return Vec::new();
+    }
+
+    // Centered and scaled so that the lifted coordinate has the same range as the others
+    let (mut min, mut max) = ([ f64::INFINITY; 3 ], [ f64::NEG_INFINITY; 3 ]);
+    for p in points {
+        for (axis, &x) in [ p.0.x, p.0.y, p.0.z ].iter().enumerate() {
+            min[axis] = min[axis].min(x as f64);
+            max[axis] = max[axis].max(x as f64);
+        }
+    }
+    let center = [ (min[0] + max[0]) / 2.0, (min[1] + max[1]) / 2.0, (min[2] + max[2]) / 2.0 ];
+    let extent = (0..3).fold(0.0f64, |extent, axis| extent.max(max[axis] - min[axis]));
+    if extent == 0.0 {
+        return Vec::new();
+    }
+    // Cospherical points lift into a hyperplane, where the rounding of their coordinates makes the hull inconsistent.
+    // A small pseudo-random offset of the lifted coordinate breaks the ties, so that any of their tetrahedralizations is picked
+    let mut random = XorShift::default();
+    let lifted = points
+        .iter()
+        .map(|p| {
+            let perturbation = PERTURBATION * random.next_f64();
+            let (x, y, z) = ((p.0.x as f64 - center[0]) / extent, (p.0.y as f64 - center[1]) / extent, (p.0.z as f64 - center[2]) / extent);
+            vec![ x, y, z, x * x + y * y + z * z + perturbation ]
+        })
+        .collect::<Vec<_>>();
+
+    let hull = match ConvexHullNd::new(&lifted) {
+        Some(hull) => hull,
+        None => return Vec::new(),
+    };
+    hull.facets()
+        .iter()
+        .filter(|facet| {
+            let length = facet.normal.iter().map(|x| x * x).sum::<f64>().sqrt();
+            facet.normal[3] < -TOLERANCE * length
+        })
+        .map(|facet| {
+            let mut t = [ facet.vertices[0], facet.vertices[1], facet.vertices[2], facet.vertices[3] ];
+            if signed_volume(points[t[0]], points[t[1]], points[t[2]], points[t[3]]) < 0.0 {
+                t.swap(2, 3);
+            }
+            t
+        })
+        .collect()
+}
+
+/// Quality measures of a tetrahedron, all of them are infinite or zero for a degenerate tetrahedron.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct TetrahedronQuality {
+    /// Smallest dihedral angle in radians, about 70.5° for a regular tetrahedron.
+    /// Slivers have well shaped faces and a short radius-edge ratio, but a dihedral angle close to 0
+    pub min_dihedral: f32,
+    /// Largest dihedral angle in radians, close to π for slivers and flat caps
+    pub max_dihedral: f32,
+    /// Circumradius over the shortest edge, √6 / 4 for a regular tetrahedron
+    pub radius_edge_ratio: f32,
+}
+
+impl TetrahedronQuality {
+    pub fn new(a: Vec3, b: Vec3, c: Vec3, d: Vec3) -> Self {
+        let angles = dihedral_angles(a, b, c, d);
+        let volume = signed_volume(a, b, c, d);
+        if volume == 0.0 {
+            return Self {
+                min_dihedral: 0.0,
+                max_dihedral: core::f32::consts::PI,
+                radius_edge_ratio: f32::INFINITY,
+            };
+        }
+
+        // Circumcenter relative to a, from the edges leaving it
+        let (u, v, w) = (b.0 - a.0, c.0 - a.0, d.0 - a.0);
+        let center = (v.cross(w) * u.magnitude2() + w.cross(u) * v.magnitude2() + u.cross(v) * w.magnitude2()) / (2.0 * volume);
+        let shortest = [ u, v, w, c.0 - b.0, d.0 - b.0, d.0 - c.0 ]
+            .iter()
+            .map(|edge| edge.magnitude())
+            .fold(f32::INFINITY, f32::min);
+        Self {
+            min_dihedral: angles.iter().cloned().fold(f32::INFINITY, f32::min),
+            max_dihedral: angles.iter().cloned().fold(0.0, f32::max),
+            radius_edge_ratio: center.magnitude() / shortest,
+        }
+    }
+}
+
+/// Returns six times the volume of the tetrahedron, positive if `d` is on the side of `a`, `b`, `c` they turn counterclockwise around.
+pub fn signed_volume(a: Vec3, b: Vec3, c: Vec3, d: Vec3) -> f32 {
+    (b.0 - a.0).cross(c.0 - a.0).dot(d.0 - a.0)
+}
+
+/// Returns the dihedral angles of the tetrahedron in radians, at the edges ab, ac, ad, bc, bd and cd.
+pub fn dihedral_angles(a: Vec3, b: Vec3, c: Vec3, d: Vec3) -> [f32; 6] {
+    let vertices = [ a.0, b.0, c.0, d.0 ];
+    // Outward normal of the face opposite each vertex
+    let normals = (0..4)
+        .map(|opposite| {
+            let face = (0..4).filter(|&i| i != opposite).map(|i| vertices[i]).collect::<Vec<_>>();
+            let normal = (face[1] - face[0]).cross(face[2] - face[0]);
+            let normal = if normal.dot(vertices[opposite] - face[0]) > 0.0 { -normal } else { normal };
+            if normal.magnitude2() > 0.0 { normal.normalize() } else { normal }
+        })
+        .collect::<Vec<_>>();
+
+    // The edge between two vertices is shared by the faces opposite the other two
+    let mut angles = [ 0.0; 6 ];
+    let edges = [ (0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3) ];
+    for (angle, &(i, j)) in angles.iter_mut().zip(&edges) {
+        let mut faces = (0..4).filter(|&k| k != i && k != j);
+        let (k, l) = (faces.next().unwrap(), faces.next().unwrap());
+        *angle = core::f32::consts::PI - normals[k].dot(normals[l]).clamp(-1.0, 1.0).acos();
+    }
+    angles
+}
+
+/// Returns the quality of each tetrahedron, as indices into `points`.
+pub fn tetrahedron_qualities(points: &[Vec3], tetrahedra: &[[usize; 4]]) -> Vec<TetrahedronQuality> {
+    tetrahedra
+        .iter()
+        .map(|t| TetrahedronQuality::new(points[t[0]], points[t[1]], points[t[2]], points[t[3]]))
+        .collect()
+}
@@ -0,0 +1,182 @@
+//! Signed distance fields of polygons, sampled on a grid: the distance from each node to the boundary,
+//! positive inside the polygon and negative outside. The field is either computed exactly at every node,
+//! or approximated by the Euclidean distance transform of the grid once the inside of the polygon is rasterized,
+//! in time linear in the number of nodes whatever the size of the polygon.
+
+use crate::math::{ Vec2, Rect, float, polygon };
+
+use alloc::{ vec, vec::Vec };
+
+/// Squared distance of the nodes without a feature, farther than any node of the grid but finite,
+/// which keeps the intersections of the parabolas defined
+const FAR: f32 = 1e20;
+
+/// Values of a field on a grid of square cells, stored row by row from the top left node like an image.
+#[derive(Clone, Debug)]
+pub struct DistanceField {
+    /// Position of the top left node, with the smallest x and the largest y
+    pub origin: Vec2,
+    /// Distance between neighbour nodes
+    pub cell: f32,
+    pub width: usize,
+    pub height: usize,
+    pub values: Vec<f32>,
+}
+
+impl DistanceField {
+    /// Computes the signed distance to the boundary of the polygon at each node of a grid that covers `bounds`,
+    /// with `resolution` nodes along its longest side. Returns `None` if the polygon has less than 3 vertices,
+    /// `bounds` is empty or `resolution` is less than 2.
+    pub fn exact(polygon: &[Vec2], bounds: Rect, resolution: usize) -> Option<Self> {
+        let _span = span!("Signed distance field");
+        let mut field = Self::grid(polygon, bounds, resolution)?;
+        field.values = (0..field.height)
+            .flat_map(|y| (0..field.width).map(move |x| (x, y)))
+            .map(|(x, y)| polygon::signed_distance(polygon, field.position(x, y)))
+            .collect();
+        Some(field)
+    }
+
+    /// Approximates the signed distances on the same grid as [`exact`](#method.exact), from the distances
+    /// between the nodes inside the polygon and those outside. The boundary lies between them,
+    /// half a cell from both on average.
+    pub fn transform(polygon: &[Vec2], bounds: Rect, resolution: usize) -> Option<Self> {
+        let _span = span!("Distance transform");
+        let mut field = Self::grid(polygon, bounds, resolution)?;
+        let inside = (0..field.height)
+            .flat_map(|y| (0..field.width).map(move |x| (x, y)))
+            .map(|(x, y)| polygon::contains(polygon, field.position(x, y)))
+            .collect::<Vec<_>>();
+        let outside = inside.iter().map(|&inside| !inside).collect::<Vec<_>>();
+        let to_inside = squared_distance_transform(&inside, field.width, field.height);
+        let to_outside = squared_distance_transform(&outside, field.width, field.height);
+
+        let cell = field.cell;
+        field.values = inside
+            .iter()
+            .zip(to_inside.iter().zip(&to_outside))
+            .map(|(&inside, (&to_inside, &to_outside))| {
+                // Without nodes on the other side, the boundary is out of the grid
+                let (distance, sign) = if inside { (to_outside, 1.0) } else { (to_inside, -1.0) };
+                if distance >= FAR { sign * f32::INFINITY } else { sign * (float::sqrt(distance) - 0.5) * cell }
+            })
+            .collect();
+        Some(field)
+    }
+
+    /// Returns a grid covering `bounds` with square cells, without values.
+    fn grid(polygon: &[Vec2], bounds: Rect, resolution: usize) -> Option<Self> {
+        let (size_x, size_y) = (bounds.width(), bounds.height());
+        if polygon.len() < 3 || resolution < 2 || size_x <= 0.0 || size_y <= 0.0 {
+            return None;
+        }
+        let cell = size_x.max(size_y) / (resolution - 1) as f32;
+        let nodes = |size: f32| float::round(size / cell) as usize + 1;
+        Some(Self {
+            origin: Vec2::new(bounds.min.x, bounds.max.y),
+            cell,
+            width: nodes(size_x),
+            height: nodes(size_y),
+            values: Vec::new(),
+        })
+    }
+
+    /// Returns the position of the node on column `x` and row `y`, rows go down from the top.
+    pub fn position(&self, x: usize, y: usize) -> Vec2 {
+        Vec2::new(self.origin.x + x as f32 * self.cell, self.origin.y - y as f32 * self.cell)
+    }
+
+    pub fn value(&self, x: usize, y: usize) -> f32 {
+        self.values[y * self.width + x]
+    }
+
+    /// Returns the segments, 2 points each, where the field crosses `level` between the nodes,
+    /// with the marching squares: the crossings are interpolated linearly along the sides of the cells.
+    /// The boundary of the polygon is the contour at 0.
+    pub fn contour(&self, level: f32) -> Vec<Vec2> {
+        let mut segments = Vec::new();
+        for y in 0..self.height.saturating_sub(1) {
+            for x in 0..self.width.saturating_sub(1) {
+                // Corners counter-clockwise from the bottom left, whose row is below
+                let corners = [ (x, y + 1), (x + 1, y + 1), (x + 1, y), (x, y) ];
+                let values = corners.iter().map(|&(x, y)| self.value(x, y) - level).collect::<Vec<_>>();
+                let crossing = |side: usize| {
+                    let (i, j) = (side, (side + 1) % 4);
+                    let (a, b) = (self.position(corners[i].0, corners[i].1), self.position(corners[j].0, corners[j].1));
+                    let t = values[i] / (values[i] - values[j]);
+                    &a + &(&(&b - &a) * t)
+                };
+                let crossed = (0..4).filter(|&side| (values[side] >= 0.0) != (values[(side + 1) % 4] >= 0.0)).collect::<Vec<_>>();
+                match crossed[..] {
+                    [ a, b ] => segments.extend_from_slice(&[ crossing(a), crossing(b) ]),
+                    // Saddle, the value at the center tells whether the positive corners are connected through it
+                    [ a, b, c, d ] => {
+                        let center = values.iter().sum::<f32>() / 4.0;
+                        let (first, second) = if (center >= 0.0) == (values[0] >= 0.0) { ((a, b), (c, d)) } else { ((d, a), (b, c)) };
+                        segments.extend_from_slice(&[ crossing(first.0), crossing(first.1), crossing(second.0), crossing(second.1) ]);
+                    },
+                    _ => {},
+                }
+            }
+        }
+        segments
+    }
+}
+
+/// Returns the squared distance, in cells, from each node of the grid to the closest node where `features` is true,
+/// with the separable algorithm of Felzenszwalb and Huttenlocher: the exact transform of each column,
+/// then of each row of the result. Nodes without any feature in the grid are at `FAR`.
+fn squared_distance_transform(features: &[bool], width: usize, height: usize) -> Vec<f32> {
+    let mut distances = features.iter().map(|&feature| if feature { 0.0 } else { FAR }).collect::<Vec<_>>();
+    let mut column = vec![ 0.0; height ];
+    for x in 0..width {
+        for (y, value) in column.iter_mut().enumerate() {
+            *value = distances[y * width + x];
+        }
+        for (y, value) in lower_envelope(&column).into_iter().enumerate() {
+            distances[y * width + x] = value;
+        }
+    }
+    for row in distances.chunks_mut(width) {
+        let transformed = lower_envelope(row);
+        row.copy_from_slice(&transformed);
+    }
+    distances
+}
+
+/// Returns the 1D transform of `f`, the minimum over q of (p - q)² + f(q) at each p,
+/// from the lower envelope of the parabolas rooted at each q.
+fn lower_envelope(f: &[f32]) -> Vec<f32> {
+    let n = f.len();
+    // Roots of the parabolas of the envelope, and the boundaries between them
+    let mut roots = vec![ 0; n ];
+    let mut boundaries = vec![ 0.0; n + 1 ];
+    let mut k = 0;
+    boundaries[0] = f32::NEG_INFINITY;
+    boundaries[1] = f32::INFINITY;
+    let intersection = |q: usize, r: usize| {
+        ((f[q] + (q * q) as f32) - (f[r] + (r * r) as f32)) / (2.0 * (q as f32 - r as f32))
+    };
+    for q in 1..n {
+        let mut s = intersection(q, roots[k]);
+        while s <= boundaries[k] {
+            k -= 1;
+            s = intersection(q, roots[k]);
+        }
+        k += 1;
+        roots[k] = q;
+        boundaries[k] = s;
+        boundaries[k + 1] = f32::INFINITY;
+    }
+
+    k = 0;
+    (0..n)
+        .map(|p| {
+            while boundaries[k + 1] < p as f32 {
+                k += 1;
+            }
+            let d = p as f32 - roots[k] as f32;
+            d * d + f[roots[k]]
+        })
+        .collect()
+}
@@ -0,0 +1,240 @@
+//! Dual graph of a triangulation: its nodes are the triangles, linked when they share an edge.
+//! Walking it finds the sleeve of triangles between two points, colors the triangles and selects connected regions.
+
+use crate::math::{ Vec2, Segment2 };
+
+use alloc::{ vec, vec::Vec, collections::{ BTreeMap, VecDeque } };
+
+/// Fraction of an edge cut off at each end before testing it against the barriers,
+/// so that a barrier ending at a vertex does not block the edges around it
+const EDGE_MARGIN: f32 = 1e-3;
+
+/// Adjacency of the triangles of a triangulation, each triangle has at most 3 neighbours in a manifold mesh.
+#[derive(Clone, Debug, Default)]
+pub struct DualGraph {
+    triangles: Vec<[usize; 3]>,
+    neighbours: Vec<Vec<usize>>,
+}
+
+impl DualGraph {
+    /// Creates the dual graph of the triangles of `indices`, 3 indices per triangle.
+    /// An edge shared by more than two triangles links all of them.
+    pub fn new(indices: &[usize]) -> Self {
+        let triangles = indices.chunks_exact(3).map(|t| [ t[0], t[1], t[2] ]).collect::<Vec<_>>();
+        let mut edges = BTreeMap::<(usize, usize), Vec<usize>>::new();
+        for (idx, t) in triangles.iter().enumerate() {
+            for i in 0..3 {
+                let (a, b) = (t[i], t[(i + 1) % 3]);
+                edges.entry((a.min(b), a.max(b))).or_default().push(idx);
+            }
+        }
+
+        let mut neighbours = vec![ Vec::new(); triangles.len() ];
+        for adjacent in edges.values() {
+            for &t in adjacent {
+                neighbours[t].extend(adjacent.iter().filter(|&&other| other != t));
+            }
+        }
+        for list in &mut neighbours {
+            list.sort_unstable();
+            list.dedup();
+        }
+        Self {
+            triangles,
+            neighbours,
+        }
+    }
+
+    /// Returns the number of triangles.
+    pub fn len(&self) -> usize {
+        self.triangles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.triangles.is_empty()
+    }
+
+    /// Returns the vertices of the triangle `t`.
+    pub fn triangle(&self, t: usize) -> [usize; 3] {
+        self.triangles[t]
+    }
+
+    /// Returns the triangles sharing an edge with `t`, in increasing order.
+    pub fn neighbours(&self, t: usize) -> &[usize] {
+        &self.neighbours[t]
+    }
+
+    /// Returns the neighbours of every triangle.
+    pub fn adjacency(&self) -> &[Vec<usize>] {
+        &self.neighbours
+    }
+
+    /// Returns the edge shared by the triangles `t` and `other`, with the smallest vertex first, or `None` if they are not neighbours.
+    pub fn shared_edge(&self, t: usize, other: usize) -> Option<(usize, usize)> {
+        let (a, b) = (self.triangles[t], self.triangles[other]);
+        let mut shared = a.iter().filter(|v| b.contains(v));
+        match (shared.next(), shared.next(), shared.next()) {
+            (Some(&u), Some(&v), None) => Some((u.min(v), u.max(v))),
+            _ => None,
+        }
+    }
+
+    /// Returns an iterator over the triangles reachable from `start`, by increasing number of edges crossed.
+    pub fn bfs(&self, start: usize) -> Bfs<'_> {
+        let mut visited = vec![ false; self.len() ];
+        visited[start] = true;
+        Bfs {
+            graph: self,
+            queue: vec![ start ].into(),
+            visited,
+        }
+    }
+
+    /// Returns an iterator over the triangles reachable from `start`, depth first: each triangle is followed
+    /// by those reachable through its first neighbour not visited yet.
+    pub fn dfs(&self, start: usize) -> Dfs<'_> {
+        Dfs {
+            graph: self,
+            stack: vec![ start ],
+            visited: vec![ false; self.len() ],
+        }
+    }
+
+    /// Returns the triangles reachable from `start` by crossing only the shared edges for which `can_cross(from, to)` is true,
+    /// breadth first, `start` included.
+    pub fn flood_fill<F>(&self, start: usize, mut can_cross: F) -> Vec<usize>
+        where F: FnMut(usize, usize) -> bool
+    {
+        let mut visited = vec![ false; self.len() ];
+        visited[start] = true;
+        let mut region = vec![ start ];
+        let mut next_idx = 0;
+        while let Some(&t) = region.get(next_idx) {
+            next_idx += 1;
+            for &next in self.neighbours(t) {
+                if !visited[next] && can_cross(t, next) {
+                    visited[next] = true;
+                    region.push(next);
+                }
+            }
+        }
+        region
+    }
+
+    /// Returns the triangles crossed by one of the paths from `from` to `to` with the fewest triangles, both included,
+    /// or `None` if `to` cannot be reached.
+    pub fn path(&self, from: usize, to: usize) -> Option<Vec<usize>> {
+        // Searching from the end, so that following the parents from the start gives the path in order
+        let mut parents = vec![ None; self.len() ];
+        let mut queue = VecDeque::new();
+        queue.push_back(to);
+        while let Some(t) = queue.pop_front() {
+            if t == from {
+                break;
+            }
+            for &next in self.neighbours(t) {
+                if next != to && parents[next].is_none() {
+                    parents[next] = Some(t);
+                    queue.push_back(next);
+                }
+            }
+        }
+        if from != to && parents[from].is_none() {
+            return None;
+        }
+
+        let mut path = vec![ from ];
+        while let Some(parent) = parents[*path.last().expect("Empty path")] {
+            path.push(parent);
+        }
+        Some(path)
+    }
+
+    /// Returns the index of the connected component of each triangle, and the number of components.
+    pub fn components(&self) -> (Vec<usize>, usize) {
+        let mut components = vec![ usize::MAX; self.len() ];
+        let mut count = 0;
+        for start in 0..self.len() {
+            if components[start] != usize::MAX {
+                continue;
+            }
+            for t in self.bfs(start) {
+                components[t] = count;
+            }
+            count += 1;
+        }
+        (components, count)
+    }
+}
+
+/// Breadth-first traversal of a [`DualGraph`](struct.DualGraph.html), created by [`bfs`](struct.DualGraph.html#method.bfs).
+pub struct Bfs<'g> {
+    graph: &'g DualGraph,
+    queue: VecDeque<usize>,
+    visited: Vec<bool>,
+}
+
+impl<'g> Iterator for Bfs<'g> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let t = self.queue.pop_front()?;
+        for &next in self.graph.neighbours(t) {
+            if !self.visited[next] {
+                self.visited[next] = true;
+                self.queue.push_back(next);
+            }
+        }
+        Some(t)
+    }
+}
+
+/// Depth-first traversal of a [`DualGraph`](struct.DualGraph.html), created by [`dfs`](struct.DualGraph.html#method.dfs).
+pub struct Dfs<'g> {
+    graph: &'g DualGraph,
+    stack: Vec<usize>,
+    visited: Vec<bool>,
+}
+
+impl<'g> Iterator for Dfs<'g> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while let Some(t) = self.stack.pop() {
+            if self.visited[t] {
+                continue;
+            }
+            self.visited[t] = true;
+            // Reversed, so that the first neighbour is visited first
+            let visited = &self.visited;
+            self.stack.extend(self.graph.neighbours(t).iter().rev().filter(|&&next| !visited[next]));
+            return Some(t);
+        }
+        None
+    }
+}
+
+/// Returns the index of a triangle of `indices` that contains `p`, boundary included, in either orientation.
+pub fn locate_triangle(points: &[Vec2], indices: &[usize], p: Vec2) -> Option<usize> {
+    indices.chunks_exact(3).position(|t| {
+        let (a, b, c) = (points[t[0]], points[t[1]], points[t[2]]);
+        let sides = [ Vec2::orientation(a, b, p), Vec2::orientation(b, c, p), Vec2::orientation(c, a, p) ];
+        sides.iter().all(|&side| side >= 0.0) || sides.iter().all(|&side| side <= 0.0)
+    })
+}
+
+/// Returns the triangles of the region of `start`: those reachable without crossing an edge cut by one of the `barriers`,
+/// like the constrained edges of a triangulation. A barrier along an edge blocks it, one that only touches its ends does not.
+/// The vertices of the triangles of `graph` are indices into `points`.
+pub fn fill_region(points: &[Vec2], graph: &DualGraph, start: usize, barriers: &[Segment2]) -> Vec<usize> {
+    graph.flood_fill(start, |t, next| {
+        let (u, v) = match graph.shared_edge(t, next) {
+            Some(edge) => edge,
+            None => return false,
+        };
+        let (u, v) = (points[u], points[v]);
+        let margin = &(&v - &u) * EDGE_MARGIN;
+        let edge = Segment2::new(&u + &margin, &v - &margin);
+        !barriers.iter().any(|barrier| barrier.intersects(&edge))
+    })
+}
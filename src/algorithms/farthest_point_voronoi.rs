@@ -0,0 +1,142 @@
+use super::{ GrahamScan, VoronoiEdge };
+use crate::math::{ Vec2, Circle };
+
+use alloc::vec::Vec;
+
+/// Farthest-point Voronoi diagram of a set of 2D points and its dual, the farthest-point Delaunay triangulation.
+/// The cell of a point contains the positions for which it is the farthest input point,
+/// only the points on the convex hull have a cell.
+#[derive(Default)]
+pub struct FarthestPointVoronoi {
+    points: Vec<Vec2>,
+    /// Indices of the points on the hull, in counter-clockwise order
+    hull: Vec<usize>,
+    /// Indices of the points that form the triangles, 3 per triangle
+    triangles: Vec<usize>,
+    edges: Vec<VoronoiEdge>,
+}
+
+impl FarthestPointVoronoi {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn points(&self) -> &[Vec2] {
+        &self.points
+    }
+
+    /// Returns the indices of the points on the convex hull, the sites of the cells.
+    pub fn hull(&self) -> &[usize] {
+        &self.hull
+    }
+
+    /// Returns the indices of the points that form the triangles of the farthest-point Delaunay triangulation.
+    pub fn triangles(&self) -> &[usize] {
+        &self.triangles
+    }
+
+    pub fn edges(&self) -> &[VoronoiEdge] {
+        &self.edges
+    }
+
+    /// Replaces the input points and computes their diagram.
+    pub fn set_points(&mut self, points: Vec<Vec2>) {
+        let _span = span!("Farthest-point Voronoi");
+        self.points = points;
+        self.hull = GrahamScan::scan(&self.points);
+        self.triangles = Self::triangulate(&self.points, &self.hull);
+        self.edges = Self::diagram(&self.points, &self.hull, &self.triangles);
+    }
+
+    pub fn clear(&mut self) {
+        self.set_points(Vec::new());
+    }
+
+    /// Triangulates the convex polygon `hull` so that the circumcircle of each triangle contains all the points.
+    /// The vertex whose circumcircle with its two neighbours is the largest is cut off first,
+    /// that circle contains all the remaining vertices.
+    pub fn triangulate(points: &[Vec2], hull: &[usize]) -> Vec<usize> {
+        let mut polygon = hull.to_vec();
+        let mut triangles = Vec::new();
+        while polygon.len() >= 3 {
+            let n = polygon.len();
+            let radius = |idx: usize| {
+                let (prev, cur, next) = (polygon[(idx + n - 1) % n], polygon[idx], polygon[(idx + 1) % n]);
+                Circle::circumscribed(points[prev], points[cur], points[next]).map_or(0.0, |c| c.radius)
+            };
+            let Some(largest) = (0..n).max_by(|&a, &b| radius(a).total_cmp(&radius(b))) else {
+                break;
+            };
+
+            triangles.extend_from_slice(&[ polygon[(largest + n - 1) % n], polygon[largest], polygon[(largest + 1) % n] ]);
+            polygon.remove(largest);
+        }
+        triangles
+    }
+
+    /// Computes the diagram dual to the farthest-point Delaunay triangulation `triangles` of the convex polygon `hull`:
+    /// the circumcenters of two adjacent triangles are linked by a segment,
+    /// and the edges of the hull are crossed by a ray going toward the interior of the hull.
+    pub fn diagram(points: &[Vec2], hull: &[usize], triangles: &[usize]) -> Vec<VoronoiEdge> {
+        let mut edges = Vec::new();
+        if hull.len() == 2 {
+            // The perpendicular bisector of the two points
+            let (a, b) = (points[hull[0]], points[hull[1]]);
+            let middle = &(&a + &b) * 0.5;
+            let normal = Vec2::new(a.y - b.y, b.x - a.x);
+            edges.push(VoronoiEdge::ray((hull[0], hull[1]), middle, normal));
+            edges.push(VoronoiEdge::ray((hull[0], hull[1]), middle, -&normal));
+            return edges;
+        }
+
+        let centers = triangles.chunks(3)
+                            .map(|tri| Circle::circumscribed(points[tri[0]], points[tri[1]], points[tri[2]])
+                                            .map_or(points[tri[0]], |c| c.center))
+                            .collect::<Vec<_>>();
+        let has_edge = |tri: &[usize], a: usize, b: usize| tri.contains(&a) && tri.contains(&b);
+
+        for (t1, tri1) in triangles.chunks(3).enumerate() {
+            for (a, b) in [ (tri1[0], tri1[1]), (tri1[1], tri1[2]), (tri1[2], tri1[0]) ].iter().cloned() {
+                let neighbour = triangles.chunks(3)
+                                    .enumerate()
+                                    .find(|&(t2, tri2)| t2 != t1 && has_edge(tri2, a, b));
+                match neighbour {
+                    Some((t2, _)) if t1 < t2 => edges.push(VoronoiEdge::segment((a, b), centers[t1], centers[t2])),
+                    Some(_) => {}, // Added with the other triangle
+                    None => {
+                        // Edge of the hull, the interior is on the left of the counter-clockwise edge
+                        let (pa, pb) = (points[a], points[b]);
+                        let (pa, pb) = if Vec2::ccw(pa, pb, points[Self::third(tri1, a, b)]) { (pa, pb) } else { (pb, pa) };
+                        let inward = Vec2::new(pa.y - pb.y, pb.x - pa.x);
+                        edges.push(VoronoiEdge::ray((a, b), centers[t1], inward));
+                    },
+                }
+            }
+        }
+        edges
+    }
+
+    fn third(tri: &[usize], a: usize, b: usize) -> usize {
+        *tri.iter().find(|&&idx| idx != a && idx != b).unwrap()
+    }
+
+    /// Returns the smallest circle containing all the points, whose center lies on the diagram:
+    /// at a vertex, or in the middle of the two sites of an edge.
+    pub fn minimum_enclosing_circle(&self) -> Option<Circle> {
+        match self.hull.len() {
+            0 => return None,
+            1 => return Some(Circle::new(self.points[self.hull[0]], 0.0)),
+            _ => {},
+        }
+
+        let vertices = self.triangles.chunks(3)
+                            .filter_map(|tri| Circle::circumscribed(self.points[tri[0]], self.points[tri[1]], self.points[tri[2]]));
+        let middles = self.edges.iter()
+                            .map(|edge| Circle::diametral(self.points[edge.sites.0], self.points[edge.sites.1]))
+                            .zip(self.edges.iter())
+                            .filter(|(circle, edge)| edge.contains(circle.center))
+                            .map(|(circle, _)| circle);
+        vertices.chain(middles)
+            .min_by(|a, b| a.radius.total_cmp(&b.radius))
+    }
+}
@@ -0,0 +1,108 @@
+//! Tests of the configurations of points that algorithms often assume away: three points on a line,
+//! four points on a circle, or points that are all vertices of their convex hull.
+
+use super::{ GrahamScan, delaunay };
+use crate::math::{ Vec2, float };
+
+use alloc::vec::Vec;
+
+/// A configuration of the input points that an algorithm does not handle, or has to handle specially.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Degeneracy {
+    /// Three points on a line, e.g. on an edge of the hull or forming a flat triangle
+    CollinearPoints,
+    /// Four points on a circle, which make the Delaunay triangulation and the Voronoi diagram ambiguous
+    CocircularPoints,
+}
+
+/// Returns true if every point is a vertex of the convex hull, without any point inside it or on one of its edges.
+/// Duplicated points are not in convex position.
+pub fn is_in_convex_position(points: &[Vec2]) -> bool {
+    GrahamScan::scan(points).len() == points.len()
+}
+
+/// Returns true if three of the points are on the same line.
+pub fn has_collinear_triple(points: &[Vec2]) -> bool {
+    collinear_triple(points).is_some()
+}
+
+/// Returns the indices of three points on the same line, in increasing order, or `None` if there are none.
+/// Duplicated points are collinear with any other point. For each point, the directions to the next points
+/// are sorted by angle so that the parallel ones are adjacent, the collinearity test is exact.
+pub fn collinear_triple(points: &[Vec2]) -> Option<[usize; 3]> {
+    let n = points.len();
+    if n < 3 {
+        return None;
+    }
+
+    let sorted = |mut triple: [usize; 3]| {
+        triple.sort_unstable();
+        Some(triple)
+    };
+    for i in 0..n {
+        let mut directions = Vec::with_capacity(n - i - 1);
+        for j in i + 1..n {
+            let d = &points[j] - &points[i];
+            if d.is_zero() {
+                let k = (0..n).find(|&k| k != i && k != j).expect("No third point");
+                return sorted([ i, j, k ]);
+            }
+            // Opposite directions are turned to the upper half-plane, so that their angles are equal
+            let d = if d.y < 0.0 || (d.y == 0.0 && d.x < 0.0) { Vec2::new(-d.x, -d.y) } else { d };
+            directions.push((float::atan2(d.y, d.x), j));
+        }
+        directions.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        // The angles close to 0 and to pi are close directions too
+        let wrap = directions.first().zip(directions.last());
+        for (a, b) in directions.windows(2).map(|pair| (pair[0], pair[1])).chain(wrap.map(|(&a, &b)| (a, b))) {
+            if a.1 != b.1 && Vec2::orientation(points[i], points[a.1], points[b.1]) == 0.0 {
+                return sorted([ i, a.1, b.1 ]);
+            }
+        }
+    }
+    None
+}
+
+/// Returns true if four of the points are on the same circle.
+pub fn has_cocircular_quadruple(points: &[Vec2]) -> bool {
+    cocircular_quadruple(points).is_some()
+}
+
+/// Returns the indices of four points on the same circle, in increasing order, or `None` if there are none,
+/// with the tolerance of the in-circle test of the Delaunay triangulation. Collinear points are not on a circle.
+/// For each pair of points, the centers of the circles through them and a third point lie on their bisector:
+/// they are sorted along it so that the circles through four points give adjacent centers.
+pub fn cocircular_quadruple(points: &[Vec2]) -> Option<[usize; 4]> {
+    let n = points.len();
+    for i in 0..n {
+        for j in i + 1..n {
+            let (a, b) = (points[i], points[j]);
+            let (ax, ay, bx, by) = (a.x as f64, a.y as f64, b.x as f64, b.y as f64);
+            let (mx, my) = ((ax + bx) / 2.0, (ay + by) / 2.0);
+            let (px, py) = (ay - by, bx - ax);
+
+            // The center m + t * p is as far from a as from c
+            let mut centers = (j + 1..n)
+                .filter(|&k| Vec2::orientation(a, b, points[k]) != 0.0)
+                .map(|k| {
+                    let (cx, cy) = (points[k].x as f64, points[k].y as f64);
+                    let sqr_distance = |x: f64, y: f64| (mx - x) * (mx - x) + (my - y) * (my - y);
+                    let t = (sqr_distance(ax, ay) - sqr_distance(cx, cy)) / (2.0 * (px * (ax - cx) + py * (ay - cy)));
+                    (t, k)
+                })
+                .collect::<Vec<_>>();
+            centers.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+            for pair in centers.windows(2) {
+                let (k, l) = (pair[0].1, pair[1].1);
+                if delaunay::on_circumcircle(a, b, points[k], points[l]) {
+                    let mut quadruple = [ i, j, k, l ];
+                    quadruple.sort_unstable();
+                    return Some(quadruple);
+                }
+            }
+        }
+    }
+    None
+}
@@ -1,144 +1,71 @@
-use crate::{
-    graphics,
-    math::{ self, Vec2 },
-    ui::window::algorithms::{ Drawable, Configurable },
-};
-
-use glium::{
-    index, Surface, Frame, Program, VertexBuffer, DrawParameters,
-    backend::Facade,
-    glutin::{ Event, WindowEvent },
-};
-
-use std::{
-    cmp::Ordering,
-    time::{ Instant, Duration },
-};
-
-#[derive(Copy, Clone, Debug)]
-pub struct Vertex {
-    position: Vec2,
-}
-
-impl Vertex {
-    pub fn new(position: Vec2) -> Self {
-        Self {
-            position
-        }
-    }
-}
-
-implement_vertex!(Vertex, position);
-
-pub struct GrahamScan<'f> {
-    facade: &'f dyn Facade,
+use super::{ Algorithm, Step, Snapshot, Record, Primitive, primitive_vertices };
+use crate::math::{ Vec2, polar };
+
+use core::cmp::Ordering;
+use alloc::vec::Vec;
+
+/// Convex hull of a set of 2D points, computed with the Graham scan every time a point is added.
+///
+/// The hull is counter-clockwise and starts with the bottommost point, the leftmost one if several points
+/// are on the same horizontal line. Only its corners are kept, the points in the middle of its edges are not.
+/// Of several points at the same position, the first one is used. A single point is its own hull,
+/// and the hull of collinear points is made of their two ends.
+#[derive(Default)]
+pub struct GrahamScan {
     /// Input points that will be wrapped in the generated hull
-    points: Vec<Vertex>,
-    program: Program,
-    /// Buffer object that stores all the points
-    points_buffer: VertexBuffer<Vertex>,
-    /// Buffer object that stores the points that form the hull
-    hull_buffer: VertexBuffer<Vertex>,
-    exec_time: Option<Duration>,
+    points: Vec<Vec2>,
+    /// Indices of the points that form the hull
+    hull: Vec<usize>,
 }
 
-impl<'f> Drawable for GrahamScan<'f> {
-    fn draw(&self, target: &mut Frame) {
-        self.draw_points(target);
-        self.draw_hull(target);
+impl GrahamScan {
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    fn handle_events(&mut self, window: &winit::Window, event: &winit::Event, io: &imgui::Io) {
-        if let Event::WindowEvent { event, .. } = event {
-            if let WindowEvent::MouseInput { button, state, .. } = event {
-                if !io.want_capture_mouse && // Ignore clicks when the cursor is over an ImGui window
-                    button == &winit::MouseButton::Left && state == &winit::ElementState::Pressed {
-
-                    // Add a point when the window is clicked
-                    let coords = graphics::window_pos_to_normalized(io.mouse_pos.into(), window);
-                    self.add_point(coords);
-                }
-            }
-        }
+    pub fn points(&self) -> &[Vec2] {
+        &self.points
     }
-}
 
-impl<'f> Configurable for GrahamScan<'f> {
-    fn name(&self) -> &'static str {
-        "Graham scan"
+    /// Returns the indices of the points that form the hull.
+    pub fn hull(&self) -> &[usize] {
+        &self.hull
     }
 
-    fn configure(&mut self, ui: &imgui::Ui) {
-        ui.text(imgui::im_str!("{} vertices", self.points.len()));
-
-        if ui.button(imgui::im_str!("Clear Points"), [0.0, 0.0]) {
-            self.clear();
-        }
-
-        if let Some(exec_time) = self.exec_time {
-            ui.text(imgui::im_str!("Execution time: {} µs", exec_time.as_micros()));
-        }
+    /// Returns the area enclosed by the hull.
+    pub fn area(&self) -> f32 {
+        super::hull_area(&self.points, &self.hull)
     }
-}
 
-impl<'f> GrahamScan<'f> {
-    pub fn new(facade: &'f dyn Facade) -> Self {
-        let vs = graphics::SHADERS._2d_vs;
-        let fs = graphics::SHADERS.basic_fs;
-        let program = Program::from_source(facade, vs, fs, None)
-                                    .expect("Could not compile shaders");
+    /// Returns the length of the boundary of the hull.
+    pub fn perimeter(&self) -> f32 {
+        super::hull_perimeter(&self.points, &self.hull)
+    }
 
-        Self {
-            facade,
-            points: Vec::new(),
-            program,
-            points_buffer: VertexBuffer::empty(facade, 0).unwrap(), // Start without any point
-            hull_buffer: VertexBuffer::empty(facade, 0).unwrap(), // Same for the hull
-            exec_time: None,
+    /// Add an input point that will be used to compute the convex hull.
+    /// Returns false if the point was a duplicate and has been ignored.
+    pub fn add_point(&mut self, point: Vec2) -> bool {
+        if self.points.contains(&point) {
+            log::warn!("Ignoring duplicate point ({}, {})", point.x, point.y);
+            return false;
         }
+        self.points.push(point);
+        self.hull = Self::scan(&self.points);
+        true
     }
 
-    fn draw_points(&self, target: &mut Frame) {
-        let indices = index::NoIndices(index::PrimitiveType::Points);
-        let uniforms = uniform! {
-            color: [ 1.0_f32, 1.0_f32, 1.0_f32 ],
-        };
-        let mut draw_params = DrawParameters::default();
-        draw_params.point_size = Some(8.0);
-        target.draw(&self.points_buffer, &indices, &self.program, &uniforms, &draw_params).expect("Draw failure");
+    /// Replaces the input points and computes their hull.
+    pub fn set_points(&mut self, points: Vec<Vec2>) {
+        self.points = points;
+        self.hull = Self::scan(&self.points);
     }
 
-    fn draw_hull(&self, target: &mut Frame) {
-        let indices = index::NoIndices(index::PrimitiveType::LineLoop);
-        let uniforms = uniform! {
-            color: [ 0.0, 0.8_f32, 0.0 ],
-        };
-        let draw_params = DrawParameters::default();
-        target.draw(&self.hull_buffer, &indices, &self.program, &uniforms, &draw_params).expect("Draw failure");
-
-        let indices = index::NoIndices(index::PrimitiveType::Points);
-        let uniforms = uniform! {
-            color: [ 0.0, 0.0, 0.8_f32 ],
-        };
-        target.draw(&self.hull_buffer, &indices, &self.program, &uniforms, &draw_params).expect("Draw failure");
-    }
-
-    /// Add an input point that will be used to compute the convex hull.
-    pub fn add_point(&mut self, point: Vec2) {
-        self.points.push(Vertex {
-            position: point,
-        });
-        self.points_buffer = VertexBuffer::new(self.facade, &self.points).unwrap(); // Regenerate the buffer
-
-        let start_time = Instant::now();
-        let hull = Self::scan(&self.points.iter().map(|p| p.position).collect::<Vec<_>>())
-                            .into_iter()
-                            .map(Vertex::new)
-                            .collect::<Vec<_>>();
-        self.exec_time = Some(Instant::now() - start_time);
-        self.hull_buffer = VertexBuffer::new(self.facade, &hull).unwrap(); // Regenerate the hull buffer from result
+    /// Replaces the input points with the vertices of the primitives and computes their hull.
+    pub fn set_primitives(&mut self, primitives: &[Primitive]) {
+        self.set_points(primitive_vertices(primitives));
     }
 
+    #[cfg(feature = "std")]
     pub fn random_points(&mut self, n: usize) {
         let x_min = -0.8; // 0.8 to prevent getting too close to the edges of the window
         let x_max = -x_min;
@@ -153,8 +80,7 @@ impl<'f> GrahamScan<'f> {
     /// Removes all the points.
     pub fn clear(&mut self) {
         self.points.clear();
-        self.points_buffer = VertexBuffer::empty(self.facade, 0).unwrap();
-        self.hull_buffer = VertexBuffer::empty(self.facade, 0).unwrap();
+        self.hull.clear();
     }
 
     /// Find the point that is the furthest on the bottom (first) and left.
@@ -170,7 +96,7 @@ impl<'f> GrahamScan<'f> {
             .scan((0, first), |leftmost, p| {
                 let pos = p.1;
                 let lpos = leftmost.1;
-                if pos.y < lpos.y || math::cmp_f32(pos.y, lpos.y) && pos.x < lpos.x {
+                if pos.y < lpos.y || (pos.y == lpos.y && pos.x < lpos.x) {
                     *leftmost = p;
                 }
                 Some(*leftmost)
@@ -184,40 +110,127 @@ impl<'f> GrahamScan<'f> {
         (b.x - a.x) * (c.y - a.y) - (c.x - a.x) * (b.y - a.y)
     }
 
-    /// Returns a `Vec` of points
-    /// that form the convex hull.
-    pub fn scan(points:  &[Vec2]) -> Vec<Vec2> {
-        let mut hull = Vec::new();
-
-        if points.len() < 2 {
-            return hull;
+    /// Returns a `Vec` of the indices of the points
+    /// that form the convex hull, counter-clockwise.
+    /// [`hull_positions()`](super::hull_positions) turns them into points.
+    pub fn scan(points: &[Vec2]) -> Vec<usize> {
+        if points.is_empty() {
+            return Vec::new();
         }
+        let _span = span!("Graham scan");
+
+        let mut steps = GrahamScanSteps::new(points);
+        steps.by_ref().for_each(drop);
+        steps.hull
+    }
 
+    /// Returns the indices of the points sorted by the angle they make with the bottommost point, which comes first.
+    /// Points with the same angle are sorted by distance, the duplicates of a point are removed.
+    fn sort_by_angle(points: &[Vec2]) -> Vec<usize> {
+        let _span = span!("Graham scan: sort");
         let bottommost = Self::bottommost_point(points.iter());
-        let mut points_clone = points.to_owned();
-
-        points_clone.remove(bottommost.0);
-
-        points_clone.sort_by(|a, b| {
-            let vec1 = a - bottommost.1;
-            let vec2 = b - bottommost.1;
-            if vec1.signed_angle(vec2) < 0.0 {
-                Ordering::Greater
-            } else if vec1.signed_angle(vec2) == 0.0 {
-                Ordering::Equal
-            } else {
-                Ordering::Less
-            }
+
+        // All the points are above the bottommost one, or on its right, so their angle is between 0 and pi.
+        // They are compared with orientation tests, rounded angles would mix up the nearly collinear points
+        let mut sorted = Self::unique(points)
+                            .into_iter()
+                            .filter(|&idx| points[idx] != *bottommost.1)
+                            .collect::<Vec<_>>();
+        sorted.sort_by(|&a, &b| polar::compare(*bottommost.1, points[a], points[b]));
+        sorted.insert(0, bottommost.0);
+        sorted
+    }
+
+    /// Returns the indices of the points, without those at the same position as a previous point.
+    fn unique(points: &[Vec2]) -> Vec<usize> {
+        let mut indices = (0..points.len()).collect::<Vec<_>>();
+        indices.sort_by(|&a, &b| {
+            points[a].x.partial_cmp(&points[b].x)
+                .unwrap_or(Ordering::Equal)
+                .then(points[a].y.partial_cmp(&points[b].y).unwrap_or(Ordering::Equal))
+                .then(a.cmp(&b))
         });
+        indices.dedup_by(|b, a| points[*a] == points[*b]);
+        indices
+    }
+}
+
+impl<'a> Algorithm<'a> for GrahamScan {
+    type Steps = GrahamScanSteps<'a>;
+
+    fn run(points: &'a [Vec2]) -> Self::Steps {
+        GrahamScanSteps::new(points)
+    }
+}
+
+/// Steps of the Graham scan, each one pushes a point onto the hull or pops the last one.
+/// The last point is popped when it does not make a left turn, so the points in the middle of the edges are popped too.
+pub struct GrahamScanSteps<'a> {
+    points: &'a [Vec2],
+    /// Indices of the points in the order they are pushed, computed by the first step
+    sorted: Option<Vec<usize>>,
+    /// Position in `sorted` of the next point to push
+    next: usize,
+    hull: Vec<usize>,
+}
 
-        hull.push(*bottommost.1);
+impl<'a> GrahamScanSteps<'a> {
+    pub fn new(points: &'a [Vec2]) -> Self {
+        Self {
+            points,
+            sorted: None,
+            next: 0,
+            hull: Vec::new(),
+        }
+    }
+
+    /// Returns the indices of the points on the hull so far, the last ones may still be popped.
+    pub fn hull(&self) -> &[usize] {
+        &self.hull
+    }
+}
+
+impl<'a> Iterator for GrahamScanSteps<'a> {
+    type Item = Step;
+
+    fn next(&mut self) -> Option<Step> {
+        if self.points.is_empty() {
+            return None;
+        }
+        let points = self.points;
+        let sorted = self.sorted.get_or_insert_with(|| GrahamScan::sort_by_angle(points));
+        let idx = *sorted.get(self.next)?;
+
+        let hull = &mut self.hull;
+        // Tested exactly like the sort, a rounded product could keep a point the sort put on the wrong side
+        if hull.len() > 1 && Vec2::orientation(points[hull[hull.len() - 2]], points[hull[hull.len() - 1]], points[idx]) <= 0.0 {
+            hull.pop();
+            return Some(Step::HullPop);
+        }
+        hull.push(idx);
+        self.next += 1;
+        Some(Step::HullPush(idx))
+    }
+}
+
+impl<'a> Record for GrahamScanSteps<'a> {
+    /// The hull is the stack, the candidate edge goes from its top to the point that the next step pushes or tests.
+    fn snapshot(&self, step: Option<Step>) -> Snapshot {
+        let mut hull = self.hull.clone();
+        let next = self.sorted.as_ref().and_then(|sorted| sorted.get(self.next));
+        let candidate = match (self.hull.last(), next) {
+            (Some(&top), Some(&next)) => Some((top, next)),
+            _ => None,
+        };
+        if self.sorted.is_some() && next.is_none() && !hull.is_empty() {
+            hull.push(hull[0]);
+        }
 
-        for point in points_clone {
-            while hull.len() > 1 && Self::prod_vec(hull[hull.len()-2], *hull.last().unwrap(), point) < 0.0 {
-                hull.pop();
-            }
-            hull.push(point);
+        Snapshot {
+            step,
+            hull,
+            candidate,
+            ..Snapshot::default()
         }
-        hull
     }
 }
@@ -0,0 +1,105 @@
+//! Registration of a point set onto another one with the iterative closest point algorithm.
+//! Each iteration pairs every point of the moving set with the closest point of the fixed set,
+//! then applies the rotation and translation that best align the pairs in the least squares sense.
+//! It converges to a local minimum, which is the right alignment when the sets start close enough.
+
+use crate::{
+    math::{ Vec2, Transform2, best_fit_transform, float },
+    spatial::KdTree,
+};
+
+use alloc::vec::Vec;
+
+/// Point set moved onto a fixed one, one iteration at a time.
+pub struct Icp {
+    /// The fixed points
+    target: Vec<Vec2>,
+    /// The moving points, in their current position
+    source: Vec<Vec2>,
+    /// Index of the closest target point of each source point, from the last iteration
+    correspondences: Vec<usize>,
+    /// Applied to the initial source points so far
+    transform: Transform2,
+    /// Root mean square distance between the pairs, after each iteration
+    errors: Vec<f32>,
+}
+
+impl Icp {
+    pub fn new(source: Vec<Vec2>, target: Vec<Vec2>) -> Self {
+        Self {
+            target,
+            source,
+            correspondences: Vec::new(),
+            transform: Transform2::identity(),
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn target(&self) -> &[Vec2] {
+        &self.target
+    }
+
+    /// Returns the moving points in their current position
+    pub fn source(&self) -> &[Vec2] {
+        &self.source
+    }
+
+    /// Returns the index of the closest target point of each source point, as paired by the last iteration
+    pub fn correspondences(&self) -> &[usize] {
+        &self.correspondences
+    }
+
+    /// Returns the transform applied to the initial source points so far
+    pub fn transform(&self) -> Transform2 {
+        self.transform
+    }
+
+    /// Returns the root mean square distance between the pairs after each iteration
+    pub fn errors(&self) -> &[f32] {
+        &self.errors
+    }
+
+    pub fn iterations(&self) -> usize {
+        self.errors.len()
+    }
+
+    /// Pairs the points, moves the source points to align the pairs and returns the new error,
+    /// or `None` if one of the sets is empty.
+    pub fn step(&mut self) -> Option<f32> {
+        if self.source.is_empty() || self.target.is_empty() {
+            return None;
+        }
+        let tree = KdTree::new(&self.target);
+        self.correspondences = self.source
+            .iter()
+            .map(|&p| tree.nearest(p).expect("Empty tree"))
+            .collect();
+        let matched = self.correspondences.iter().map(|&idx| self.target[idx]).collect::<Vec<_>>();
+
+        let transform = best_fit_transform(&self.source, &matched);
+        for p in &mut self.source {
+            *p = transform.apply(*p);
+        }
+        self.transform = self.transform.then(&transform);
+
+        let sum = self.source.iter().zip(&matched).map(|(p, q)| (p - q).sqr_length()).sum::<f32>();
+        let error = float::sqrt(sum / self.source.len() as f32);
+        self.errors.push(error);
+        Some(error)
+    }
+
+    /// Iterates until the error improves by less than `tolerance`, at most `max_iterations` times,
+    /// and returns the last error.
+    pub fn run(&mut self, max_iterations: usize, tolerance: f32) -> Option<f32> {
+        let mut previous = None;
+        for _ in 0..max_iterations {
+            let error = self.step()?;
+            match previous {
+                Some(previous) if previous - error < tolerance => return Some(error),
+                _ => {},
+            }
+            previous = Some(error);
+        }
+        previous
+    }
+}
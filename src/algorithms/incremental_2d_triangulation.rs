@@ -1,283 +1,185 @@
-use std::{
-    cmp::Ordering,
-    time::{ Duration, Instant },
-};
+use core::cmp::Ordering;
+use alloc::{ vec, vec::Vec };
 
+use super::{ Algorithm, Step, Snapshot, Record, delaunay };
 use crate::{
-    graphics,
-    math::{ self, Vec2 },
-    ui::window::algorithms::{ Drawable, Configurable },
-};
-
-use glium::{
-    index, Surface, Frame, Program, VertexBuffer, IndexBuffer, DrawParameters, PolygonMode,
-    backend::Facade,
-    glutin::{ Event, WindowEvent },
+    math::Vec2,
+    task::Progress,
 };
 
-use cgmath::Matrix4;
-use cgmath::Vector4;
-use cgmath::SquareMatrix;
-
-#[derive(Copy, Clone, Debug)]
-pub struct Vertex {
-    position: Vec2,
+/// Triangulation of a set of 2D points, optionally turned into a Delaunay triangulation by edge flipping.
+#[derive(Default)]
+pub struct Incremental2dTriangulation {
+    /// Input points that will be triangulated, sorted by the last triangulation
+    points: Vec<Vec2>,
+    /// Indices of the points that form the triangles, 3 per triangle
+    indices: Vec<usize>,
 }
 
-impl Vertex {
-    pub fn new(position: Vec2) -> Self {
-        Self {
-            position,
-        }
+impl Incremental2dTriangulation {
+    pub fn new() -> Self {
+        Self::default()
     }
-}
-
-implement_vertex!(Vertex, position);
-
-pub struct Incremental2dTriangulation<'f> {
-    facade: &'f dyn Facade,
-    /// Input points that will be triangulated
-    points: Vec<Vertex>,
-    program: Program,
-    /// Buffer object that stores all the points
-    points_buffer: VertexBuffer<Vertex>,
-    triangles_buffer: IndexBuffer<u32>,
-    exec_time: Option<Duration>,
-}
 
-impl<'f> Drawable for Incremental2dTriangulation<'f> {
-    fn draw(&self, target: &mut Frame) {
-        self.draw_triangles(target);
-        self.draw_points(target);
+    pub fn points(&self) -> &[Vec2] {
+        &self.points
     }
 
-    fn handle_events(&mut self, window: &winit::Window, event: &winit::Event, io: &imgui::Io) {
-        if let Event::WindowEvent { event, .. } = event {
-            if let WindowEvent::MouseInput { button, state, .. } = event {
-                if !io.want_capture_mouse && // Ignore clicks when the cursor is over an ImGui window
-                    button == &winit::MouseButton::Left && state == &winit::ElementState::Pressed {
-
-                    // Add a point on click
-                    let coords = graphics::window_pos_to_normalized(io.mouse_pos.into(), window);
-                    self.add_point(coords);
-                }
-            }
-        }
+    /// Returns the indices of the points that form the triangles, 3 per triangle.
+    pub fn indices(&self) -> &[usize] {
+        &self.indices
     }
-}
 
-impl<'f> Configurable for Incremental2dTriangulation<'f> {
-    fn name(&self) -> &'static str {
-        "Incremental 2D triangulation"
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
     }
 
-    fn configure(&mut self, ui: &imgui::Ui) {
-        ui.text(imgui::im_str!("{} vertices, {} triangles", self.points.len(), self.triangles_buffer.get_size() / 3 / std::mem::size_of::<u32>()));
-
-        if ui.button(imgui::im_str!("Clear Points"), [0.0, 0.0]) {
-            self.clear();
-        }
-
-        if ui.button(imgui::im_str!("Edge Flipping"), [0.0, 0.0]) {
-            self.flip_edges();
-        }
-
-        
-
-        if let Some(exec_time) = self.exec_time {
-            ui.text(imgui::im_str!("Execution time: {} µs", exec_time.as_micros()));
+    /// Add a point to the input set of points and triangulate again.
+    /// Returns false if the point was a duplicate and has been ignored.
+    pub fn add_point(&mut self, point: Vec2) -> bool {
+        if !self.push_point(point) {
+            return false;
         }
+        self.retriangulate(false);
+        true
     }
-}
 
-impl<'f> Incremental2dTriangulation<'f> {
-    pub fn new(facade: &'f dyn Facade) -> Self {
-        let vs = graphics::SHADERS._2d_vs;
-        let fs = graphics::SHADERS.basic_fs;
-        let program = Program::from_source(facade, vs, fs, None)
-                                    .expect("Could not compile shaders");
-
-        Self {
-            facade,
-            points: Vec::new(),
-            program,
-            points_buffer: VertexBuffer::empty(facade, 0).unwrap(), // Start without any point
-            triangles_buffer: IndexBuffer::empty(facade, index::PrimitiveType::TrianglesList, 0).unwrap(),
-            exec_time: None,
+    /// Adds a point without triangulating, returns false if it was a duplicate.
+    pub fn push_point(&mut self, point: Vec2) -> bool {
+        if self.points.contains(&point) {
+            log::warn!("Ignoring duplicate point ({}, {})", point.x, point.y);
+            return false;
         }
+        self.points.push(point);
+        true
     }
 
-    fn draw_points(&self, target: &mut Frame) {
-        let indices = index::NoIndices(index::PrimitiveType::Points);
-        let uniforms = uniform! {
-            color: [ 1.0_f32, 1.0_f32, 1.0_f32 ],
-        };
-        let mut draw_params = DrawParameters::default();
-        draw_params.point_size = Some(8.0);
-        target.draw(&self.points_buffer, &indices, &self.program, &uniforms, &draw_params).expect("Draw failure");
-    }
-
-    fn draw_triangles(&self, target: &mut Frame) {
-        let uniforms = uniform! {
-            color: [ 0.0_f32, 0.2_f32, 1.0_f32 ],
-        };
-        let mut draw_params = DrawParameters::default();
-        draw_params.polygon_mode = PolygonMode::Line;
-        target.draw(&self.points_buffer, &self.triangles_buffer, &self.program, &uniforms, &draw_params).expect("Draw failure");
-    }
-
-    pub fn flip_edges(&mut self) {
-        self.points_buffer = VertexBuffer::new(self.facade, &self.points).unwrap(); // Regenerate the vertex buffer
-
-        let mut positions = self.points
-                                    .iter()
-                                    .map(|v| v.position)
-                                    .collect::<Vec<_>>();
-        //let start_time = Instant::now();
-        let mut indices = Self::triangulate(&mut positions);
-        //dbg!(&indices);
-        if !indices.is_empty() {
-            Self::edge_flipping(&mut indices, &positions);
-
-            //dbg!(&indices);
-        }
-        //self.exec_time = Some(Instant::now() - start_time);
-        
-        // Convert our positions back to vertices, since the triangulate function sorts the input data (positions),
-        // we need to change the order of our vertices vector as well so we recreate it from the positions
-        self.points = positions
-                            .iter()
-                            .map(|p| Vertex { position: *p })
-                            .collect();
-        
-        self.points_buffer = VertexBuffer::new(self.facade, &self.points).unwrap(); // Regenerate the vertex buffer
-
-        // Convert usize indices to u32s
-        let indices = indices.iter()
-                                .map(|&idx| idx as u32)
-                                .collect::<Vec<_>>();
-        self.triangles_buffer = IndexBuffer::new(self.facade, index::PrimitiveType::TrianglesList, &indices).unwrap();
-        //println!("flipped edges");
-    }
-
-    /// Add a point to the input set of points.
-    pub fn add_point(&mut self, point: Vec2) {
-        self.points.push(Vertex::new(point));
-        self.points_buffer = VertexBuffer::new(self.facade, &self.points).unwrap(); // Regenerate the vertex buffer
-
-        let mut positions = self.points
-                                    .iter()
-                                    .map(|v| v.position)
-                                    .collect::<Vec<_>>();
-        let start_time = Instant::now();
-        let indices = Self::triangulate(&mut positions);
-        self.exec_time = Some(Instant::now() - start_time);
-        // Convert our positions back to vertices, since the triangulate function sorts the input data (positions),
-        // we need to change the order of our vertices vector as well so we recreate it from the positions
-        self.points = positions
-                            .iter()
-                            .map(|p| Vertex { position: *p })
-                            .collect();
-        
-        self.points_buffer = VertexBuffer::new(self.facade, &self.points).unwrap(); // Regenerate the vertex buffer
-
-        // Convert usize indices to u32s
-        let indices = indices.iter()
-                                .map(|&idx| idx as u32)
-                                .collect::<Vec<_>>();
-        self.triangles_buffer = IndexBuffer::new(self.facade, index::PrimitiveType::TrianglesList, &indices).unwrap();
+    /// Replaces the input points without triangulating them.
+    pub fn set_points(&mut self, points: Vec<Vec2>) {
+        self.points = points;
+        self.indices.clear();
     }
 
-    pub fn random_points(&mut self, n: usize) {
+    /// Adds `n` random points without triangulating them.
+    #[cfg(feature = "std")]
+    pub fn push_random_points(&mut self, n: usize) {
         let x_min = -0.8; // 0.8 to prevent getting too close to the edges of the window
         let x_max = -x_min;
         let y_min = x_min;
         let y_max = -y_min;
 
         for _ in 0..n {
-            self.add_point(Vec2::random_range(x_min, x_max, y_min, y_max));
+            self.push_point(Vec2::random_range(x_min, x_max, y_min, y_max));
+        }
+    }
+
+    /// Triangulates the current points, then flips the edges if `flip_edges` is true.
+    pub fn retriangulate(&mut self, flip_edges: bool) {
+        let points = self.points.clone();
+        if let Some((points, indices)) = Self::compute(points, flip_edges, &Progress::default()) {
+            self.set_triangulation(points, indices);
         }
     }
 
+    /// Replaces the points and the triangles with the result of [`compute()`](#method.compute),
+    /// e.g. when it ran on a worker thread.
+    pub fn set_triangulation(&mut self, points: Vec<Vec2>, indices: Vec<usize>) {
+        self.points = points;
+        self.indices = indices;
+    }
+
     /// Removes all the points.
     pub fn clear(&mut self) {
         self.points.clear();
-        self.points_buffer = VertexBuffer::empty(self.facade, 0).unwrap();
-        self.triangles_buffer = IndexBuffer::empty(self.facade, index::PrimitiveType::TrianglesList, 0).unwrap();
+        self.indices.clear();
     }
 
-    /// Sorts points by increasing x coordinates, and by increasing y coordinates if two points are on the same vertical line
-    fn sort(points: &mut Vec<Vec2>) {
-        points.sort_by(|a, b| {
-            if a.x < b.x || (math::cmp_f32(a.x, b.x) && a.y < b.y) {
-                Ordering::Less
-            } else {
-                Ordering::Greater
-            }
-        });
+    /// Triangulates `points`, then flips the edges if `flip_edges` is true.
+    /// Returns the points in the order used by the indices, where coincident points are only kept once,
+    /// and the indices of the triangles, or `None` if the computation was cancelled.
+    pub fn compute(mut points: Vec<Vec2>, flip_edges: bool, progress: &Progress) -> Option<(Vec<Vec2>, Vec<usize>)> {
+        let mut indices = Self::triangulate_with_progress(&mut points, progress)?;
+        if flip_edges && !indices.is_empty() {
+            Self::edge_flipping_with_progress(&mut indices, &points, progress)?;
+        }
+        Some((points, indices))
     }
 
-    pub fn triangulate(points: &mut Vec<Vec2>) -> Vec<usize> {
-        let n = points.len();
-        if n < 3 {
-            return Vec::new();
+    /// Records the states of the triangulation of `points` after each step, followed by those of the edge flipping
+    /// if `flip_edges` is true. Returns the points in the order used by the indices of the states and the states.
+    pub fn record(points: Vec<Vec2>, flip_edges: bool) -> (Vec<Vec2>, Vec<Snapshot>) {
+        let mut steps = TriangulationSteps::new(points);
+        let mut snapshots = steps.record();
+        if flip_edges {
+            // Flipping does not change the hull, and its first state is the last one of the triangulation
+            let hull = snapshots.last().map(|snapshot| snapshot.hull.clone()).unwrap_or_default();
+            let mut flipping = EdgeFlippingSteps::new(steps.indices(), steps.points());
+            snapshots.extend(flipping.record()
+                                .into_iter()
+                                .skip(1)
+                                .map(|snapshot| Snapshot { hull: hull.clone(), ..snapshot }));
         }
+        (steps.points, snapshots)
+    }
 
-        Self::sort(points);
-        let mut indices = Vec::new();
+    /// Sorts points by increasing x coordinates, and by increasing y coordinates if two points are on the same vertical line
+    fn sort(points: &mut [Vec2]) {
+        let _span = span!("Triangulation: sort");
+        // Comparing the x coordinates with a tolerance would not be a total order, the hull of the sorted points would be wrong.
+        // partial_cmp() considers that 0 and -0 are equal, unlike total_cmp()
+        let cmp = |a: f32, b: f32| a.partial_cmp(&b).unwrap_or(Ordering::Equal);
+        points.sort_by(|a, b| cmp(a.x, b.x).then(cmp(a.y, b.y)));
+    }
 
-        let mut polygon = vec![points[0]];
-        let mut first_idx = 0;
-        let mut last_collinear = Vec2::default();
-        for i in 1..n {
-            let a = points[i - 1];
-            let b = points[i];
-            let s = &a - &b;
+    /// Returns the Delaunay triangulation of the points, 3 indices per triangle into `points`, whose order is kept:
+    /// unlike [`triangulate()`](#method.triangulate), other data attached to the points still matches them.
+    /// Of coincident points, only the first one is in triangles.
+    pub fn delaunay(points: &[Vec2]) -> Vec<usize> {
+        let cmp = |a: f32, b: f32| a.partial_cmp(&b).unwrap_or(Ordering::Equal);
+        let mut order = (0..points.len()).collect::<Vec<_>>();
+        order.sort_by(|&i, &j| cmp(points[i].x, points[j].x).then(cmp(points[i].y, points[j].y)).then(i.cmp(&j)));
+        order.dedup_by(|&mut j, &mut i| points[i] == points[j]);
+        // Sorting the sorted points again keeps them in place
+        let mut sorted = order.iter().map(|&idx| points[idx]).collect();
+        let mut indices = Self::triangulate(&mut sorted);
+        Self::edge_flipping(&mut indices, &sorted);
+        indices.into_iter().map(|idx| order[idx]).collect()
+    }
 
-            if i > 1 && !last_collinear.collinear(s) {
-                first_idx = i;
+    /// Sorts `points`, keeping one of the coincident ones, and returns their triangulation, 3 indices per triangle.
+    pub fn triangulate(points: &mut Vec<Vec2>) -> Vec<usize> {
+        Self::triangulate_with_progress(points, &Progress::default()).unwrap_or_default()
+    }
+
+    /// Same as [`triangulate()`](#method.triangulate) but reports the number of inserted points to `progress`.
+    /// Returns `None` if the computation was cancelled.
+    pub fn triangulate_with_progress(points: &mut Vec<Vec2>, progress: &Progress) -> Option<Vec<usize>> {
+        if points.len() < 3 {
+            return Some(Vec::new());
+        }
+        let _span = span!("Triangulation");
+
+        let mut steps = TriangulationSteps::new(core::mem::take(points));
+        let n = steps.points.len();
+        progress.set_total(n);
+        let mut cancelled = false;
+        while steps.next < n {
+            if progress.is_cancelled() {
+                cancelled = true;
                 break;
             }
-            last_collinear = s;
-            polygon.push(b);
+            progress.set_done(steps.next);
+            steps.insert_next();
         }
 
-        for i in first_idx..n {
-            // Refresh the convex hull
-            let hull_indices = super::JarvisMarch::march(polygon.iter());
-            let convex_hull = hull_indices.iter()
-                                    .map(|&idx| polygon[idx])
-                                    .collect::<Vec<_>>();
-
-            let mut added_to_polygon = false;
-            let hull_size = convex_hull.len();
-            for j in 0..hull_size {
-                let idx_b = hull_indices[j];
-                let idx_a = if j + 1 < hull_size {
-                    hull_indices[j + 1]
-                } else {
-                    hull_indices[0]
-                };
-                let idx_c = i;
-                let a = polygon[idx_a];
-                let b = polygon[idx_b];
-                let c = points[idx_c];
-                if Vec2::cw(a, b, c) {
-                    indices.push(idx_a);
-                    indices.push(idx_b);
-                    indices.push(idx_c);
-                    if !added_to_polygon {
-                        polygon.push(c);
-                        added_to_polygon = true;
-                    }
-                }
-            }
+        *points = steps.points;
+        if cancelled {
+            return None;
         }
-
-        indices
+        Some(steps.indices)
     }
 
-    pub fn get_triangles(indices: &mut Vec<usize>) -> Vec<(usize, usize, usize)> {
+    pub fn get_triangles(indices: &[usize]) -> Vec<(usize, usize, usize)> {
         let mut triangles = vec![];
         for i in (0..indices.len()).step_by(3) {
             triangles.push((indices[i], indices[i+1], indices[i+2]));
@@ -286,19 +188,32 @@ impl<'f> Incremental2dTriangulation<'f> {
     }
 
     pub fn determinant(triangle1: &(usize, usize, usize), triangle2: &(usize, usize, usize), pos: &[Vec2]) -> f32 {
-        let mut vec = vec![triangle1.0, triangle1.1, triangle1.2, triangle2.0, triangle2.1, triangle2.2];
-        vec.dedup();
-
-        let mat = Matrix4::from_cols(
-            Vector4::new(pos[vec[0]].x,pos[vec[1]].x,pos[vec[2]].x,pos[vec[3]].x),
-            Vector4::new(pos[vec[0]].y,pos[vec[1]].y,pos[vec[2]].y,pos[vec[3]].y),
-            Vector4::new(pos[vec[0]].x.powf(2.0) + pos[vec[0]].y.powf(2.0),pos[vec[1]].x.powf(2.0) + pos[vec[1]].y.powf(2.0),pos[vec[2]].x.powf(2.0) + pos[vec[2]].y.powf(2.0),pos[vec[3]].x.powf(2.0) + pos[vec[3]].y.powf(2.0)),
-            Vector4::new(1.0_f32, 1.0_f32,1.0_f32,1.0_f32)
-        );
-
-        mat.determinant()
+        // The vertex of the second triangle that is not in the first one, they share the other two
+        let opposite = [ triangle2.0, triangle2.1, triangle2.2 ]
+                            .iter()
+                            .cloned()
+                            .find(|&idx| !Self::indice_in_triangle(idx, triangle1))
+                            .unwrap_or(triangle2.2);
+
+        // Determinant of the rows (x, y, x² + y², 1) of the 4 points,
+        // computed as the 3x3 determinant of the first 3 rows minus the last one
+        let last = pos[opposite];
+        let row = |p: Vec2| (p.x - last.x, p.y - last.y, p.sqr_length() - last.sqr_length());
+        let (a, b, c) = (row(pos[triangle1.0]), row(pos[triangle1.1]), row(pos[triangle1.2]));
+        a.0 * (b.1 * c.2 - b.2 * c.1)
+            - a.1 * (b.0 * c.2 - b.2 * c.0)
+            + a.2 * (b.0 * c.1 - b.1 * c.0)
     }
     
+    /// Returns true if the vertex of the second triangle that is not in the first one is inside
+    /// the circumcircle of the first one, with the same tolerance as [`validate_delaunay()`](super::validate_delaunay).
+    pub fn is_illegal(triangle1: &(usize, usize, usize), triangle2: &(usize, usize, usize), pos: &[Vec2]) -> bool {
+        [ triangle2.0, triangle2.1, triangle2.2 ]
+            .iter()
+            .filter(|&&idx| !Self::indice_in_triangle(idx, triangle1))
+            .any(|&opposite| delaunay::in_circumcircle(pos[triangle1.0], pos[triangle1.1], pos[triangle1.2], pos[opposite]))
+    }
+
     pub fn indice_in_triangle(i: usize, triangle: &(usize, usize, usize)) -> bool {
         i == triangle.0 || i == triangle.1 || i == triangle.2
     }
@@ -310,7 +225,6 @@ impl<'f> Incremental2dTriangulation<'f> {
         //let (mut opposite1, mut opposite2, mut edge1, mut edge2) = (0, 0, 0, 0);
         let mut edges = vec![];
         let mut opposites = vec![];
-        //dbg!(&single);
         for s in single{
             if vec.iter().filter(|&n| *n == s).count() > 1 {
                 opposites.push(s);
@@ -327,7 +241,7 @@ impl<'f> Incremental2dTriangulation<'f> {
 
     pub fn has_sim_edge(triangle1: &(usize, usize, usize), triangle2: &(usize, usize, usize)) -> bool {
         let vec1 = vec![triangle1.0, triangle1.1, triangle1.2];
-        let vec2 = vec![triangle2.0, triangle2.1, triangle2.2];
+        let vec2 = [triangle2.0, triangle2.1, triangle2.2];
 
         let mut p1 = false;
         let mut p2 = false;
@@ -345,63 +259,28 @@ impl<'f> Incremental2dTriangulation<'f> {
     }
 
     pub fn edge_flipping(indices: &mut Vec<usize>, points: &[Vec2]) {
-        let mut triangles = Self::get_triangles(indices);
-        let mut res = vec![];
-        //dbg!(&indices);
-        //dbg!(&triangles);
-
-        //dbg!(&pos);
-        //let mut i = pos.len();
-        while !triangles.is_empty() {
-            //i-=1;
-            let mut to_push = None;
-            let triangle = &mut triangles.pop().unwrap();
-            for other_triangle in &mut triangles {
-                if *triangle == *other_triangle {
-                    continue;
-                }
-                // if is_ccw > 0, its ccw, if is_ccw = 0 then collinear, else cw
-                let is_ccw = Self::prod_vec(points[triangle.0], points[triangle.1], points[triangle.2]);
-                if Self::has_sim_edge(triangle, other_triangle) &&
-                ((is_ccw > 0.00001 && Self::determinant(triangle, other_triangle, &points) > 0.00001) ||
-                 (is_ccw < -0.00001 && Self::determinant(triangle, other_triangle, &points) < -0.00001)) {
-                    //println!("Flipping edge");
-                    let (opposite1, opposite2, edge1, edge2) = Self::get_opposite(triangle, other_triangle);
-                    //*triangle = (opposite1, edge1, opposite2);
-                    *triangle = if is_ccw > 0.0 { 
-                        (edge1, opposite1, edge2)
-                    } else {
-                        (edge2, opposite1, edge1)
-                    };
-                    //*other_triangle = (opposite1, opposite2, edge2);
-                    *other_triangle = if is_ccw > 0.0 { 
-                        (edge1, edge2, opposite2)
-                    } else {
-                        (opposite2, edge2, edge1)
-                    };
-                    //*other_triangle = (edge1, edge2, opposite2);
-                    //newTri1 = [iOpposite1, edge[0], iOpposite2]
-                    //newTri2 = [iOpposite1, iOpposite2, edge[1]]
-                    to_push = Some(*triangle);
-                    break;
-                }
-            }
-            if to_push == None {
-                res.insert(0,triangle.2);
-                res.insert(0,triangle.1);
-                res.insert(0,triangle.0);
-            } else {
-                triangles.push(to_push.unwrap());
-            }
-            //indices[pos[i]] = triangle.0;
-            //indices[pos[i]+1] = triangle.1;
-            //indices[pos[i]+2] = triangle.2;
+        Self::edge_flipping_with_progress(indices, points, &Progress::default());
+    }
 
-            //dbg!(&res);
+    /// Same as [`edge_flipping()`](#method.edge_flipping) but reports the number of triangles
+    /// that do not need to be flipped anymore to `progress`.
+    /// Returns `None` and leaves `indices` untouched if the computation was cancelled.
+    pub fn edge_flipping_with_progress(indices: &mut Vec<usize>, points: &[Vec2], progress: &Progress) -> Option<()> {
+        let _span = span!("Edge flipping");
+        let mut steps = EdgeFlippingSteps::new(indices, points);
+        progress.set_total(steps.triangles.len());
+
+        while !steps.triangles.is_empty() {
+            if progress.is_cancelled() {
+                return None;
+            }
+            progress.set_done(steps.res.len() / 3);
+            steps.flip_next();
         }
 
-        *indices = res;
-        //dbg!(&indices);
+        log::debug!("Edge flipping: {} flips, {} triangles", steps.flips, steps.res.len() / 3);
+        *indices = steps.res;
+        Some(())
 
         // ve: get_edges();
         // while (!ve.is_empty()) {
@@ -416,3 +295,280 @@ impl<'f> Incremental2dTriangulation<'f> {
         // }
     }
 }
+
+impl<'a> Algorithm<'a> for Incremental2dTriangulation {
+    type Steps = TriangulationSteps;
+
+    fn run(points: &'a [Vec2]) -> Self::Steps {
+        TriangulationSteps::new(points.to_vec())
+    }
+}
+
+/// Steps of the incremental triangulation, each one adds a triangle.
+/// The points are sorted when the steps are created, the indices refer to [`points()`](#method.points).
+pub struct TriangulationSteps {
+    points: Vec<Vec2>,
+    /// Triangles created so far, some of them may not have been returned by a step yet
+    indices: Vec<usize>,
+    /// Number of triangles returned by the steps
+    returned: usize,
+    /// Index of the next point to insert
+    next: usize,
+}
+
+impl TriangulationSteps {
+    /// Sorts the points and removes the coincident ones, which would make degenerate triangles.
+    pub fn new(mut points: Vec<Vec2>) -> Self {
+        if points.len() >= 3 {
+            Incremental2dTriangulation::sort(&mut points);
+            points.dedup();
+        }
+        let n = points.len();
+        let mut steps = Self {
+            points: Vec::new(),
+            indices: Vec::new(),
+            returned: 0,
+            next: n, // Nothing to insert until the first non-collinear points are found
+        };
+        if n < 3 {
+            steps.points = points;
+            return steps;
+        }
+
+        let mut first_idx = 0;
+        let mut last_collinear = Vec2::default();
+        for i in 1..n {
+            let a = points[i - 1];
+            let b = points[i];
+            let s = &a - &b;
+
+            if i > 1 && !last_collinear.collinear(s) {
+                first_idx = i;
+                break;
+            }
+            last_collinear = s;
+        }
+        steps.points = points;
+        if first_idx == 0 {
+            log::warn!("All the points are collinear, no triangle can be formed");
+            return steps;
+        }
+
+        steps.next = first_idx;
+        steps
+    }
+
+    /// Returns the input points, sorted and without the coincident ones.
+    pub fn points(&self) -> &[Vec2] {
+        &self.points
+    }
+
+    /// Returns the indices of the triangles added so far, 3 per triangle.
+    pub fn indices(&self) -> &[usize] {
+        &self.indices[..self.returned * 3]
+    }
+
+    /// Connects the next point to the edges of the hull that it can see.
+    fn insert_next(&mut self) {
+        let i = self.next;
+        self.next += 1;
+
+        // Refresh the convex hull of the inserted points, walked clockwise from the leftmost one
+        let hull_indices = Self::boundary(&self.points[..i]);
+        let hull_size = hull_indices.len();
+        for j in (0..hull_size).rev() {
+            let idx_a = hull_indices[j];
+            let idx_b = hull_indices[(j + 1) % hull_size];
+            let idx_c = i;
+            let a = self.points[idx_a];
+            let b = self.points[idx_b];
+            let c = self.points[idx_c];
+            if Vec2::cw(a, b, c) {
+                self.indices.push(idx_a);
+                self.indices.push(idx_b);
+                self.indices.push(idx_c);
+            }
+        }
+    }
+
+    /// Returns the indices of the points on the convex hull of sorted points, counter-clockwise from the first one,
+    /// with the points in the middle of its edges. The hull of collinear points goes to the last one and back.
+    fn boundary(points: &[Vec2]) -> Vec<usize> {
+        // Monotone chain, the turns are only checked to be clockwise to keep the collinear points
+        let mut hull: Vec<usize> = Vec::new();
+        let chain = (0..points.len()).chain((0..points.len() - 1).rev());
+        let mut lower_len = 0;
+        for (pos, idx) in chain.enumerate() {
+            if pos == points.len() {
+                lower_len = hull.len(); // The upper chain does not pop the lower one
+            }
+            while hull.len() > lower_len.max(1) && Vec2::cw(points[hull[hull.len() - 2]], points[hull[hull.len() - 1]], points[idx]) {
+                hull.pop();
+            }
+            hull.push(idx);
+        }
+        hull.pop(); // The first point closes the upper chain
+        hull
+    }
+}
+
+impl Iterator for TriangulationSteps {
+    type Item = Step;
+
+    fn next(&mut self) -> Option<Step> {
+        while self.returned * 3 == self.indices.len() {
+            if self.next >= self.points.len() {
+                return None;
+            }
+            self.insert_next();
+        }
+
+        let triangle = &self.indices[self.returned * 3..self.returned * 3 + 3];
+        self.returned += 1;
+        Some(Step::Triangle(triangle[0], triangle[1], triangle[2]))
+    }
+}
+
+impl Record for TriangulationSteps {
+    /// The hull is that of the inserted points, the candidate edge is the hull edge the last triangle was built on.
+    fn snapshot(&self, step: Option<Step>) -> Snapshot {
+        // The last inserted point is not on the hull until all its triangles have been returned
+        let inserted = if self.returned * 3 < self.indices.len() { self.next - 1 } else { self.next };
+        let mut hull = if inserted == 0 { Vec::new() } else { Self::boundary(&self.points[..inserted]) };
+        if let Some(&first) = hull.first() {
+            hull.push(first);
+        }
+        let candidate = match step {
+            Some(Step::Triangle(a, b, _)) => Some((a, b)),
+            _ => None,
+        };
+
+        Snapshot {
+            step,
+            hull,
+            triangles: self.indices().to_vec(),
+            candidate,
+        }
+    }
+}
+
+/// Steps of the edge flipping, each one flips an edge that does not satisfy the Delaunay criterion.
+pub struct EdgeFlippingSteps<'a> {
+    points: &'a [Vec2],
+    /// Triangles that may still have to be flipped
+    triangles: Vec<(usize, usize, usize)>,
+    /// Indices of the triangles that do not need to be flipped anymore, 3 per triangle
+    res: Vec<usize>,
+    flips: usize,
+}
+
+impl<'a> EdgeFlippingSteps<'a> {
+    /// `indices` contains 3 indices into `points` per triangle.
+    pub fn new(indices: &[usize], points: &'a [Vec2]) -> Self {
+        Self {
+            points,
+            triangles: Incremental2dTriangulation::get_triangles(indices),
+            res: Vec::new(),
+            flips: 0,
+        }
+    }
+
+    /// Returns the current triangles.
+    pub fn triangles(&self) -> impl Iterator<Item = (usize, usize, usize)> + '_ {
+        self.triangles.iter()
+            .cloned()
+            .chain(self.res.chunks(3).map(|t| (t[0], t[1], t[2])))
+    }
+
+    /// Checks the last triangle of the queue against the others,
+    /// returns the flip if its edge shared with one of them had to be flipped.
+    fn flip_next(&mut self) -> Option<Step> {
+        let points = self.points;
+        let mut to_push = None;
+        let triangle = &mut self.triangles.pop()?;
+        for other_triangle in &mut self.triangles {
+            if *triangle == *other_triangle {
+                continue;
+            }
+            // if is_ccw > 0, its ccw, if is_ccw = 0 then collinear, else cw
+            let is_ccw = Incremental2dTriangulation::prod_vec(points[triangle.0], points[triangle.1], points[triangle.2]);
+            if Incremental2dTriangulation::has_sim_edge(triangle, other_triangle) &&
+               Incremental2dTriangulation::is_illegal(triangle, other_triangle, points) {
+                log::trace!("Flipping the edge shared by {:?} and {:?}", triangle, other_triangle);
+                self.flips += 1;
+                let (opposite1, opposite2, edge1, edge2) = Incremental2dTriangulation::get_opposite(triangle, other_triangle);
+                *triangle = if is_ccw > 0.0 {
+                    (edge1, opposite1, edge2)
+                } else {
+                    (edge2, opposite1, edge1)
+                };
+                *other_triangle = if is_ccw > 0.0 {
+                    (edge1, edge2, opposite2)
+                } else {
+                    (opposite2, edge2, edge1)
+                };
+                to_push = Some(Step::Flip(*triangle, *other_triangle));
+                break;
+            }
+        }
+        match to_push {
+            Some(Step::Flip(first, second)) => {
+                // The triangles that were done and share an edge with the new ones have to be checked again
+                let mut idx = 0;
+                while idx < self.res.len() {
+                    let done = (self.res[idx], self.res[idx + 1], self.res[idx + 2]);
+                    if Incremental2dTriangulation::has_sim_edge(&done, &first) || Incremental2dTriangulation::has_sim_edge(&done, &second) {
+                        self.res.drain(idx..idx + 3);
+                        self.triangles.insert(0, done);
+                    } else {
+                        idx += 3;
+                    }
+                }
+                self.triangles.push(*triangle);
+            },
+            _ => {
+                self.res.insert(0, triangle.2);
+                self.res.insert(0, triangle.1);
+                self.res.insert(0, triangle.0);
+            },
+        }
+        to_push
+    }
+}
+
+impl<'a> Iterator for EdgeFlippingSteps<'a> {
+    type Item = Step;
+
+    fn next(&mut self) -> Option<Step> {
+        while !self.triangles.is_empty() {
+            if let Some(step) = self.flip_next() {
+                return Some(step);
+            }
+        }
+        None
+    }
+}
+
+impl<'a> Record for EdgeFlippingSteps<'a> {
+    /// The candidate edge is the one created by the last flip.
+    fn snapshot(&self, step: Option<Step>) -> Snapshot {
+        let candidate = match step {
+            Some(Step::Flip(first, second)) => {
+                let shared = [ first.0, first.1, first.2 ]
+                                .iter()
+                                .cloned()
+                                .filter(|&idx| Incremental2dTriangulation::indice_in_triangle(idx, &second))
+                                .collect::<Vec<_>>();
+                Some((shared[0], shared[1]))
+            },
+            _ => None,
+        };
+
+        Snapshot {
+            step,
+            triangles: self.triangles().flat_map(|t| vec![ t.0, t.1, t.2 ]).collect(),
+            candidate,
+            ..Snapshot::default()
+        }
+    }
+}
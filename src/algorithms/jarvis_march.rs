@@ -1,140 +1,70 @@
-use crate::{
-    graphics,
-    math::{ self, Vec2 },
-    ui::window::algorithms::{ Drawable, Configurable },
-};
-
-use std::time::{ Duration, Instant };
-
-use glium::{
-    index, Surface, Frame, Program, VertexBuffer, DrawParameters,
-    backend::Facade,
-    glutin::{ Event, WindowEvent },
-};
-
-#[derive(Copy, Clone, Debug)]
-pub struct Vertex {
-    position: Vec2,
-}
-
-impl Vertex {
-    pub fn new(position: Vec2) -> Self {
-        Self {
-            position,
-        }
-    }
-}
-
-implement_vertex!(Vertex, position);
-
-pub struct JarvisMarch<'f> {
-    facade: &'f dyn Facade,
+use super::{ Algorithm, Step, Snapshot, Record, Primitive, primitive_vertices };
+use crate::math::Vec2;
+
+use alloc::{ vec, vec::Vec };
+
+/// Convex hull of a set of 2D points, computed with the Jarvis march every time a point is added.
+///
+/// The hull is counter-clockwise and starts with the leftmost point, the lowest one if several points
+/// are on the same vertical line. Only its corners are kept, the points in the middle of its edges are not.
+/// Of several points at the same position, the first one is used. A single point is its own hull,
+/// and the hull of collinear points is made of their two ends.
+#[derive(Default)]
+pub struct JarvisMarch {
     /// Input points that will be wrapped in the generated hull
-    points: Vec<Vertex>,
-    program: Program,
-    /// Buffer object that stores all the points
-    points_buffer: VertexBuffer<Vertex>,
-    /// Buffer object that stores the points that form the hull
-    hull_buffer: VertexBuffer<Vertex>,
-    exec_time: Option<Duration>,
+    points: Vec<Vec2>,
+    /// Indices of the points that form the hull
+    hull: Vec<usize>,
 }
 
-impl<'f> Drawable for JarvisMarch<'f> {
-    fn draw(&self, target: &mut Frame) {
-        self.draw_points(target);
-        self.draw_hull(target);
+impl JarvisMarch {
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    fn handle_events(&mut self, window: &winit::Window, event: &winit::Event, io: &imgui::Io) {
-        if let Event::WindowEvent { event, .. } = event {
-            if let WindowEvent::MouseInput { button, state, .. } = event {
-                if !io.want_capture_mouse && // Ignore clicks when the cursor is over an ImGui window
-                    button == &winit::MouseButton::Left && state == &winit::ElementState::Pressed {
-
-                    // Add a point on click
-                    let coords = graphics::window_pos_to_normalized(io.mouse_pos.into(), window);
-                    self.add_point(coords);
-                }
-            }
-        }
+    pub fn points(&self) -> &[Vec2] {
+        &self.points
     }
-}
 
-impl<'f> Configurable for JarvisMarch<'f> {
-    fn name(&self) -> &'static str {
-        "Jarvis march"
+    /// Returns the indices of the points that form the hull.
+    pub fn hull(&self) -> &[usize] {
+        &self.hull
     }
 
-    fn configure(&mut self, ui: &imgui::Ui) {
-        ui.text(imgui::im_str!("{} vertices", self.points.len()));
-
-        if ui.button(imgui::im_str!("Clear Points"), [0.0, 0.0]) {
-            self.clear();
-        }
-
-        if let Some(exec_time) = self.exec_time {
-            ui.text(imgui::im_str!("Execution time: {} µs", exec_time.as_micros()));
-        }
+    /// Returns the area enclosed by the hull.
+    pub fn area(&self) -> f32 {
+        super::hull_area(&self.points, &self.hull)
     }
-}
-
-impl<'f> JarvisMarch<'f> {
-    pub fn new(facade: &'f dyn Facade) -> Self {
-        let vs = graphics::SHADERS._2d_vs;
-        let fs = graphics::SHADERS.basic_fs;
-        let program = Program::from_source(facade, vs, fs, None)
-                                    .expect("Could not compile shaders");
 
-        Self {
-            facade,
-            points: Vec::new(),
-            program,
-            points_buffer: VertexBuffer::empty(facade, 0).unwrap(), // Start without any point
-            hull_buffer: VertexBuffer::empty(facade, 0).unwrap(), // Same for the hull
-            exec_time: None,
-        }
+    /// Returns the length of the boundary of the hull.
+    pub fn perimeter(&self) -> f32 {
+        super::hull_perimeter(&self.points, &self.hull)
     }
 
-    fn draw_points(&self, target: &mut Frame) {
-        let indices = index::NoIndices(index::PrimitiveType::Points);
-        let uniforms = uniform! {
-            color: [ 1.0_f32, 1.0_f32, 1.0_f32 ],
-        };
-        let mut draw_params = DrawParameters::default();
-        draw_params.point_size = Some(8.0);
-        target.draw(&self.points_buffer, &indices, &self.program, &uniforms, &draw_params).expect("Draw failure");
+    /// Add an input point that will be used to compute the convex hull.
+    /// Returns false if the point was a duplicate and has been ignored.
+    pub fn add_point(&mut self, point: Vec2) -> bool {
+        if self.points.contains(&point) {
+            log::warn!("Ignoring duplicate point ({}, {})", point.x, point.y);
+            return false;
+        }
+        self.points.push(point);
+        self.hull = Self::march(self.points.iter());
+        true
     }
 
-    fn draw_hull(&self, target: &mut Frame) {
-        let indices = index::NoIndices(index::PrimitiveType::LineLoop);
-        let uniforms = uniform! {
-            color: [ 0.0, 0.8_f32, 0.0 ],
-        };
-        let draw_params = DrawParameters::default();
-        target.draw(&self.hull_buffer, &indices, &self.program, &uniforms, &draw_params).expect("Draw failure");
-
-        let indices = index::NoIndices(index::PrimitiveType::Points);
-        let uniforms = uniform! {
-            color: [ 0.0, 0.0, 0.8_f32 ],
-        };
-        target.draw(&self.hull_buffer, &indices, &self.program, &uniforms, &draw_params).expect("Draw failure");
+    /// Replaces the input points and computes their hull.
+    pub fn set_points(&mut self, points: Vec<Vec2>) {
+        self.points = points;
+        self.hull = Self::march(self.points.iter());
     }
 
-    /// Add an input point that will be used to compute the convex hull.
-    pub fn add_point(&mut self, point: Vec2) {
-        self.points.push(Vertex::new(point));
-        self.points_buffer = VertexBuffer::new(self.facade, &self.points).unwrap(); // Regenerate the buffer
-
-        let input = self.points.iter().map(|p| &p.position); // Prepare input for the march algorithm
-        let start_time = Instant::now();
-        let hull = Self::march(input)
-                            .into_iter()
-                            .map(|idx| self.points[idx])
-                            .collect::<Vec<_>>();
-        self.exec_time = Some(Instant::now() - start_time);
-        self.hull_buffer = VertexBuffer::new(self.facade, &hull).unwrap(); // Regenerate the hull buffer from result
+    /// Replaces the input points with the vertices of the primitives and computes their hull.
+    pub fn set_primitives(&mut self, primitives: &[Primitive]) {
+        self.set_points(primitive_vertices(primitives));
     }
 
+    #[cfg(feature = "std")]
     pub fn random_points(&mut self, n: usize) {
         let x_min = -0.8; // 0.8 to prevent getting too close to the edges of the window
         let x_max = -x_min;
@@ -149,8 +79,7 @@ impl<'f> JarvisMarch<'f> {
     /// Removes all the points.
     pub fn clear(&mut self) {
         self.points.clear();
-        self.points_buffer = VertexBuffer::empty(self.facade, 0).unwrap();
-        self.hull_buffer = VertexBuffer::empty(self.facade, 0).unwrap();
+        self.hull.clear();
     }
 
     /// Find the point that is the furthest on the left.
@@ -166,7 +95,7 @@ impl<'f> JarvisMarch<'f> {
             .scan((0, first), |leftmost, p| {
                 let pos = p.1;
                 let lpos = leftmost.1;
-                if pos.x < lpos.x || (math::cmp_f32(pos.x, lpos.x) && pos.y < lpos.y) {
+                if pos.x < lpos.x || (pos.x == lpos.x && pos.y < lpos.y) {
                     *leftmost = p;
                 }
                 Some(*leftmost)
@@ -176,38 +105,137 @@ impl<'f> JarvisMarch<'f> {
     }
 
     /// Returns a `Vec` of the indices of the points in the
-    /// specified iterator `points` that form the convex hull.
+    /// specified iterator `points` that form the convex hull, counter-clockwise.
     pub fn march<'a, I>(points: I) -> Vec<usize>
     where I: ExactSizeIterator<Item = &'a Vec2> + Clone {
         let mut hull = Vec::new();
 
-        if points.len() < 2 {
+        if points.len() == 0 {
             return hull;
         }
+        let _span = span!("Jarvis march");
 
         let leftmost = Self::leftmost_point(points.clone());
         let mut hull_point = leftmost; // Start with the leftmost point
-        let mut peekable = points.clone().peekable();
+        let mut on_hull = vec![false; points.len()];
 
         loop {
             hull.push(hull_point.0);
-            let mut best = (0, *peekable.peek().unwrap()); // The current best point candidate for the hull
-
-            for checked in points.clone().enumerate() {
-                let hullpoint_to_checked = checked.1 - hull_point.1;
-                let hullpoint_to_best = best.1 - hull_point.1;
-                let angle = hullpoint_to_checked.signed_angle(hullpoint_to_best);
-                if best.0 == hull_point.0 || angle < 0.0 {
-                    best = checked; // We found a better candidate
-                }
-            }
-            hull_point = best; // Add the point we found to the hull
+            on_hull[hull_point.0] = true;
+            hull_point = Self::next_hull_point(points.clone(), hull_point); // Add the point we find to the hull
 
-            if hull_point.0 == leftmost.0 { // Wrapped around all points, we're done
+            // Wrapped around all points, we're done. Rounding errors could make the march cycle
+            // without coming back to the leftmost point, so stop at any point already on the hull
+            if on_hull[hull_point.0] {
                 break;
             }
         }
 
         hull
     }
+
+    /// Returns the point that follows `hull_point` on the hull, all the other points being on its left.
+    /// Of several candidates on the same line, the farthest one is returned, so that the middle points are skipped.
+    /// Returns `hull_point` if all the points are at its position.
+    fn next_hull_point<'a, I>(points: I, hull_point: (usize, &'a Vec2)) -> (usize, &'a Vec2)
+    where I: ExactSizeIterator<Item = &'a Vec2> + Clone {
+        let mut best: Option<(usize, &Vec2)> = None; // The current best point candidate for the hull
+
+        for checked in points.enumerate() {
+            if checked.1 == hull_point.1 {
+                continue; // The hull point itself or one of its duplicates
+            }
+            let hullpoint_to_checked = checked.1 - hull_point.1;
+            let better = match best {
+                Some(best) => {
+                    let hullpoint_to_best = best.1 - hull_point.1;
+                    let cross = hullpoint_to_best.x * hullpoint_to_checked.y - hullpoint_to_best.y * hullpoint_to_checked.x;
+                    cross < 0.0 || cross == 0.0 && hullpoint_to_checked.sqr_length() > hullpoint_to_best.sqr_length()
+                },
+                None => true,
+            };
+            if better {
+                best = Some(checked); // We found a better candidate
+            }
+        }
+        best.unwrap_or(hull_point)
+    }
+}
+
+impl<'a> Algorithm<'a> for JarvisMarch {
+    type Steps = JarvisMarchSteps<'a>;
+
+    fn run(points: &'a [Vec2]) -> Self::Steps {
+        JarvisMarchSteps::new(points)
+    }
+}
+
+/// Steps of the Jarvis march, each one adds a point to the hull.
+pub struct JarvisMarchSteps<'a> {
+    points: &'a [Vec2],
+    hull: Vec<usize>,
+    on_hull: Vec<bool>,
+    /// The point that the next step adds to the hull, `None` once the march wrapped around
+    next: Option<(usize, &'a Vec2)>,
+}
+
+impl<'a> JarvisMarchSteps<'a> {
+    pub fn new(points: &'a [Vec2]) -> Self {
+        let next = if points.is_empty() {
+            None
+        } else {
+            Some(JarvisMarch::leftmost_point(points.iter()))
+        };
+
+        Self {
+            points,
+            hull: Vec::new(),
+            on_hull: vec![false; points.len()],
+            next,
+        }
+    }
+
+    /// Returns the indices of the points added to the hull so far.
+    pub fn hull(&self) -> &[usize] {
+        &self.hull
+    }
+}
+
+impl<'a> Iterator for JarvisMarchSteps<'a> {
+    type Item = Step;
+
+    fn next(&mut self) -> Option<Step> {
+        let hull_point = self.next?;
+        self.hull.push(hull_point.0);
+        self.on_hull[hull_point.0] = true;
+
+        let next = JarvisMarch::next_hull_point(self.points.iter(), hull_point);
+        self.next = if self.on_hull[next.0] {
+            None
+        } else {
+            Some(next)
+        };
+        Some(Step::HullPush(hull_point.0))
+    }
+}
+
+impl<'a> Record for JarvisMarchSteps<'a> {
+    /// The candidate edge goes from the last point of the hull to the point that the next step adds.
+    fn snapshot(&self, step: Option<Step>) -> Snapshot {
+        let mut hull = self.hull.clone();
+        let candidate = match (self.hull.last(), self.next) {
+            (Some(&last), Some((next, _))) => Some((last, next)),
+            _ => None,
+        };
+        if self.next.is_none() && !hull.is_empty() {
+            hull.push(hull[0]);
+        }
+
+        Snapshot {
+            step,
+            hull,
+            candidate,
+            ..Snapshot::default()
+        }
+    }
 }
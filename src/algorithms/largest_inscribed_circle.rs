@@ -0,0 +1,130 @@
+use crate::math::{ Vec2, Circle, polygon };
+
+use alloc::collections::BinaryHeap;
+use core::cmp::Ordering;
+
+/// Square cell of the quadtree refinement, ordered by the largest distance to the boundary it may contain.
+#[derive(Copy, Clone)]
+struct Cell {
+    center: Vec2,
+    /// Half of the side of the cell
+    half: f32,
+    /// Signed distance from the center to the boundary of the polygon
+    distance: f32,
+    /// No point of the cell is farther from the boundary than this
+    max_distance: f32,
+}
+
+impl Cell {
+    fn new<F: Fn(Vec2) -> f32>(center: Vec2, half: f32, distance: &F) -> Self {
+        let distance = distance(center);
+        Self {
+            center,
+            half,
+            distance,
+            max_distance: distance + half * core::f32::consts::SQRT_2,
+        }
+    }
+}
+
+impl PartialEq for Cell {
+    fn eq(&self, other: &Self) -> bool {
+        self.max_distance == other.max_distance
+    }
+}
+
+impl Eq for Cell {}
+
+impl PartialOrd for Cell {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Cell {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.max_distance.partial_cmp(&other.max_distance).unwrap_or(Ordering::Equal)
+    }
+}
+
+/// Result of [`largest_inscribed_circle`](fn.largest_inscribed_circle.html).
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct InscribedCircle {
+    /// Centered on the pole of inaccessibility, the point of the polygon farthest from its boundary
+    pub circle: Circle,
+    /// Number of cells whose distance to the boundary was computed
+    pub probes: usize,
+}
+
+/// Finds the largest circle inside the simple polygon `polygon` by quadtree refinement (the polylabel algorithm):
+/// the cells that may contain a point farther from the boundary than the best one found so far are split
+/// into 4, the most promising first, until none can improve the radius by more than `precision`.
+/// Returns `None` if the polygon has less than 3 vertices.
+pub fn largest_inscribed_circle(polygon: &[Vec2], precision: f32) -> Option<InscribedCircle> {
+    let _span = span!("Largest inscribed circle");
+    largest_empty_circle(polygon, &[], precision)
+}
+
+/// Finds the largest circle inside the simple polygon `polygon` that does not overlap `circles`,
+/// with the same refinement as [`largest_inscribed_circle`](fn.largest_inscribed_circle.html):
+/// the distance to the boundary is replaced by the distance to the boundary or to the closest circle.
+/// The radius is 0 if the circles leave no room. Returns `None` if the polygon has less than 3 vertices.
+pub fn largest_empty_circle(polygon: &[Vec2], circles: &[Circle], precision: f32) -> Option<InscribedCircle> {
+    if polygon.len() < 3 {
+        return None;
+    }
+    let rect = polygon::bounding_rect(polygon)?;
+    let (width, height) = (rect.width(), rect.height());
+    let size = width.min(height);
+    if size <= 0.0 {
+        return Some(InscribedCircle { circle: Circle::new(polygon[0], 0.0), probes: 0 });
+    }
+
+    // Like the distance to the boundary, the distance to the closest circle changes at most as fast as the position,
+    // so the bound of the cells holds for their minimum
+    let distance = |p: Vec2| {
+        circles.iter()
+            .map(|circle| (&p - &circle.center).length() - circle.radius)
+            .fold(polygon::signed_distance(polygon, p), f32::min)
+    };
+
+    // Cover the bounding rectangle with square cells
+    let half = size / 2.0;
+    let mut queue = BinaryHeap::new();
+    let mut x = rect.min.x;
+    while x < rect.max.x {
+        let mut y = rect.min.y;
+        while y < rect.max.y {
+            queue.push(Cell::new(Vec2::new(x + half, y + half), half, &distance));
+            y += size;
+        }
+        x += size;
+    }
+    let mut probes = queue.len();
+
+    // The centroid is a good first guess for most polygons
+    let mut best = Cell::new(polygon::centroid(polygon)?, 0.0, &distance);
+    probes += 1;
+
+    while let Some(cell) = queue.pop() {
+        if cell.distance > best.distance {
+            best = cell;
+        }
+        if cell.max_distance - best.distance <= precision {
+            // The queue is sorted, no other cell can improve the result
+            break;
+        }
+
+        let half = cell.half / 2.0;
+        for &(dx, dy) in &[ (-1.0, -1.0), (1.0, -1.0), (-1.0, 1.0), (1.0, 1.0) ] {
+            let center = Vec2::new(cell.center.x + dx * half, cell.center.y + dy * half);
+            queue.push(Cell::new(center, half, &distance));
+            probes += 1;
+        }
+    }
+
+    Some(InscribedCircle {
+        circle: Circle::new(best.center, best.distance.max(0.0)),
+        probes,
+    })
+}
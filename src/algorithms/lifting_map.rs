@@ -0,0 +1,97 @@
+//! Lifting map of 2D points onto the paraboloid z = x² + y².
+//! A circle lifts to a plane section of the paraboloid: the points inside the circle lift below the plane,
+//! those outside of it above. A triangle has an empty circumcircle when no lifted point is below the plane
+//! of its lifted vertices, so the lower facets of the 3D hull of the lifted points project onto the Delaunay triangulation.
+
+use crate::math::{ Vec2, XorShift };
+use super::ConvexHullNd;
+
+use alloc::{ vec, vec::Vec, collections::BTreeSet };
+
+/// Relative tolerance under which a facet of the lifted hull is vertical, its projection is flat
+const TOLERANCE: f64 = 1e-9;
+/// Largest offset of the lifted points, relative to the extent of the points squared.
+/// Above the rounding of single precision coordinates, and small enough to only reorder the points that are nearly cocircular
+const PERTURBATION: f64 = 1e-6;
+
+/// Returns the height of the point lifted onto the paraboloid z = x² + y².
+pub fn paraboloid_height(p: Vec2) -> f32 {
+    p.sqr_length()
+}
+
+/// Returns the triangles of the lower hull of the points lifted onto the paraboloid, 3 indices into `points` each,
+/// counterclockwise in the plane. Cocircular points, up to a small tolerance, lift to a flat face split into triangles in any way,
+/// so the triangles of nearly cocircular points can slightly break the Delaunay criterion.
+/// Returns nothing if there are less than 3 points or if they are collinear.
+pub fn lower_hull(points: &[Vec2]) -> Vec<usize> {
+    let _span = span!("Lifted lower hull");
+    if points.len() < 3 {
+        return Vec::new();
+    }
+    if points.len() == 3 {
+        // 3 lifted points are always in a plane, the hull has no interior
+        return match Vec2::orientation(points[0], points[1], points[2]) {
+            area if area > 0.0 => vec![ 0, 1, 2 ],
+            area if area < 0.0 => vec![ 0, 2, 1 ],
+            _ => Vec::new(),
+        };
+    }
+
+    // Centered and scaled so that the lifted coordinate has the same range as the others
+    let (mut min, mut max) = ([ f64::INFINITY; 2 ], [ f64::NEG_INFINITY; 2 ]);
+    for p in points {
+        for (axis, &x) in [ p.x, p.y ].iter().enumerate() {
+            min[axis] = min[axis].min(x as f64);
+            max[axis] = max[axis].max(x as f64);
+        }
+    }
+    let center = [ (min[0] + max[0]) / 2.0, (min[1] + max[1]) / 2.0 ];
+    let extent = (max[0] - min[0]).max(max[1] - min[1]);
+    if extent == 0.0 {
+        return Vec::new();
+    }
+    // Cocircular points lift into a plane, where the rounding of their coordinates makes the hull inconsistent.
+    // A small pseudo-random offset of the lifted coordinate breaks the ties, so that any of their triangulations is picked
+    let mut random = XorShift::default();
+    let lifted = points
+        .iter()
+        .map(|p| {
+            let perturbation = PERTURBATION * random.next_f64();
+            let (x, y) = ((p.x as f64 - center[0]) / extent, (p.y as f64 - center[1]) / extent);
+            vec![ x, y, x * x + y * y + perturbation ]
+        })
+        .collect::<Vec<_>>();
+
+    let hull = match ConvexHullNd::new(&lifted) {
+        Some(hull) => hull,
+        None => return Vec::new(),
+    };
+    hull.facets()
+        .iter()
+        .filter(|facet| {
+            // Compared squared, f64 has no square root without the standard library
+            let sqr_length = facet.normal.iter().map(|x| x * x).sum::<f64>();
+            facet.normal[2] < 0.0 && facet.normal[2] * facet.normal[2] > TOLERANCE * TOLERANCE * sqr_length
+        })
+        .flat_map(|facet| {
+            let (a, b, c) = (facet.vertices[0], facet.vertices[1], facet.vertices[2]);
+            if Vec2::orientation(points[a], points[b], points[c]) < 0.0 {
+                vec![ a, c, b ]
+            } else {
+                vec![ a, b, c ]
+            }
+        })
+        .collect()
+}
+
+/// Returns the number of triangles of `a` that are also in `b`, whatever the order of their vertices.
+/// Both contain 3 indices per triangle.
+pub fn shared_triangles(a: &[usize], b: &[usize]) -> usize {
+    let sorted = |tri: &[usize]| {
+        let mut tri = [ tri[0], tri[1], tri[2] ];
+        tri.sort_unstable();
+        tri
+    };
+    let b = b.chunks(3).map(sorted).collect::<BTreeSet<_>>();
+    a.chunks(3).filter(|tri| b.contains(&sorted(tri))).count()
+}
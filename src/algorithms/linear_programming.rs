@@ -0,0 +1,130 @@
+//! Linear programming in the plane with Seidel's randomized incremental algorithm.
+//! The constraints are added in a random order while keeping the optimum of those added so far:
+//! it only changes when the new constraint excludes it, and then lies on the boundary of that constraint,
+//! where it is the solution of a 1D program. This happens with probability 2 / i for the i-th constraint,
+//! so the expected running time is linear.
+
+use crate::math::{ Vec2, Rect, HalfPlane, XorShift };
+
+use alloc::vec::Vec;
+
+/// Half-size of the box that bounds the programs, the optimum is unbounded if it reaches it
+const BOUND: f64 = 1e9;
+/// Tolerance of the tests against the constraints, whose normals are scaled to a largest coordinate of 1
+const EPSILON: f64 = 1e-9;
+
+/// Constraint `a x + b y <= c` in double precision, scaled so that max(|a|, |b|) = 1
+#[derive(Copy, Clone, Debug)]
+struct Constraint {
+    a: f64,
+    b: f64,
+    c: f64,
+}
+
+impl Constraint {
+    /// Returns `None` for a constraint with a zero normal, which is either always or never satisfied
+    fn new(half_plane: &HalfPlane) -> Option<Self> {
+        let (a, b, c) = (f64::from(half_plane.normal.x), f64::from(half_plane.normal.y), f64::from(half_plane.offset));
+        let scale = a.abs().max(b.abs());
+        if scale == 0.0 {
+            return None;
+        }
+        Some(Self {
+            a: a / scale,
+            b: b / scale,
+            c: c / scale,
+        })
+    }
+
+    fn violated_by(&self, (x, y): (f64, f64)) -> bool {
+        self.a * x + self.b * y > self.c + EPSILON
+    }
+}
+
+/// Returns the point of the intersection of the half-planes that maximizes `objective · p`.
+/// Returns `None` if the intersection is empty, or if the objective grows without bound in it.
+/// With a zero objective, returns a point of the intersection.
+pub fn lp2d(constraints: &[HalfPlane], objective: Vec2) -> Option<Vec2> {
+    let mut order = Vec::with_capacity(constraints.len());
+    for half_plane in constraints {
+        match Constraint::new(half_plane) {
+            Some(constraint) => order.push(constraint),
+            // 0 <= offset
+            None if half_plane.offset < 0.0 => return None,
+            None => {},
+        }
+    }
+    // A fixed shuffle avoids the bad orders of sorted inputs and keeps the results reproducible
+    XorShift::default().shuffle(&mut order);
+
+    // The box makes every program bounded, its sides are the first constraints
+    let objective = (f64::from(objective.x), f64::from(objective.y));
+    let mut all = Vec::with_capacity(order.len() + 4);
+    all.extend_from_slice(&[
+        Constraint { a: 1.0, b: 0.0, c: BOUND },
+        Constraint { a: -1.0, b: 0.0, c: BOUND },
+        Constraint { a: 0.0, b: 1.0, c: BOUND },
+        Constraint { a: 0.0, b: -1.0, c: BOUND },
+    ]);
+    let corner = |x: f64| if x > 0.0 { BOUND } else if x < 0.0 { -BOUND } else { 0.0 };
+    let mut optimum = (corner(objective.0), corner(objective.1));
+
+    for constraint in order {
+        if constraint.violated_by(optimum) {
+            optimum = lp1d(&constraint, &all, objective)?;
+        }
+        all.push(constraint);
+    }
+
+    // The optimum is on the box only when nothing else stops the objective
+    let unbounded = (objective.0 != 0.0 || objective.1 != 0.0) &&
+                    optimum.0.abs().max(optimum.1.abs()) >= BOUND * (1.0 - EPSILON);
+    if unbounded {
+        return None;
+    }
+    Some(Vec2::new(optimum.0 as f32, optimum.1 as f32))
+}
+
+/// Returns the polygon of the intersection of the half-planes inside `bounds`, empty if they do not meet.
+pub fn feasible_region(constraints: &[HalfPlane], bounds: &Rect) -> Vec<Vec2> {
+    constraints.iter().fold(bounds.to_polygon(), |region, half_plane| half_plane.clip(&region))
+}
+
+/// Maximizes the objective on the boundary line of `line` under the constraints,
+/// returns `None` if they leave nothing of it.
+fn lp1d(line: &Constraint, constraints: &[Constraint], objective: (f64, f64)) -> Option<(f64, f64)> {
+    // The points of the line are origin + t * direction
+    let length = line.a * line.a + line.b * line.b;
+    let origin = (line.a * line.c / length, line.b * line.c / length);
+    let direction = (-line.b, line.a);
+
+    let (mut t_min, mut t_max) = (f64::NEG_INFINITY, f64::INFINITY);
+    for constraint in constraints {
+        let slope = constraint.a * direction.0 + constraint.b * direction.1;
+        let room = constraint.c - (constraint.a * origin.0 + constraint.b * origin.1);
+        if slope.abs() <= EPSILON {
+            // Parallel lines, the constraint keeps all of the line or none of it
+            if room < -EPSILON {
+                return None;
+            }
+        } else if slope > 0.0 {
+            t_max = t_max.min(room / slope);
+        } else {
+            t_min = t_min.max(room / slope);
+        }
+    }
+    if t_min > t_max + EPSILON {
+        return None;
+    }
+
+    let gain = objective.0 * direction.0 + objective.1 * direction.1;
+    let t = if gain > 0.0 {
+        t_max
+    } else if gain < 0.0 {
+        t_min
+    } else {
+        // Every point of the segment is optimal, keep the one closest to the origin
+        0.0f64.max(t_min).min(t_max)
+    };
+    Some((origin.0 + t * direction.0, origin.1 + t * direction.1))
+}
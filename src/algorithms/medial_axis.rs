@@ -0,0 +1,90 @@
+//! Approximation of the medial axis of a polygon, the centers of the circles inside it that touch its boundary
+//! at two points or more. The boundary is sampled, and the Voronoi diagram of the samples is built from the circumcenters
+//! of their Delaunay triangulation: as the samples get denser, the Voronoi edges inside the polygon that separate samples
+//! from distant parts of the boundary converge to the medial axis. The edges between consecutive samples cut across
+//! the polygon towards its boundary, they are filtered out with the edges that leave the polygon.
+
+use crate::{
+    algorithms::{ Incremental2dTriangulation, VoronoiEdge },
+    math::{ Vec2, Circle, polygon },
+};
+
+use alloc::{ vec::Vec, collections::BTreeMap };
+
+use core::cmp::Ordering;
+
+/// Voronoi edges of the boundary samples of a polygon that approximate its medial axis.
+#[derive(Clone, Debug)]
+pub struct MedialAxis {
+    /// Points of the boundary, in its order from its first vertex, and including all of its vertices
+    pub samples: Vec<Vec2>,
+    /// Segments of the axis, their sites are indices into `samples`
+    pub edges: Vec<VoronoiEdge>,
+}
+
+impl MedialAxis {
+    /// Samples the boundary of the simple polygon at most `spacing` apart, and keeps the Voronoi edges of the samples
+    /// that are inside the polygon and separate samples that are not consecutive on the boundary.
+    /// Returns `None` if the polygon has less than 3 vertices or `spacing` is not positive.
+    pub fn new(polygon: &[Vec2], spacing: f32) -> Option<Self> {
+        let _span = span!("Medial axis");
+        if polygon.len() < 3 || spacing <= 0.0 {
+            return None;
+        }
+        let samples = sample_boundary(polygon, spacing);
+        let n = samples.len();
+
+        let indices = Incremental2dTriangulation::delaunay(&samples);
+
+        // Voronoi vertices, and the triangles on both sides of each Delaunay edge
+        let centers = indices
+            .chunks(3)
+            .map(|t| Circle::circumscribed(samples[t[0]], samples[t[1]], samples[t[2]]).map(|circle| circle.center))
+            .collect::<Vec<_>>();
+        let mut triangles_of_edge = BTreeMap::new();
+        for (triangle, t) in indices.chunks(3).enumerate() {
+            for &(a, b) in &[ (t[0], t[1]), (t[1], t[2]), (t[2], t[0]) ] {
+                triangles_of_edge.entry((a.min(b), a.max(b))).or_insert_with(Vec::new).push(triangle);
+            }
+        }
+
+        let consecutive = |a: usize, b: usize| b - a == 1 || a == 0 && b == n - 1;
+        let edges = triangles_of_edge
+            .into_iter()
+            .filter(|&((a, b), _)| !consecutive(a, b))
+            .filter_map(|(sites, triangles)| match triangles[..] {
+                [ first, second ] => Some((sites, centers[first]?, centers[second]?)),
+                _ => None,
+            })
+            .filter(|&(_, start, end)| start != end && polygon::contains(polygon, start) && polygon::contains(polygon, end))
+            .map(|(sites, start, end)| VoronoiEdge::segment(sites, start, end))
+            .collect();
+        Some(Self { samples, edges })
+    }
+
+    /// Returns the radius of the largest circle inside the polygon centered on `p`, a point of the axis,
+    /// approximated by the distance to the closest sample.
+    pub fn radius(&self, p: Vec2) -> f32 {
+        self.samples.iter().map(|s| (s - &p).length()).fold(f32::INFINITY, f32::min)
+    }
+}
+
+/// Returns the vertices of the polygon and points evenly spaced on each edge, at most `spacing` apart.
+/// Repeated vertices, or a boundary that goes through the same point twice, would give coincident samples:
+/// only the first of them is kept.
+fn sample_boundary(polygon: &[Vec2], spacing: f32) -> Vec<Vec2> {
+    let mut samples = Vec::new();
+    for (a, b) in polygon::edges(polygon) {
+        let ab = &b - &a;
+        let parts = (ab.length() / spacing) as usize + 1;
+        samples.extend((0..parts).map(|i| &a + &(&ab * (i as f32 / parts as f32))));
+    }
+
+    let cmp = |a: f32, b: f32| a.partial_cmp(&b).unwrap_or(Ordering::Equal);
+    let mut order = (0..samples.len()).collect::<Vec<_>>();
+    order.sort_by(|&i, &j| cmp(samples[i].x, samples[j].x).then(cmp(samples[i].y, samples[j].y)).then(i.cmp(&j)));
+    order.dedup_by(|&mut j, &mut i| samples[i] == samples[j]);
+    // Back to the order of the boundary
+    order.sort_unstable();
+    order.into_iter().map(|i| samples[i]).collect()
+}
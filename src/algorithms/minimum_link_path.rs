@@ -0,0 +1,275 @@
+//! Minimum-link paths inside a simple polygon: the paths with the fewest segments, or turns, between two points.
+//! They follow the window partition of the polygon (Suri): the points seen from the source are reached with 1 link,
+//! the edges of this region that cross the polygon are its windows, and the points of the pockets behind them
+//! that see a window are reached with 2 links, and so on.
+
+use crate::math::{ Vec2, Segment2, Ray2, polygon };
+
+use alloc::{ vec, vec::Vec, collections::VecDeque };
+
+/// Region of the [`WindowPartition`](struct.WindowPartition.html): the points of its pocket
+/// that are reached with `links` segments from the source, and not less.
+#[derive(Clone, Debug)]
+pub struct Region {
+    /// Part of the polygon behind the window, counter-clockwise, whose last edge is the window.
+    /// The first region covers the whole polygon.
+    pub pocket: Vec<Vec2>,
+    /// Chord of the polygon between this region and its parent, `None` for the first region
+    pub window: Option<Segment2>,
+    /// Index of the region seen through the window
+    pub parent: Option<usize>,
+    /// Number of segments of the minimum-link paths from the source to the points of the region
+    pub links: usize,
+    /// Point of the window of the parent (or the source) on the line of the window,
+    /// that sees all of it past the reflex vertex where it starts
+    anchor: Vec2,
+}
+
+/// Partition of a simple polygon in regions of constant link distance from a source point.
+#[derive(Clone, Debug)]
+pub struct WindowPartition {
+    pub source: Vec2,
+    /// The first region is the visibility polygon of the source, the parents come before their children
+    pub regions: Vec<Region>,
+}
+
+/// Where the points of a region are seen from.
+#[derive(Copy, Clone)]
+enum Source {
+    Point(Vec2),
+    /// The last edge of the pocket, from its last vertex to its first one
+    Window,
+}
+
+/// Returns the window partition of `polygon` from `source`, or `None` if the polygon has less than 3 vertices
+/// or the source is outside of it. The polygon can be clockwise or counter-clockwise.
+pub fn window_partition(polygon: &[Vec2], source: Vec2) -> Option<WindowPartition> {
+    let _span = span!("Window partition");
+    if polygon.len() < 3 || !polygon::contains(polygon, source) {
+        return None;
+    }
+    let mut pocket = polygon.to_vec();
+    if polygon::signed_area(&pocket) < 0.0 {
+        pocket.reverse();
+    }
+
+    let mut regions = vec![
+        Region { pocket, window: None, parent: None, links: 1, anchor: source },
+    ];
+    let mut queue = VecDeque::new();
+    queue.push_back(0);
+    while let Some(idx) = queue.pop_front() {
+        // Every region has less links than the polygon has vertices, this only guards against degenerate pockets
+        if regions[idx].links > polygon.len() {
+            continue;
+        }
+        let source = match regions[idx].window {
+            Some(_) => Source::Window,
+            None => Source::Point(source),
+        };
+        for (window, anchor, pocket) in windows(&regions[idx].pocket, source) {
+            queue.push_back(regions.len());
+            let links = regions[idx].links + 1;
+            regions.push(Region { pocket, window: Some(window), parent: Some(idx), links, anchor });
+        }
+    }
+    Some(WindowPartition { source, regions })
+}
+
+/// Returns a minimum-link path from `start` to `end` inside the simple polygon `polygon`,
+/// or `None` if one of them is outside of it.
+pub fn minimum_link_path(polygon: &[Vec2], start: Vec2, end: Vec2) -> Option<Vec<Vec2>> {
+    window_partition(polygon, start)?.path_to(end)
+}
+
+impl WindowPartition {
+    /// Returns the index of the region that contains `p`, or `None` if it is outside of the polygon.
+    pub fn region(&self, p: Vec2) -> Option<usize> {
+        if !polygon::contains(&self.regions[0].pocket, p) {
+            return None;
+        }
+        // The pockets of the children of a region are disjoint
+        let mut idx = 0;
+        while let Some(child) = (idx + 1..self.regions.len()).find(|&child| {
+            self.regions[child].parent == Some(idx) && polygon::contains(&self.regions[child].pocket, p)
+        }) {
+            idx = child;
+        }
+        Some(idx)
+    }
+
+    /// Returns the number of segments of the minimum-link paths from the source to `p`.
+    pub fn link_distance(&self, p: Vec2) -> Option<usize> {
+        self.region(p).map(|idx| self.regions[idx].links)
+    }
+
+    /// Returns a minimum-link path from the source to `target`, or `None` if it is outside of the polygon.
+    /// The path goes back from the target: its last segment reaches the window of the region of the target,
+    /// the previous ones follow the lines of the windows to their anchors, down to the source.
+    /// They may touch the reflex vertices where the windows start.
+    pub fn path_to(&self, target: Vec2) -> Option<Vec<Vec2>> {
+        let mut idx = self.region(target)?;
+        let mut path = vec![ target ];
+        if self.regions[idx].window.is_some() {
+            // The middle of the longest part of the window that the target sees
+            let pocket = &self.regions[idx].pocket;
+            let (u0, u1) = visible_intervals(pocket, target)
+                .into_iter()
+                .max_by(|a, b| (a.1 - a.0).total_cmp(&(b.1 - b.0)))?;
+            path.push(window_point(pocket, (u0 + u1) / 2.0));
+        }
+        while let Some(parent) = self.regions[idx].parent {
+            path.push(self.regions[idx].anchor);
+            idx = parent;
+        }
+        if path.len() == 1 {
+            path.push(self.source);
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Returns the windows of the region of `pocket` seen from `source`, each with its anchor and the pocket behind it.
+/// A window starts at a reflex vertex, on the line from the last point of the source that sees the vertex,
+/// and ends where this line leaves the pocket.
+fn windows(pocket: &[Vec2], source: Source) -> Vec<(Segment2, Vec2, Vec<Vec2>)> {
+    let n = pocket.len();
+    let mut windows = Vec::new();
+    for i in 0..n {
+        let (prev, r, next) = (pocket[(i + n - 1) % n], pocket[i], pocket[(i + 1) % n]);
+        if Vec2::orientation(prev, r, next) >= 0.0 {
+            continue;
+        }
+        let (first, last) = match visible_range(pocket, source, i) {
+            Some(range) => range,
+            None => continue,
+        };
+
+        // The part of the pocket hidden behind the vertex is next to the outgoing edge if the source is
+        // on the outer side of its line, or next to the incoming edge if it is on the outer side of this one.
+        // The window follows the line from the source point that sees the farthest behind the vertex.
+        let outgoing = Vec2::orientation(r, next, first) < 0.0 && Vec2::orientation(r, next, last) < 0.0;
+        let incoming = Vec2::orientation(prev, r, first) < 0.0 && Vec2::orientation(prev, r, last) < 0.0;
+        let ccw = Vec2::orientation(r, first, last) > 0.0;
+        let anchor = match (outgoing, incoming) {
+            (true, _) => if ccw { first } else { last },
+            (_, true) => if ccw { last } else { first },
+            _ => continue,
+        };
+        let direction = &r - &anchor;
+
+        // The window ends on the closest edge that the ray hits, the edges around the vertex excepted
+        let ray = Ray2::new(r, direction);
+        let hit = (0..n)
+            .filter(|&j| j != i && (j + 1) % n != i)
+            .filter_map(|j| {
+                ray.intersect_segment(&Segment2::new(pocket[j], pocket[(j + 1) % n]))
+                    .filter(|&t| t > 0.0)
+                    .map(|t| (t, j))
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0));
+        let (t, j) = match hit {
+            Some(hit) => hit,
+            None => continue,
+        };
+        let end = ray.at(t);
+
+        // The window is the last edge of the pocket behind it
+        let mut behind = Vec::new();
+        if outgoing {
+            let mut k = i;
+            while k != j {
+                behind.push(pocket[k]);
+                k = (k + 1) % n;
+            }
+            behind.push(pocket[j]);
+            behind.push(end);
+        } else {
+            behind.push(end);
+            let mut k = (j + 1) % n;
+            while k != i {
+                behind.push(pocket[k]);
+                k = (k + 1) % n;
+            }
+            behind.push(r);
+        }
+        behind.dedup();
+        if behind.len() >= 3 {
+            windows.push((Segment2::new(r, end), anchor, behind));
+        }
+    }
+    windows
+}
+
+/// Returns the first and the last points of the source that see the vertex `i` of the pocket.
+fn visible_range(pocket: &[Vec2], source: Source, i: usize) -> Option<(Vec2, Vec2)> {
+    let n = pocket.len();
+    let r = pocket[i];
+    match source {
+        Source::Point(p) => if sees(pocket, p, r, false) { Some((p, p)) } else { None },
+        // The ends of the window see all of it, along its own line
+        Source::Window if i == 0 => Some((pocket[n - 1], pocket[n - 1])),
+        Source::Window if i == n - 1 => Some((pocket[0], pocket[0])),
+        Source::Window => {
+            let intervals = visible_intervals(pocket, r);
+            Some((window_point(pocket, intervals.first()?.0), window_point(pocket, intervals.last()?.1)))
+        },
+    }
+}
+
+/// Returns the point at the parameter `u` along the window of the pocket, from its last vertex to its first one.
+fn window_point(pocket: &[Vec2], u: f32) -> Vec2 {
+    let (a, b) = (pocket[pocket.len() - 1], pocket[0]);
+    &a + &(&(&b - &a) * u)
+}
+
+/// Returns the parameters along the window of the pocket, from its last vertex to its first one,
+/// of the ends of the parts of the window that `p` sees, in order.
+/// Visibility only changes where the line from `p` through a vertex crosses the window,
+/// one point between each pair of consecutive crossings tells whether the whole interval is seen.
+fn visible_intervals(pocket: &[Vec2], p: Vec2) -> Vec<(f32, f32)> {
+    let n = pocket.len();
+    let (a, b) = (pocket[n - 1], pocket[0]);
+    let ab = &b - &a;
+    let cross = |u: Vec2, v: Vec2| u.x * v.y - u.y * v.x;
+
+    let mut events = vec![ 0.0, 1.0 ];
+    for &w in pocket {
+        let pw = &w - &p;
+        let denominator = cross(pw, ab);
+        if denominator != 0.0 {
+            let u = cross(pw, &p - &a) / denominator;
+            if u > 0.0 && u < 1.0 {
+                events.push(u);
+            }
+        }
+    }
+    events.sort_by(|a, b| a.total_cmp(b));
+
+    let mut intervals: Vec<(f32, f32)> = Vec::new();
+    for pair in events.windows(2) {
+        let (u0, u1) = (pair[0], pair[1]);
+        if u0 == u1 || !sees(pocket, p, window_point(pocket, (u0 + u1) / 2.0), true) {
+            continue;
+        }
+        match intervals.last_mut() {
+            Some(last) if last.1 == u0 => last.1 = u1,
+            _ => intervals.push((u0, u1)),
+        }
+    }
+    intervals
+}
+
+/// Checks whether the segment from `p` to `q` stays inside the pocket, ignoring its window if `q` is on it.
+/// The segment may touch the boundary but not cross it.
+fn sees(pocket: &[Vec2], p: Vec2, q: Vec2, q_on_window: bool) -> bool {
+    let n = pocket.len();
+    let crosses = polygon::edges(pocket)
+        .take(if q_on_window { n - 1 } else { n })
+        .any(|(a, b)| {
+            Vec2::orientation(p, q, a) * Vec2::orientation(p, q, b) < 0.0 &&
+            Vec2::orientation(a, b, p) * Vec2::orientation(a, b, q) < 0.0
+        });
+    !crosses && polygon::contains(pocket, Vec2::new((p.x + q.x) / 2.0, (p.y + q.y) / 2.0))
+}
@@ -1,11 +1,137 @@
+pub mod steps;
+pub use steps::{ Algorithm, Step, Snapshot, Record };
+
 pub mod jarvis_march;
 pub mod graham_scan;
 
 pub use jarvis_march::JarvisMarch;
 pub use graham_scan::GrahamScan;
 
+pub mod convex_hull;
+pub use convex_hull::{ validate_hull, hull_positions, hull_area, hull_perimeter, primitive_vertices, HullViolation, Primitive };
+
 pub mod incremental_2d_triangulation;
 pub use incremental_2d_triangulation::Incremental2dTriangulation;
 
+pub mod boolean;
+pub use boolean::{ boolean, polygon_boolean, Operation };
+
+pub mod delaunay;
+pub use delaunay::{ validate_delaunay, repair_delaunay, update_delaunay };
+
+pub mod lifting_map;
+pub use lifting_map::{ lower_hull, paraboloid_height, shared_triangles };
+
+pub mod triangle_quality;
+pub use triangle_quality::{ triangle_qualities, histogram, TriangleQuality };
+
+pub mod refinement;
+pub use refinement::{ refine, Refinement, SizeField };
+
+pub mod smoothing;
+pub use smoothing::{ laplacian_smoothing, angle_based_smoothing };
+
+pub mod dual_graph;
+pub use dual_graph::{ locate_triangle, fill_region, DualGraph };
+
+pub mod point_location;
+pub use point_location::{ TriangleWalk, TriangleGrid, HistoryDag, Location };
+
+pub mod coloring;
+pub use coloring::{ greedy_coloring, color_triangles, color_count };
+
+pub mod general_position;
+pub use general_position::{ is_in_convex_position, has_collinear_triple, has_cocircular_quadruple, Degeneracy };
+
+pub mod voronoi;
+pub use voronoi::{ VoronoiEdge, VoronoiCell, voronoi_diagram };
+
+pub mod farthest_point_voronoi;
+pub use farthest_point_voronoi::FarthestPointVoronoi;
+
+pub mod order_k_voronoi;
+pub use order_k_voronoi::{ OrderKVoronoi, VoronoiRegion };
+
+pub mod rotating_calipers;
+pub use rotating_calipers::{ RotatingCalipers, Caliper };
+
+pub mod largest_inscribed_circle;
+pub use largest_inscribed_circle::{ largest_inscribed_circle, largest_empty_circle, InscribedCircle };
+
+pub mod minimum_link_path;
+pub use minimum_link_path::{ minimum_link_path, window_partition, WindowPartition };
+
+pub mod polygon_triangulation;
+pub use polygon_triangulation::triangulate_polygon;
+
+pub mod monotone_polygon;
+pub use monotone_polygon::{ monotone_decomposition, triangulate_monotone, triangulate_polygon_monotone, is_y_monotone };
+
+pub mod shortest_path;
+pub use shortest_path::{ shortest_path, ShortestPath };
+
+pub mod pathfinding;
+pub use pathfinding::{ a_star, theta_star, visibility_graph, OccupancyGrid, GridPath, VisibilityGraph };
+
+pub mod morphing;
+pub use morphing::Morph;
+
+pub mod offset;
+pub use offset::{ offset, OffsetCurve };
+
+pub mod medial_axis;
+pub use medial_axis::MedialAxis;
+
+pub mod distance_field;
+pub use distance_field::DistanceField;
+
+pub mod contour_lines;
+pub use contour_lines::{ contour_lines, contour_levels, ContourLine };
+pub mod contour_tracing;
+pub use contour_tracing::{ trace_contours, TracedRegion };
+
+pub mod visibility;
+pub use visibility::{ visible_points, Sight };
+
+pub mod circle_packing;
+pub use circle_packing::CirclePacking;
+
+pub mod arrangement;
+pub use arrangement::Arrangement;
+
+pub mod segment_intersections;
+pub use segment_intersections::{ bentley_ottmann, SegmentIntersection };
+
+pub mod bounding;
+pub use bounding::Bounded;
+
+pub mod convex_hull_nd;
+pub use convex_hull_nd::{ ConvexHullNd, Facet };
+
+pub mod linear_programming;
+pub use linear_programming::{ lp2d, feasible_region };
+
+pub mod icp;
+pub use icp::Icp;
+
+pub mod clustering;
+pub use clustering::{ dbscan, k_means, Clustering, ClusterStatistics };
+
+pub mod data_depth;
+pub use data_depth::{ tukey_depth, tukey_regions, ConvexLayers };
+
+// The 3D types are backed by cgmath, which needs the standard library
+#[cfg(feature = "std")]
 pub mod convex_hull_3d;
-pub use convex_hull_3d::ConvexHull;
+#[cfg(feature = "std")]
+pub use convex_hull_3d::{ ConvexHull, IncrementalHull };
+#[cfg(feature = "std")]
+pub mod delaunay_3d;
+#[cfg(feature = "std")]
+pub use delaunay_3d::{ delaunay_3d, tetrahedron_qualities, TetrahedronQuality };
+
+// Samples the initial points with rand
+#[cfg(feature = "std")]
+pub mod stippling;
+#[cfg(feature = "std")]
+pub use stippling::{ DensityMap, Stippling };
@@ -0,0 +1,330 @@
+//! Decomposition of simple polygons into y-monotone pieces, and triangulation of the pieces.
+//! A polygon is y-monotone if every horizontal line crosses it along one segment at most: its boundary goes down
+//! from the top vertex to the bottom one along a left chain, and back up along a right chain.
+//!
+//! A sweep line moving down from the top vertex finds the vertices that break the monotony: the split vertices,
+//! with both neighbours below and the interior above, and the merge vertices, with both neighbours above and the interior below.
+//! Each one gets a diagonal to the helper of the edge on its left, the lowest vertex seen between that edge and the next one,
+//! so the decomposition takes O(n log n) time. A monotone piece is then triangulated in linear time, its vertices being
+//! already sorted along its two chains.
+
+use crate::math::{ Vec2, polygon, float };
+
+use alloc::{ vec, vec::Vec };
+use core::cmp::Ordering;
+
+/// Kind of a vertex for the sweep, from the position of its neighbours and its interior angle.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum VertexKind {
+    /// Both neighbours below, convex: a new piece starts
+    Start,
+    /// Both neighbours above, convex: a piece ends
+    End,
+    /// Both neighbours below, reflex: the piece above is split in two
+    Split,
+    /// Both neighbours above, reflex: two pieces merge
+    Merge,
+    /// On the left chain, going down, or on the right chain, going up
+    Left,
+    Right,
+}
+
+/// Returns the ordering of the sweep: by decreasing y, then by increasing x, so that no two distinct points are at the same height.
+fn sweep_order(p: Vec2, q: Vec2) -> Ordering {
+    let cmp = |a: f32, b: f32| a.partial_cmp(&b).unwrap_or(Ordering::Equal);
+    cmp(q.y, p.y).then(cmp(p.x, q.x))
+}
+
+/// Returns true if `p` comes before `q` in the sweep.
+fn is_above(p: Vec2, q: Vec2) -> bool {
+    sweep_order(p, q) == Ordering::Less
+}
+
+/// Returns true if the polygon is y-monotone: in either orientation, its boundary only turns from going down to going up once.
+/// Vertices at the same height are ordered by x as in the sweep, as if the polygon were turned slightly clockwise.
+pub fn is_y_monotone(polygon: &[Vec2]) -> bool {
+    let n = polygon.len();
+    let tops = (0..n).filter(|&i| is_above(polygon[i], polygon[(i + n - 1) % n]) && is_above(polygon[i], polygon[(i + 1) % n])).count();
+    tops <= 1
+}
+
+/// Sweep state of the decomposition, over the polygon without its repeated vertices, counter-clockwise.
+struct Sweep<'a> {
+    points: &'a [Vec2],
+    /// The edge from a vertex to the next one is named after the vertex
+    helper: Vec<usize>,
+    kinds: Vec<VertexKind>,
+    /// Edges with the interior on their right, crossing the sweep line, from left to right
+    status: Vec<usize>,
+    diagonals: Vec<(usize, usize)>,
+}
+
+/// Returns the y-monotone pieces of the simple polygon, clockwise or counter-clockwise, as indices into `polygon`
+/// per counter-clockwise piece. The pieces are separated by diagonals between vertices of the polygon, and consecutive
+/// vertices at the same position count as one. The status of the sweep is a sorted vector: finding an edge is logarithmic,
+/// but inserting one moves those on its right.
+pub fn monotone_decomposition(polygon: &[Vec2]) -> Vec<Vec<usize>> {
+    let _span = span!("Monotone decomposition");
+    // Indices of the vertices counter-clockwise, without the repeated ones
+    let mut order = if polygon::signed_area(polygon) >= 0.0 {
+        (0..polygon.len()).collect::<Vec<_>>()
+    } else {
+        (0..polygon.len()).rev().collect::<Vec<_>>()
+    };
+    order.dedup_by(|&mut a, &mut b| polygon[a].x == polygon[b].x && polygon[a].y == polygon[b].y);
+    while order.len() > 1 && polygon[order[0]].x == polygon[order[order.len() - 1]].x && polygon[order[0]].y == polygon[order[order.len() - 1]].y {
+        order.pop();
+    }
+    let n = order.len();
+    if n < 3 {
+        return Vec::new();
+    }
+    let points = order.iter().map(|&idx| polygon[idx]).collect::<Vec<_>>();
+
+    let kinds = (0..n)
+        .map(|v| {
+            let (p, q) = (points[(v + n - 1) % n], points[(v + 1) % n]);
+            let convex = Vec2::orientation(p, points[v], q) > 0.0;
+            match (is_above(points[v], p), is_above(points[v], q)) {
+                (true, true) => if convex { VertexKind::Start } else { VertexKind::Split },
+                (false, false) => if convex { VertexKind::End } else { VertexKind::Merge },
+                // The boundary goes down along the left chain, counter-clockwise
+                (false, true) => VertexKind::Left,
+                (true, false) => VertexKind::Right,
+            }
+        })
+        .collect();
+    let mut sweep = Sweep {
+        points: &points,
+        helper: vec![ 0; n ],
+        kinds,
+        status: Vec::new(),
+        diagonals: Vec::new(),
+    };
+    let mut events = (0..n).collect::<Vec<_>>();
+    events.sort_by(|&a, &b| sweep_order(points[a], points[b]));
+    for v in events {
+        sweep.handle_vertex(v);
+    }
+
+    split(&points, &sweep.diagonals)
+        .into_iter()
+        .map(|piece| piece.into_iter().map(|v| order[v]).collect())
+        .collect()
+}
+
+impl<'a> Sweep<'a> {
+    /// Returns the abscissa of the edge at the height `y`, which it crosses.
+    fn x_at(&self, edge: usize, y: f32) -> f32 {
+        let (a, b) = (self.points[edge], self.points[(edge + 1) % self.points.len()]);
+        if a.y == b.y {
+            return a.x.max(b.x);
+        }
+        a.x + (y - a.y) * (b.x - a.x) / (b.y - a.y)
+    }
+
+    /// Returns the position in the status of the edge directly on the left of `p`.
+    fn left_of(&self, p: Vec2) -> Option<usize> {
+        self.status.partition_point(|&edge| self.x_at(edge, p.y) <= p.x).checked_sub(1)
+    }
+
+    fn insert(&mut self, edge: usize) {
+        let p = self.points[edge];
+        let idx = self.status.partition_point(|&other| self.x_at(other, p.y) < p.x);
+        self.status.insert(idx, edge);
+        self.helper[edge] = edge;
+    }
+
+    /// Removes the edge ending at its lower end `p`, found by its position unless the rounding moved it.
+    fn remove(&mut self, edge: usize, p: Vec2) {
+        let start = self.status.partition_point(|&other| self.x_at(other, p.y) < p.x).saturating_sub(1);
+        let idx = self.status[start..]
+                    .iter()
+                    .position(|&other| other == edge)
+                    .map(|idx| start + idx)
+                    .or_else(|| self.status.iter().position(|&other| other == edge));
+        if let Some(idx) = idx {
+            self.status.remove(idx);
+        }
+    }
+
+    /// Adds a diagonal from `v` to the helper of the edge if it is a merge vertex, whose piece below would not be monotone.
+    fn connect_merge(&mut self, v: usize, edge: usize) {
+        let helper = self.helper[edge];
+        if self.kinds[helper] == VertexKind::Merge {
+            self.diagonals.push((v, helper));
+        }
+    }
+
+    fn handle_vertex(&mut self, v: usize) {
+        let n = self.points.len();
+        let (p, prev) = (self.points[v], (v + n - 1) % n);
+        match self.kinds[v] {
+            VertexKind::Start => self.insert(v),
+            VertexKind::End => {
+                self.connect_merge(v, prev);
+                self.remove(prev, p);
+            },
+            VertexKind::Split => {
+                if let Some(idx) = self.left_of(p) {
+                    let edge = self.status[idx];
+                    self.diagonals.push((v, self.helper[edge]));
+                    self.helper[edge] = v;
+                }
+                self.insert(v);
+            },
+            VertexKind::Merge => {
+                self.connect_merge(v, prev);
+                self.remove(prev, p);
+                if let Some(idx) = self.left_of(p) {
+                    let edge = self.status[idx];
+                    self.connect_merge(v, edge);
+                    self.helper[edge] = v;
+                }
+            },
+            VertexKind::Left => {
+                self.connect_merge(v, prev);
+                self.remove(prev, p);
+                self.insert(v);
+            },
+            VertexKind::Right => {
+                if let Some(idx) = self.left_of(p) {
+                    let edge = self.status[idx];
+                    self.connect_merge(v, edge);
+                    self.helper[edge] = v;
+                }
+            },
+        }
+    }
+}
+
+/// Splits the counter-clockwise polygon along the diagonals and returns its pieces, counter-clockwise.
+/// Each piece is walked with its interior on the left, turning at each vertex onto the first edge clockwise from the one it came along.
+fn split(points: &[Vec2], diagonals: &[(usize, usize)]) -> Vec<Vec<usize>> {
+    let n = points.len();
+    let mut outgoing = (0..n).map(|v| vec![ (v + 1) % n ]).collect::<Vec<_>>();
+    for &(u, v) in diagonals {
+        outgoing[u].push(v);
+        outgoing[v].push(u);
+    }
+    let angle = |u: usize, v: usize| float::atan2(points[v].y - points[u].y, points[v].x - points[u].x);
+    for (v, edges) in outgoing.iter_mut().enumerate() {
+        edges.sort_by(|&a, &b| angle(v, a).partial_cmp(&angle(v, b)).unwrap_or(Ordering::Equal));
+    }
+
+    let mut used = outgoing.iter().map(|edges| vec![ false; edges.len() ]).collect::<Vec<_>>();
+    let mut pieces = Vec::with_capacity(diagonals.len() + 1);
+    for start in 0..n {
+        for first in 0..outgoing[start].len() {
+            if used[start][first] {
+                continue;
+            }
+            let mut piece = Vec::new();
+            let (mut u, mut i) = (start, first);
+            while !used[u][i] {
+                used[u][i] = true;
+                piece.push(u);
+                let v = outgoing[u][i];
+                let back = angle(v, u);
+                let edges = &outgoing[v];
+                let j = edges.partition_point(|&w| angle(v, w) < back);
+                let j = if j == 0 { edges.len() - 1 } else { j - 1 };
+                u = v;
+                i = j;
+            }
+            pieces.push(piece);
+        }
+    }
+    pieces
+}
+
+/// Returns the triangulation of the y-monotone piece, counter-clockwise indices into `polygon`, as 3 indices into `polygon`
+/// per counter-clockwise triangle. The vertices are visited from top to bottom, merging the two chains:
+/// a stack keeps the vertices that still need diagonals, a reflex chain on one side of the piece.
+/// A piece going through a vertex twice, which only a polygon that is not simple has, gives no triangle.
+pub fn triangulate_monotone(polygon: &[Vec2], piece: &[usize]) -> Vec<usize> {
+    let k = piece.len();
+    if k < 3 {
+        return Vec::new();
+    }
+    let top = (0..k).min_by(|&a, &b| sweep_order(polygon[piece[a]], polygon[piece[b]])).unwrap_or(0);
+    let bottom = (0..k).max_by(|&a, &b| sweep_order(polygon[piece[a]], polygon[piece[b]])).unwrap_or(0);
+
+    // Counter-clockwise from the top, the left chain goes down to the bottom and the right chain back up
+    let left = (1..k).map(|i| piece[(top + i) % k]).take_while(|&v| v != piece[bottom]).collect::<Vec<_>>();
+    let right = (1..k).map(|i| piece[(top + k - i) % k]).take_while(|&v| v != piece[bottom]).collect::<Vec<_>>();
+    if left.len() + right.len() != k - 2 {
+        // The bottom is visited twice, by a piece of a polygon that is not simple
+        return Vec::new();
+    }
+    let mut sorted = Vec::with_capacity(k);
+    sorted.push((piece[top], true));
+    let (mut l, mut r) = (0, 0);
+    while l < left.len() || r < right.len() {
+        if r == right.len() || (l < left.len() && is_above(polygon[left[l]], polygon[right[r]])) {
+            sorted.push((left[l], true));
+            l += 1;
+        } else {
+            sorted.push((right[r], false));
+            r += 1;
+        }
+    }
+
+    let mut triangles = Vec::with_capacity(3 * (k - 2));
+    // Adds the triangle of the edge `ab` of the chain, `a` above, and of `c` on its right for the left chain
+    let fan = |triangles: &mut Vec<usize>, a: usize, b: usize, c: usize, on_left: bool| {
+        if on_left {
+            triangles.extend_from_slice(&[ a, b, c ]);
+        } else {
+            triangles.extend_from_slice(&[ b, a, c ]);
+        }
+    };
+    let mut stack = vec![ sorted[0], sorted[1] ];
+    for &(v, on_left) in &sorted[2..] {
+        let (_, top_left) = stack[stack.len() - 1];
+        if on_left != top_left {
+            // The whole stack is on the other chain, each of its edges makes a triangle with v
+            for pair in stack.windows(2) {
+                fan(&mut triangles, pair[0].0, pair[1].0, v, top_left);
+            }
+            let last = stack[stack.len() - 1];
+            stack.clear();
+            stack.push(last);
+        } else {
+            // Cut off the convex vertices of the chain
+            let mut last = stack.pop().expect("Empty stack");
+            while let Some(&(s, _)) = stack.last() {
+                let convex = if on_left {
+                    Vec2::orientation(polygon[s], polygon[last.0], polygon[v]) > 0.0
+                } else {
+                    Vec2::orientation(polygon[v], polygon[last.0], polygon[s]) > 0.0
+                };
+                if !convex {
+                    break;
+                }
+                fan(&mut triangles, s, last.0, v, on_left);
+                last = stack.pop().expect("Empty stack");
+            }
+            stack.push(last);
+        }
+        stack.push((v, on_left));
+    }
+
+    // The bottom closes the chain left on the stack
+    let (_, top_left) = stack[stack.len() - 1];
+    for pair in stack.windows(2) {
+        fan(&mut triangles, pair[0].0, pair[1].0, piece[bottom], top_left);
+    }
+    triangles
+}
+
+/// Returns the triangulation of the simple polygon by its monotone pieces, in the same format as
+/// [`triangulate_polygon`](../polygon_triangulation/fn.triangulate_polygon.html), in O(n log n) time.
+/// Like it, a polygon that is not simple is only partly triangulated.
+pub fn triangulate_polygon_monotone(polygon: &[Vec2]) -> Vec<usize> {
+    let _span = span!("Monotone triangulation");
+    monotone_decomposition(polygon)
+        .iter()
+        .flat_map(|piece| triangulate_monotone(polygon, piece))
+        .collect()
+}
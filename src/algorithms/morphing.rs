@@ -0,0 +1,67 @@
+//! Morphing between two polygons. Both boundaries are resampled to the same number of points, evenly spaced
+//! by arc length, so that the points of one polygon correspond to those of the other in order.
+//! The intermediate polygons interpolate linearly between corresponding points.
+
+use crate::math::{ Vec2, polygon, polyline };
+
+use alloc::vec::Vec;
+
+/// Pair of polygons with the same number of vertices, the vertices at the same index correspond.
+#[derive(Clone, Debug)]
+pub struct Morph {
+    /// Counter-clockwise, the polygon at 0
+    pub from: Vec<Vec2>,
+    /// Counter-clockwise, the polygon at 1
+    pub to: Vec<Vec2>,
+}
+
+impl Morph {
+    /// Resamples both polygons to `samples` vertices and matches them, or returns `None` if one of the polygons
+    /// has less than 3 vertices or `samples` is less than 3. The polygons can be clockwise or counter-clockwise.
+    /// The first vertex of `to` is the sample that minimizes the squared distances between corresponding vertices,
+    /// once both polygons are centered on their centroids, so that the morph does not twist.
+    pub fn new(from: &[Vec2], to: &[Vec2], samples: usize) -> Option<Self> {
+        let _span = span!("Morph");
+        if from.len() < 3 || to.len() < 3 || samples < 3 {
+            return None;
+        }
+        let from = resample_ccw(from, samples);
+        let mut to = resample_ccw(to, samples);
+
+        let (from_center, to_center) = (polygon::centroid(&from)?, polygon::centroid(&to)?);
+        let cost = |shift: usize| -> f32 {
+            from.iter()
+                .enumerate()
+                .map(|(i, p)| {
+                    let d = &(p - &from_center) - &(&to[(i + shift) % samples] - &to_center);
+                    d.x * d.x + d.y * d.y
+                })
+                .sum()
+        };
+        let shift = (0..samples)
+            .map(|shift| (shift, cost(shift)))
+            .min_by(|a, b| a.1.total_cmp(&b.1))?
+            .0;
+        to.rotate_left(shift);
+        Some(Self { from, to })
+    }
+
+    /// Returns the polygon at `t`, `from` at 0 and `to` at 1.
+    pub fn at(&self, t: f32) -> Vec<Vec2> {
+        self.from
+            .iter()
+            .zip(&self.to)
+            .map(|(a, b)| &(a * (1.0 - t)) + &(b * t))
+            .collect()
+    }
+}
+
+/// Returns `count` points evenly spaced along the boundary of the polygon, counter-clockwise.
+fn resample_ccw(polygon: &[Vec2], count: usize) -> Vec<Vec2> {
+    let mut samples = polyline::resample(polygon, true, count);
+    if polygon::signed_area(polygon) < 0.0 {
+        // Keeps the first vertex first
+        samples[1..].reverse();
+    }
+    samples
+}
@@ -0,0 +1,313 @@
+//! Offsets of polylines at a signed distance, on their left for a positive distance and on their right otherwise.
+//! Every segment moves along its normal and round joins fill the gaps at the outer corners. The raw offset curve
+//! that results loops back on itself at the inner corners, and has cusps where the polyline bends more tightly
+//! than the distance: it is cut at its self-intersections, and where it leaves the neighbourhood of an open polyline
+//! around its ends, and the pieces that come closer to the polyline than the distance are dropped. The polyline has to be simple
+//! for the pieces to be entirely on either side of the distance.
+//! Bézier curves are offset through their flattening, polygons are closed polylines.
+
+use crate::math::{ Vec2, Polyline, float, polyline };
+
+use alloc::{ vec, vec::Vec, collections::BTreeMap };
+
+/// Largest angle between consecutive points of the round joins
+const JOIN_STEP: f32 = core::f32::consts::PI / 16.0;
+/// Pieces of the raw offset that come closer to the polyline than the distance are dropped,
+/// with this tolerance relative to the distance and to the coordinates
+const KEEP_TOLERANCE: f32 = 1e-5;
+/// Crossings of the raw offset closer than this fraction of the distance are merged
+const MERGE_RATIO: f32 = 1e-3;
+
+/// Connected part of the offset of a polyline.
+pub type OffsetCurve = Polyline;
+
+/// Piece of the raw offset between two cuts, where it crosses itself or the circle around an end of the polyline,
+/// or between a cut and one of its ends.
+struct Piece {
+    points: Vec<Vec2>,
+    /// For each edge, whether it is a chord of the arc of a round join
+    chords: Vec<bool>,
+    start: Option<usize>,
+    end: Option<usize>,
+}
+
+/// Returns the offset of the polyline at `distance`, which can be made of several curves once the loops
+/// of the raw offset are removed, or none if the polyline is too small for the distance.
+pub fn offset(polyline: &Polyline, distance: f32) -> Vec<OffsetCurve> {
+    let _span = span!("Offset");
+    let (points, closed) = cleaned(&polyline.points, polyline.closed);
+    if distance == 0.0 {
+        return vec![ OffsetCurve { points, closed } ];
+    }
+    let raw = raw_offset_with_joins(&points, closed, distance);
+    let k = raw.len();
+    if k < 2 {
+        return Vec::new();
+    }
+    let edge_count = if closed { k } else { k - 1 };
+    let edge = |i: usize| (raw[i].0, raw[(i + 1) % k].0);
+    let is_chord = |i: usize| match (raw[i].1, raw[(i + 1) % k].1) {
+        (Some(a), Some(b)) => a == b,
+        _ => false,
+    };
+
+    // Self-intersections between edges that do not share a vertex, with their parameters along both edges
+    let merge_radius = float::abs(distance) * MERGE_RATIO;
+    let mut crossings = Vec::new();
+    let mut cuts = vec![ Vec::new(); edge_count ];
+    for i in 0..edge_count {
+        for j in i + 2..edge_count {
+            if closed && i == 0 && j == edge_count - 1 {
+                continue;
+            }
+            let ((a, b), (c, d)) = (edge(i), edge(j));
+            if let Some((t, u)) = segment_crossing(a, b, c, d) {
+                let id = crossing_id(&mut crossings, &a + &(&(&b - &a) * t), merge_radius);
+                cuts[i].push((t, id));
+                cuts[j].push((u, id));
+            }
+        }
+    }
+
+    // The neighbourhood of an open polyline is also bounded by the offset on its other side, and by the circles
+    // around its ends: the offset can go in and out of it there without crossing itself
+    if !closed {
+        let other_side = raw_offset_with_joins(&points, false, -distance).into_iter().map(|(p, _)| p).collect::<Vec<_>>();
+        let ends = [ points[0], points[points.len() - 1] ];
+        for (i, edge_cuts) in cuts.iter_mut().enumerate() {
+            let (a, b) = edge(i);
+            let r = &b - &a;
+            let mut cut = |t: f32| {
+                edge_cuts.push((t, crossing_id(&mut crossings, &a + &(&r * t), merge_radius)));
+            };
+            for pair in other_side.windows(2) {
+                if let Some((t, _)) = segment_crossing(a, b, pair[0], pair[1]) {
+                    cut(t);
+                }
+            }
+            for center in &ends {
+                // Roots of |a + t r - center|² = distance²
+                let ac = &a - center;
+                let (qa, qb, qc) = (r.sqr_length(), 2.0 * r.dot(ac), ac.sqr_length() - distance * distance);
+                let discriminant = qb * qb - 4.0 * qa * qc;
+                if qa == 0.0 || discriminant <= 0.0 {
+                    continue;
+                }
+                for &sign in &[ -1.0, 1.0 ] {
+                    let t = (-qb + sign * float::sqrt(discriminant)) / (2.0 * qa);
+                    if t > 0.0 && t < 1.0 {
+                        cut(t);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut pieces = vec![ Piece { points: vec![ raw[0].0 ], chords: Vec::new(), start: None, end: None } ];
+    for (i, edge_cuts) in cuts.iter_mut().enumerate() {
+        edge_cuts.sort_by(|a, b| a.0.total_cmp(&b.0));
+        for &(_, id) in edge_cuts.iter() {
+            let piece = pieces.last_mut().expect("No piece");
+            piece.points.push(crossings[id]);
+            piece.chords.push(is_chord(i));
+            piece.end = Some(id);
+            pieces.push(Piece { points: vec![ crossings[id] ], chords: Vec::new(), start: Some(id), end: None });
+        }
+        let piece = pieces.last_mut().expect("No piece");
+        piece.points.push(edge(i).1);
+        piece.chords.push(is_chord(i));
+    }
+    // The start of a closed curve is not a cut, its last piece goes on with the first one
+    if closed && pieces.len() > 1 {
+        let first = pieces.remove(0);
+        let last = pieces.last_mut().expect("No piece");
+        last.points.extend_from_slice(&first.points[1..]);
+        last.chords.extend_from_slice(&first.chords);
+        last.end = first.end;
+    }
+
+    // Drops the pieces between merged crossings
+    pieces.retain(|piece| piece.points.iter().any(|p| (p - &piece.points[0]).length() >= merge_radius));
+
+    // The middles of the chords of the joins are closer to the polyline than their arcs
+    let threshold = float::abs(distance) * (1.0 - KEEP_TOLERANCE) - KEEP_TOLERANCE;
+    let chord_threshold = float::abs(distance) * (float::cos(JOIN_STEP / 2.0) - KEEP_TOLERANCE) - KEEP_TOLERANCE;
+    let pieces = pieces
+        .into_iter()
+        .filter(|piece| piece.points.windows(2).zip(&piece.chords).all(|(pair, &chord)| {
+            let middle = Vec2::new((pair[0].x + pair[1].x) / 2.0, (pair[0].y + pair[1].y) / 2.0);
+            polyline::distance(&points, closed, middle) >= if chord { chord_threshold } else { threshold }
+        }))
+        .collect::<Vec<_>>();
+    chain(&pieces)
+}
+
+/// Links the pieces that meet at the same cut into curves. The chains that start with a piece
+/// that no other one leads to are open, the others are loops.
+fn chain(pieces: &[Piece]) -> Vec<OffsetCurve> {
+    let mut starting_at = BTreeMap::new();
+    for (idx, piece) in pieces.iter().enumerate() {
+        if let Some(id) = piece.start {
+            starting_at.entry(id).or_insert_with(Vec::new).push(idx);
+        }
+    }
+    let has_predecessor = |idx: usize| pieces[idx].start.is_some() && pieces.iter().any(|piece| piece.end == pieces[idx].start);
+
+    let mut used = vec![ false; pieces.len() ];
+    let mut curves = Vec::new();
+    for first in (0..pieces.len()).filter(|&idx| !has_predecessor(idx)).chain(0..pieces.len()) {
+        if used[first] {
+            continue;
+        }
+        let mut points: Vec<Vec2> = Vec::new();
+        let mut idx = first;
+        loop {
+            used[idx] = true;
+            let skip = if points.is_empty() { 0 } else { 1 };
+            points.extend_from_slice(&pieces[idx].points[skip..]);
+            let next = pieces[idx].end
+                .and_then(|id| starting_at.get(&id))
+                .and_then(|next| next.iter().copied().find(|&next| !used[next]));
+            match next {
+                Some(next) => idx = next,
+                None => break,
+            }
+        }
+
+        let closed = points.len() > 3 && points.first() == points.last();
+        if closed {
+            points.pop();
+        }
+        if points.len() >= 2 {
+            curves.push(OffsetCurve { points, closed });
+        }
+    }
+    curves
+}
+
+/// Returns the index of the crossing at `p`, added if there is none yet. Crossings closer than `radius` are merged:
+/// where more than two parts of the raw offset almost meet, the tiny pieces between their crossings are too close
+/// to the distance to tell whether to keep them, and would break the curves.
+fn crossing_id(crossings: &mut Vec<Vec2>, p: Vec2, radius: f32) -> usize {
+    match crossings.iter().position(|crossing| (crossing - &p).length() < radius) {
+        Some(id) => id,
+        None => {
+            crossings.push(p);
+            crossings.len() - 1
+        },
+    }
+}
+
+/// Returns the parameters along both segments of their intersection, if they cross away from their ends.
+fn segment_crossing(a: Vec2, b: Vec2, c: Vec2, d: Vec2) -> Option<(f32, f32)> {
+    let cross = |u: Vec2, v: Vec2| u.x * v.y - u.y * v.x;
+    let (r, s) = (&b - &a, &d - &c);
+    let denominator = cross(r, s);
+    if denominator == 0.0 {
+        return None;
+    }
+    let ac = &c - &a;
+    let (t, u) = (cross(ac, s) / denominator, cross(ac, r) / denominator);
+    if t > 0.0 && t < 1.0 && u > 0.0 && u < 1.0 { Some((t, u)) } else { None }
+}
+
+/// Returns the offset of each segment at `distance`, linked by round joins at the outer corners
+/// and directly at the inner ones, before the removal of its loops.
+pub fn raw_offset(polyline: &Polyline, distance: f32) -> Vec<Vec2> {
+    raw_offset_with_joins(&polyline.points, polyline.closed, distance).into_iter().map(|(p, _)| p).collect()
+}
+
+/// Returns the raw offset, with the vertex of the polyline at the center of the round join of each point
+/// that is on one: the edges between two points of the same join are chords of its arc.
+fn raw_offset_with_joins(points: &[Vec2], closed: bool, distance: f32) -> Vec<(Vec2, Option<Vec2>)> {
+    let (points, closed) = cleaned(points, closed);
+    let normals = normals(&points, closed);
+    let (n, count) = (points.len(), normals.len());
+    let outer = (0..count)
+        .map(|i| (closed || i + 1 < count) && is_outer_corner(normals[i], normals[(i + 1) % count], distance))
+        .collect::<Vec<_>>();
+
+    let mut raw: Vec<(Vec2, Option<Vec2>)> = Vec::new();
+    for (i, normal) in normals.iter().enumerate() {
+        let (a, b) = (points[i], points[(i + 1) % n]);
+        let previous = if i > 0 { outer[i - 1] } else { closed && outer[count - 1] };
+        raw.push((&a + &(normal * distance), if previous { Some(a) } else { None }));
+        raw.push((&b + &(normal * distance), if outer[i] { Some(b) } else { None }));
+        if outer[i] {
+            raw.extend(join(b, *normal, normals[(i + 1) % count], distance).map(|p| (p, Some(b))));
+        }
+    }
+    raw.dedup_by(|next, previous| next.0 == previous.0);
+    if closed && raw.len() > 1 && raw[0].0 == raw[raw.len() - 1].0 {
+        raw.pop();
+    }
+    raw
+}
+
+/// Returns triangles, 3 points each, that cover the points between the polyline and its offset at `distance`.
+/// They overlap at the inner corners and where the polyline bends more tightly than the distance.
+pub fn band(polyline: &Polyline, distance: f32) -> Vec<Vec2> {
+    let (points, closed) = cleaned(&polyline.points, polyline.closed);
+    let normals = normals(&points, closed);
+    let n = points.len();
+
+    let mut triangles = Vec::new();
+    for (i, normal) in normals.iter().enumerate() {
+        let (a, b) = (points[i], points[(i + 1) % n]);
+        let (a_offset, b_offset) = (&a + &(normal * distance), &b + &(normal * distance));
+        triangles.extend_from_slice(&[ a, b, b_offset, a, b_offset, a_offset ]);
+
+        if closed || i + 1 < normals.len() {
+            let next = normals[(i + 1) % normals.len()];
+            if is_outer_corner(*normal, next, distance) {
+                // Fan around the vertex
+                let mut arc = vec![ b_offset ];
+                arc.extend(join(b, *normal, next, distance));
+                arc.push(&b + &(&next * distance));
+                for pair in arc.windows(2) {
+                    triangles.extend_from_slice(&[ b, pair[0], pair[1] ]);
+                }
+            }
+        }
+    }
+    triangles
+}
+
+/// Removes the consecutive duplicates of the polyline, and the last point of a closed one if it repeats the first.
+/// A closed polyline needs 3 points, it is open otherwise.
+fn cleaned(points: &[Vec2], closed: bool) -> (Vec<Vec2>, bool) {
+    let mut points = points.to_vec();
+    points.dedup();
+    if closed && points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+    let closed = closed && points.len() >= 3;
+    (points, closed)
+}
+
+/// Returns the unit normals on the left of the segments of the polyline.
+fn normals(points: &[Vec2], closed: bool) -> Vec<Vec2> {
+    polyline::edges(points, closed)
+        .map(|(a, b)| {
+            let direction = (&b - &a).normalized();
+            Vec2::new(-direction.y, direction.x)
+        })
+        .collect()
+}
+
+/// Checks whether the offsets of two consecutive segments leave a gap between them,
+/// when the polyline turns away from the side of the offset.
+fn is_outer_corner(normal: Vec2, next: Vec2, distance: f32) -> bool {
+    (normal.x * next.y - normal.y * next.x) * distance < 0.0
+}
+
+/// Returns the points strictly inside the arc of radius `distance` around `center`, from `normal` to `next`.
+fn join(center: Vec2, normal: Vec2, next: Vec2, distance: f32) -> impl Iterator<Item = Vec2> {
+    let angle = normal.signed_angle(next);
+    let steps = (float::abs(angle) / JOIN_STEP) as usize + 1;
+    (1..steps).map(move |step| {
+        let (sin, cos) = (float::sin(angle * step as f32 / steps as f32), float::cos(angle * step as f32 / steps as f32));
+        let rotated = Vec2::new(normal.x * cos - normal.y * sin, normal.x * sin + normal.y * cos);
+        &center + &(&rotated * distance)
+    })
+}
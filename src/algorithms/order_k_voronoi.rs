@@ -0,0 +1,125 @@
+use super::voronoi::clip_to_bisector;
+use crate::math::{ Vec2, float };
+
+use alloc::{ vec, vec::Vec };
+
+/// Part of a cell of an order-k Voronoi diagram, a convex polygon whose positions all have the same k nearest input points.
+/// A cell can be split into several regions, all with the same `sites`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct VoronoiRegion {
+    /// Indices of the k nearest input points, in increasing order
+    pub sites: Vec<usize>,
+    /// Vertices of the region, in counter-clockwise order
+    pub polygon: Vec<Vec2>,
+}
+
+impl VoronoiRegion {
+    pub fn area(&self) -> f32 {
+        let n = self.polygon.len();
+        (0..n).map(|idx| {
+                let (a, b) = (self.polygon[idx], self.polygon[(idx + 1) % n]);
+                a.x * b.y - b.x * a.y
+            })
+            .sum::<f32>() * 0.5
+    }
+}
+
+/// Order-k Voronoi diagram of a set of 2D points: the plane is divided by the set of the k nearest input points.
+/// The order-1 diagram is the usual Voronoi diagram, the order-(n-1) diagram is the farthest-point one.
+/// The diagram is computed inside a box enclosing the points, the unbounded cells are cut by it.
+#[derive(Default)]
+pub struct OrderKVoronoi {
+    points: Vec<Vec2>,
+    k: usize,
+    regions: Vec<VoronoiRegion>,
+}
+
+impl OrderKVoronoi {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn points(&self) -> &[Vec2] {
+        &self.points
+    }
+
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    pub fn regions(&self) -> &[VoronoiRegion] {
+        &self.regions
+    }
+
+    /// Returns the number of cells, the number of distinct sets of k nearest points.
+    pub fn cell_count(&self) -> usize {
+        let mut sites = self.regions.iter().map(|region| &region.sites).collect::<Vec<_>>();
+        sites.sort();
+        sites.dedup();
+        sites.len()
+    }
+
+    /// Replaces the input points and the order and computes the diagram.
+    /// With fewer than `k` points, the diagram is that of all the points.
+    pub fn set_points(&mut self, points: Vec<Vec2>, k: usize) {
+        let _span = span!("Order-k Voronoi");
+        self.points = points;
+        self.k = k;
+        self.regions = Self::diagram(&self.points, k.min(self.points.len()), &Self::bounds(&self.points));
+    }
+
+    pub fn clear(&mut self) {
+        self.set_points(Vec::new(), self.k);
+    }
+
+    /// Returns a square enclosing the points and the [-1, 1] viewport, with a margin, in counter-clockwise order.
+    fn bounds(points: &[Vec2]) -> Vec<Vec2> {
+        let extent = points.iter()
+                        .map(|p| float::abs(p.x).max(float::abs(p.y)))
+                        .fold(1.0, f32::max) * 2.0;
+        vec![
+            Vec2::new(-extent, -extent), Vec2::new(extent, -extent),
+            Vec2::new(extent, extent), Vec2::new(-extent, extent),
+        ]
+    }
+
+    /// Computes the regions of the order-k diagram inside the convex polygon `bounds`.
+    /// Each region of the order-(k-1) diagram is refined by the order-1 diagram of the points that are not its sites:
+    /// the part closest to the point `t` gets the sites of the region and `t`.
+    pub fn diagram(points: &[Vec2], k: usize, bounds: &[Vec2]) -> Vec<VoronoiRegion> {
+        if points.is_empty() || k == 0 {
+            return Vec::new();
+        }
+        // Regions thinner than this are clipping artifacts at the corners of the cells
+        let min_area = 1e-9;
+
+        let mut regions = vec![ VoronoiRegion { sites: Vec::new(), polygon: bounds.to_vec() } ];
+        for _ in 0..k {
+            let mut refined = Vec::new();
+            for region in &regions {
+                let others = (0..points.len())
+                                .filter(|idx| region.sites.binary_search(idx).is_err())
+                                .collect::<Vec<_>>();
+                for &t in &others {
+                    let mut polygon = region.polygon.clone();
+                    for &u in others.iter().filter(|&&u| u != t) {
+                        polygon = clip_to_bisector(&polygon, points[t], points[u]);
+                        if polygon.is_empty() {
+                            break;
+                        }
+                    }
+
+                    let mut sites = region.sites.clone();
+                    let pos = sites.binary_search(&t).unwrap_err();
+                    sites.insert(pos, t);
+                    let region = VoronoiRegion { sites, polygon };
+                    if region.area() > min_area {
+                        refined.push(region);
+                    }
+                }
+            }
+            regions = refined;
+        }
+        regions
+    }
+}
@@ -0,0 +1,404 @@
+//! Paths between two points among obstacles, on an occupancy grid and exactly.
+//! A* moves between the centers of neighbouring free cells, so its paths only turn by multiples of 45°.
+//! Theta* links each cell to the parent of the cell it is reached from whenever the line of sight between them
+//! only crosses free cells, which gives any-angle paths. The shortest path itself only bends at the corners
+//! of the obstacles, it is found in their visibility graph.
+
+use crate::math::{ Vec2, Rect, Segment2, float, polygon };
+
+use alloc::{ collections::BinaryHeap, vec, vec::Vec };
+use core::cmp::Ordering;
+
+/// Square cells covering a rectangle, blocked where an obstacle overlaps them.
+#[derive(Clone, Debug)]
+pub struct OccupancyGrid {
+    /// Bottom left corner of the first cell
+    pub origin: Vec2,
+    pub cell_size: f32,
+    pub columns: usize,
+    pub rows: usize,
+    /// Whether each cell is blocked, row by row from the bottom left one
+    pub blocked: Vec<bool>,
+}
+
+impl OccupancyGrid {
+    /// Covers `bounds` with cells of side `cell_size`, and blocks the cells inside the polygons
+    /// or crossed by their edges and by the segments.
+    pub fn new(bounds: Rect, cell_size: f32, polygons: &[Vec<Vec2>], segments: &[Segment2]) -> Self {
+        let _span = span!("Occupancy grid");
+        let columns = (float::floor(bounds.width() / cell_size) as usize + 1).max(1);
+        let rows = (float::floor(bounds.height() / cell_size) as usize + 1).max(1);
+        let mut grid = Self {
+            origin: bounds.min,
+            cell_size,
+            columns,
+            rows,
+            blocked: vec![ false; columns * rows ],
+        };
+
+        let mut walls = segments.to_vec();
+        for polygon in polygons {
+            walls.extend(polygon::edges(polygon).map(|(a, b)| Segment2::new(a, b)));
+            // Only the cells of the bounding rectangle of the polygon can have their center inside it
+            if let Some(rect) = polygon::bounding_rect(polygon) {
+                for cell in grid.cells_in(&rect) {
+                    if polygon::contains(polygon, grid.center(cell)) {
+                        grid.block(cell);
+                    }
+                }
+            }
+        }
+        for wall in &walls {
+            for cell in grid.cells_in(&wall.bounding_rect()) {
+                if grid.crosses(cell, wall) {
+                    grid.block(cell);
+                }
+            }
+        }
+        grid
+    }
+
+    /// Returns the column and the row of the cell containing `p`, or `None` out of the grid.
+    pub fn cell(&self, p: Vec2) -> Option<(usize, usize)> {
+        let (x, y) = ((p.x - self.origin.x) / self.cell_size, (p.y - self.origin.y) / self.cell_size);
+        if x < 0.0 || y < 0.0 || x >= self.columns as f32 || y >= self.rows as f32 {
+            return None;
+        }
+        Some((x as usize, y as usize))
+    }
+
+    pub fn center(&self, (column, row): (usize, usize)) -> Vec2 {
+        Vec2::new(self.origin.x + (column as f32 + 0.5) * self.cell_size, self.origin.y + (row as f32 + 0.5) * self.cell_size)
+    }
+
+    pub fn cell_rect(&self, (column, row): (usize, usize)) -> Rect {
+        let min = Vec2::new(self.origin.x + column as f32 * self.cell_size, self.origin.y + row as f32 * self.cell_size);
+        Rect::new(min, Vec2::new(min.x + self.cell_size, min.y + self.cell_size))
+    }
+
+    /// Returns whether the cell is blocked, the cells out of the grid are.
+    pub fn is_blocked(&self, column: isize, row: isize) -> bool {
+        if column < 0 || row < 0 || column >= self.columns as isize || row >= self.rows as isize {
+            return true;
+        }
+        self.blocked[row as usize * self.columns + column as usize]
+    }
+
+    /// Returns whether the segment between the centers of two cells only crosses free cells.
+    /// Where it goes through the corner of a cell, both cells beside the corner have to be free,
+    /// as for the diagonal moves of the searches.
+    pub fn line_of_sight(&self, a: (usize, usize), b: (usize, usize)) -> bool {
+        let (mut x, mut y) = (a.0 as isize, a.1 as isize);
+        let (dx, dy) = (b.0 as isize - x, b.1 as isize - y);
+        let (step_x, step_y, nx, ny) = (dx.signum(), dy.signum(), dx.abs(), dy.abs());
+        if self.is_blocked(x, y) {
+            return false;
+        }
+        // The segment leaves the ith column at t = (2i + 1) / 2nx and the jth row at t = (2j + 1) / 2ny,
+        // compared with integers so that the corners are found exactly
+        let (mut i, mut j) = (0, 0);
+        while i < nx || j < ny {
+            match ((2 * i + 1) * ny).cmp(&((2 * j + 1) * nx)) {
+                Ordering::Less => {
+                    x += step_x;
+                    i += 1;
+                },
+                Ordering::Greater => {
+                    y += step_y;
+                    j += 1;
+                },
+                Ordering::Equal => {
+                    if self.is_blocked(x + step_x, y) || self.is_blocked(x, y + step_y) {
+                        return false;
+                    }
+                    x += step_x;
+                    y += step_y;
+                    i += 1;
+                    j += 1;
+                },
+            }
+            if self.is_blocked(x, y) {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn block(&mut self, (column, row): (usize, usize)) {
+        self.blocked[row * self.columns + column] = true;
+    }
+
+    /// Returns the cells overlapping the rectangle.
+    fn cells_in(&self, rect: &Rect) -> impl Iterator<Item = (usize, usize)> {
+        let clamp = |value: f32, count: usize| (float::floor(value / self.cell_size).max(0.0) as usize).min(count - 1);
+        let (min_column, max_column) = (clamp(rect.min.x - self.origin.x, self.columns), clamp(rect.max.x - self.origin.x, self.columns));
+        let (min_row, max_row) = (clamp(rect.min.y - self.origin.y, self.rows), clamp(rect.max.y - self.origin.y, self.rows));
+        (min_row..=max_row).flat_map(move |row| (min_column..=max_column).map(move |column| (column, row)))
+    }
+
+    /// Returns whether the segment has a point in the cell.
+    fn crosses(&self, cell: (usize, usize), segment: &Segment2) -> bool {
+        let rect = self.cell_rect(cell);
+        if rect.contains(segment.a) || rect.contains(segment.b) {
+            return true;
+        }
+        let corners = rect.corners();
+        (0..4).any(|k| Segment2::new(corners[k], corners[(k + 1) % 4]).intersects(segment))
+    }
+}
+
+/// Path found by [`a_star`](fn.a_star.html) or [`theta_star`](fn.theta_star.html).
+#[derive(Clone, PartialEq, Debug)]
+pub struct GridPath {
+    /// From the start to the end, through the centers of the cells where the path turns
+    pub points: Vec<Vec2>,
+    /// Number of cells taken out of the open list
+    pub expanded: usize,
+}
+
+impl GridPath {
+    pub fn length(&self) -> f32 {
+        self.points.windows(2).map(|link| (&link[1] - &link[0]).length()).sum()
+    }
+}
+
+/// Cell of the open list, ordered so that the cell with the smallest estimated cost comes first out of the heap
+#[derive(Copy, Clone)]
+struct Open {
+    estimate: f32,
+    cell: usize,
+}
+
+impl PartialEq for Open {
+    fn eq(&self, other: &Self) -> bool {
+        self.estimate == other.estimate
+    }
+}
+
+impl Eq for Open {}
+
+impl PartialOrd for Open {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Open {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimate.partial_cmp(&self.estimate).unwrap_or(Ordering::Equal)
+    }
+}
+
+/// Finds the shortest path from `start` to `end` moving between the 8 neighbours of the free cells,
+/// without cutting the corners of the blocked cells. Returns `None` if a point is in a blocked cell or out of the grid,
+/// or if no path joins them.
+pub fn a_star(grid: &OccupancyGrid, start: Vec2, end: Vec2) -> Option<GridPath> {
+    let _span = span!("A*");
+    search(grid, start, end, false)
+}
+
+/// Finds an any-angle path from `start` to `end`: like A*, except that a cell reached from another one
+/// is linked to the parent of that cell when it is in sight, instead of to that cell.
+/// The path is at most as long as the one of A*, not always the shortest one among the free cells.
+pub fn theta_star(grid: &OccupancyGrid, start: Vec2, end: Vec2) -> Option<GridPath> {
+    let _span = span!("Theta*");
+    search(grid, start, end, true)
+}
+
+fn search(grid: &OccupancyGrid, start: Vec2, end: Vec2, any_angle: bool) -> Option<GridPath> {
+    let (first, last) = (grid.cell(start)?, grid.cell(end)?);
+    if grid.is_blocked(first.0 as isize, first.1 as isize) || grid.is_blocked(last.0 as isize, last.1 as isize) {
+        return None;
+    }
+    let index = |(column, row): (usize, usize)| row * grid.columns + column;
+    let cell = |idx: usize| (idx % grid.columns, idx / grid.columns);
+    let distance = |a: usize, b: usize| (&grid.center(cell(b)) - &grid.center(cell(a))).length();
+    // The octile distance is exact without obstacles for the 8 moves of A*, the straight line for Theta*
+    let heuristic = |idx: usize| {
+        let ((x, y), (goal_x, goal_y)) = (cell(idx), last);
+        let (dx, dy) = (float::abs(x as f32 - goal_x as f32), float::abs(y as f32 - goal_y as f32));
+        grid.cell_size * if any_angle {
+            float::sqrt(dx * dx + dy * dy)
+        } else {
+            dx.max(dy) + (core::f32::consts::SQRT_2 - 1.0) * dx.min(dy)
+        }
+    };
+
+    let (source, target) = (index(first), index(last));
+    let mut costs = vec![ f32::INFINITY; grid.blocked.len() ];
+    let mut parents = vec![ usize::MAX; grid.blocked.len() ];
+    let mut closed = vec![ false; grid.blocked.len() ];
+    let mut open = BinaryHeap::new();
+    costs[source] = 0.0;
+    parents[source] = source;
+    open.push(Open { estimate: heuristic(source), cell: source });
+    let mut expanded = 0;
+
+    while let Some(Open { cell: current, .. }) = open.pop() {
+        // A cell is pushed again each time its cost decreases, only its first pop counts
+        if closed[current] {
+            continue;
+        }
+        closed[current] = true;
+        expanded += 1;
+        if current == target {
+            break;
+        }
+
+        let (x, y) = (cell(current).0 as isize, cell(current).1 as isize);
+        for &(dx, dy) in &[ (1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1), (0, -1), (1, -1) ] {
+            let (nx, ny) = (x + dx, y + dy);
+            // The diagonal moves need both cells beside them to be free
+            if grid.is_blocked(nx, ny) || (dx != 0 && dy != 0 && (grid.is_blocked(x + dx, y) || grid.is_blocked(x, y + dy))) {
+                continue;
+            }
+            let neighbour = index((nx as usize, ny as usize));
+            if closed[neighbour] {
+                continue;
+            }
+            let parent = parents[current];
+            let (from, cost) = if any_angle && grid.line_of_sight(cell(parent), cell(neighbour)) {
+                (parent, costs[parent] + distance(parent, neighbour))
+            } else {
+                (current, costs[current] + distance(current, neighbour))
+            };
+            if cost < costs[neighbour] {
+                costs[neighbour] = cost;
+                parents[neighbour] = from;
+                open.push(Open { estimate: cost + heuristic(neighbour), cell: neighbour });
+            }
+        }
+    }
+    if !closed[target] {
+        return None;
+    }
+
+    // The ends of the path are the points themselves instead of the centers of their cells
+    let mut cells = vec![ target ];
+    while *cells.last()? != source {
+        cells.push(parents[*cells.last()?]);
+    }
+    cells.reverse();
+    // A* moves from cell to cell, only the cells where it turns are kept
+    let offset = |a: usize, b: usize| {
+        let ((ax, ay), (bx, by)) = (cell(a), cell(b));
+        (bx as isize - ax as isize, by as isize - ay as isize)
+    };
+    let turns = (1..cells.len().saturating_sub(1)).filter(|&i| {
+        let ((ux, uy), (vx, vy)) = (offset(cells[i - 1], cells[i]), offset(cells[i], cells[i + 1]));
+        ux * vy != uy * vx || ux * vx + uy * vy < 0
+    });
+    let mut points = vec![ start ];
+    points.extend(turns.map(|i| grid.center(cell(cells[i]))));
+    points.push(end);
+    Some(GridPath { points, expanded })
+}
+
+/// Visibility graph of the corners of the obstacles and of two points, as returned by [`visibility_graph`](fn.visibility_graph.html).
+#[derive(Clone, PartialEq, Debug)]
+pub struct VisibilityGraph {
+    /// The start, the end, then the vertices of the polygons and the ends of the segments
+    pub nodes: Vec<Vec2>,
+    /// Pairs of nodes in sight of each other, the segment between them goes through no obstacle
+    pub edges: Vec<(usize, usize)>,
+}
+
+impl VisibilityGraph {
+    /// Returns the shortest path from the start to the end along the edges with Dijkstra's algorithm,
+    /// or `None` if they are not connected.
+    pub fn shortest_path(&self) -> Option<Vec<Vec2>> {
+        let n = self.nodes.len();
+        let mut neighbours = vec![ Vec::new(); n ];
+        for &(a, b) in &self.edges {
+            neighbours[a].push(b);
+            neighbours[b].push(a);
+        }
+        let mut costs = vec![ f32::INFINITY; n ];
+        let mut parents = vec![ usize::MAX; n ];
+        let mut closed = vec![ false; n ];
+        let mut open = BinaryHeap::new();
+        costs[0] = 0.0;
+        open.push(Open { estimate: 0.0, cell: 0 });
+        while let Some(Open { cell: current, .. }) = open.pop() {
+            if closed[current] {
+                continue;
+            }
+            closed[current] = true;
+            for &next in &neighbours[current] {
+                let cost = costs[current] + (&self.nodes[next] - &self.nodes[current]).length();
+                if !closed[next] && cost < costs[next] {
+                    costs[next] = cost;
+                    parents[next] = current;
+                    open.push(Open { estimate: cost, cell: next });
+                }
+            }
+        }
+        if !closed[1] {
+            return None;
+        }
+
+        let mut path = vec![ 1 ];
+        while *path.last()? != 0 {
+            path.push(parents[*path.last()?]);
+        }
+        Some(path.iter().rev().map(|&idx| self.nodes[idx]).collect())
+    }
+}
+
+/// Builds the visibility graph of `start`, `end` and the corners of the obstacles by testing all the pairs of nodes
+/// against all the obstacles. A segment between two nodes may go along the boundary of a polygon or touch its corners,
+/// but not cross its inside or a segment. The graph has no edge if the start or the end is inside a polygon.
+pub fn visibility_graph(polygons: &[Vec<Vec2>], segments: &[Segment2], start: Vec2, end: Vec2) -> VisibilityGraph {
+    let _span = span!("Visibility graph");
+    let mut nodes = vec![ start, end ];
+    nodes.extend(polygons.iter().flatten());
+    nodes.extend(segments.iter().flat_map(|s| vec![ s.a, s.b ]));
+    let walls = polygons
+        .iter()
+        .flat_map(|polygon| polygon::edges(polygon).map(|(a, b)| Segment2::new(a, b)))
+        .chain(segments.iter().copied())
+        .collect::<Vec<_>>();
+
+    let mut edges = Vec::new();
+    if polygons.iter().any(|polygon| polygon::contains(polygon, start) || polygon::contains(polygon, end)) {
+        return VisibilityGraph { nodes, edges };
+    }
+    for a in 0..nodes.len() {
+        for b in a + 1..nodes.len() {
+            if nodes[a] != nodes[b] && in_sight(polygons, &walls, nodes[a], nodes[b]) {
+                edges.push((a, b));
+            }
+        }
+    }
+    VisibilityGraph { nodes, edges }
+}
+
+/// Returns whether the segment from `p` to `q` crosses no wall and does not go inside a polygon.
+fn in_sight(polygons: &[Vec<Vec2>], walls: &[Segment2], p: Vec2, q: Vec2) -> bool {
+    // Proper crossings, the ends of the walls on the segment are handled below
+    let crosses = |wall: &Segment2| {
+        let (o1, o2) = (Vec2::orientation(p, q, wall.a), Vec2::orientation(p, q, wall.b));
+        let (o3, o4) = (Vec2::orientation(wall.a, wall.b, p), Vec2::orientation(wall.a, wall.b, q));
+        o1 * o2 < 0.0 && o3 * o4 < 0.0
+    };
+    if walls.iter().any(crosses) {
+        return false;
+    }
+
+    // Between the corners it goes through, the segment is either inside or outside of each polygon
+    let direction = &q - &p;
+    let mut cuts = polygons
+        .iter()
+        .flatten()
+        .filter(|&&v| Vec2::orientation(p, q, v) == 0.0)
+        .map(|&v| (&v - &p).dot(direction) / direction.sqr_length())
+        .filter(|&t| t > 0.0 && t < 1.0)
+        .collect::<Vec<_>>();
+    cuts.push(0.0);
+    cuts.push(1.0);
+    cuts.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    cuts.windows(2).all(|pair| {
+        let middle = &p + &(&direction * ((pair[0] + pair[1]) / 2.0));
+        polygons.iter().all(|polygon| !polygon::contains(polygon, middle) || polygon::boundary_distance(polygon, middle) < 1e-6)
+    })
+}
@@ -0,0 +1,423 @@
+//! Point location in a 2D triangulation: finding the triangle that contains a query point, with three strategies.
+//! The walk crosses the triangles towards the point from a starting one, the history DAG descends through
+//! the triangles created and destroyed while building a Delaunay triangulation incrementally,
+//! and the grid only tests the triangles overlapping the cell of the point.
+
+use super::delaunay::in_circumcircle;
+use crate::math::{ Vec2, Rect, XorShift };
+
+use alloc::{ vec, vec::Vec, collections::BTreeMap };
+
+/// Result of a point location query.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Location {
+    /// Index of the triangle containing the point, `None` if it is out of the triangulation
+    pub triangle: Option<usize>,
+    /// Number of triangles, or nodes of the DAG, tested to find it
+    pub visited: usize,
+}
+
+/// Returns true if `p` is in the counter-clockwise triangle (a, b, c), boundary included.
+fn contains(a: Vec2, b: Vec2, c: Vec2, p: Vec2) -> bool {
+    Vec2::orientation(a, b, p) >= 0.0 && Vec2::orientation(b, c, p) >= 0.0 && Vec2::orientation(c, a, p) >= 0.0
+}
+
+/// Returns the triangles of `indices`, counter-clockwise.
+fn oriented_triangles(points: &[Vec2], indices: &[usize]) -> Vec<[usize; 3]> {
+    indices.chunks_exact(3)
+        .map(|t| if Vec2::orientation(points[t[0]], points[t[1]], points[t[2]]) < 0.0 { [ t[0], t[2], t[1] ] } else { [ t[0], t[1], t[2] ] })
+        .collect()
+}
+
+/// Visibility walk: from a triangle, it crosses an edge that has the query point on its other side until there is none.
+/// It takes O(√n) steps on average between random points of a Delaunay triangulation, and needs no other structure
+/// than the neighbours of the triangles.
+pub struct TriangleWalk<'a> {
+    points: &'a [Vec2],
+    triangles: Vec<[usize; 3]>,
+    /// Triangle across each edge, the edge `i` going from vertex `i` to vertex `i + 1`
+    neighbours: Vec<[Option<usize>; 3]>,
+}
+
+impl<'a> TriangleWalk<'a> {
+    /// `indices` contains 3 indices into `points` per triangle, in any orientation.
+    pub fn new(points: &'a [Vec2], indices: &[usize]) -> Self {
+        let triangles = oriented_triangles(points, indices);
+        let mut edges = BTreeMap::new();
+        for (idx, t) in triangles.iter().enumerate() {
+            for i in 0..3 {
+                edges.insert((t[i], t[(i + 1) % 3]), idx);
+            }
+        }
+        let neighbours = triangles.iter()
+                            .map(|t| {
+                                let mut neighbours = [ None; 3 ];
+                                for (i, neighbour) in neighbours.iter_mut().enumerate() {
+                                    *neighbour = edges.get(&(t[(i + 1) % 3], t[i])).cloned();
+                                }
+                                neighbours
+                            })
+                            .collect();
+        Self {
+            points,
+            triangles,
+            neighbours,
+        }
+    }
+
+    /// Walks from the triangle `start` to the one containing `p`.
+    /// The edge tested first changes at each step, so that the walk cannot cycle forever in a triangulation that is not Delaunay.
+    pub fn locate(&self, p: Vec2, start: usize) -> Location {
+        let mut current = start;
+        let mut visited = 0;
+        let mut random = XorShift::default();
+        if current >= self.triangles.len() {
+            return Location { triangle: None, visited };
+        }
+        loop {
+            visited += 1;
+            let t = self.triangles[current];
+            let first = random.below(3);
+            let crossed = (0..3)
+                .map(|k| (first + k) % 3)
+                .find(|&i| Vec2::orientation(self.points[t[i]], self.points[t[(i + 1) % 3]], p) < 0.0);
+            match crossed {
+                None => return Location { triangle: Some(current), visited },
+                Some(i) => match self.neighbours[current][i] {
+                    Some(next) if visited <= self.triangles.len() => current = next,
+                    // Out of the hull, or lost in a degenerate triangulation
+                    _ => return Location { triangle: None, visited },
+                },
+            }
+        }
+    }
+}
+
+/// Uniform grid whose cells list the triangles overlapping them, about one triangle per cell.
+/// A query tests the triangles of its cell, which is fast when the triangles have similar sizes.
+pub struct TriangleGrid<'a> {
+    points: &'a [Vec2],
+    triangles: Vec<[usize; 3]>,
+    bounds: Rect,
+    columns: usize,
+    rows: usize,
+    /// The triangles overlapping cell `c` are `cells[starts[c]..starts[c + 1]]`
+    starts: Vec<usize>,
+    cells: Vec<usize>,
+}
+
+impl<'a> TriangleGrid<'a> {
+    /// `indices` contains 3 indices into `points` per triangle, in any orientation.
+    pub fn new(points: &'a [Vec2], indices: &[usize]) -> Self {
+        let triangles = oriented_triangles(points, indices);
+        let bounds = Rect::from_points(points).unwrap_or_else(|| Rect::new(Vec2::new(0.0, 0.0), Vec2::new(0.0, 0.0)));
+        let side = crate::math::float::sqrt(triangles.len() as f32) as usize + 1;
+        let mut grid = Self {
+            points,
+            triangles,
+            bounds,
+            columns: side,
+            rows: side,
+            starts: vec![ 0; side * side + 1 ],
+            cells: Vec::new(),
+        };
+
+        // Cells overlapped by the bounding rectangle of each triangle, counted then sorted by cell
+        let ranges = grid.triangles.iter()
+                        .map(|t| {
+                            let rect = Rect::from_points(&[ points[t[0]], points[t[1]], points[t[2]] ]).expect("Empty triangle");
+                            (grid.cell(rect.min), grid.cell(rect.max))
+                        })
+                        .collect::<Vec<_>>();
+        for &((x0, y0), (x1, y1)) in &ranges {
+            for y in y0..=y1 {
+                for x in x0..=x1 {
+                    grid.starts[y * grid.columns + x + 1] += 1;
+                }
+            }
+        }
+        for cell in 0..grid.columns * grid.rows {
+            grid.starts[cell + 1] += grid.starts[cell];
+        }
+        grid.cells = vec![ 0; grid.starts[grid.columns * grid.rows] ];
+        let mut next = grid.starts.clone();
+        for (idx, &((x0, y0), (x1, y1))) in ranges.iter().enumerate() {
+            for y in y0..=y1 {
+                for x in x0..=x1 {
+                    let cell = y * grid.columns + x;
+                    grid.cells[next[cell]] = idx;
+                    next[cell] += 1;
+                }
+            }
+        }
+        grid
+    }
+
+    /// Returns the column and the row of the cell containing `p`, clamped to the grid.
+    fn cell(&self, p: Vec2) -> (usize, usize) {
+        let index = |value: f32, min: f32, size: f32, count: usize| {
+            let t = if size > 0.0 { (value - min) / size } else { 0.0 };
+            ((t * count as f32).max(0.0) as usize).min(count - 1)
+        };
+        (index(p.x, self.bounds.min.x, self.bounds.width(), self.columns),
+         index(p.y, self.bounds.min.y, self.bounds.height(), self.rows))
+    }
+
+    /// Tests the triangles of the cell of `p`.
+    pub fn locate(&self, p: Vec2) -> Location {
+        if !self.bounds.contains(p) {
+            return Location { triangle: None, visited: 0 };
+        }
+        let (x, y) = self.cell(p);
+        let cell = y * self.columns + x;
+        let mut visited = 0;
+        for &idx in &self.cells[self.starts[cell]..self.starts[cell + 1]] {
+            visited += 1;
+            let t = self.triangles[idx];
+            if contains(self.points[t[0]], self.points[t[1]], self.points[t[2]], p) {
+                return Location { triangle: Some(idx), visited };
+            }
+        }
+        Location { triangle: None, visited }
+    }
+}
+
+/// Node of the history DAG: a triangle that was part of the triangulation at some point of the construction.
+#[derive(Clone, Debug)]
+struct Node {
+    /// Counter-clockwise vertices
+    vertices: [usize; 3],
+    /// Triangles that replaced this one, which cover it, empty for the triangles of the final triangulation
+    children: Vec<usize>,
+    /// Node across each edge while the triangle is in the triangulation, the edge `i` going from vertex `i` to vertex `i + 1`
+    neighbours: [Option<usize>; 3],
+}
+
+/// Delaunay triangulation built by inserting the points in random order into a large enclosing triangle,
+/// keeping every triangle ever created in a directed acyclic graph: a destroyed triangle points to those that replaced it.
+/// Locating a point descends from the enclosing triangle through the triangles containing it, O(log n) expected nodes.
+pub struct HistoryDag {
+    /// The input points, then the 3 vertices of the enclosing triangle
+    vertices: Vec<Vec2>,
+    nodes: Vec<Node>,
+    /// Final triangles without a vertex of the enclosing triangle, as node indices, and the triangle of each node
+    triangles: Vec<usize>,
+    triangle_of: Vec<Option<usize>>,
+}
+
+impl HistoryDag {
+    /// Distance from the center of the points to the vertices of the enclosing triangle, relative to their extent
+    const ENCLOSING_SCALE: f32 = 1e3;
+
+    pub fn new(points: &[Vec2]) -> Self {
+        let _span = span!("History DAG");
+        let bounds = Rect::from_points(points).unwrap_or_else(|| Rect::new(Vec2::new(0.0, 0.0), Vec2::new(0.0, 0.0)));
+        let (center, size) = (bounds.center(), bounds.width().max(bounds.height()).max(1e-3) * Self::ENCLOSING_SCALE);
+        let mut vertices = points.to_vec();
+        vertices.extend_from_slice(&[
+            Vec2::new(center.x - size, center.y - size),
+            Vec2::new(center.x + size, center.y - size),
+            Vec2::new(center.x, center.y + size),
+        ]);
+        let n = points.len();
+        let mut dag = Self {
+            vertices,
+            nodes: vec![ Node { vertices: [ n, n + 1, n + 2 ], children: Vec::new(), neighbours: [ None; 3 ] } ],
+            triangles: Vec::new(),
+            triangle_of: Vec::new(),
+        };
+
+        // Fixed xorshift shuffle, so that the construction is reproducible
+        let mut order = (0..n).collect::<Vec<_>>();
+        XorShift::default().shuffle(&mut order);
+        for p in order {
+            dag.insert(p);
+        }
+
+        dag.triangle_of = vec![ None; dag.nodes.len() ];
+        for (idx, node) in dag.nodes.iter().enumerate() {
+            if node.children.is_empty() && node.vertices.iter().all(|&v| v < n) {
+                dag.triangle_of[idx] = Some(dag.triangles.len());
+                dag.triangles.push(idx);
+            }
+        }
+        dag
+    }
+
+    /// Returns the Delaunay triangulation of the points, 3 indices per counter-clockwise triangle.
+    pub fn indices(&self) -> Vec<usize> {
+        self.triangles.iter().flat_map(|&node| self.nodes[node].vertices.iter().cloned()).collect()
+    }
+
+    /// Returns the number of nodes of the DAG, every triangle created during the construction.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Descends from the enclosing triangle to the triangle of [`indices()`](#method.indices) containing `p`.
+    pub fn locate(&self, p: Vec2) -> Location {
+        let (node, visited) = self.descend(p);
+        Location {
+            triangle: self.triangle_of[node],
+            visited,
+        }
+    }
+
+    /// Returns the leaf containing `p`, and the number of nodes tested.
+    fn descend(&self, p: Vec2) -> (usize, usize) {
+        let mut current = 0;
+        let mut visited = 1;
+        while !self.nodes[current].children.is_empty() {
+            let children = &self.nodes[current].children;
+            visited += children.len();
+            // Rounding can leave a point on an edge out of every child, the closest one is taken then
+            current = children.iter()
+                        .cloned()
+                        .max_by(|&a, &b| self.margin(a, p).total_cmp(&self.margin(b, p)))
+                        .expect("No child");
+        }
+        (current, visited)
+    }
+
+    /// Returns the smallest orientation of `p` relative to the edges of the triangle of `node`, not negative if it contains `p`.
+    fn margin(&self, node: usize, p: Vec2) -> f64 {
+        let [ a, b, c ] = self.nodes[node].vertices.map(|v| self.vertices[v]);
+        Vec2::orientation(a, b, p).min(Vec2::orientation(b, c, p)).min(Vec2::orientation(c, a, p))
+    }
+
+    /// Inserts the point `p`, splitting the triangle or the edge it lies on, then restores the Delaunay criterion with flips.
+    fn insert(&mut self, p: usize) {
+        let point = self.vertices[p];
+        let (leaf, _) = self.descend(point);
+        let vertices = self.nodes[leaf].vertices;
+        if vertices.iter().any(|&v| self.vertices[v] == point) {
+            return; // Duplicate point
+        }
+
+        match (0..3).find(|&i| Vec2::orientation(self.vertices[vertices[i]], self.vertices[vertices[(i + 1) % 3]], point) == 0.0) {
+            None => {
+                // (a, b, c) becomes (a, b, p), (b, c, p), (c, a, p)
+                let [ a, b, c ] = vertices;
+                let outer = self.nodes[leaf].neighbours;
+                let first = self.nodes.len();
+                let new = [ first, first + 1, first + 2 ];
+                for (i, &(u, v)) in [ (a, b), (b, c), (c, a) ].iter().enumerate() {
+                    self.nodes.push(Node {
+                        vertices: [ u, v, p ],
+                        children: Vec::new(),
+                        neighbours: [ outer[i], Some(new[(i + 1) % 3]), Some(new[(i + 2) % 3]) ],
+                    });
+                    self.replace_neighbour(outer[i], leaf, new[i]);
+                }
+                self.nodes[leaf].children = new.to_vec();
+                for &node in &new {
+                    self.legalize(node);
+                }
+            },
+            Some(i) => {
+                // On the edge (a, b) shared with the triangle (b, a, d): (a, b, c) becomes (b, c, p), (c, a, p)
+                // and (b, a, d) becomes (a, d, p), (d, b, p)
+                let (a, b, c) = (vertices[i], vertices[(i + 1) % 3], vertices[(i + 2) % 3]);
+                let outer = self.nodes[leaf].neighbours;
+                let other = match outer[i] {
+                    Some(other) => other,
+                    None => return, // On the enclosing triangle, impossible for the input points
+                };
+                let j = self.edge_index(other, b, a);
+                let other_vertices = self.nodes[other].vertices;
+                let other_outer = self.nodes[other].neighbours;
+                let d = other_vertices[(j + 2) % 3];
+
+                let first = self.nodes.len();
+                let new = [ first, first + 1, first + 2, first + 3 ];
+                let triangles = [
+                    ([ b, c, p ], [ outer[(i + 1) % 3], Some(new[1]), Some(new[3]) ]),
+                    ([ c, a, p ], [ outer[(i + 2) % 3], Some(new[2]), Some(new[0]) ]),
+                    ([ a, d, p ], [ other_outer[(j + 1) % 3], Some(new[3]), Some(new[1]) ]),
+                    ([ d, b, p ], [ other_outer[(j + 2) % 3], Some(new[0]), Some(new[2]) ]),
+                ];
+                for &(vertices, neighbours) in &triangles {
+                    self.nodes.push(Node { vertices, children: Vec::new(), neighbours });
+                }
+                self.replace_neighbour(outer[(i + 1) % 3], leaf, new[0]);
+                self.replace_neighbour(outer[(i + 2) % 3], leaf, new[1]);
+                self.replace_neighbour(other_outer[(j + 1) % 3], other, new[2]);
+                self.replace_neighbour(other_outer[(j + 2) % 3], other, new[3]);
+                self.nodes[leaf].children = vec![ new[0], new[1] ];
+                self.nodes[other].children = vec![ new[2], new[3] ];
+                for &node in &new {
+                    self.legalize(node);
+                }
+            },
+        }
+    }
+
+    /// Flips the edge of `node` opposite its last vertex, the inserted point, while the triangle across it
+    /// has its opposite vertex in the circumcircle. The new triangles are legalized in turn.
+    fn legalize(&mut self, node: usize) {
+        let mut stack = vec![ node ];
+        while let Some(node) = stack.pop() {
+            if !self.nodes[node].children.is_empty() {
+                continue;
+            }
+            let [ a, b, p ] = self.nodes[node].vertices;
+            let outer = self.nodes[node].neighbours;
+            let other = match outer[0] {
+                Some(other) => other,
+                None => continue,
+            };
+            let j = self.edge_index(other, b, a);
+            let other_outer = self.nodes[other].neighbours;
+            let d = self.nodes[other].vertices[(j + 2) % 3];
+            if !self.is_illegal(a, b, p, d) {
+                continue;
+            }
+
+            // (a, b, p) and (b, a, d) become (a, d, p) and (d, b, p)
+            let first = self.nodes.len();
+            self.nodes.push(Node { vertices: [ a, d, p ], children: Vec::new(), neighbours: [ other_outer[(j + 1) % 3], Some(first + 1), outer[2] ] });
+            self.nodes.push(Node { vertices: [ d, b, p ], children: Vec::new(), neighbours: [ other_outer[(j + 2) % 3], outer[1], Some(first) ] });
+            self.replace_neighbour(other_outer[(j + 1) % 3], other, first);
+            self.replace_neighbour(outer[2], node, first);
+            self.replace_neighbour(other_outer[(j + 2) % 3], other, first + 1);
+            self.replace_neighbour(outer[1], node, first + 1);
+            self.nodes[node].children = vec![ first, first + 1 ];
+            self.nodes[other].children = vec![ first, first + 1 ];
+            stack.push(first);
+            stack.push(first + 1);
+        }
+    }
+
+    /// Returns true if the edge (a, b) of the triangle (a, b, p) must be flipped, `d` being the opposite vertex across it.
+    /// The vertices of the enclosing triangle are treated as if they were infinitely far: the in-circle test
+    /// with their real positions would break down, and would keep edges to them that belong to the hull.
+    fn is_illegal(&self, a: usize, b: usize, p: usize, d: usize) -> bool {
+        let n = self.vertices.len() - 3;
+        let [ pa, pb, pp, pd ] = [ a, b, p, d ].map(|v| self.vertices[v]);
+        match (a >= n, b >= n, d >= n) {
+            (false, false, false) => in_circumcircle(pa, pb, pp, pd),
+            // A circle through 3 points contains no point at infinity
+            (false, false, true) | (true, true, _) => false,
+            // With one end at infinity the circle becomes a half-plane, the edge is flipped whenever the quadrilateral is convex.
+            // Between two edges to the enclosing triangle, the one to its vertex of smallest index is preferred
+            _ => {
+                let convex = Vec2::orientation(pa, pd, pp) > 0.0 && Vec2::orientation(pd, pb, pp) > 0.0;
+                convex && (d < n || d < a.max(b))
+            },
+        }
+    }
+
+    /// Returns the index of the edge (u, v) in the triangle of `node`.
+    fn edge_index(&self, node: usize, u: usize, v: usize) -> usize {
+        let vertices = self.nodes[node].vertices;
+        (0..3).find(|&i| vertices[i] == u && vertices[(i + 1) % 3] == v).expect("Edge not in the triangle")
+    }
+
+    fn replace_neighbour(&mut self, node: Option<usize>, from: usize, to: usize) {
+        if let Some(node) = node {
+            for neighbour in self.nodes[node].neighbours.iter_mut().filter(|n| **n == Some(from)) {
+                *neighbour = Some(to);
+            }
+        }
+    }
+}
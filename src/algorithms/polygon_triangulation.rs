@@ -0,0 +1,78 @@
+//! Triangulation of simple polygons by ear clipping. An ear is a convex vertex whose triangle with its two neighbours
+//! contains no other vertex: the diagonal between the neighbours lies inside the polygon, and cutting the ear off
+//! leaves a simple polygon with one vertex less. Every simple polygon with more than 3 vertices has two ears
+//! (Meisters), so cutting ears until a triangle is left triangulates it with n - 3 diagonals.
+
+use crate::math::{ Vec2, polygon };
+
+use alloc::vec::Vec;
+
+/// Returns the triangulation of the simple polygon, clockwise or counter-clockwise, as 3 indices into `polygon`
+/// per counter-clockwise triangle. Vertices on the line through their neighbours are kept, in triangles of positive area,
+/// and consecutive vertices at the same position count as one. A polygon that is not simple is only triangulated
+/// until no ear is left.
+///
+/// The remaining vertices form a linked list walked around until an ear is found, so that each ear is searched
+/// where the last one was cut. Testing a vertex is linear, the whole triangulation quadratic in most cases.
+pub fn triangulate_polygon(polygon: &[Vec2]) -> Vec<usize> {
+    let _span = span!("Ear clipping");
+    let n = polygon.len();
+    if n < 3 {
+        return Vec::new();
+    }
+
+    // Neighbours of the vertices not cut off yet, counter-clockwise
+    let (mut prev, mut next): (Vec<_>, Vec<_>) = if polygon::signed_area(polygon) >= 0.0 {
+        (0..n).map(|i| ((i + n - 1) % n, (i + 1) % n)).unzip()
+    } else {
+        (0..n).map(|i| ((i + 1) % n, (i + n - 1) % n)).unzip()
+    };
+
+    let mut triangles = Vec::with_capacity(3 * (n - 2));
+    let (mut remaining, mut i, mut tested) = (n, 0, 0);
+    while remaining > 3 {
+        let (a, c) = (prev[i], next[i]);
+        // Consecutive vertices at the same position count as one
+        let duplicate = polygon[i] == polygon[c];
+        if duplicate || is_ear(polygon, &next, a, i, c) {
+            if !duplicate {
+                triangles.extend_from_slice(&[ a, i, c ]);
+            }
+            next[a] = c;
+            prev[c] = a;
+            remaining -= 1;
+            i = c;
+            tested = 0;
+        } else {
+            // Only a polygon that is not simple has no ear
+            tested += 1;
+            if tested > remaining {
+                return triangles;
+            }
+            i = next[i];
+        }
+    }
+    let (a, c) = (prev[i], next[i]);
+    if Vec2::orientation(polygon[a], polygon[i], polygon[c]) > 0.0 {
+        triangles.extend_from_slice(&[ a, i, c ]);
+    }
+    triangles
+}
+
+/// Returns true if `b` is an ear between `a` and `c`: strictly convex, with no other remaining vertex
+/// inside of the triangle or on its boundary.
+fn is_ear(polygon: &[Vec2], next: &[usize], a: usize, b: usize, c: usize) -> bool {
+    let (pa, pb, pc) = (polygon[a], polygon[b], polygon[c]);
+    if Vec2::orientation(pa, pb, pc) <= 0.0 {
+        return false;
+    }
+    let mut v = next[c];
+    while v != a {
+        let p = polygon[v];
+        if Vec2::orientation(pa, pb, p) >= 0.0 && Vec2::orientation(pb, pc, p) >= 0.0 && Vec2::orientation(pc, pa, p) >= 0.0 {
+            return false;
+        }
+        v = next[v];
+    }
+    true
+}
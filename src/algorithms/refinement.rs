@@ -0,0 +1,187 @@
+//! Delaunay refinement driven by a sizing field: points are inserted at the circumcenters of the triangles
+//! that are too large for the target edge length around them, until every triangle fits.
+//! The target lengths are sampled on a background grid, so they can vary over the plane to grade the mesh.
+
+use super::{ Incremental2dTriangulation, GrahamScan, hull_positions };
+use crate::{
+    math::{ Vec2, Rect, Circle, float, polygon },
+    spatial::SpatialHash,
+    task::Progress,
+};
+
+use alloc::{ vec, vec::Vec };
+
+/// Smallest target edge length, so that a field painted down to zero still lets the refinement end
+pub const MIN_SIZE: f32 = 1e-3;
+
+/// Target edge lengths on a grid of square cells, stored row by row from the top left node like a [`DistanceField`](super::DistanceField).
+/// Between the nodes the length is interpolated bilinearly, out of the grid it is the one of the closest node.
+#[derive(Clone, Debug)]
+pub struct SizeField {
+    /// Position of the top left node, with the smallest x and the largest y
+    pub origin: Vec2,
+    /// Distance between neighbour nodes
+    pub cell: f32,
+    pub width: usize,
+    pub height: usize,
+    pub values: Vec<f32>,
+}
+
+impl SizeField {
+    /// Creates a grid covering `bounds` with `resolution` nodes along its longest side, all of them with the length `size`.
+    /// Returns `None` if `bounds` is empty or `resolution` is less than 2.
+    pub fn uniform(bounds: Rect, resolution: usize, size: f32) -> Option<Self> {
+        let (size_x, size_y) = (bounds.width(), bounds.height());
+        if resolution < 2 || size_x <= 0.0 || size_y <= 0.0 {
+            return None;
+        }
+        let cell = size_x.max(size_y) / (resolution - 1) as f32;
+        let nodes = |size: f32| float::round(size / cell) as usize + 1;
+        let (width, height) = (nodes(size_x), nodes(size_y));
+        Some(Self {
+            origin: Vec2::new(bounds.min.x, bounds.max.y),
+            cell,
+            width,
+            height,
+            values: vec![ size.max(MIN_SIZE); width * height ],
+        })
+    }
+
+    /// Returns the position of the node on column `x` and row `y`, rows go down from the top.
+    pub fn position(&self, x: usize, y: usize) -> Vec2 {
+        Vec2::new(self.origin.x + x as f32 * self.cell, self.origin.y - y as f32 * self.cell)
+    }
+
+    pub fn value(&self, x: usize, y: usize) -> f32 {
+        self.values[y * self.width + x]
+    }
+
+    /// Returns the target edge length at `p`.
+    pub fn size_at(&self, p: Vec2) -> f32 {
+        // Continuous column and row, clamped to the grid
+        let fx = ((p.x - self.origin.x) / self.cell).max(0.0).min((self.width - 1) as f32);
+        let fy = ((self.origin.y - p.y) / self.cell).max(0.0).min((self.height - 1) as f32);
+        let (x, y) = ((fx as usize).min(self.width - 2), (fy as usize).min(self.height - 2));
+        let (tx, ty) = (fx - x as f32, fy - y as f32);
+        let top = self.value(x, y) * (1.0 - tx) + self.value(x + 1, y) * tx;
+        let bottom = self.value(x, y + 1) * (1.0 - tx) + self.value(x + 1, y + 1) * tx;
+        top * (1.0 - ty) + bottom * ty
+    }
+
+    /// Moves the lengths of the nodes within `radius` of `center` towards `size`, by `strength` between 0 and 1
+    /// at the center and less towards the edge of the brush.
+    pub fn paint(&mut self, center: Vec2, radius: f32, size: f32, strength: f32) {
+        let size = size.max(MIN_SIZE);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let distance = (&self.position(x, y) - &center).length();
+                if distance >= radius {
+                    continue;
+                }
+                let weight = strength * (1.0 - distance / radius);
+                let value = &mut self.values[y * self.width + x];
+                *value += (size - *value) * weight;
+            }
+        }
+    }
+}
+
+/// Result of a refinement.
+#[derive(Clone, Debug)]
+pub struct Refinement {
+    /// The input points first, then the inserted ones
+    pub points: Vec<Vec2>,
+    /// Delaunay triangulation of the points, 3 indices per triangle
+    pub indices: Vec<usize>,
+    /// Number of rounds of insertions
+    pub rounds: usize,
+}
+
+/// Refines the Delaunay triangulation of `points` until the longest edge of every triangle is at most
+/// the target length of `field` at its centroid, or until there are `max_points` points.
+/// Each round triangulates the points again and inserts the circumcenters of the triangles that are too large,
+/// or the middle of their longest edge when the circumcenter is out of the convex hull, which keeps the domain unchanged.
+/// An insertion is kept at half the target length from every vertex and from the other insertions of its round,
+/// so that it does not create tiny edges, nor a duplicate vertex the triangulation would have to skip.
+/// Returns `None` if the computation was cancelled.
+pub fn refine(points: &[Vec2], field: &SizeField, max_points: usize, progress: &Progress) -> Option<Refinement> {
+    let _span = span!("Delaunay refinement");
+    let hull = hull_positions(points, &GrahamScan::scan(points));
+    let mut points = points.to_vec();
+    let mut indices = Incremental2dTriangulation::delaunay(&points);
+    let mut rounds = 0;
+    progress.set_total(max_points.max(1));
+
+    // Every vertex in cells as large as the largest spacing, so that the close ones are in the cells around
+    let largest = field.values.iter().fold(MIN_SIZE, |largest, &size| largest.max(size));
+    let mut grid = SpatialHash::new(0.5 * largest, max_points.max(points.len()));
+    for &p in &points {
+        grid.insert(p);
+    }
+
+    while points.len() < max_points {
+        if progress.is_cancelled() {
+            return None;
+        }
+        progress.set_done(points.len());
+
+        // Triangles too large for the field, the largest first relative to their target
+        let mut candidates = indices
+            .chunks_exact(3)
+            .filter_map(|t| {
+                let (a, b, c) = (points[t[0]], points[t[1]], points[t[2]]);
+                let centroid = &(&(&a + &b) + &c) * (1.0 / 3.0);
+                let size = field.size_at(centroid);
+                let (longest, (p, q)) = [ (a, b), (b, c), (c, a) ]
+                    .iter()
+                    .map(|&(p, q)| ((&q - &p).length(), (p, q)))
+                    .fold((0.0, (a, b)), |longest, edge| if edge.0 > longest.0 { edge } else { longest });
+                if longest <= size {
+                    return None;
+                }
+                // The middle of the longest edge is as far as possible from the vertices,
+                // a point near the boundary would leave a sliver along it
+                let insertion = match Circle::circumscribed(a, b, c) {
+                    Some(circle) if polygon::contains(&hull, circle.center) => circle.center,
+                    _ => &(&p + &q) * 0.5,
+                };
+                // No vertex is closer to the circumcenter than half the longest edge, so more than half the size of the triangle
+                Some((longest / size, insertion, size.min(field.size_at(insertion))))
+            })
+            .collect::<Vec<_>>();
+        if candidates.is_empty() {
+            break;
+        }
+        candidates.sort_by(|x, y| y.0.total_cmp(&x.0));
+
+        let mut inserted = Vec::<(Vec2, f32)>::new();
+        for (_, p, size) in candidates {
+            if points.len() + inserted.len() >= max_points {
+                break;
+            }
+            // The vertices of the triangle are in the grid too, the insertions of the round also keep their own spacing
+            let spacing = 0.5 * size.max(MIN_SIZE);
+            let far = grid.around(p).into_iter().all(|idx| match idx.checked_sub(points.len()) {
+                Some(round_idx) => (&p - &inserted[round_idx].0).length() >= spacing.max(0.5 * inserted[round_idx].1),
+                None => (&p - &points[idx]).length() >= spacing,
+            });
+            if far {
+                grid.insert(p);
+                inserted.push((p, size));
+            }
+        }
+        if inserted.is_empty() {
+            break;
+        }
+        points.extend(inserted.into_iter().map(|(p, _)| p));
+        indices = Incremental2dTriangulation::delaunay(&points);
+        rounds += 1;
+    }
+
+    progress.set_done(max_points.max(1));
+    Some(Refinement {
+        points,
+        indices,
+        rounds,
+    })
+}
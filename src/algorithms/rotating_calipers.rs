@@ -0,0 +1,191 @@
+use super::GrahamScan;
+use crate::math::{ Vec2, float };
+
+use alloc::{ vec, vec::Vec, collections::BTreeSet };
+
+/// Relative tolerance used to detect the edges parallel to the one the calipers are flush with
+const EPSILON: f32 = 1e-5;
+
+/// A position of the rotating calipers: two parallel support lines on both sides of the hull,
+/// the first one flush with an edge and the second one touching the vertex that is the farthest from it.
+/// The positions refer to the vertices of the hull, not to the input points.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Caliper {
+    /// Position of the first vertex of the edge in the hull, the edge ends at the next vertex
+    pub edge: usize,
+    /// Position of the vertex touched by the opposite line
+    pub antipode: usize,
+    /// Outward unit normal of the edge
+    pub normal: Vec2,
+    /// Value of the support function of the hull in the direction of the normal,
+    /// the signed distance from the origin to the line flush with the edge
+    pub support: f32,
+    /// Distance between the two lines, the width of the hull in the direction of the normal
+    pub width: f32,
+    /// The smallest rectangle containing the hull that has a side flush with the edge, counter-clockwise
+    pub rectangle: [Vec2; 4],
+}
+
+impl Caliper {
+    /// Returns the area of the enclosing rectangle flush with the edge.
+    pub fn area(&self) -> f32 {
+        (&self.rectangle[1] - &self.rectangle[0]).length() * self.width
+    }
+}
+
+/// Rotating calipers around the convex hull of a set of 2D points: a pair of parallel lines is rotated
+/// around the hull, pivoting on a vertex at each side. The positions where a line is flush with an edge
+/// give the width of the hull, its antipodal pairs of vertices, its diameter and its minimum-area enclosing rectangle.
+#[derive(Default)]
+pub struct RotatingCalipers {
+    points: Vec<Vec2>,
+    /// Indices of the points on the hull, in counter-clockwise order
+    hull: Vec<usize>,
+    calipers: Vec<Caliper>,
+    /// Pairs of positions in the hull of the vertices that admit parallel support lines, smallest position first
+    antipodal_pairs: Vec<(usize, usize)>,
+}
+
+impl RotatingCalipers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn points(&self) -> &[Vec2] {
+        &self.points
+    }
+
+    /// Returns the indices of the points on the convex hull, in counter-clockwise order.
+    pub fn hull(&self) -> &[usize] {
+        &self.hull
+    }
+
+    /// Returns the position of the calipers for each edge of the hull, in counter-clockwise order.
+    pub fn calipers(&self) -> &[Caliper] {
+        &self.calipers
+    }
+
+    /// Returns the antipodal pairs of the hull, as positions in the hull with the smallest one first.
+    pub fn antipodal_pairs(&self) -> &[(usize, usize)] {
+        &self.antipodal_pairs
+    }
+
+    /// Replaces the input points and rotates the calipers around their hull.
+    pub fn set_points(&mut self, points: Vec<Vec2>) {
+        let _span = span!("Rotating calipers");
+        self.points = points;
+        self.hull = GrahamScan::scan(&self.points);
+        let hull = self.hull.iter().map(|&idx| self.points[idx]).collect::<Vec<_>>();
+        self.calipers = Self::rotate(&hull);
+        self.antipodal_pairs = Self::pairs(&hull, &self.calipers);
+    }
+
+    pub fn clear(&mut self) {
+        self.set_points(Vec::new());
+    }
+
+    /// Returns the position of the calipers where the hull is the thinnest.
+    pub fn minimum_width(&self) -> Option<&Caliper> {
+        self.calipers.iter().min_by(|a, b| a.width.total_cmp(&b.width))
+    }
+
+    /// Returns the position of the calipers whose enclosing rectangle has the smallest area,
+    /// one of the sides of the minimum-area rectangle is always flush with an edge of the hull.
+    pub fn minimum_area(&self) -> Option<&Caliper> {
+        self.calipers.iter().min_by(|a, b| a.area().total_cmp(&b.area()))
+    }
+
+    /// Returns the antipodal pair of vertices that are the farthest apart, and their distance.
+    pub fn diameter(&self) -> Option<((usize, usize), f32)> {
+        let position = |pos: usize| self.points[self.hull[pos]];
+        self.antipodal_pairs
+            .iter()
+            .map(|&(a, b)| ((a, b), (&position(b) - &position(a)).length()))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    /// Rotates the calipers around `hull`, a convex polygon in counter-clockwise order,
+    /// and returns their position for each of its edges. Every support line only moves forward,
+    /// so they go around the hull once in total.
+    pub fn rotate(hull: &[Vec2]) -> Vec<Caliper> {
+        let n = hull.len();
+        if n < 2 {
+            return Vec::new();
+        }
+
+        // Moves `pos` forward as long as the next vertex is farther in the direction `f`
+        let advance = |mut pos: usize, f: &dyn Fn(Vec2) -> f32| {
+            for _ in 0..n {
+                let next = (pos + 1) % n;
+                if f(hull[next]) <= f(hull[pos]) {
+                    break;
+                }
+                pos = next;
+            }
+            pos
+        };
+
+        let mut calipers = Vec::with_capacity(n);
+        let (mut right, mut antipode, mut left) = (1, 1, 1);
+        for i in 0..n {
+            let (a, b) = (hull[i], hull[(i + 1) % n]);
+            let direction = (&b - &a).normalized();
+            let normal = Vec2::new(direction.y, -direction.x); // The hull is on the left of its edges
+
+            // Farthest vertices along the edge, away from it and back along it
+            right = advance(if i == 0 { (i + 1) % n } else { right }, &|p| p.dot(direction));
+            antipode = advance(if i == 0 { right } else { antipode }, &|p| -p.dot(normal));
+            left = advance(if i == 0 { antipode } else { left }, &|p| -p.dot(direction));
+
+            let support = a.dot(normal);
+            let width = support - hull[antipode].dot(normal);
+            let (start, end) = (hull[left].dot(direction), hull[right].dot(direction));
+            let corner = |s: f32, t: f32| &(s * &direction) + &(t * &normal);
+            calipers.push(Caliper {
+                edge: i,
+                antipode,
+                normal,
+                support,
+                width,
+                rectangle: [
+                    corner(start, support),
+                    corner(end, support),
+                    corner(end, support - width),
+                    corner(start, support - width),
+                ],
+            });
+        }
+        calipers
+    }
+
+    /// Returns the antipodal pairs found by the calipers: the ends of each edge with the opposite vertex,
+    /// and with the next one too when the opposite edge is parallel.
+    fn pairs(hull: &[Vec2], calipers: &[Caliper]) -> Vec<(usize, usize)> {
+        let n = hull.len();
+        let mut pairs = BTreeSet::new();
+        for caliper in calipers {
+            let (a, b) = (caliper.edge, (caliper.edge + 1) % n);
+            let mut antipodes = vec![ caliper.antipode ];
+            let next = (caliper.antipode + 1) % n;
+            let distance = caliper.support - hull[next].dot(caliper.normal);
+            if float::abs(distance - caliper.width) <= EPSILON * caliper.width {
+                antipodes.push(next);
+            }
+            for &c in &antipodes {
+                for &v in &[ a, b ] {
+                    if v != c {
+                        pairs.insert((v.min(c), v.max(c)));
+                    }
+                }
+            }
+        }
+        pairs.into_iter().collect()
+    }
+}
+
+/// Returns the support function of the points in `direction`, the largest dot product of a point with it:
+/// the line orthogonal to a unit `direction` at this distance from the origin touches the points
+/// and has all of them on the same side.
+pub fn support(points: &[Vec2], direction: Vec2) -> Option<f32> {
+    points.iter().map(|p| p.dot(direction)).max_by(|a, b| a.total_cmp(b))
+}
@@ -0,0 +1,183 @@
+//! Intersections of n segments with the Bentley-Ottmann sweep: a vertical line sweeps the plane from left to right,
+//! stopping at the ends of the segments and at their intersections, kept sorted in an event queue.
+//! The status holds the segments crossing the sweep line, ordered from bottom to top. Two segments can only intersect
+//! after they become neighbours in the status, so only the neighbours are tested when it changes.
+
+use crate::math::{ Vec2, Segment2, float };
+
+use alloc::{ vec, vec::Vec, collections::BTreeMap };
+use core::cmp::Ordering;
+
+/// Tolerance relative to the extent of the segments, under which two events are the same point
+/// and a segment passes through an event
+const TOLERANCE: f32 = 1e-5;
+
+/// Point where two input segments or more meet.
+#[derive(Clone, PartialEq, Debug)]
+pub struct SegmentIntersection {
+    pub point: Vec2,
+    /// Indices of the segments through the point, in increasing order
+    pub segments: Vec<usize>,
+}
+
+impl SegmentIntersection {
+    /// Returns each pair of segments meeting at the point, the smallest index first.
+    pub fn pairs(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.segments
+            .iter()
+            .enumerate()
+            .flat_map(move |(i, &a)| self.segments[i + 1..].iter().map(move |&b| (a, b)))
+    }
+}
+
+/// Event point of the sweep, ordered by x then by y: the sweep line visits the points of a vertical line from bottom to top.
+#[derive(Copy, Clone, Debug)]
+struct Event(Vec2);
+
+impl Ord for Event {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let cmp = |a: f32, b: f32| a.partial_cmp(&b).unwrap_or(Ordering::Equal);
+        cmp(self.0.x, other.0.x).then(cmp(self.0.y, other.0.y))
+    }
+}
+
+impl PartialOrd for Event {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Event {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Event {}
+
+struct Sweep<'a> {
+    /// The input segments, each from its left end to its right end
+    segments: &'a [Segment2],
+    epsilon: f32,
+    /// Segments starting at each event, empty for the right ends and the intersections
+    queue: BTreeMap<Event, Vec<usize>>,
+    /// Segments crossing the sweep line, from bottom to top
+    status: Vec<usize>,
+    intersections: Vec<SegmentIntersection>,
+}
+
+/// Returns the points where the segments meet, in the order of the sweep: by increasing x, then by increasing y.
+/// Segments touching at an end intersect, and collinear segments that overlap are reported at the ends of their common part.
+/// Segments without length are ignored.
+///
+/// The status is a sorted vector: finding a segment is logarithmic, but inserting one moves those above it.
+pub fn bentley_ottmann(segments: &[Segment2]) -> Vec<SegmentIntersection> {
+    let _span = span!("Bentley-Ottmann");
+    let segments = segments.iter()
+                        .map(|s| if Event(s.b) < Event(s.a) { Segment2::new(s.b, s.a) } else { *s })
+                        .collect::<Vec<_>>();
+    let extent = segments.iter()
+                        .flat_map(|s| vec![ s.a, s.b ])
+                        .map(|p| float::abs(p.x).max(float::abs(p.y)))
+                        .fold(0.0, f32::max);
+    let mut sweep = Sweep {
+        segments: &segments,
+        epsilon: TOLERANCE * extent.max(1e-6),
+        queue: BTreeMap::new(),
+        status: Vec::new(),
+        intersections: Vec::new(),
+    };
+    for (idx, s) in segments.iter().enumerate() {
+        if s.as_vec2().sqr_length() > 0.0 {
+            sweep.insert_event(s.a, Some(idx));
+            sweep.insert_event(s.b, None);
+        }
+    }
+
+    while let Some(&event) = sweep.queue.keys().next() {
+        let upper = sweep.queue.remove(&event).unwrap_or_default();
+        sweep.handle_event(event.0, upper);
+    }
+    sweep.intersections
+}
+
+impl<'a> Sweep<'a> {
+    fn is_near(&self, p: Vec2, q: Vec2) -> bool {
+        float::abs(p.x - q.x) <= self.epsilon && float::abs(p.y - q.y) <= self.epsilon
+    }
+
+    /// Adds an event at `p`, or merges it with an event closer than the tolerance. `upper` is a segment starting at `p`.
+    fn insert_event(&mut self, p: Vec2, upper: Option<usize>) {
+        let range = Event(Vec2::new(p.x - self.epsilon, f32::NEG_INFINITY))..=Event(Vec2::new(p.x + self.epsilon, f32::INFINITY));
+        let near = self.queue.range(range).map(|(event, _)| *event).find(|event| self.is_near(event.0, p));
+        let segments = self.queue.entry(near.unwrap_or(Event(p))).or_default();
+        segments.extend(upper);
+    }
+
+    /// Returns the height of the segment where it crosses the vertical line through `p`, or at its closest end.
+    /// Vertical segments cross it along their whole length, the height of `p` is returned if it is on them.
+    fn height(&self, idx: usize, p: Vec2) -> f32 {
+        let s = &self.segments[idx];
+        let v = s.as_vec2();
+        if float::abs(v.x) <= self.epsilon {
+            return p.y.max(s.a.y).min(s.b.y);
+        }
+        let x = p.x.max(s.a.x).min(s.b.x);
+        s.a.y + (x - s.a.x) * v.y / v.x
+    }
+
+    /// Returns whether the segment passes through the event at `p`. An intersection merged with a nearby event
+    /// may be up to the tolerance away from `p` in both directions, so the segments through it are accepted too.
+    fn passes_through(&self, idx: usize, p: Vec2) -> bool {
+        self.segments[idx].distance_to_point(p) <= 2.0 * self.epsilon
+    }
+
+    /// Returns the slope of the segment, which orders the segments leaving the same point from bottom to top.
+    fn slope(&self, idx: usize) -> f32 {
+        let v = self.segments[idx].as_vec2();
+        if float::abs(v.x) <= self.epsilon { f32::INFINITY } else { v.y / v.x }
+    }
+
+    fn handle_event(&mut self, p: Vec2, mut upper: Vec<usize>) {
+        // The segments through `p` are next to each other in the status, around the height of `p`
+        let middle = self.status.partition_point(|&idx| self.height(idx, p) < p.y);
+        let lo = middle - self.status[..middle].iter().rev().take_while(|&&idx| self.passes_through(idx, p)).count();
+        let hi = middle + self.status[middle..].iter().take_while(|&&idx| self.passes_through(idx, p)).count();
+        let through = self.status.drain(lo..hi).collect::<Vec<_>>();
+
+        if through.len() + upper.len() > 1 {
+            let mut segments = through.iter().chain(&upper).cloned().collect::<Vec<_>>();
+            segments.sort_unstable();
+            segments.dedup();
+            self.intersections.push(SegmentIntersection { point: p, segments });
+        }
+
+        // The segments that do not end at `p` go on, swapped as they cross, with those starting at `p`
+        upper.extend(through.into_iter().filter(|&idx| !self.is_near(self.segments[idx].b, p)));
+        upper.sort_by(|&a, &b| self.slope(a).partial_cmp(&self.slope(b)).unwrap_or(Ordering::Equal).then(a.cmp(&b)));
+        let count = upper.len();
+        self.status.splice(lo..lo, upper);
+
+        if count == 0 {
+            if lo > 0 && lo < self.status.len() {
+                self.find_event(self.status[lo - 1], self.status[lo], p);
+            }
+        } else {
+            if lo > 0 {
+                self.find_event(self.status[lo - 1], self.status[lo], p);
+            }
+            if lo + count < self.status.len() {
+                self.find_event(self.status[lo + count - 1], self.status[lo + count], p);
+            }
+        }
+    }
+
+    /// Adds the intersection of two neighbouring segments as an event if it is after the sweep line at `p`.
+    fn find_event(&mut self, a: usize, b: usize, p: Vec2) {
+        if let Some((_, _, q)) = self.segments[a].parametric_intersection(&self.segments[b]) {
+            if Event(q) > Event(p) && !self.is_near(p, q) {
+                self.insert_event(q, None);
+            }
+        }
+    }
+}
@@ -0,0 +1,110 @@
+//! Shortest paths inside a simple polygon. The polygon is triangulated, the triangles between the two points
+//! form a sleeve whose diagonals the path has to cross in order, and the funnel algorithm pulls the path taut
+//! through them: it only bends at the reflex vertices of the polygon.
+
+use super::{ locate_triangle, triangulate_polygon, DualGraph };
+use crate::math::Vec2;
+
+use alloc::{ vec, vec::Vec };
+
+/// Result of [`shortest_path`](fn.shortest_path.html).
+#[derive(Clone, Debug)]
+pub struct ShortestPath {
+    /// From the start to the end, the other points are vertices of the polygon
+    pub path: Vec<Vec2>,
+    /// Triangulation of the polygon, 3 indices into its vertices per counter-clockwise triangle
+    pub triangles: Vec<usize>,
+    /// Indices of the triangles crossed by the path, in order, from the one that contains the start
+    pub sleeve: Vec<usize>,
+}
+
+impl ShortestPath {
+    /// Returns the geodesic distance between the start and the end.
+    pub fn length(&self) -> f32 {
+        self.path.windows(2).map(|link| (&link[1] - &link[0]).length()).sum()
+    }
+}
+
+/// Returns the shortest path from `start` to `end` inside the simple polygon `polygon`, clockwise or counter-clockwise,
+/// or `None` if the polygon has less than 3 vertices or one of the points is outside of it.
+pub fn shortest_path(polygon: &[Vec2], start: Vec2, end: Vec2) -> Option<ShortestPath> {
+    let _span = span!("Shortest path");
+    let triangles = triangulate_polygon(polygon);
+    let (first, last) = (locate_triangle(polygon, &triangles, start)?, locate_triangle(polygon, &triangles, end)?);
+    // The triangles of a simple polygon form a tree in the dual graph, so the only path is the sleeve
+    let sleeve = DualGraph::new(&triangles).path(first, last)?;
+
+    // The diagonals between consecutive triangles, with their left and right ends when going through them.
+    // A counter-clockwise triangle has its inside on the left of its edges, so leaving it through the edge (u, v)
+    // puts v on the left
+    let mut portals = vec![ (start, start) ];
+    for pair in sleeve.windows(2) {
+        let (tri, next) = (&triangles[3 * pair[0]..3 * pair[0] + 3], &triangles[3 * pair[1]..3 * pair[1] + 3]);
+        let k = (0..3).find(|&k| !next.contains(&tri[k]))?;
+        let (u, v) = (tri[(k + 1) % 3], tri[(k + 2) % 3]);
+        portals.push((polygon[v], polygon[u]));
+    }
+    portals.push((end, end));
+
+    Some(ShortestPath {
+        path: funnel(&portals),
+        triangles,
+        sleeve,
+    })
+}
+
+/// Pulls a path taut through the portals, pairs of left and right points, the first and last ones being
+/// the start and the end. The funnel is the cone from its apex to the current portal: each portal narrows it,
+/// and when one side crosses the other, the apex moves to the crossed side which becomes a corner of the path.
+fn funnel(portals: &[(Vec2, Vec2)]) -> Vec<Vec2> {
+    let (start, end) = (portals[0].0, portals[portals.len() - 1].0);
+    let mut path = vec![ start ];
+    let (mut apex, mut left, mut right) = (start, start, start);
+    let (mut left_idx, mut right_idx) = (0, 0);
+
+    let mut i = 1;
+    while i < portals.len() {
+        let (next_left, next_right) = portals[i];
+
+        // The right side moves in if the new point is on its left
+        if Vec2::orientation(apex, right, next_right) >= 0.0 {
+            if apex == right || Vec2::orientation(apex, left, next_right) < 0.0 {
+                right = next_right;
+                right_idx = i;
+            } else {
+                // It crosses the left side, which becomes the new apex. Several diagonals around a reflex vertex
+                // make it the apex again when the funnel restarts from it
+                if path.last() != Some(&left) {
+                    path.push(left);
+                }
+                apex = left;
+                right = apex;
+                right_idx = left_idx;
+                i = left_idx + 1;
+                continue;
+            }
+        }
+
+        if Vec2::orientation(apex, left, next_left) <= 0.0 {
+            if apex == left || Vec2::orientation(apex, right, next_left) > 0.0 {
+                left = next_left;
+                left_idx = i;
+            } else {
+                if path.last() != Some(&right) {
+                    path.push(right);
+                }
+                apex = right;
+                left = apex;
+                left_idx = right_idx;
+                i = right_idx + 1;
+                continue;
+            }
+        }
+        i += 1;
+    }
+
+    if path.last() != Some(&end) {
+        path.push(end);
+    }
+    path
+}
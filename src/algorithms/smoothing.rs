@@ -0,0 +1,116 @@
+//! Smoothing of 2D triangulations: the interior vertices are moved to improve the shape of the triangles,
+//! without changing the connectivity. The vertices on the boundary, those of an edge with a single triangle, stay in place.
+//! A move that would turn an incident triangle over is skipped, so the triangulation stays valid.
+
+use crate::math::Vec2;
+
+use alloc::{ vec, vec::Vec, collections::BTreeMap };
+
+/// Neighbourhood of an interior vertex: each neighbour with the two vertices opposite the edge to it.
+type Ring = Vec<(usize, usize, usize)>;
+
+/// Moves every interior vertex to the centroid of its neighbours, `iterations` times.
+/// Returns the new positions, in the order of `points`. `indices` contains 3 indices into `points` per triangle.
+pub fn laplacian_smoothing(points: &[Vec2], indices: &[usize], iterations: usize) -> Vec<Vec2> {
+    smooth(points, indices, iterations, |points, _, ring| {
+        let sum = ring.iter().fold(Vec2::new(0.0, 0.0), |sum, &(neighbour, _, _)| &sum + &points[neighbour]);
+        &sum * (1.0 / ring.len() as f32)
+    })
+}
+
+/// Angle-based smoothing of Zhou and Shimada: for each neighbour, the vertex is rotated around it
+/// onto the bisector of the angle the neighbour makes with the two vertices around it,
+/// and moved to the mean of these positions, `iterations` times. It evens out the angles of the triangles
+/// and turns fewer of them over than the Laplacian smoothing near concave regions.
+pub fn angle_based_smoothing(points: &[Vec2], indices: &[usize], iterations: usize) -> Vec<Vec2> {
+    smooth(points, indices, iterations, |points, idx, ring| {
+        let p = points[idx];
+        let sum = ring.iter().fold(Vec2::new(0.0, 0.0), |sum, &(neighbour, a, b)| {
+            let n = points[neighbour];
+            let (to_a, to_b) = ((&points[a] - &n).normalized(), (&points[b] - &n).normalized());
+            let mut bisector = &to_a + &to_b;
+            if bisector.is_zero() {
+                bisector = Vec2::new(-to_a.y, to_a.x); // Straight angle, on a straight part of the ring
+            }
+            let offset = &p - &n;
+            // The bisector of the angle that contains the vertex, which can be reflex
+            if bisector.dot(offset) < 0.0 {
+                bisector = -&bisector;
+            }
+            &sum + &(&n + &(&bisector.normalized() * offset.length()))
+        });
+        &sum * (1.0 / ring.len() as f32)
+    })
+}
+
+/// Moves each interior vertex to the position computed by `target` from the current positions, `iterations` times,
+/// unless the move turns one of its triangles over. Degenerate triangles can be opened in either direction.
+fn smooth<F>(points: &[Vec2], indices: &[usize], iterations: usize, target: F) -> Vec<Vec2>
+    where F: Fn(&[Vec2], usize, &Ring) -> Vec2
+{
+    let mut points = points.to_vec();
+    let rings = rings(points.len(), indices);
+    let mut triangles = vec![ Vec::new(); points.len() ];
+    for t in indices.chunks_exact(3) {
+        for &idx in t {
+            triangles[idx].push([ t[0], t[1], t[2] ]);
+        }
+    }
+
+    for _ in 0..iterations {
+        for (idx, ring) in rings.iter().enumerate() {
+            let ring = match ring {
+                Some(ring) => ring,
+                None => continue,
+            };
+            let previous = points[idx];
+            let orientations = triangles[idx].iter().map(|t| orientation(&points, t)).collect::<Vec<_>>();
+            points[idx] = target(&points, idx, ring);
+            let flipped = triangles[idx]
+                .iter()
+                .zip(&orientations)
+                .any(|(t, &before)| before != 0.0 && before * orientation(&points, t) <= 0.0);
+            if flipped {
+                points[idx] = previous;
+            }
+        }
+    }
+    points
+}
+
+/// Returns the twice signed area of the triangle.
+fn orientation(points: &[Vec2], t: &[usize; 3]) -> f64 {
+    Vec2::orientation(points[t[0]], points[t[1]], points[t[2]])
+}
+
+/// Returns the ring of each vertex, or `None` for the vertices on the boundary and those of no triangle.
+fn rings(n: usize, indices: &[usize]) -> Vec<Option<Ring>> {
+    // Vertices opposite each edge, with the smallest index first
+    let mut opposite = BTreeMap::<(usize, usize), Vec<usize>>::new();
+    for t in indices.chunks_exact(3) {
+        for i in 0..3 {
+            let (a, b, c) = (t[i], t[(i + 1) % 3], t[(i + 2) % 3]);
+            opposite.entry((a.min(b), a.max(b))).or_default().push(c);
+        }
+    }
+
+    let mut rings = vec![ Some(Vec::new()); n ];
+    for (&(a, b), others) in &opposite {
+        if let [ c, d ] = others[..] {
+            for &(v, neighbour) in &[ (a, b), (b, a) ] {
+                if let Some(ring) = &mut rings[v] {
+                    ring.push((neighbour, c, d));
+                }
+            }
+        } else {
+            rings[a] = None;
+            rings[b] = None;
+        }
+    }
+    for ring in &mut rings {
+        if ring.as_ref().is_some_and(|ring| ring.is_empty()) {
+            *ring = None;
+        }
+    }
+    rings
+}
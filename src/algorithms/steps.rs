@@ -0,0 +1,56 @@
+use alloc::{ vec, vec::Vec };
+
+use crate::math::Vec2;
+
+/// A change made by an algorithm to the result it is building, indices refer to the input points.
+/// The state reached after a step can be retrieved from the iterator that produced it,
+/// e.g. [`JarvisMarchSteps::hull()`](../jarvis_march/struct.JarvisMarchSteps.html#method.hull).
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Step {
+    /// A point was added at the end of the hull
+    HullPush(usize),
+    /// The last point of the hull was removed
+    HullPop,
+    /// A triangle was added to the triangulation
+    Triangle(usize, usize, usize),
+    /// The edge shared by two triangles was flipped, they were replaced by these two triangles
+    Flip((usize, usize, usize), (usize, usize, usize)),
+}
+
+/// An algorithm that can be run step by step, to animate it or to check properties at every step.
+pub trait Algorithm<'a> {
+    type Steps: Iterator<Item = Step> + 'a;
+
+    /// Starts the algorithm on `points`, nothing is computed until the steps are iterated.
+    fn run(points: &'a [Vec2]) -> Self::Steps;
+}
+
+/// State of an algorithm after a step, recorded to play the steps back and forth.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Snapshot {
+    /// The step that led to this state, `None` for the state before the first step
+    pub step: Option<Step>,
+    /// Points of the hull built so far, in order, the stack of the Graham scan.
+    /// The first point is repeated at the end when the hull is closed
+    pub hull: Vec<usize>,
+    /// Triangles built so far, 3 indices per triangle
+    pub triangles: Vec<usize>,
+    /// The edge being considered: the next hull edge, or the edge a triangle was built on or flipped to
+    pub candidate: Option<(usize, usize)>,
+}
+
+/// Steps whose intermediate states can be recorded.
+pub trait Record: Iterator<Item = Step> {
+    /// Returns the current state, `step` being the step that led to it.
+    fn snapshot(&self, step: Option<Step>) -> Snapshot;
+
+    /// Runs the remaining steps and returns the current state followed by the state after each step.
+    /// Every state is copied, so this needs memory quadratic in the number of points.
+    fn record(&mut self) -> Vec<Snapshot> {
+        let mut snapshots = vec![ self.snapshot(None) ];
+        while let Some(step) = self.next() {
+            snapshots.push(self.snapshot(Some(step)));
+        }
+        snapshots
+    }
+}
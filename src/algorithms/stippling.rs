@@ -0,0 +1,186 @@
+use crate::{
+    math::Vec2,
+    spatial::PointGrid,
+    task::Progress,
+};
+
+use rand::Rng;
+
+/// Density of the stipples over a grid of pixels, fitted in the [-1, 1] square.
+/// The rows are stored from the top one, like those of an image.
+#[derive(Clone, PartialEq, Debug)]
+pub struct DensityMap {
+    width: usize,
+    height: usize,
+    values: Vec<f32>,
+}
+
+impl DensityMap {
+    pub fn new(width: usize, height: usize, values: Vec<f32>) -> Self {
+        assert_eq!(values.len(), width * height);
+        Self {
+            width,
+            height,
+            values,
+        }
+    }
+
+    /// Creates a density map from the pixels of a grayscale image, the darker pixels are the denser.
+    pub fn from_luma(width: usize, height: usize, pixels: &[u8]) -> Self {
+        let values = pixels.iter()
+                        .map(|&luma| 1.0 - luma as f32 / 255.0)
+                        .collect();
+        Self::new(width, height, values)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn value(&self, x: usize, y: usize) -> f32 {
+        self.values[y * self.width + x]
+    }
+
+    /// Returns the map averaged over blocks of pixels so that it is at most `max_size` pixels wide and high,
+    /// each relaxation visits every pixel.
+    pub fn downsampled(&self, max_size: usize) -> Self {
+        let factor = self.width.max(self.height).div_ceil(max_size.max(1));
+        if factor <= 1 {
+            return self.clone();
+        }
+
+        let (width, height) = (self.width.div_ceil(factor), self.height.div_ceil(factor));
+        let mut values = vec![ 0.0; width * height ];
+        let mut counts = vec![ 0; width * height ];
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let idx = (y / factor) * width + x / factor;
+                values[idx] += self.value(x, y);
+                counts[idx] += 1;
+            }
+        }
+        for (value, &count) in values.iter_mut().zip(&counts) {
+            *value /= count as f32;
+        }
+        Self::new(width, height, values)
+    }
+
+    /// Returns the side of a pixel in the [-1, 1] square.
+    pub fn pixel_size(&self) -> f32 {
+        2.0 / self.width.max(self.height).max(1) as f32
+    }
+
+    /// Returns the position of the center of the pixel on column `x` and row `y`,
+    /// the map is centered in the [-1, 1] square and keeps its aspect ratio.
+    pub fn position(&self, x: usize, y: usize) -> Vec2 {
+        let size = self.pixel_size();
+        Vec2::new((x as f32 + 0.5 - self.width as f32 / 2.0) * size,
+                  (self.height as f32 / 2.0 - y as f32 - 0.5) * size)
+    }
+
+    /// Returns `count` random positions distributed according to the density.
+    pub fn sample(&self, count: usize) -> Vec<Vec2> {
+        let mut cumulative = Vec::with_capacity(self.values.len());
+        let mut total = 0.0;
+        for &value in &self.values {
+            total += value.max(0.0);
+            cumulative.push(total);
+        }
+        if total <= 0.0 {
+            return Vec::new();
+        }
+
+        let mut rng = rand::thread_rng();
+        let half = self.pixel_size() / 2.0;
+        (0..count).map(|_| {
+                let target = rng.gen_range(0.0, total);
+                let idx = cumulative.partition_point(|&sum| sum <= target).min(self.values.len() - 1);
+                let center = self.position(idx % self.width, idx / self.width);
+                &center + &Vec2::new(rng.gen_range(-half, half), rng.gen_range(-half, half))
+            })
+            .collect()
+    }
+}
+
+/// Stippling of a density map: points spread like the dots of a drawing, closer together where the density is high.
+/// The points are moved by weighted Lloyd relaxation toward a centroidal Voronoi tessellation,
+/// where each point is the centroid of its Voronoi cell weighted by the density.
+pub struct Stippling {
+    density: DensityMap,
+    points: Vec<Vec2>,
+}
+
+impl Stippling {
+    /// Samples `count` points from the density, they are not relaxed yet.
+    pub fn new(density: DensityMap, count: usize) -> Self {
+        let points = density.sample(count);
+        Self {
+            density,
+            points,
+        }
+    }
+
+    pub fn density(&self) -> &DensityMap {
+        &self.density
+    }
+
+    pub fn points(&self) -> &[Vec2] {
+        &self.points
+    }
+
+    /// Moves every point to the weighted centroid of its Voronoi cell, computed over the pixels of the density map.
+    /// Points whose cell has no density stay in place. Returns the largest displacement.
+    pub fn relax(&mut self) -> f32 {
+        let _span = span!("Lloyd relaxation");
+        let mut sums = vec![ (Vec2::new(0.0, 0.0), 0.0_f32); self.points.len() ];
+        {
+            let grid = PointGrid::new(&self.points);
+            for y in 0..self.density.height {
+                for x in 0..self.density.width {
+                    let weight = self.density.value(x, y);
+                    if weight <= 0.0 {
+                        continue;
+                    }
+                    let position = self.density.position(x, y);
+                    if let Some(nearest) = grid.nearest(position) {
+                        let (sum, total) = &mut sums[nearest];
+                        *sum += &(&position * weight);
+                        *total += weight;
+                    }
+                }
+            }
+        }
+
+        let mut displacement = 0.0_f32;
+        for (point, (sum, total)) in self.points.iter_mut().zip(sums) {
+            if total > 0.0 {
+                let centroid = &sum / total;
+                displacement = displacement.max((&centroid - point).length());
+                *point = centroid;
+            }
+        }
+        displacement
+    }
+
+    /// Relaxes the points `iterations` times, or until they move less than a hundredth of a pixel.
+    /// Returns `None` if `progress` was cancelled.
+    pub fn run(&mut self, iterations: usize, progress: &Progress) -> Option<()> {
+        progress.set_total(iterations);
+        let min_displacement = self.density.pixel_size() * 0.01;
+        for iteration in 0..iterations {
+            if progress.is_cancelled() {
+                return None;
+            }
+            if self.relax() < min_displacement {
+                break;
+            }
+            progress.set_done(iteration + 1);
+        }
+        progress.set_done(iterations);
+        Some(())
+    }
+}
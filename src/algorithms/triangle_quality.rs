@@ -0,0 +1,69 @@
+//! Shape quality of the triangles of a mesh. Thin triangles make interpolation and finite element
+//! computations inaccurate, the Delaunay triangulation maximizes the smallest angle and refinement bounds it from below.
+
+use crate::math::{ Vec2, float };
+
+use alloc::{ vec, vec::Vec };
+
+/// Quality measures of a triangle, all of them are infinite or zero for a degenerate triangle.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct TriangleQuality {
+    /// Smallest angle in radians, π / 3 for an equilateral triangle
+    pub min_angle: f32,
+    /// Circumradius over twice the inradius, 1 for an equilateral triangle and larger for thinner ones
+    pub aspect_ratio: f32,
+    /// Circumradius over the shortest edge, 1 / √3 for an equilateral triangle.
+    /// Ruppert's refinement bounds it, which bounds the smallest angle: ratio ≤ √2 means angles ≥ 20.7°
+    pub radius_edge_ratio: f32,
+}
+
+impl TriangleQuality {
+    pub fn new(a: Vec2, b: Vec2, c: Vec2) -> Self {
+        let (ab, bc, ca) = ((&b - &a).length(), (&c - &b).length(), (&a - &c).length());
+        let area = float::abs(Vec2::shoelace(a, b, c)) / 2.0;
+        if area == 0.0 {
+            return Self {
+                min_angle: 0.0,
+                aspect_ratio: f32::INFINITY,
+                radius_edge_ratio: f32::INFINITY,
+            };
+        }
+
+        // The smallest angle is opposite the shortest edge, law of cosines
+        let mut edges = [ ab, bc, ca ];
+        edges.sort_by(|x, y| x.total_cmp(y));
+        let [ shortest, middle, longest ] = edges;
+        let cos = ((middle * middle + longest * longest - shortest * shortest) / (2.0 * middle * longest)).clamp(-1.0, 1.0);
+
+        let circumradius = ab * bc * ca / (4.0 * area);
+        let inradius = 2.0 * area / (ab + bc + ca);
+        Self {
+            min_angle: float::atan2(float::sqrt(1.0 - cos * cos), cos),
+            aspect_ratio: circumradius / (2.0 * inradius),
+            radius_edge_ratio: circumradius / shortest,
+        }
+    }
+}
+
+/// Returns the quality of each triangle, `indices` contains 3 indices into `points` per triangle.
+pub fn triangle_qualities(points: &[Vec2], indices: &[usize]) -> Vec<TriangleQuality> {
+    indices
+        .chunks_exact(3)
+        .map(|t| TriangleQuality::new(points[t[0]], points[t[1]], points[t[2]]))
+        .collect()
+}
+
+/// Returns the number of values in each of `bins` equal intervals between `min` and `max`.
+/// The values out of the range are counted in the first or the last bin, `max` can be below `min` to reverse the bins.
+pub fn histogram(values: &[f32], bins: usize, (min, max): (f32, f32)) -> Vec<usize> {
+    let mut counts = vec![ 0; bins ];
+    if bins == 0 {
+        return counts;
+    }
+    for &value in values {
+        let t = if max != min { (value - min) / (max - min) } else { 0.0 };
+        let bin = (t * bins as f32).max(0.0) as usize; // NaN is cast to 0
+        counts[bin.min(bins - 1)] += 1;
+    }
+    counts
+}
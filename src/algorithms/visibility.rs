@@ -0,0 +1,128 @@
+//! Points visible from a query point among segment obstacles, found with an angular sweep:
+//! a ray turns around the query point and stops at the points and at the ends of the obstacles,
+//! keeping the obstacles it crosses sorted by their distance along it. A point is visible
+//! if it is closer than the first obstacle on its ray.
+//!
+//! The obstacles are split where they cross, so that their order along the ray only changes at their ends.
+//! A ray through the end of an obstacle is blocked by it, but not by the obstacles aligned with the query point.
+
+use crate::{
+    algorithms::Arrangement,
+    math::{ Vec2, Segment2, float },
+};
+
+use alloc::{ vec, vec::Vec };
+use core::cmp::Ordering;
+
+/// Whether a point is visible from the query point.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Sight {
+    Visible,
+    /// Hidden behind an obstacle, which the ray to the point hits first at this position
+    Blocked(Vec2),
+}
+
+/// What happens when the ray reaches an angle, sorted in the order they are processed at equal angles
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+enum Event {
+    /// The ray starts crossing the obstacle
+    Insert(usize),
+    Point(usize),
+    /// The ray leaves the obstacle
+    Remove(usize),
+}
+
+/// Returns whether each point is visible from `query`, i.e. whether the segment between them crosses no obstacle.
+pub fn visible_points(query: Vec2, points: &[Vec2], obstacles: &[Segment2]) -> Vec<Sight> {
+    let _span = span!("Angular sweep");
+    let arrangement = Arrangement::new(obstacles);
+    let (vertices, half_edges) = (arrangement.vertices(), arrangement.half_edges());
+    // Each obstacle goes counter-clockwise around the query point, from `a` to `b`
+    let obstacles = half_edges
+        .iter()
+        .enumerate()
+        .filter(|&(idx, half_edge)| idx < half_edge.twin)
+        .map(|(_, half_edge)| (vertices[half_edge.origin], vertices[half_edges[half_edge.twin].origin]))
+        .filter_map(|(a, b)| match Vec2::orientation(query, a, b).partial_cmp(&0.0) {
+            Some(Ordering::Greater) => Some(Segment2::new(a, b)),
+            Some(Ordering::Less) => Some(Segment2::new(b, a)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let angle = |p: Vec2| float::atan2(p.y - query.y, p.x - query.x);
+    let mut events = Vec::with_capacity(points.len() + 2 * obstacles.len());
+    events.extend(points.iter().enumerate().map(|(idx, &p)| (angle(p), Event::Point(idx))));
+    for (idx, obstacle) in obstacles.iter().enumerate() {
+        events.push((angle(obstacle.a), Event::Insert(idx)));
+        events.push((angle(obstacle.b), Event::Remove(idx)));
+    }
+    events.sort_by(|(a, event_a), (b, event_b)| a.partial_cmp(b).unwrap_or(Ordering::Equal).then(event_a.cmp(event_b)));
+
+    // The sweep starts along the negative x axis, the obstacles across it are crossed from the start
+    let mut active: Vec<usize> = Vec::new();
+    let start = Vec2::new(-1.0, 0.0);
+    let mut crossing = (0..obstacles.len()).filter(|&idx| angle(obstacles[idx].a) > angle(obstacles[idx].b)).collect::<Vec<_>>();
+    crossing.sort_by(|&a, &b| {
+        distance_along(&obstacles[a], query, start).partial_cmp(&distance_along(&obstacles[b], query, start)).unwrap_or(Ordering::Equal)
+    });
+    active.extend(crossing);
+
+    let mut sights = vec![ Sight::Visible; points.len() ];
+    for (_, event) in events {
+        match event {
+            Event::Remove(idx) => {
+                if let Some(pos) = active.iter().position(|&active| active == idx) {
+                    active.remove(pos);
+                }
+            },
+            Event::Point(idx) => {
+                let offset = &points[idx] - &query;
+                let distance = offset.length();
+                if distance == 0.0 {
+                    continue;
+                }
+                let direction = &offset * (1.0 / distance);
+                if let Some(&first) = active.first() {
+                    let t = distance_along(&obstacles[first], query, direction);
+                    // Points on an obstacle are visible
+                    if t < distance * (1.0 - 1e-6) {
+                        sights[idx] = Sight::Blocked(&query + &(&direction * t));
+                    }
+                }
+            },
+            Event::Insert(idx) => {
+                // The ray goes through the start of the obstacle, whose distance is the one to compare with
+                let obstacle = &obstacles[idx];
+                let direction = (&obstacle.a - &query).normalized();
+                let t = (&obstacle.a - &query).length();
+                let pos = active
+                    .iter()
+                    .position(|&other| {
+                        let other = &obstacles[other];
+                        if other.a == obstacle.a {
+                            // Obstacles from the same end: the one on the side of the query point is closer
+                            Vec2::orientation(obstacle.a, other.b, obstacle.b) > 0.0
+                        } else {
+                            distance_along(other, query, direction) > t
+                        }
+                    })
+                    .unwrap_or(active.len());
+                active.insert(pos, idx);
+            },
+        }
+    }
+    sights
+}
+
+/// Returns the distance from `origin` to the line of `segment` along the unit `direction`,
+/// or to its closest end if they are parallel.
+fn distance_along(segment: &Segment2, origin: Vec2, direction: Vec2) -> f32 {
+    let cross = |u: Vec2, v: Vec2| u.x * v.y - u.y * v.x;
+    let v = segment.as_vec2();
+    let denominator = cross(direction, v);
+    if denominator == 0.0 {
+        return (&segment.a - &origin).length().min((&segment.b - &origin).length());
+    }
+    cross(&segment.a - &origin, v) / denominator
+}
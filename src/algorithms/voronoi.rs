@@ -0,0 +1,164 @@
+use super::Incremental2dTriangulation;
+use crate::math::{ Vec2, Rect };
+
+use alloc::{ vec, vec::Vec, collections::BTreeMap };
+use core::cmp::Ordering;
+
+/// An edge of a Voronoi diagram, the boundary between the cells of two input points.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct VoronoiEdge {
+    /// Indices of the input points whose cells are separated by the edge
+    pub sites: (usize, usize),
+    pub start: Vec2,
+    /// `None` if the edge is a ray going to infinity from `start`
+    pub end: Option<Vec2>,
+    /// Unit vector from `start` toward `end`, or along the ray
+    pub direction: Vec2,
+}
+
+impl VoronoiEdge {
+    pub fn segment(sites: (usize, usize), start: Vec2, end: Vec2) -> Self {
+        Self {
+            sites,
+            start,
+            end: Some(end),
+            direction: (&end - &start).normalized(),
+        }
+    }
+
+    pub fn ray(sites: (usize, usize), start: Vec2, direction: Vec2) -> Self {
+        Self {
+            sites,
+            start,
+            end: None,
+            direction: direction.normalized(),
+        }
+    }
+
+    /// Returns the end of the edge, or the point at `length` from the start of a ray, e.g. to draw it.
+    pub fn clipped_end(&self, length: f32) -> Vec2 {
+        self.end.unwrap_or_else(|| &self.start + &(&self.direction * length))
+    }
+
+    /// Checks whether `p`, which lies on the line supporting the edge, is between its ends.
+    pub fn contains(&self, p: Vec2) -> bool {
+        let epsilon = 1e-5;
+        match self.end {
+            Some(end) => {
+                let length = (&end - &self.start).length() + epsilon;
+                (&p - &self.start).length() <= length && (&p - &end).length() <= length
+            },
+            None => (&p - &self.start).dot(self.direction) >= -epsilon,
+        }
+    }
+}
+
+/// Cell of an input point in the Voronoi diagram: the positions closer to it than to any other input point.
+#[derive(Clone, PartialEq, Debug)]
+pub struct VoronoiCell {
+    /// Index of the input point
+    pub site: usize,
+    /// Vertices of the cell cut by the bounds, in counter-clockwise order.
+    /// Empty if the cell is outside of the bounds, or if the site is a duplicate of an earlier point
+    pub polygon: Vec<Vec2>,
+    /// Indices of the points whose cells share an edge with this one, its neighbours in the Delaunay triangulation
+    pub neighbours: Vec<usize>,
+    /// False for the points on the convex hull, whose cells go to infinity and are cut by the bounds
+    pub bounded: bool,
+}
+
+impl VoronoiCell {
+    pub fn area(&self) -> f32 {
+        let n = self.polygon.len();
+        (0..n).map(|idx| {
+                let (a, b) = (self.polygon[idx], self.polygon[(idx + 1) % n]);
+                a.x * b.y - b.x * a.y
+            })
+            .sum::<f32>() * 0.5
+    }
+}
+
+/// Computes the Voronoi diagram of the points as the dual of their Delaunay triangulation, one cell per point in the same order.
+/// Each cell is the rectangle `bounds` clipped by the perpendicular bisectors between its site and its Delaunay neighbours.
+/// Collinear points have no triangulation, the neighbours of each point are then the previous and the next one along the line.
+pub fn voronoi_diagram(points: &[Vec2], bounds: &Rect) -> Vec<VoronoiCell> {
+    let _span = span!("Voronoi diagram");
+    let mut cells = (0..points.len())
+                        .map(|site| VoronoiCell { site, polygon: Vec::new(), neighbours: Vec::new(), bounded: false })
+                        .collect::<Vec<_>>();
+
+    // Duplicate points would make degenerate triangles, only the first of them gets a cell
+    let cmp = |a: f32, b: f32| a.partial_cmp(&b).unwrap_or(Ordering::Equal);
+    let mut order = (0..points.len()).collect::<Vec<_>>();
+    order.sort_by(|&i, &j| cmp(points[i].x, points[j].x).then(cmp(points[i].y, points[j].y)).then(i.cmp(&j)));
+    order.dedup_by(|&mut j, &mut i| points[i] == points[j]);
+    let unique = order.iter().map(|&idx| points[idx]).collect::<Vec<_>>();
+
+    let triangles = Incremental2dTriangulation::delaunay(&unique);
+    // Number of triangles along each edge, 1 for the edges of the hull
+    let mut edges = BTreeMap::new();
+    for tri in triangles.chunks(3) {
+        for &(a, b) in &[ (tri[0], tri[1]), (tri[1], tri[2]), (tri[2], tri[0]) ] {
+            *edges.entry((a.min(b), a.max(b))).or_insert(0) += 1;
+        }
+    }
+    if triangles.is_empty() {
+        // Sorted along the line
+        for idx in 1..unique.len() {
+            edges.insert((idx - 1, idx), 1);
+        }
+    }
+
+    let mut neighbours = vec![ Vec::new(); unique.len() ];
+    let mut on_hull = vec![ triangles.is_empty(); unique.len() ];
+    for (&(a, b), &count) in &edges {
+        neighbours[a].push(b);
+        neighbours[b].push(a);
+        if count == 1 {
+            on_hull[a] = true;
+            on_hull[b] = true;
+        }
+    }
+
+    for (idx, &site) in order.iter().enumerate() {
+        let mut polygon = bounds.to_polygon();
+        for &neighbour in &neighbours[idx] {
+            polygon = clip_to_bisector(&polygon, unique[idx], unique[neighbour]);
+            if polygon.is_empty() {
+                break;
+            }
+        }
+        let cell = &mut cells[site];
+        cell.polygon = polygon;
+        cell.neighbours = neighbours[idx].iter().map(|&neighbour| order[neighbour]).collect();
+        cell.neighbours.sort_unstable();
+        cell.bounded = !on_hull[idx];
+    }
+    cells
+}
+
+/// Clips the convex `polygon` to the half-plane of the positions closer to `near` than to `far`,
+/// bounded by their perpendicular bisector. Returns an empty polygon if nothing is left.
+pub fn clip_to_bisector(polygon: &[Vec2], near: Vec2, far: Vec2) -> Vec<Vec2> {
+    let middle = &(&near + &far) * 0.5;
+    let normal = &far - &near;
+    // Positive on the side of `far`
+    let side = |p: Vec2| (&p - &middle).dot(normal);
+
+    let mut clipped = Vec::with_capacity(polygon.len() + 1);
+    for (idx, &cur) in polygon.iter().enumerate() {
+        let next = polygon[(idx + 1) % polygon.len()];
+        let (d_cur, d_next) = (side(cur), side(next));
+        if d_cur <= 0.0 {
+            clipped.push(cur);
+        }
+        if d_cur < 0.0 && d_next > 0.0 || d_cur > 0.0 && d_next < 0.0 {
+            let t = d_cur / (d_cur - d_next);
+            clipped.push(&cur + &(&(&next - &cur) * t));
+        }
+    }
+    if clipped.len() < 3 {
+        clipped.clear();
+    }
+    clipped
+}
@@ -0,0 +1,240 @@
+use computational_geometry::{
+    io,
+    harness::{ self, Suite },
+    math::Vec2,
+    algorithms::{ JarvisMarch, GrahamScan, Incremental2dTriangulation, hull_positions },
+};
+
+use std::{
+    fs::File,
+    io::{ BufReader, BufWriter, Write },
+    path::Path,
+    time::Duration,
+};
+
+use clap::{ App, AppSettings, Arg, ArgMatches, SubCommand, crate_version };
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+fn main() {
+    let input = Arg::with_name("INPUT")
+                    .help("CSV file with one \"x,y\" point per line, - for the standard input")
+                    .required(true);
+    let output = Arg::with_name("output")
+                    .short("o")
+                    .long("output")
+                    .takes_value(true)
+                    .value_name("FILE")
+                    .help("Writes the result to FILE instead of the standard output");
+
+    let matches = App::new("cgeom")
+        .version(crate_version!())
+        .about("Runs the computational geometry algorithms without opening the viewer")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .arg(Arg::with_name("verbose")
+            .short("v")
+            .long("verbose")
+            .multiple(true)
+            .global(true)
+            .help("Logs the phases of the algorithms and their durations to the standard error, repeat for more details"))
+        .subcommand(SubCommand::with_name("hull")
+            .about("Computes the convex hull of a set of points, written as CSV")
+            .arg(input.clone())
+            .arg(Arg::with_name("algo")
+                .long("algo")
+                .takes_value(true)
+                .possible_values(&[ "jarvis", "graham" ])
+                .default_value("graham")
+                .help("Algorithm used to compute the hull"))
+            .arg(output.clone()))
+        .subcommand(SubCommand::with_name("triangulate")
+            .about("Triangulates a set of points, written as JSON if the output file ends with .json, CSV otherwise")
+            .arg(input)
+            .arg(Arg::with_name("delaunay")
+                .long("delaunay")
+                .help("Flips the edges to get a Delaunay triangulation"))
+            .arg(output))
+        .subcommand(SubCommand::with_name("bench")
+            .about("Times the algorithms on generated sets of points")
+            .arg(Arg::with_name("sizes")
+                .long("sizes")
+                .takes_value(true)
+                .use_delimiter(true)
+                .default_value("100,500,1000")
+                .help("Numbers of points"))
+            .arg(Arg::with_name("runs")
+                .long("runs")
+                .takes_value(true)
+                .default_value("5")
+                .help("Number of runs averaged for each size"))
+            .arg(Arg::with_name("distributions")
+                .long("distributions")
+                .takes_value(true)
+                .use_delimiter(true)
+                .possible_values(&Suite::ALL.iter().map(|suite| suite.name()).collect::<Vec<_>>())
+                .default_value("uniform")
+                .help("Distributions of the generated points"))
+            .arg(Arg::with_name("seed")
+                .long("seed")
+                .takes_value(true)
+                .help("Generates the points of the runs from this seed, to time the same points again, random by default"))
+            .arg(Arg::with_name("csv")
+                .long("csv")
+                .takes_value(true)
+                .value_name("FILE")
+                .help("Exports every run and the duration of its phases to FILE as CSV"))
+            .arg(Arg::with_name("z-order")
+                .long("z-order")
+                .help("Sorts the points along a Z-order curve before timing, which keeps nearby points close in memory")))
+        .subcommand(SubCommand::with_name("compare")
+            .about("Checks that all the hull and triangulation implementations agree on generated sets of points, \
+                    exits with an error if they do not")
+            .arg(Arg::with_name("sizes")
+                .long("sizes")
+                .takes_value(true)
+                .use_delimiter(true)
+                .default_value("3,20,200,1000")
+                .help("Numbers of points")))
+        .get_matches();
+    init_logger(matches.occurrences_of("verbose"));
+
+    let result = match matches.subcommand() {
+        ("hull", Some(args)) => hull(args),
+        ("triangulate", Some(args)) => triangulate(args),
+        ("bench", Some(args)) => bench(args),
+        ("compare", Some(args)) => compare(args),
+        _ => unreachable!("A subcommand is required"),
+    };
+    if let Err(err) = result {
+        eprintln!("cgeom: {}", err);
+        std::process::exit(1);
+    }
+}
+
+/// Only warnings are shown by default, `RUST_LOG` overrides the level set with `-v`.
+fn init_logger(verbosity: u64) {
+    let level = match verbosity {
+        0 => "warn",
+        1 => "info",
+        2 => "debug",
+        _ => "trace",
+    };
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(level))
+        .format(|buf, record| writeln!(buf, "[{}] {}: {}", record.level(), record.target(), record.args()))
+        .init();
+}
+
+fn read_input(args: &ArgMatches) -> Result<Vec<Vec2>> {
+    let path = args.value_of("INPUT").expect("INPUT is required");
+    let points = if path == "-" {
+        let stdin = std::io::stdin();
+        let lock = stdin.lock();
+        io::read_points_csv(lock)?
+    } else {
+        let file = File::open(path).map_err(|err| format!("Could not open {}: {}", path, err))?;
+        io::read_points_csv(BufReader::new(file))?
+    };
+    log::info!("Read {} points from {}", points.len(), path);
+    Ok(points)
+}
+
+fn output(args: &ArgMatches) -> Result<Box<dyn Write>> {
+    match args.value_of("output") {
+        Some(path) => {
+            let file = File::create(path).map_err(|err| format!("Could not create {}: {}", path, err))?;
+            Ok(Box::new(BufWriter::new(file)))
+        },
+        None => Ok(Box::new(std::io::stdout())),
+    }
+}
+
+fn hull(args: &ArgMatches) -> Result<()> {
+    let points = read_input(args)?;
+    let hull = match args.value_of("algo") {
+        Some("jarvis") => JarvisMarch::march(points.iter()),
+        _ => GrahamScan::scan(&points),
+    };
+    let hull = hull_positions(&points, &hull);
+    log::info!("{} points on the hull", hull.len());
+    io::write_points_csv(output(args)?, &hull)?;
+    Ok(())
+}
+
+fn triangulate(args: &ArgMatches) -> Result<()> {
+    let mut triangulation = Incremental2dTriangulation::new();
+    for point in read_input(args)? {
+        triangulation.push_point(point); // Duplicates are dropped
+    }
+    triangulation.retriangulate(args.is_present("delaunay"));
+    log::info!("{} triangles", triangulation.triangle_count());
+
+    let json = args.value_of("output")
+                    .and_then(|path| Path::new(path).extension())
+                    .map(|ext| ext == "json")
+                    .unwrap_or(false);
+    let writer = output(args)?;
+    if json {
+        io::write_triangulation_json(writer, triangulation.points(), triangulation.indices())?;
+    } else {
+        io::write_triangles_csv(writer, triangulation.points(), triangulation.indices())?;
+    }
+    Ok(())
+}
+
+fn sizes(args: &ArgMatches) -> Result<Vec<usize>> {
+    let sizes = args.values_of("sizes")
+                    .expect("sizes has a default value")
+                    .map(|size| size.parse::<usize>().map_err(|err| format!("Invalid size \"{}\": {}", size, err)))
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(sizes)
+}
+
+fn bench(args: &ArgMatches) -> Result<()> {
+    let sizes = sizes(args)?;
+    let runs = args.value_of("runs")
+                    .expect("runs has a default value")
+                    .parse::<usize>()
+                    .map_err(|err| format!("Invalid number of runs: {}", err))?
+                    .max(1);
+    let suites = args.values_of("distributions")
+                    .expect("distributions has a default value")
+                    .map(|name| Suite::from_name(name).expect("The distributions are validated by clap"))
+                    .collect::<Vec<_>>();
+    let seed = match args.value_of("seed") {
+        Some(seed) => seed.parse::<u64>().map_err(|err| format!("Invalid seed \"{}\": {}", seed, err))?,
+        None => rand::random(),
+    };
+    let timings = harness::benchmark(&suites, &sizes, runs, seed, args.is_present("z-order"));
+
+    // Timings are ordered by distribution, size and run, the averages are over the runs of each size
+    println!("seed {}", seed);
+    print!("{:<10} {:>8}", "suite", "points");
+    for benchmark in harness::BENCHMARKS.iter() {
+        print!(" {:>18}", format!("{} (µs)", benchmark.name.to_lowercase()));
+    }
+    println!();
+    for group in timings.chunks(runs * harness::BENCHMARKS.len()) {
+        print!("{:<10} {:>8}", group[0].suite.name(), group[0].count);
+        for (idx, _) in harness::BENCHMARKS.iter().enumerate() {
+            let total = group.iter().skip(idx).step_by(harness::BENCHMARKS.len()).map(|timing| timing.total()).sum::<Duration>();
+            print!(" {:>18}", total.as_micros() / runs as u128);
+        }
+        println!();
+    }
+
+    if let Some(path) = args.value_of("csv") {
+        let file = File::create(path).map_err(|err| format!("Could not create {}: {}", path, err))?;
+        io::write_timings_csv(BufWriter::new(file), &timings)?;
+        log::info!("Wrote {} timings to {}", timings.len(), path);
+    }
+    Ok(())
+}
+
+fn compare(args: &ArgMatches) -> Result<()> {
+    let report = harness::compare(&sizes(args)?);
+    println!("{}", report);
+    if !report.is_compatible() {
+        return Err(format!("{} results disagree", report.failures().count()).into());
+    }
+    Ok(())
+}
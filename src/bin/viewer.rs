@@ -0,0 +1,134 @@
+// The events are matched level by level, as in the library
+#![allow(clippy::collapsible_match)]
+
+use computational_geometry::{ ui, graphics, scene::SharedScene };
+
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    time::{ Duration, Instant },
+};
+
+use glium::{
+    Surface, Display,
+    glutin::{ ContextBuilder, GlProfile },
+};
+use winit::{
+    dpi::LogicalSize,
+    event::{ Event, WindowEvent, ElementState },
+    event_loop::{ EventLoop, ControlFlow },
+    window::WindowBuilder,
+};
+
+/// Minimum time between two frames, vsync is disabled so the framerate is limited to 144 fps.
+const FRAME_TIME: Duration = Duration::from_nanos(1_000_000_000 / 144);
+
+fn main() {
+    // Created first so that it captures every log message
+    let console = ui::window::Console::new();
+
+    // An invalid settings file is reported once the notifications can be shown
+    let (settings, settings_error) = match ui::Settings::load() {
+        Ok(settings) => (settings, None),
+        Err(err) => (ui::Settings::default(), Some(err)),
+    };
+    let settings = Rc::new(RefCell::new(settings));
+
+    let event_loop = EventLoop::new();
+    let [width, height] = settings.borrow().layout.window_size;
+    let wb = WindowBuilder::new()
+                        .with_title("Computational Geometry")
+                        .with_inner_size(LogicalSize::new(width, height));
+    let cb = ContextBuilder::new()
+                        .with_gl_profile(GlProfile::Core)
+                        .with_vsync(false)
+                        .with_double_buffer(Some(true))
+                        .with_multisampling(settings.borrow().msaa)
+                        .with_hardware_acceleration(Some(true));
+    let display = match Display::new(wb, cb, &event_loop) {
+        Ok(display) => display,
+        Err(err) => exit_with_error(err),
+    };
+    // `EventLoop::run()` never returns, the display lives as long as the program
+    let display: &'static Display = Box::leak(Box::new(display));
+    graphics::print_api_info(display);
+
+    // Create Dear ImGui windows
+    let mut ui_mngr = match ui::init(display, settings.clone()) {
+        Ok(ui_mngr) => ui_mngr,
+        Err(err) => exit_with_error(err),
+    };
+    if let Some(err) = settings_error {
+        ui_mngr.show_error(err);
+    }
+    // Geometry shared by all the algorithms
+    let scene = SharedScene::default();
+    ui_mngr.add_window(ui::window::Debug::default());
+    ui_mngr.add_window(ui::window::Scene::new(scene.clone(), settings.clone()));
+    match ui::window::Algorithms::new(display, &scene, &settings) {
+        Ok(algorithms) => ui_mngr.add_window(algorithms),
+        Err(err) => ui_mngr.show_error(err),
+    }
+    ui_mngr.add_window(console);
+    ui_mngr.add_window(ui::window::Settings::new(settings.clone()));
+
+    let mut last_frame = Instant::now();
+    event_loop.run(move |event, _, control_flow| {
+        let gl_window = display.gl_window();
+        let window = gl_window.window();
+        ui_mngr.handle_events(window, &event);
+
+        match event {
+            Event::WindowEvent { event: WindowEvent::CloseRequested, .. } => {
+                *control_flow = ControlFlow::Exit;
+            },
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input, .. }, .. } => {
+                if !ui_mngr.imgui_io().want_capture_keyboard && input.state == ElementState::Pressed {
+                    let keybindings = settings.borrow().keybindings.clone();
+                    if input.virtual_keycode == Some(keybindings.quit) {
+                        *control_flow = ControlFlow::Exit;
+                    } else if input.virtual_keycode == Some(keybindings.clear_points) {
+                        scene.borrow_mut().clear_points();
+                    }
+                }
+            },
+            Event::WindowEvent { event: WindowEvent::Resized(size), .. } => {
+                let size = size.to_logical::<f64>(window.scale_factor());
+                settings.borrow_mut().layout.window_size = [size.width, size.height];
+            },
+            Event::MainEventsCleared => {
+                // Events arriving before the next frame is due do not trigger a redraw
+                if last_frame.elapsed() >= FRAME_TIME {
+                    ui_mngr.prepare_frame(window);
+                    window.request_redraw();
+                }
+            },
+            Event::RedrawRequested(_) => {
+                last_frame = Instant::now();
+
+                let mut target = display.draw();
+                target.clear_color_srgb_and_depth((0.12, 0.12, 0.12, 1.0), 1.0);
+                ui_mngr.draw(window, &mut target);
+                if let Err(err) = target.finish() {
+                    log::error!("Could not swap buffers: {}", err);
+                }
+
+                if *control_flow != ControlFlow::Exit {
+                    *control_flow = ControlFlow::WaitUntil(last_frame + FRAME_TIME);
+                }
+            },
+            Event::LoopDestroyed => {
+                if let Err(err) = settings.borrow().save() {
+                    eprintln!("viewer: {}", err);
+                }
+            },
+            _ => {},
+        }
+    });
+}
+
+/// Errors happening before the user interface is created cannot be shown as notifications.
+fn exit_with_error(err: impl std::fmt::Display) -> ! {
+    eprintln!("viewer: {}", err);
+    std::process::exit(1);
+}
@@ -0,0 +1,184 @@
+//! C interface to embed the algorithms in C and C++ programs.
+//!
+//! The header `include/computational_geometry.h` is generated by cbindgen when building with the `capi` feature.
+
+use crate::{
+    algorithms::{ GrahamScan, Incremental2dTriangulation, Operation, hull_positions },
+    math::Vec2,
+    task::Progress,
+};
+
+use std::{ ptr, slice };
+
+/// A 2D point, laid out as two consecutive floats.
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct CgPoint {
+    pub x: f32,
+    pub y: f32,
+}
+
+/// A triangulation allocated by [`delaunay_2d`], to release with [`triangulation_free`].
+#[repr(C)]
+pub struct CgTriangulation {
+    /// Input points in the order used by the indices, without the duplicates
+    pub points: *mut CgPoint,
+    pub points_len: usize,
+    /// Indices of the points that form the triangles, 3 per triangle
+    pub indices: *mut usize,
+    pub indices_len: usize,
+}
+
+/// Polygons allocated by [`polygon_boolean`], to release with [`polygons_free`].
+#[repr(C)]
+pub struct CgPolygons {
+    /// Vertices of all the polygons, one polygon after the other
+    pub points: *mut CgPoint,
+    pub points_len: usize,
+    /// Number of vertices of each polygon
+    pub sizes: *mut usize,
+    pub sizes_len: usize,
+}
+
+/// Boolean operation between two polygons, the difference is the first polygon without the second one.
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub enum CgOperation {
+    Union,
+    Intersection,
+    Difference,
+    Xor,
+}
+
+impl From<CgOperation> for Operation {
+    fn from(operation: CgOperation) -> Self {
+        match operation {
+            CgOperation::Union => Operation::Union,
+            CgOperation::Intersection => Operation::Intersection,
+            CgOperation::Difference => Operation::Difference,
+            CgOperation::Xor => Operation::Xor,
+        }
+    }
+}
+
+impl From<CgPoint> for Vec2 {
+    fn from(p: CgPoint) -> Self {
+        Vec2::new(p.x, p.y)
+    }
+}
+
+impl From<Vec2> for CgPoint {
+    fn from(v: Vec2) -> Self {
+        CgPoint { x: v.x, y: v.y }
+    }
+}
+
+/// Reads `len` points, an empty set if `points` is null.
+unsafe fn read_points(points: *const CgPoint, len: usize) -> Vec<Vec2> {
+    if points.is_null() {
+        return Vec::new();
+    }
+    slice::from_raw_parts(points, len).iter().map(|&p| p.into()).collect()
+}
+
+/// Gives the ownership of `vec` to the caller, who releases it with [`free_vec`].
+fn into_raw<T>(vec: Vec<T>) -> (*mut T, usize) {
+    let boxed = vec.into_boxed_slice();
+    let len = boxed.len();
+    (Box::into_raw(boxed) as *mut T, len)
+}
+
+unsafe fn free_vec<T>(ptr: *mut T, len: usize) {
+    if !ptr.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(ptr, len)));
+    }
+}
+
+/// Computes the convex hull of `len` points with the Graham scan and writes its vertices
+/// in counter-clockwise order to `out`, which must have room for `len` points.
+/// Returns the number of vertices of the hull.
+///
+/// # Safety
+///
+/// `points` must point to `len` readable points and `out` to `len` writable points.
+#[no_mangle]
+pub unsafe extern "C" fn convex_hull_2d(points: *const CgPoint, len: usize, out: *mut CgPoint) -> usize {
+    if out.is_null() {
+        return 0;
+    }
+    let points = read_points(points, len);
+    let hull = hull_positions(&points, &GrahamScan::scan(&points));
+    let out = slice::from_raw_parts_mut(out, len);
+    for (dst, &p) in out.iter_mut().zip(&hull) {
+        *dst = p.into();
+    }
+    hull.len()
+}
+
+/// Triangulates `len` points, the triangulation is a Delaunay triangulation if `delaunay` is true.
+/// Coincident points are only kept once, so the result may have fewer points than the input.
+/// The result must be released with [`triangulation_free`].
+///
+/// # Safety
+///
+/// `points` must point to `len` readable points.
+#[no_mangle]
+pub unsafe extern "C" fn delaunay_2d(points: *const CgPoint, len: usize, delaunay: bool) -> CgTriangulation {
+    let (points, indices) = Incremental2dTriangulation::compute(read_points(points, len), delaunay, &Progress::default())
+                                .unwrap_or_default(); // Only cancelled computations fail
+    let (points, points_len) = into_raw(points.into_iter().map(CgPoint::from).collect());
+    let (indices, indices_len) = into_raw(indices);
+    CgTriangulation { points, points_len, indices, indices_len }
+}
+
+/// Releases a triangulation returned by [`delaunay_2d`], its pointers are set to null.
+///
+/// # Safety
+///
+/// `triangulation` must be null or have been returned by [`delaunay_2d`] and not modified since.
+#[no_mangle]
+pub unsafe extern "C" fn triangulation_free(triangulation: *mut CgTriangulation) {
+    if let Some(triangulation) = triangulation.as_mut() {
+        free_vec(triangulation.points, triangulation.points_len);
+        free_vec(triangulation.indices, triangulation.indices_len);
+        triangulation.points = ptr::null_mut();
+        triangulation.points_len = 0;
+        triangulation.indices = ptr::null_mut();
+        triangulation.indices_len = 0;
+    }
+}
+
+/// Computes the union, intersection, difference or exclusive or of two simple polygons of `a_len` and `b_len` points,
+/// clockwise or counter-clockwise. The result is made of counter-clockwise outer boundaries and clockwise holes,
+/// and must be released with [`polygons_free`].
+///
+/// # Safety
+///
+/// `a` must point to `a_len` readable points, `b` to `b_len` readable points,
+/// and `operation` must be one of the values of [`CgOperation`].
+#[no_mangle]
+pub unsafe extern "C" fn polygon_boolean(
+    a: *const CgPoint, a_len: usize, b: *const CgPoint, b_len: usize, operation: CgOperation,
+) -> CgPolygons {
+    let polygons = crate::algorithms::polygon_boolean(&read_points(a, a_len), &read_points(b, b_len), operation.into());
+    let (sizes, sizes_len) = into_raw(polygons.iter().map(Vec::len).collect());
+    let (points, points_len) = into_raw(polygons.into_iter().flatten().map(CgPoint::from).collect());
+    CgPolygons { points, points_len, sizes, sizes_len }
+}
+
+/// Releases polygons returned by [`polygon_boolean`], their pointers are set to null.
+///
+/// # Safety
+///
+/// `polygons` must be null or have been returned by [`polygon_boolean`] and not modified since.
+#[no_mangle]
+pub unsafe extern "C" fn polygons_free(polygons: *mut CgPolygons) {
+    if let Some(polygons) = polygons.as_mut() {
+        free_vec(polygons.points, polygons.points_len);
+        free_vec(polygons.sizes, polygons.sizes_len);
+        polygons.points = ptr::null_mut();
+        polygons.points_len = 0;
+        polygons.sizes = ptr::null_mut();
+        polygons.sizes_len = 0;
+    }
+}
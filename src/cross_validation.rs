@@ -0,0 +1,92 @@
+//! Comparison of the Delaunay triangulation of the crate with the one of [spade](https://docs.rs/spade),
+//! to find the inputs on which the edge flipping is not robust.
+//!
+//! A Delaunay triangulation is not unique when 4 points or more are cocircular, e.g. on a grid,
+//! so the triangles that differ are only reported when one of them is wrong:
+//! a triangle of the crate whose circumcircle contains another point,
+//! or a triangle of spade whose area is not covered by the triangulation of the crate.
+//! The crate does not compute Voronoi diagrams yet, they would be checked through their dual triangulation.
+
+use crate::math::Vec2;
+
+use std::collections::HashSet;
+
+use spade::{ DelaunayTriangulation, Point2, Triangulation };
+
+/// Returns the triangles of the Delaunay triangulation `indices` of `points` that are wrong,
+/// followed by those of spade that are missing from it, 3 indices into `points` per triangle.
+pub fn delaunay_differences(points: &[Vec2], indices: &[usize]) -> Vec<usize> {
+    let mut reference = DelaunayTriangulation::<Point2<f64>>::new();
+    // Index in `points` of each vertex of spade, duplicates are merged with the first occurrence
+    let mut original = Vec::new();
+    for (idx, p) in points.iter().enumerate() {
+        match reference.insert(Point2::new(p.x as f64, p.y as f64)) {
+            Ok(vertex) if vertex.index() == original.len() => original.push(idx),
+            Ok(_) => {},
+            Err(err) => log::warn!("spade rejected the point ({}, {}): {:?}", p.x, p.y, err),
+        }
+    }
+
+    let triangles = indices.chunks(3)
+                        .map(|tri| sorted([ tri[0], tri[1], tri[2] ]))
+                        .collect::<HashSet<_>>();
+    let expected = reference.inner_faces()
+                        .map(|face| {
+                            let [ a, b, c ] = face.vertices();
+                            sorted([ original[a.fix().index()], original[b.fix().index()], original[c.fix().index()] ])
+                        })
+                        .collect::<HashSet<_>>();
+
+    let mut differences = Vec::new();
+    for tri in triangles.difference(&expected) {
+        if points.iter().any(|&p| in_circumcircle(points, *tri, p)) {
+            differences.extend_from_slice(tri);
+        }
+    }
+    for tri in expected.difference(&triangles) {
+        let centroid = &(&(&points[tri[0]] + &points[tri[1]]) + &points[tri[2]]) / 3.0;
+        if !indices.chunks(3).any(|other| in_triangle(points, [ other[0], other[1], other[2] ], centroid)) {
+            differences.extend_from_slice(tri);
+        }
+    }
+    differences
+}
+
+fn sorted(mut tri: [usize; 3]) -> [usize; 3] {
+    tri.sort_unstable();
+    tri
+}
+
+fn to_f64(p: Vec2) -> (f64, f64) {
+    (p.x as f64, p.y as f64)
+}
+
+/// Twice the signed area of the triangle (a, b, c), positive if it is counter-clockwise.
+fn orientation(a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> f64 {
+    (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0)
+}
+
+/// Tests if `p` lies strictly inside the circumcircle of the triangle, with a tolerance relative to its size
+/// so that cocircular points are not reported.
+fn in_circumcircle(points: &[Vec2], tri: [usize; 3], p: Vec2) -> bool {
+    let (a, b, c, p) = (to_f64(points[tri[0]]), to_f64(points[tri[1]]), to_f64(points[tri[2]]), to_f64(p));
+    let (ax, ay) = (a.0 - p.0, a.1 - p.1);
+    let (bx, by) = (b.0 - p.0, b.1 - p.1);
+    let (cx, cy) = (c.0 - p.0, c.1 - p.1);
+    let det = (ax * ax + ay * ay) * (bx * cy - cx * by)
+            - (bx * bx + by * by) * (ax * cy - cx * ay)
+            + (cx * cx + cy * cy) * (ax * by - bx * ay);
+    let det = det * orientation(a, b, c).signum();
+
+    let size = [ ax, ay, bx, by, cx, cy ].iter().fold(0.0_f64, |max, c| max.max(c.abs()));
+    det > size.powi(4) * 1e-6
+}
+
+/// Tests if `p` lies inside the triangle or on its edges.
+fn in_triangle(points: &[Vec2], tri: [usize; 3], p: Vec2) -> bool {
+    let (a, b, c, p) = (to_f64(points[tri[0]]), to_f64(points[tri[1]]), to_f64(points[tri[2]]), to_f64(p));
+    let d1 = orientation(a, b, p);
+    let d2 = orientation(b, c, p);
+    let d3 = orientation(c, a, p);
+    !((d1 < 0.0 || d2 < 0.0 || d3 < 0.0) && (d1 > 0.0 || d2 > 0.0 || d3 > 0.0))
+}
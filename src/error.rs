@@ -0,0 +1,151 @@
+//! Errors reported by the library and the viewer.
+
+use std::{ fmt, io };
+
+/// Everything that can go wrong when loading files or creating GPU resources.
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    /// A line of an input file could not be parsed, `line` starts at 1
+    Parse { line: usize, content: String, expected: &'static str },
+    /// A number of an input file is NaN or infinite, `line` and `column` start at 1
+    NotFinite { line: usize, column: usize, content: String },
+    /// Invalid Well-Known Text
+    Wkt(String),
+    /// Invalid or compressed LAS point cloud
+    Las(String),
+    /// Invalid image file
+    Image(String),
+    /// The extension of a file does not match any supported format
+    UnsupportedFormat(String),
+    #[cfg(feature = "gui")]
+    Obj(tobj::LoadError),
+    #[cfg(feature = "gui")]
+    Shader(glium::ProgramCreationError),
+    #[cfg(feature = "gui")]
+    VertexBuffer(glium::vertex::BufferCreationError),
+    #[cfg(feature = "gui")]
+    IndexBuffer(glium::index::BufferCreationError),
+    #[cfg(feature = "gui")]
+    Draw(glium::DrawError),
+    #[cfg(feature = "gui")]
+    ImGui(imgui_glium_renderer::RendererError),
+    #[cfg(feature = "gui")]
+    Window(glium::glutin::error::ExternalError),
+    #[cfg(feature = "gui")]
+    Settings(toml::de::Error),
+    #[cfg(feature = "gui")]
+    SettingsWrite(toml::ser::Error),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "{}", err),
+            Error::Parse { line, content, expected } => write!(f, "Line {}: expected \"{}\", got \"{}\"", line, expected, content),
+            Error::NotFinite { line, column, content } => write!(f, "Line {}, column {}: \"{}\" is not a finite number", line, column, content),
+            Error::Wkt(err) => write!(f, "Invalid WKT: {}", err),
+            Error::Las(err) => write!(f, "Invalid LAS file: {}", err),
+            Error::Image(err) => write!(f, "Invalid image: {}", err),
+            Error::UnsupportedFormat(extension) => write!(f, "Unsupported file format \"{}\"", extension),
+            #[cfg(feature = "gui")]
+            Error::Obj(err) => write!(f, "Could not load model: {}", err),
+            #[cfg(feature = "gui")]
+            Error::Shader(err) => write!(f, "Could not compile shaders: {}", err),
+            #[cfg(feature = "gui")]
+            Error::VertexBuffer(err) => write!(f, "Could not create vertex buffer: {}", err),
+            #[cfg(feature = "gui")]
+            Error::IndexBuffer(err) => write!(f, "Could not create index buffer: {}", err),
+            #[cfg(feature = "gui")]
+            Error::Draw(err) => write!(f, "Draw failure: {}", err),
+            #[cfg(feature = "gui")]
+            Error::ImGui(err) => write!(f, "Could not render ImGui: {}", err),
+            #[cfg(feature = "gui")]
+            Error::Window(err) => write!(f, "Window error: {}", err),
+            #[cfg(feature = "gui")]
+            Error::Settings(err) => write!(f, "Invalid TOML file: {}", err),
+            #[cfg(feature = "gui")]
+            Error::SettingsWrite(err) => write!(f, "Could not write TOML: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+#[cfg(feature = "gui")]
+impl From<tobj::LoadError> for Error {
+    fn from(err: tobj::LoadError) -> Self {
+        Error::Obj(err)
+    }
+}
+
+#[cfg(feature = "gui")]
+impl From<glium::ProgramCreationError> for Error {
+    fn from(err: glium::ProgramCreationError) -> Self {
+        Error::Shader(err)
+    }
+}
+
+#[cfg(feature = "gui")]
+impl From<glium::vertex::BufferCreationError> for Error {
+    fn from(err: glium::vertex::BufferCreationError) -> Self {
+        Error::VertexBuffer(err)
+    }
+}
+
+#[cfg(feature = "gui")]
+impl From<glium::index::BufferCreationError> for Error {
+    fn from(err: glium::index::BufferCreationError) -> Self {
+        Error::IndexBuffer(err)
+    }
+}
+
+#[cfg(feature = "gui")]
+impl From<glium::DrawError> for Error {
+    fn from(err: glium::DrawError) -> Self {
+        Error::Draw(err)
+    }
+}
+
+#[cfg(feature = "gui")]
+impl From<imgui_glium_renderer::RendererError> for Error {
+    fn from(err: imgui_glium_renderer::RendererError) -> Self {
+        Error::ImGui(err)
+    }
+}
+
+#[cfg(feature = "gui")]
+impl From<glium::glutin::error::ExternalError> for Error {
+    fn from(err: glium::glutin::error::ExternalError) -> Self {
+        Error::Window(err)
+    }
+}
+
+#[cfg(feature = "gui")]
+impl From<toml::de::Error> for Error {
+    fn from(err: toml::de::Error) -> Self {
+        Error::Settings(err)
+    }
+}
+
+#[cfg(feature = "gui")]
+impl From<toml::ser::Error> for Error {
+    fn from(err: toml::ser::Error) -> Self {
+        Error::SettingsWrite(err)
+    }
+}
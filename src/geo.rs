@@ -0,0 +1,128 @@
+//! Conversions from and to the types of [geo-types](https://docs.rs/geo-types),
+//! to run the algorithms on the geometries of the geo ecosystem and compare the results.
+//!
+//! The algorithms compute with `f32` coordinates, those of the results are converted back to `T`.
+
+use crate::{
+    algorithms::{ GrahamScan, Incremental2dTriangulation, Operation, hull_positions, boolean::{ self, Contour, Edge } },
+    math::Vec2,
+    task::Progress,
+};
+
+use geo_types::{ Coordinate, CoordinateType, LineString, MultiPoint, MultiPolygon, Point, Polygon, Triangle };
+use num_traits::Float;
+
+use std::{ cmp::Ordering, slice };
+
+impl<T: CoordinateType + Float> From<Coordinate<T>> for Vec2 {
+    fn from(c: Coordinate<T>) -> Self {
+        Vec2::new(c.x.to_f32().unwrap_or(f32::NAN), c.y.to_f32().unwrap_or(f32::NAN))
+    }
+}
+
+impl<T: CoordinateType + Float> From<Point<T>> for Vec2 {
+    fn from(p: Point<T>) -> Self {
+        p.0.into()
+    }
+}
+
+impl<T: CoordinateType + Float> From<Vec2> for Coordinate<T> {
+    fn from(v: Vec2) -> Self {
+        // Any f32 can be cast to a float type, NaN and infinities included
+        Coordinate {
+            x: T::from(v.x).unwrap(),
+            y: T::from(v.y).unwrap(),
+        }
+    }
+}
+
+impl<T: CoordinateType + Float> From<Vec2> for Point<T> {
+    fn from(v: Vec2) -> Self {
+        Point(v.into())
+    }
+}
+
+/// Geometries whose vertices can be used as the input points of the algorithms.
+pub trait ToPoints {
+    fn to_points(&self) -> Vec<Vec2>;
+}
+
+impl<T: CoordinateType + Float> ToPoints for MultiPoint<T> {
+    fn to_points(&self) -> Vec<Vec2> {
+        self.0.iter().map(|&p| p.into()).collect()
+    }
+}
+
+impl<T: CoordinateType + Float> ToPoints for LineString<T> {
+    /// The last point of a closed line string is skipped, it is the same as the first one.
+    fn to_points(&self) -> Vec<Vec2> {
+        let mut coords = self.0.as_slice();
+        if coords.len() > 1 && coords.first() == coords.last() {
+            coords = &coords[..coords.len() - 1];
+        }
+        coords.iter().map(|&c| c.into()).collect()
+    }
+}
+
+impl<T: CoordinateType + Float> ToPoints for Polygon<T> {
+    /// Only the exterior ring is used, the interiors are inside of it.
+    fn to_points(&self) -> Vec<Vec2> {
+        self.exterior().to_points()
+    }
+}
+
+/// Returns the convex hull of the points of `geometry` computed with the Graham scan,
+/// as a counter-clockwise polygon without interiors.
+pub fn convex_hull<T, G>(geometry: &G) -> Polygon<T>
+where T: CoordinateType + Float, G: ToPoints {
+    let points = geometry.to_points();
+    let hull = hull_positions(&points, &GrahamScan::scan(&points));
+    let exterior = hull.into_iter().map(Coordinate::from).collect::<Vec<_>>();
+    Polygon::new(LineString(exterior), Vec::new())
+}
+
+/// Triangulates the points of `geometry`, the triangulation is a Delaunay triangulation if `delaunay` is true.
+pub fn triangulate<T, G>(geometry: &G, delaunay: bool) -> Vec<Triangle<T>>
+where T: CoordinateType + Float, G: ToPoints {
+    let (points, indices) = match Incremental2dTriangulation::compute(geometry.to_points(), delaunay, &Progress::default()) {
+        Some(triangulation) => triangulation,
+        None => return Vec::new(), // Only happens if the computation is cancelled
+    };
+
+    indices.chunks(3)
+        .map(|tri| Triangle(points[tri[0]].into(), points[tri[1]].into(), points[tri[2]].into()))
+        .collect()
+}
+
+/// Returns the contours of the polygon, its exterior counter-clockwise and its interiors clockwise
+fn contours<T: CoordinateType + Float>(polygon: &Polygon<T>) -> Vec<Contour> {
+    let mut contours = vec![ boolean::polygon_contour(&polygon.exterior().to_points()) ];
+    for interior in polygon.interiors() {
+        let contour = boolean::polygon_contour(&interior.to_points());
+        contours.push(contour.iter().rev().map(Edge::reversed).collect());
+    }
+    contours
+}
+
+/// Returns the union, intersection, difference or exclusive or of two polygons with their interiors.
+/// Each polygon of the result has a counter-clockwise exterior and clockwise interiors.
+pub fn boolean<T: CoordinateType + Float>(a: &Polygon<T>, b: &Polygon<T>, operation: Operation) -> MultiPolygon<T> {
+    let area = |contour: &Contour| boolean::area(slice::from_ref(contour));
+    let ring = |contour: &Contour| LineString(contour.iter().map(|edge| edge.start().into()).collect());
+    let (exteriors, interiors): (Vec<_>, Vec<_>) = boolean::boolean(&contours(a), &contours(b), operation)
+        .into_iter()
+        .partition(|contour| area(contour) > 0.0);
+
+    // Each interior goes in the smallest exterior around it
+    let mut polygons = exteriors.iter().map(|exterior| (ring(exterior), Vec::new())).collect::<Vec<_>>();
+    for interior in interiors.iter() {
+        let inside = interior[0].point(0.5);
+        let around = (0..exteriors.len())
+            .filter(|&i| boolean::contains(slice::from_ref(&exteriors[i]), inside))
+            .min_by(|&i, &j| area(&exteriors[i]).partial_cmp(&area(&exteriors[j])).unwrap_or(Ordering::Equal));
+        if let Some(i) = around {
+            polygons[i].1.push(ring(interior));
+        }
+    }
+    MultiPolygon(polygons.into_iter().map(|(exterior, interiors)| Polygon::new(exterior, interiors)).collect())
+}
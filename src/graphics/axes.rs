@@ -1,6 +1,7 @@
 use crate::{
-    graphics::OrbitCamera, ui::window::algorithms::Drawable,
-    math::{ ToArray, Vec3 },
+    Result,
+    graphics::{ OrbitCamera, Viewport }, ui::window::algorithms::Drawable,
+    math::{ ToArray, Vec2, Vec3 },
 };
 
 use glium::{
@@ -8,6 +9,7 @@ use glium::{
     backend::Facade, vertex::VertexBufferAny,
 };
 use cgmath::SquareMatrix;
+use winit::{ event::Event, window::Window };
 
 pub struct Axes {
     buffer: VertexBufferAny,
@@ -16,36 +18,43 @@ pub struct Axes {
 }
 
 impl Axes {
-    pub fn new(facade: &dyn Facade) -> Self {
+    pub fn new(facade: &dyn Facade) -> Result<Self> {
         let vs = super::SHADERS.obj_vs;
         let fs = super::SHADERS.obj_fs;
-        let program = Program::from_source(facade, vs, fs, None)
-                                    .expect("Could not compile shaders");
+        let program = Program::from_source(facade, vs, fs, None)?;
 
-        let (vertex_buffer, _scale) = super::load_obj(facade, &std::path::PathBuf::from("models/axes.obj"));
+        let (vertex_buffer, _scale) = super::load_obj(facade, &std::path::PathBuf::from("models/axes.obj"))?;
 
-        let mut cam = OrbitCamera::new(6.5, Vec3::new(0.0, 0.0, 0.0), 45.0, 1.0, 0.1, 50.0);
+        let mut cam = OrbitCamera::new(6.5, Vec3::new(0.0, 0.0, 0.0), 45.0, 0.1, 50.0);
         cam.zoom = false;
 
-        Self {
+        Ok(Self {
             buffer: vertex_buffer,
             program,
             cam,
-        }
+        })
+    }
+
+    /// Rotation sensitivity of the camera, which should match the one of the scene camera.
+    pub fn set_sensitivity(&mut self, sensitivity: Vec2) {
+        self.cam.sensitivity = sensitivity;
     }
 }
 
 impl Drawable for Axes {
-    fn draw(&self, target: &mut Frame) {
+    /// Draws the axes in the bottom right corner of the viewport.
+    fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
         let uniforms = uniform! {
             model: cgmath::Matrix4::<f32>::identity().to_array(),
-            projection: self.cam.get_projection_matrix().to_array(),
+            projection: self.cam.get_projection_matrix(1.0).to_array(),
             view: self.cam.get_view_matrix().to_array(),
         };
 
-        let width = 128;
+        // Shrink the axes along with small viewports
+        let rect = viewport.rect(target);
+        let width = 128.min(rect.width / 2).min(rect.height / 2);
         let height = width;
-        let offset = 32;
+        let offset = width / 4;
 
         let params = glium::DrawParameters {
             depth: glium::Depth {
@@ -54,8 +63,8 @@ impl Drawable for Axes {
                 .. Default::default()
             },
             viewport: Some(glium::Rect {
-                left: target.get_dimensions().0 - width - offset,
-                bottom: offset,
+                left: rect.left + rect.width - width - offset,
+                bottom: rect.bottom + offset,
                 width,
                 height,
             }),
@@ -63,11 +72,13 @@ impl Drawable for Axes {
         };
 
         target.draw(&self.buffer,
-                    &glium::index::NoIndices(glium::index::PrimitiveType::TrianglesList),
-                    &self.program, &uniforms, &params).unwrap();
+                    glium::index::NoIndices(glium::index::PrimitiveType::TrianglesList),
+                    &self.program, &uniforms, &params)?;
+        Ok(())
     }
 
-    fn handle_events(&mut self, window: &winit::Window, event: &winit::Event, io: &imgui::Io) {
+    fn handle_events(&mut self, window: &Window, event: &Event<()>, io: &imgui::Io, _viewport: &Viewport) -> Result<()> {
         self.cam.handle_events(window, event, io);
+        Ok(())
     }
 }
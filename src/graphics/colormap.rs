@@ -0,0 +1,121 @@
+use crate::{ Result, graphics::{ self, Viewport }, math::Vec2 };
+
+use glium::{
+    index::{ NoIndices, PrimitiveType }, Surface, Frame, Program, VertexBuffer,
+    backend::Facade,
+};
+
+/// Colors of the heights between the lowest and the highest, like on a physical map:
+/// green lowlands, tan hills, brown mountains and white summits
+const STOPS: [(f32, [f32; 3]); 4] = [
+    (0.0, [ 0.3, 0.6, 0.3 ]),
+    (0.5, [ 0.8, 0.75, 0.45 ]),
+    (0.8, [ 0.6, 0.45, 0.3 ]),
+    (1.0, [ 0.95, 0.95, 0.95 ]),
+];
+
+#[derive(Copy, Clone, Debug)]
+struct Vertex {
+    position: Vec2,
+    color: [f32; 3],
+}
+
+implement_vertex!(Vertex, position, color);
+
+/// Returns the color of `t` between 0 and 1, clamped, interpolated between the stops of the map.
+pub fn color(t: f32) -> [f32; 3] {
+    let t = t.clamp(0.0, 1.0);
+    let pair = STOPS.windows(2).find(|pair| t <= pair[1].0).unwrap_or(&STOPS[2..]);
+    let s = (t - pair[0].0) / (pair[1].0 - pair[0].0);
+    let mut color = [ 0.0; 3 ];
+    for (k, channel) in color.iter_mut().enumerate() {
+        *channel = pair[0].1[k] + (pair[1].1[k] - pair[0].1[k]) * s;
+    }
+    color
+}
+
+/// Distinct colors of the categories, e.g. clusters, reused in order when there are more categories
+const PALETTE: [[f32; 3]; 8] = [
+    [ 0.9, 0.35, 0.35 ],
+    [ 0.35, 0.65, 0.95 ],
+    [ 0.45, 0.85, 0.4 ],
+    [ 0.95, 0.75, 0.25 ],
+    [ 0.75, 0.45, 0.9 ],
+    [ 0.3, 0.85, 0.8 ],
+    [ 0.95, 0.55, 0.75 ],
+    [ 0.7, 0.6, 0.45 ],
+];
+
+/// Returns the color of the category `idx`, neighbouring categories have clearly different colors.
+pub fn category(idx: usize) -> [f32; 3] {
+    PALETTE[idx % PALETTE.len()]
+}
+
+/// Returns the smallest and the largest of `values`, or `None` if there are none.
+pub fn range(values: &[f32]) -> Option<(f32, f32)> {
+    if values.is_empty() {
+        return None;
+    }
+    Some(values.iter().fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), &v| (min.min(v), max.max(v))))
+}
+
+/// 2D vertices with a value each, drawn with the color of the value relative to the range of the values,
+/// e.g. points colored by their height. The colors are interpolated across lines and triangles.
+pub struct ColorMappedShape<'f> {
+    facade: &'f dyn Facade,
+    program: Program,
+    buffer: VertexBuffer<Vertex>,
+    primitive: PrimitiveType,
+    /// Diameter of the points or width of the lines, in pixels
+    pub size: f32,
+}
+
+impl<'f> ColorMappedShape<'f> {
+    pub fn new(facade: &'f dyn Facade, primitive: PrimitiveType) -> Result<Self> {
+        let program = Program::from_source(facade, graphics::SHADERS._2d_color_vs, graphics::SHADERS.color_fs, None)?;
+        Ok(Self {
+            facade,
+            program,
+            buffer: VertexBuffer::empty(facade, 0)?,
+            primitive,
+            size: if primitive == PrimitiveType::Points { 8.0 } else { 1.0 },
+        })
+    }
+
+    /// Sets the vertices and their values, one per vertex. The values between `min` and `max`
+    /// go through the whole map, a single value is drawn with the color of the middle.
+    /// `max` can be below `min` to reverse the map.
+    pub fn set_vertices(&mut self, vertices: &[Vec2], values: &[f32], (min, max): (f32, f32)) -> Result<()> {
+        let vertices = vertices.iter()
+                            .zip(values)
+                            .map(|(&position, &value)| Vertex {
+                                position,
+                                color: color(if max != min { (value - min) / (max - min) } else { 0.5 }),
+                            })
+                            .collect::<Vec<_>>();
+        self.buffer = VertexBuffer::new(self.facade, &vertices)?;
+        Ok(())
+    }
+
+    /// Sets the vertices with a color each instead of a value, e.g. the colors of their [`category`].
+    pub fn set_colored_vertices(&mut self, vertices: &[Vec2], colors: &[[f32; 3]]) -> Result<()> {
+        let vertices = vertices.iter()
+                            .zip(colors)
+                            .map(|(&position, &color)| Vertex { position, color })
+                            .collect::<Vec<_>>();
+        self.buffer = VertexBuffer::new(self.facade, &vertices)?;
+        Ok(())
+    }
+
+    pub fn clear(&mut self) -> Result<()> {
+        self.set_vertices(&[], &[], (0.0, 0.0))
+    }
+
+    pub fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        let mut draw_params = viewport.draw_parameters(target);
+        draw_params.point_size = Some(self.size);
+        draw_params.line_width = Some(self.size);
+        target.draw(&self.buffer, NoIndices(self.primitive), &self.program, &glium::uniforms::EmptyUniforms, &draw_params)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+use crate::{ Result, graphics::Viewport, math::Vec2 };
+
+use glium::{
+    index, Surface, Frame, Program, VertexBuffer,
+    backend::Facade,
+};
+
+#[derive(Copy, Clone, Debug)]
+struct Vertex {
+    position: Vec2,
+}
+
+implement_vertex!(Vertex, position);
+
+/// Lines drawn in normalized device coordinates every `spacing` units,
+/// used as a visual guide when snapping points to a grid.
+pub struct Grid<'f> {
+    facade: &'f dyn Facade,
+    program: Program,
+    buffer: VertexBuffer<Vertex>,
+    spacing: f32,
+}
+
+impl<'f> Grid<'f> {
+    pub fn new(facade: &'f dyn Facade, spacing: f32) -> Result<Self> {
+        let vs = super::SHADERS._2d_vs;
+        let fs = super::SHADERS.basic_fs;
+        let program = Program::from_source(facade, vs, fs, None)?;
+
+        let mut grid = Self {
+            facade,
+            program,
+            buffer: VertexBuffer::empty(facade, 0)?,
+            spacing: 0.0,
+        };
+        grid.set_spacing(spacing)?;
+        Ok(grid)
+    }
+
+    pub fn spacing(&self) -> f32 {
+        self.spacing
+    }
+
+    /// Changes the distance between two lines and regenerates the buffer if needed.
+    pub fn set_spacing(&mut self, spacing: f32) -> Result<()> {
+        if spacing <= 0.0 || crate::math::cmp_f32(spacing, self.spacing) {
+            return Ok(());
+        }
+        self.spacing = spacing;
+
+        let mut lines = Vec::new();
+        let steps = (1.0 / spacing).floor() as i32;
+        for i in -steps..=steps {
+            let offset = i as f32 * spacing;
+            lines.push(Vertex { position: Vec2::new(offset, -1.0) });
+            lines.push(Vertex { position: Vec2::new(offset, 1.0) });
+            lines.push(Vertex { position: Vec2::new(-1.0, offset) });
+            lines.push(Vertex { position: Vec2::new(1.0, offset) });
+        }
+        self.buffer = VertexBuffer::new(self.facade, &lines)?; // Regenerate the buffer
+        Ok(())
+    }
+
+    pub fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        let indices = index::NoIndices(index::PrimitiveType::LinesList);
+        let uniforms = uniform! {
+            color: [ 0.2_f32, 0.2_f32, 0.2_f32 ],
+        };
+        let draw_params = viewport.draw_parameters(target);
+        target.draw(&self.buffer, indices, &self.program, &uniforms, &draw_params)?;
+        Ok(())
+    }
+}
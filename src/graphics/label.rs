@@ -0,0 +1,56 @@
+//! Numbers drawn with lines in the 2D view, like on a seven-segment display, e.g. to label the contours of a terrain.
+//! Only digits, minus signs and decimal points are drawn, the other characters leave a blank.
+
+use crate::math::Vec2;
+
+/// Width of a character relative to its height
+const WIDTH: f32 = 0.5;
+/// Space between two characters relative to their height
+const SPACING: f32 = 0.25;
+
+/// Segments of a digit, from the top one clockwise and then the middle one, as ends in a unit wide
+/// and unit tall box whose origin is at the bottom left
+const SEGMENTS: [((f32, f32), (f32, f32)); 7] = [
+    ((0.0, 1.0), (1.0, 1.0)),
+    ((1.0, 1.0), (1.0, 0.5)),
+    ((1.0, 0.5), (1.0, 0.0)),
+    ((1.0, 0.0), (0.0, 0.0)),
+    ((0.0, 0.0), (0.0, 0.5)),
+    ((0.0, 0.5), (0.0, 1.0)),
+    ((0.0, 0.5), (1.0, 0.5)),
+];
+
+/// Lit segments of each digit, the bit `i` for the segment `i`
+const DIGITS: [u8; 10] = [
+    0b011_1111, 0b000_0110, 0b101_1011, 0b100_1111, 0b110_0110,
+    0b110_1101, 0b111_1101, 0b000_0111, 0b111_1111, 0b110_1111,
+];
+
+/// Returns the lines, 2 points each, that draw `text` with characters `height` tall,
+/// centered on `center`. Draw them as `LinesList`.
+pub fn text_lines(text: &str, center: Vec2, height: f32) -> Vec<Vec2> {
+    let advance = (WIDTH + SPACING) * height;
+    let width = text.chars().count() as f32 * advance - SPACING * height;
+    let mut origin = Vec2::new(center.x - width / 2.0, center.y - height / 2.0);
+
+    let mut lines = Vec::new();
+    for c in text.chars() {
+        let point = |(x, y): (f32, f32)| Vec2::new(origin.x + x * WIDTH * height, origin.y + y * height);
+        let segments: u8 = match c {
+            '0'..='9' => DIGITS[c as usize - '0' as usize],
+            '-' => 1 << 6,
+            _ => 0,
+        };
+        for (i, &(a, b)) in SEGMENTS.iter().enumerate() {
+            if segments & (1 << i) != 0 {
+                lines.extend_from_slice(&[ point(a), point(b) ]);
+            }
+        }
+        if c == '.' {
+            // A short vertical stroke at the baseline
+            lines.extend_from_slice(&[ point((0.5, 0.0)), point((0.5, 0.1)) ]);
+        }
+        origin.x += advance;
+    }
+    lines
+}
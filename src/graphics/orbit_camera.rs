@@ -1,35 +1,60 @@
-use crate::math::{ self, Vec2, Vec3 };
-
-use cgmath::{ Matrix4, Point3 };
-use glium::glutin::{ Event, WindowEvent, DeviceEvent, MouseScrollDelta };
+use crate::{
+    algorithms::bounding::Sphere,
+    math::{ self, Vec2, Vec3 },
+};
+
+use cgmath::{ Matrix4, Point3, InnerSpace, SquareMatrix };
+use serde::{ Serialize, Deserialize };
+use winit::{
+    event::{ Event, WindowEvent, DeviceEvent, MouseScrollDelta, MouseButton, ElementState },
+    window::Window,
+};
+
+/// Position of an orbit camera around its target, exported to reproduce a view.
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct CameraPose {
+    pub target: [f32; 3],
+    pub distance: f32,
+    /// Angles around the vertical axis and above the horizontal plane, in radians
+    pub phi: f32,
+    pub theta: f32,
+}
 
 pub struct OrbitCamera {
     pub target: Vec3,
+    /// Rotation in radians per pixel of mouse motion
     pub sensitivity: Vec2,
+    /// Distance per line of mouse wheel scroll
+    pub zoom_sensitivity: f32,
     pub rotate: bool,
     pub zoom: bool,
 
     is_rotating: bool,
 
-    projection_matrix: Matrix4<f32>,
+    fov: cgmath::Rad<f32>,
+    near: f32,
+    far: f32,
     distance: f32,
     phi: f32,
     theta: f32,
 }
 
 impl OrbitCamera {
-    pub fn new(distance: f32, target: Vec3, fov: f32, aspect_ratio: f32, near: f32, far: f32) -> Self {
-        let fov: cgmath::Rad<f32> = cgmath::Deg { 0: fov }.into();
+    pub fn new(distance: f32, target: Vec3, fov: f32, near: f32, far: f32) -> Self {
+        let fov: cgmath::Rad<f32> = cgmath::Deg(fov).into();
 
         Self {
             target,
             sensitivity: Vec2::new(0.01, 0.01),
+            zoom_sensitivity: 1.0,
             rotate: true,
             zoom: true,
 
             is_rotating: false,
 
-            projection_matrix: cgmath::perspective(fov, aspect_ratio, near, far),
+            fov,
+            near,
+            far,
             distance,
             phi: 90.0_f32.to_radians(),
             theta: 0.0,
@@ -42,7 +67,7 @@ impl OrbitCamera {
             self.theta.sin(),
             self.theta.cos() * self.phi.sin(),
         );
-        pos.0 *= self.distance;
+        pos.0 = self.target.0 + pos.0 * self.distance;
         pos
     }
 
@@ -58,8 +83,80 @@ impl OrbitCamera {
         Matrix4::<f32>::look_at(pos, target, up)
     }
 
-    pub fn get_projection_matrix(&self) -> &Matrix4<f32> {
-        &self.projection_matrix
+    /// Returns the view matrix of an eye moved by `offset` to the right of the camera, looking in the same direction.
+    pub fn get_eye_view_matrix(&self, offset: f32) -> Matrix4<f32> {
+        Matrix4::from_translation(cgmath::vec3(-offset, 0.0, 0.0)) * self.get_view_matrix()
+    }
+
+    /// Returns the unit vector along which the camera looks.
+    pub fn get_direction(&self) -> Vec3 {
+        (self.target.0 - self.get_position().0).normalize().into()
+    }
+
+    /// Returns the origin and the unit direction of the ray from the camera through `coords`,
+    /// between -1 and 1 in a viewport with the given aspect ratio.
+    pub fn ray(&self, coords: Vec2, aspect_ratio: f32) -> (Vec3, Vec3) {
+        let forward = self.get_direction().0;
+        let right = forward.cross(cgmath::vec3(0.0, 1.0, 0.0)).normalize();
+        let up = right.cross(forward);
+        let half_height = (self.fov.0 / 2.0).tan();
+        let direction = forward + right * (coords.x * half_height * aspect_ratio) + up * (coords.y * half_height);
+        (self.get_position(), direction.normalize().into())
+    }
+
+    /// Returns the position of `p` between -1 and 1 in a viewport with the given aspect ratio,
+    /// or `None` if it is behind the camera.
+    pub fn project(&self, p: Vec3, aspect_ratio: f32) -> Option<Vec2> {
+        let clip = self.get_projection_matrix(aspect_ratio) * self.get_view_matrix() * p.0.extend(1.0);
+        if clip.w <= 0.0 {
+            return None;
+        }
+        Some(Vec2::new(clip.x / clip.w, clip.y / clip.w))
+    }
+
+    /// Returns the distance between the camera and its target.
+    pub fn distance(&self) -> f32 {
+        self.distance
+    }
+
+    /// Returns the projection matrix for a viewport with the given aspect ratio.
+    pub fn get_projection_matrix(&self, aspect_ratio: f32) -> Matrix4<f32> {
+        cgmath::perspective(self.fov, aspect_ratio, self.near, self.far)
+    }
+
+    /// Returns the projection matrix of an eye moved by `offset` to the right of the camera.
+    /// Its frustum is shifted back towards the target, so that the points at the distance of the target
+    /// are at the same place in the views of both eyes and appear at the depth of the screen.
+    pub fn get_eye_projection_matrix(&self, aspect_ratio: f32, offset: f32) -> Matrix4<f32> {
+        let projection = self.get_projection_matrix(aspect_ratio);
+        let mut shift = Matrix4::identity();
+        shift.w.x = projection.x.x * offset / self.distance;
+        shift * projection
+    }
+
+    pub fn pose(&self) -> CameraPose {
+        CameraPose {
+            target: [ self.target.0.x, self.target.0.y, self.target.0.z ],
+            distance: self.distance,
+            phi: self.phi,
+            theta: self.theta,
+        }
+    }
+
+    /// Moves the camera to `pose`, within the limits of its distance and of its angle above the horizontal plane.
+    pub fn set_pose(&mut self, pose: &CameraPose) {
+        self.target = Vec3::new(pose.target[0], pose.target[1], pose.target[2]);
+        self.distance = math::clamp(pose.distance, 0.5, 50.0);
+        self.phi = pose.phi;
+        self.theta = math::clamp(pose.theta, (-89.0_f32).to_radians(), 89.0_f32.to_radians());
+    }
+
+    /// Looks at the center of `sphere` from far enough to see all of it, keeping the direction of view.
+    pub fn frame(&mut self, sphere: &Sphere) {
+        // The sphere fits in the vertical field of view, with a margin
+        let distance = 1.1 * sphere.radius / (self.fov.0 / 2.0).sin();
+        self.target = sphere.center;
+        self.distance = math::clamp(distance, 0.5, 50.0);
     }
 
     pub fn zoom(&mut self, offset: f32) {
@@ -67,20 +164,20 @@ impl OrbitCamera {
         self.distance = math::clamp(self.distance, 0.5, 50.0);
     }
 
-    pub fn handle_events(&mut self, _window: &winit::Window, event: &winit::Event, io: &imgui::Io) {
+    pub fn handle_events(&mut self, _window: &Window, event: &Event<()>, io: &imgui::Io) {
         if let Event::WindowEvent { event, .. } = event {
             if let WindowEvent::MouseInput { button, state, .. } = event {
                 if !io.want_capture_mouse && // Ignore clicks when the cursor is over an ImGui window
-                    button == &winit::MouseButton::Left && self.rotate {
+                    button == &MouseButton::Left && self.rotate {
 
-                    self.is_rotating = state == &winit::ElementState::Pressed;
+                    self.is_rotating = state == &ElementState::Pressed;
                 }
             }
 
             if let WindowEvent::MouseWheel { delta, .. } = event {
                 if let MouseScrollDelta::LineDelta { 0: _scroll_x, 1: scroll_y } = delta {
                     if !io.want_capture_mouse && self.zoom {
-                        self.zoom(*scroll_y)
+                        self.zoom(*scroll_y * self.zoom_sensitivity)
                     }
                 }
             }
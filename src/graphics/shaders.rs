@@ -1,21 +1,31 @@
 pub struct Shaders {
     // Vertex shaders
     pub _2d_vs: &'static str,
+    pub _2d_color_vs: &'static str,
     pub _3d_vs: &'static str,
     pub obj_vs: &'static str,
+    pub draw_list_vs: &'static str,
 
     // Fragment shaders
     pub basic_fs: &'static str,
+    pub color_fs: &'static str,
     pub phong_fs: &'static str,
+    pub phong_color_fs: &'static str,
     pub obj_fs: &'static str,
+    pub draw_list_fs: &'static str,
 }
 
 pub static SHADERS: Shaders = Shaders {
     _2d_vs: include_str!("../../shaders/2d.vs.glsl"),
+    _2d_color_vs: include_str!("../../shaders/2d_color.vs.glsl"),
     _3d_vs: include_str!("../../shaders/3d.vs.glsl"),
     obj_vs: include_str!("../../shaders/obj.vs.glsl"),
+    draw_list_vs: include_str!("../../shaders/draw_list.vs.glsl"),
 
     basic_fs: include_str!("../../shaders/basic.fs.glsl"),
+    color_fs: include_str!("../../shaders/color.fs.glsl"),
     phong_fs: include_str!("../../shaders/phong.fs.glsl"),
+    phong_color_fs: include_str!("../../shaders/phong_color.fs.glsl"),
     obj_fs: include_str!("../../shaders/obj.fs.glsl"),
+    draw_list_fs: include_str!("../../shaders/draw_list.fs.glsl"),
 };
@@ -0,0 +1,74 @@
+use crate::{ Result, graphics::Viewport, math::Vec2 };
+
+use glium::{
+    index::{ NoIndices, PrimitiveType }, Surface, Frame, Program, VertexBuffer,
+    backend::Facade,
+};
+
+#[derive(Copy, Clone, Debug)]
+struct Vertex {
+    position: Vec2,
+}
+
+implement_vertex!(Vertex, position);
+
+/// 2D vertices drawn with a single color, e.g. the points, edges or circles computed by an algorithm.
+/// Drawn with the 2D program of the renderer that owns it.
+pub struct Shape<'f> {
+    facade: &'f dyn Facade,
+    buffer: VertexBuffer<Vertex>,
+    primitive: PrimitiveType,
+    pub color: [f32; 3],
+    /// Diameter of the points or width of the lines, in pixels
+    pub size: f32,
+}
+
+impl<'f> Shape<'f> {
+    /// Creates an empty shape, the vertices are grouped as points, lines or triangles depending on `primitive`.
+    pub fn new(facade: &'f dyn Facade, primitive: PrimitiveType, color: [f32; 3]) -> Result<Self> {
+        Ok(Self {
+            facade,
+            buffer: VertexBuffer::empty(facade, 0)?,
+            primitive,
+            color,
+            size: if primitive == PrimitiveType::Points { 8.0 } else { 1.0 },
+        })
+    }
+
+    pub fn set_vertices(&mut self, vertices: &[Vec2]) -> Result<()> {
+        let vertices = vertices.iter()
+                            .map(|&position| Vertex { position })
+                            .collect::<Vec<_>>();
+        self.buffer = VertexBuffer::new(self.facade, &vertices)?;
+        Ok(())
+    }
+
+    pub fn clear(&mut self) -> Result<()> {
+        self.set_vertices(&[])
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buffer.len() == 0
+    }
+
+    pub fn draw(&self, target: &mut Frame, program: &Program, viewport: &Viewport) -> Result<()> {
+        let uniforms = uniform! {
+            color: self.color,
+        };
+        let mut draw_params = viewport.draw_parameters(target);
+        draw_params.point_size = Some(self.size);
+        draw_params.line_width = Some(self.size);
+        target.draw(&self.buffer, NoIndices(self.primitive), program, &uniforms, &draw_params)?;
+        Ok(())
+    }
+}
+
+/// Returns the vertices of the edges of triangles, 3 indices into `points` per triangle, to draw as `LinesList`.
+pub fn triangle_edges(points: &[Vec2], indices: &[usize]) -> Vec<Vec2> {
+    indices.chunks(3)
+        .flat_map(|tri| {
+            let (a, b, c) = (points[tri[0]], points[tri[1]], points[tri[2]]);
+            vec![ a, b, b, c, c, a ]
+        })
+        .collect()
+}
@@ -0,0 +1,111 @@
+//! Stereo rendering of the 3D views: the scene is drawn once per eye, from two cameras side by side.
+
+use crate::graphics::{ OrbitCamera, Viewport };
+
+use cgmath::Matrix4;
+use glium::{ Surface, Frame, DrawParameters };
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum StereoMode {
+    Off,
+    /// Both views in the same viewport, the left one in red and the right one in cyan, for red-cyan glasses
+    Anaglyph,
+    /// The left view in the left half of the viewport and the right view in the right half, for a stereoscope or parallel viewing
+    SideBySide,
+}
+
+impl StereoMode {
+    pub const ALL: [StereoMode; 3] = [ StereoMode::Off, StereoMode::Anaglyph, StereoMode::SideBySide ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            StereoMode::Off => "Off",
+            StereoMode::Anaglyph => "Anaglyph (red-cyan)",
+            StereoMode::SideBySide => "Side by side",
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Stereo {
+    pub mode: StereoMode,
+    /// Distance between the eyes relative to the distance of the camera to its target
+    pub separation: f32,
+}
+
+impl Default for Stereo {
+    fn default() -> Self {
+        Self {
+            mode: StereoMode::Off,
+            separation: 0.03,
+        }
+    }
+}
+
+/// Region and camera from which one eye sees the scene.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Eye {
+    pub viewport: Viewport,
+    /// Distance of the eye to the right of the camera, negative for the left eye
+    pub offset: f32,
+    /// Color channels written by the eye
+    pub color_mask: (bool, bool, bool, bool),
+    /// Whether the depth buffer is cleared before drawing, when the eye draws over the view of the other one
+    pub clear_depth: bool,
+}
+
+impl Stereo {
+    /// Returns the eyes to draw the scene for in `viewport`: a single one, at the camera, when stereo is off.
+    pub fn eyes(&self, viewport: &Viewport, cam: &OrbitCamera) -> Vec<Eye> {
+        let offset = self.separation * cam.distance() / 2.0;
+        match self.mode {
+            StereoMode::Off => vec![ Eye::new(*viewport, 0.0) ],
+            StereoMode::Anaglyph => vec![
+                Eye { color_mask: (true, false, false, true), .. Eye::new(*viewport, -offset) },
+                Eye { color_mask: (false, true, true, true), clear_depth: true, .. Eye::new(*viewport, offset) },
+            ],
+            StereoMode::SideBySide => {
+                let half_width = viewport.width / 2.0;
+                vec![
+                    Eye::new(Viewport::new(viewport.left, viewport.bottom, half_width, viewport.height), -offset),
+                    Eye::new(Viewport::new(viewport.left + half_width, viewport.bottom, half_width, viewport.height), offset),
+                ]
+            },
+        }
+    }
+}
+
+impl Eye {
+    fn new(viewport: Viewport, offset: f32) -> Self {
+        Self {
+            viewport,
+            offset,
+            color_mask: (true, true, true, true),
+            clear_depth: false,
+        }
+    }
+
+    /// Prepares `target` for the eye to draw on it.
+    pub fn begin(&self, target: &mut Frame) {
+        if self.clear_depth {
+            let rect = self.viewport.rect(target);
+            target.clear(Some(&rect), None, false, Some(1.0), None);
+        }
+    }
+
+    pub fn view_matrix(&self, cam: &OrbitCamera) -> Matrix4<f32> {
+        cam.get_eye_view_matrix(self.offset)
+    }
+
+    pub fn projection_matrix(&self, cam: &OrbitCamera, target: &Frame) -> Matrix4<f32> {
+        cam.get_eye_projection_matrix(self.viewport.aspect_ratio(target), self.offset)
+    }
+
+    /// Returns the default draw parameters restricted to the viewport of the eye and to its color channels.
+    pub fn draw_parameters<'a>(&self, target: &Frame) -> DrawParameters<'a> {
+        DrawParameters {
+            color_mask: self.color_mask,
+            .. self.viewport.draw_parameters(target)
+        }
+    }
+}
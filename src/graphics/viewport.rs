@@ -0,0 +1,89 @@
+use crate::math::Vec2;
+
+use glium::{ Surface, Frame, DrawParameters };
+use winit::window::Window;
+
+/// A region of the window in which an algorithm is rendered.
+/// Coordinates are fractions of the window size with the origin at the bottom left corner,
+/// so the region follows the window when it is resized.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Viewport {
+    pub left: f32,
+    pub bottom: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Default for Viewport {
+    /// A viewport that covers the whole window.
+    fn default() -> Self {
+        Self::new(0.0, 0.0, 1.0, 1.0)
+    }
+}
+
+impl Viewport {
+    pub fn new(left: f32, bottom: f32, width: f32, height: f32) -> Self {
+        Self {
+            left,
+            bottom,
+            width,
+            height,
+        }
+    }
+
+    /// Returns the region covered by the viewport in the pixels of `target`.
+    pub fn rect(&self, target: &Frame) -> glium::Rect {
+        let (width, height) = target.get_dimensions();
+        let (width, height) = (width as f32, height as f32);
+        glium::Rect {
+            left: (self.left * width).round() as u32,
+            bottom: (self.bottom * height).round() as u32,
+            width: (self.width * width).round() as u32,
+            height: (self.height * height).round() as u32,
+        }
+    }
+
+    /// Returns the default draw parameters restricted to the viewport.
+    pub fn draw_parameters<'a>(&self, target: &Frame) -> DrawParameters<'a> {
+        DrawParameters {
+            viewport: Some(self.rect(target)),
+            .. Default::default()
+        }
+    }
+
+    /// Returns the ratio between the width and the height of the viewport in the pixels of `target`.
+    pub fn aspect_ratio(&self, target: &Frame) -> f32 {
+        let rect = self.rect(target);
+        if rect.height == 0 {
+            return 1.0;
+        }
+        rect.width as f32 / rect.height as f32
+    }
+
+    /// Returns the ratio between the width and the height of the viewport in `window`.
+    pub fn window_aspect_ratio(&self, window: &Window) -> f32 {
+        let size = super::window_size(window);
+        (self.width * size.width as f32) / (self.height * size.height as f32).max(1e-6)
+    }
+
+    /// Returns whether a position in window space coordinates (origin at the top left corner) is inside the viewport.
+    pub fn contains(&self, pos: Vec2, window: &Window) -> bool {
+        let size = super::window_size(window);
+        let x = pos.x / size.width as f32;
+        let y = 1.0 - pos.y / size.height as f32;
+        x >= self.left && x <= self.left + self.width &&
+        y >= self.bottom && y <= self.bottom + self.height
+    }
+
+    /// Takes a position in window space coordinates and converts it to coordinates
+    /// between -1.0 and 1.0 relative to the viewport.
+    pub fn window_pos_to_normalized(&self, pos: Vec2, window: &Window) -> Vec2 {
+        let size = super::window_size(window);
+        let x = pos.x / size.width as f32;
+        let y = 1.0 - pos.y / size.height as f32;
+        Vec2::new(
+            (x - self.left) / self.width * 2.0 - 1.0,
+            (y - self.bottom) / self.height * 2.0 - 1.0,
+        )
+    }
+}
@@ -0,0 +1,458 @@
+//! Runs every hull and triangulation implementation on the same generated sets of points
+//! and checks that they agree, to catch the implementations that break on some inputs.
+//!
+//! The hulls must have the same vertices, up to the starting vertex and the orientation.
+//! Triangulations of the same points are not unique, so each one must cover the hull
+//! with the same number of triangles, and the Delaunay ones must not have illegal edges.
+//!
+//! The same sets of points also time the implementations and their phases, seeded so that every run can be repeated.
+//! The polygon triangulations are timed on the star-shaped polygon of the points, sorted by angle around their centroid.
+
+use crate::{
+    algorithms::{
+        Algorithm, JarvisMarch, GrahamScan, Incremental2dTriangulation, HistoryDag,
+        incremental_2d_triangulation::EdgeFlippingSteps,
+        validate_hull, validate_delaunay, repair_delaunay, hull_positions,
+        triangulate_polygon, monotone_decomposition, triangulate_monotone, lower_hull,
+    },
+    math::{ Vec2, float, polygon, morton },
+};
+
+use std::{
+    fmt,
+    cmp::Ordering,
+    time::{ Duration, Instant },
+};
+
+use rand::{ Rng, SeedableRng, rngs::StdRng };
+
+/// A way to generate points, each one stresses different cases.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Suite {
+    /// Uniformly distributed in a square
+    Uniform,
+    /// On a coarse grid, with many collinear and cocircular points
+    Grid,
+    /// On a circle, all the points are on the hull
+    Circle,
+    /// On nested regular octagons, like the convex layers of an onion
+    Onion,
+    /// On a line, nothing can be triangulated
+    Collinear,
+}
+
+impl Suite {
+    pub const ALL: [Suite; 5] = [ Suite::Uniform, Suite::Grid, Suite::Circle, Suite::Onion, Suite::Collinear ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Suite::Uniform => "uniform",
+            Suite::Grid => "grid",
+            Suite::Circle => "circle",
+            Suite::Onion => "onion",
+            Suite::Collinear => "collinear",
+        }
+    }
+
+    /// Returns the suite called `name`, as returned by [`name`](#method.name).
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|suite| suite.name() == name)
+    }
+
+    /// Generates at most `count` distinct points, fewer if the suite runs out of positions.
+    pub fn generate(self, count: usize) -> Vec<Vec2> {
+        self.generate_with(count, &mut rand::thread_rng())
+    }
+
+    /// Generates the same points as [`generate`](#method.generate) every time for the same `seed`.
+    pub fn generate_seeded(self, count: usize, seed: u64) -> Vec<Vec2> {
+        self.generate_with(count, &mut StdRng::seed_from_u64(seed))
+    }
+
+    fn generate_with<R: Rng>(self, count: usize, rng: &mut R) -> Vec<Vec2> {
+        let points = match self {
+            Suite::Uniform => (0..count).map(|_| Vec2::new(rng.gen_range(-1.0, 1.0), rng.gen_range(-1.0, 1.0))).collect::<Vec<_>>(),
+            Suite::Grid => (0..count).map(|_| Vec2::new(rng.gen_range(-1.0, 1.0), rng.gen_range(-1.0, 1.0)).snapped(0.125)).collect(),
+            Suite::Circle => (0..count)
+                                .map(|_| {
+                                    let angle = rng.gen_range(0.0, 2.0 * core::f32::consts::PI);
+                                    Vec2::new(float::cos(angle), float::sin(angle))
+                                })
+                                .collect(),
+            Suite::Onion => (0..count)
+                                .map(|idx| {
+                                    let (layer, corner) = (idx / 8, idx % 8);
+                                    let radius = 1.0 / (1 + layer) as f32;
+                                    let angle = corner as f32 * core::f32::consts::FRAC_PI_4 + layer as f32 * 0.3;
+                                    Vec2::new(radius * float::cos(angle), radius * float::sin(angle))
+                                })
+                                .collect(),
+            Suite::Collinear => {
+                // Doubling is exact, so the points are exactly collinear despite the rounding
+                (0..count).map(|_| {
+                    let t = rng.gen_range(-1.0, 1.0);
+                    Vec2::new(t, 2.0 * t)
+                }).collect()
+            },
+        };
+
+        // The duplicates would make the triangulations disagree on their number of triangles
+        let mut unique = Vec::with_capacity(points.len());
+        for p in points {
+            if !unique.contains(&p) {
+                unique.push(p);
+            }
+        }
+        unique
+    }
+}
+
+/// A convex hull implementation, returning the indices of the hull points.
+pub struct Hull {
+    pub name: &'static str,
+    pub compute: fn(&[Vec2]) -> Vec<usize>,
+}
+
+/// A triangulation implementation, returning the corners of the triangles since some of them sort the points.
+pub struct Triangulation {
+    pub name: &'static str,
+    /// Whether the result must be a Delaunay triangulation
+    pub delaunay: bool,
+    pub compute: fn(&[Vec2]) -> Vec<[Vec2; 3]>,
+}
+
+/// The hull implementations, the first one is the reference.
+pub const HULLS: [Hull; 4] = [
+    Hull {
+        name: "Graham scan",
+        compute: GrahamScan::scan,
+    },
+    Hull {
+        name: "Graham scan steps",
+        compute: |points| {
+            let mut steps = GrahamScan::run(points);
+            steps.by_ref().for_each(drop);
+            steps.hull().to_vec()
+        },
+    },
+    Hull {
+        name: "Jarvis march",
+        compute: |points| JarvisMarch::march(points.iter()),
+    },
+    Hull {
+        name: "Jarvis march steps",
+        compute: |points| {
+            let mut steps = JarvisMarch::run(points);
+            steps.by_ref().for_each(drop);
+            steps.hull().to_vec()
+        },
+    },
+];
+
+/// The triangulation implementations.
+pub const TRIANGULATIONS: [Triangulation; 7] = [
+    Triangulation {
+        name: "Incremental",
+        delaunay: false,
+        compute: |points| {
+            let mut points = points.to_vec();
+            let indices = Incremental2dTriangulation::triangulate(&mut points);
+            corners(&points, &indices)
+        },
+    },
+    Triangulation {
+        name: "Incremental steps",
+        delaunay: false,
+        compute: |points| {
+            let mut steps = Incremental2dTriangulation::run(points);
+            steps.by_ref().for_each(drop);
+            corners(steps.points(), steps.indices())
+        },
+    },
+    Triangulation {
+        name: "Edge flipping",
+        delaunay: true,
+        compute: |points| {
+            let mut points = points.to_vec();
+            let mut indices = Incremental2dTriangulation::triangulate(&mut points);
+            Incremental2dTriangulation::edge_flipping(&mut indices, &points);
+            corners(&points, &indices)
+        },
+    },
+    Triangulation {
+        name: "Edge flipping steps",
+        delaunay: true,
+        compute: |points| {
+            let mut points = points.to_vec();
+            let indices = Incremental2dTriangulation::triangulate(&mut points);
+            let mut steps = EdgeFlippingSteps::new(&indices, &points);
+            steps.by_ref().for_each(drop);
+            steps.triangles().map(|(a, b, c)| [ points[a], points[b], points[c] ]).collect()
+        },
+    },
+    Triangulation {
+        name: "Delaunay repair",
+        delaunay: true,
+        compute: |points| {
+            let mut points = points.to_vec();
+            let mut indices = Incremental2dTriangulation::triangulate(&mut points);
+            repair_delaunay(&points, &mut indices);
+            corners(&points, &indices)
+        },
+    },
+    Triangulation {
+        name: "Lifted lower hull",
+        delaunay: true,
+        compute: |points| corners(points, &lower_hull(points)),
+    },
+    Triangulation {
+        name: "History DAG",
+        delaunay: true,
+        compute: |points| corners(points, &HistoryDag::new(points).indices()),
+    },
+];
+
+/// An implementation timed phase by phase.
+pub struct Benchmark {
+    pub name: &'static str,
+    /// Runs the implementation and returns the name and the duration of each phase
+    pub run: fn(&[Vec2]) -> Vec<(&'static str, Duration)>,
+}
+
+/// The timed implementations.
+pub const BENCHMARKS: [Benchmark; 6] = [
+    Benchmark {
+        name: "Jarvis march",
+        run: |points| vec![ ("hull", time(|| { JarvisMarch::march(points.iter()); })) ],
+    },
+    Benchmark {
+        name: "Graham scan",
+        run: |points| vec![ ("hull", time(|| { GrahamScan::scan(points); })) ],
+    },
+    Benchmark {
+        name: "Triangulation",
+        run: |points| {
+            let mut points = points.to_vec();
+            vec![ ("triangulate", time(|| { Incremental2dTriangulation::triangulate(&mut points); })) ]
+        },
+    },
+    Benchmark {
+        name: "Delaunay",
+        run: |points| {
+            let mut points = points.to_vec();
+            let mut indices = Vec::new();
+            let triangulate = time(|| indices = Incremental2dTriangulation::triangulate(&mut points));
+            let flip = time(|| Incremental2dTriangulation::edge_flipping(&mut indices, &points));
+            vec![ ("triangulate", triangulate), ("flip", flip) ]
+        },
+    },
+    Benchmark {
+        name: "Ear clipping",
+        run: |points| {
+            let polygon = star_polygon(points);
+            vec![ ("triangulate", time(|| { triangulate_polygon(&polygon); })) ]
+        },
+    },
+    Benchmark {
+        name: "Monotone",
+        run: |points| {
+            let polygon = star_polygon(points);
+            let mut pieces = Vec::new();
+            let decompose = time(|| pieces = monotone_decomposition(&polygon));
+            let triangulate = time(|| pieces.iter().for_each(|piece| { triangulate_monotone(&polygon, piece); }));
+            vec![ ("decompose", decompose), ("triangulate", triangulate) ]
+        },
+    },
+];
+
+fn time<F: FnOnce()>(f: F) -> Duration {
+    let start_time = Instant::now();
+    f();
+    Instant::now() - start_time
+}
+
+/// Returns the points sorted by angle around their centroid, a simple polygon unless several points have the same angle.
+pub fn star_polygon(points: &[Vec2]) -> Vec<Vec2> {
+    let sum = points.iter().fold(Vec2::new(0.0, 0.0), |sum, p| &sum + p);
+    let center = &sum / points.len().max(1) as f32;
+    let angle = |p: &Vec2| float::atan2(p.y - center.y, p.x - center.x);
+    let mut polygon = points.to_vec();
+    polygon.sort_by(|a, b| angle(a).partial_cmp(&angle(b)).unwrap_or(Ordering::Equal));
+    polygon
+}
+
+fn corners(points: &[Vec2], indices: &[usize]) -> Vec<[Vec2; 3]> {
+    indices.chunks(3).map(|t| [ points[t[0]], points[t[1]], points[t[2]] ]).collect()
+}
+
+/// The result of an implementation on a set of points.
+pub struct Comparison {
+    pub suite: Suite,
+    /// Number of generated points
+    pub count: usize,
+    pub implementation: &'static str,
+    pub time: Duration,
+    /// Why the result is wrong or differs from the others, `None` if it agrees
+    pub error: Option<String>,
+}
+
+/// The results of all the implementations on all the suites.
+#[derive(Default)]
+pub struct Report {
+    pub comparisons: Vec<Comparison>,
+}
+
+impl Report {
+    /// Returns true if all the implementations agreed on all the suites.
+    pub fn is_compatible(&self) -> bool {
+        self.comparisons.iter().all(|comparison| comparison.error.is_none())
+    }
+
+    /// Returns the results that disagree.
+    pub fn failures(&self) -> impl Iterator<Item = &Comparison> {
+        self.comparisons.iter().filter(|comparison| comparison.error.is_some())
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{:<10} {:>8} {:<20} {:>10}  result", "suite", "points", "implementation", "time (µs)")?;
+        for comparison in &self.comparisons {
+            writeln!(f, "{:<10} {:>8} {:<20} {:>10}  {}",
+                     comparison.suite.name(), comparison.count, comparison.implementation,
+                     comparison.time.as_micros(), comparison.error.as_deref().unwrap_or("ok"))?;
+        }
+        let failures = self.failures().count();
+        write!(f, "{} of {} results agree", self.comparisons.len() - failures, self.comparisons.len())
+    }
+}
+
+/// Runs every implementation on every suite, once for each number of points.
+pub fn compare(counts: &[usize]) -> Report {
+    let _span = span!("Comparison harness");
+    let mut report = Report::default();
+    for &suite in Suite::ALL.iter() {
+        for &count in counts {
+            let points = suite.generate(count);
+            compare_hulls(suite, &points, &mut report);
+            compare_triangulations(suite, &points, &mut report);
+        }
+    }
+    report
+}
+
+fn compare_hulls(suite: Suite, points: &[Vec2], report: &mut Report) {
+    let mut reference = None;
+    for hull in HULLS.iter() {
+        let start_time = Instant::now();
+        let indices = (hull.compute)(points);
+        let time = Instant::now() - start_time;
+
+        let violations = validate_hull(points, &indices);
+        let positions = hull_positions(points, &indices);
+        let error = if !violations.is_empty() {
+            Some(format!("invalid hull: {:?}", violations))
+        } else {
+            let (name, expected) = reference.get_or_insert_with(|| (hull.name, positions.clone()));
+            if same_cycle(expected, &positions) { None } else { Some(format!("differs from the {}", name)) }
+        };
+        report.comparisons.push(Comparison { suite, count: points.len(), implementation: hull.name, time, error });
+    }
+}
+
+fn compare_triangulations(suite: Suite, points: &[Vec2], report: &mut Report) {
+    let hull = hull_positions(points, &GrahamScan::scan(points));
+    let hull_area = polygon::signed_area(&hull);
+
+    let mut reference = None;
+    for triangulation in TRIANGULATIONS.iter() {
+        let start_time = Instant::now();
+        let triangles = (triangulation.compute)(points);
+        let time = Instant::now() - start_time;
+
+        let area = triangles.iter().map(|t| float::abs(polygon::signed_area(t))).sum::<f32>();
+        let (name, count) = *reference.get_or_insert((triangulation.name, triangles.len()));
+        let error = if float::abs(area - hull_area) > 1e-4 * hull_area.max(1.0) {
+            Some(format!("covers an area of {} instead of {}", area, hull_area))
+        } else if count != triangles.len() {
+            Some(format!("{} triangles instead of {} for the {}", triangles.len(), count, name))
+        } else if triangulation.delaunay {
+            let illegal = illegal_edges(points, &triangles);
+            if illegal > 0 { Some(format!("{} illegal edges", illegal)) } else { None }
+        } else {
+            None
+        };
+        report.comparisons.push(Comparison { suite, count: points.len(), implementation: triangulation.name, time, error });
+    }
+}
+
+/// Returns the number of edges of the triangles that are not locally Delaunay.
+fn illegal_edges(points: &[Vec2], triangles: &[[Vec2; 3]]) -> usize {
+    let indices = triangles.iter()
+                        .flat_map(|t| t.iter().map(|p| points.iter().position(|q| q == p).expect("Unknown corner")))
+                        .collect::<Vec<_>>();
+    validate_delaunay(points, &indices).len()
+}
+
+/// Returns true if the two polygons have the same vertices in the same cyclic order, in either direction.
+fn same_cycle(a: &[Vec2], b: &[Vec2]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    if a.is_empty() {
+        return true;
+    }
+    let n = a.len();
+    let start = match b.iter().position(|p| *p == a[0]) {
+        Some(start) => start,
+        None => return false,
+    };
+    let forward = (0..n).all(|i| a[i] == b[(start + i) % n]);
+    let backward = (0..n).all(|i| a[i] == b[(start + n - i) % n]);
+    forward || backward
+}
+
+/// A run of a benchmark on generated points.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Timing {
+    pub implementation: &'static str,
+    pub suite: Suite,
+    /// Number of generated points, fewer than requested if the suite ran out of positions
+    pub count: usize,
+    /// Seed the points were generated from
+    pub seed: u64,
+    /// Name and duration of each phase, in the order they ran
+    pub phases: Vec<(&'static str, Duration)>,
+}
+
+impl Timing {
+    /// Returns the duration of all the phases.
+    pub fn total(&self) -> Duration {
+        self.phases.iter().map(|&(_, duration)| duration).sum()
+    }
+}
+
+/// Times every benchmark on every suite, `runs` times for each number of points, and returns the timings
+/// ordered by suite, number of points, run and benchmark. The points of a run are generated from `seed` plus the index
+/// of the run, so all the implementations and all the sessions with the same seed time the same points.
+/// With `z_order`, the points are sorted along a Z-order curve before they are timed.
+pub fn benchmark(suites: &[Suite], counts: &[usize], runs: usize, seed: u64, z_order: bool) -> Vec<Timing> {
+    let _span = span!("Benchmark");
+    let mut timings = Vec::with_capacity(suites.len() * counts.len() * runs * BENCHMARKS.len());
+    for &suite in suites {
+        for &count in counts {
+            for run in 0..runs {
+                let seed = seed.wrapping_add(run as u64);
+                let mut points = suite.generate_seeded(count, seed);
+                if z_order {
+                    morton::sort2(&mut points);
+                }
+                for benchmark in BENCHMARKS.iter() {
+                    let phases = (benchmark.run)(&points);
+                    timings.push(Timing { implementation: benchmark.name, suite, count: points.len(), seed, phases });
+                }
+            }
+        }
+    }
+    timings
+}
@@ -0,0 +1,237 @@
+//! Grayscale images, read from PGM files, e.g. as density maps for stippling, and written to them.
+//! Black and white PBM images are read too, e.g. to trace the outlines of their shapes,
+//! and color images are read from and written to PPM files, e.g. the offscreen renderings of the draw lists.
+//! Other formats can be converted with `convert image.png image.pgm` (ImageMagick) or any image editor.
+
+use crate::{ Error, Result, render::RgbImage };
+
+use std::{
+    fs::File,
+    io::{ BufReader, BufWriter, Read, Write },
+    path::Path,
+};
+
+/// 8 bit grayscale image, the pixels are stored row by row from the top left corner.
+#[derive(Clone, PartialEq, Debug)]
+pub struct GrayImage {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u8>,
+}
+
+impl GrayImage {
+    pub fn new(width: usize, height: usize, pixels: Vec<u8>) -> Self {
+        assert_eq!(pixels.len(), width * height);
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Maps the values, row by row from the top left corner, linearly from black at `min` to white at `max`.
+    /// The values outside of the range are clamped to it.
+    pub fn from_values(width: usize, height: usize, values: &[f32], min: f32, max: f32) -> Self {
+        let pixels = values.iter()
+                           .map(|&value| ((value - min) / (max - min) * 255.0).clamp(0.0, 255.0).round() as u8)
+                           .collect();
+        Self::new(width, height, pixels)
+    }
+
+    pub fn pixel(&self, x: usize, y: usize) -> u8 {
+        self.pixels[y * self.width + x]
+    }
+
+    /// Returns whether each pixel is darker than `level`, row by row from the top left corner.
+    pub fn threshold(&self, level: u8) -> Vec<bool> {
+        self.pixels.iter().map(|&pixel| pixel < level).collect()
+    }
+}
+
+/// Reads a grayscale image, the format is chosen from the extension of `path`: `.pgm` and `.pbm` files are supported.
+pub fn read(path: &Path) -> Result<GrayImage> {
+    let extension = path.extension()
+                        .and_then(|ext| ext.to_str())
+                        .unwrap_or("")
+                        .to_ascii_lowercase();
+    let reader = BufReader::new(File::open(path)?);
+    match extension.as_str() {
+        "pgm" => read_pgm(reader),
+        "pbm" => read_pbm(reader),
+        _ => Err(Error::UnsupportedFormat(extension)),
+    }
+}
+
+/// Writes a grayscale image, the format is chosen from the extension of `path`: only `.pgm` files are supported.
+pub fn write(path: &Path, image: &GrayImage) -> Result<()> {
+    let extension = path.extension()
+                        .and_then(|ext| ext.to_str())
+                        .unwrap_or("")
+                        .to_ascii_lowercase();
+    match extension.as_str() {
+        "pgm" => write_pgm(BufWriter::new(File::create(path)?), image),
+        _ => Err(Error::UnsupportedFormat(extension)),
+    }
+}
+
+/// Writes a binary (`P5`) PGM image.
+pub fn write_pgm<W: Write>(mut writer: W, image: &GrayImage) -> Result<()> {
+    write!(writer, "P5\n{} {}\n255\n", image.width, image.height)?;
+    writer.write_all(&image.pixels)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads a binary (`P5`) or ASCII (`P2`) PGM image. 16 bit images are reduced to 8 bits.
+pub fn read_pgm<R: Read>(mut reader: R) -> Result<GrayImage> {
+    let invalid = |msg: &str| Error::Image(msg.to_owned());
+
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+
+    let mut pos = 0;
+    let number = |pos: &mut usize, name: &str| -> Result<usize> {
+        header_token(&data, pos).and_then(|t| std::str::from_utf8(t).ok())
+                                .and_then(|t| t.parse::<usize>().ok())
+                                .ok_or_else(|| invalid(&format!("invalid {}", name)))
+    };
+
+    let binary = match header_token(&data, &mut pos) {
+        Some(b"P5") => true,
+        Some(b"P2") => false,
+        _ => return Err(invalid("missing P2 or P5 signature")),
+    };
+    let width = number(&mut pos, "width")?;
+    let height = number(&mut pos, "height")?;
+    let max_value = number(&mut pos, "maximum value")?;
+    if max_value == 0 || max_value > 65535 {
+        return Err(invalid("invalid maximum value"));
+    }
+    let count = width * height;
+    let to_u8 = |value: usize| (value.min(max_value) * 255 / max_value) as u8;
+
+    let pixels = if binary {
+        // A single whitespace character separates the header from the pixels
+        let start = pos + 1;
+        let bytes_per_pixel = if max_value < 256 { 1 } else { 2 };
+        let raster = data.get(start..start + count * bytes_per_pixel).ok_or_else(|| invalid("truncated pixels"))?;
+        if bytes_per_pixel == 1 {
+            raster.iter().map(|&value| to_u8(value as usize)).collect()
+        } else {
+            raster.chunks(2).map(|value| to_u8(u16::from_be_bytes([ value[0], value[1] ]) as usize)).collect()
+        }
+    } else {
+        (0..count).map(|_| number(&mut pos, "pixel").map(to_u8)).collect::<Result<Vec<_>>>()?
+    };
+    Ok(GrayImage::new(width, height, pixels))
+}
+
+/// Reads a binary (`P4`) or ASCII (`P1`) PBM image, its black pixels become 0 and its white pixels 255.
+pub fn read_pbm<R: Read>(mut reader: R) -> Result<GrayImage> {
+    let invalid = |msg: &str| Error::Image(msg.to_owned());
+
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+
+    let mut pos = 0;
+    let number = |pos: &mut usize, name: &str| -> Result<usize> {
+        header_token(&data, pos).and_then(|t| std::str::from_utf8(t).ok())
+                                .and_then(|t| t.parse::<usize>().ok())
+                                .ok_or_else(|| invalid(&format!("invalid {}", name)))
+    };
+
+    let binary = match header_token(&data, &mut pos) {
+        Some(b"P4") => true,
+        Some(b"P1") => false,
+        _ => return Err(invalid("missing P1 or P4 signature")),
+    };
+    let width = number(&mut pos, "width")?;
+    let height = number(&mut pos, "height")?;
+    let count = width * height;
+    // A set bit is black
+    let color = |black: bool| if black { 0 } else { 255 };
+
+    let pixels = if binary {
+        // A single whitespace character separates the header from the pixels, each row starts on a new byte
+        let start = pos + 1;
+        let row_bytes = width.div_ceil(8);
+        let raster = data.get(start..start + row_bytes * height).ok_or_else(|| invalid("truncated pixels"))?;
+        (0..count).map(|idx| {
+            let (x, y) = (idx % width, idx / width);
+            color((raster[y * row_bytes + x / 8] >> (7 - x % 8)) & 1 == 1)
+        }).collect()
+    } else {
+        // The digits do not need whitespace between them
+        let pixels = data[pos..].iter()
+                                .filter(|c| !c.is_ascii_whitespace())
+                                .take(count)
+                                .map(|&c| match c {
+                                    b'0' => Ok(color(false)),
+                                    b'1' => Ok(color(true)),
+                                    _ => Err(invalid("invalid pixel")),
+                                })
+                                .collect::<Result<Vec<_>>>()?;
+        if pixels.len() < count {
+            return Err(invalid("truncated pixels"));
+        }
+        pixels
+    };
+    Ok(GrayImage::new(width, height, pixels))
+}
+
+/// Writes a binary (`P6`) PPM image.
+pub fn write_ppm<W: Write>(mut writer: W, image: &RgbImage) -> Result<()> {
+    write!(writer, "P6\n{} {}\n255\n", image.width, image.height)?;
+    writer.write_all(&image.pixels)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads a binary (`P6`) PPM image with 8 bits per component.
+pub fn read_ppm<R: Read>(mut reader: R) -> Result<RgbImage> {
+    let invalid = |msg: &str| Error::Image(msg.to_owned());
+
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+
+    let mut pos = 0;
+    let number = |pos: &mut usize, name: &str| -> Result<usize> {
+        header_token(&data, pos).and_then(|t| std::str::from_utf8(t).ok())
+                                .and_then(|t| t.parse::<usize>().ok())
+                                .ok_or_else(|| invalid(&format!("invalid {}", name)))
+    };
+
+    if header_token(&data, &mut pos) != Some(b"P6") {
+        return Err(invalid("missing P6 signature"));
+    }
+    let width = number(&mut pos, "width")?;
+    let height = number(&mut pos, "height")?;
+    if number(&mut pos, "maximum value")? != 255 {
+        return Err(invalid("only 8 bit components are supported"));
+    }
+    // A single whitespace character separates the header from the pixels
+    let start = pos + 1;
+    let pixels = data.get(start..start + 3 * width * height).ok_or_else(|| invalid("truncated pixels"))?;
+    Ok(RgbImage::new(width, height, pixels.to_vec()))
+}
+
+/// Returns the next whitespace separated token of a PNM header, skipping the comments from '#' to the end of the line.
+fn header_token<'a>(data: &'a [u8], pos: &mut usize) -> Option<&'a [u8]> {
+    loop {
+        while *pos < data.len() && data[*pos].is_ascii_whitespace() {
+            *pos += 1;
+        }
+        if *pos < data.len() && data[*pos] == b'#' {
+            while *pos < data.len() && data[*pos] != b'\n' {
+                *pos += 1;
+            }
+        } else {
+            break;
+        }
+    }
+    let start = *pos;
+    while *pos < data.len() && !data[*pos].is_ascii_whitespace() {
+        *pos += 1;
+    }
+    if start == *pos { None } else { Some(&data[start..*pos]) }
+}
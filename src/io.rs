@@ -0,0 +1,216 @@
+//! Reading and writing point sets and the results of the algorithms.
+
+use crate::{ Error, Result, math::{ Vec2, ToArray }, scene::Mesh, harness::Timing };
+
+use std::{
+    io::{ BufRead, Write },
+    ops::RangeInclusive,
+    path::Path,
+};
+
+use cgmath::InnerSpace;
+
+/// Reads points from lines of comma separated `x,y` coordinates.
+/// Empty lines, lines starting with `#` and a non numeric header line are skipped.
+pub fn read_points_csv<R: BufRead>(reader: R) -> Result<Vec<Vec2>> {
+    let rows = read_rows_csv(reader, 2..=2, "x,y")?;
+    Ok(rows.iter().map(|row| Vec2::new(row[0], row[1])).collect())
+}
+
+/// Reads points and their values from lines of `x,y,value`, like [`read_points_csv`](fn.read_points_csv.html).
+/// The value is optional and defaults to 0, so plain `x,y` files are read too.
+pub fn read_points_values_csv<R: BufRead>(reader: R) -> Result<(Vec<Vec2>, Vec<f32>)> {
+    let rows = read_rows_csv(reader, 2..=3, "x,y,value")?;
+    let points = rows.iter().map(|row| Vec2::new(row[0], row[1])).collect();
+    let values = rows.iter().map(|row| row.get(2).copied().unwrap_or(0.0)).collect();
+    Ok((points, values))
+}
+
+/// Reads the numbers of each line, whose count must be in `columns`. NaN and infinite numbers are rejected.
+fn read_rows_csv<R: BufRead>(reader: R, columns: RangeInclusive<usize>, expected: &'static str) -> Result<Vec<Vec<f32>>> {
+    let mut rows = Vec::new();
+    for (line_idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let row = line.split(',')
+                        .map(|c| c.trim().parse::<f32>())
+                        .collect::<std::result::Result<Vec<_>, _>>();
+        match row {
+            Ok(row) if columns.contains(&row.len()) => {
+                if let Some(column) = row.iter().position(|c| !c.is_finite()) {
+                    let content = line.split(',').nth(column).unwrap_or_default().trim().to_owned();
+                    return Err(Error::NotFinite { line: line_idx + 1, column: column + 1, content });
+                }
+                rows.push(row);
+            },
+            Err(_) if line_idx == 0 => continue, // Header
+            _ => return Err(Error::Parse { line: line_idx + 1, content: line.to_owned(), expected }),
+        }
+    }
+    Ok(rows)
+}
+
+/// Writes one `x,y` line per point.
+pub fn write_points_csv<W: Write>(mut writer: W, points: &[Vec2]) -> Result<()> {
+    for p in points {
+        writeln!(writer, "{},{}", p.x, p.y)?;
+    }
+    Ok(())
+}
+
+/// Writes one `x,y,value` line per point, with the values in the order of the points.
+pub fn write_points_values_csv<W: Write>(mut writer: W, points: &[Vec2], values: &[f32]) -> Result<()> {
+    for (p, value) in points.iter().zip(values) {
+        writeln!(writer, "{},{},{}", p.x, p.y, value)?;
+    }
+    Ok(())
+}
+
+/// Writes one `x1,y1,x2,y2,x3,y3` line per triangle, `indices` containing 3 indices into `points` per triangle.
+pub fn write_triangles_csv<W: Write>(mut writer: W, points: &[Vec2], indices: &[usize]) -> Result<()> {
+    for tri in indices.chunks(3) {
+        let (a, b, c) = (points[tri[0]], points[tri[1]], points[tri[2]]);
+        writeln!(writer, "{},{},{},{},{},{}", a.x, a.y, b.x, b.y, c.x, c.y)?;
+    }
+    Ok(())
+}
+
+/// Writes the timings of a benchmark with a header line, then one
+/// `implementation,distribution,points,seed,phase,time_ns` line per phase of each run, to be plotted with other tools.
+pub fn write_timings_csv<W: Write>(mut writer: W, timings: &[Timing]) -> Result<()> {
+    writeln!(writer, "implementation,distribution,points,seed,phase,time_ns")?;
+    for timing in timings {
+        for (phase, duration) in &timing.phases {
+            writeln!(writer, "{},{},{},{},{},{}",
+                     timing.implementation, timing.suite.name(), timing.count, timing.seed, phase, duration.as_nanos())?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes a triangulation as a JSON object with the `points` as `[x, y]` arrays
+/// and the `triangles` as arrays of 3 indices into `points`.
+pub fn write_triangulation_json<W: Write>(mut writer: W, points: &[Vec2], indices: &[usize]) -> Result<()> {
+    let points = points.iter()
+                    .map(|p| format!("[{}, {}]", p.x, p.y))
+                    .collect::<Vec<_>>();
+    let triangles = indices.chunks(3)
+                        .map(|tri| format!("[{}, {}, {}]", tri[0], tri[1], tri[2]))
+                        .collect::<Vec<_>>();
+    writeln!(writer, "{{")?;
+    writeln!(writer, "  \"points\": [{}],", points.join(", "))?;
+    writeln!(writer, "  \"triangles\": [{}]", triangles.join(", "))?;
+    writeln!(writer, "}}")?;
+    Ok(())
+}
+
+/// File formats of the exported meshes.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum MeshFormat {
+    /// Wavefront OBJ, with vertex normals
+    Obj,
+    /// Binary STL, with face normals
+    Stl,
+    /// ASCII PLY, with vertex normals
+    Ply,
+    /// Object File Format, positions only
+    Off,
+}
+
+impl MeshFormat {
+    /// Chooses the format from the extension of `path`.
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let extension = path.extension()
+                            .and_then(|ext| ext.to_str())
+                            .unwrap_or("")
+                            .to_ascii_lowercase();
+        match extension.as_str() {
+            "obj" => Ok(MeshFormat::Obj),
+            "stl" => Ok(MeshFormat::Stl),
+            "ply" => Ok(MeshFormat::Ply),
+            "off" => Ok(MeshFormat::Off),
+            _ => Err(Error::UnsupportedFormat(extension)),
+        }
+    }
+}
+
+/// Writes the triangles of `mesh` in the given format.
+pub fn write_mesh<W: Write>(writer: W, mesh: &Mesh, format: MeshFormat) -> Result<()> {
+    match format {
+        MeshFormat::Obj => write_mesh_obj(writer, mesh),
+        MeshFormat::Stl => write_mesh_stl(writer, mesh),
+        MeshFormat::Ply => write_mesh_ply(writer, mesh),
+        MeshFormat::Off => write_mesh_off(writer, mesh),
+    }
+}
+
+pub fn write_mesh_obj<W: Write>(mut writer: W, mesh: &Mesh) -> Result<()> {
+    for v in &mesh.vertices {
+        writeln!(writer, "v {} {} {}", v.0.x, v.0.y, v.0.z)?;
+    }
+    for n in mesh.vertex_normals() {
+        writeln!(writer, "vn {} {} {}", n.0.x, n.0.y, n.0.z)?;
+    }
+    // Indices start at 1, each vertex uses the normal with the same index
+    for tri in mesh.indices.chunks(3) {
+        writeln!(writer, "f {0}//{0} {1}//{1} {2}//{2}", tri[0] + 1, tri[1] + 1, tri[2] + 1)?;
+    }
+    Ok(())
+}
+
+pub fn write_mesh_stl<W: Write>(mut writer: W, mesh: &Mesh) -> Result<()> {
+    let mut header = [0_u8; 80];
+    let title = b"computational-geometry";
+    header[..title.len()].copy_from_slice(title);
+    writer.write_all(&header)?;
+    writer.write_all(&(mesh.indices.len() as u32 / 3).to_le_bytes())?;
+
+    for (tri, normal) in mesh.indices.chunks(3).zip(mesh.face_normals()) {
+        let normal = normal.0;
+        let normal = if normal.magnitude2() > 0.0 { normal.normalize() } else { normal };
+        let vertices = tri.iter().map(|&idx| mesh.vertices[idx].to_array());
+        for coords in std::iter::once([ normal.x, normal.y, normal.z ]).chain(vertices) {
+            for c in coords.iter() {
+                writer.write_all(&c.to_le_bytes())?;
+            }
+        }
+        writer.write_all(&0_u16.to_le_bytes())?; // Attribute byte count
+    }
+    Ok(())
+}
+
+pub fn write_mesh_ply<W: Write>(mut writer: W, mesh: &Mesh) -> Result<()> {
+    writeln!(writer, "ply")?;
+    writeln!(writer, "format ascii 1.0")?;
+    writeln!(writer, "element vertex {}", mesh.vertices.len())?;
+    for property in &[ "x", "y", "z", "nx", "ny", "nz" ] {
+        writeln!(writer, "property float {}", property)?;
+    }
+    writeln!(writer, "element face {}", mesh.indices.len() / 3)?;
+    writeln!(writer, "property list uchar int vertex_indices")?;
+    writeln!(writer, "end_header")?;
+
+    for (v, n) in mesh.vertices.iter().zip(mesh.vertex_normals()) {
+        writeln!(writer, "{} {} {} {} {} {}", v.0.x, v.0.y, v.0.z, n.0.x, n.0.y, n.0.z)?;
+    }
+    for tri in mesh.indices.chunks(3) {
+        writeln!(writer, "3 {} {} {}", tri[0], tri[1], tri[2])?;
+    }
+    Ok(())
+}
+
+pub fn write_mesh_off<W: Write>(mut writer: W, mesh: &Mesh) -> Result<()> {
+    writeln!(writer, "OFF")?;
+    writeln!(writer, "{} {} 0", mesh.vertices.len(), mesh.indices.len() / 3)?;
+    for v in &mesh.vertices {
+        writeln!(writer, "{} {} {}", v.0.x, v.0.y, v.0.z)?;
+    }
+    for tri in mesh.indices.chunks(3) {
+        writeln!(writer, "3 {} {} {}", tri[0], tri[1], tri[2])?;
+    }
+    Ok(())
+}
@@ -0,0 +1,55 @@
+//! Computational geometry algorithms.
+//!
+//! The `gui` feature (enabled by default) provides the interactive viewer,
+//! the `cli` feature (enabled by default too) the `cgeom` command line tool.
+//! Without the `std` feature, which `gui` enables, `math`, `spatial` and the 2D algorithms
+//! only need `alloc`.
+
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
+// The events of the viewer are matched level by level, the collapsed patterns would be much harder to read
+#![allow(clippy::collapsible_match)]
+
+extern crate alloc;
+
+#[cfg(feature = "gui")]
+#[macro_use]
+extern crate glium;
+
+#[macro_use]
+pub mod span;
+pub mod math;
+pub mod task;
+#[cfg(test)]
+mod tests;
+pub mod spatial;
+pub mod algorithms;
+pub mod render;
+#[cfg(feature = "std")]
+pub mod io;
+#[cfg(feature = "std")]
+pub mod wkt;
+#[cfg(feature = "std")]
+pub mod point_cloud;
+#[cfg(feature = "std")]
+pub mod image;
+#[cfg(feature = "std")]
+pub mod scene;
+#[cfg(feature = "std")]
+pub mod harness;
+#[cfg(feature = "std")]
+pub mod error;
+#[cfg(feature = "std")]
+pub use error::{ Error, Result };
+#[cfg(feature = "geo")]
+pub mod geo;
+#[cfg(feature = "cross-validate")]
+pub mod cross_validation;
+#[cfg(feature = "capi")]
+pub mod capi;
+
+#[cfg(feature = "gui")]
+pub mod ui;
+#[cfg(feature = "gui")]
+pub mod graphics;
+#[cfg(feature = "gui")]
+pub mod renderers;
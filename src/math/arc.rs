@@ -0,0 +1,157 @@
+//! Circular arcs, as they bound the offsets of polygons at their outer corners, and their intersections
+//! with segments and other arcs.
+
+use super::{ Vec2, Circle, Segment2, float };
+
+use alloc::{ vec, vec::Vec };
+
+use core::f32::consts::PI;
+
+/// Tolerance on the angles, in radians, for the intersections at the ends of the arcs
+const ANGLE_TOLERANCE: f32 = 1e-5;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Arc {
+    pub circle: Circle,
+    /// Angle of the first point from the center, counter-clockwise from the x axis
+    pub start: f32,
+    /// Angle from the first point to the last one, counter-clockwise if positive and clockwise otherwise
+    pub sweep: f32,
+}
+
+impl Arc {
+    pub fn new(circle: Circle, start: f32, sweep: f32) -> Self {
+        Self {
+            circle,
+            start,
+            sweep,
+        }
+    }
+
+    /// Returns the arc from `a` to `c` through `b`, or `None` if the points are collinear
+    pub fn through(a: Vec2, b: Vec2, c: Vec2) -> Option<Self> {
+        let circle = Circle::circumscribed(a, b, c)?;
+        let angle = |p: Vec2| float::atan2(p.y - circle.center.y, p.x - circle.center.x);
+        let start = angle(a);
+        let (to_b, to_c) = (normalized_angle(angle(b) - start), normalized_angle(angle(c) - start));
+        // Counter-clockwise if `b` comes before `c` that way, clockwise otherwise
+        let sweep = if to_b < to_c { to_c } else { to_c - 2.0 * PI };
+        Some(Self::new(circle, start, sweep))
+    }
+
+    /// Returns the point at the parameter `t`, from the first point at 0 to the last one at 1
+    pub fn point(&self, t: f32) -> Vec2 {
+        let angle = self.start + t * self.sweep;
+        Vec2::new(
+            self.circle.center.x + self.circle.radius * float::cos(angle),
+            self.circle.center.y + self.circle.radius * float::sin(angle),
+        )
+    }
+
+    pub fn start_point(&self) -> Vec2 {
+        self.point(0.0)
+    }
+
+    pub fn end_point(&self) -> Vec2 {
+        self.point(1.0)
+    }
+
+    pub fn length(&self) -> f32 {
+        float::abs(self.sweep) * self.circle.radius
+    }
+
+    /// Checks whether the arc covers the direction of `p` from its center, `p` does not have to be on the circle
+    pub fn covers(&self, p: Vec2) -> bool {
+        let angle = float::atan2(p.y - self.circle.center.y, p.x - self.circle.center.x);
+        let relative = if self.sweep >= 0.0 { angle - self.start } else { self.start - angle };
+        let relative = normalized_angle(relative + ANGLE_TOLERANCE) - ANGLE_TOLERANCE;
+        relative <= float::abs(self.sweep) + ANGLE_TOLERANCE
+    }
+
+    /// Returns the points where the arc crosses or touches the segment, in order along the segment
+    pub fn segment_intersections(&self, segment: &Segment2) -> Vec<Vec2> {
+        self.circle
+            .segment_intersections(segment)
+            .into_iter()
+            .filter(|&p| self.covers(p))
+            .collect()
+    }
+
+    /// Returns the points where the arcs cross or touch. Where the arcs are on the same circle and overlap,
+    /// returns the ends of each one that are on the other.
+    pub fn intersections(&self, other: &Self) -> Vec<Vec2> {
+        if self.circle.center == other.circle.center && super::cmp_f32(self.circle.radius, other.circle.radius) {
+            let mut ends = Vec::new();
+            for (arc, p) in [
+                (other, self.start_point()), (other, self.end_point()),
+                (self, other.start_point()), (self, other.end_point()),
+            ].iter() {
+                if arc.covers(*p) && !ends.contains(p) {
+                    ends.push(*p);
+                }
+            }
+            return ends;
+        }
+        self.circle
+            .intersections(&other.circle)
+            .into_iter()
+            .filter(|&p| self.covers(p) && other.covers(p))
+            .collect()
+    }
+
+    /// Returns `segments + 1` points along the arc, from its first point to its last one, e.g. to draw it
+    pub fn flatten(&self, segments: usize) -> Vec<Vec2> {
+        let segments = segments.max(1);
+        (0..=segments).map(|i| self.point(i as f32 / segments as f32)).collect()
+    }
+}
+
+impl Circle {
+    /// Returns the points where the circles cross, or the point where they touch. Returns none for
+    /// concentric circles, even if they are the same.
+    pub fn intersections(&self, other: &Self) -> Vec<Vec2> {
+        let between = &other.center - &self.center;
+        let d = between.length();
+        if d == 0.0 || d > self.radius + other.radius || d < float::abs(self.radius - other.radius) {
+            return Vec::new();
+        }
+        // Distance from the center of `self` to the chord through both points, along `between`
+        let along = (d * d + self.radius * self.radius - other.radius * other.radius) / (2.0 * d);
+        let direction = &between * (1.0 / d);
+        let middle = &self.center + &(&direction * along);
+        let sqr_height = self.radius * self.radius - along * along;
+        if sqr_height <= 0.0 {
+            return vec![ middle ];
+        }
+        let offset = &Vec2::new(-direction.y, direction.x) * float::sqrt(sqr_height);
+        vec![ &middle + &offset, &middle - &offset ]
+    }
+
+    /// Returns the points where the segment crosses or touches the circle, in order along the segment
+    pub fn segment_intersections(&self, segment: &Segment2) -> Vec<Vec2> {
+        // Roots of |a + t r - center|² = radius² between 0 and 1
+        let r = segment.as_vec2();
+        let ac = &segment.a - &self.center;
+        let (qa, qb, qc) = (r.sqr_length(), 2.0 * r.dot(ac), ac.sqr_length() - self.radius * self.radius);
+        let discriminant = qb * qb - 4.0 * qa * qc;
+        if qa == 0.0 || discriminant < 0.0 {
+            return Vec::new();
+        }
+        let root = float::sqrt(discriminant);
+        let mut roots = vec![ (-qb - root) / (2.0 * qa) ];
+        if root > 0.0 {
+            roots.push((-qb + root) / (2.0 * qa));
+        }
+        roots
+            .into_iter()
+            .filter(|&t| (0.0..=1.0).contains(&t))
+            .map(|t| &segment.a + &(&r * t))
+            .collect()
+    }
+}
+
+/// Returns the angle modulo a full turn, between 0 and 2π.
+pub(crate) fn normalized_angle(angle: f32) -> f32 {
+    let angle = angle % (2.0 * PI);
+    if angle < 0.0 { angle + 2.0 * PI } else { angle }
+}
@@ -0,0 +1,160 @@
+//! Cubic Bézier curves, and paths of them joined end to end. A path is given by its control points:
+//! every curve starts at the last point of the previous one and takes the next 3 points,
+//! and a closed path ends with a curve back to its first point.
+//! The parameters of the curves are not proportional to their arc length: the points at evenly spaced parameters
+//! crowd where the curves bend. Arc-length tables map the distances along a path to its parameters.
+
+use super::Vec2;
+
+use alloc::{ vec, vec::Vec };
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct CubicBezier {
+    /// The curve starts at the first point towards the second one, and ends at the last point from the third one
+    pub points: [Vec2; 4],
+}
+
+impl CubicBezier {
+    pub fn new(p0: Vec2, p1: Vec2, p2: Vec2, p3: Vec2) -> Self {
+        Self {
+            points: [ p0, p1, p2, p3 ],
+        }
+    }
+
+    /// Returns the point of the curve at the parameter `t`, between 0 and 1.
+    pub fn point(&self, t: f32) -> Vec2 {
+        let [ p0, p1, p2, p3 ] = self.points;
+        let s = 1.0 - t;
+        let (b0, b1, b2, b3) = (s * s * s, 3.0 * s * s * t, 3.0 * s * t * t, t * t * t);
+        Vec2::new(
+            b0 * p0.x + b1 * p1.x + b2 * p2.x + b3 * p3.x,
+            b0 * p0.y + b1 * p1.y + b2 * p2.y + b3 * p3.y,
+        )
+    }
+
+    /// Returns the derivative of the curve at the parameter `t`, tangent to it.
+    pub fn derivative(&self, t: f32) -> Vec2 {
+        let [ p0, p1, p2, p3 ] = self.points;
+        let s = 1.0 - t;
+        let (b0, b1, b2) = (3.0 * s * s, 6.0 * s * t, 3.0 * t * t);
+        Vec2::new(
+            b0 * (p1.x - p0.x) + b1 * (p2.x - p1.x) + b2 * (p3.x - p2.x),
+            b0 * (p1.y - p0.y) + b1 * (p2.y - p1.y) + b2 * (p3.y - p2.y),
+        )
+    }
+
+    /// Returns `segments + 1` points of the curve at evenly spaced parameters, from its start to its end.
+    pub fn flatten(&self, segments: usize) -> Vec<Vec2> {
+        let segments = segments.max(1);
+        (0..=segments).map(|i| self.point(i as f32 / segments as f32)).collect()
+    }
+}
+
+/// Parameters sampled per curve by the arc-length table of `resample_path`
+pub const ARC_LENGTH_SAMPLES: usize = 64;
+
+/// Cumulative lengths along a path of curves at evenly spaced parameters, which approximate its arc length
+/// by the lengths of the chords between them.
+#[derive(Clone, Debug)]
+pub struct ArcLengthTable {
+    curves: Vec<CubicBezier>,
+    samples: usize,
+    /// Length from the start of the path to each sampled parameter, `samples` per curve and the end of the last one
+    lengths: Vec<f32>,
+}
+
+impl ArcLengthTable {
+    /// Samples `samples` parameters on each curve, at least 1.
+    pub fn new(curves: Vec<CubicBezier>, samples: usize) -> Self {
+        let samples = samples.max(1);
+        let mut lengths = vec![ 0.0 ];
+        let mut total = 0.0;
+        for curve in &curves {
+            let mut previous = curve.points[0];
+            for i in 1..=samples {
+                let point = curve.point(i as f32 / samples as f32);
+                total += (&point - &previous).length();
+                lengths.push(total);
+                previous = point;
+            }
+        }
+        Self { curves, samples, lengths }
+    }
+
+    /// Returns the length of the path.
+    pub fn length(&self) -> f32 {
+        self.lengths[self.lengths.len() - 1]
+    }
+
+    /// Returns the index of the curve and the parameter along it at the arc length `distance` from the start,
+    /// clamped to the ends of the path, or `None` if it has no curves.
+    pub fn parameter(&self, distance: f32) -> Option<(usize, f32)> {
+        if self.curves.is_empty() {
+            return None;
+        }
+        let distance = distance.max(0.0).min(self.length());
+        // The first sample at the distance or beyond, interpolated linearly with the previous one
+        let k = self.lengths.partition_point(|&length| length < distance).max(1);
+        let (before, after) = (self.lengths[k - 1], self.lengths[k]);
+        let fraction = if after > before { (distance - before) / (after - before) } else { 0.0 };
+        let sample = (k - 1) as f32 + fraction;
+        let curve = ((k - 1) / self.samples).min(self.curves.len() - 1);
+        Some((curve, sample / self.samples as f32 - curve as f32))
+    }
+
+    /// Returns the point at the arc length `distance` from the start of the path, clamped to its ends.
+    pub fn point_at(&self, distance: f32) -> Option<Vec2> {
+        self.parameter(distance).map(|(curve, t)| self.curves[curve].point(t))
+    }
+
+    /// Returns `count` points evenly spaced by arc length along the path, like `polyline::resample`:
+    /// those of an open path end at its end, those of a closed one split it in `count` equal parts.
+    pub fn resample(&self, closed: bool, count: usize) -> Vec<Vec2> {
+        if self.curves.is_empty() {
+            return Vec::new();
+        }
+        let parts = if closed { count } else { count.saturating_sub(1).max(1) };
+        let step = self.length() / parts as f32;
+        (0..count).filter_map(|i| self.point_at(step * i as f32)).collect()
+    }
+}
+
+/// Returns the curves of the path through the control points. The points left over after the last complete curve
+/// are ignored: a closed path needs a multiple of 3 points, it is open otherwise.
+pub fn curves(control_points: &[Vec2], closed: bool) -> Vec<CubicBezier> {
+    let n = control_points.len();
+    let count = if is_closed(control_points, closed) { n / 3 } else { n.saturating_sub(1) / 3 };
+    (0..count)
+        .map(|i| {
+            let p = |k: usize| control_points[(3 * i + k) % n];
+            CubicBezier::new(p(0), p(1), p(2), p(3))
+        })
+        .collect()
+}
+
+/// Returns the polyline through the curves of the path, each cut in `segments` segments.
+/// The polyline of a closed path does not repeat its first point at the end.
+pub fn flatten_path(control_points: &[Vec2], closed: bool, segments: usize) -> Vec<Vec2> {
+    let mut points = Vec::new();
+    for curve in curves(control_points, closed) {
+        let flattened = curve.flatten(segments);
+        // Consecutive curves share their ends
+        let skip = if points.is_empty() { 0 } else { 1 };
+        points.extend_from_slice(&flattened[skip..]);
+    }
+    if is_closed(control_points, closed) {
+        points.pop();
+    }
+    points
+}
+
+/// Returns `count` points of the path evenly spaced by arc length, see `ArcLengthTable::resample`.
+pub fn resample_path(control_points: &[Vec2], closed: bool, count: usize) -> Vec<Vec2> {
+    ArcLengthTable::new(curves(control_points, closed), ARC_LENGTH_SAMPLES).resample(is_closed(control_points, closed), count)
+}
+
+/// Checks whether the last curve of the path goes back to its first point.
+fn is_closed(control_points: &[Vec2], closed: bool) -> bool {
+    let (curves, left_over) = (control_points.len() / 3, control_points.len() % 3);
+    closed && curves > 0 && left_over == 0
+}
@@ -0,0 +1,53 @@
+use super::{ Vec2, float };
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Circle {
+    pub center: Vec2,
+    pub radius: f32,
+}
+
+impl Circle {
+    pub fn new(center: Vec2, radius: f32) -> Self {
+        Self {
+            center,
+            radius,
+        }
+    }
+
+    /// Returns the circle going through the three points, or `None` if they are collinear
+    pub fn circumscribed(a: Vec2, b: Vec2, c: Vec2) -> Option<Self> {
+        // Computed relative to `a` to keep the products small
+        let (b, c) = (&b - &a, &c - &a);
+        let d = 2.0 * (b.x * c.y - b.y * c.x);
+        if d == 0.0 {
+            return None;
+        }
+        let (b2, c2) = (b.sqr_length(), c.sqr_length());
+        let center = Vec2::new((c.y * b2 - b.y * c2) / d, (b.x * c2 - c.x * b2) / d);
+        Some(Self::new(&a + &center, center.length()))
+    }
+
+    /// Returns the smallest circle going through the two points
+    pub fn diametral(a: Vec2, b: Vec2) -> Self {
+        let center = &(&a + &b) * 0.5;
+        Self::new(center, (&a - &center).length())
+    }
+
+    /// Checks whether `p` lies inside the circle or on it, with a tolerance relative to the radius
+    pub fn contains(&self, p: Vec2) -> bool {
+        (&p - &self.center).length() <= self.radius * (1.0 + 1e-5) + 1e-6
+    }
+
+    pub fn area(&self) -> f32 {
+        core::f32::consts::PI * self.radius * self.radius
+    }
+
+    /// Returns `n` points evenly spaced on the circle, e.g. to draw it as a polygon
+    pub fn points(&self, n: usize) -> impl Iterator<Item = Vec2> + '_ {
+        (0..n).map(move |i| {
+            let angle = i as f32 / n as f32 * 2.0 * core::f32::consts::PI;
+            let (sin, cos) = (float::sin(angle), float::cos(angle));
+            Vec2::new(self.center.x + self.radius * cos, self.center.y + self.radius * sin)
+        })
+    }
+}
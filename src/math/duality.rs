@@ -0,0 +1,83 @@
+//! Point-line duality: the point (a, b) is dual to the line y = ax - b, and the line y = mx + c to the point (m, -c).
+//!
+//! The transform is its own inverse, preserves incidence (p is on l if and only if l* is on p*)
+//! and reverses the order (p is above l if and only if l* is above p*), so problems on points,
+//! like finding collinear triples or the lines that split them, become problems on arrangements of lines.
+//! Vertical lines have no dual.
+
+use super::{ Vec2, Segment2, float };
+
+/// A non-vertical line, the points (x, y) such that y = slope * x + intercept.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Line {
+    pub slope: f32,
+    pub intercept: f32,
+}
+
+impl Line {
+    pub fn new(slope: f32, intercept: f32) -> Self {
+        Self {
+            slope,
+            intercept,
+        }
+    }
+
+    /// Returns the line through `a` and `b`, `None` if it is vertical.
+    pub fn through(a: Vec2, b: Vec2) -> Option<Self> {
+        if a.x == b.x {
+            return None;
+        }
+        let slope = (b.y - a.y) / (b.x - a.x);
+        Some(Self::new(slope, a.y - slope * a.x))
+    }
+
+    /// Returns the line through the ends of the segment, `None` if it is vertical.
+    pub fn from_segment(segment: &Segment2) -> Option<Self> {
+        Self::through(segment.a, segment.b)
+    }
+
+    pub fn y_at(&self, x: f32) -> f32 {
+        self.slope * x + self.intercept
+    }
+
+    /// Returns the vertical distance from the line to `p`, positive if `p` is above it.
+    pub fn height_of(&self, p: Vec2) -> f32 {
+        p.y - self.y_at(p.x)
+    }
+
+    /// Returns the part of the line between the abscissas `x_min` and `x_max`, e.g. to draw it
+    /// or to add it to an [`Arrangement`](crate::algorithms::Arrangement).
+    pub fn segment(&self, x_min: f32, x_max: f32) -> Segment2 {
+        Segment2::new(Vec2::new(x_min, self.y_at(x_min)), Vec2::new(x_max, self.y_at(x_max)))
+    }
+
+    /// Returns the intersection of the two lines, `None` if they are parallel.
+    pub fn intersection(&self, other: &Self) -> Option<Vec2> {
+        let slopes = self.slope - other.slope;
+        if float::abs(slopes) <= f32::EPSILON * float::abs(self.slope).max(float::abs(other.slope)) {
+            return None;
+        }
+        let x = (other.intercept - self.intercept) / slopes;
+        Some(Vec2::new(x, self.y_at(x)))
+    }
+}
+
+/// Returns the line dual to `point`: the point (a, b) is dual to the line y = ax - b.
+pub fn dual_line(point: Vec2) -> Line {
+    Line::new(point.x, -point.y)
+}
+
+/// Returns the point dual to `line`: the line y = mx + c is dual to the point (m, -c).
+pub fn dual_point(line: &Line) -> Vec2 {
+    Vec2::new(line.slope, -line.intercept)
+}
+
+/// Returns the lines dual to `points`, in the same order.
+pub fn dual_lines(points: &[Vec2]) -> impl Iterator<Item = Line> + '_ {
+    points.iter().cloned().map(dual_line)
+}
+
+/// Returns the points dual to `lines`, in the same order.
+pub fn dual_points(lines: &[Line]) -> impl Iterator<Item = Vec2> + '_ {
+    lines.iter().map(dual_point)
+}
@@ -0,0 +1,70 @@
+use alloc::vec::Vec;
+
+use super::Vec2;
+
+/// Closed half-plane of the points `p` with `normal · p <= offset`.
+/// The normal points out of it and does not need to be normalized.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct HalfPlane {
+    pub normal: Vec2,
+    pub offset: f32,
+}
+
+impl HalfPlane {
+    pub fn new(normal: Vec2, offset: f32) -> Self {
+        Self {
+            normal,
+            offset,
+        }
+    }
+
+    /// Creates the half-plane on the left of the directed line from `a` through `b`
+    pub fn left_of(a: Vec2, b: Vec2) -> Self {
+        let normal = Vec2::new(b.y - a.y, a.x - b.x);
+        Self::new(normal, normal.dot(a))
+    }
+
+    /// Returns the direction of the boundary line, with the half-plane on its left
+    pub fn direction(&self) -> Vec2 {
+        Vec2::new(-self.normal.y, self.normal.x)
+    }
+
+    /// Returns the point of the boundary line closest to the origin, or `None` if the normal is zero
+    pub fn boundary_point(&self) -> Option<Vec2> {
+        let length = self.normal.sqr_length();
+        if length == 0.0 {
+            return None;
+        }
+        Some(&self.normal * (self.offset / length))
+    }
+
+    /// Returns `normal · p - offset`, negative inside the half-plane and positive outside
+    pub fn signed_distance(&self, p: Vec2) -> f32 {
+        self.normal.dot(p) - self.offset
+    }
+
+    /// Returns whether the point is in the half-plane, its boundary included
+    pub fn contains(&self, p: Vec2) -> bool {
+        self.signed_distance(p) <= 0.0
+    }
+
+    /// Returns the part of the convex polygon inside the half-plane, empty if nothing is left
+    pub fn clip(&self, polygon: &[Vec2]) -> Vec<Vec2> {
+        let mut clipped = Vec::with_capacity(polygon.len() + 1);
+        for (idx, &cur) in polygon.iter().enumerate() {
+            let next = polygon[(idx + 1) % polygon.len()];
+            let (d_cur, d_next) = (self.signed_distance(cur), self.signed_distance(next));
+            if d_cur <= 0.0 {
+                clipped.push(cur);
+            }
+            if d_cur < 0.0 && d_next > 0.0 || d_cur > 0.0 && d_next < 0.0 {
+                let t = d_cur / (d_cur - d_next);
+                clipped.push(&cur + &(&(&next - &cur) * t));
+            }
+        }
+        if clipped.len() < 3 {
+            clipped.clear();
+        }
+        clipped
+    }
+}
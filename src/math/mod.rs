@@ -1,7 +1,9 @@
 pub mod vec2;
 pub use vec2::Vec2;
 
+#[cfg(feature = "std")]
 pub mod vec3;
+#[cfg(feature = "std")]
 pub use vec3::Vec3;
 
 pub mod segment2;
@@ -10,11 +12,46 @@ pub use segment2::Segment2;
 pub mod rect;
 pub use rect::Rect;
 
+pub mod ray2;
+pub use ray2::Ray2;
+
+pub mod transform2;
+pub use transform2::{ Transform2, best_fit_transform };
+
+pub mod half_plane;
+pub use half_plane::HalfPlane;
+
+pub mod circle;
+pub use circle::Circle;
+
+pub mod arc;
+pub use arc::Arc;
+
+pub mod polygon;
+
+pub mod polyline;
+pub use polyline::Polyline;
+
+pub mod bezier;
+pub use bezier::{ CubicBezier, ArcLengthTable };
+
+pub mod duality;
+pub use duality::Line;
+
+pub mod pca;
+
+pub mod morton;
+
+pub mod polar;
+
+pub mod random;
+pub use random::XorShift;
+
 
 pub mod tests;
 
 pub fn cmp_f32(a: f32, b: f32) -> bool {
-    (a - b).abs() < 0.00001
+    float::abs(a - b) < 0.00001
 }
 
 pub fn clamp(mut x: f32, min: f32, max: f32) -> f32 {
@@ -24,11 +61,51 @@ pub fn clamp(mut x: f32, min: f32, max: f32) -> f32 {
     x
 }
 
+/// Float functions that are only provided by the standard library,
+/// `libm` implements them when it is not available.
+pub mod float {
+    #[cfg(feature = "std")]
+    pub fn abs(x: f32) -> f32 { x.abs() }
+    #[cfg(not(feature = "std"))]
+    pub fn abs(x: f32) -> f32 { libm::fabsf(x) }
+
+    #[cfg(feature = "std")]
+    pub fn sqrt(x: f32) -> f32 { x.sqrt() }
+    #[cfg(not(feature = "std"))]
+    pub fn sqrt(x: f32) -> f32 { libm::sqrtf(x) }
+
+    #[cfg(feature = "std")]
+    pub fn round(x: f32) -> f32 { x.round() }
+    #[cfg(not(feature = "std"))]
+    pub fn round(x: f32) -> f32 { libm::roundf(x) }
+
+    #[cfg(feature = "std")]
+    pub fn floor(x: f32) -> f32 { x.floor() }
+    #[cfg(not(feature = "std"))]
+    pub fn floor(x: f32) -> f32 { libm::floorf(x) }
+
+    #[cfg(feature = "std")]
+    pub fn atan2(y: f32, x: f32) -> f32 { y.atan2(x) }
+    #[cfg(not(feature = "std"))]
+    pub fn atan2(y: f32, x: f32) -> f32 { libm::atan2f(y, x) }
+
+    #[cfg(feature = "std")]
+    pub fn sin(x: f32) -> f32 { x.sin() }
+    #[cfg(not(feature = "std"))]
+    pub fn sin(x: f32) -> f32 { libm::sinf(x) }
+
+    #[cfg(feature = "std")]
+    pub fn cos(x: f32) -> f32 { x.cos() }
+    #[cfg(not(feature = "std"))]
+    pub fn cos(x: f32) -> f32 { libm::cosf(x) }
+}
+
 pub trait ToArray {
     type Output;
     fn to_array(&self) -> Self::Output;
 }
 
+#[cfg(feature = "std")]
 impl<T: cgmath::BaseNum> ToArray for cgmath::Matrix4<T> {
     type Output = [[T; 4]; 4];
     fn to_array(&self) -> Self::Output {
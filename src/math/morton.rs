@@ -0,0 +1,117 @@
+//! Morton codes, which interleave the bits of the coordinates of a point on a grid.
+//! Sorting points by their codes walks the grid along a Z-order curve, which keeps nearby points close
+//! in the order: it makes the accesses to their neighbours cache-friendly and groups them into boxes.
+
+use super::{ Vec2, Rect };
+#[cfg(feature = "std")]
+use super::Vec3;
+
+use alloc::vec::Vec;
+
+/// Largest coordinate on the grid of the 2D codes, 16 bits per axis
+const MAX_2D: u32 = 0xFFFF;
+/// Largest coordinate on the grid of the 3D codes, 10 bits per axis
+#[cfg(feature = "std")]
+const MAX_3D: u32 = 0x3FF;
+
+/// Returns the code of the cell (`x`, `y`), the bits of `x` are the even bits of the code.
+pub fn encode2(x: u16, y: u16) -> u32 {
+    spread2(u32::from(x)) | (spread2(u32::from(y)) << 1)
+}
+
+/// Returns the cell of a code of [`encode2`](fn.encode2.html).
+pub fn decode2(code: u32) -> (u16, u16) {
+    (compact2(code) as u16, compact2(code >> 1) as u16)
+}
+
+/// Returns the code of the cell (`x`, `y`, `z`), only the 10 lowest bits of the coordinates are used.
+pub fn encode3(x: u16, y: u16, z: u16) -> u32 {
+    spread3(u32::from(x)) | (spread3(u32::from(y)) << 1) | (spread3(u32::from(z)) << 2)
+}
+
+/// Returns the code of `p` on a grid of 2^16 by 2^16 cells covering `bounds`.
+pub fn code2(p: Vec2, bounds: &Rect) -> u32 {
+    let x = quantize(p.x, bounds.min.x, bounds.width(), MAX_2D);
+    let y = quantize(p.y, bounds.min.y, bounds.height(), MAX_2D);
+    encode2(x as u16, y as u16)
+}
+
+/// Returns the indices of the points in the order of their codes on the grid covering them.
+/// Points in the same cell keep their order.
+pub fn order2(points: &[Vec2]) -> Vec<usize> {
+    let bounds = match Rect::from_points(points) {
+        Some(bounds) => bounds,
+        None => return Vec::new(),
+    };
+    let codes = points.iter().map(|&p| code2(p, &bounds)).collect::<Vec<_>>();
+    let mut order = (0..points.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&idx| codes[idx]);
+    order
+}
+
+/// Sorts the points along the Z-order curve.
+pub fn sort2(points: &mut [Vec2]) {
+    let sorted = order2(points).into_iter().map(|idx| points[idx]).collect::<Vec<_>>();
+    points.copy_from_slice(&sorted);
+}
+
+/// Returns the indices of the points in the order of their codes on a grid of 2^10 cells per axis covering them.
+#[cfg(feature = "std")]
+pub fn order3(points: &[Vec3]) -> Vec<usize> {
+    let (first, rest) = match points.split_first() {
+        Some(split) => split,
+        None => return Vec::new(),
+    };
+    let (min, max) = rest.iter().fold((first.0, first.0), |(min, max), p| {
+        (cgmath::vec3(min.x.min(p.0.x), min.y.min(p.0.y), min.z.min(p.0.z)),
+         cgmath::vec3(max.x.max(p.0.x), max.y.max(p.0.y), max.z.max(p.0.z)))
+    });
+    let codes = points
+        .iter()
+        .map(|p| {
+            let x = quantize(p.0.x, min.x, max.x - min.x, MAX_3D);
+            let y = quantize(p.0.y, min.y, max.y - min.y, MAX_3D);
+            let z = quantize(p.0.z, min.z, max.z - min.z, MAX_3D);
+            encode3(x as u16, y as u16, z as u16)
+        })
+        .collect::<Vec<_>>();
+    let mut order = (0..points.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&idx| codes[idx]);
+    order
+}
+
+/// Returns the cell of `value` among `max + 1` cells covering [min, min + size].
+fn quantize(value: f32, min: f32, size: f32, max: u32) -> u32 {
+    if size <= 0.0 {
+        return 0;
+    }
+    let cell = (value - min) / size * max as f32;
+    (cell.max(0.0) as u32).min(max)
+}
+
+/// Inserts a 0 bit before each of the 16 lowest bits of `x`.
+fn spread2(x: u32) -> u32 {
+    let mut x = x & 0xFFFF;
+    x = (x | (x << 8)) & 0x00FF_00FF;
+    x = (x | (x << 4)) & 0x0F0F_0F0F;
+    x = (x | (x << 2)) & 0x3333_3333;
+    (x | (x << 1)) & 0x5555_5555
+}
+
+/// Keeps the even bits of `x`, the inverse of `spread2`.
+fn compact2(x: u32) -> u32 {
+    let mut x = x & 0x5555_5555;
+    x = (x | (x >> 1)) & 0x3333_3333;
+    x = (x | (x >> 2)) & 0x0F0F_0F0F;
+    x = (x | (x >> 4)) & 0x00FF_00FF;
+    (x | (x >> 8)) & 0xFFFF
+}
+
+/// Inserts two 0 bits before each of the 10 lowest bits of `x`.
+fn spread3(x: u32) -> u32 {
+    let mut x = x & 0x3FF;
+    x = (x | (x << 16)) & 0x0300_00FF;
+    x = (x | (x << 8)) & 0x0300_F00F;
+    x = (x | (x << 4)) & 0x030C_30C3;
+    (x | (x << 2)) & 0x0924_9249
+}
@@ -0,0 +1,161 @@
+//! Principal component analysis of sets of points: the principal axes are the eigenvectors of the covariance matrix,
+//! orthogonal directions along which the spread of the points is the largest, then the smallest.
+//! Their eigenvalues are the variances of the points along them.
+
+use super::{ Vec2, float };
+#[cfg(feature = "std")]
+use super::Vec3;
+
+/// Principal axes of a set of 2D points, the axis of largest variance first.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct PrincipalAxes {
+    pub centroid: Vec2,
+    /// Unit vectors, the second one is the first one rotated counter-clockwise
+    pub axes: [Vec2; 2],
+    /// Variances of the points along the axes, the eigenvalues of the covariance matrix
+    pub variances: [f32; 2],
+}
+
+impl PrincipalAxes {
+    /// Returns the coordinates of `p` in the principal frame, whose origin is the centroid.
+    pub fn to_local(&self, p: Vec2) -> Vec2 {
+        let offset = &p - &self.centroid;
+        Vec2::new(offset.dot(self.axes[0]), offset.dot(self.axes[1]))
+    }
+
+    /// Rotates `p` around the centroid so that the principal axes become the x and y axes.
+    pub fn align(&self, p: Vec2) -> Vec2 {
+        &self.centroid + &self.to_local(p)
+    }
+}
+
+/// Returns the principal axes of the points, or `None` if there is no point.
+/// The axes are arbitrary when the variance is the same in every direction.
+pub fn principal_axes(points: &[Vec2]) -> Option<PrincipalAxes> {
+    if points.is_empty() {
+        return None;
+    }
+    let n = points.len() as f32;
+    let sum = points.iter().fold(Vec2::new(0.0, 0.0), |sum, p| &sum + p);
+    let centroid = (1.0 / n) * &sum;
+
+    // Covariance matrix [[xx, xy], [xy, yy]]
+    let (mut xx, mut xy, mut yy) = (0.0, 0.0, 0.0);
+    for p in points {
+        let d = p - &centroid;
+        xx += d.x * d.x;
+        xy += d.x * d.y;
+        yy += d.y * d.y;
+    }
+    let (xx, xy, yy) = (xx / n, xy / n, yy / n);
+
+    // Eigenvalues of a symmetric 2x2 matrix, the first eigenvector makes the angle θ with the x axis, tan 2θ = 2xy / (xx - yy)
+    let mean = (xx + yy) / 2.0;
+    let radius = float::sqrt(((xx - yy) / 2.0) * ((xx - yy) / 2.0) + xy * xy);
+    let angle = float::atan2(2.0 * xy, xx - yy) / 2.0;
+    let axis = Vec2::new(float::cos(angle), float::sin(angle));
+    Some(PrincipalAxes {
+        centroid,
+        axes: [ axis, Vec2::new(-axis.y, axis.x) ],
+        variances: [ mean + radius, (mean - radius).max(0.0) ],
+    })
+}
+
+/// Principal axes of a set of 3D points, by decreasing variance.
+#[cfg(feature = "std")]
+#[derive(Copy, Clone, Debug)]
+pub struct PrincipalAxes3 {
+    pub centroid: Vec3,
+    /// Unit vectors forming a right-handed frame
+    pub axes: [Vec3; 3],
+    /// Variances of the points along the axes, the eigenvalues of the covariance matrix
+    pub variances: [f32; 3],
+}
+
+#[cfg(feature = "std")]
+impl PrincipalAxes3 {
+    /// Returns the coordinates of `p` in the principal frame, whose origin is the centroid.
+    pub fn to_local(&self, p: Vec3) -> Vec3 {
+        use cgmath::InnerSpace;
+        let offset = p.0 - self.centroid.0;
+        Vec3::new(offset.dot(self.axes[0].0), offset.dot(self.axes[1].0), offset.dot(self.axes[2].0))
+    }
+
+    /// Rotates `p` around the centroid so that the principal axes become the x, y and z axes.
+    pub fn align(&self, p: Vec3) -> Vec3 {
+        (self.centroid.0 + self.to_local(p).0).into()
+    }
+}
+
+/// Maximum number of sweeps of the Jacobi eigenvalue algorithm, it converges in a few of them for a 3x3 matrix
+#[cfg(feature = "std")]
+const MAX_SWEEPS: usize = 32;
+
+/// Returns the principal axes of the points, or `None` if there is no point.
+/// The eigenvectors of the covariance matrix are found with the Jacobi eigenvalue algorithm,
+/// which cancels the off-diagonal terms one after the other with rotations.
+#[cfg(feature = "std")]
+pub fn principal_axes_3d(points: &[Vec3]) -> Option<PrincipalAxes3> {
+    use cgmath::{ InnerSpace, Vector3 };
+
+    if points.is_empty() {
+        return None;
+    }
+    let n = points.len() as f64;
+    let centroid = points.iter().fold(Vector3::new(0.0, 0.0, 0.0), |sum, p| sum + p.0) / n as f32;
+
+    let mut a = [[0.0f64; 3]; 3];
+    for p in points {
+        let d = p.0 - centroid;
+        let d = [ d.x as f64, d.y as f64, d.z as f64 ];
+        for i in 0..3 {
+            for j in 0..3 {
+                a[i][j] += d[i] * d[j] / n;
+            }
+        }
+    }
+
+    // The columns of `v` are the eigenvectors
+    let mut v = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+    for _ in 0..MAX_SWEEPS {
+        let off_diagonal = a[0][1] * a[0][1] + a[0][2] * a[0][2] + a[1][2] * a[1][2];
+        if off_diagonal <= f64::EPSILON * f64::EPSILON * (a[0][0] * a[0][0] + a[1][1] * a[1][1] + a[2][2] * a[2][2]) {
+            break;
+        }
+        for &(p, q) in &[ (0, 1), (0, 2), (1, 2) ] {
+            if a[p][q] == 0.0 {
+                continue;
+            }
+            // Rotation in the plane (p, q) that cancels a[p][q]
+            let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+            let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+            let c = 1.0 / (t * t + 1.0).sqrt();
+            let s = t * c;
+            for row in &mut a {
+                let (akp, akq) = (row[p], row[q]);
+                row[p] = c * akp - s * akq;
+                row[q] = s * akp + c * akq;
+            }
+            let (row_p, row_q) = (a[p], a[q]);
+            for k in 0..3 {
+                a[p][k] = c * row_p[k] - s * row_q[k];
+                a[q][k] = s * row_p[k] + c * row_q[k];
+            }
+            for row in &mut v {
+                let (vkp, vkq) = (row[p], row[q]);
+                row[p] = c * vkp - s * vkq;
+                row[q] = s * vkp + c * vkq;
+            }
+        }
+    }
+
+    let mut order = [ 0, 1, 2 ];
+    order.sort_by(|&i, &j| a[j][j].total_cmp(&a[i][i]));
+    let axis = |i: usize| Vector3::new(v[0][i] as f32, v[1][i] as f32, v[2][i] as f32).normalize();
+    let (first, second) = (axis(order[0]), axis(order[1]));
+    Some(PrincipalAxes3 {
+        centroid: centroid.into(),
+        axes: [ first.into(), second.into(), first.cross(second).into() ],
+        variances: [ a[order[0]][order[0]].max(0.0) as f32, a[order[1]][order[1]].max(0.0) as f32, a[order[2]][order[2]].max(0.0) as f32 ],
+    })
+}
@@ -0,0 +1,51 @@
+//! Ordering of points by their polar angle around a center, compared with orientation tests instead of angles.
+//! `atan2` rounds the angles of nearly collinear points, so that different directions can compare equal
+//! and the order of the points can contradict the turns between them. The orientation tests are exact
+//! unless the coordinates are far apart in magnitude, which keeps the comparison consistent with the turns.
+
+use super::Vec2;
+
+use core::cmp::Ordering;
+use alloc::vec::Vec;
+
+/// Returns 0 if the direction from `center` to `p` has an angle in [0, π), 1 if it is in [π, 2π).
+/// The center itself is in the first half.
+fn half(center: Vec2, p: Vec2) -> u8 {
+    if p.y > center.y || (p.y == center.y && p.x >= center.x) { 0 } else { 1 }
+}
+
+fn sqr_distance(center: Vec2, p: Vec2) -> f64 {
+    let (dx, dy) = (p.x as f64 - center.x as f64, p.y as f64 - center.y as f64);
+    dx * dx + dy * dy
+}
+
+/// Compares the polar angles of `a` and `b` around `center`, counter-clockwise from the direction of the x axis.
+/// Points in the same direction are ordered by their distance to the center, which comes before any other point.
+pub fn compare(center: Vec2, a: Vec2, b: Vec2) -> Ordering {
+    half(center, a)
+        .cmp(&half(center, b))
+        .then_with(|| {
+            // In the same half, b comes after a if it turns left from it. Collinear points are then in the same direction
+            let orientation = Vec2::orientation(center, a, b);
+            if orientation > 0.0 {
+                Ordering::Less
+            } else if orientation < 0.0 {
+                Ordering::Greater
+            } else {
+                Ordering::Equal
+            }
+        })
+        .then_with(|| sqr_distance(center, a).partial_cmp(&sqr_distance(center, b)).unwrap_or(Ordering::Equal))
+}
+
+/// Returns the indices of the points in increasing polar angle around `center`, as ordered by [`compare`](fn.compare.html).
+pub fn order(center: Vec2, points: &[Vec2]) -> Vec<usize> {
+    let mut indices = (0..points.len()).collect::<Vec<_>>();
+    indices.sort_by(|&a, &b| compare(center, points[a], points[b]));
+    indices
+}
+
+/// Sorts the points in increasing polar angle around `center`, as ordered by [`compare`](fn.compare.html).
+pub fn sort(center: Vec2, points: &mut [Vec2]) {
+    points.sort_by(|&a, &b| compare(center, a, b));
+}
@@ -0,0 +1,83 @@
+//! Functions over simple polygons, given as their vertices in order.
+//! The last vertex is implicitly linked to the first one, the polygons can be clockwise or counter-clockwise.
+
+use super::{ Vec2, Rect };
+
+/// Returns the area of the polygon, positive if its vertices are in counter-clockwise order.
+pub fn signed_area(polygon: &[Vec2]) -> f32 {
+    edges(polygon)
+        .map(|(a, b)| a.x * b.y - b.x * a.y)
+        .sum::<f32>() * 0.5
+}
+
+/// Returns the center of mass of the polygon, or its first vertex if it has no area.
+pub fn centroid(polygon: &[Vec2]) -> Option<Vec2> {
+    let first = *polygon.first()?;
+    let area = signed_area(polygon);
+    if area == 0.0 {
+        return Some(first);
+    }
+
+    // Computed relative to the first vertex to keep the products small
+    let (mut x, mut y) = (0.0, 0.0);
+    for (a, b) in edges(polygon) {
+        let (a, b) = (&a - &first, &b - &first);
+        let cross = a.x * b.y - b.x * a.y;
+        x += (a.x + b.x) * cross;
+        y += (a.y + b.y) * cross;
+    }
+    Some(&first + &Vec2::new(x / (6.0 * area), y / (6.0 * area)))
+}
+
+/// Returns the smallest rectangle containing the polygon.
+pub fn bounding_rect(polygon: &[Vec2]) -> Option<Rect> {
+    Rect::from_points(polygon)
+}
+
+/// Checks whether `p` lies inside the polygon with the even-odd rule, points on the boundary may be on either side.
+pub fn contains(polygon: &[Vec2], p: Vec2) -> bool {
+    let mut inside = false;
+    for (a, b) in edges(polygon) {
+        // Edges crossing the horizontal line through `p`, on its right
+        if (a.y > p.y) != (b.y > p.y) && p.x < a.x + (p.y - a.y) / (b.y - a.y) * (b.x - a.x) {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+/// Returns the distance from `p` to the closest edge of the polygon.
+pub fn boundary_distance(polygon: &[Vec2], p: Vec2) -> f32 {
+    super::polyline::distance(polygon, true, p)
+}
+
+/// Returns the distance from `p` to the boundary of the polygon, positive inside and negative outside.
+pub fn signed_distance(polygon: &[Vec2], p: Vec2) -> f32 {
+    let distance = boundary_distance(polygon, p);
+    if contains(polygon, p) { distance } else { -distance }
+}
+
+/// Returns the edges of the polygon, including the one from the last vertex to the first one.
+pub fn edges(polygon: &[Vec2]) -> impl Iterator<Item = (Vec2, Vec2)> + '_ {
+    polygon.iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .map(|(&a, &b)| (a, b))
+}
+
+/// Checks whether two convex polygons overlap with the separating axis theorem: they are disjoint
+/// if and only if their projections on the normal of one of their edges are disjoint. Touching polygons overlap.
+pub fn convex_overlap(a: &[Vec2], b: &[Vec2]) -> bool {
+    if a.is_empty() || b.is_empty() {
+        return false;
+    }
+    let project = |polygon: &[Vec2], axis: Vec2| {
+        polygon.iter()
+            .map(|p| p.dot(axis))
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), d| (min.min(d), max.max(d)))
+    };
+    edges(a).chain(edges(b)).all(|(p, q)| {
+        let axis = Vec2::new(p.y - q.y, q.x - p.x);
+        let ((a_min, a_max), (b_min, b_max)) = (project(a, axis), project(b, axis));
+        a_min <= b_max && b_min <= a_max
+    })
+}
@@ -0,0 +1,190 @@
+//! Functions over polylines, given as their vertices in order.
+//! A closed polyline has an implicit edge from its last vertex back to its first one, like a polygon.
+
+use super::{ Vec2, Segment2 };
+
+use alloc::{ vec, vec::Vec };
+
+/// Returns the edges of the polyline, the closing one last if it is closed.
+pub fn edges(points: &[Vec2], closed: bool) -> impl Iterator<Item = (Vec2, Vec2)> + '_ {
+    let closing = match (points.first(), points.last()) {
+        (Some(&first), Some(&last)) if closed && points.len() > 1 => Some((last, first)),
+        _ => None,
+    };
+    points.windows(2).map(|pair| (pair[0], pair[1])).chain(closing)
+}
+
+/// Returns the length of the polyline, its perimeter if it is closed.
+pub fn length(points: &[Vec2], closed: bool) -> f32 {
+    edges(points, closed).map(|(a, b)| (&b - &a).length()).sum()
+}
+
+/// Returns the distance from `p` to the closest edge of the polyline, infinite if it is empty.
+pub fn distance(points: &[Vec2], closed: bool, p: Vec2) -> f32 {
+    if points.len() == 1 {
+        return (&p - &points[0]).length();
+    }
+    edges(points, closed)
+        .map(|(a, b)| Segment2::new(a, b).distance_to_point(p))
+        .fold(f32::INFINITY, f32::min)
+}
+
+/// Returns the point at the arc length `distance` from the first vertex, or `None` if the polyline is empty.
+/// The distance wraps around a closed polyline and is clamped to the ends of an open one.
+pub fn point_at(points: &[Vec2], closed: bool, distance: f32) -> Option<Vec2> {
+    let first = *points.first()?;
+    let total = length(points, closed);
+    if total == 0.0 {
+        return Some(first);
+    }
+    let mut distance = if closed { (distance % total + total) % total } else { distance.max(0.0).min(total) };
+
+    for (a, b) in edges(points, closed) {
+        let edge = (&b - &a).length();
+        if distance <= edge && edge > 0.0 {
+            return Some(&a + &(&(&b - &a) * (distance / edge)));
+        }
+        distance -= edge;
+    }
+    Some(if closed { first } else { points[points.len() - 1] })
+}
+
+/// Returns `count` points evenly spaced by arc length along the polyline, from its first vertex.
+/// The points of an open polyline end at its last vertex, those of a closed one split it in `count` equal parts.
+/// Returns no points if the polyline is empty.
+pub fn resample(points: &[Vec2], closed: bool, count: usize) -> Vec<Vec2> {
+    let first = match points.first() {
+        Some(&first) => first,
+        None => return Vec::new(),
+    };
+    let total = length(points, closed);
+    if total == 0.0 || count < 2 {
+        return vec![ first; count ];
+    }
+    let step = if closed { total / count as f32 } else { total / (count - 1) as f32 };
+
+    // A single walk along the edges, the targets are increasing
+    let mut samples = Vec::with_capacity(count);
+    let mut edges = edges(points, closed);
+    let (mut a, mut b) = edges.next().expect("No edge");
+    let mut start = 0.0;
+    while samples.len() < count {
+        let target = step * samples.len() as f32;
+        let edge = (&b - &a).length();
+        if target > start + edge {
+            match edges.next() {
+                Some(next) => {
+                    start += edge;
+                    a = next.0;
+                    b = next.1;
+                    continue;
+                },
+                // The rounding of the steps can leave the last targets just past the end
+                None => {
+                    samples.push(b);
+                    continue;
+                },
+            }
+        }
+        let t = if edge > 0.0 { (target - start) / edge } else { 0.0 };
+        samples.push(&a + &(&(&b - &a) * t));
+    }
+    samples
+}
+
+/// Chain of points, the polylines of the free functions with whether they are closed.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Polyline {
+    pub points: Vec<Vec2>,
+    /// Whether the last point is linked to the first one
+    pub closed: bool,
+}
+
+impl Polyline {
+    pub fn new(points: Vec<Vec2>, closed: bool) -> Self {
+        Self {
+            points,
+            closed,
+        }
+    }
+
+    /// Creates an open polyline, from its first point to its last one
+    pub fn open(points: Vec<Vec2>) -> Self {
+        Self::new(points, false)
+    }
+
+    pub fn edges(&self) -> impl Iterator<Item = (Vec2, Vec2)> + '_ {
+        edges(&self.points, self.closed)
+    }
+
+    /// Returns the length of the polyline, its perimeter if it is closed
+    pub fn length(&self) -> f32 {
+        length(&self.points, self.closed)
+    }
+
+    pub fn distance(&self, p: Vec2) -> f32 {
+        distance(&self.points, self.closed, p)
+    }
+
+    /// Returns the point at the arc length `distance` from the first point, see [`point_at`](fn.point_at.html)
+    pub fn point_at(&self, distance: f32) -> Option<Vec2> {
+        point_at(&self.points, self.closed, distance)
+    }
+
+    /// Returns the point at the fraction `t` of the length, from 0 at the first point to 1 at the end
+    pub fn point_at_parameter(&self, t: f32) -> Option<Vec2> {
+        self.point_at(t * self.length())
+    }
+
+    /// Returns the polyline through `count` points evenly spaced by arc length, see [`resample`](fn.resample.html)
+    pub fn resampled(&self, count: usize) -> Self {
+        Self::new(resample(&self.points, self.closed, count), self.closed)
+    }
+
+    /// Returns the polyline without the points closer than `tolerance` to the simplified chain,
+    /// with the Ramer-Douglas-Peucker algorithm: the ends are kept, and the chain between two kept points
+    /// is split at its farthest point from their segment as long as it is farther than the tolerance.
+    /// A closed polyline is split at its first point, which is kept.
+    pub fn simplified(&self, tolerance: f32) -> Self {
+        let mut chain = self.points.clone();
+        if self.closed {
+            if let Some(&first) = chain.first() {
+                chain.push(first);
+            }
+        }
+        if chain.len() < 3 {
+            return self.clone();
+        }
+
+        let mut keep = vec![ false; chain.len() ];
+        keep[0] = true;
+        keep[chain.len() - 1] = true;
+        let mut stack = vec![ (0, chain.len() - 1) ];
+        while let Some((start, end)) = stack.pop() {
+            let base = Segment2::new(chain[start], chain[end]);
+            let farthest = (start + 1..end)
+                .map(|i| (i, base.distance_to_point(chain[i])))
+                .max_by(|a, b| a.1.total_cmp(&b.1));
+            if let Some((i, distance)) = farthest {
+                if distance > tolerance {
+                    keep[i] = true;
+                    stack.push((start, i));
+                    stack.push((i, end));
+                }
+            }
+        }
+
+        let mut points = chain.into_iter().zip(keep).filter(|&(_, keep)| keep).map(|(p, _)| p).collect::<Vec<_>>();
+        if self.closed {
+            points.pop();
+        }
+        let closed = self.closed && points.len() >= 3;
+        Self::new(points, closed)
+    }
+}
+
+impl From<Vec<Vec2>> for Polyline {
+    fn from(points: Vec<Vec2>) -> Self {
+        Self::open(points)
+    }
+}
@@ -0,0 +1,50 @@
+//! Reproducible pseudo-random numbers without the standard library, for the shuffles and the small perturbations
+//! of the algorithms. They only need to avoid the bad orders of sorted inputs, not to be statistically strong.
+
+/// Seed of the sequences of the algorithms, and of the generators created with a zero seed
+const SEED: u64 = 0x2545_F491_4F6C_DD1D;
+
+/// Xorshift generator with the (13, 7, 17) shifts on 64 bits.
+#[derive(Clone, Debug)]
+pub struct XorShift {
+    state: u64,
+}
+
+impl XorShift {
+    /// A zero seed, which the generator would never leave, is replaced with the default seed.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            state: if seed == 0 { SEED } else { seed },
+        }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    /// Returns a number between 0 included and 1 excluded, from the 53 high bits of the next number.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Returns a number below `n`, which must not be 0.
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// Shuffles the items in place (Fisher-Yates).
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for idx in (1..items.len()).rev() {
+            items.swap(idx, self.below(idx + 1));
+        }
+    }
+}
+
+impl Default for XorShift {
+    fn default() -> Self {
+        Self::new(SEED)
+    }
+}
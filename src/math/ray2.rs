@@ -0,0 +1,66 @@
+use super::{ Vec2, Segment2, Circle, Rect, float, polygon };
+
+/// Half-line from `origin` in `direction`, its points are `origin + t * direction` for `t >= 0`.
+/// The parameters returned by the intersections are in units of `direction`, which does not need to be normalized.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Ray2 {
+    pub origin: Vec2,
+    pub direction: Vec2,
+}
+
+impl Ray2 {
+    pub fn new(origin: Vec2, direction: Vec2) -> Self {
+        Self {
+            origin,
+            direction,
+        }
+    }
+
+    /// Creates the ray from `origin` through `target`, where `t` is 1
+    pub fn through(origin: Vec2, target: Vec2) -> Self {
+        Self::new(origin, &target - &origin)
+    }
+
+    /// Returns the point `origin + t * direction`
+    pub fn at(&self, t: f32) -> Vec2 {
+        &self.origin + &(&self.direction * t)
+    }
+
+    /// Returns the parameter of the point where the ray hits the segment,
+    /// or `None` if it misses it or runs parallel to it
+    pub fn intersect_segment(&self, segment: &Segment2) -> Option<f32> {
+        segment.ray_intersection(self.origin, self.direction)
+    }
+
+    /// Returns the parameter of the first point where the ray crosses the circle,
+    /// the way out if the origin is inside it
+    pub fn intersect_circle(&self, circle: &Circle) -> Option<f32> {
+        // Roots of |origin + t * direction - center|² = radius²
+        let a = self.direction.sqr_length();
+        if a == 0.0 {
+            return None;
+        }
+        let offset = &self.origin - &circle.center;
+        let half_b = offset.dot(self.direction);
+        let c = offset.sqr_length() - circle.radius * circle.radius;
+        let discriminant = half_b * half_b - a * c;
+        if discriminant < 0.0 {
+            return None;
+        }
+        let root = float::sqrt(discriminant);
+        [ (-half_b - root) / a, (-half_b + root) / a ].iter().copied().find(|&t| t >= 0.0)
+    }
+
+    /// Returns the parameter of the first point where the ray crosses the sides of the rectangle,
+    /// the way out if the origin is inside it
+    pub fn intersect_rect(&self, rect: &Rect) -> Option<f32> {
+        self.intersect_polygon(&rect.corners())
+    }
+
+    /// Returns the parameter of the first point where the ray crosses an edge of the polygon
+    pub fn intersect_polygon(&self, polygon: &[Vec2]) -> Option<f32> {
+        polygon::edges(polygon)
+            .filter_map(|(a, b)| self.intersect_segment(&Segment2::new(a, b)))
+            .fold(None, |first: Option<f32>, t| Some(first.map_or(t, |first| first.min(t))))
+    }
+}
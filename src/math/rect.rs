@@ -1,32 +1,105 @@
+use alloc::vec::Vec;
+
 use super::Vec2;
 
+/// Axis-aligned rectangle, from its corner with the smallest coordinates to the one with the largest
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub struct Rect {
-    pub left: f32,
-    pub right: f32,
-    pub top: f32,
-    pub bottom: f32,
+    pub min: Vec2,
+    pub max: Vec2,
 }
 
 impl Rect {
     /// Creates a rectangle delimited by two corners
     pub fn new(point_a: Vec2, point_b: Vec2) -> Self {
-        let (left, right) = if point_a.x < point_b.x {
-            (point_a.x, point_b.x)
-        } else {
-            (point_b.x, point_a.x)
-        };
-        let (top, bottom) = if point_a.y < point_b.y {
-            (point_a.y, point_b.y)
-        } else {
-            (point_b.y, point_a.y)
-        };
+        Self {
+            min: Vec2::new(point_a.x.min(point_b.x), point_a.y.min(point_b.y)),
+            max: Vec2::new(point_a.x.max(point_b.x), point_a.y.max(point_b.y)),
+        }
+    }
 
+    /// Returns the smallest rectangle that contains all the points, or `None` if there are none
+    pub fn from_points(points: &[Vec2]) -> Option<Self> {
+        let (first, rest) = points.split_first()?;
+        Some(rest.iter().fold(Self::new(*first, *first), |rect, &p| rect.union(&Self::new(p, p))))
+    }
+
+    pub fn width(&self) -> f32 {
+        self.max.x - self.min.x
+    }
+
+    pub fn height(&self) -> f32 {
+        self.max.y - self.min.y
+    }
+
+    pub fn center(&self) -> Vec2 {
+        Vec2::new((self.min.x + self.max.x) * 0.5, (self.min.y + self.max.y) * 0.5)
+    }
+
+    pub fn area(&self) -> f32 {
+        self.width() * self.height()
+    }
+
+    pub fn perimeter(&self) -> f32 {
+        2.0 * (self.width() + self.height())
+    }
+
+    /// Returns the smallest rectangle that contains both rectangles
+    pub fn union(&self, other: &Self) -> Self {
         Self {
-            left,
-            right,
-            top,
-            bottom,
+            min: Vec2::new(self.min.x.min(other.min.x), self.min.y.min(other.min.y)),
+            max: Vec2::new(self.max.x.max(other.max.x), self.max.y.max(other.max.y)),
         }
     }
+
+    /// Returns the rectangle shared by both rectangles, or `None` if they do not overlap
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        if !self.overlaps(other) {
+            return None;
+        }
+        Some(Self {
+            min: Vec2::new(self.min.x.max(other.min.x), self.min.y.max(other.min.y)),
+            max: Vec2::new(self.max.x.min(other.max.x), self.max.y.min(other.max.y)),
+        })
+    }
+
+    /// Returns whether the two rectangles overlap, borders included
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.min.x <= other.max.x && other.min.x <= self.max.x &&
+        self.min.y <= other.max.y && other.min.y <= self.max.y
+    }
+
+    /// Returns whether the point is inside the rectangle, borders included
+    pub fn contains(&self, point: Vec2) -> bool {
+        point.x >= self.min.x && point.x <= self.max.x &&
+        point.y >= self.min.y && point.y <= self.max.y
+    }
+
+    /// Returns whether `other` is entirely inside the rectangle, borders included
+    pub fn contains_rect(&self, other: &Self) -> bool {
+        self.contains(other.min) && self.contains(other.max)
+    }
+
+    /// Returns the rectangle grown by `margin` on every side, or shrunk if it is negative,
+    /// down to its center at most
+    pub fn inflate(&self, margin: f32) -> Self {
+        let center = self.center();
+        Self {
+            min: Vec2::new((self.min.x - margin).min(center.x), (self.min.y - margin).min(center.y)),
+            max: Vec2::new((self.max.x + margin).max(center.x), (self.max.y + margin).max(center.y)),
+        }
+    }
+
+    /// Returns the corners of the rectangle, counter-clockwise from the one with the smallest coordinates
+    pub fn corners(&self) -> [Vec2; 4] {
+        [
+            self.min, Vec2::new(self.max.x, self.min.y),
+            self.max, Vec2::new(self.min.x, self.max.y),
+        ]
+    }
+
+    /// Returns the rectangle as a counter-clockwise polygon, for the functions of the `polygon` module
+    pub fn to_polygon(&self) -> Vec<Vec2> {
+        self.corners().to_vec()
+    }
 }
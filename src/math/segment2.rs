@@ -1,4 +1,16 @@
-use super::{ Vec2, Rect, cmp_f32 };
+use super::{ Vec2, Rect, cmp_f32, clamp, float };
+
+/// Tolerance of `contains` and `intersects`, relative to the lengths of the segments
+const TOLERANCE: f32 = 1e-5;
+
+fn cross(u: Vec2, v: Vec2) -> f32 {
+    u.x * v.y - u.y * v.x
+}
+
+/// Returns whether the parameter `t` of a point of the line falls between the ends of the segment
+fn within_ends(t: f32) -> bool {
+    (-TOLERANCE..=1.0 + TOLERANCE).contains(&t)
+}
 
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub struct Segment2 {
@@ -31,16 +43,59 @@ impl Segment2 {
         Rect::new(self.a, self.b)
     }
 
-    /// Returns whether the segment contains a given point
+    /// Returns the bounding rectangle grown by the tolerance of `contains` and `intersects`, so that it holds every
+    /// point the tests may accept.
+    pub fn tolerant_bounding_rect(&self) -> Rect {
+        self.bounding_rect().inflate(TOLERANCE * self.as_vec2().length())
+    }
+
+    /// Returns whether the segment contains a given point, within a distance relative to its length
     pub fn contains(&self, point: Vec2) -> bool {
+        let v = self.as_vec2();
+        let sqr_length = v.sqr_length();
+        if sqr_length == 0.0 {
+            return false;
+        }
+
+        // The distance to the line is |cross| / |v|, compared to a fraction of |v|
+        let offset = &point - &self.a;
+        if float::abs(cross(v, offset)) > TOLERANCE * sqr_length {
+            return false;
+        }
+        let t = offset.dot(v) / sqr_length;
+        within_ends(t)
+    }
+
+    /// Returns whether the two segments intersect, endpoints and collinear overlaps included
+    pub fn intersects(&self, other: &Self) -> bool {
+        let (v1, v2) = (self.as_vec2(), other.as_vec2());
+        if v1.sqr_length() == 0.0 || v2.sqr_length() == 0.0 {
+            return false;
+        }
+
+        // The cross product is the sine of the angle between the segments times their lengths
+        let denominator = cross(v1, v2);
+        if float::abs(denominator) <= TOLERANCE * v1.length() * v2.length() {
+            // One of the segments may be inside the other one
+            return self.contains(other.a) || self.contains(other.b) ||
+                    other.contains(self.a) || other.contains(self.b);
+        }
+
+        let offset = &other.a - &self.a;
+        let t = cross(offset, v2) / denominator;
+        let u = cross(offset, v1) / denominator;
+        within_ends(t) && within_ends(u)
+    }
+
+    /// Returns whether the segment contains a given point, with the slope of the segment
+    #[deprecated(note = "breaks down for near-vertical segments and at small scales, use `contains`")]
+    pub fn contains_by_slope(&self, point: Vec2) -> bool {
         if self.is_degenerate() {
             return false;
         }
 
-        let bounds = self.bounding_rect();
         // Check if the point is contained in the segment's bounding rectangle
-        if point.x < bounds.left || point.x > bounds.right ||
-            point.y < bounds.top || point.y > bounds.bottom {
+        if !self.bounding_rect().contains(point) {
             return false;
         }
 
@@ -56,8 +111,10 @@ impl Segment2 {
         cmp_f32(a * point.x + b, point.y)
     }
 
-    /// Returns whether the two segments intersect
-    pub fn intersects(&self, other: &Self) -> bool {
+    /// Returns whether the two segments intersect, with their slopes
+    #[deprecated(note = "breaks down for near-vertical segments and at small scales, use `intersects`")]
+    #[allow(deprecated)]
+    pub fn intersects_by_slope(&self, other: &Self) -> bool {
         if self.is_degenerate() || other.is_degenerate() {
             return false;
         }
@@ -65,11 +122,13 @@ impl Segment2 {
         let v1 = self.as_vec2();
         let v2 = other.as_vec2();
         if v1.collinear(v2) {
-            return self.contains(other.a) || self.contains(other.b);
+            // One of the segments may be inside the other one
+            return self.contains_by_slope(other.a) || self.contains_by_slope(other.b) ||
+                    other.contains_by_slope(self.a) || other.contains_by_slope(self.b);
         }
 
         let intersection = self.intersection(other);
-        self.contains(intersection) && other.contains(intersection)
+        self.contains_by_slope(intersection) && other.contains_by_slope(intersection)
     }
 
     /// Returns the intersection point of two segments
@@ -78,7 +137,7 @@ impl Segment2 {
         let v2 = other.as_vec2();
 
         if !v1.intersects(v2) {
-            return Vec2::new(std::f32::NAN, std::f32::NAN);
+            return Vec2::new(f32::NAN, f32::NAN);
         }
 
         let a1 = v1.slope();
@@ -103,10 +162,68 @@ impl Segment2 {
         Vec2::new(x, y)
     }
 
+    /// Returns the parameters `t` on this segment and `u` on `other` of the point where they cross,
+    /// both in [0, 1], and the point itself, or `None` if they do not cross or are parallel
+    pub fn parametric_intersection(&self, other: &Self) -> Option<(f32, f32, Vec2)> {
+        let (v1, v2) = (self.as_vec2(), other.as_vec2());
+        let denominator = cross(v1, v2);
+        if denominator == 0.0 {
+            return None;
+        }
+        let offset = &other.a - &self.a;
+        let t = cross(offset, v2) / denominator;
+        let u = cross(offset, v1) / denominator;
+        if (0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u) {
+            Some((t, u, self.lerp(t)))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the point `a + t * (b - a)`, `t` is not clamped
+    pub fn lerp(&self, t: f32) -> Vec2 {
+        &self.a + &(&self.as_vec2() * t)
+    }
+
+    /// Returns the parameter `t` of the orthogonal projection of `point` on the line of the segment,
+    /// in [0, 1] if it falls between the ends, 0 if the segment is degenerate
+    pub fn project(&self, point: Vec2) -> f32 {
+        let v = self.as_vec2();
+        let sqr_length = v.sqr_length();
+        if sqr_length == 0.0 {
+            return 0.0;
+        }
+        (&point - &self.a).dot(v) / sqr_length
+    }
+
+    /// Returns the point of the segment closest to `point`
+    pub fn closest_point(&self, point: Vec2) -> Vec2 {
+        self.lerp(clamp(self.project(point), 0.0, 1.0))
+    }
+
+    /// Returns the distance from `point` to the closest point of the segment
+    pub fn distance_to_point(&self, point: Vec2) -> f32 {
+        (&point - &self.closest_point(point)).length()
+    }
+
+    /// Returns the parameter `t` of the point `origin + t * direction` where the ray hits the segment,
+    /// or `None` if it misses it or runs parallel to it
+    pub fn ray_intersection(&self, origin: Vec2, direction: Vec2) -> Option<f32> {
+        let v = self.as_vec2();
+        let denominator = cross(direction, v);
+        if denominator == 0.0 {
+            return None;
+        }
+        let offset = &self.a - &origin;
+        let t = cross(offset, v) / denominator;
+        let u = cross(offset, direction) / denominator;
+        if t >= 0.0 && (0.0..=1.0).contains(&u) { Some(t) } else { None }
+    }
+
     /// Returns the y value of the point that satisfies x = 0
     pub fn y_intercept(self) -> f32 {
         if cmp_f32(self.a.x, self.b.x) {
-            return std::f32::NAN;
+            return f32::NAN;
         }
         self.a.y - self.as_vec2().slope() * self.a.x
     }
@@ -17,11 +17,11 @@ fn vec2_is_zero() {
 fn vec2_length() {
     let mut v = Vec2::new(6.0, 3.0);
     assert!(cmp_f32(v.sqr_length(), 45.0));
-    assert!(cmp_f32(v.length(), 6.708203933));
+    assert!(cmp_f32(v.length(), 6.708_204));
 
     v = Vec2::new(-4.0, 2.0);
     assert!(cmp_f32(v.sqr_length(), 20.0));
-    assert!(cmp_f32(v.length(), 4.472135955));
+    assert!(cmp_f32(v.length(), 4.472_136));
 
     v = Vec2::new(0.0, -5.0);
     assert!(cmp_f32(v.sqr_length(), 25.0));
@@ -56,6 +56,17 @@ fn vec2_clamp() {
     assert_eq!(v, Vec2::new(1.6, 1.2));
 }
 
+#[test]
+fn vec2_snap() {
+    let mut v = Vec2::new(0.26, -0.74);
+    v.snap(0.25);
+    assert_eq!(v, Vec2::new(0.25, -0.75));
+
+    v = Vec2::new(0.13, 0.37);
+    assert_eq!(v.snapped(0.1), Vec2::new(0.1, 0.4));
+    assert_eq!(v.snapped(0.0), v);
+}
+
 #[test]
 fn vec2_slope() {
     let mut v = Vec2::new(4.0, 2.0);
@@ -132,10 +143,60 @@ fn segment2_degenerate() {
 fn segment2_bounds() {
     let s = Segment2::new(Vec2::new(-4.0, 0.0), Vec2::new(2.0, -1.0));
     let r = s.bounding_rect();
-    assert!(cmp_f32(r.left, -4.0));
-    assert!(cmp_f32(r.right, 2.0));
-    assert!(cmp_f32(r.top, -1.0));
-    assert!(cmp_f32(r.bottom, 0.0));
+    assert!(cmp_f32(r.min.x, -4.0));
+    assert!(cmp_f32(r.max.x, 2.0));
+    assert!(cmp_f32(r.min.y, -1.0));
+    assert!(cmp_f32(r.max.y, 0.0));
+}
+
+#[test]
+fn rect_operations() {
+    let a = Rect::new(Vec2::new(2.0, 1.0), Vec2::new(-2.0, -1.0));
+    assert_eq!(a.min, Vec2::new(-2.0, -1.0));
+    assert_eq!(a.max, Vec2::new(2.0, 1.0));
+    assert!(cmp_f32(a.width(), 4.0) && cmp_f32(a.height(), 2.0));
+    assert_eq!(a.center(), Vec2::new(0.0, 0.0));
+
+    let b = Rect::new(Vec2::new(1.0, 0.0), Vec2::new(3.0, 3.0));
+    assert_eq!(a.union(&b), Rect::new(Vec2::new(-2.0, -1.0), Vec2::new(3.0, 3.0)));
+    assert_eq!(a.intersection(&b), Some(Rect::new(Vec2::new(1.0, 0.0), Vec2::new(2.0, 1.0))));
+    assert_eq!(a.intersection(&Rect::new(Vec2::new(5.0, 5.0), Vec2::new(6.0, 6.0))), None);
+
+    assert!(a.contains(Vec2::new(2.0, -1.0)));
+    assert!(!a.contains(Vec2::new(0.0, 1.5)));
+    assert!(a.inflate(1.0).contains_rect(&a));
+    assert!(!a.contains_rect(&b));
+    assert_eq!(a.inflate(-2.0), Rect::new(Vec2::new(0.0, 0.0), Vec2::new(0.0, 0.0)));
+
+    assert_eq!(a.corners()[1], Vec2::new(2.0, -1.0));
+    assert!(cmp_f32(polygon::signed_area(&a.to_polygon()), a.area()));
+}
+
+#[test]
+fn segment2_closest_point() {
+    let s = Segment2::new(Vec2::new(0.0, 0.0), Vec2::new(4.0, 0.0));
+    assert_eq!(s.lerp(0.25), Vec2::new(1.0, 0.0));
+    assert!(cmp_f32(s.project(Vec2::new(6.0, 3.0)), 1.5));
+    assert_eq!(s.closest_point(Vec2::new(6.0, 3.0)), Vec2::new(4.0, 0.0));
+    assert_eq!(s.closest_point(Vec2::new(2.0, -1.0)), Vec2::new(2.0, 0.0));
+    assert!(cmp_f32(s.distance_to_point(Vec2::new(-3.0, 4.0)), 5.0));
+
+    let point = Segment2::new(Vec2::new(1.0, 1.0), Vec2::new(1.0, 1.0));
+    assert!(cmp_f32(point.distance_to_point(Vec2::new(1.0, 3.0)), 2.0));
+}
+
+#[test]
+fn segment2_parametric_intersection() {
+    let s1 = Segment2::new(Vec2::new(0.0, 0.0), Vec2::new(4.0, 0.0));
+    let s2 = Segment2::new(Vec2::new(1.0, -1.0), Vec2::new(1.0, 3.0));
+    let (t, u, p) = s1.parametric_intersection(&s2).unwrap();
+    assert!(cmp_f32(t, 0.25) && cmp_f32(u, 0.25));
+    assert_eq!(p, Vec2::new(1.0, 0.0));
+
+    let short = Segment2::new(Vec2::new(1.0, 1.0), Vec2::new(1.0, 3.0));
+    assert_eq!(s1.parametric_intersection(&short), None);
+    let parallel = Segment2::new(Vec2::new(0.0, 1.0), Vec2::new(4.0, 1.0));
+    assert_eq!(s1.parametric_intersection(&parallel), None);
 }
 
 #[test]
@@ -157,10 +218,79 @@ fn segment2_intersections() {
     assert!(s2.intersects(&s3));
     assert!(!s1.intersects(&s3));
 
-    assert_eq!(s1.intersection(&s2), Vec2::new(4.666666666, 0.333333333));
+    assert_eq!(s1.intersection(&s2), Vec2::new(4.666_666_5, 0.333_333_34));
     assert_eq!(s2.intersection(&s3), Vec2::new(3.0, 1.375));
 }
 
+#[test]
+#[allow(deprecated)]
+fn segment2_slope_versions() {
+    let s1 = Segment2::new(Vec2::new(8.0, 2.0), Vec2::new(4.0, 0.0));
+    let s2 = Segment2::new(Vec2::new(2.0, 2.0), Vec2::new(6.0, -0.5));
+    let s3 = Segment2::new(Vec2::new(3.0, 3.0), Vec2::new(3.0, -4.0));
+    assert!(s1.contains_by_slope(Vec2::new(6.0, 1.0)));
+    assert!(s1.contains_by_slope(Vec2::new(5.0, 0.5)));
+    assert!(!s1.contains_by_slope(Vec2::new(6.0, 0.6)));
+    assert!(s3.contains_by_slope(Vec2::new(3.0, 0.0)));
+    assert!(s1.intersects_by_slope(&s2));
+    assert!(s2.intersects_by_slope(&s3));
+    assert!(!s1.intersects_by_slope(&s3));
+
+    // Below the absolute tolerance, segments are still degenerate and contain nothing
+    let tiny = Segment2::new(Vec2::new(0.0, 0.0), Vec2::new(1e-6, 1e-6));
+    assert!(!tiny.contains_by_slope(Vec2::new(5e-7, 5e-7)));
+    assert!(!tiny.intersects_by_slope(&Segment2::new(Vec2::new(0.0, 1e-6), Vec2::new(1e-6, 0.0))));
+}
+
+#[test]
+fn segment2_vertical_and_small() {
+    let vertical = Segment2::new(Vec2::new(1.0, -1.0), Vec2::new(1.000001, 1.0));
+    assert!(vertical.contains(Vec2::new(1.0000005, 0.0)));
+    assert!(!vertical.contains(Vec2::new(1.0, 1.5)));
+    assert!(vertical.intersects(&Segment2::new(Vec2::new(0.0, 0.0), Vec2::new(2.0, 0.0))));
+
+    // Far below the absolute tolerance of the slopes
+    let tiny = Segment2::new(Vec2::new(0.0, 0.0), Vec2::new(1e-6, 1e-6));
+    assert!(tiny.contains(Vec2::new(5e-7, 5e-7)));
+    assert!(!tiny.contains(Vec2::new(5e-7, 0.0)));
+    assert!(tiny.intersects(&Segment2::new(Vec2::new(0.0, 1e-6), Vec2::new(1e-6, 0.0))));
+    assert!(!tiny.intersects(&Segment2::new(Vec2::new(2e-6, 0.0), Vec2::new(2e-6, 1e-6))));
+}
+
+#[test]
+fn segment2_ray_intersection() {
+    let s = Segment2::new(Vec2::new(1.0, -1.0), Vec2::new(1.0, 1.0));
+    assert_eq!(s.ray_intersection(Vec2::new(0.0, 0.5), Vec2::new(2.0, 0.0)), Some(0.5));
+    assert_eq!(s.ray_intersection(Vec2::new(0.0, 0.5), Vec2::new(-1.0, 0.0)), None);
+    assert_eq!(s.ray_intersection(Vec2::new(0.0, 2.0), Vec2::new(1.0, 0.0)), None);
+    assert_eq!(s.ray_intersection(Vec2::new(0.0, 0.0), Vec2::new(0.0, 1.0)), None);
+}
+
+#[test]
+fn ray2_intersections() {
+    let ray = Ray2::through(Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.0));
+    assert_eq!(ray.at(2.0), Vec2::new(2.0, 0.0));
+    assert_eq!(ray.intersect_segment(&Segment2::new(Vec2::new(3.0, -1.0), Vec2::new(3.0, 1.0))), Some(3.0));
+    assert_eq!(ray.intersect_segment(&Segment2::new(Vec2::new(-3.0, -1.0), Vec2::new(-3.0, 1.0))), None);
+
+    assert_eq!(ray.intersect_circle(&Circle::new(Vec2::new(4.0, 0.0), 1.0)), Some(3.0));
+    assert_eq!(ray.intersect_circle(&Circle::new(Vec2::new(0.0, 0.0), 2.0)), Some(2.0));
+    assert_eq!(ray.intersect_circle(&Circle::new(Vec2::new(4.0, 2.0), 1.0)), None);
+
+    let rect = Rect::new(Vec2::new(1.0, -1.0), Vec2::new(2.0, 1.0));
+    assert_eq!(ray.intersect_rect(&rect), Some(1.0));
+    assert_eq!(Ray2::new(Vec2::new(1.5, 0.0), Vec2::new(0.0, 2.0)).intersect_rect(&rect), Some(0.5));
+    assert_eq!(ray.intersect_polygon(&[ Vec2::new(-1.0, 1.0), Vec2::new(-2.0, 0.0), Vec2::new(-1.0, -1.0) ]), None);
+}
+
+#[test]
+fn segment2_collinear_overlap() {
+    let inner = Segment2::new(Vec2::new(0.0, 0.125), Vec2::new(-0.0625, 0.125));
+    let outer = Segment2::new(Vec2::new(-0.125, 0.125), Vec2::new(0.125, 0.125));
+    assert!(inner.intersects(&outer));
+    assert!(outer.intersects(&inner));
+}
+
 #[test]
 fn segment2_y_intercept() {
     let s = Segment2::new(Vec2::new(3.0, 3.0), Vec2::new(3.0, -4.0));
@@ -169,3 +299,339 @@ fn segment2_y_intercept() {
     let s = Segment2::new(Vec2::new(8.0, 2.0), Vec2::new(4.0, 0.0));
     assert!(cmp_f32(s.y_intercept(), -2.0));
 }
+
+#[test]
+fn circle_circumscribed() {
+    let c = Circle::circumscribed(Vec2::new(1.0, 1.0), Vec2::new(3.0, 1.0), Vec2::new(1.0, 3.0)).unwrap();
+    assert_eq!(c.center, Vec2::new(2.0, 2.0));
+    assert!(cmp_f32(c.radius, core::f32::consts::SQRT_2));
+    assert!(c.contains(Vec2::new(3.0, 3.0)));
+    assert!(!c.contains(Vec2::new(3.5, 2.0)));
+
+    assert!(Circle::circumscribed(Vec2::new(0.0, 0.0), Vec2::new(1.0, 1.0), Vec2::new(2.0, 2.0)).is_none());
+    assert_eq!(Circle::diametral(Vec2::new(-1.0, 0.0), Vec2::new(1.0, 0.0)), Circle::new(Vec2::new(0.0, 0.0), 1.0));
+}
+
+#[test]
+fn polygon_measures() {
+    // L shape, clockwise
+    let l = vec![
+        Vec2::new(0.0, 0.0), Vec2::new(0.0, 2.0), Vec2::new(1.0, 2.0),
+        Vec2::new(1.0, 1.0), Vec2::new(2.0, 1.0), Vec2::new(2.0, 0.0),
+    ];
+    assert!(cmp_f32(polygon::signed_area(&l), -3.0));
+    let centroid = polygon::centroid(&l).unwrap();
+    assert!(cmp_f32(centroid.x, 5.0 / 6.0) && cmp_f32(centroid.y, 5.0 / 6.0));
+    assert_eq!(polygon::bounding_rect(&l), Some(Rect::new(Vec2::new(0.0, 0.0), Vec2::new(2.0, 2.0))));
+
+    assert!(polygon::contains(&l, Vec2::new(0.5, 1.5)));
+    assert!(!polygon::contains(&l, Vec2::new(1.5, 1.5)));
+    assert!(cmp_f32(polygon::signed_distance(&l, Vec2::new(0.5, 0.25)), 0.25));
+    assert!(cmp_f32(polygon::signed_distance(&l, Vec2::new(1.5, 1.5)), -0.5));
+    assert!(cmp_f32(polygon::signed_distance(&l, Vec2::new(3.0, 2.0)), -2.0_f32.sqrt()));
+
+    assert_eq!(polygon::centroid(&[]), None);
+    assert_eq!(polygon::edges(&l).count(), 6);
+}
+
+#[test]
+fn polygon_convex_overlap() {
+    let square = vec![ Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.0), Vec2::new(1.0, 1.0), Vec2::new(0.0, 1.0) ];
+    let triangle = |dx: f32| vec![ Vec2::new(1.2 + dx, 0.0), Vec2::new(2.0 + dx, 0.0), Vec2::new(1.2 + dx, 0.8) ];
+
+    // The boxes of the diamond and the square overlap, but a diagonal separates them
+    let diamond = vec![ Vec2::new(1.5, 0.8), Vec2::new(1.8, 1.1), Vec2::new(1.5, 1.4), Vec2::new(0.9, 1.6) ];
+    assert!(!polygon::convex_overlap(&square, &diamond));
+    assert!(!polygon::convex_overlap(&square, &triangle(0.0)));
+    assert!(polygon::convex_overlap(&triangle(-0.5), &square));
+
+    // Clockwise polygons, and polygons sharing an edge
+    let reversed = square.iter().rev().cloned().collect::<Vec<_>>();
+    assert!(polygon::convex_overlap(&reversed, &triangle(-0.5)));
+    assert!(polygon::convex_overlap(&square, &triangle(-0.2)));
+    assert!(!polygon::convex_overlap(&square, &[]));
+}
+
+#[test]
+fn polyline_resample() {
+    let open = vec![ Vec2::new(0.0, 0.0), Vec2::new(2.0, 0.0), Vec2::new(2.0, 1.0) ];
+    assert_eq!(polyline::edges(&open, false).count(), 2);
+    assert!(cmp_f32(polyline::length(&open, false), 3.0));
+    assert_eq!(polyline::resample(&open, false, 4), vec![ Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.0), Vec2::new(2.0, 0.0), Vec2::new(2.0, 1.0) ]);
+    assert_eq!(polyline::point_at(&open, false, -1.0), Some(Vec2::new(0.0, 0.0)));
+    assert_eq!(polyline::point_at(&open, false, 10.0), Some(Vec2::new(2.0, 1.0)));
+
+    // The closing edge counts, the distance wraps around
+    let square = vec![ Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.0), Vec2::new(1.0, 1.0), Vec2::new(0.0, 1.0) ];
+    assert_eq!(polyline::edges(&square, true).count(), 4);
+    assert!(cmp_f32(polyline::length(&square, true), 4.0));
+    assert_eq!(polyline::point_at(&square, true, 5.5), Some(Vec2::new(1.0, 0.5)));
+    assert_eq!(polyline::point_at(&square, true, -0.5), Some(Vec2::new(0.0, 0.5)));
+    let samples = polyline::resample(&square, true, 8);
+    assert_eq!(samples.len(), 8);
+    assert_eq!(samples[1], Vec2::new(0.5, 0.0));
+    assert_eq!(samples[7], Vec2::new(0.0, 0.5));
+
+    assert!(polyline::resample(&[], true, 3).is_empty());
+    assert_eq!(polyline::point_at(&[], false, 0.0), None);
+    assert_eq!(polyline::resample(&[ Vec2::new(1.0, 1.0) ], true, 3), vec![ Vec2::new(1.0, 1.0); 3 ]);
+}
+
+#[test]
+fn polyline_type() {
+    let zigzag = Polyline::open(vec![
+        Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.01), Vec2::new(2.0, 0.0), Vec2::new(3.0, 1.0), Vec2::new(4.0, 1.0),
+    ]);
+    assert!(cmp_f32(zigzag.point_at_parameter(0.0).unwrap().x, 0.0));
+    assert_eq!(zigzag.point_at_parameter(1.0), Some(Vec2::new(4.0, 1.0)));
+    assert_eq!(zigzag.resampled(5).points.len(), 5);
+    assert!(cmp_f32(zigzag.resampled(2).length(), 17.0_f32.sqrt()));
+
+    // The bump under the tolerance is removed, the ends and the corners are kept
+    let simplified = zigzag.simplified(0.05);
+    assert_eq!(simplified.points, vec![ Vec2::new(0.0, 0.0), Vec2::new(2.0, 0.0), Vec2::new(3.0, 1.0), Vec2::new(4.0, 1.0) ]);
+    assert_eq!(zigzag.simplified(0.0).points.len(), 5);
+
+    let square = Polyline::new(vec![
+        Vec2::new(0.0, 0.0), Vec2::new(0.5, 0.0), Vec2::new(1.0, 0.0), Vec2::new(1.0, 1.0), Vec2::new(0.0, 1.0),
+    ], true);
+    let simplified = square.simplified(0.01);
+    assert!(simplified.closed);
+    assert_eq!(simplified.points.len(), 4);
+    assert!(cmp_f32(simplified.length(), 4.0));
+}
+
+#[test]
+fn bezier_path() {
+    let curve = CubicBezier::new(Vec2::new(0.0, 0.0), Vec2::new(0.0, 1.0), Vec2::new(1.0, 1.0), Vec2::new(1.0, 0.0));
+    assert_eq!(curve.point(0.0), Vec2::new(0.0, 0.0));
+    assert_eq!(curve.point(1.0), Vec2::new(1.0, 0.0));
+    assert_eq!(curve.point(0.5), Vec2::new(0.5, 0.75));
+    assert_eq!(curve.derivative(0.0), Vec2::new(0.0, 3.0));
+    assert_eq!(curve.derivative(0.5), Vec2::new(1.5, 0.0));
+    assert_eq!(curve.flatten(4).len(), 5);
+
+    // The points after the last complete curve are ignored
+    let points = (0..8).map(|i| Vec2::new(i as f32, (i % 3) as f32)).collect::<Vec<_>>();
+    assert_eq!(bezier::curves(&points, false).len(), 2);
+    assert_eq!(bezier::flatten_path(&points, false, 4).len(), 9);
+    assert_eq!(bezier::curves(&points[..6], true).len(), 2);
+    assert_eq!(bezier::curves(&points[..6], true)[1].points[3], points[0]);
+    assert_eq!(bezier::flatten_path(&points[..6], true, 4).len(), 8);
+    assert_eq!(bezier::curves(&points[..5], true).len(), 1);
+    assert!(bezier::curves(&points[..3], false).is_empty());
+}
+
+#[test]
+fn bezier_arc_length() {
+    // The parameter runs fast at the start of this straight curve and slow at its end
+    let curve = CubicBezier::new(Vec2::new(0.0, 0.0), Vec2::new(0.8, 0.0), Vec2::new(0.9, 0.0), Vec2::new(1.0, 0.0));
+    let table = ArcLengthTable::new(vec![ curve ], 64);
+    assert!((table.length() - 1.0).abs() < 1e-5);
+    let (index, t) = table.parameter(0.5).unwrap();
+    assert_eq!(index, 0);
+    assert!((curve.point(t).x - 0.5).abs() < 1e-3);
+    assert_eq!(table.point_at(2.0), Some(Vec2::new(1.0, 0.0)));
+    let samples = table.resample(false, 5);
+    assert_eq!(samples.len(), 5);
+    for (i, p) in samples.iter().enumerate() {
+        assert!((p.x - i as f32 / 4.0).abs() < 1e-3);
+    }
+
+    // Circle of radius 1 from 4 quarters
+    let k = 0.552_284_8;
+    let control_points = [
+        (1.0, 0.0), (1.0, k), (k, 1.0),
+        (0.0, 1.0), (-k, 1.0), (-1.0, k),
+        (-1.0, 0.0), (-1.0, -k), (-k, -1.0),
+        (0.0, -1.0), (k, -1.0), (1.0, -k),
+    ].iter().map(|&(x, y)| Vec2::new(x, y)).collect::<Vec<_>>();
+    let table = ArcLengthTable::new(bezier::curves(&control_points, true), 64);
+    assert!((table.length() - 2.0 * core::f32::consts::PI).abs() < 1e-2);
+    let samples = bezier::resample_path(&control_points, true, 12);
+    assert_eq!(samples.len(), 12);
+    let chord = (&samples[1] - &samples[0]).length();
+    for i in 0..12 {
+        assert!(((&samples[(i + 1) % 12] - &samples[i]).length() - chord).abs() < 1e-3);
+    }
+    assert!(bezier::resample_path(&control_points[..2], false, 12).is_empty());
+}
+
+#[test]
+fn arc_intersections() {
+    let circle = Circle::new(Vec2::new(0.0, 0.0), 1.0);
+    let other = Circle::new(Vec2::new(1.0, 0.0), 1.0);
+    let points = circle.intersections(&other);
+    assert_eq!(points.len(), 2);
+    assert!(points.contains(&Vec2::new(0.5, 0.866_025_4)) && points.contains(&Vec2::new(0.5, -0.866_025_4)));
+    assert_eq!(circle.intersections(&Circle::new(Vec2::new(2.0, 0.0), 1.0)), vec![ Vec2::new(1.0, 0.0) ]);
+    assert!(circle.intersections(&Circle::new(Vec2::new(3.0, 0.0), 1.0)).is_empty());
+    assert!(circle.intersections(&Circle::new(Vec2::new(0.1, 0.0), 0.5)).is_empty());
+
+    let segment = Segment2::new(Vec2::new(-2.0, 0.5), Vec2::new(2.0, 0.5));
+    assert_eq!(circle.segment_intersections(&segment), vec![ Vec2::new(-0.866_025_4, 0.5), Vec2::new(0.866_025_4, 0.5) ]);
+    assert_eq!(circle.segment_intersections(&Segment2::new(Vec2::new(0.0, 0.0), Vec2::new(0.0, 2.0))), vec![ Vec2::new(0.0, 1.0) ]);
+
+    // Upper half of the circle, counter-clockwise and then clockwise
+    let upper = Arc::new(circle, 0.0, core::f32::consts::PI);
+    assert_eq!(upper.segment_intersections(&segment).len(), 2);
+    assert!(upper.segment_intersections(&Segment2::new(Vec2::new(-2.0, -0.5), Vec2::new(2.0, -0.5))).is_empty());
+    let through = Arc::through(Vec2::new(-1.0, 0.0), Vec2::new(0.0, 1.0), Vec2::new(1.0, 0.0)).unwrap();
+    assert!(through.sweep < 0.0);
+    assert_eq!(through.point(0.5), Vec2::new(0.0, 1.0));
+    assert!((through.length() - core::f32::consts::PI).abs() < 1e-5);
+    assert_eq!(upper.intersections(&Arc::new(other, 0.0, 2.0 * core::f32::consts::PI)), vec![ Vec2::new(0.5, 0.866_025_4) ]);
+
+    // Overlapping arcs of the same circle meet at the ends inside the other one
+    let right = Arc::new(circle, -core::f32::consts::FRAC_PI_2, core::f32::consts::PI);
+    let ends = upper.intersections(&right);
+    assert_eq!(ends.len(), 2);
+    assert!(ends.contains(&Vec2::new(1.0, 0.0)) && ends.contains(&Vec2::new(0.0, 1.0)));
+}
+
+#[test]
+fn duality_transform() {
+    let (p, q) = (Vec2::new(1.0, 2.0), Vec2::new(-1.0, 0.5));
+    let line = duality::dual_line(p);
+    assert_eq!(line, Line::new(1.0, -2.0));
+    assert_eq!(duality::dual_point(&line), p);
+
+    // Incidence: the line through p and q is dual to the intersection of their dual lines
+    let through = Line::through(p, q).unwrap();
+    let intersection = line.intersection(&duality::dual_line(q)).unwrap();
+    assert!(cmp_f32(intersection.x, duality::dual_point(&through).x));
+    assert!(cmp_f32(intersection.y, duality::dual_point(&through).y));
+
+    // Order: a point above a line is dual to a line above the dual point
+    let r = Vec2::new(0.0, 3.0);
+    assert!(through.height_of(r) > 0.0);
+    assert!(duality::dual_line(r).height_of(duality::dual_point(&through)) > 0.0);
+
+    assert!(Line::through(p, Vec2::new(1.0, 5.0)).is_none());
+    assert!(line.intersection(&Line::new(1.0, 3.0)).is_none());
+    assert_eq!(line.segment(-1.0, 1.0), Segment2::new(Vec2::new(-1.0, -3.0), Vec2::new(1.0, -1.0)));
+}
+
+#[test]
+fn pca_2d() {
+    // Points along a line of slope 1/2, spread a little across it
+    let direction = Vec2::new(2.0, 1.0).normalized();
+    let normal = Vec2::new(-direction.y, direction.x);
+    let points = (0..40)
+        .map(|i| {
+            let along = (i / 2) as f32 - 9.5;
+            let across = if i % 2 == 0 { 0.5 } else { -0.5 };
+            &Vec2::new(3.0, -1.0) + &(&(along * &direction) + &(across * &normal))
+        })
+        .collect::<Vec<_>>();
+    let pca = pca::principal_axes(&points).unwrap();
+    assert!(cmp_f32(pca.centroid.x, 3.0) && cmp_f32(pca.centroid.y, -1.0));
+    assert!(cmp_f32(pca.axes[0].dot(direction).abs(), 1.0));
+    assert!(cmp_f32(pca.axes[0].dot(pca.axes[1]), 0.0));
+    assert!(float::abs(pca.variances[0] - 33.25) < 1e-3);
+    assert!(cmp_f32(pca.variances[1], 0.25));
+
+    // Once aligned, the points spread along x
+    let aligned = points.iter().map(|&p| pca.align(p)).collect::<Vec<_>>();
+    let pca = pca::principal_axes(&aligned).unwrap();
+    assert!(cmp_f32(pca.axes[0].x.abs(), 1.0));
+
+    assert!(pca::principal_axes(&[]).is_none());
+    assert_eq!(pca::principal_axes(&[ Vec2::new(1.0, 2.0) ]).unwrap().variances, [ 0.0, 0.0 ]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn pca_3d() {
+    use cgmath::InnerSpace;
+
+    // Box of sides 6, 2 and 4 along rotated axes
+    let axes = [ Vec3::new(0.6, 0.8, 0.0), Vec3::new(0.0, 0.0, 1.0), Vec3::new(0.8, -0.6, 0.0) ];
+    let mut points = Vec::new();
+    for &(x, y, z) in &[ (3.0, 2.0, 1.0), (-3.0, 2.0, 1.0), (3.0, -2.0, 1.0), (-3.0, -2.0, 1.0),
+                         (3.0, 2.0, -1.0), (-3.0, 2.0, -1.0), (3.0, -2.0, -1.0), (-3.0, -2.0, -1.0) ] {
+        points.push(Vec3(axes[0].0 * x + axes[1].0 * y + axes[2].0 * z + cgmath::vec3(1.0, 2.0, 3.0)));
+    }
+    let pca = pca::principal_axes_3d(&points).unwrap();
+    assert!((pca.centroid.0 - cgmath::vec3(1.0, 2.0, 3.0)).magnitude() < 1e-5);
+    for (i, &expected) in [ 9.0, 4.0, 1.0 ].iter().enumerate() {
+        assert!(float::abs(pca.variances[i] - expected) < 1e-4);
+        assert!(cmp_f32(pca.axes[i].0.dot(axes[i].0).abs(), 1.0));
+    }
+    assert!(cmp_f32(pca.axes[0].0.cross(pca.axes[1].0).dot(pca.axes[2].0), 1.0));
+
+    let aligned = pca.align(points[0]);
+    assert!(float::abs((aligned.0 - pca.centroid.0).magnitude() - 14.0_f32.sqrt()) < 1e-5);
+}
+
+#[test]
+fn morton_codes() {
+    assert_eq!(morton::encode2(0b11, 0b00), 0b0101);
+    assert_eq!(morton::encode2(0b00, 0b11), 0b1010);
+    assert_eq!(morton::decode2(morton::encode2(12345, 54321)), (12345, 54321));
+    assert_eq!(morton::encode3(1, 1, 1), 0b111);
+    assert_eq!(morton::encode3(0b10, 0, 0), 0b1000);
+
+    // The quadrants are visited in Z order: bottom left, bottom right, top left, top right
+    let points = vec![ Vec2::new(1.0, 1.0), Vec2::new(-1.0, 1.0), Vec2::new(1.0, -1.0), Vec2::new(-1.0, -1.0) ];
+    assert_eq!(morton::order2(&points), vec![ 3, 2, 1, 0 ]);
+    let mut sorted = points.clone();
+    morton::sort2(&mut sorted);
+    assert_eq!(sorted[0], Vec2::new(-1.0, -1.0));
+    assert!(morton::order2(&[]).is_empty());
+}
+
+#[test]
+fn best_fit_rigid_transform() {
+    let src = [ Vec2::new(0.0, 0.0), Vec2::new(2.0, 0.0), Vec2::new(2.0, 1.0), Vec2::new(-1.0, 3.0) ];
+    let transform = Transform2::new(0.7, Vec2::new(1.5, -2.0));
+    let dst = src.iter().map(|&p| transform.apply(p)).collect::<Vec<_>>();
+
+    let fit = best_fit_transform(&src, &dst);
+    assert!(float::abs(fit.angle - 0.7) < 1e-5);
+    assert!((&fit.translation - &transform.translation).length() < 1e-4);
+
+    // Composing with the inverse gives back the points
+    let back = fit.then(&fit.inverse());
+    assert!(src.iter().all(|&p| (&back.apply(p) - &p).length() < 1e-4));
+
+    assert_eq!(best_fit_transform(&[], &dst), Transform2::identity());
+}
+
+#[test]
+fn polar_order() {
+    use core::cmp::Ordering;
+
+    // Counter-clockwise from the x axis, the closer point first in each direction, the center before everything
+    let center = Vec2::new(1.0, 1.0);
+    let points = vec![
+        Vec2::new(1.0, 0.0), Vec2::new(3.0, 1.0), Vec2::new(0.0, 1.0), Vec2::new(2.0, 1.0),
+        Vec2::new(1.0, 2.0), Vec2::new(1.0, 1.0), Vec2::new(0.0, 0.0), Vec2::new(2.0, 0.0),
+    ];
+    assert_eq!(polar::order(center, &points), vec![ 5, 3, 1, 4, 2, 6, 0, 7 ]);
+    let mut sorted = points.clone();
+    polar::sort(center, &mut sorted);
+    assert_eq!(sorted[1], Vec2::new(2.0, 1.0));
+
+    // Directions an atan2 in single precision cannot tell apart
+    let (a, b) = (Vec2::new(1.0, 0.3), Vec2::new(10.0, 3.0000002));
+    assert_eq!(a.y.atan2(a.x), b.y.atan2(b.x));
+    assert_eq!(polar::compare(Vec2::new(0.0, 0.0), a, b), Ordering::Less);
+    assert_eq!(polar::compare(Vec2::new(0.0, 0.0), b, a), Ordering::Greater);
+}
+
+#[test]
+fn xorshift() {
+    let mut random = XorShift::default();
+    let first = random.next_u64();
+    assert_eq!(XorShift::new(0x2545_F491_4F6C_DD1D).next_u64(), first);
+    assert_eq!(XorShift::new(0).next_u64(), first);
+    assert!((0..1000).map(|_| random.next_f64()).all(|x| (0.0..1.0).contains(&x)));
+    assert!((0..1000).all(|_| random.below(3) < 3));
+
+    let mut items = (0..100).collect::<Vec<_>>();
+    XorShift::default().shuffle(&mut items);
+    assert_ne!(items, (0..100).collect::<Vec<_>>());
+    items.sort_unstable();
+    assert_eq!(items, (0..100).collect::<Vec<_>>());
+}
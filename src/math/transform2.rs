@@ -0,0 +1,76 @@
+//! Rigid transforms of the plane, and the one that best aligns two sets of matching points.
+
+use super::{ Vec2, float };
+
+/// Rotation around the origin followed by a translation.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Transform2 {
+    /// Counter-clockwise, in radians
+    pub angle: f32,
+    pub translation: Vec2,
+}
+
+impl Default for Transform2 {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Transform2 {
+    pub fn new(angle: f32, translation: Vec2) -> Self {
+        Self {
+            angle,
+            translation,
+        }
+    }
+
+    pub fn identity() -> Self {
+        Self::new(0.0, Vec2::new(0.0, 0.0))
+    }
+
+    /// Rotates `p` around the origin, without the translation
+    pub fn rotate(&self, p: Vec2) -> Vec2 {
+        let (sin, cos) = (float::sin(self.angle), float::cos(self.angle));
+        Vec2::new(cos * p.x - sin * p.y, sin * p.x + cos * p.y)
+    }
+
+    pub fn apply(&self, p: Vec2) -> Vec2 {
+        &self.rotate(p) + &self.translation
+    }
+
+    /// Returns the transform that applies `self`, then `other`
+    pub fn then(&self, other: &Self) -> Self {
+        Self::new(self.angle + other.angle, other.apply(self.translation))
+    }
+
+    pub fn inverse(&self) -> Self {
+        let rotation = Self::new(-self.angle, Vec2::new(0.0, 0.0));
+        Self::new(-self.angle, -&rotation.rotate(self.translation))
+    }
+}
+
+/// Returns the rigid transform that moves the points of `src` the closest to the points of `dst` at the same indices,
+/// in the least squares sense (the 2D case of the Kabsch algorithm). Extra points of the longer set are ignored,
+/// and the identity is returned when there is no pair.
+/// The translation matches the centroids, and the angle maximizes the sum of the dot products of the centered pairs.
+pub fn best_fit_transform(src: &[Vec2], dst: &[Vec2]) -> Transform2 {
+    let n = src.len().min(dst.len());
+    if n == 0 {
+        return Transform2::identity();
+    }
+    let (src, dst) = (&src[..n], &dst[..n]);
+    let centroid = |points: &[Vec2]| {
+        let sum = points.iter().fold(Vec2::new(0.0, 0.0), |sum, p| &sum + p);
+        &sum * (1.0 / n as f32)
+    };
+    let (src_center, dst_center) = (centroid(src), centroid(dst));
+
+    let (mut dot, mut cross) = (0.0, 0.0);
+    for (p, q) in src.iter().zip(dst) {
+        let (p, q) = (p - &src_center, q - &dst_center);
+        dot += p.dot(q);
+        cross += p.x * q.y - p.y * q.x;
+    }
+    let rotation = Transform2::new(float::atan2(cross, dot), Vec2::new(0.0, 0.0));
+    Transform2::new(rotation.angle, &dst_center - &rotation.rotate(src_center))
+}
@@ -1,8 +1,10 @@
-use std::ops::{ Mul, MulAssign, Div, DivAssign, Add, AddAssign, Sub, SubAssign, Neg };
+use core::ops::{ Mul, MulAssign, Div, DivAssign, Add, AddAssign, Sub, SubAssign, Neg };
 
-use super::cmp_f32;
+use super::{ cmp_f32, float };
 
+#[cfg(feature = "std")]
 use rand::Rng;
+#[cfg(feature = "gui")]
 use glium::vertex;
 
 #[derive(Copy, Clone, Debug)]
@@ -17,6 +19,7 @@ impl Default for Vec2 {
     }
 }
 
+#[cfg(feature = "gui")]
 unsafe impl vertex::Attribute for Vec2 {
     fn get_type() -> vertex::AttributeType {
         vertex::AttributeType::F32F32
@@ -29,8 +32,8 @@ impl Vec2 {
     }
 
     pub fn is_zero(self) -> bool {
-        self.x.abs() < 0.00001 &&
-        self.y.abs() < 0.00001
+        float::abs(self.x) < 0.00001 &&
+        float::abs(self.y) < 0.00001
     }
 
     const ZERO: Self = Self { x: 0.0, y: 0.0 };
@@ -39,12 +42,12 @@ impl Vec2 {
 impl Vec2 {
     /// Returns the length of the vector
     pub fn length(self) -> f32 {
-        self.sqr_length().sqrt()
+        float::sqrt(self.sqr_length())
     }
 
     /// Returns the squared length (faster than [`length()`](#method.length)) of the vector
     pub fn sqr_length(self) -> f32 {
-        self.x.powi(2) + self.y.powi(2)
+        self.x * self.x + self.y * self.y
     }
 
     /// Sets the length of the vector to 1 but with the same direction
@@ -65,7 +68,7 @@ impl Vec2 {
 
     /// Modifies the vector so that the direction stays the same but the magnitude does not exceed `max_length`
     pub fn clamp(&mut self, max_length: f32) -> &mut Self {
-        if self.sqr_length() > max_length.powi(2) {
+        if self.sqr_length() > max_length * max_length {
             self.normalize();
             *self *= max_length;
         }
@@ -78,6 +81,21 @@ impl Vec2 {
         self
     }
 
+    /// Rounds `x` and `y` to the nearest multiple of `spacing`
+    pub fn snap(&mut self, spacing: f32) -> &mut Self {
+        if spacing > 0.0 {
+            self.x = float::round(self.x / spacing) * spacing;
+            self.y = float::round(self.y / spacing) * spacing;
+        }
+        self
+    }
+
+    /// Returns a [`snap()`](#method.snap)ped version of the vector
+    pub fn snapped(mut self, spacing: f32) -> Self {
+        self.snap(spacing);
+        self
+    }
+
     /// Ratio of the vertical change to the horizontal change between two points on the line described by the vector
     pub fn slope(self) -> f32 {
         self.y / self.x
@@ -107,6 +125,7 @@ impl Vec2 {
     }
 
     /// Sets random values for `x` and `y`
+    #[cfg(feature = "std")]
     pub fn randomize(&mut self) -> &mut Self {
         let mut rng = rand::thread_rng();
         self.x = rng.gen();
@@ -115,6 +134,7 @@ impl Vec2 {
     }
 
     /// Sets random values for `x` between `x_min` and `x_max` and random values for `y` between `y_min` and `y_max`
+    #[cfg(feature = "std")]
     pub fn randomize_range(&mut self, x_min: f32, x_max: f32, y_min: f32, y_max: f32) -> &mut Self {
         let mut rng = rand::thread_rng();
         self.x = rng.gen_range(x_min, x_max);
@@ -123,6 +143,7 @@ impl Vec2 {
     }
 
     /// Returns a vector with random values for `x` and `y`
+    #[cfg(feature = "std")]
     pub fn random() -> Self {
         let mut v = Self::default();
         v.randomize();
@@ -130,6 +151,7 @@ impl Vec2 {
     }
 
     /// Returns a vector with random values for `x` between `x_min` and `x_max` and random values for `y` between `y_min` and `y_max`
+    #[cfg(feature = "std")]
     pub fn random_range(x_min: f32, x_max: f32, y_min: f32, y_max: f32) -> Self {
         let mut v = Self::default();
         v.randomize_range(x_min, x_max, y_min, y_max);
@@ -143,15 +165,22 @@ impl Vec2 {
 
     /// Returns the signed angle in radians between -pi and pi.
     pub fn signed_angle(self, other: Self) -> f32 {
-        (self.x * other.y - self.y * other.x).atan2(self.dot(other))
+        float::atan2(self.x * other.y - self.y * other.x, self.dot(other))
     }
 
     pub fn cw(a: Self, b: Self, c: Self) -> bool {
-        Self::shoelace(a, b, c) < 0.0
+        Self::orientation(a, b, c) < 0.0
     }
 
     pub fn ccw(a: Self, b: Self, c: Self) -> bool {
-        Self::shoelace(a, b, c) > 0.0
+        Self::orientation(a, b, c) > 0.0
+    }
+
+    /// Returns twice the signed area of the triangle (a, b, c), positive if it is counter-clockwise.
+    /// It is computed in f64 relative to `a`, so that its sign stays right for nearly collinear points.
+    pub fn orientation(a: Self, b: Self, c: Self) -> f64 {
+        let (ax, ay) = (a.x as f64, a.y as f64);
+        (b.x as f64 - ax) * (c.y as f64 - ay) - (c.x as f64 - ax) * (b.y as f64 - ay)
     }
 
     pub fn shoelace(a: Self, b: Self, c: Self) -> f32 {
@@ -295,9 +324,9 @@ impl From<&[f32; 2]> for Vec2 {
     }
 }
 
-impl Into<[f32; 2]> for &Vec2 {
-    fn into(self) -> [f32; 2] {
-        [ self.x, self.y ]
+impl From<&Vec2> for [f32; 2] {
+    fn from(val: &Vec2) -> Self {
+        [ val.x, val.y ]
     }
 }
 
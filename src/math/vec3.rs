@@ -1,14 +1,13 @@
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, PartialEq, Debug)]
 pub struct Vec3(pub cgmath::Vector3::<f32>);
 
 impl Vec3 {
     pub fn new(x: f32, y: f32, z: f32) -> Self {
-        Self {
-            0: cgmath::vec3(x, y, z)
-        }
+        Self(cgmath::vec3(x, y, z))
     }
 }
 
+#[cfg(feature = "gui")]
 unsafe impl glium::vertex::Attribute for Vec3 {
     fn get_type() -> glium::vertex::AttributeType {
         glium::vertex::AttributeType::F32F32F32
@@ -22,8 +21,8 @@ impl super::ToArray for Vec3 {
     }
 }
 
-impl Into<Vec3> for cgmath::Vector3<f32> {
-    fn into(self) -> Vec3 {
-        Vec3::new(self.x, self.y, self.z)
+impl From<cgmath::Vector3<f32>> for Vec3 {
+    fn from(val: cgmath::Vector3<f32>) -> Self {
+        Vec3::new(val.x, val.y, val.z)
     }
 }
@@ -0,0 +1,293 @@
+//! Large 3D point clouds: reading LAS and XYZ files and decimating them
+//! to a number of points the 3D algorithms can process interactively, and generating random ones.
+
+use crate::{ Error, Result, math::Vec3 };
+
+use rand::Rng;
+
+use std::{
+    collections::{ HashMap, HashSet },
+    fs::File,
+    io::{ self, BufRead, BufReader, Read },
+    path::Path,
+};
+
+/// Reads a point cloud, the format is chosen from the extension of `path`:
+/// `.las` for LAS files, `.xyz`, `.txt` and `.pts` for text files of `x y z` lines.
+pub fn read(path: &Path) -> Result<Vec<Vec3>> {
+    let extension = path.extension()
+                        .and_then(|ext| ext.to_str())
+                        .unwrap_or("")
+                        .to_ascii_lowercase();
+    let reader = BufReader::new(File::open(path)?);
+    match extension.as_str() {
+        "las" | "laz" => read_las(reader),
+        "xyz" | "txt" | "pts" => read_xyz(reader),
+        _ => Err(Error::UnsupportedFormat(extension)),
+    }
+}
+
+/// Reads points from lines of `x y z` coordinates separated by spaces, tabs or commas.
+/// Additional columns (colors, intensities, normals...) are ignored.
+/// Empty lines, lines starting with `#` and a non numeric header line are skipped, NaN and infinite coordinates are rejected.
+pub fn read_xyz<R: BufRead>(reader: R) -> Result<Vec<Vec3>> {
+    let mut points = Vec::new();
+    for (line_idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let columns = line.split(|c: char| c == ',' || c.is_whitespace())
+                        .filter(|c| !c.is_empty())
+                        .take(3)
+                        .collect::<Vec<_>>();
+        let coords = columns.iter()
+                        .map(|c| c.parse::<f32>())
+                        .collect::<std::result::Result<Vec<_>, _>>();
+        match coords {
+            Ok(ref coords) if coords.len() == 3 => {
+                if let Some(column) = coords.iter().position(|c| !c.is_finite()) {
+                    return Err(Error::NotFinite { line: line_idx + 1, column: column + 1, content: columns[column].to_owned() });
+                }
+                points.push(Vec3::new(coords[0], coords[1], coords[2]));
+            },
+            Err(_) if line_idx == 0 => continue, // Header
+            _ => return Err(Error::Parse { line: line_idx + 1, content: line.to_owned(), expected: "x y z" }),
+        }
+    }
+    Ok(points)
+}
+
+/// Reads the points of an uncompressed LAS file, versions 1.0 to 1.4.
+///
+/// The coordinates are returned relative to the center of the bounding box of the header:
+/// georeferenced coordinates are too large to be stored in `f32` without losing precision.
+/// Compressed LAZ files are rejected, they can be decompressed with `laszip` first.
+pub fn read_las<R: Read>(mut reader: R) -> Result<Vec<Vec3>> {
+    let invalid = |msg: &str| Error::Las(msg.to_owned());
+
+    // Fields shared by all the versions, the 1.4 header adds 64 bit point counts after them
+    let mut header = [0_u8; 227];
+    reader.read_exact(&mut header).map_err(|_| invalid("truncated header"))?;
+    if &header[0..4] != b"LASF" {
+        return Err(invalid("missing LASF signature"));
+    }
+    let u16_at = |at: usize| u16::from_le_bytes([ header[at], header[at + 1] ]);
+    let u32_at = |at: usize| u32::from_le_bytes([ header[at], header[at + 1], header[at + 2], header[at + 3] ]);
+    let f64_at = |at: usize| {
+        let mut bytes = [0_u8; 8];
+        bytes.copy_from_slice(&header[at..at + 8]);
+        f64::from_le_bytes(bytes)
+    };
+
+    let header_size = u16_at(94) as usize;
+    let point_offset = u32_at(96) as usize;
+    let format = header[104];
+    let record_length = u16_at(105) as usize;
+    let mut count = u32_at(107) as u64;
+    let scale = [ f64_at(131), f64_at(139), f64_at(147) ];
+    let offset = [ f64_at(155), f64_at(163), f64_at(171) ];
+    let center = [
+        (f64_at(179) + f64_at(187)) / 2.0,
+        (f64_at(195) + f64_at(203)) / 2.0,
+        (f64_at(211) + f64_at(219)) / 2.0,
+    ];
+
+    if format & 0x80 != 0 {
+        return Err(invalid("compressed LAZ files are not supported, decompress them with laszip"));
+    }
+    if header_size < header.len() || point_offset < header_size || record_length < 12 {
+        return Err(invalid("inconsistent header sizes"));
+    }
+    if scale.iter().chain(&offset).chain(&center).any(|v| !v.is_finite()) {
+        return Err(invalid("non finite scale, offset or bounds"));
+    }
+
+    let mut extra = vec![0_u8; header_size - header.len()];
+    reader.read_exact(&mut extra).map_err(|_| invalid("truncated header"))?;
+    if count == 0 && extra.len() >= 28 {
+        // LAS 1.4: the legacy count is 0 when there are more than 2^32 points or for the newer point formats
+        let mut bytes = [0_u8; 8];
+        bytes.copy_from_slice(&extra[20..28]);
+        count = u64::from_le_bytes(bytes);
+    }
+
+    // Skip the variable length records
+    let skipped = io::copy(&mut (&mut reader).take((point_offset - header_size) as u64), &mut io::sink())?;
+    if skipped < (point_offset - header_size) as u64 {
+        return Err(invalid("truncated variable length records"));
+    }
+
+    let mut points = Vec::with_capacity(count as usize);
+    let mut record = vec![0_u8; record_length];
+    for _ in 0..count {
+        reader.read_exact(&mut record).map_err(|_| invalid("truncated point records"))?;
+        let coord = |axis: usize| {
+            let at = axis * 4;
+            let raw = i32::from_le_bytes([ record[at], record[at + 1], record[at + 2], record[at + 3] ]);
+            (raw as f64 * scale[axis] + offset[axis] - center[axis]) as f32
+        };
+        points.push(Vec3::new(coord(0), coord(1), coord(2)));
+    }
+    Ok(points)
+}
+
+/// Replaces the points falling in the same cell of a regular grid by their centroid,
+/// with the smallest cells that leave at most `target` points.
+/// The points are returned unchanged if there are `target` or less.
+pub fn decimate(points: &[Vec3], target: usize) -> Vec<Vec3> {
+    if points.len() <= target {
+        return points.to_vec();
+    }
+    if target == 0 {
+        return Vec::new();
+    }
+
+    let (min, max) = bounds(points);
+    let extent = (max - min).x.max((max - min).y).max((max - min).z);
+    if extent <= 0.0 {
+        return vec![ points[0] ]; // All the points are the same
+    }
+
+    // All the points fall in the first cell when it is twice as large as the bounding box,
+    // bisect between sizes that give too many cells and sizes that give few enough
+    let mut too_small = 0.0;
+    let mut large_enough = extent * 2.0;
+    for _ in 0..24 {
+        let size = (too_small + large_enough) / 2.0;
+        if cell_count(points, min, size) <= target {
+            large_enough = size;
+        } else {
+            too_small = size;
+        }
+    }
+
+    let mut cells = HashMap::new();
+    let mut sums: Vec<(cgmath::Vector3<f32>, u32)> = Vec::new();
+    for p in points {
+        let idx = *cells.entry(cell(p.0, min, large_enough)).or_insert_with(|| {
+            sums.push((cgmath::vec3(0.0, 0.0, 0.0), 0));
+            sums.len() - 1
+        });
+        sums[idx].0 += p.0;
+        sums[idx].1 += 1;
+    }
+    sums.into_iter()
+        .map(|(sum, n)| (sum / n as f32).into())
+        .collect()
+}
+
+/// Translates and scales the points so that they are centered on the origin
+/// and the largest side of their bounding box measures `size`.
+pub fn fit(points: &mut [Vec3], size: f32) {
+    if points.is_empty() {
+        return;
+    }
+    let (min, max) = bounds(points);
+    let center = (min + max) / 2.0;
+    let extent = (max - min).x.max((max - min).y).max((max - min).z);
+    let scale = if extent > 0.0 { size / extent } else { 1.0 };
+    for p in points {
+        p.0 = (p.0 - center) * scale;
+    }
+}
+
+/// Shape of a random point cloud, all fit in the cube [-1, 1]³.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Distribution {
+    /// On the unit sphere
+    Sphere,
+    /// Inside the unit ball
+    Ball,
+    /// Inside the cube [-1, 1]³
+    Box,
+    /// Inside a torus around the z axis, with radii 0.7 and 0.3
+    Torus,
+    /// Normal distribution around the origin, with a standard deviation of 0.35 along each axis
+    Gaussian,
+}
+
+impl Distribution {
+    pub const ALL: [Distribution; 5] = [ Distribution::Sphere, Distribution::Ball, Distribution::Box, Distribution::Torus, Distribution::Gaussian ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Distribution::Sphere => "Sphere",
+            Distribution::Ball => "Ball",
+            Distribution::Box => "Box",
+            Distribution::Torus => "Torus",
+            Distribution::Gaussian => "Gaussian",
+        }
+    }
+
+    /// Generates `count` random points.
+    pub fn generate(self, count: usize) -> Vec<Vec3> {
+        let mut rng = rand::thread_rng();
+        let mut cube = move || cgmath::vec3(rng.gen_range(-1.0, 1.0), rng.gen_range(-1.0, 1.0), rng.gen_range(-1.0f32, 1.0));
+        (0..count)
+            .map(|_| match self {
+                Distribution::Sphere => loop {
+                    // Normalizing the points of the ball keeps them uniform, those of the cube would gather in the corners
+                    let p = cube();
+                    let length = p.x * p.x + p.y * p.y + p.z * p.z;
+                    if length <= 1.0 && length > 1e-6 {
+                        break p / length.sqrt();
+                    }
+                },
+                Distribution::Ball => loop {
+                    let p = cube();
+                    if p.x * p.x + p.y * p.y + p.z * p.z <= 1.0 {
+                        break p;
+                    }
+                },
+                Distribution::Box => cube(),
+                Distribution::Torus => loop {
+                    let p = cube();
+                    let ring = (p.x * p.x + p.y * p.y).sqrt() - 0.7;
+                    if ring * ring + p.z * p.z <= 0.3 * 0.3 {
+                        break p;
+                    }
+                },
+                Distribution::Gaussian => loop {
+                    // Box-Muller transform of two points of the cube, the points beyond it are drawn again
+                    let (u, v) = (cube(), cube());
+                    let p = cgmath::vec3(gaussian(u.x, v.x), gaussian(u.y, v.y), gaussian(u.z, v.z)) * 0.35;
+                    if p.x.abs() <= 1.0 && p.y.abs() <= 1.0 && p.z.abs() <= 1.0 {
+                        break p;
+                    }
+                },
+            })
+            .map(Into::into)
+            .collect()
+    }
+}
+
+/// Returns a sample of the standard normal distribution from two uniform samples in [-1, 1].
+fn gaussian(u: f32, v: f32) -> f32 {
+    let u = (u + 1.0) / 2.0;
+    (-2.0 * (1.0 - u).ln()).sqrt() * (std::f32::consts::PI * v).cos()
+}
+
+fn bounds(points: &[Vec3]) -> (cgmath::Vector3<f32>, cgmath::Vector3<f32>) {
+    let mut min = cgmath::vec3(f32::INFINITY, f32::INFINITY, f32::INFINITY);
+    let mut max = -min;
+    for p in points {
+        min = cgmath::vec3(min.x.min(p.0.x), min.y.min(p.0.y), min.z.min(p.0.z));
+        max = cgmath::vec3(max.x.max(p.0.x), max.y.max(p.0.y), max.z.max(p.0.z));
+    }
+    (min, max)
+}
+
+fn cell(p: cgmath::Vector3<f32>, min: cgmath::Vector3<f32>, size: f32) -> (i64, i64, i64) {
+    let idx = (p - min) / size;
+    (idx.x.floor() as i64, idx.y.floor() as i64, idx.z.floor() as i64)
+}
+
+fn cell_count(points: &[Vec3], min: cgmath::Vector3<f32>, size: f32) -> usize {
+    points.iter()
+        .map(|p| cell(p.0, min, size))
+        .collect::<HashSet<_>>()
+        .len()
+}
@@ -0,0 +1,56 @@
+//! Backend of the draw lists on glium, which draws them on the frames of the viewer or on framebuffers.
+
+use super::{ DrawList, Renderer, Vertex };
+use crate::{ Result, graphics };
+
+use glium::{
+    Blend, DrawParameters, Program, Rect, Surface, VertexBuffer,
+    backend::Facade, index::{ NoIndices, PrimitiveType }, uniforms::EmptyUniforms,
+};
+
+implement_vertex!(Vertex, position, color);
+
+pub struct GliumRenderer<'f> {
+    facade: &'f dyn Facade,
+    program: Program,
+}
+
+impl<'f> GliumRenderer<'f> {
+    pub fn new(facade: &'f dyn Facade) -> Result<Self> {
+        let vs = graphics::SHADERS.draw_list_vs;
+        let fs = graphics::SHADERS.draw_list_fs;
+        let program = Program::from_source(facade, vs, fs, None)?;
+        Ok(Self {
+            facade,
+            program,
+        })
+    }
+
+    /// Draws the items of the list over what the target already shows, the view of the list fills `rect`,
+    /// e.g. the viewport of an algorithm in the window.
+    pub fn draw_in<S: Surface>(&self, list: &DrawList, target: &mut S, rect: Rect) -> Result<()> {
+        let vertices = list.tessellate(rect.width, rect.height);
+        if vertices.is_empty() {
+            return Ok(());
+        }
+        let buffer = VertexBuffer::new(self.facade, &vertices)?;
+        let params = DrawParameters {
+            viewport: Some(rect),
+            blend: Blend::alpha_blending(),
+            .. Default::default()
+        };
+        target.draw(&buffer, NoIndices(PrimitiveType::TrianglesList), &self.program, &EmptyUniforms, &params)?;
+        Ok(())
+    }
+}
+
+impl<'f, S: Surface> Renderer<S> for GliumRenderer<'f> {
+    type Error = crate::Error;
+
+    fn draw(&mut self, list: &DrawList, target: &mut S) -> Result<()> {
+        let [ r, g, b, a ] = list.background;
+        target.clear_color(r, g, b, a);
+        let (width, height) = target.get_dimensions();
+        self.draw_in(list, target, Rect { left: 0, bottom: 0, width, height })
+    }
+}
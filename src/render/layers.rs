@@ -0,0 +1,112 @@
+//! Layers that the viewer draws for the results of the algorithms, so that the offscreen renderings of the same
+//! results look like the viewer. The layers are in the coordinates of the 2D views of the viewer, from -1 to 1
+//! in both directions, stretched to their viewports.
+
+use super::{ Color, DrawList, BLACK, WHITE };
+use crate::{
+    algorithms::{ MedialAxis, OffsetCurve, VoronoiCell },
+    math::{ Vec2, Rect, Polyline },
+};
+
+use alloc::vec::Vec;
+
+pub const POINT_COLOR: Color = [ 1.0, 1.0, 1.0, 1.0 ];
+pub const POINT_SIZE: f32 = 8.0;
+pub const HULL_COLOR: Color = [ 0.0, 0.8, 0.0, 1.0 ];
+pub const HULL_VERTEX_COLOR: Color = [ 0.0, 0.0, 0.8, 1.0 ];
+
+/// Returns an empty list on the region shown by the 2D views of the viewer
+pub fn list() -> DrawList {
+    DrawList::new(Rect::new(Vec2::new(-1.0, -1.0), Vec2::new(1.0, 1.0)), BLACK)
+}
+
+/// Input points of the algorithms
+pub fn points(points: &[Vec2]) -> DrawList {
+    let mut list = list();
+    list.points(points, POINT_SIZE, POINT_COLOR);
+    list
+}
+
+/// Closed polyline of a convex hull, with its vertices as single pixels
+pub fn convex_hull(hull: &[Vec2]) -> DrawList {
+    let mut list = list();
+    list.polyline(hull, true, 1.0, HULL_COLOR)
+        .points(hull, 1.0, HULL_VERTEX_COLOR);
+    list
+}
+
+/// Edges of a triangulation, 3 indices into `points` per triangle
+pub fn triangulation(points: &[Vec2], indices: &[usize]) -> DrawList {
+    let mut list = list();
+    list.triangle_edges(points, indices, 1.0, [ 0.0, 0.2, 1.0, 1.0 ]);
+    list
+}
+
+/// Edges of the cells of a Voronoi diagram, with their sites
+pub fn voronoi(sites: &[Vec2], cells: &[VoronoiCell]) -> DrawList {
+    let mut list = list();
+    for cell in cells {
+        list.polyline(&cell.polygon, true, 1.0, [ 1.0, 0.6, 0.0, 1.0 ]);
+    }
+    list.points(sites, POINT_SIZE, [ 0.0, 0.0, 0.8, 1.0 ]);
+    list
+}
+
+/// Triangles of a polygon, filled if `filled` is true, with the diagonals between its vertices, its outline and its vertices
+pub fn polygon_triangulation(polygon: &[Vec2], triangles: &[usize], filled: bool) -> DrawList {
+    let mut list = list();
+    if filled {
+        list.triangles(polygon, triangles, [ 0.1, 0.15, 0.25, 1.0 ]);
+    }
+    // The edges of the triangles between vertices that are not consecutive
+    let n = polygon.len();
+    let diagonals = triangles
+        .chunks_exact(3)
+        .flat_map(|t| [ (t[0], t[1]), (t[1], t[2]), (t[2], t[0]) ].to_vec())
+        .filter(|&(u, v)| u < v && v - u != 1 && v - u != n - 1)
+        .map(|(u, v)| (polygon[u], polygon[v]));
+    list.segments(diagonals, 1.0, [ 0.0, 0.6, 1.0, 1.0 ])
+        .polyline(polygon, true, 1.0, WHITE)
+        .points(polygon, POINT_SIZE, WHITE);
+    list
+}
+
+/// Boundary of a polygon and its medial axis, with the samples of the boundary if `samples` is true, and its vertices
+pub fn medial_axis(polygon: &[Vec2], axis: Option<&MedialAxis>, samples: bool) -> DrawList {
+    let mut list = list();
+    list.polyline(polygon, true, 1.0, WHITE);
+    if let Some(axis) = axis {
+        list.segments(axis.edges.iter().map(|edge| (edge.start, edge.clipped_end(0.0))), 2.0, [ 1.0, 1.0, 0.0, 1.0 ]);
+        if samples {
+            list.points(&axis.samples, 3.0, [ 0.5, 0.5, 0.5, 1.0 ]);
+        }
+    }
+    list.points(polygon, POINT_SIZE, [ 1.0, 0.4, 0.4, 1.0 ]);
+    list
+}
+
+/// Curve and its offsets over the band between them, `band` has 3 points per triangle. The control points of the curve
+/// are drawn on top, and the polygon between them if `control_polygon` is true, e.g. for a path of Bézier curves.
+pub fn offset(control_points: &[Vec2], curve: &Polyline, band: &[Vec2], offsets: &[OffsetCurve], control_polygon: bool) -> DrawList {
+    let mut list = list();
+    list.triangles(band, &(0..band.len()).collect::<Vec<_>>(), [ 0.2, 0.25, 0.4, 1.0 ]);
+    if control_polygon {
+        list.polyline(control_points, curve.closed, 1.0, [ 0.4, 0.4, 0.4, 1.0 ]);
+    }
+    list.polyline(&curve.points, curve.closed, 1.0, WHITE);
+    for offset in offsets {
+        list.polyline(&offset.points, offset.closed, 2.0, [ 1.0, 1.0, 0.0, 1.0 ]);
+    }
+    list.points(control_points, POINT_SIZE, [ 1.0, 0.4, 0.4, 1.0 ]);
+    list
+}
+
+/// Edges of a refined triangulation, 3 indices into `points` per triangle, with the points inserted by the refinement
+/// after the `inputs`, which are drawn on top
+pub fn refinement(inputs: &[Vec2], points: &[Vec2], indices: &[usize]) -> DrawList {
+    let mut list = list();
+    list.triangle_edges(points, indices, 1.0, [ 0.2, 0.4, 1.0, 1.0 ])
+        .points(&points[inputs.len().min(points.len())..], 3.0, [ 1.0, 0.6, 0.2, 1.0 ])
+        .points(inputs, POINT_SIZE, WHITE);
+    list
+}
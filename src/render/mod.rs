@@ -0,0 +1,199 @@
+//! Drawing of the results of the algorithms independently of the graphics API. A [`DrawList`] holds points,
+//! segments and triangles in the coordinates of the plane, and is turned into colored triangles in clip space
+//! that any backend can draw. The glium backend comes with the `gui` feature, the wgpu backend with the `wgpu` feature,
+//! and it can render offscreen into images.
+
+#[cfg(feature = "gui")]
+pub mod glium_backend;
+#[cfg(feature = "gui")]
+pub use glium_backend::GliumRenderer;
+
+#[cfg(feature = "wgpu")]
+pub mod wgpu_backend;
+#[cfg(feature = "wgpu")]
+pub use wgpu_backend::WgpuRenderer;
+
+pub mod layers;
+
+use crate::math::{ Vec2, Rect, float };
+
+use alloc::vec::Vec;
+
+/// RGBA color, each component between 0 and 1
+pub type Color = [f32; 4];
+
+pub const BLACK: Color = [ 0.0, 0.0, 0.0, 1.0 ];
+pub const WHITE: Color = [ 1.0, 1.0, 1.0, 1.0 ];
+pub const RED: Color = [ 0.9, 0.2, 0.2, 1.0 ];
+pub const GREEN: Color = [ 0.2, 0.7, 0.3, 1.0 ];
+pub const BLUE: Color = [ 0.2, 0.4, 0.9, 1.0 ];
+
+/// Vertex of the triangles that backends draw, its position is in clip space, from -1 to 1 with y upwards
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[repr(C)]
+pub struct Vertex {
+    pub position: [f32; 2],
+    pub color: Color,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum Item {
+    /// Squares of `size` pixels centered on the points
+    Points { points: Vec<Vec2>, size: f32, color: Color },
+    /// Segments `width` pixels wide
+    Segments { segments: Vec<(Vec2, Vec2)>, width: f32, color: Color },
+    /// Filled triangles, 3 points per triangle
+    Triangles { points: Vec<Vec2>, color: Color },
+}
+
+/// Items drawn one over the other, in their order, over a background.
+#[derive(Clone, PartialEq, Debug)]
+pub struct DrawList {
+    /// Region of the plane that fills the target, stretched to its size
+    pub view: Rect,
+    pub background: Color,
+    pub items: Vec<Item>,
+}
+
+/// A way to draw the draw lists on targets of type `T`, e.g. windows or offscreen textures.
+pub trait Renderer<T: ?Sized> {
+    type Error;
+
+    /// Clears the target with the background of the list and draws its items
+    fn draw(&mut self, list: &DrawList, target: &mut T) -> Result<(), Self::Error>;
+}
+
+/// 8 bit RGB image, the pixels are stored row by row from the top left corner, 3 bytes per pixel,
+/// e.g. an offscreen rendering of a draw list.
+#[derive(Clone, PartialEq, Debug)]
+pub struct RgbImage {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u8>,
+}
+
+impl DrawList {
+    pub fn new(view: Rect, background: Color) -> Self {
+        Self {
+            view,
+            background,
+            items: Vec::new(),
+        }
+    }
+
+    pub fn points(&mut self, points: &[Vec2], size: f32, color: Color) -> &mut Self {
+        self.items.push(Item::Points { points: points.to_vec(), size, color });
+        self
+    }
+
+    pub fn segments<I: IntoIterator<Item = (Vec2, Vec2)>>(&mut self, segments: I, width: f32, color: Color) -> &mut Self {
+        self.items.push(Item::Segments { segments: segments.into_iter().collect(), width, color });
+        self
+    }
+
+    /// Adds the segments between consecutive points, and from the last point to the first one if `closed` is true
+    pub fn polyline(&mut self, points: &[Vec2], closed: bool, width: f32, color: Color) -> &mut Self {
+        let end = if closed && points.len() > 2 { points.len() } else { points.len().saturating_sub(1) };
+        self.segments((0..end).map(|i| (points[i], points[(i + 1) % points.len()])), width, color)
+    }
+
+    /// Adds the triangles of a triangulation, `indices` has 3 indices into `points` per triangle
+    pub fn triangles(&mut self, points: &[Vec2], indices: &[usize], color: Color) -> &mut Self {
+        self.items.push(Item::Triangles { points: indices.iter().map(|&idx| points[idx]).collect(), color });
+        self
+    }
+
+    /// Adds the edges of the triangles of a triangulation, `indices` has 3 indices into `points` per triangle
+    pub fn triangle_edges(&mut self, points: &[Vec2], indices: &[usize], width: f32, color: Color) -> &mut Self {
+        let edges = indices
+            .chunks_exact(3)
+            .flat_map(|t| [ (t[0], t[1]), (t[1], t[2]), (t[2], t[0]) ].to_vec())
+            .map(|(a, b)| (points[a], points[b]));
+        self.segments(edges, width, color)
+    }
+
+    /// Returns the triangles that cover the items for a target of `width` by `height` pixels
+    pub fn tessellate(&self, width: u32, height: u32) -> Vec<Vertex> {
+        let (width, height) = (width.max(1) as f32, height.max(1) as f32);
+        // From the plane to pixels, with y upwards, then from pixels to clip space
+        let scale = Vec2::new(width / self.view.width(), height / self.view.height());
+        let to_pixels = |p: Vec2| &(&p - &self.view.min) * &scale;
+        let to_clip = |p: Vec2| [ p.x / width * 2.0 - 1.0, p.y / height * 2.0 - 1.0 ];
+
+        let quad = |corners: [Vec2; 4], color: Color| [ 0, 1, 2, 0, 2, 3 ].map(|i| Vertex { position: to_clip(corners[i]), color });
+
+        let mut vertices = Vec::new();
+        for item in &self.items {
+            match item {
+                Item::Points { points, size, color } => {
+                    let half = size / 2.0;
+                    for &p in points {
+                        let p = to_pixels(p);
+                        vertices.extend(quad([
+                            Vec2::new(p.x - half, p.y - half), Vec2::new(p.x + half, p.y - half),
+                            Vec2::new(p.x + half, p.y + half), Vec2::new(p.x - half, p.y + half),
+                        ], *color));
+                    }
+                }
+                Item::Segments { segments, width, color } => {
+                    for &(a, b) in segments {
+                        let (a, b) = (to_pixels(a), to_pixels(b));
+                        let direction = (&b - &a).normalized();
+                        let normal = &Vec2::new(-direction.y, direction.x) * (width / 2.0);
+                        vertices.extend(quad([ &a - &normal, &b - &normal, &b + &normal, &a + &normal ], *color));
+                    }
+                }
+                Item::Triangles { points, color } => {
+                    for &p in points.iter().take(points.len() / 3 * 3) {
+                        vertices.push(Vertex { position: to_clip(to_pixels(p)), color: *color });
+                    }
+                }
+            }
+        }
+        vertices
+    }
+}
+
+impl RgbImage {
+    pub fn new(width: usize, height: usize, pixels: Vec<u8>) -> Self {
+        assert_eq!(pixels.len(), 3 * width * height);
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    pub fn pixel(&self, x: usize, y: usize) -> [u8; 3] {
+        let idx = 3 * (y * self.width + x);
+        [ self.pixels[idx], self.pixels[idx + 1], self.pixels[idx + 2] ]
+    }
+
+    /// Returns the fraction of the pixels that look different in `other`, 1 if the sizes differ.
+    /// A pixel looks the same if one of the pixels of `other` at most one pixel away from it has a color closer than
+    /// `threshold`, so that the edges can move by a pixel, e.g. between the rasterizations of different GPUs.
+    /// The distance between colors is the "redmean" approximation of the perceived difference, from 0 to 1.
+    pub fn difference(&self, other: &Self, threshold: f32) -> f32 {
+        if (self.width, self.height) != (other.width, other.height) {
+            return 1.0;
+        }
+        if self.pixels.is_empty() {
+            return 0.0;
+        }
+        let distance = |a: [u8; 3], b: [u8; 3]| {
+            let mean_red = (a[0] as f32 + b[0] as f32) / 2.0;
+            let [ dr, dg, db ] = [ 0, 1, 2 ].map(|i| a[i] as f32 - b[i] as f32);
+            let sqr = (2.0 + mean_red / 256.0) * dr * dr + 4.0 * dg * dg + (2.0 + (255.0 - mean_red) / 256.0) * db * db;
+            float::sqrt(sqr) / (3.0 * 255.0)
+        };
+        let different = (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .filter(|&(x, y)| {
+                let color = self.pixel(x, y);
+                let (xs, ys) = (x.saturating_sub(1)..(x + 2).min(self.width), y.saturating_sub(1)..(y + 2).min(self.height));
+                !ys.flat_map(|v| xs.clone().map(move |u| (u, v))).any(|(u, v)| distance(color, other.pixel(u, v)) <= threshold)
+            })
+            .count();
+        different as f32 / (self.width * self.height) as f32
+    }
+}
@@ -0,0 +1,185 @@
+//! Backend of the draw lists on wgpu, which runs on Vulkan, Metal, DX12 and OpenGL. It draws into textures,
+//! those of window surfaces or offscreen ones that are read back into images, e.g. to compare them with references.
+//! Without a GPU, the software OpenGL of Mesa (llvmpipe) can be used as the adapter.
+
+use super::{ DrawList, Renderer, RgbImage, Vertex };
+
+use wgpu::util::DeviceExt;
+
+use std::{ convert::Infallible, mem };
+
+/// Format of the textures drawn by [`WgpuRenderer::headless`]
+const OFFSCREEN_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+/// The rows of the copies from textures to buffers are padded to a multiple of this many bytes
+const ROW_ALIGNMENT: u32 = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+
+pub struct WgpuRenderer {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    pipeline: wgpu::RenderPipeline,
+    format: wgpu::TextureFormat,
+}
+
+impl WgpuRenderer {
+    /// Creates a renderer that draws with the device into textures of the given format
+    pub fn new(device: wgpu::Device, queue: wgpu::Queue, format: wgpu::TextureFormat) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Draw list"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../../shaders/draw_list.wgsl").into()),
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Draw list"),
+            bind_group_layouts: &[],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Draw list"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                compilation_options: Default::default(),
+                buffers: &[ wgpu::VertexBufferLayout {
+                    array_stride: mem::size_of::<Vertex>() as wgpu::BufferAddress,
+                    step_mode: wgpu::VertexStepMode::Vertex,
+                    attributes: &wgpu::vertex_attr_array![ 0 => Float32x2, 1 => Float32x4 ],
+                } ],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                compilation_options: Default::default(),
+                targets: &[ Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                }) ],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        Self {
+            device,
+            queue,
+            pipeline,
+            format,
+        }
+    }
+
+    /// Creates a renderer for offscreen rendering on the default adapter, or `None` if there is no adapter
+    pub fn headless() -> Option<Self> {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default()))?;
+        log::info!("Offscreen rendering on {:?}", adapter.get_info());
+        let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor::default(), None)).ok()?;
+        Some(Self::new(device, queue, OFFSCREEN_FORMAT))
+    }
+
+    /// Returns the device, e.g. to configure the surface of a window
+    pub fn device(&self) -> &wgpu::Device {
+        &self.device
+    }
+
+    pub fn format(&self) -> wgpu::TextureFormat {
+        self.format
+    }
+
+    /// Draws the list into a new texture of `width` by `height` pixels and reads it back.
+    /// The format of the renderer has to be one of the 8 bit RGBA or BGRA formats.
+    pub fn render_to_image(&mut self, list: &DrawList, width: u32, height: u32) -> Result<RgbImage, wgpu::BufferAsyncError> {
+        let size = wgpu::Extent3d { width, height, depth_or_array_layers: 1 };
+        let mut texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Offscreen"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: self.format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let Ok(()) = self.draw(list, &mut texture);
+
+        let padded_row = (4 * width).div_ceil(ROW_ALIGNMENT) * ROW_ALIGNMENT;
+        let buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Offscreen readback"),
+            size: (padded_row * height) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("Offscreen readback") });
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::TexelCopyBufferInfo {
+                buffer: &buffer,
+                layout: wgpu::TexelCopyBufferLayout { offset: 0, bytes_per_row: Some(padded_row), rows_per_image: Some(height) },
+            },
+            size,
+        );
+        self.queue.submit(Some(encoder.finish()));
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let slice = buffer.slice(..);
+        slice.map_async(wgpu::MapMode::Read, move |result| sender.send(result).unwrap_or(()));
+        self.device.poll(wgpu::Maintain::Wait);
+        receiver.recv().unwrap_or(Err(wgpu::BufferAsyncError))?;
+
+        let bgra = matches!(self.format, wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb);
+        let data = slice.get_mapped_range();
+        let pixels = data
+            .chunks(padded_row as usize)
+            .flat_map(|row| row[..4 * width as usize].chunks(4))
+            .flat_map(|pixel| if bgra { [ pixel[2], pixel[1], pixel[0] ] } else { [ pixel[0], pixel[1], pixel[2] ] })
+            .collect();
+        Ok(RgbImage::new(width as usize, height as usize, pixels))
+    }
+}
+
+impl Renderer<wgpu::Texture> for WgpuRenderer {
+    type Error = Infallible;
+
+    fn draw(&mut self, list: &DrawList, target: &mut wgpu::Texture) -> Result<(), Infallible> {
+        let vertices = list.tessellate(target.width(), target.height());
+        let bytes = vertices
+            .iter()
+            .flat_map(|vertex| vertex.position.iter().chain(&vertex.color))
+            .flat_map(|x| x.to_ne_bytes())
+            .collect::<Vec<u8>>();
+        let buffer = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Draw list"),
+            contents: &bytes,
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+
+        let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+        let [ r, g, b, a ] = list.background;
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("Draw list") });
+        {
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Draw list"),
+                color_attachments: &[ Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color { r: r as f64, g: g as f64, b: b as f64, a: a as f64 }),
+                        store: wgpu::StoreOp::Store,
+                    },
+                }) ],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            if !vertices.is_empty() {
+                pass.set_pipeline(&self.pipeline);
+                pass.set_vertex_buffer(0, buffer.slice(..));
+                pass.draw(0..vertices.len() as u32, 0..1);
+            }
+        }
+        self.queue.submit(Some(encoder.finish()));
+        Ok(())
+    }
+}
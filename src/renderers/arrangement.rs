@@ -0,0 +1,241 @@
+use crate::{
+    Result,
+    algorithms::Arrangement,
+    graphics::{ self, Shape, Viewport },
+    math::{ Vec2, Segment2, polygon },
+    scene::SharedScene,
+    ui::{ PointInput, ExecTimeHistory, window::algorithms::{ Drawable, Configurable } },
+};
+
+use std::time::Instant;
+
+use glium::{
+    index::PrimitiveType, Frame, Program,
+    backend::Facade,
+};
+use winit::{
+    event::{ Event, WindowEvent, MouseButton, ElementState },
+    window::Window,
+};
+
+pub struct ArrangementRenderer<'f> {
+    alg: Arrangement,
+    /// The input segments are those of the scene
+    scene: SharedScene,
+    /// Revision of the scene the arrangement was computed from
+    revision: u64,
+    program: Program,
+    edges: Shape<'f>,
+    vertices: Shape<'f>,
+    /// Boundary of the face under the cursor
+    highlight: Shape<'f>,
+    /// First end of the segment being drawn
+    start: Shape<'f>,
+    pending: Option<Vec2>,
+    hovered: Option<usize>,
+    random_count: i32,
+    exec_time: ExecTimeHistory,
+    point_input: PointInput<'f>,
+}
+
+impl<'f> Drawable for ArrangementRenderer<'f> {
+    fn update(&mut self) -> Result<()> {
+        if self.scene.borrow().revision() != self.revision {
+            self.compute()?;
+        }
+        Ok(())
+    }
+
+    fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        self.point_input.draw(target, viewport)?;
+        self.edges.draw(target, &self.program, viewport)?;
+        self.highlight.draw(target, &self.program, viewport)?;
+        self.vertices.draw(target, &self.program, viewport)?;
+        self.start.draw(target, &self.program, viewport)
+    }
+
+    fn handle_events(&mut self, window: &Window, event: &Event<()>, io: &imgui::Io, viewport: &Viewport) -> Result<()> {
+        if let Event::WindowEvent { event, .. } = event {
+            match event {
+                WindowEvent::MouseInput { button, state, .. } => {
+                    if !io.want_capture_mouse && // Ignore clicks when the cursor is over an ImGui window
+                        button == &MouseButton::Left && state == &ElementState::Pressed {
+
+                        // The first click starts a segment, the second one ends it
+                        let coords = viewport.window_pos_to_normalized(io.mouse_pos.into(), window);
+                        self.add_end(self.point_input.place(coords))?;
+                    }
+                },
+                WindowEvent::CursorMoved { .. } => {
+                    let coords = viewport.window_pos_to_normalized(io.mouse_pos.into(), window);
+                    self.hover(Some(self.alg.locate(coords)))?;
+                },
+                WindowEvent::CursorLeft { .. } => self.hover(None)?,
+                _ => {},
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'f> Configurable for ArrangementRenderer<'f> {
+    fn name(&self) -> &'static str {
+        "Segment arrangement"
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        Some("The segments divide the plane into vertices, edges and faces, stored in a doubly connected edge list: \
+              each edge is made of two opposite half-edges, and each half-edge knows the face on its left \
+              and the next half-edge around that face. The segments are split at their intersections, \
+              then the half-edges leaving each vertex are sorted by angle to link them. \
+              Click twice to add a segment, the face under the cursor is highlighted.")
+    }
+
+    fn pseudo_code(&self) -> Option<&'static str> {
+        Some(concat!(
+            "split the segments at their intersections\n",
+            "for each vertex v:\n",
+            "    sort the half-edges leaving v by angle\n",
+            "    for each half-edge e arriving at v:\n",
+            "        next(e) = half-edge leaving v\n",
+            "                  clockwise after twin(e)\n",
+            "for each cycle of next pointers:\n",
+            "    if counter-clockwise: new face\n",
+            "    else: hole in the face around it",
+        ))
+    }
+
+    fn complexity(&self) -> Option<&'static str> {
+        Some("O(n² + k log k) for n segments and k intersections, every pair of segments is tested")
+    }
+
+    fn configure(&mut self, ui: &imgui::Ui) -> Result<()> {
+        let (v, e, f) = (self.alg.vertices().len(), self.alg.edge_count(), self.alg.faces().len());
+        ui.text(imgui::im_str!("{} vertices, {} edges, {} faces", v, e, f));
+        ui.text(imgui::im_str!("{} connected components", self.alg.component_count()));
+        ui.text_disabled(imgui::im_str!("V - E + F = {} = 1 + components", v as isize - e as isize + f as isize));
+
+        match self.hovered {
+            Some(0) => ui.text(imgui::im_str!("Hovered: unbounded face, {} holes", self.alg.faces()[0].inner.len())),
+            Some(face) => {
+                let boundaries = self.alg.face_boundaries(face);
+                let area = boundaries.iter().map(|ring| polygon::signed_area(ring)).sum::<f32>();
+                ui.text(imgui::im_str!("Hovered: face {}, {} edges, {} holes, area {:.4}",
+                                       face, boundaries[0].len(), boundaries.len() - 1, area));
+            },
+            None => {},
+        }
+
+        if ui.button(imgui::im_str!("Clear Segments"), [0.0, 0.0]) {
+            self.pending = None;
+            self.start.clear()?;
+            self.scene.borrow_mut().clear_segments();
+        }
+
+        ui.input_int(imgui::im_str!("Count"), &mut self.random_count).build();
+        if ui.button(imgui::im_str!("Random Segments"), [0.0, 0.0]) {
+            let mut scene = self.scene.borrow_mut();
+            for _ in 0..self.random_count.max(0) {
+                let a = Vec2::random_range(-0.8, 0.8, -0.8, 0.8);
+                let b = Vec2::random_range(-0.8, 0.8, -0.8, 0.8);
+                scene.add_segment(Segment2::new(a, b));
+            }
+        }
+
+        if let Some(point) = self.point_input.configure(ui)? {
+            self.add_end(point)?;
+        }
+
+        self.exec_time.configure(ui);
+        Ok(())
+    }
+}
+
+impl<'f> ArrangementRenderer<'f> {
+    pub fn new(facade: &'f dyn Facade, scene: SharedScene) -> Result<Self> {
+        let vs = graphics::SHADERS._2d_vs;
+        let fs = graphics::SHADERS.basic_fs;
+        let program = Program::from_source(facade, vs, fs, None)?;
+
+        let mut highlight = Shape::new(facade, PrimitiveType::LinesList, [ 1.0, 0.8, 0.0 ])?;
+        highlight.size = 3.0;
+
+        Ok(Self {
+            alg: Arrangement::default(),
+            scene,
+            revision: 0,
+            program,
+            edges: Shape::new(facade, PrimitiveType::LinesList, [ 0.0, 0.2, 1.0 ])?,
+            vertices: Shape::new(facade, PrimitiveType::Points, [ 1.0, 1.0, 1.0 ])?,
+            highlight,
+            start: Shape::new(facade, PrimitiveType::Points, [ 1.0, 0.8, 0.0 ])?,
+            pending: None,
+            hovered: None,
+            random_count: 20,
+            exec_time: ExecTimeHistory::default(),
+            point_input: PointInput::new(facade)?,
+        })
+    }
+
+    /// Starts a segment at `point`, or ends the pending one and adds it to the scene.
+    fn add_end(&mut self, point: Vec2) -> Result<()> {
+        match self.pending.take() {
+            Some(start) => {
+                self.start.clear()?;
+                self.scene.borrow_mut().add_segment(Segment2::new(start, point));
+            },
+            None => {
+                self.pending = Some(point);
+                self.start.set_vertices(&[ point ])?;
+            },
+        }
+        Ok(())
+    }
+
+    /// Highlights the boundary of `face`.
+    fn hover(&mut self, face: Option<usize>) -> Result<()> {
+        if face == self.hovered {
+            return Ok(());
+        }
+        self.hovered = face;
+
+        let lines = face.map_or_else(Vec::new, |face| {
+            self.alg.face_boundaries(face)
+                .iter()
+                .flat_map(|ring| polygon::edges(ring).flat_map(|(a, b)| vec![ a, b ]).collect::<Vec<_>>())
+                .collect()
+        });
+        self.highlight.set_vertices(&lines)
+    }
+
+    /// Computes the arrangement of the segments of the scene and regenerates the buffers.
+    fn compute(&mut self) -> Result<()> {
+        let scene = self.scene.borrow();
+        self.revision = scene.revision();
+
+        let start_time = Instant::now();
+        self.alg = Arrangement::new(scene.segments());
+        let is_empty = scene.segments().is_empty();
+        drop(scene);
+        if is_empty {
+            self.exec_time.clear();
+        } else {
+            self.exec_time.push(Instant::now() - start_time);
+        }
+
+        let edges = self.alg.half_edges()
+                            .iter()
+                            .step_by(2)
+                            .flat_map(|edge| {
+                                let twin = &self.alg.half_edges()[edge.twin];
+                                vec![ self.alg.vertices()[edge.origin], self.alg.vertices()[twin.origin] ]
+                            })
+                            .collect::<Vec<_>>();
+        self.edges.set_vertices(&edges)?;
+        self.vertices.set_vertices(self.alg.vertices())?;
+
+        // The faces have new indices
+        self.hovered = None;
+        self.highlight.clear()
+    }
+}
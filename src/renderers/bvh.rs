@@ -0,0 +1,309 @@
+use crate::{
+    Result,
+    graphics::{ self, Shape, Viewport },
+    math::{ Vec2, Segment2, polygon },
+    scene::SharedScene,
+    spatial::{ self, Bvh, bvh::Node },
+    ui::{ PointInput, ExecTimeHistory, window::algorithms::{ Drawable, Configurable } },
+};
+
+use std::time::{ Duration, Instant };
+
+use glium::{
+    index::PrimitiveType, Frame, Program,
+    backend::Facade,
+};
+use winit::{
+    event::{ Event, WindowEvent, MouseButton, ElementState },
+    window::Window,
+};
+
+/// Length of the rays that hit nothing, long enough to cross the viewport
+const RAY_LENGTH: f32 = 10.0;
+/// Maximum length of the random segments
+const RANDOM_LENGTH: f32 = 0.15;
+
+/// Result of the last query of one of the methods.
+#[derive(Copy, Clone)]
+struct Query {
+    /// Number of segments tested against the query
+    tested: usize,
+    time: Duration,
+}
+
+pub struct BvhRenderer<'f> {
+    /// The input segments are those of the scene
+    scene: SharedScene,
+    /// Revision of the scene the hierarchy was built from
+    revision: u64,
+    segments: Vec<Segment2>,
+    program: Program,
+    edges: Shape<'f>,
+    boxes: Shape<'f>,
+    /// Segments tested by the hierarchy during the last query
+    tested: Shape<'f>,
+    /// Segments hit by the last query
+    hits: Shape<'f>,
+    query: Shape<'f>,
+    /// Origin of the ray or first end of the query segment, the cursor gives the direction or the other end
+    anchor: Option<Vec2>,
+    /// Whether the query is a segment instead of a ray
+    segment_query: bool,
+    /// Whether the hierarchy is built from the Morton codes instead of the surface area heuristic
+    linear: bool,
+    /// Only the boxes down to this depth are drawn
+    max_depth: i32,
+    /// Nodes of the hierarchy of the segments
+    nodes: Vec<Node>,
+    depth: usize,
+    brute_force: Option<Query>,
+    bvh: Option<Query>,
+    random_count: i32,
+    exec_time: ExecTimeHistory,
+    point_input: PointInput<'f>,
+}
+
+impl<'f> Drawable for BvhRenderer<'f> {
+    fn update(&mut self) -> Result<()> {
+        if self.scene.borrow().revision() != self.revision {
+            self.compute()?;
+        }
+        Ok(())
+    }
+
+    fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        self.point_input.draw(target, viewport)?;
+        self.boxes.draw(target, &self.program, viewport)?;
+        self.edges.draw(target, &self.program, viewport)?;
+        self.tested.draw(target, &self.program, viewport)?;
+        self.hits.draw(target, &self.program, viewport)?;
+        self.query.draw(target, &self.program, viewport)
+    }
+
+    fn handle_events(&mut self, window: &Window, event: &Event<()>, io: &imgui::Io, viewport: &Viewport) -> Result<()> {
+        if let Event::WindowEvent { event, .. } = event {
+            let coords = viewport.window_pos_to_normalized(io.mouse_pos.into(), window);
+            match event {
+                WindowEvent::MouseInput { button, state, .. } => {
+                    if !io.want_capture_mouse && // Ignore clicks when the cursor is over an ImGui window
+                        button == &MouseButton::Left && state == &ElementState::Pressed {
+
+                        // A click moves the origin of the ray or the first end of the query segment
+                        self.anchor = Some(self.point_input.place(coords));
+                        self.run_query(coords)?;
+                    }
+                },
+                WindowEvent::CursorMoved { .. } => self.run_query(coords)?,
+                _ => {},
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'f> Configurable for BvhRenderer<'f> {
+    fn name(&self) -> &'static str {
+        "Bounding volume hierarchy"
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        Some("The segments are grouped in a tree of boxes: every box contains the boxes of its two children. \
+              Each node is split where the surface area heuristic expects queries to be the cheapest, \
+              the probability of a random line crossing a box being proportional to its perimeter. \
+              Click to place the origin of a ray, or the first end of a query segment, and move the cursor to aim it: \
+              the boxes the query misses are skipped with all their segments, and a ray stops \
+              at the boxes behind its closest hit. The highlighted segments are the ones the hierarchy tested. \
+              Built from Morton codes, the segments are sorted along a Z-order curve by the centers of their boxes \
+              and every node is split where the highest differing bit of its codes changes: \
+              the hierarchy is faster to build but slower to query.")
+    }
+
+    fn pseudo_code(&self) -> Option<&'static str> {
+        Some(concat!(
+            "build(segments):\n",
+            "    for each axis, sorted by center:\n",
+            "        for each split position i:\n",
+            "            cost = perimeter(left) * i\n",
+            "                 + perimeter(right) * (n - i)\n",
+            "    split at the cheapest position\n",
+            "    unless a leaf is cheaper\n",
+            "\n",
+            "raycast(node, ray):\n",
+            "    if ray misses node.box or enters it\n",
+            "       after the closest hit: return\n",
+            "    if node is a leaf: test its segments\n",
+            "    else: raycast the closest child first",
+        ))
+    }
+
+    fn complexity(&self) -> Option<&'static str> {
+        Some("O(n log² n) to build, about O(log n) per query for short segments")
+    }
+
+    fn configure(&mut self, ui: &imgui::Ui) -> Result<()> {
+        ui.text(imgui::im_str!("{} segments, {} nodes, depth {}", self.segments.len(), self.nodes.len(), self.depth));
+        if let (Some(brute_force), Some(bvh)) = (self.brute_force, self.bvh) {
+            ui.text(imgui::im_str!("Brute force: {} µs, {} segments tested", brute_force.time.as_micros(), brute_force.tested));
+            ui.text(imgui::im_str!("Hierarchy: {} µs, {} segments tested", bvh.time.as_micros(), bvh.tested));
+        }
+
+        if ui.checkbox(imgui::im_str!("Query with a segment"), &mut self.segment_query) {
+            self.anchor = None;
+            self.clear_query()?;
+        }
+        if ui.checkbox(imgui::im_str!("Build from Morton codes"), &mut self.linear) {
+            self.compute()?;
+        }
+        if imgui::Slider::new(imgui::im_str!("Depth"), 0..=self.depth.max(1) as i32).build(ui, &mut self.max_depth) {
+            self.draw_boxes()?;
+        }
+
+        if ui.button(imgui::im_str!("Clear Segments"), [0.0, 0.0]) {
+            self.scene.borrow_mut().clear_segments();
+        }
+
+        ui.input_int(imgui::im_str!("Count"), &mut self.random_count).build();
+        if ui.button(imgui::im_str!("Random Segments"), [0.0, 0.0]) {
+            let mut scene = self.scene.borrow_mut();
+            for _ in 0..self.random_count.max(0) {
+                let a = Vec2::random_range(-0.8, 0.8, -0.8, 0.8);
+                let b = &a + &Vec2::random_range(-RANDOM_LENGTH, RANDOM_LENGTH, -RANDOM_LENGTH, RANDOM_LENGTH);
+                scene.add_segment(Segment2::new(a, b));
+            }
+        }
+
+        if let Some(point) = self.point_input.configure(ui)? {
+            self.anchor = Some(point);
+            self.clear_query()?;
+        }
+
+        self.exec_time.configure(ui);
+        Ok(())
+    }
+}
+
+impl<'f> BvhRenderer<'f> {
+    pub fn new(facade: &'f dyn Facade, scene: SharedScene) -> Result<Self> {
+        let vs = graphics::SHADERS._2d_vs;
+        let fs = graphics::SHADERS.basic_fs;
+        let program = Program::from_source(facade, vs, fs, None)?;
+
+        let mut hits = Shape::new(facade, PrimitiveType::LinesList, [ 0.0, 1.0, 0.0 ])?;
+        hits.size = 3.0;
+        let mut tested = Shape::new(facade, PrimitiveType::LinesList, [ 1.0, 0.5, 0.0 ])?;
+        tested.size = 2.0;
+
+        Ok(Self {
+            scene,
+            revision: 0,
+            segments: Vec::new(),
+            program,
+            edges: Shape::new(facade, PrimitiveType::LinesList, [ 1.0, 1.0, 1.0 ])?,
+            boxes: Shape::new(facade, PrimitiveType::LinesList, [ 0.3, 0.3, 0.6 ])?,
+            tested,
+            hits,
+            query: Shape::new(facade, PrimitiveType::LinesList, [ 1.0, 0.0, 0.0 ])?,
+            anchor: None,
+            segment_query: false,
+            linear: false,
+            max_depth: 4,
+            nodes: Vec::new(),
+            depth: 0,
+            brute_force: None,
+            bvh: None,
+            random_count: 500,
+            exec_time: ExecTimeHistory::default(),
+            point_input: PointInput::new(facade)?,
+        })
+    }
+
+    /// Copies the segments of the scene, builds their hierarchy and forgets the last query.
+    fn compute(&mut self) -> Result<()> {
+        let scene = self.scene.borrow();
+        self.revision = scene.revision();
+        self.segments = scene.segments().to_vec();
+        drop(scene);
+
+        let start_time = Instant::now();
+        let bvh = if self.linear { Bvh::new_linear(&self.segments) } else { Bvh::new(&self.segments) };
+        if self.segments.is_empty() {
+            self.exec_time.clear();
+        } else {
+            self.exec_time.push(Instant::now() - start_time);
+        }
+        self.depth = bvh.depth();
+        self.nodes = bvh.nodes().to_vec();
+
+        self.edges.set_vertices(&self.segments.iter().flat_map(|s| vec![ s.a, s.b ]).collect::<Vec<_>>())?;
+        self.draw_boxes()?;
+        self.clear_query()
+    }
+
+    /// Regenerates the outlines of the boxes down to the selected depth.
+    fn draw_boxes(&mut self) -> Result<()> {
+        let max_depth = self.max_depth.max(0) as usize;
+        let boxes = self.nodes
+                       .iter()
+                       .filter(|node| node.depth <= max_depth)
+                       .flat_map(|node| polygon::edges(&node.bounds.corners()).flat_map(|(a, b)| vec![ a, b ]).collect::<Vec<_>>())
+                       .collect::<Vec<_>>();
+        self.boxes.set_vertices(&boxes)
+    }
+
+    fn clear_query(&mut self) -> Result<()> {
+        self.brute_force = None;
+        self.bvh = None;
+        self.tested.clear()?;
+        self.hits.clear()?;
+        self.query.clear()
+    }
+
+    /// Casts the ray or tests the segment from the anchor to `cursor`, with brute force and with the hierarchy.
+    fn run_query(&mut self, cursor: Vec2) -> Result<()> {
+        let anchor = match self.anchor {
+            Some(anchor) if anchor != cursor => anchor,
+            _ => return self.clear_query(),
+        };
+        // The hierarchy is built again here because it borrows the segments, only the queries are timed
+        let bvh = if self.linear { Bvh::new_linear(&self.segments) } else { Bvh::new(&self.segments) };
+        let mut tested = Vec::new();
+
+        let (brute_force_time, bvh_time, hits, end) = if self.segment_query {
+            let query = Segment2::new(anchor, cursor);
+
+            let start_time = Instant::now();
+            spatial::intersecting(&self.segments, &query);
+            let brute_force_time = Instant::now() - start_time;
+
+            let start_time = Instant::now();
+            let hits = bvh.intersecting_visiting(&query, |idx| tested.push(idx));
+            (brute_force_time, Instant::now() - start_time, hits, cursor)
+        } else {
+            let direction = &cursor - &anchor;
+
+            let start_time = Instant::now();
+            spatial::raycast(&self.segments, anchor, direction);
+            let brute_force_time = Instant::now() - start_time;
+
+            let start_time = Instant::now();
+            let hit = bvh.raycast_visiting(anchor, direction, |idx| tested.push(idx));
+            let bvh_time = Instant::now() - start_time;
+
+            // The ray stops at the closest hit
+            let end = match hit {
+                Some((_, t)) => &anchor + &(t * &direction),
+                None => &anchor + &(RAY_LENGTH * &direction.normalized()),
+            };
+            (brute_force_time, bvh_time, hit.map(|(idx, _)| idx).into_iter().collect(), end)
+        };
+
+        self.brute_force = Some(Query { tested: self.segments.len(), time: brute_force_time });
+        self.bvh = Some(Query { tested: tested.len(), time: bvh_time });
+
+        let segments = &self.segments;
+        let vertices = |indices: &[usize]| indices.iter().flat_map(|&idx| vec![ segments[idx].a, segments[idx].b ]).collect::<Vec<_>>();
+        self.tested.set_vertices(&vertices(&tested))?;
+        self.hits.set_vertices(&vertices(&hits))?;
+        self.query.set_vertices(&[ anchor, end ])
+    }
+}
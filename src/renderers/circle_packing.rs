@@ -0,0 +1,225 @@
+use crate::{
+    Result,
+    algorithms::CirclePacking,
+    graphics::{ self, Shape, Viewport },
+    math::Vec2,
+    scene::SharedScene,
+    ui::{ PointInput, ExecTimeHistory, window::algorithms::{ Drawable, Configurable } },
+};
+
+use std::time::{ Duration, Instant };
+
+use glium::{
+    index::PrimitiveType, Frame, Program,
+    backend::Facade,
+};
+use winit::{
+    event::{ Event, WindowEvent, MouseButton, ElementState },
+    window::Window,
+};
+
+/// Time spent adding circles each frame, so that the packing grows progressively without freezing the viewer
+const FRAME_BUDGET: Duration = Duration::from_millis(10);
+
+pub struct CirclePackingRenderer<'f> {
+    alg: CirclePacking,
+    /// The last polygon of the scene is loaded when it changes
+    scene: SharedScene,
+    revision: u64,
+    program: Program,
+    outline: Shape<'f>,
+    vertices: Shape<'f>,
+    circles: Shape<'f>,
+    /// Circles are added every frame until one of the targets is reached
+    running: bool,
+    max_count: i32,
+    target_coverage: f32,
+    min_radius: f32,
+    /// Time spent packing since the last reset
+    packing_time: Duration,
+    exec_time: ExecTimeHistory,
+    point_input: PointInput<'f>,
+}
+
+impl<'f> Drawable for CirclePackingRenderer<'f> {
+    fn update(&mut self) -> Result<()> {
+        // The scene must not be borrowed anymore when packing, hence the block
+        let polygon = {
+            let scene = self.scene.borrow();
+            if scene.revision() == self.revision {
+                None
+            } else {
+                self.revision = scene.revision();
+                scene.polygons().last().cloned()
+            }
+        };
+        if let Some(polygon) = polygon {
+            self.set_polygon(polygon)?;
+        }
+
+        if self.running {
+            self.pack()?;
+        }
+        Ok(())
+    }
+
+    fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        self.point_input.draw(target, viewport)?;
+        self.outline.draw(target, &self.program, viewport)?;
+        self.circles.draw(target, &self.program, viewport)?;
+        self.vertices.draw(target, &self.program, viewport)
+    }
+
+    fn handle_events(&mut self, window: &Window, event: &Event<()>, io: &imgui::Io, viewport: &Viewport) -> Result<()> {
+        if let Event::WindowEvent { event, .. } = event {
+            if let WindowEvent::MouseInput { button, state, .. } = event {
+                if !io.want_capture_mouse && // Ignore clicks when the cursor is over an ImGui window
+                    button == &MouseButton::Left && state == &ElementState::Pressed {
+
+                    // Add a vertex on click
+                    let coords = viewport.window_pos_to_normalized(io.mouse_pos.into(), window);
+                    self.add_vertex(self.point_input.place(coords))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'f> Configurable for CirclePackingRenderer<'f> {
+    fn name(&self) -> &'static str {
+        "Circle packing"
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        Some("Packs circles in a polygon greedily: each new circle is the largest one that fits \
+              between the boundary and the previous circles. It is found like the largest inscribed circle, \
+              by refining the cells that may contain a point farther from the boundary and from the circles \
+              than the best one so far. Click to add vertices to the polygon, \
+              the last polygon of the scene is loaded when it changes.")
+    }
+
+    fn pseudo_code(&self) -> Option<&'static str> {
+        Some(concat!(
+            "circles = []\n",
+            "while count and coverage are below the targets:\n",
+            "    d(p) = min(distance from p to the boundary,\n",
+            "               |p - c.center| - c.radius for c in circles)\n",
+            "    c = point maximizing d, by quadtree refinement\n",
+            "    if d(c) < minimum radius: stop\n",
+            "    add circle (c, d(c)) to circles",
+        ))
+    }
+
+    fn complexity(&self) -> Option<&'static str> {
+        Some("O(n + k) per probed cell for n vertices and k circles")
+    }
+
+    fn configure(&mut self, ui: &imgui::Ui) -> Result<()> {
+        ui.text(imgui::im_str!("{} vertices, {} circles", self.alg.polygon().len(), self.alg.circles().len()));
+        ui.text(imgui::im_str!("Coverage: {:.1}%", self.alg.coverage() * 100.0));
+        ui.text(imgui::im_str!("Packing time: {:.3}s", self.packing_time.as_secs_f32()));
+
+        ui.input_int(imgui::im_str!("Max circles"), &mut self.max_count).step(100).build();
+        self.max_count = self.max_count.max(0);
+        imgui::Slider::new(imgui::im_str!("Target coverage"), 0.0..=1.0).build(ui, &mut self.target_coverage);
+        ui.input_float(imgui::im_str!("Min radius"), &mut self.min_radius).step(0.001).build();
+        self.min_radius = self.min_radius.max(0.0);
+
+        let label = if self.running { imgui::im_str!("Pause") } else { imgui::im_str!("Pack") };
+        if ui.button(label, [0.0, 0.0]) {
+            self.running = !self.running;
+        }
+        ui.same_line(0.0);
+        if ui.button(imgui::im_str!("Reset"), [0.0, 0.0]) {
+            self.set_polygon(self.alg.polygon().to_vec())?;
+        }
+
+        if ui.button(imgui::im_str!("Clear Polygon"), [0.0, 0.0]) {
+            self.set_polygon(Vec::new())?;
+        }
+        ui.same_line(0.0);
+        if ui.button(imgui::im_str!("Add to Scene"), [0.0, 0.0]) && self.alg.polygon().len() >= 3 {
+            self.scene.borrow_mut().add_polygon(self.alg.polygon().to_vec());
+        }
+
+        if let Some(point) = self.point_input.configure(ui)? {
+            self.add_vertex(point)?;
+        }
+
+        self.exec_time.configure(ui);
+        Ok(())
+    }
+}
+
+impl<'f> CirclePackingRenderer<'f> {
+    pub fn new(facade: &'f dyn Facade, scene: SharedScene) -> Result<Self> {
+        let vs = graphics::SHADERS._2d_vs;
+        let fs = graphics::SHADERS.basic_fs;
+        let program = Program::from_source(facade, vs, fs, None)?;
+
+        Ok(Self {
+            alg: CirclePacking::default(),
+            scene,
+            revision: 0,
+            program,
+            outline: Shape::new(facade, PrimitiveType::LineLoop, [ 0.0, 0.2, 1.0 ])?,
+            vertices: Shape::new(facade, PrimitiveType::Points, [ 1.0, 1.0, 1.0 ])?,
+            circles: Shape::new(facade, PrimitiveType::LinesList, [ 0.0, 0.8, 0.0 ])?,
+            running: false,
+            max_count: 500,
+            target_coverage: 0.8,
+            min_radius: 0.005,
+            packing_time: Duration::default(),
+            exec_time: ExecTimeHistory::default(),
+            point_input: PointInput::new(facade)?,
+        })
+    }
+
+    fn add_vertex(&mut self, point: Vec2) -> Result<()> {
+        let mut polygon = self.alg.polygon().to_vec();
+        polygon.push(point);
+        self.set_polygon(polygon)
+    }
+
+    /// Replaces the polygon and removes the circles.
+    fn set_polygon(&mut self, polygon: Vec<Vec2>) -> Result<()> {
+        self.outline.set_vertices(&polygon)?;
+        self.vertices.set_vertices(&polygon)?;
+        self.alg = CirclePacking::new(polygon);
+        self.packing_time = Duration::default();
+        self.exec_time.clear();
+        self.circles.clear()
+    }
+
+    /// Adds circles for the duration of a frame, stops running when a target is reached.
+    fn pack(&mut self) -> Result<()> {
+        let start_time = Instant::now();
+        let mut added = false;
+        while Instant::now() - start_time < FRAME_BUDGET {
+            let done = self.alg.circles().len() >= self.max_count as usize ||
+                       self.alg.coverage() >= self.target_coverage;
+            if done || self.alg.step(self.min_radius).is_none() {
+                self.running = false;
+                break;
+            }
+            added = true;
+        }
+
+        if added {
+            let duration = Instant::now() - start_time;
+            self.packing_time += duration;
+            self.exec_time.push(duration);
+
+            let lines = self.alg.circles()
+                                .iter()
+                                .flat_map(|circle| {
+                                    let points = circle.points(32).collect::<Vec<_>>();
+                                    (0..points.len()).flat_map(move |idx| vec![ points[idx], points[(idx + 1) % points.len()] ])
+                                })
+                                .collect::<Vec<_>>();
+            self.circles.set_vertices(&lines)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,333 @@
+use crate::{
+    Result,
+    algorithms::{ dbscan, k_means, Clustering, ClusterStatistics },
+    graphics::{ self, Shape, ColorMappedShape, Viewport, colormap },
+    math::Vec2,
+    scene::SharedScene,
+    ui::{ PointInput, ExecTimeHistory, window::algorithms::{ Drawable, Configurable } },
+};
+
+use std::time::Instant;
+
+use glium::{
+    index::PrimitiveType, Frame, Program,
+    backend::Facade,
+};
+use winit::{
+    event::{ Event, WindowEvent, MouseButton, ElementState },
+    window::Window,
+};
+
+/// Iterations of k-means, it usually converges long before
+const MAX_ITERATIONS: usize = 100;
+/// Number of blobs and points per blob added by the Clustered Points button
+const BLOBS: usize = 5;
+const BLOB_POINTS: usize = 60;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum Method {
+    Dbscan,
+    KMeans,
+}
+
+pub struct ClusteringRenderer<'f> {
+    /// The input points are those of the scene
+    scene: SharedScene,
+    revision: u64,
+    points: Vec<Vec2>,
+    method: Method,
+    /// Parameters of DBSCAN
+    radius: f32,
+    min_points: i32,
+    /// Parameter of k-means
+    k: i32,
+    clustering: Option<Clustering>,
+    statistics: Vec<ClusterStatistics>,
+    /// Cluster highlighted from the statistics panel
+    selected: Option<usize>,
+    show_hulls: bool,
+    program: Program,
+    clustered: ColorMappedShape<'f>,
+    noise: Shape<'f>,
+    hulls: ColorMappedShape<'f>,
+    /// Hull, diameter and centroid of the selected cluster
+    highlight: Shape<'f>,
+    diameter: Shape<'f>,
+    centroid: Shape<'f>,
+    exec_time: ExecTimeHistory,
+    point_input: PointInput<'f>,
+}
+
+impl<'f> Drawable for ClusteringRenderer<'f> {
+    fn update(&mut self) -> Result<()> {
+        if self.scene.borrow().revision() != self.revision {
+            self.compute()?;
+        }
+        Ok(())
+    }
+
+    fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        self.point_input.draw(target, viewport)?;
+        if self.show_hulls {
+            self.hulls.draw(target, viewport)?;
+        }
+        self.noise.draw(target, &self.program, viewport)?;
+        self.clustered.draw(target, viewport)?;
+        self.highlight.draw(target, &self.program, viewport)?;
+        self.diameter.draw(target, &self.program, viewport)?;
+        self.centroid.draw(target, &self.program, viewport)
+    }
+
+    fn handle_events(&mut self, window: &Window, event: &Event<()>, io: &imgui::Io, viewport: &Viewport) -> Result<()> {
+        if let Event::WindowEvent { event, .. } = event {
+            if let WindowEvent::MouseInput { button, state, .. } = event {
+                if !io.want_capture_mouse && // Ignore clicks when the cursor is over an ImGui window
+                    button == &MouseButton::Left && state == &ElementState::Pressed {
+
+                    // Add a point on click
+                    let coords = viewport.window_pos_to_normalized(io.mouse_pos.into(), window);
+                    self.scene.borrow_mut().add_point(self.point_input.place(coords));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'f> Configurable for ClusteringRenderer<'f> {
+    fn name(&self) -> &'static str {
+        "Clustering"
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        Some("Splits the points of the scene into clusters, drawn with their convex hulls. \
+              DBSCAN calls core points those with enough points within the radius, and grows each cluster \
+              from a core point to the points within the radius of its core points. \
+              The points out of reach of every core point are noise, drawn in gray. \
+              k-means starts from k points far from each other, then assigns every point to the closest center \
+              and moves each center to the centroid of its points, until no point changes cluster. \
+              Both find the neighbours with a k-d tree. The statistics of a cluster come from its hull, \
+              whose diameter is found with rotating calipers, click one of them to highlight the cluster. \
+              Left click to add points.")
+    }
+
+    fn pseudo_code(&self) -> Option<&'static str> {
+        Some(match self.method {
+            Method::Dbscan => concat!(
+                "for each unvisited point p:\n",
+                "    if |points within r of p| < m: continue\n",
+                "    new cluster C, queue = [p]\n",
+                "    while queue is not empty:\n",
+                "        q = pop(queue), add q to C\n",
+                "        if q was visited: continue\n",
+                "        mark q visited\n",
+                "        N = points within r of q\n",
+                "        if |N| >= m: push N to queue",
+            ),
+            Method::KMeans => concat!(
+                "centers = k points far from each other\n",
+                "repeat until no point changes cluster:\n",
+                "    for each point p:\n",
+                "        cluster(p) = closest center\n",
+                "    for each center c:\n",
+                "        c = centroid of its points",
+            ),
+        })
+    }
+
+    fn complexity(&self) -> Option<&'static str> {
+        Some(match self.method {
+            Method::Dbscan => "About O(n log n) for n points when the clusters are not too dense, one radius query per point",
+            Method::KMeans => "O(n log k) per iteration for n points and k clusters",
+        })
+    }
+
+    fn configure(&mut self, ui: &imgui::Ui) -> Result<()> {
+        if let Some(clustering) = &self.clustering {
+            let noise = clustering.labels.iter().filter(|label| label.is_none()).count();
+            ui.text(imgui::im_str!("{} points, {} clusters, {} noise points", self.points.len(), clustering.count, noise));
+        }
+
+        let dbscan = ui.radio_button(imgui::im_str!("DBSCAN"), &mut self.method, Method::Dbscan);
+        ui.same_line(0.0);
+        let k_means = ui.radio_button(imgui::im_str!("k-means"), &mut self.method, Method::KMeans);
+        let changed = match self.method {
+            Method::Dbscan => {
+                let radius = imgui::Slider::new(imgui::im_str!("Radius"), 0.005..=0.5).build(ui, &mut self.radius);
+                let min_points = ui.input_int(imgui::im_str!("Min points"), &mut self.min_points).build();
+                self.min_points = self.min_points.max(1);
+                radius || min_points
+            },
+            Method::KMeans => {
+                let k = ui.input_int(imgui::im_str!("k"), &mut self.k).build();
+                self.k = self.k.max(1);
+                k
+            },
+        };
+        if dbscan || k_means || changed {
+            self.compute()?;
+        }
+        ui.checkbox(imgui::im_str!("Hulls"), &mut self.show_hulls);
+
+        if !self.statistics.is_empty() && ui.collapsing_header(imgui::im_str!("Cluster statistics")).build() {
+            let mut clicked = None;
+            for (cluster, stats) in self.statistics.iter().enumerate() {
+                let centroid = stats.centroid.unwrap_or_default();
+                let diameter = stats.diameter.map_or(0.0, |(_, distance)| distance);
+                let label = imgui::im_str!("#{}: {} points, area {:.4}, centroid ({:.3}, {:.3}), diameter {:.3}",
+                                           cluster, stats.size, stats.area, centroid.x, centroid.y, diameter);
+                if imgui::Selectable::new(&label).selected(self.selected == Some(cluster)).build(ui) {
+                    clicked = Some(cluster);
+                }
+            }
+            if let Some(cluster) = clicked {
+                // Clicking the highlighted cluster again removes the highlight
+                self.selected = if self.selected == Some(cluster) { None } else { Some(cluster) };
+                self.highlight()?;
+            }
+        }
+
+        if ui.button(imgui::im_str!("Clustered Points"), [0.0, 0.0]) {
+            let mut points = Vec::with_capacity(BLOBS * BLOB_POINTS);
+            for _ in 0..BLOBS {
+                let center = Vec2::random_range(-0.7, 0.7, -0.7, 0.7);
+                let spread = 0.05 + rand::random::<f32>() * 0.1;
+                for _ in 0..BLOB_POINTS {
+                    // The sum of two uniform offsets is denser in the middle of the blob
+                    let offset = &Vec2::random_range(-spread, spread, -spread, spread) + &Vec2::random_range(-spread, spread, -spread, spread);
+                    points.push(&center + &offset);
+                }
+            }
+            self.scene.borrow_mut().add_points(points);
+        }
+        ui.same_line(0.0);
+        if ui.button(imgui::im_str!("Clear Points"), [0.0, 0.0]) {
+            self.scene.borrow_mut().clear_points();
+        }
+
+        if let Some(point) = self.point_input.configure(ui)? {
+            self.scene.borrow_mut().add_point(point);
+        }
+
+        self.exec_time.configure(ui);
+        Ok(())
+    }
+}
+
+impl<'f> ClusteringRenderer<'f> {
+    pub fn new(facade: &'f dyn Facade, scene: SharedScene) -> Result<Self> {
+        let vs = graphics::SHADERS._2d_vs;
+        let fs = graphics::SHADERS.basic_fs;
+        let program = Program::from_source(facade, vs, fs, None)?;
+
+        let mut clustered = ColorMappedShape::new(facade, PrimitiveType::Points)?;
+        clustered.size = 6.0;
+        let mut noise = Shape::new(facade, PrimitiveType::Points, [ 0.5, 0.5, 0.5 ])?;
+        noise.size = 4.0;
+        let mut hulls = ColorMappedShape::new(facade, PrimitiveType::LinesList)?;
+        hulls.size = 2.0;
+        let mut highlight = Shape::new(facade, PrimitiveType::LineLoop, [ 1.0, 1.0, 1.0 ])?;
+        highlight.size = 4.0;
+        let mut diameter = Shape::new(facade, PrimitiveType::LinesList, [ 1.0, 1.0, 1.0 ])?;
+        diameter.size = 2.0;
+        let mut centroid = Shape::new(facade, PrimitiveType::Points, [ 1.0, 1.0, 1.0 ])?;
+        centroid.size = 10.0;
+
+        Ok(Self {
+            scene,
+            revision: 0,
+            points: Vec::new(),
+            method: Method::Dbscan,
+            radius: 0.08,
+            min_points: 4,
+            k: 5,
+            clustering: None,
+            statistics: Vec::new(),
+            selected: None,
+            show_hulls: true,
+            program,
+            clustered,
+            noise,
+            hulls,
+            highlight,
+            diameter,
+            centroid,
+            exec_time: ExecTimeHistory::default(),
+            point_input: PointInput::new(facade)?,
+        })
+    }
+
+    /// Copies the points of the scene, clusters them and regenerates the buffers.
+    fn compute(&mut self) -> Result<()> {
+        let scene = self.scene.borrow();
+        self.revision = scene.revision();
+        self.points = scene.points().to_vec();
+        drop(scene);
+
+        let start_time = Instant::now();
+        let clustering = match self.method {
+            Method::Dbscan => dbscan(&self.points, self.radius, self.min_points as usize),
+            Method::KMeans => k_means(&self.points, self.k as usize, MAX_ITERATIONS),
+        };
+        self.exec_time.push(Instant::now() - start_time);
+
+        let (mut clustered, mut colors, mut noise) = (Vec::new(), Vec::new(), Vec::new());
+        for (&p, label) in self.points.iter().zip(&clustering.labels) {
+            match label {
+                Some(cluster) => {
+                    clustered.push(p);
+                    colors.push(colormap::category(*cluster));
+                },
+                None => noise.push(p),
+            }
+        }
+        self.clustered.set_colored_vertices(&clustered, &colors)?;
+        self.noise.set_vertices(&noise)?;
+
+        let (mut edges, mut edge_colors) = (Vec::new(), Vec::new());
+        let statistics = clustering.statistics(&self.points);
+        for (cluster, stats) in statistics.iter().enumerate() {
+            let hull = &stats.hull;
+            for (i, &idx) in hull.iter().enumerate() {
+                edges.push(self.points[idx]);
+                edges.push(self.points[hull[(i + 1) % hull.len()]]);
+                edge_colors.push(colormap::category(cluster));
+                edge_colors.push(colormap::category(cluster));
+            }
+        }
+        self.hulls.set_colored_vertices(&edges, &edge_colors)?;
+
+        self.clustering = Some(clustering);
+        self.statistics = statistics;
+        if self.selected.is_some_and(|cluster| cluster >= self.statistics.len()) {
+            self.selected = None;
+        }
+        self.highlight()
+    }
+
+    /// Regenerates the buffers of the selected cluster.
+    fn highlight(&mut self) -> Result<()> {
+        let stats = match self.selected.and_then(|cluster| self.statistics.get(cluster)) {
+            Some(stats) => stats,
+            None => {
+                self.highlight.clear()?;
+                self.diameter.clear()?;
+                return self.centroid.clear();
+            },
+        };
+        // Gather what is drawn first, since the statistics are borrowed from self
+        let hull = stats.hull.iter().map(|&idx| self.points[idx]).collect::<Vec<_>>();
+        let diameter = stats.diameter.map(|((a, b), _)| [ self.points[a], self.points[b] ]);
+        let centroid = stats.centroid;
+
+        self.highlight.set_vertices(&hull)?;
+        match diameter {
+            Some(diameter) => self.diameter.set_vertices(&diameter)?,
+            None => self.diameter.clear()?,
+        }
+        match centroid {
+            Some(centroid) => self.centroid.set_vertices(&[ centroid ]),
+            None => self.centroid.clear(),
+        }
+    }
+}
@@ -0,0 +1,268 @@
+use crate::{
+    Result,
+    graphics::{ self, Shape, Viewport },
+    math::{ Vec2, polygon },
+    spatial::SweepAndPrune,
+    ui::{ ExecTimeHistory, window::algorithms::{ Drawable, Configurable } },
+};
+
+use std::{ f32::consts::PI, time::Instant };
+
+use glium::{
+    index::PrimitiveType, Frame, Program,
+    backend::Facade,
+};
+use winit::{
+    event::{ Event, WindowEvent, MouseButton, ElementState },
+    window::Window,
+};
+
+/// The shapes bounce off the sides of this square
+const BOUNDS: f32 = 0.95;
+/// Range of the distances from the center of a shape to its vertices
+const MIN_RADIUS: f32 = 0.015;
+const MAX_RADIUS: f32 = 0.05;
+/// The frames longer than this are simulated as if they lasted this long, e.g. after the window was moved
+const MAX_TIME_STEP: f32 = 0.1;
+
+/// A convex polygon moving and spinning at a constant speed.
+struct Body {
+    /// Vertices relative to the center, in counter-clockwise order
+    shape: Vec<Vec2>,
+    position: Vec2,
+    velocity: Vec2,
+    angle: f32,
+    spin: f32,
+}
+
+impl Body {
+    /// Returns a random convex polygon centered on `position`, with 3 to 7 vertices on a circle.
+    fn random(position: Vec2) -> Self {
+        let radius = MIN_RADIUS + rand::random::<f32>() * (MAX_RADIUS - MIN_RADIUS);
+        let mut angles = (0..3 + rand::random::<usize>() % 5).map(|_| rand::random::<f32>() * 2.0 * PI).collect::<Vec<_>>();
+        angles.sort_by(|a, b| a.total_cmp(b));
+        Self {
+            shape: angles.iter().map(|&a| Vec2::new(radius * a.cos(), radius * a.sin())).collect(),
+            position,
+            velocity: Vec2::random_range(-0.2, 0.2, -0.2, 0.2),
+            angle: 0.0,
+            spin: (rand::random::<f32>() - 0.5) * PI,
+        }
+    }
+
+    /// Returns the vertices of the shape at its current position.
+    fn vertices(&self) -> Vec<Vec2> {
+        let (sin, cos) = self.angle.sin_cos();
+        self.shape
+            .iter()
+            .map(|p| Vec2::new(self.position.x + cos * p.x - sin * p.y, self.position.y + sin * p.x + cos * p.y))
+            .collect()
+    }
+
+    /// Moves the body during `dt` seconds, bouncing off the sides of the bounds.
+    fn step(&mut self, dt: f32) {
+        self.position = &self.position + &(dt * &self.velocity);
+        self.angle += dt * self.spin;
+        if self.position.x.abs() > BOUNDS {
+            self.velocity.x = -self.velocity.x.abs() * self.position.x.signum();
+        }
+        if self.position.y.abs() > BOUNDS {
+            self.velocity.y = -self.velocity.y.abs() * self.position.y.signum();
+        }
+    }
+}
+
+pub struct CollisionRenderer<'f> {
+    bodies: Vec<Body>,
+    sweep_and_prune: SweepAndPrune,
+    program: Program,
+    outlines: Shape<'f>,
+    colliding: Shape<'f>,
+    boxes: Shape<'f>,
+    count: i32,
+    speed: f32,
+    paused: bool,
+    show_boxes: bool,
+    last_update: Instant,
+    /// Pairs of shapes whose boxes overlap, and pairs of shapes that overlap
+    candidates: usize,
+    collisions: usize,
+    broad_phase_time: ExecTimeHistory,
+    narrow_phase_time: ExecTimeHistory,
+}
+
+impl<'f> Drawable for CollisionRenderer<'f> {
+    fn update(&mut self) -> Result<()> {
+        let now = Instant::now();
+        let dt = (now - self.last_update).as_secs_f32().min(MAX_TIME_STEP);
+        self.last_update = now;
+        if !self.paused {
+            for body in &mut self.bodies {
+                body.step(dt * self.speed);
+            }
+        }
+        self.detect()
+    }
+
+    fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        if self.show_boxes {
+            self.boxes.draw(target, &self.program, viewport)?;
+        }
+        self.outlines.draw(target, &self.program, viewport)?;
+        self.colliding.draw(target, &self.program, viewport)
+    }
+
+    fn handle_events(&mut self, window: &Window, event: &Event<()>, io: &imgui::Io, viewport: &Viewport) -> Result<()> {
+        if let Event::WindowEvent { event, .. } = event {
+            if let WindowEvent::MouseInput { button, state, .. } = event {
+                if !io.want_capture_mouse && // Ignore clicks when the cursor is over an ImGui window
+                    button == &MouseButton::Left && state == &ElementState::Pressed {
+
+                    // Throw a new shape on click
+                    let coords = viewport.window_pos_to_normalized(io.mouse_pos.into(), window);
+                    self.bodies.push(Body::random(coords));
+                    self.count = self.bodies.len() as i32;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'f> Configurable for CollisionRenderer<'f> {
+    fn name(&self) -> &'static str {
+        "Collision detection"
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        Some("Moving convex shapes are tested for collisions in two phases. The broad phase sweeps a line \
+              across the bounding boxes sorted by their left side, and only compares the boxes that the line crosses \
+              at the same time: since the shapes move a little between frames, the order of the previous frame \
+              is nearly right and an insertion sort fixes it in about linear time. The narrow phase tests \
+              the pairs of overlapping boxes with the separating axis theorem: two convex shapes are disjoint \
+              if and only if their projections on the normal of one of their edges are disjoint.")
+    }
+
+    fn pseudo_code(&self) -> Option<&'static str> {
+        Some(concat!(
+            "insertion sort the boxes by left side\n",
+            "for each box a, in this order:\n",
+            "    for each next box b while\n",
+            "          b.left <= a.right:\n",
+            "        if a and b overlap vertically:\n",
+            "            candidates += (a, b)\n",
+            "for each candidate (a, b):\n",
+            "    for each edge normal n of a and b:\n",
+            "        if the projections of a and b\n",
+            "              on n are disjoint:\n",
+            "            no collision\n",
+            "    collision",
+        ))
+    }
+
+    fn complexity(&self) -> Option<&'static str> {
+        Some("O(n + k) per frame for the broad phase when the order barely changes, k the number of candidate pairs, then O(m) per pair for m vertices")
+    }
+
+    fn configure(&mut self, ui: &imgui::Ui) -> Result<()> {
+        let n = self.bodies.len();
+        ui.text(imgui::im_str!("{} shapes, {} pairs", n, n * n.saturating_sub(1) / 2));
+        ui.text(imgui::im_str!("{} candidate pairs, {} collisions", self.candidates, self.collisions));
+
+        if imgui::Slider::new(imgui::im_str!("Shapes"), 0..=2000).build(ui, &mut self.count) {
+            self.reset();
+        }
+        imgui::Slider::new(imgui::im_str!("Speed"), 0.0..=5.0).build(ui, &mut self.speed);
+        ui.checkbox(imgui::im_str!("Pause"), &mut self.paused);
+        ui.checkbox(imgui::im_str!("Bounding boxes"), &mut self.show_boxes);
+        if ui.button(imgui::im_str!("Reset"), [0.0, 0.0]) {
+            self.reset();
+        }
+
+        ui.text(imgui::im_str!("Broad phase"));
+        self.broad_phase_time.configure(ui);
+        ui.text(imgui::im_str!("Narrow phase"));
+        self.narrow_phase_time.configure(ui);
+        Ok(())
+    }
+}
+
+impl<'f> CollisionRenderer<'f> {
+    pub fn new(facade: &'f dyn Facade) -> Result<Self> {
+        let vs = graphics::SHADERS._2d_vs;
+        let fs = graphics::SHADERS.basic_fs;
+        let program = Program::from_source(facade, vs, fs, None)?;
+
+        let mut colliding = Shape::new(facade, PrimitiveType::LinesList, [ 1.0, 0.0, 0.0 ])?;
+        colliding.size = 2.0;
+
+        let mut renderer = Self {
+            bodies: Vec::new(),
+            sweep_and_prune: SweepAndPrune::new(),
+            program,
+            outlines: Shape::new(facade, PrimitiveType::LinesList, [ 1.0, 1.0, 1.0 ])?,
+            colliding,
+            boxes: Shape::new(facade, PrimitiveType::LinesList, [ 0.3, 0.3, 0.6 ])?,
+            count: 200,
+            speed: 1.0,
+            paused: false,
+            show_boxes: false,
+            last_update: Instant::now(),
+            candidates: 0,
+            collisions: 0,
+            broad_phase_time: ExecTimeHistory::default(),
+            narrow_phase_time: ExecTimeHistory::default(),
+        };
+        renderer.reset();
+        Ok(renderer)
+    }
+
+    /// Replaces the shapes with `count` random ones.
+    fn reset(&mut self) {
+        self.bodies = (0..self.count.max(0))
+            .map(|_| Body::random(Vec2::random_range(-BOUNDS, BOUNDS, -BOUNDS, BOUNDS)))
+            .collect();
+        self.broad_phase_time.clear();
+        self.narrow_phase_time.clear();
+    }
+
+    /// Finds the colliding shapes and regenerates the buffers.
+    fn detect(&mut self) -> Result<()> {
+        let polygons = self.bodies.iter().map(Body::vertices).collect::<Vec<_>>();
+        let boxes = polygons.iter().map(|p| polygon::bounding_rect(p).expect("Empty shape")).collect::<Vec<_>>();
+
+        let start_time = Instant::now();
+        let candidates = self.sweep_and_prune.pairs(&boxes);
+        self.broad_phase_time.push(Instant::now() - start_time);
+
+        let start_time = Instant::now();
+        let collisions = candidates
+            .iter()
+            .filter(|&&(a, b)| polygon::convex_overlap(&polygons[a], &polygons[b]))
+            .collect::<Vec<_>>();
+        self.narrow_phase_time.push(Instant::now() - start_time);
+        self.candidates = candidates.len();
+        self.collisions = collisions.len();
+
+        let mut colliding = vec![ false; polygons.len() ];
+        for &&(a, b) in &collisions {
+            colliding[a] = true;
+            colliding[b] = true;
+        }
+        let outlines = |colliding_shapes: bool| {
+            polygons
+                .iter()
+                .zip(&colliding)
+                .filter(|&(_, &c)| c == colliding_shapes)
+                .flat_map(|(p, _)| polygon::edges(p).flat_map(|(a, b)| vec![ a, b ]).collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        };
+        self.outlines.set_vertices(&outlines(false))?;
+        self.colliding.set_vertices(&outlines(true))?;
+
+        let boxes = boxes.iter()
+                         .flat_map(|b| polygon::edges(&b.corners()).flat_map(|(a, b)| vec![ a, b ]).collect::<Vec<_>>())
+                         .collect::<Vec<_>>();
+        self.boxes.set_vertices(&boxes)
+    }
+}
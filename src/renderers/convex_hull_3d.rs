@@ -0,0 +1,1042 @@
+use crate::{
+    Result,
+    algorithms::{ Bounded, bounding::Sphere, convex_hull_3d::{ ConvexHull, IncrementalHull, Color } },
+    graphics::{ self, Viewport, stereo::Eye },
+    io::{ self, MeshFormat },
+    point_cloud::Distribution,
+    math::{ Vec2, Vec3, Segment2, ToArray, pca::{ self, PrincipalAxes3 } },
+    scene::{ Mesh, SharedScene },
+    ui::{ CameraPanel, ExecTimeHistory, SharedSettings, window::algorithms::{ Drawable, Configurable } },
+};
+
+use std::{
+    fs::File,
+    io::BufWriter,
+    path::Path,
+    time::{ Duration, Instant },
+};
+
+use glium::{
+    index, Surface, Frame, Program, VertexBuffer, IndexBuffer, Depth, DepthTest,
+    backend::Facade,
+};
+use cgmath::{ SquareMatrix, InnerSpace };
+use winit::{
+    event::{ Event, WindowEvent, DeviceEvent, MouseButton, ElementState },
+    window::Window,
+};
+
+/// Number of random points the scene starts with, and the maximum of the slider
+const DEFAULT_POINT_COUNT: i32 = 100;
+const MAX_POINT_COUNT: i32 = 10000;
+
+/// Number of lines of the grid that shows the construction plane along each direction
+const PLANE_LINES: usize = 11;
+
+/// Largest distance between the cursor and a picked point or axis of the gizmo, in half heights of the viewport
+const PICK_TOLERANCE: f32 = 0.03;
+/// Length of the axes of the gizmo relative to the distance of the camera, so that it keeps its size on the screen
+const GIZMO_SIZE: f32 = 0.15;
+/// Colors of the next point to insert and of its horizon while stepping
+const NEXT_POINT_COLOR: [f32; 3] = [ 1.0, 0.85, 0.1 ];
+const HORIZON_COLOR: [f32; 3] = [ 0.1, 0.9, 0.3 ];
+/// Depth added to the faces in normalized device coordinates, so that the edges are drawn over them
+const FACES_DEPTH_BIAS: f32 = 0.0005;
+
+/// Plane on which the clicked points are placed.
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum ConstructionPlane {
+    Xy,
+    Xz,
+    Yz,
+    /// Perpendicular to the direction of view
+    Camera,
+}
+
+impl ConstructionPlane {
+    const ALL: [ConstructionPlane; 4] = [ ConstructionPlane::Xy, ConstructionPlane::Xz, ConstructionPlane::Yz, ConstructionPlane::Camera ];
+
+    fn name(self) -> &'static str {
+        match self {
+            ConstructionPlane::Xy => "XY",
+            ConstructionPlane::Xz => "XZ",
+            ConstructionPlane::Yz => "YZ",
+            ConstructionPlane::Camera => "Facing the camera",
+        }
+    }
+}
+
+/// Axis of the translate gizmo being dragged.
+#[derive(Copy, Clone, Debug)]
+struct Drag {
+    axis: usize,
+    /// Position of the point when it was grabbed
+    start: Vec3,
+    /// Position of the grabbed point of the axis along it, from `start`
+    grab: f32,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Vertex {
+    position: Vec3,
+    normal: Vec3,
+}
+
+impl Vertex {
+    pub fn new(position: Vec3, normal: Vec3) -> Self {
+        Self {
+            position,
+            normal,
+        }
+    }
+}
+
+implement_vertex!(Vertex, position, normal);
+
+/// Vertex of the faces, edges and vertices of the construction being stepped through, in the color of their element
+#[derive(Copy, Clone, Debug)]
+struct ColoredVertex {
+    position: Vec3,
+    normal: Vec3,
+    color_diffuse: [f32; 3],
+}
+
+impl ColoredVertex {
+    fn new(position: Vec3, normal: Vec3, color_diffuse: [f32; 3]) -> Self {
+        Self {
+            position,
+            normal,
+            color_diffuse,
+        }
+    }
+}
+
+implement_vertex!(ColoredVertex, position, normal, color_diffuse);
+
+pub struct ConvexHullRenderer<'f> {
+    facade: &'f dyn Facade,
+    alg: ConvexHull,
+    /// The input points are the vertices of the meshes of the scene, the hull is written back to it
+    scene: SharedScene,
+    /// Revision of the scene the hull was computed from
+    revision: u64,
+    /// Provides the sensitivities of the camera
+    settings: SharedSettings,
+    cam: graphics::OrbitCamera,
+    /// Stereo rendering and the pose files of the camera
+    camera_panel: CameraPanel,
+    /// Bounding sphere of the points the camera last framed, it frames them again when they are replaced or moved
+    framed: Option<Sphere>,
+    axes: graphics::axes::Axes,
+    points_program: Program,
+    faces_program: Program,
+    /// Shades the faces in the color of their vertices, and draws the edges and vertices in theirs
+    colored_faces_program: Program,
+    colored_program: Program,
+    /// Buffer object that stores all the points
+    points_buffer: VertexBuffer<Vertex>,
+    faces_indices: IndexBuffer<u32>,
+    /// Construction being stepped through, the complete hull is shown when there is none
+    steps: Option<IncrementalHull>,
+    /// While playing, a point is inserted every `interval` seconds
+    playing: bool,
+    interval: f32,
+    last_step: Instant,
+    /// Each point not inserted yet joined to the centroid of each face it sees
+    conflicts_buffer: VertexBuffer<Vertex>,
+    show_conflicts: bool,
+    /// The faces, edges and vertices of the hull, colored by the next point to insert while stepping through its construction
+    colored_faces: VertexBuffer<ColoredVertex>,
+    colored_edges: VertexBuffer<ColoredVertex>,
+    colored_vertices: VertexBuffer<ColoredVertex>,
+    /// The next point to insert and the loop of its horizon, highlighted while stepping
+    next_point: VertexBuffer<ColoredVertex>,
+    horizon: VertexBuffer<ColoredVertex>,
+    show_colors: bool,
+    show_faces: bool,
+    show_edges: bool,
+    show_vertices: bool,
+    principal_axes: Option<PrincipalAxes3>,
+    /// Both ends of each principal axis, at two standard deviations from the centroid
+    axes_buffer: VertexBuffer<Vertex>,
+    show_principal_axes: bool,
+    exec_time: ExecTimeHistory,
+    rotate_model: bool,
+    is_rotating: bool,
+    model_matrix: cgmath::Matrix4<f32>,
+    /// Path of the file the hull is exported to, its extension chooses the format
+    export_path: imgui::ImString,
+    /// Shape and number of the random points that replace the meshes of the scene
+    distribution: Distribution,
+    point_count: i32,
+    /// While adding points, a click places a point where the ray under the cursor meets the construction plane
+    adding_points: bool,
+    plane: ConstructionPlane,
+    /// Coordinate of the XY, XZ or YZ plane along its normal
+    offset: f32,
+    /// Distance between the camera and the plane facing it
+    depth: f32,
+    /// Grid drawn on the construction plane while adding points
+    plane_buffer: VertexBuffer<Vertex>,
+    /// While moving points, a click selects the closest point under the cursor and its gizmo moves it along an axis
+    moving_points: bool,
+    selected: Option<usize>,
+    drag: Option<Drag>,
+    /// The selected point followed by the ends of the axes of its gizmo, each after the point
+    gizmo_buffer: VertexBuffer<Vertex>,
+}
+
+impl<'f> Drawable for ConvexHullRenderer<'f> {
+    fn update(&mut self) -> Result<()> {
+        let revision = self.scene.borrow().revision();
+        if revision != self.revision {
+            self.revision = revision;
+            let points = self.scene.borrow()
+                                    .meshes()
+                                    .iter()
+                                    .flat_map(|mesh| mesh.vertices.iter().cloned())
+                                    .collect();
+            self.set_points(points)?;
+        }
+
+        if self.playing && Instant::now() - self.last_step >= Duration::from_secs_f32(self.interval) {
+            self.last_step = Instant::now();
+            if !self.step()? {
+                self.playing = false;
+            }
+        }
+
+        // The plane facing the camera turns with it, and the gizmo follows its zoom
+        if self.adding_points {
+            self.update_plane()?;
+        }
+        if self.moving_points {
+            self.update_gizmo()?;
+        }
+        Ok(())
+    }
+
+    fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        for eye in self.camera_panel.stereo.eyes(viewport, &self.cam) {
+            eye.begin(target);
+            if self.show_vertices {
+                self.draw_points(target, &eye)?;
+            }
+            if self.show_faces {
+                self.draw_faces(target, &eye)?;
+            }
+            self.draw_graph(target, &eye)?;
+            if self.show_conflicts && self.steps.is_some() {
+                self.draw_lines(target, &eye, &self.conflicts_buffer, [ 0.35, 0.35, 0.35 ])?;
+            }
+            if self.adding_points {
+                self.draw_lines(target, &eye, &self.plane_buffer, [ 0.3, 0.5, 0.3 ])?;
+            }
+            if self.moving_points && self.selected.is_some() {
+                self.draw_gizmo(target, &eye)?;
+            }
+            if self.show_principal_axes {
+                self.draw_principal_axes(target, &eye)?;
+            }
+        }
+        self.axes.draw(target, viewport)
+    }
+
+    fn handle_events(&mut self, window: &Window, event: &Event<()>, io: &imgui::Io, viewport: &Viewport) -> Result<()> {
+        let camera = self.settings.borrow().camera.clone();
+        self.cam.sensitivity = Vec2::new(camera.rotation, camera.rotation);
+        self.cam.zoom_sensitivity = camera.zoom;
+        self.axes.set_sensitivity(self.cam.sensitivity);
+
+        self.cam.handle_events(window, event, io);
+        if !self.rotate_model {
+            self.axes.handle_events(window, event, io, viewport)?;
+        }
+
+        if let Event::WindowEvent { event, .. } = event {
+            if let WindowEvent::MouseInput { button, state, .. } = event {
+                if !io.want_capture_mouse && // Ignore clicks when the cursor is over an ImGui window
+                    button == &MouseButton::Left && self.rotate_model {
+
+                    self.is_rotating = state == &ElementState::Pressed;
+                }
+
+                if !io.want_capture_mouse && button == &MouseButton::Left && state == &ElementState::Pressed &&
+                    self.adding_points && viewport.contains(io.mouse_pos.into(), window) {
+
+                    let coords = viewport.window_pos_to_normalized(io.mouse_pos.into(), window);
+                    let (origin, direction) = self.cam.ray(coords, viewport.window_aspect_ratio(window));
+                    if let Some(point) = self.place(origin, direction) {
+                        self.scene.borrow_mut().add_vertex(point);
+                    }
+                }
+
+                if self.moving_points && button == &MouseButton::Left {
+                    match state {
+                        ElementState::Pressed if !io.want_capture_mouse && viewport.contains(io.mouse_pos.into(), window) => {
+                            let coords = viewport.window_pos_to_normalized(io.mouse_pos.into(), window);
+                            self.grab(coords, viewport.window_aspect_ratio(window));
+                        },
+                        ElementState::Released => self.drag = None,
+                        _ => {},
+                    }
+                }
+            }
+
+            if let WindowEvent::CursorMoved { .. } = event {
+                if let (Some(drag), Some(idx)) = (self.drag, self.selected) {
+                    let coords = viewport.window_pos_to_normalized(io.mouse_pos.into(), window);
+                    let (origin, direction) = self.cam.ray(coords, viewport.window_aspect_ratio(window));
+                    if let Some(t) = axis_parameter(drag.start, axis(drag.axis), origin, direction) {
+                        self.move_point(idx, (drag.start.0 + axis(drag.axis) * (t - drag.grab)).into())?;
+                    }
+                }
+            }
+        }
+
+        if let Event::DeviceEvent { event, .. } = event {
+            if let DeviceEvent::MouseMotion { delta } = event {
+                if self.rotate_model && self.is_rotating {
+                    self.model_matrix = self.model_matrix * cgmath::Matrix4::<f32>::from_angle_y(cgmath::Rad(delta.0 as f32 * 0.01));
+                    self.model_matrix = self.model_matrix * cgmath::Matrix4::<f32>::from_angle_x(cgmath::Rad(delta.1 as f32 * 0.01));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'f> Configurable for ConvexHullRenderer<'f> {
+    fn name(&self) -> &'static str {
+        "3D Convex hull"
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        Some("Incremental construction of the convex hull of a set of 3D points. Starting from a tetrahedron, \
+              each new point removes the faces it can see and connects itself to the horizon, \
+              the loop of edges separating the visible faces from the hidden ones. \
+              A conflict graph links each point not inserted yet to the faces it sees, so the visible faces are known without searching the hull. \
+              A point that sees a new face saw one of the two faces around its horizon edge, only their points are tested again. \
+              Stepping through the construction draws the conflicts as lines from each point to the faces it sees, \
+              and colors the hull by the next point, drawn in yellow: the faces it sees in red, the hidden ones in blue \
+              and the loop of the horizon between them in green, where the new faces will join the point.")
+    }
+
+    fn pseudo_code(&self) -> Option<&'static str> {
+        Some(concat!(
+            "shuffle the points\n",
+            "hull = tetrahedron of 4 points\n",
+            "for each other point q:\n",
+            "    conflicts(q) = faces of the hull seen by q\n",
+            "for each remaining point p:\n",
+            "    if conflicts(p) is empty: p is inside the hull\n",
+            "    for each horizon edge e of conflicts(p),\n",
+            "        between a visible face f and a hidden face g:\n",
+            "        h = face joining e to p\n",
+            "        for each q seeing f or g:\n",
+            "            if q sees h: add h to conflicts(q)\n",
+            "    remove the faces of conflicts(p)",
+        ))
+    }
+
+    fn complexity(&self) -> Option<&'static str> {
+        Some("Expected O(n log n) with the points inserted in random order, O(n²) in the worst case")
+    }
+
+    fn configure(&mut self, ui: &imgui::Ui) -> Result<()> {
+        ui.text(imgui::im_str!("{} vertices", self.alg.points().len()));
+        ui.text(imgui::im_str!("Volume: {:.4}, surface area: {:.4}", self.alg.volume(), self.alg.surface_area()));
+        if let Some(centroid) = self.alg.centroid() {
+            ui.text(imgui::im_str!("Centroid: ({:.3}, {:.3}, {:.3})", centroid.0.x, centroid.0.y, centroid.0.z));
+        }
+
+        if ui.checkbox(imgui::im_str!("Rotate model"), &mut self.rotate_model) {
+            self.update_camera_controls();
+        }
+
+        if ui.button(imgui::im_str!("Frame Points"), [0.0, 0.0]) {
+            self.frame();
+        }
+
+        let names = Distribution::ALL.iter()
+                                     .map(|distribution| imgui::ImString::new(distribution.name()))
+                                     .collect::<Vec<_>>();
+        let names = names.iter()
+                         .map(|name| name.as_ref())
+                         .collect::<Vec<&imgui::ImStr>>();
+        let mut idx = Distribution::ALL.iter().position(|&distribution| distribution == self.distribution).unwrap_or(0);
+        let mut generate = imgui::ComboBox::new(imgui::im_str!("Distribution")).build_simple_string(ui, &mut idx, &names[..]);
+        self.distribution = Distribution::ALL[idx];
+        generate |= imgui::Slider::new(imgui::im_str!("Points"), 4..=MAX_POINT_COUNT).build(ui, &mut self.point_count);
+        generate |= ui.button(imgui::im_str!("Random Points"), [0.0, 0.0]);
+        if generate {
+            self.generate_points();
+        }
+
+        if ui.checkbox(imgui::im_str!("Add points"), &mut self.adding_points) {
+            self.moving_points &= !self.adding_points;
+            self.update_camera_controls();
+            self.update_plane()?;
+        }
+        if self.adding_points {
+            let names = ConstructionPlane::ALL.iter()
+                                              .map(|plane| imgui::ImString::new(plane.name()))
+                                              .collect::<Vec<_>>();
+            let names = names.iter()
+                             .map(|name| name.as_ref())
+                             .collect::<Vec<&imgui::ImStr>>();
+            let mut idx = ConstructionPlane::ALL.iter().position(|&plane| plane == self.plane).unwrap_or(0);
+            if imgui::ComboBox::new(imgui::im_str!("Plane")).build_simple_string(ui, &mut idx, &names[..]) {
+                self.plane = ConstructionPlane::ALL[idx];
+                if self.plane == ConstructionPlane::Camera {
+                    self.depth = self.cam.distance(); // Through the target of the camera
+                }
+            }
+            match self.plane {
+                ConstructionPlane::Camera => imgui::Slider::new(imgui::im_str!("Depth"), 0.5..=50.0).build(ui, &mut self.depth),
+                _ => imgui::Slider::new(imgui::im_str!("Offset"), -5.0..=5.0).build(ui, &mut self.offset),
+            };
+            ui.text_disabled(imgui::im_str!("Click to add a point on the plane"));
+        }
+
+        if ui.checkbox(imgui::im_str!("Move points"), &mut self.moving_points) {
+            self.adding_points &= !self.moving_points;
+            self.drag = None;
+            self.update_camera_controls();
+        }
+        if self.moving_points {
+            match self.selected {
+                Some(idx) => {
+                    let p = self.alg.points()[idx];
+                    ui.text(imgui::im_str!("Point {}: ({:.3}, {:.3}, {:.3})", idx, p.0.x, p.0.y, p.0.z));
+                },
+                None => ui.text_disabled(imgui::im_str!("Click a point to select it, then drag the axes to move it")),
+            }
+        }
+
+        if ui.button(imgui::im_str!("Step"), [0.0, 0.0]) {
+            self.playing = false;
+            self.step()?;
+        }
+        ui.same_line(0.0);
+        ui.checkbox(imgui::im_str!("Play"), &mut self.playing);
+        ui.same_line(0.0);
+        if ui.button(imgui::im_str!("Reset"), [0.0, 0.0]) {
+            self.playing = false;
+            self.steps = None;
+            self.set_faces(self.hull_faces())?;
+            self.set_colors()?;
+        }
+        imgui::Slider::new(imgui::im_str!("Interval (s)"), 0.01..=1.0).build(ui, &mut self.interval);
+        ui.checkbox(imgui::im_str!("Conflicts"), &mut self.show_conflicts);
+        ui.same_line(0.0);
+        if ui.checkbox(imgui::im_str!("Colors"), &mut self.show_colors) {
+            self.set_colors()?;
+        }
+        ui.checkbox(imgui::im_str!("Faces"), &mut self.show_faces);
+        ui.same_line(0.0);
+        ui.checkbox(imgui::im_str!("Edges"), &mut self.show_edges);
+        ui.same_line(0.0);
+        ui.checkbox(imgui::im_str!("Vertices"), &mut self.show_vertices);
+        if self.show_colors && self.steps.is_some() {
+            ui.text_disabled(imgui::im_str!("Yellow: next point, red: faces it sees, blue: hidden from it, green: horizon"));
+        }
+        if let Some(steps) = &self.steps {
+            ui.text(imgui::im_str!("{} points left, {} faces, {} conflicts", steps.remaining(), steps.faces().count(), steps.conflicts().count()));
+            if let Some(p) = steps.next_point() {
+                let visible = steps.conflicts().filter(|&(q, _)| q == p).count();
+                ui.text(imgui::im_str!("Next point sees {} faces, horizon of {} edges", visible, steps.horizon().len()));
+            }
+        }
+
+        ui.checkbox(imgui::im_str!("Principal axes"), &mut self.show_principal_axes);
+        if let Some(axes) = self.principal_axes {
+            ui.text(imgui::im_str!("Variances: {:.4}, {:.4}, {:.4}", axes.variances[0], axes.variances[1], axes.variances[2]));
+            if ui.button(imgui::im_str!("Align to Principal Axes"), [0.0, 0.0]) {
+                self.scene.borrow_mut().transform_meshes(|p| axes.align(p));
+            }
+        }
+
+        ui.input_text(imgui::im_str!("Path"), &mut self.export_path).build();
+        if ui.button(imgui::im_str!("Export Mesh"), [0.0, 0.0]) {
+            self.export_mesh()?;
+        }
+        ui.text_disabled(imgui::im_str!("OBJ, STL, PLY or OFF, depending on the extension"));
+
+        self.camera_panel.configure(ui, &mut self.cam)?;
+        self.exec_time.configure(ui);
+        Ok(())
+    }
+}
+
+impl<'f> ConvexHullRenderer<'f> {
+    pub fn new(facade: &'f dyn Facade, scene: SharedScene, settings: SharedSettings) -> Result<Self> {
+        let vs = graphics::SHADERS._3d_vs;
+        let fs = graphics::SHADERS.basic_fs;
+        let points_program = Program::from_source(facade, vs, fs, None)?;
+
+        let vs = graphics::SHADERS._3d_vs;
+        let fs = graphics::SHADERS.phong_fs;
+        let faces_program = Program::from_source(facade, vs, fs, None)?;
+
+        let vs = graphics::SHADERS._3d_vs;
+        let fs = graphics::SHADERS.phong_color_fs;
+        let colored_faces_program = Program::from_source(facade, vs, fs, None)?;
+
+        let vs = graphics::SHADERS.obj_vs;
+        let fs = graphics::SHADERS.obj_fs;
+        let colored_program = Program::from_source(facade, vs, fs, None)?;
+
+        let mut export_path = imgui::ImString::with_capacity(256);
+        if let Some(dir) = &settings.borrow().export_directory {
+            export_path.push_str(&dir.join("hull.obj").to_string_lossy());
+        }
+        let camera_panel = CameraPanel::new(settings.clone(), "hull_camera.toml");
+
+        let mut renderer = Self {
+            facade,
+            alg: ConvexHull::new(),
+            scene,
+            revision: 0,
+            settings,
+            cam: graphics::OrbitCamera::new(10.0, Vec3::new(0.0, 0.0, 0.0), 45.0, 0.1, 100.0),
+            camera_panel,
+            framed: None,
+            axes: graphics::axes::Axes::new(facade)?,
+            points_program,
+            faces_program,
+            colored_faces_program,
+            colored_program,
+            points_buffer: VertexBuffer::empty(facade, 0)?,
+            faces_indices: IndexBuffer::empty(facade, index::PrimitiveType::TrianglesList, 0)?,
+            steps: None,
+            playing: false,
+            interval: 0.1,
+            last_step: Instant::now(),
+            conflicts_buffer: VertexBuffer::empty(facade, 0)?,
+            show_conflicts: true,
+            colored_faces: VertexBuffer::empty(facade, 0)?,
+            colored_edges: VertexBuffer::empty(facade, 0)?,
+            colored_vertices: VertexBuffer::empty(facade, 0)?,
+            next_point: VertexBuffer::empty(facade, 0)?,
+            horizon: VertexBuffer::empty(facade, 0)?,
+            show_colors: true,
+            show_faces: true,
+            show_edges: true,
+            show_vertices: true,
+            principal_axes: None,
+            axes_buffer: VertexBuffer::empty(facade, 0)?,
+            show_principal_axes: false,
+            exec_time: ExecTimeHistory::default(),
+            rotate_model: false,
+            is_rotating: false,
+            model_matrix: cgmath::Matrix4::<f32>::identity(),
+            export_path,
+            distribution: Distribution::Sphere,
+            point_count: DEFAULT_POINT_COUNT,
+            adding_points: false,
+            plane: ConstructionPlane::Xy,
+            offset: 0.0,
+            depth: 10.0,
+            plane_buffer: VertexBuffer::empty(facade, 0)?,
+            moving_points: false,
+            selected: None,
+            drag: None,
+            gizmo_buffer: VertexBuffer::empty(facade, 0)?,
+        };
+        if renderer.scene.borrow().meshes().is_empty() {
+            renderer.generate_points();
+        }
+        Ok(renderer)
+    }
+
+    /// Replaces the meshes of the scene with random points of the chosen distribution.
+    fn generate_points(&mut self) {
+        let mut scene = self.scene.borrow_mut();
+        scene.clear_meshes();
+        scene.add_mesh(Mesh {
+            vertices: self.distribution.generate(self.point_count as usize),
+            indices: Vec::new(),
+        });
+    }
+
+    fn draw_points(&self, target: &mut Frame, eye: &Eye) -> Result<()> {
+        let indices = index::NoIndices(index::PrimitiveType::Points);
+        let uniforms = uniform! {
+            model: cgmath::Matrix4::<f32>::identity().to_array(),
+            view: eye.view_matrix(&self.cam).to_array(),
+            projection: eye.projection_matrix(&self.cam, target).to_array(),
+            color: [ 0.0_f32, 0.2_f32, 1.0_f32 ],
+        };
+        let mut draw_params = eye.draw_parameters(target);
+        draw_params.point_size = Some(8.0);
+        draw_params.depth = Depth {
+            write: true,
+            test: DepthTest::IfLess,
+            .. Default::default()
+        };
+        target.draw(&self.points_buffer, indices, &self.points_program, &uniforms, &draw_params)?;
+        Ok(())
+    }
+
+    fn draw_faces(&self, target: &mut Frame, eye: &Eye) -> Result<()> {
+        let uniforms = uniform! {
+            model: self.model_matrix.to_array(),
+            view: eye.view_matrix(&self.cam).to_array(),
+            projection: eye.projection_matrix(&self.cam, target).to_array(),
+            viewPosition: self.cam.get_position().to_array(),
+            materialAmbient: [ 1.0_f32, 1.0_f32, 1.0_f32 ],
+            materialDiffuse: [ 1.0_f32, 1.0_f32, 1.0_f32 ],
+            materialSpecular: [ 0.5_f32, 0.5_f32, 0.5_f32 ],
+            materialShininess: 32.0_f32,
+            lightPosition: [ 5.0_f32, 1.0_f32, 0.0_f32 ],
+            lightAmbient: [ 0.2_f32, 0.2_f32, 0.2_f32 ],
+            lightDiffuse: [ 0.75_f32, 0.75_f32, 0.75_f32 ],
+            lightSpecular: [ 0.6_f32, 0.6_f32, 0.6_f32 ],
+            // Pushed back so that the edges drawn over them do not z-fight with them
+            depthBias: FACES_DEPTH_BIAS,
+        };
+        let mut draw_params = eye.draw_parameters(target);
+        draw_params.depth = Depth {
+            write: true,
+            test: DepthTest::IfLess,
+            .. Default::default()
+        };
+        if self.show_colors && self.steps.is_some() {
+            let indices = index::NoIndices(index::PrimitiveType::TrianglesList);
+            target.draw(&self.colored_faces, indices, &self.colored_faces_program, &uniforms, &draw_params)?;
+        } else {
+            target.draw(&self.points_buffer, &self.faces_indices, &self.faces_program, &uniforms, &draw_params)?;
+        }
+        Ok(())
+    }
+
+    /// Draws the edges of the hull, and the vertices of the construction being stepped through in their colors,
+    /// with the next point and its horizon over them.
+    fn draw_graph(&self, target: &mut Frame, eye: &Eye) -> Result<()> {
+        let uniforms = uniform! {
+            model: self.model_matrix.to_array(),
+            view: eye.view_matrix(&self.cam).to_array(),
+            projection: eye.projection_matrix(&self.cam, target).to_array(),
+        };
+        let mut draw_params = eye.draw_parameters(target);
+        draw_params.line_width = Some(2.0);
+        draw_params.point_size = Some(10.0);
+        draw_params.depth = Depth {
+            write: true,
+            test: DepthTest::IfLessOrEqual,
+            .. Default::default()
+        };
+        if self.show_edges {
+            let indices = index::NoIndices(index::PrimitiveType::LinesList);
+            target.draw(&self.colored_edges, indices, &self.colored_program, &uniforms, &draw_params)?;
+        }
+        // The vertices of the complete hull are among the points
+        if self.show_vertices && self.show_colors && self.steps.is_some() {
+            let indices = index::NoIndices(index::PrimitiveType::Points);
+            target.draw(&self.colored_vertices, indices, &self.colored_program, &uniforms, &draw_params)?;
+        }
+        if self.show_colors && self.steps.is_some() {
+            draw_params.line_width = Some(5.0);
+            draw_params.point_size = Some(16.0);
+            let indices = index::NoIndices(index::PrimitiveType::LineLoop);
+            target.draw(&self.horizon, indices, &self.colored_program, &uniforms, &draw_params)?;
+            let indices = index::NoIndices(index::PrimitiveType::Points);
+            target.draw(&self.next_point, indices, &self.colored_program, &uniforms, &draw_params)?;
+        }
+        Ok(())
+    }
+
+    /// Draws each principal axis of the points in the color of the matching axis of the frame: red, green and blue.
+    fn draw_principal_axes(&self, target: &mut Frame, eye: &Eye) -> Result<()> {
+        let indices = index::NoIndices(index::PrimitiveType::LinesList);
+        let colors = [ [ 1.0_f32, 0.0, 0.0 ], [ 0.0, 1.0, 0.0 ], [ 0.0, 0.0, 1.0 ] ];
+        let mut draw_params = eye.draw_parameters(target);
+        draw_params.line_width = Some(2.0);
+        for (i, &color) in colors.iter().enumerate() {
+            let uniforms = uniform! {
+                model: cgmath::Matrix4::<f32>::identity().to_array(),
+                view: eye.view_matrix(&self.cam).to_array(),
+                projection: eye.projection_matrix(&self.cam, target).to_array(),
+                color: color,
+            };
+            if let Some(axis) = self.axes_buffer.slice(2 * i..2 * i + 2) {
+                target.draw(axis, indices, &self.points_program, &uniforms, &draw_params)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Draws faint lines, like the conflicts of the construction being stepped through, without hiding what is behind them.
+    fn draw_lines(&self, target: &mut Frame, eye: &Eye, lines: &VertexBuffer<Vertex>, color: [f32; 3]) -> Result<()> {
+        let indices = index::NoIndices(index::PrimitiveType::LinesList);
+        let uniforms = uniform! {
+            model: cgmath::Matrix4::<f32>::identity().to_array(),
+            view: eye.view_matrix(&self.cam).to_array(),
+            projection: eye.projection_matrix(&self.cam, target).to_array(),
+            color: color,
+        };
+        let mut draw_params = eye.draw_parameters(target);
+        draw_params.line_width = Some(1.0);
+        draw_params.depth = Depth {
+            write: false,
+            test: DepthTest::IfLess,
+            .. Default::default()
+        };
+        target.draw(lines, indices, &self.points_program, &uniforms, &draw_params)?;
+        Ok(())
+    }
+
+    /// Returns the unit normal of the construction plane and its offset: the points of the plane satisfy `normal · x = offset`.
+    fn construction_plane(&self) -> (cgmath::Vector3<f32>, f32) {
+        match self.plane {
+            ConstructionPlane::Xy => (cgmath::vec3(0.0, 0.0, 1.0), self.offset),
+            ConstructionPlane::Xz => (cgmath::vec3(0.0, 1.0, 0.0), self.offset),
+            ConstructionPlane::Yz => (cgmath::vec3(1.0, 0.0, 0.0), self.offset),
+            ConstructionPlane::Camera => {
+                let normal = self.cam.get_direction().0;
+                (normal, normal.dot(self.cam.get_position().0) + self.depth)
+            },
+        }
+    }
+
+    /// Returns where the ray meets the construction plane, or `None` if it is parallel to the plane or points away from it.
+    fn place(&self, origin: Vec3, direction: Vec3) -> Option<Vec3> {
+        let (normal, offset) = self.construction_plane();
+        let along = normal.dot(direction.0);
+        if along.abs() < 1e-6 {
+            return None;
+        }
+        let t = (offset - normal.dot(origin.0)) / along;
+        if t < 0.0 {
+            return None;
+        }
+        Some((origin.0 + direction.0 * t).into())
+    }
+
+    /// Regenerates the grid of the construction plane, centered on the projection of the target of the camera
+    /// and as large as the points framed by the camera.
+    fn update_plane(&mut self) -> Result<()> {
+        let (normal, offset) = self.construction_plane();
+        let target = self.cam.target.0;
+        let center = target - normal * (normal.dot(target) - offset);
+        let axis = if normal.y.abs() < 0.9 { cgmath::vec3(0.0, 1.0, 0.0) } else { cgmath::vec3(1.0, 0.0, 0.0) };
+        let u = normal.cross(axis).normalize();
+        let v = normal.cross(u);
+        let half_size = self.framed.as_ref().map_or(1.0, |sphere| sphere.radius.max(0.1) * 1.2);
+
+        let mut lines = Vec::with_capacity(4 * PLANE_LINES);
+        for i in 0..PLANE_LINES {
+            let s = half_size * (2.0 * i as f32 / (PLANE_LINES - 1) as f32 - 1.0);
+            lines.extend_from_slice(&[
+                center + u * s - v * half_size, center + u * s + v * half_size,
+                center + v * s - u * half_size, center + v * s + u * half_size,
+            ]);
+        }
+        let lines = lines.into_iter().map(|p| Vertex::new(p.into(), Vec3::new(0.0, 0.0, 0.0))).collect::<Vec<_>>();
+        self.plane_buffer = VertexBuffer::new(self.facade, &lines)?;
+        Ok(())
+    }
+
+    /// Draws the selected point and the axes of its gizmo, in the colors of the axes of the frame, over the rest of the scene.
+    fn draw_gizmo(&self, target: &mut Frame, eye: &Eye) -> Result<()> {
+        let colors = [ [ 1.0_f32, 0.0, 0.0 ], [ 0.0, 1.0, 0.0 ], [ 0.0, 0.0, 1.0 ], [ 1.0, 0.9, 0.0 ] ];
+        let mut draw_params = eye.draw_parameters(target);
+        draw_params.line_width = Some(3.0);
+        draw_params.point_size = Some(12.0);
+        for (i, &color) in colors.iter().enumerate() {
+            let uniforms = uniform! {
+                model: cgmath::Matrix4::<f32>::identity().to_array(),
+                view: eye.view_matrix(&self.cam).to_array(),
+                projection: eye.projection_matrix(&self.cam, target).to_array(),
+                color: color,
+            };
+            let (vertices, primitive) = match i {
+                3 => (self.gizmo_buffer.slice(0..1), index::PrimitiveType::Points),
+                _ => (self.gizmo_buffer.slice(2 * i..2 * i + 2), index::PrimitiveType::LinesList),
+            };
+            if let Some(vertices) = vertices {
+                target.draw(vertices, index::NoIndices(primitive), &self.points_program, &uniforms, &draw_params)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Regenerates the gizmo of the selected point, empty if there is none.
+    fn update_gizmo(&mut self) -> Result<()> {
+        let size = GIZMO_SIZE * self.cam.distance();
+        let ends = self.selected.iter().flat_map(|&idx| {
+                let p = self.alg.points()[idx].0;
+                (0..3).flat_map(move |i| vec![ p, p + axis(i) * size ])
+            })
+            .map(|p| Vertex::new(p.into(), Vec3::new(0.0, 0.0, 0.0)))
+            .collect::<Vec<_>>();
+        self.gizmo_buffer = VertexBuffer::new(self.facade, &ends)?;
+        Ok(())
+    }
+
+    /// Grabs the axis of the gizmo under the cursor at `coords`, or else selects the point under it, if any.
+    fn grab(&mut self, coords: Vec2, aspect_ratio: f32) {
+        if let Some(idx) = self.selected {
+            let p = self.alg.points()[idx];
+            let size = GIZMO_SIZE * self.cam.distance();
+            let grabbed = (0..3).find(|&i| {
+                let end = (p.0 + axis(i) * size).into();
+                match (self.cam.project(p, aspect_ratio), self.cam.project(end, aspect_ratio)) {
+                    (Some(a), Some(b)) => screen_distance_to_segment(a, b, coords, aspect_ratio) < PICK_TOLERANCE,
+                    _ => false,
+                }
+            });
+            if let Some(i) = grabbed {
+                let (origin, direction) = self.cam.ray(coords, aspect_ratio);
+                if let Some(grab) = axis_parameter(p, axis(i), origin, direction) {
+                    self.drag = Some(Drag { axis: i, start: p, grab });
+                    return;
+                }
+            }
+        }
+        self.selected = self.pick(coords, aspect_ratio);
+    }
+
+    /// Returns the point closest to `coords` on the screen, if it is close enough to the cursor.
+    fn pick(&self, coords: Vec2, aspect_ratio: f32) -> Option<usize> {
+        self.alg.points()
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, &p)| self.cam.project(p, aspect_ratio).map(|q| (idx, screen_distance_to_segment(q, q, coords, aspect_ratio))))
+            .filter(|&(_, distance)| distance < PICK_TOLERANCE)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(idx, _)| idx)
+    }
+
+    /// Moves the point at `idx` in the scene and updates the hull, without framing the points again.
+    fn move_point(&mut self, idx: usize, point: Vec3) -> Result<()> {
+        self.scene.borrow_mut().move_vertex(idx, point);
+        // The hull follows the move here instead of being rebuilt from the scene
+        self.revision = self.scene.borrow().revision();
+
+        let start_time = Instant::now();
+        self.alg.move_point(idx, point);
+        self.exec_time.push(Instant::now() - start_time);
+
+        let points = self.alg.points().to_vec();
+        self.update_principal_axes(&points)?;
+        self.show_hull()
+    }
+
+    /// Lets the camera turn and zoom with the mouse unless it rotates the model or edits the points.
+    fn update_camera_controls(&mut self) {
+        self.cam.zoom = !self.rotate_model;
+        self.cam.rotate = !self.rotate_model && !self.adding_points && !self.moving_points;
+    }
+
+    /// Replaces the input points, builds their hull and stores it in the scene.
+    fn set_points(&mut self, points: Vec<Vec3>) -> Result<()> {
+        let sphere = points.bounding_circle();
+        if sphere.is_some() && sphere != self.framed {
+            self.framed = sphere;
+            self.frame();
+        }
+        self.selected = None;
+        self.drag = None;
+
+        self.update_principal_axes(&points)?;
+
+        let start_time = Instant::now();
+        self.alg.set_points(points);
+        self.exec_time.push(Instant::now() - start_time);
+        self.show_hull()
+    }
+
+    /// Computes the principal axes of the points and regenerates their buffer.
+    fn update_principal_axes(&mut self, points: &[Vec3]) -> Result<()> {
+        self.principal_axes = pca::principal_axes_3d(points);
+        let ends = self.principal_axes.iter().flat_map(|axes| {
+                (0..3).flat_map(move |i| {
+                    let half_axis = axes.axes[i].0 * 2.0 * axes.variances[i].sqrt();
+                    vec![ axes.centroid.0 - half_axis, axes.centroid.0 + half_axis ]
+                })
+            })
+            .map(|p| Vertex::new(p.into(), Vec3::new(0.0, 0.0, 0.0)))
+            .collect::<Vec<_>>();
+        self.axes_buffer = VertexBuffer::new(self.facade, &ends)?;
+        Ok(())
+    }
+
+    /// Shows the complete hull, stopping the construction being stepped through, and stores it in the scene.
+    fn show_hull(&mut self) -> Result<()> {
+        self.playing = false;
+        self.steps = None;
+        let hull = self.set_faces(self.hull_faces())?;
+        self.set_colors()?;
+        self.scene.borrow_mut().set_hull_3d(hull);
+        Ok(())
+    }
+
+    /// Returns the faces of the complete hull, 3 indices into the input points per face.
+    fn hull_faces(&self) -> Vec<u32> {
+        self.alg.graph().faces.iter().fold(vec![], |mut vec, indices| {
+            vec.push(indices.idx.0);
+            vec.push(indices.idx.1);
+            vec.push(indices.idx.2);
+            vec
+        })
+    }
+
+    /// Shows the faces, 3 indices into the input points per face, and returns them as a mesh of the input points.
+    fn set_faces(&mut self, faces: Vec<u32>) -> Result<Mesh> {
+        let mesh = Mesh {
+            vertices: self.alg.points().to_vec(),
+            indices: faces.iter().map(|&idx| idx as usize).collect(),
+        };
+        let vertices = mesh.vertices
+                            .iter()
+                            .zip(mesh.vertex_normals())
+                            .map(|(&p, n)| Vertex::new(p, n))
+                            .collect::<Vec<_>>();
+        self.points_buffer = VertexBuffer::new(self.facade, &vertices)?;
+        self.faces_indices = IndexBuffer::new(self.facade, index::PrimitiveType::TrianglesList, &faces)?;
+        Ok(mesh)
+    }
+
+    /// Inserts the next point of the construction being stepped through, or starts one from the tetrahedron if there is none.
+    /// Returns false once all the points are inserted, or if the points have no hull.
+    fn step(&mut self) -> Result<bool> {
+        let inserted = match &mut self.steps {
+            Some(steps) => steps.step().is_some(),
+            None => {
+                self.steps = IncrementalHull::new(self.alg.points());
+                self.steps.is_some()
+            },
+        };
+        let steps = match &self.steps {
+            Some(steps) => steps,
+            None => return Ok(false),
+        };
+
+        let faces = steps.faces().flat_map(|face| face.to_vec()).map(|idx| idx as u32).collect();
+        let points = self.alg.points();
+        let conflicts = steps.conflicts()
+            .flat_map(|(p, [ a, b, c ])| vec![ points[p].0, (points[a].0 + points[b].0 + points[c].0) / 3.0 ])
+            .map(|p| Vertex::new(p.into(), Vec3::new(0.0, 0.0, 0.0)))
+            .collect::<Vec<_>>();
+        self.conflicts_buffer = VertexBuffer::new(self.facade, &conflicts)?;
+        self.set_faces(faces)?;
+        self.set_colors()?;
+        Ok(inserted)
+    }
+
+    /// Regenerates the colored faces, edges and vertices from the graph of the construction being stepped through,
+    /// or of the complete hull if there is none. Everything is white when the colors are hidden.
+    fn set_colors(&mut self) -> Result<()> {
+        let stepped = self.steps.as_ref().map(IncrementalHull::graph);
+        let graph = stepped.as_ref().unwrap_or_else(|| self.alg.graph());
+        let show_colors = self.show_colors;
+        let color_of = |color, face| rgb(if show_colors { color } else { Color::White }, face);
+        let points = self.alg.points();
+        let no_normal = Vec3::new(0.0, 0.0, 0.0);
+        let faces = graph.faces
+            .iter()
+            .flat_map(|face| {
+                let (normal, color) = (face.normal(points), color_of(face.color, true));
+                vec![ face.idx.0, face.idx.1, face.idx.2 ].into_iter().map(move |idx| ColoredVertex::new(points[idx as usize], normal, color))
+            })
+            .collect::<Vec<_>>();
+        let edges = graph.edges
+            .iter()
+            .flat_map(|edge| {
+                let color = color_of(edge.color, false);
+                vec![ edge.idx.0, edge.idx.1 ].into_iter().map(move |idx| ColoredVertex::new(points[idx as usize], no_normal, color))
+            })
+            .collect::<Vec<_>>();
+        let vertices = graph.vertices
+            .iter()
+            .map(|vertex| ColoredVertex::new(points[vertex.idx as usize], no_normal, color_of(vertex.color, false)))
+            .collect::<Vec<_>>();
+        self.colored_faces = VertexBuffer::new(self.facade, &faces)?;
+        self.colored_edges = VertexBuffer::new(self.facade, &edges)?;
+        self.colored_vertices = VertexBuffer::new(self.facade, &vertices)?;
+
+        let (next_point, horizon) = match &self.steps {
+            Some(steps) => (steps.next_point(), steps.horizon()),
+            None => (None, Vec::new()),
+        };
+        let next_point = next_point.iter()
+            .map(|&p| ColoredVertex::new(points[p], no_normal, NEXT_POINT_COLOR))
+            .collect::<Vec<_>>();
+        let horizon = horizon.iter()
+            .map(|&v| ColoredVertex::new(points[v], no_normal, HORIZON_COLOR))
+            .collect::<Vec<_>>();
+        self.next_point = VertexBuffer::new(self.facade, &next_point)?;
+        self.horizon = VertexBuffer::new(self.facade, &horizon)?;
+        Ok(())
+    }
+
+    /// Points the camera at the bounding sphere of the points, so that they fill the view.
+    fn frame(&mut self) {
+        if let Some(sphere) = &self.framed {
+            self.cam.frame(sphere);
+        }
+    }
+
+    /// Writes the hull stored in the scene to the file at `export_path`.
+    fn export_mesh(&self) -> Result<()> {
+        let path = Path::new(self.export_path.to_str().trim());
+        let format = MeshFormat::from_path(path)?;
+
+        let scene = self.scene.borrow();
+        match scene.hull_3d() {
+            Some(hull) => {
+                io::write_mesh(BufWriter::new(File::create(path)?), hull, format)?;
+                log::info!("Exported the hull to {}", path.display());
+                self.settings.borrow_mut().export_directory = path.canonicalize().ok()
+                                                                .and_then(|path| path.parent().map(Path::to_owned));
+            },
+            None => log::warn!("No hull to export"),
+        }
+        Ok(())
+    }
+}
+
+/// Returns the color of an element of the hull under construction, lighter for the faces so that their edges stand out.
+fn rgb(color: Color, face: bool) -> [f32; 3] {
+    match (color, face) {
+        (Color::White, true) => [ 1.0, 1.0, 1.0 ],
+        (Color::White, false) => [ 1.0, 0.6, 0.1 ],
+        (Color::Red, true) => [ 1.0, 0.55, 0.5 ],
+        (Color::Red, false) => [ 0.9, 0.1, 0.1 ],
+        (Color::Blue, true) => [ 0.55, 0.65, 1.0 ],
+        (Color::Blue, false) => [ 0.1, 0.25, 0.9 ],
+        (Color::Purple, _) => [ 0.7, 0.15, 0.9 ],
+    }
+}
+
+/// Returns the unit vector along the axis `i` of the frame.
+fn axis(i: usize) -> cgmath::Vector3<f32> {
+    let mut axis = cgmath::vec3(0.0, 0.0, 0.0);
+    axis[i] = 1.0;
+    axis
+}
+
+/// Returns the position along the line through `point` with the unit direction `axis` of its point closest to the ray,
+/// or `None` if they are parallel.
+fn axis_parameter(point: Vec3, axis: cgmath::Vector3<f32>, origin: Vec3, direction: Vec3) -> Option<f32> {
+    let offset = point.0 - origin.0;
+    let cos = axis.dot(direction.0);
+    let denominator = 1.0 - cos * cos;
+    if denominator < 1e-6 {
+        return None;
+    }
+    Some((cos * direction.0.dot(offset) - axis.dot(offset)) / denominator)
+}
+
+/// Returns the distance between `p` and the segment (a, b) on the screen, in viewport coordinates
+/// with the horizontal ones scaled by the aspect ratio so that both axes have the same unit.
+fn screen_distance_to_segment(a: Vec2, b: Vec2, p: Vec2, aspect_ratio: f32) -> f32 {
+    let scale = |v: Vec2| Vec2::new(v.x * aspect_ratio, v.y);
+    let segment = Segment2::new(scale(a), scale(b));
+    if segment.is_degenerate() {
+        return (&scale(p) - &segment.a).length();
+    }
+    segment.distance_to_point(scale(p))
+}
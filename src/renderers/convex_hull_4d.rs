@@ -0,0 +1,293 @@
+use crate::{
+    Result,
+    algorithms::ConvexHullNd,
+    graphics::{ self, Shape, Viewport },
+    math::{ Vec2, float },
+    ui::{ ExecTimeHistory, window::algorithms::{ Drawable, Configurable } },
+};
+
+use std::time::Instant;
+
+use glium::{
+    index::PrimitiveType, Frame, Program,
+    backend::Facade,
+};
+use winit::{
+    event::{ Event, WindowEvent, MouseButton, ElementState },
+    window::Window,
+};
+
+/// Distance of the 4D eye from the origin along the w axis, the points are inside the unit 3-sphere
+const EYE_DISTANCE_4D: f32 = 3.0;
+/// Distance of the 3D eye from the origin along the z axis
+const EYE_DISTANCE_3D: f32 = 4.0;
+/// Radius on the screen of the projection of the unit 3-sphere, before the perspectives
+const SCALE: f32 = 0.4;
+/// Radians of 3D rotation per normalized unit of mouse drag
+const DRAG_SENSITIVITY: f32 = 2.0;
+const MAX_TIME_STEP: f32 = 0.1;
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Input {
+    /// The 16 corners of the 4D cube
+    Tesseract,
+    /// The 8 unit vectors along the axes and their opposites, the 4D octahedron
+    CrossPolytope,
+    /// Random points on the unit 3-sphere, all of them are vertices of the hull
+    Sphere,
+    /// Random points in the 4D cube
+    Cube,
+}
+
+pub struct ConvexHull4dRenderer<'f> {
+    input: Input,
+    /// Number of random points
+    count: i32,
+    points: Vec<[f32; 4]>,
+    hull: Option<ConvexHullNd>,
+    /// Edges and sorted vertices of the hull, kept for the projection of each frame
+    hull_edges: Vec<(usize, usize)>,
+    hull_vertices: Vec<usize>,
+    /// Angle of the rotation in the xw and yz planes, which animates the projection
+    angle: f32,
+    speed: f32,
+    paused: bool,
+    last_update: Instant,
+    /// Rotation of the projected 3D points around the y and x axes, dragged with the mouse
+    yaw: f32,
+    pitch: f32,
+    dragging: Option<Vec2>,
+    program: Program,
+    edges: Shape<'f>,
+    vertices: Shape<'f>,
+    inner_points: Shape<'f>,
+    exec_time: ExecTimeHistory,
+}
+
+impl<'f> Drawable for ConvexHull4dRenderer<'f> {
+    fn update(&mut self) -> Result<()> {
+        let now = Instant::now();
+        let dt = (now - self.last_update).as_secs_f32().min(MAX_TIME_STEP);
+        self.last_update = now;
+        if !self.paused {
+            self.angle += dt * self.speed;
+        }
+        self.project()
+    }
+
+    fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        self.inner_points.draw(target, &self.program, viewport)?;
+        self.edges.draw(target, &self.program, viewport)?;
+        self.vertices.draw(target, &self.program, viewport)
+    }
+
+    fn handle_events(&mut self, window: &Window, event: &Event<()>, io: &imgui::Io, viewport: &Viewport) -> Result<()> {
+        if let Event::WindowEvent { event, .. } = event {
+            let coords = viewport.window_pos_to_normalized(io.mouse_pos.into(), window);
+            match event {
+                WindowEvent::MouseInput { button: MouseButton::Left, state: ElementState::Pressed, .. } => {
+                    if !io.want_capture_mouse { // Ignore clicks when the cursor is over an ImGui window
+                        self.dragging = Some(coords);
+                    }
+                },
+                WindowEvent::MouseInput { button: MouseButton::Left, state: ElementState::Released, .. } => {
+                    self.dragging = None;
+                },
+                WindowEvent::CursorMoved { .. } => {
+                    // Turn the 3D projection
+                    if let Some(last) = self.dragging {
+                        let delta = &coords - &last;
+                        self.yaw += delta.x * DRAG_SENSITIVITY;
+                        self.pitch = crate::math::clamp(self.pitch - delta.y * DRAG_SENSITIVITY, -1.5, 1.5);
+                        self.dragging = Some(coords);
+                    }
+                },
+                _ => {},
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'f> Configurable for ConvexHull4dRenderer<'f> {
+    fn name(&self) -> &'static str {
+        "4D convex hull"
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        Some("Convex hull of points in four dimensions, whose facets are tetrahedra. \
+              The hull starts as a simplex of five points, then each point outside of it removes the facets it sees \
+              and is joined to the ridges on the horizon of these facets, the triangles shared with a hidden facet. \
+              The hull turns in the xw and yz planes, is projected into 3D by a perspective along the w axis, \
+              then onto the screen by a perspective along the z axis. \
+              The facets are the half-spaces n · x <= c whose intersection is the hull, \
+              which makes it a starting point for linear programs and feasibility problems. \
+              Drag with the mouse to turn the 3D projection.")
+    }
+
+    fn pseudo_code(&self) -> Option<&'static str> {
+        Some(concat!(
+            "hull = facets of a simplex of d + 1 points\n",
+            "for each other point p:\n",
+            "    visible = facets with n · p > c\n",
+            "    horizon = ridges of a single visible facet\n",
+            "    remove the visible facets\n",
+            "    for each ridge r on the horizon:\n",
+            "        add the facet through r and p",
+        ))
+    }
+
+    fn complexity(&self) -> Option<&'static str> {
+        Some("O(n f d³) for n points, f facets in d dimensions, the hull has O(n^⌊d/2⌋) facets")
+    }
+
+    fn configure(&mut self, ui: &imgui::Ui) -> Result<()> {
+        match &self.hull {
+            Some(hull) => ui.text(imgui::im_str!("{} points, {} vertices, {} edges, {} tetrahedra",
+                                                self.points.len(), self.hull_vertices.len(), self.hull_edges.len(), hull.facets().len())),
+            None => ui.text(imgui::im_str!("{} points in a hyperplane, no hull", self.points.len())),
+        }
+
+        let mut changed = ui.radio_button(imgui::im_str!("Tesseract"), &mut self.input, Input::Tesseract);
+        ui.same_line(0.0);
+        changed |= ui.radio_button(imgui::im_str!("16-cell"), &mut self.input, Input::CrossPolytope);
+        changed |= ui.radio_button(imgui::im_str!("On a 3-sphere"), &mut self.input, Input::Sphere);
+        ui.same_line(0.0);
+        changed |= ui.radio_button(imgui::im_str!("In a 4D cube"), &mut self.input, Input::Cube);
+        if self.input == Input::Sphere || self.input == Input::Cube {
+            if ui.input_int(imgui::im_str!("Points"), &mut self.count).build() {
+                self.count = self.count.clamp(5, 500);
+                changed = true;
+            }
+            changed |= ui.button(imgui::im_str!("Randomize"), [0.0, 0.0]);
+        }
+        if changed {
+            self.reset();
+        }
+
+        if ui.input_float(imgui::im_str!("Speed"), &mut self.speed).step(0.1).build() {
+            self.speed = crate::math::clamp(self.speed, 0.0, 2.0);
+        }
+        ui.checkbox(imgui::im_str!("Pause"), &mut self.paused);
+
+        self.exec_time.configure(ui);
+        Ok(())
+    }
+}
+
+impl<'f> ConvexHull4dRenderer<'f> {
+    pub fn new(facade: &'f dyn Facade) -> Result<Self> {
+        let vs = graphics::SHADERS._2d_vs;
+        let fs = graphics::SHADERS.basic_fs;
+        let program = Program::from_source(facade, vs, fs, None)?;
+
+        let mut edges = Shape::new(facade, PrimitiveType::LinesList, [ 0.3, 0.8, 1.0 ])?;
+        edges.size = 2.0;
+        let mut vertices = Shape::new(facade, PrimitiveType::Points, [ 1.0, 1.0, 1.0 ])?;
+        vertices.size = 6.0;
+        let mut inner_points = Shape::new(facade, PrimitiveType::Points, [ 0.5, 0.5, 0.5 ])?;
+        inner_points.size = 4.0;
+
+        let mut renderer = Self {
+            input: Input::Tesseract,
+            count: 50,
+            points: Vec::new(),
+            hull: None,
+            hull_edges: Vec::new(),
+            hull_vertices: Vec::new(),
+            angle: 0.0,
+            speed: 0.5,
+            paused: false,
+            last_update: Instant::now(),
+            yaw: 0.5,
+            pitch: 0.3,
+            dragging: None,
+            program,
+            edges,
+            vertices,
+            inner_points,
+            exec_time: ExecTimeHistory::default(),
+        };
+        renderer.reset();
+        Ok(renderer)
+    }
+
+    /// Generates the points of the input and computes their hull.
+    fn reset(&mut self) {
+        let count = self.count.max(0) as usize;
+        self.points = match self.input {
+            Input::Tesseract => (0..16)
+                .map(|i| {
+                    let corner = |bit: usize| if i & (1 << bit) == 0 { -0.5 } else { 0.5 };
+                    [ corner(0), corner(1), corner(2), corner(3) ]
+                })
+                .collect(),
+            Input::CrossPolytope => (0..8)
+                .map(|i| {
+                    let mut p = [ 0.0; 4 ];
+                    p[i / 2] = if i % 2 == 0 { 1.0 } else { -1.0 };
+                    p
+                })
+                .collect(),
+            Input::Sphere => (0..count).map(|_| random_on_sphere()).collect(),
+            Input::Cube => (0..count).map(|_| random_in_cube()).collect(),
+        };
+
+        let points = self.points.iter().map(|p| p.iter().map(|&x| f64::from(x)).collect()).collect::<Vec<_>>();
+        self.exec_time.clear();
+        let start_time = Instant::now();
+        self.hull = ConvexHullNd::new(&points);
+        self.exec_time.push(Instant::now() - start_time);
+        self.hull_edges = self.hull.as_ref().map(ConvexHullNd::edges).unwrap_or_default();
+        self.hull_vertices = self.hull.as_ref().map(ConvexHullNd::vertices).unwrap_or_default();
+    }
+
+    /// Projects the rotated points onto the screen and regenerates the buffers.
+    fn project(&mut self) -> Result<()> {
+        let (sin, cos) = (float::sin(self.angle), float::cos(self.angle));
+        let (sin_yaw, cos_yaw) = (float::sin(self.yaw), float::cos(self.yaw));
+        let (sin_pitch, cos_pitch) = (float::sin(self.pitch), float::cos(self.pitch));
+        let projected = self.points
+            .iter()
+            .map(|&[ x, y, z, w ]| {
+                // Double rotation in the xw and yz planes
+                let (x, w) = (cos * x - sin * w, sin * x + cos * w);
+                let (y, z) = (cos * y - sin * z, sin * y + cos * z);
+                // Perspective from the w axis into 3D
+                let factor = EYE_DISTANCE_4D / (EYE_DISTANCE_4D - w);
+                let (x, y, z) = (x * factor, y * factor, z * factor);
+                // Rotation of the 3D view, then perspective from the z axis onto the screen
+                let (x, z) = (cos_yaw * x + sin_yaw * z, -sin_yaw * x + cos_yaw * z);
+                let (y, z) = (cos_pitch * y - sin_pitch * z, sin_pitch * y + cos_pitch * z);
+                let factor = SCALE * EYE_DISTANCE_3D / (EYE_DISTANCE_3D - z);
+                Vec2::new(x * factor, y * factor)
+            })
+            .collect::<Vec<_>>();
+
+        let edges = self.hull_edges.iter().flat_map(|&(a, b)| vec![ projected[a], projected[b] ]).collect::<Vec<_>>();
+        self.edges.set_vertices(&edges)?;
+        self.vertices.set_vertices(&self.hull_vertices.iter().map(|&idx| projected[idx]).collect::<Vec<_>>())?;
+        let inner_points = (0..projected.len())
+            .filter(|idx| self.hull_vertices.binary_search(idx).is_err())
+            .map(|idx| projected[idx])
+            .collect::<Vec<_>>();
+        self.inner_points.set_vertices(&inner_points)
+    }
+}
+
+/// Returns a random point of the 4D cube [-0.5, 0.5]⁴.
+fn random_in_cube() -> [f32; 4] {
+    [ rand::random::<f32>() - 0.5, rand::random::<f32>() - 0.5, rand::random::<f32>() - 0.5, rand::random::<f32>() - 0.5 ]
+}
+
+/// Returns a random point of the unit 3-sphere, uniformly distributed.
+fn random_on_sphere() -> [f32; 4] {
+    // Points of the cube inside the ball are uniform in the ball, so their directions are uniform
+    loop {
+        let p = random_in_cube();
+        let length = float::sqrt(p.iter().map(|x| x * x).sum::<f32>());
+        if length > 0.01 && length <= 0.5 {
+            return [ p[0] / length, p[1] / length, p[2] / length, p[3] / length ];
+        }
+    }
+}
@@ -0,0 +1,300 @@
+use crate::{
+    Result,
+    algorithms::{ ConvexLayers, tukey_regions },
+    graphics::{ self, Shape, ColorMappedShape, Viewport, colormap },
+    math::{ Vec2, polygon },
+    scene::SharedScene,
+    ui::{ PointInput, ExecTimeHistory, window::algorithms::{ Drawable, Configurable } },
+};
+
+use std::time::Instant;
+
+use glium::{
+    index::PrimitiveType, Frame, Program,
+    backend::Facade,
+};
+use winit::{
+    event::{ Event, WindowEvent, MouseButton, ElementState },
+    window::Window,
+};
+
+/// The Tukey regions take O(n³) for n points, they are not computed for more points than this
+const MAX_TUKEY_POINTS: usize = 400;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum Method {
+    HullPeeling,
+    Tukey,
+}
+
+/// Region of the legend, one per depth
+struct Region {
+    polygon: Vec<Vec2>,
+    /// Number of points of this depth, or of this depth and deeper for the Tukey regions
+    points: usize,
+    area: f32,
+}
+
+pub struct DataDepthRenderer<'f> {
+    /// The input points are those of the scene
+    scene: SharedScene,
+    revision: u64,
+    points: Vec<Vec2>,
+    method: Method,
+    /// Nested regions from the outermost one
+    regions: Vec<Region>,
+    /// Centroid of the deepest region, the median of the points for this depth
+    median: Option<Vec2>,
+    filled: bool,
+    random_count: i32,
+    program: Program,
+    fill: ColorMappedShape<'f>,
+    outlines: ColorMappedShape<'f>,
+    point_shape: Shape<'f>,
+    median_shape: Shape<'f>,
+    exec_time: ExecTimeHistory,
+    point_input: PointInput<'f>,
+}
+
+impl<'f> Drawable for DataDepthRenderer<'f> {
+    fn update(&mut self) -> Result<()> {
+        if self.scene.borrow().revision() != self.revision {
+            self.compute()?;
+        }
+        Ok(())
+    }
+
+    fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        self.point_input.draw(target, viewport)?;
+        if self.filled {
+            self.fill.draw(target, viewport)?;
+        }
+        self.outlines.draw(target, viewport)?;
+        self.point_shape.draw(target, &self.program, viewport)?;
+        self.median_shape.draw(target, &self.program, viewport)
+    }
+
+    fn handle_events(&mut self, window: &Window, event: &Event<()>, io: &imgui::Io, viewport: &Viewport) -> Result<()> {
+        if let Event::WindowEvent { event, .. } = event {
+            if let WindowEvent::MouseInput { button, state, .. } = event {
+                if !io.want_capture_mouse && // Ignore clicks when the cursor is over an ImGui window
+                    button == &MouseButton::Left && state == &ElementState::Pressed {
+
+                    // Add a point on click
+                    let coords = viewport.window_pos_to_normalized(io.mouse_pos.into(), window);
+                    self.scene.borrow_mut().add_point(self.point_input.place(coords));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'f> Configurable for DataDepthRenderer<'f> {
+    fn name(&self) -> &'static str {
+        "Data depth"
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        Some("Ranks the points of the scene from the outside in, like the median ranks values. \
+              Hull peeling removes the convex hull of the points again and again, \
+              the depth of a point is the number of hulls removed before its own. \
+              The Tukey depth of a position is the smallest number of points in a half-plane containing it: \
+              the positions of depth k or more form a convex region, bounded by lines through two points \
+              with less than k points on their other side. The centroid of the deepest region is a median of the points, \
+              drawn in red. The legend lists the regions from the outermost one. Left click to add points.")
+    }
+
+    fn pseudo_code(&self) -> Option<&'static str> {
+        Some(match self.method {
+            Method::HullPeeling => concat!(
+                "depth = 0\n",
+                "while points is not empty:\n",
+                "    H = convex hull of points\n",
+                "    for each point p on H:\n",
+                "        depth(p) = depth\n",
+                "        remove p from points\n",
+                "    depth = depth + 1",
+            ),
+            Method::Tukey => concat!(
+                "region = bounding box of the points\n",
+                "for k = 1, 2, ...:\n",
+                "    for each line L through 2 points\n",
+                "        with k - 1 points strictly on a side:\n",
+                "        region = region on the other side of L\n",
+                "    if region is empty: stop\n",
+                "    D(k) = region",
+            ),
+        })
+    }
+
+    fn complexity(&self) -> Option<&'static str> {
+        Some(match self.method {
+            Method::HullPeeling => "O(n² log n) for n points, at most n layers of one O(n log n) Graham scan each",
+            Method::Tukey => "O(n³) for n points, counting the points on each side of the O(n²) lines through two points",
+        })
+    }
+
+    fn configure(&mut self, ui: &imgui::Ui) -> Result<()> {
+        ui.text(imgui::im_str!("{} points, {} depths", self.points.len(), self.regions.len()));
+        if let Some(median) = self.median {
+            ui.text(imgui::im_str!("Median: ({:.4}, {:.4})", median.x, median.y));
+        }
+        if self.method == Method::Tukey && self.points.len() > MAX_TUKEY_POINTS {
+            ui.text_colored([ 1.0, 0.3, 0.3, 1.0 ], imgui::im_str!("Too many points for the Tukey regions, at most {}", MAX_TUKEY_POINTS));
+        }
+
+        let peeling = ui.radio_button(imgui::im_str!("Hull peeling"), &mut self.method, Method::HullPeeling);
+        ui.same_line(0.0);
+        let tukey = ui.radio_button(imgui::im_str!("Tukey depth"), &mut self.method, Method::Tukey);
+        if peeling || tukey {
+            self.exec_time.clear();
+            self.compute()?;
+        }
+        ui.checkbox(imgui::im_str!("Filled"), &mut self.filled);
+
+        if !self.regions.is_empty() && ui.collapsing_header(imgui::im_str!("Legend")).build() {
+            for (depth, region) in self.regions.iter().enumerate() {
+                let [ r, g, b ] = self.color(depth);
+                let label = match self.method {
+                    Method::HullPeeling => imgui::im_str!("Depth {}: {} points, area {:.4}", depth, region.points, region.area),
+                    Method::Tukey => imgui::im_str!("Depth {}+: {} points, area {:.4}", depth + 1, region.points, region.area),
+                };
+                ui.text_colored([ r, g, b, 1.0 ], &label);
+            }
+        }
+
+        if ui.button(imgui::im_str!("Clear Points"), [0.0, 0.0]) {
+            self.scene.borrow_mut().clear_points();
+        }
+
+        ui.input_int(imgui::im_str!("Count"), &mut self.random_count).build();
+        if ui.button(imgui::im_str!("Random Points"), [0.0, 0.0]) {
+            // The sum of two uniform offsets is denser in the middle, which gives the depths a shape
+            self.scene.borrow_mut().add_points((0..self.random_count.max(0)).map(|_| {
+                &Vec2::random_range(-0.4, 0.4, -0.4, 0.4) + &Vec2::random_range(-0.4, 0.4, -0.4, 0.4)
+            }));
+        }
+
+        if let Some(point) = self.point_input.configure(ui)? {
+            self.scene.borrow_mut().add_point(point);
+        }
+
+        self.exec_time.configure(ui);
+        Ok(())
+    }
+}
+
+impl<'f> DataDepthRenderer<'f> {
+    pub fn new(facade: &'f dyn Facade, scene: SharedScene) -> Result<Self> {
+        let vs = graphics::SHADERS._2d_vs;
+        let fs = graphics::SHADERS.basic_fs;
+        let program = Program::from_source(facade, vs, fs, None)?;
+
+        let mut outlines = ColorMappedShape::new(facade, PrimitiveType::LinesList)?;
+        outlines.size = 2.0;
+        let mut point_shape = Shape::new(facade, PrimitiveType::Points, [ 1.0, 1.0, 1.0 ])?;
+        point_shape.size = 4.0;
+        let mut median_shape = Shape::new(facade, PrimitiveType::Points, [ 1.0, 0.2, 0.2 ])?;
+        median_shape.size = 12.0;
+
+        Ok(Self {
+            scene,
+            revision: 0,
+            points: Vec::new(),
+            method: Method::HullPeeling,
+            regions: Vec::new(),
+            median: None,
+            filled: true,
+            random_count: 200,
+            program,
+            fill: ColorMappedShape::new(facade, PrimitiveType::TrianglesList)?,
+            outlines,
+            point_shape,
+            median_shape,
+            exec_time: ExecTimeHistory::default(),
+            point_input: PointInput::new(facade)?,
+        })
+    }
+
+    /// Returns the color of the region of a depth, from the lowlands outside to the summits inside.
+    fn color(&self, depth: usize) -> [f32; 3] {
+        colormap::color(depth as f32 / (self.regions.len().max(2) - 1) as f32)
+    }
+
+    /// Copies the points of the scene, computes their depth regions and regenerates the buffers.
+    fn compute(&mut self) -> Result<()> {
+        let scene = self.scene.borrow();
+        self.revision = scene.revision();
+        self.points = scene.points().to_vec();
+        drop(scene);
+
+        let start_time = Instant::now();
+        self.regions = match self.method {
+            Method::HullPeeling => {
+                let layers = ConvexLayers::new(&self.points);
+                let mut counts = vec![ 0; layers.count() ];
+                for &depth in &layers.depths {
+                    counts[depth] += 1;
+                }
+                layers.layers
+                    .iter()
+                    .zip(counts)
+                    .map(|(layer, points)| {
+                        let polygon = layer.iter().map(|&idx| self.points[idx]).collect::<Vec<_>>();
+                        Region { area: polygon::signed_area(&polygon), polygon, points }
+                    })
+                    .collect()
+            },
+            Method::Tukey if self.points.len() <= MAX_TUKEY_POINTS => {
+                tukey_regions(&self.points)
+                    .into_iter()
+                    .map(|polygon| Region {
+                        points: self.points.iter().filter(|&&p| polygon::signed_distance(&polygon, p) > -1e-5).count(),
+                        area: polygon::signed_area(&polygon),
+                        polygon,
+                    })
+                    .collect()
+            },
+            Method::Tukey => Vec::new(),
+        };
+        if self.regions.is_empty() {
+            self.exec_time.clear();
+        } else {
+            self.exec_time.push(Instant::now() - start_time);
+        }
+
+        // The innermost peeling layer can be a single point or a segment, then its centroid is that of its corners
+        self.median = self.regions.last().and_then(|region| match self.method {
+            Method::HullPeeling if region.area == 0.0 => {
+                let sum = region.polygon.iter().fold(Vec2::new(0.0, 0.0), |sum, p| &sum + p);
+                Some(&sum / region.polygon.len() as f32)
+            },
+            _ => polygon::centroid(&region.polygon),
+        });
+
+        // The regions are nested, each one is drawn over the previous one
+        let (mut triangles, mut triangle_colors, mut edges, mut edge_colors) = (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+        for (depth, region) in self.regions.iter().enumerate() {
+            let color = self.color(depth);
+            // The outlines are lighter than the fill, to separate the regions
+            let outline = [ 0.5 + color[0] * 0.5, 0.5 + color[1] * 0.5, 0.5 + color[2] * 0.5 ];
+            let p = &region.polygon;
+            for i in 1..p.len().saturating_sub(1) {
+                triangles.extend_from_slice(&[ p[0], p[i], p[i + 1] ]);
+                triangle_colors.extend_from_slice(&[ color; 3 ]);
+            }
+            for i in 0..p.len() {
+                edges.extend_from_slice(&[ p[i], p[(i + 1) % p.len()] ]);
+                edge_colors.extend_from_slice(&[ outline; 2 ]);
+            }
+        }
+        self.fill.set_colored_vertices(&triangles, &triangle_colors)?;
+        self.outlines.set_colored_vertices(&edges, &edge_colors)?;
+        self.point_shape.set_vertices(&self.points)?;
+        match self.median {
+            Some(median) => self.median_shape.set_vertices(&[ median ]),
+            None => self.median_shape.clear(),
+        }
+    }
+}
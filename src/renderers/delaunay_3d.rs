@@ -0,0 +1,346 @@
+use crate::{
+    Result,
+    algorithms::{ Bounded, bounding::Box3, histogram, delaunay_3d::{ delaunay_3d, tetrahedron_qualities, dihedral_angles, TetrahedronQuality } },
+    graphics::{ self, Viewport, stereo::Eye },
+    math::{ Vec2, Vec3, ToArray },
+    point_cloud,
+    scene::SharedScene,
+    ui::{ CameraPanel, ExecTimeHistory, SharedSettings, window::algorithms::{ Drawable, Configurable } },
+};
+
+use std::time::Instant;
+
+use glium::{
+    index, Surface, Frame, Program, VertexBuffer, Depth, DepthTest,
+    backend::Facade,
+};
+use cgmath::{ SquareMatrix, InnerSpace };
+use winit::{
+    event::Event,
+    window::Window,
+};
+
+/// Largest number of points tetrahedralized, the vertices of larger meshes are decimated
+const MAX_POINTS: usize = 2000;
+/// Number of bins of the histograms of the dihedral angles and of the radius-edge ratios
+const HISTOGRAM_BINS: usize = 30;
+/// Radius-edge ratios above this one are counted in the last bin of their histogram
+const MAX_RADIUS_EDGE_RATIO: f32 = 3.0;
+
+#[derive(Copy, Clone, Debug)]
+struct Vertex {
+    position: Vec3,
+    normal: Vec3,
+}
+
+implement_vertex!(Vertex, position, normal);
+
+pub struct Delaunay3dRenderer<'f> {
+    facade: &'f dyn Facade,
+    /// The input points are the vertices of the meshes of the scene
+    scene: SharedScene,
+    /// Revision of the scene the tetrahedralization was computed from
+    revision: u64,
+    /// Provides the sensitivities of the camera
+    settings: SharedSettings,
+    cam: graphics::OrbitCamera,
+    /// Stereo rendering and the pose files of the camera
+    camera_panel: CameraPanel,
+    axes: graphics::axes::Axes,
+    points_program: Program,
+    faces_program: Program,
+    points: Vec<Vec3>,
+    /// Number of vertices of the meshes, more than the points if they were decimated
+    vertex_count: usize,
+    tetrahedra: Vec<[usize; 4]>,
+    qualities: Vec<TetrahedronQuality>,
+    /// All the dihedral angles of the tetrahedra, in degrees
+    dihedral_angles: Vec<f32>,
+    bounds: Option<Box3>,
+    points_buffer: VertexBuffer<Vertex>,
+    /// Faces of the tetrahedra that are shown, the slivers apart to draw them in another color
+    faces_buffer: VertexBuffer<Vertex>,
+    slivers_buffer: VertexBuffer<Vertex>,
+    /// While clipping, the tetrahedra whose centroid is on one side of a plane perpendicular to an axis are hidden
+    clipping: bool,
+    clip_axis: usize,
+    /// Position of the clipping plane between the smallest and the largest coordinate of the points along its axis, from 0 to 1
+    clip_position: f32,
+    /// Hides the tetrahedra below the plane instead of above it
+    flip: bool,
+    /// Scale of each tetrahedron around its centroid, the gaps between them show the faces inside
+    shrink: f32,
+    /// Tetrahedra with a smaller dihedral angle, in degrees, are highlighted as slivers
+    sliver_angle: f32,
+    exec_time: ExecTimeHistory,
+}
+
+impl<'f> Drawable for Delaunay3dRenderer<'f> {
+    fn update(&mut self) -> Result<()> {
+        let revision = self.scene.borrow().revision();
+        if revision != self.revision {
+            self.revision = revision;
+            let points = self.scene.borrow()
+                                    .meshes()
+                                    .iter()
+                                    .flat_map(|mesh| mesh.vertices.iter().cloned())
+                                    .collect::<Vec<_>>();
+            self.vertex_count = points.len();
+            self.set_points(point_cloud::decimate(&points, MAX_POINTS))?;
+        }
+        Ok(())
+    }
+
+    fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        for eye in self.camera_panel.stereo.eyes(viewport, &self.cam) {
+            eye.begin(target);
+            self.draw_points(target, &eye)?;
+            self.draw_faces(target, &eye, &self.faces_buffer, [ 0.8, 0.8, 0.8 ])?;
+            self.draw_faces(target, &eye, &self.slivers_buffer, [ 1.0, 0.25, 0.2 ])?;
+        }
+        self.axes.draw(target, viewport)
+    }
+
+    fn handle_events(&mut self, window: &Window, event: &Event<()>, io: &imgui::Io, viewport: &Viewport) -> Result<()> {
+        let camera = self.settings.borrow().camera.clone();
+        self.cam.sensitivity = Vec2::new(camera.rotation, camera.rotation);
+        self.cam.zoom_sensitivity = camera.zoom;
+        self.axes.set_sensitivity(self.cam.sensitivity);
+
+        self.cam.handle_events(window, event, io);
+        self.axes.handle_events(window, event, io, viewport)
+    }
+}
+
+impl<'f> Configurable for Delaunay3dRenderer<'f> {
+    fn name(&self) -> &'static str {
+        "3D Delaunay"
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        Some("Delaunay tetrahedralization of the vertices of the meshes: the circumscribed sphere of each tetrahedron contains no other point. \
+              Lifted onto the paraboloid w = x² + y² + z², a sphere becomes a hyperplane and the points inside of it fall below it, \
+              so the tetrahedra are the lower facets of the 4D convex hull of the lifted points. \
+              Unlike in 2D, the Delaunay tetrahedralization does not avoid slivers: flat tetrahedra with four nearly cospherical vertices, \
+              well shaped faces and a good radius-edge ratio, but a dihedral angle close to 0. \
+              A clipping plane hides the tetrahedra on one side to show the inside of the mesh.")
+    }
+
+    fn pseudo_code(&self) -> Option<&'static str> {
+        Some(concat!(
+            "for each point p:\n",
+            "    lift p to (p.x, p.y, p.z, |p|²)\n",
+            "hull = 4D convex hull of the lifted points\n",
+            "for each facet f of hull:\n",
+            "    if the normal of f points down the w axis:\n",
+            "        add the 4 vertices of f as a tetrahedron",
+        ))
+    }
+
+    fn complexity(&self) -> Option<&'static str> {
+        Some("O(n²) tetrahedra in the worst case, O(n) for points spread evenly in a volume")
+    }
+
+    fn configure(&mut self, ui: &imgui::Ui) -> Result<()> {
+        ui.text(imgui::im_str!("{} points, {} tetrahedra", self.points.len(), self.tetrahedra.len()));
+        if self.vertex_count > self.points.len() {
+            ui.text_disabled(imgui::im_str!("Decimated from the {} vertices of the meshes", self.vertex_count));
+        }
+
+        let mut changed = ui.checkbox(imgui::im_str!("Clip"), &mut self.clipping);
+        if self.clipping {
+            changed |= ui.radio_button(imgui::im_str!("X"), &mut self.clip_axis, 0);
+            ui.same_line(0.0);
+            changed |= ui.radio_button(imgui::im_str!("Y"), &mut self.clip_axis, 1);
+            ui.same_line(0.0);
+            changed |= ui.radio_button(imgui::im_str!("Z"), &mut self.clip_axis, 2);
+            changed |= imgui::Slider::new(imgui::im_str!("Position"), 0.0..=1.0).build(ui, &mut self.clip_position);
+            changed |= ui.checkbox(imgui::im_str!("Flip"), &mut self.flip);
+        }
+        changed |= imgui::Slider::new(imgui::im_str!("Shrink"), 0.5..=1.0).build(ui, &mut self.shrink);
+        changed |= imgui::Slider::new(imgui::im_str!("Sliver angle (°)"), 1.0..=30.0).build(ui, &mut self.sliver_angle);
+        if changed {
+            self.update_faces()?;
+        }
+
+        let sliver_angle = self.sliver_angle.to_radians();
+        let slivers = self.qualities.iter().filter(|quality| quality.min_dihedral < sliver_angle).count();
+        ui.text(imgui::im_str!("{} slivers", slivers));
+
+        if let Some((min, max)) = graphics::colormap::range(&self.dihedral_angles) {
+            ui.text(imgui::im_str!("Dihedral angles: {:.2}° to {:.2}°", min, max));
+            let counts = histogram(&self.dihedral_angles, HISTOGRAM_BINS, (0.0, 180.0))
+                            .into_iter()
+                            .map(|count| count as f32)
+                            .collect::<Vec<_>>();
+            ui.plot_histogram(imgui::im_str!("##dihedral_histogram"), &counts)
+                .scale_min(0.0)
+                .graph_size([0.0, 60.0])
+                .build();
+        }
+
+        let ratios = self.qualities.iter().map(|quality| quality.radius_edge_ratio).collect::<Vec<_>>();
+        if let Some((min, max)) = graphics::colormap::range(&ratios) {
+            ui.text(imgui::im_str!("Radius-edge ratios: {:.3} to {:.3}", min, max));
+            let counts = histogram(&ratios, HISTOGRAM_BINS, (0.0, MAX_RADIUS_EDGE_RATIO))
+                            .into_iter()
+                            .map(|count| count as f32)
+                            .collect::<Vec<_>>();
+            ui.plot_histogram(imgui::im_str!("##radius_edge_histogram"), &counts)
+                .scale_min(0.0)
+                .graph_size([0.0, 60.0])
+                .build();
+        }
+
+        self.camera_panel.configure(ui, &mut self.cam)?;
+        self.exec_time.configure(ui);
+        Ok(())
+    }
+}
+
+impl<'f> Delaunay3dRenderer<'f> {
+    pub fn new(facade: &'f dyn Facade, scene: SharedScene, settings: SharedSettings) -> Result<Self> {
+        let vs = graphics::SHADERS._3d_vs;
+        let fs = graphics::SHADERS.basic_fs;
+        let points_program = Program::from_source(facade, vs, fs, None)?;
+
+        let vs = graphics::SHADERS._3d_vs;
+        let fs = graphics::SHADERS.phong_fs;
+        let faces_program = Program::from_source(facade, vs, fs, None)?;
+        let camera_panel = CameraPanel::new(settings.clone(), "delaunay_3d_camera.toml");
+
+        Ok(Self {
+            facade,
+            scene,
+            revision: 0,
+            settings,
+            cam: graphics::OrbitCamera::new(10.0, Vec3::new(0.0, 0.0, 0.0), 45.0, 0.1, 100.0),
+            camera_panel,
+            axes: graphics::axes::Axes::new(facade)?,
+            points_program,
+            faces_program,
+            points: Vec::new(),
+            vertex_count: 0,
+            tetrahedra: Vec::new(),
+            qualities: Vec::new(),
+            dihedral_angles: Vec::new(),
+            bounds: None,
+            points_buffer: VertexBuffer::empty(facade, 0)?,
+            faces_buffer: VertexBuffer::empty(facade, 0)?,
+            slivers_buffer: VertexBuffer::empty(facade, 0)?,
+            clipping: false,
+            clip_axis: 0,
+            clip_position: 0.5,
+            flip: false,
+            shrink: 0.85,
+            sliver_angle: 10.0,
+            exec_time: ExecTimeHistory::default(),
+        })
+    }
+
+    fn draw_points(&self, target: &mut Frame, eye: &Eye) -> Result<()> {
+        let indices = index::NoIndices(index::PrimitiveType::Points);
+        let uniforms = uniform! {
+            model: cgmath::Matrix4::<f32>::identity().to_array(),
+            view: eye.view_matrix(&self.cam).to_array(),
+            projection: eye.projection_matrix(&self.cam, target).to_array(),
+            color: [ 0.0_f32, 0.2_f32, 1.0_f32 ],
+        };
+        let mut draw_params = eye.draw_parameters(target);
+        draw_params.point_size = Some(4.0);
+        draw_params.depth = Depth {
+            write: true,
+            test: DepthTest::IfLess,
+            .. Default::default()
+        };
+        target.draw(&self.points_buffer, indices, &self.points_program, &uniforms, &draw_params)?;
+        Ok(())
+    }
+
+    fn draw_faces(&self, target: &mut Frame, eye: &Eye, faces: &VertexBuffer<Vertex>, color: [f32; 3]) -> Result<()> {
+        let indices = index::NoIndices(index::PrimitiveType::TrianglesList);
+        let uniforms = uniform! {
+            model: cgmath::Matrix4::<f32>::identity().to_array(),
+            view: eye.view_matrix(&self.cam).to_array(),
+            projection: eye.projection_matrix(&self.cam, target).to_array(),
+            viewPosition: self.cam.get_position().to_array(),
+            materialAmbient: color,
+            materialDiffuse: color,
+            materialSpecular: [ 0.5_f32, 0.5_f32, 0.5_f32 ],
+            materialShininess: 32.0_f32,
+            lightPosition: [ 5.0_f32, 1.0_f32, 0.0_f32 ],
+            lightAmbient: [ 0.2_f32, 0.2_f32, 0.2_f32 ],
+            lightDiffuse: [ 0.75_f32, 0.75_f32, 0.75_f32 ],
+            lightSpecular: [ 0.6_f32, 0.6_f32, 0.6_f32 ],
+        };
+        let mut draw_params = eye.draw_parameters(target);
+        draw_params.depth = Depth {
+            write: true,
+            test: DepthTest::IfLess,
+            .. Default::default()
+        };
+        target.draw(faces, indices, &self.faces_program, &uniforms, &draw_params)?;
+        Ok(())
+    }
+
+    /// Replaces the input points, tetrahedralizes them and frames them with the camera.
+    fn set_points(&mut self, points: Vec<Vec3>) -> Result<()> {
+        if let Some(sphere) = points.bounding_circle() {
+            self.cam.frame(&sphere);
+        }
+        self.bounds = points.bounding_rect();
+
+        let start_time = Instant::now();
+        self.tetrahedra = delaunay_3d(&points);
+        self.exec_time.push(Instant::now() - start_time);
+
+        self.qualities = tetrahedron_qualities(&points, &self.tetrahedra);
+        self.dihedral_angles = self.tetrahedra
+            .iter()
+            .flat_map(|t| dihedral_angles(points[t[0]], points[t[1]], points[t[2]], points[t[3]]).to_vec())
+            .map(f32::to_degrees)
+            .collect();
+
+        let vertices = points.iter().map(|&p| Vertex { position: p, normal: Vec3::new(0.0, 0.0, 0.0) }).collect::<Vec<_>>();
+        self.points_buffer = VertexBuffer::new(self.facade, &vertices)?;
+        self.points = points;
+        self.update_faces()
+    }
+
+    /// Returns true if the tetrahedron is hidden by the clipping plane.
+    fn is_clipped(&self, t: &[usize; 4]) -> bool {
+        let bounds = match (&self.bounds, self.clipping) {
+            (Some(bounds), true) => bounds,
+            _ => return false,
+        };
+        let (min, max) = (bounds.min.0[self.clip_axis], bounds.max.0[self.clip_axis]);
+        let plane = min + (max - min) * self.clip_position;
+        let centroid = t.iter().map(|&idx| self.points[idx].0[self.clip_axis]).sum::<f32>() / 4.0;
+        (centroid > plane) != self.flip
+    }
+
+    /// Regenerates the faces of the tetrahedra that are not clipped, shrunk around their centroid.
+    fn update_faces(&mut self) -> Result<()> {
+        let sliver_angle = self.sliver_angle.to_radians();
+        let (mut faces, mut slivers) = (Vec::new(), Vec::new());
+        for (t, quality) in self.tetrahedra.iter().zip(&self.qualities) {
+            if self.is_clipped(t) {
+                continue;
+            }
+            let corners = [ self.points[t[0]].0, self.points[t[1]].0, self.points[t[2]].0, self.points[t[3]].0 ];
+            let centroid = (corners[0] + corners[1] + corners[2] + corners[3]) / 4.0;
+            let corners = [ 0, 1, 2, 3 ].map(|i| centroid + (corners[i] - centroid) * self.shrink);
+
+            let buffer = if quality.min_dihedral < sliver_angle { &mut slivers } else { &mut faces };
+            // The tetrahedra are positively oriented, so the faces opposite each vertex turn counterclockwise seen from outside
+            for &[ a, b, c ] in &[ [ 1, 2, 3 ], [ 0, 3, 2 ], [ 0, 1, 3 ], [ 0, 2, 1 ] ] {
+                let normal = (corners[b] - corners[a]).cross(corners[c] - corners[a]);
+                let normal = if normal.magnitude2() > 0.0 { normal.normalize() } else { normal };
+                buffer.extend([ a, b, c ].iter().map(|&i| Vertex { position: corners[i].into(), normal: normal.into() }));
+            }
+        }
+        self.faces_buffer = VertexBuffer::new(self.facade, &faces)?;
+        self.slivers_buffer = VertexBuffer::new(self.facade, &slivers)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,305 @@
+use crate::{
+    Result,
+    algorithms::DistanceField,
+    graphics::{ self, Shape, Viewport },
+    image::{ self, GrayImage },
+    math::{ Vec2, Rect },
+    scene::SharedScene,
+    ui::{ PointInput, ExecTimeHistory, SharedSettings, window::algorithms::{ Drawable, Configurable } },
+};
+
+use std::{ path::Path, time::Instant };
+
+use glium::{
+    index::{ NoIndices, PrimitiveType }, Surface, Frame, Program, VertexBuffer,
+    backend::Facade,
+};
+use winit::{
+    event::{ Event, WindowEvent, MouseButton, ElementState },
+    window::Window,
+};
+
+/// Most nodes offered by the slider along each side of the grid
+const MAX_RESOLUTION: i32 = 256;
+
+#[derive(Copy, Clone, Debug)]
+struct Vertex {
+    position: Vec2,
+    color: [f32; 3],
+}
+
+implement_vertex!(Vertex, position, color);
+
+/// How the distances are computed
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Method {
+    /// Distance to the boundary at every node
+    Exact,
+    /// Euclidean distance transform of the rasterized polygon
+    Transform,
+}
+
+pub struct DistanceFieldRenderer<'f> {
+    facade: &'f dyn Facade,
+    /// The last polygon of the scene is loaded when it changes
+    scene: SharedScene,
+    revision: u64,
+    settings: SharedSettings,
+    /// Left clicks add vertices
+    polygon: Vec<Vec2>,
+    method: Method,
+    /// Nodes along each side of the grid, which covers the [-1, 1] square
+    resolution: i32,
+    /// Distance at which the colors and the exported grays saturate
+    range: f32,
+    /// Draws the contours at multiples of a fifth of the range
+    show_isolines: bool,
+    field: Option<DistanceField>,
+    program: Program,
+    /// Draws the field with the color of its nodes
+    heatmap_program: Program,
+    heatmap_buffer: VertexBuffer<Vertex>,
+    boundary: Shape<'f>,
+    vertices: Shape<'f>,
+    zero_contour: Shape<'f>,
+    isolines: Shape<'f>,
+    /// Path of the PGM file the field is exported to
+    export_path: imgui::ImString,
+    exec_time: ExecTimeHistory,
+    point_input: PointInput<'f>,
+}
+
+impl<'f> Drawable for DistanceFieldRenderer<'f> {
+    fn update(&mut self) -> Result<()> {
+        let scene = self.scene.borrow();
+        if scene.revision() != self.revision {
+            self.revision = scene.revision();
+            if let Some(polygon) = scene.polygons().last() {
+                self.polygon = polygon.clone();
+                drop(scene);
+                self.compute()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        let draw_params = viewport.draw_parameters(target);
+        target.draw(&self.heatmap_buffer, NoIndices(PrimitiveType::TrianglesList), &self.heatmap_program,
+                    &glium::uniforms::EmptyUniforms, &draw_params)?;
+
+        self.point_input.draw(target, viewport)?;
+        if self.show_isolines {
+            self.isolines.draw(target, &self.program, viewport)?;
+        }
+        self.boundary.draw(target, &self.program, viewport)?;
+        self.zero_contour.draw(target, &self.program, viewport)?;
+        self.vertices.draw(target, &self.program, viewport)
+    }
+
+    fn handle_events(&mut self, window: &Window, event: &Event<()>, io: &imgui::Io, viewport: &Viewport) -> Result<()> {
+        if let Event::WindowEvent { event, .. } = event {
+            if let WindowEvent::MouseInput { button, state, .. } = event {
+                if !io.want_capture_mouse && // Ignore clicks when the cursor is over an ImGui window
+                    button == &MouseButton::Left && state == &ElementState::Pressed {
+
+                    // Add a vertex on click
+                    let coords = viewport.window_pos_to_normalized(io.mouse_pos.into(), window);
+                    self.polygon.push(self.point_input.place(coords));
+                    self.compute()?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'f> Configurable for DistanceFieldRenderer<'f> {
+    fn name(&self) -> &'static str {
+        "Signed distance field"
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        Some("Computes the signed distance from the nodes of a grid to the boundary of a polygon, positive inside \
+              and negative outside, and draws it as a heatmap: blue inside and orange outside, lighter near the boundary. \
+              The distances are either exact at every node, or approximated by the Euclidean distance transform \
+              of the nodes inside the polygon, whose cost does not depend on the number of vertices. \
+              The contour at 0, found with the marching squares, follows the boundary. \
+              The field can be exported as a PGM image, gray at 0 and white inside. \
+              Left click to add vertices, the last polygon of the scene is loaded when it changes.")
+    }
+
+    fn pseudo_code(&self) -> Option<&'static str> {
+        Some(concat!(
+            "exact:\n",
+            "    for each node p: d(p) = ±min over the edges of dist(p, edge)\n",
+            "transform:\n",
+            "    inside(p) = p is in the polygon\n",
+            "    for each column, then each row:\n",
+            "        lower envelope of the parabolas (p - q)² + f(q)\n",
+            "    d(p) = ±(distance to the nodes on the other side - half a cell)",
+        ))
+    }
+
+    fn complexity(&self) -> Option<&'static str> {
+        Some("O(g n) for g nodes and n vertices exactly, O(g n) to rasterize and O(g) for the distance transform")
+    }
+
+    fn configure(&mut self, ui: &imgui::Ui) -> Result<()> {
+        match &self.field {
+            Some(field) => ui.text(imgui::im_str!("{} vertices, {}x{} nodes", self.polygon.len(), field.width, field.height)),
+            None => ui.text(imgui::im_str!("{} vertices", self.polygon.len())),
+        }
+        let mut changed = ui.radio_button(imgui::im_str!("Exact"), &mut self.method, Method::Exact);
+        ui.same_line(0.0);
+        changed |= ui.radio_button(imgui::im_str!("Distance transform"), &mut self.method, Method::Transform);
+        changed |= imgui::Slider::new(imgui::im_str!("Resolution"), 8..=MAX_RESOLUTION).build(ui, &mut self.resolution);
+        if changed {
+            self.compute()?;
+        }
+        if imgui::Slider::new(imgui::im_str!("Range"), 0.05..=2.0).build(ui, &mut self.range) {
+            self.update_buffers()?;
+        }
+        ui.checkbox(imgui::im_str!("Isolines"), &mut self.show_isolines);
+
+        if ui.button(imgui::im_str!("Clear Polygon"), [0.0, 0.0]) {
+            self.polygon.clear();
+            self.compute()?;
+        }
+        ui.same_line(0.0);
+        if ui.button(imgui::im_str!("Add to Scene"), [0.0, 0.0]) && self.polygon.len() >= 3 {
+            self.scene.borrow_mut().add_polygon(self.polygon.clone());
+        }
+
+        ui.input_text(imgui::im_str!("Path"), &mut self.export_path).build();
+        if ui.button(imgui::im_str!("Export Image"), [0.0, 0.0]) {
+            self.export_image()?;
+        }
+
+        if let Some(point) = self.point_input.configure(ui)? {
+            self.polygon.push(point);
+            self.compute()?;
+        }
+
+        self.exec_time.configure(ui);
+        Ok(())
+    }
+}
+
+impl<'f> DistanceFieldRenderer<'f> {
+    pub fn new(facade: &'f dyn Facade, scene: SharedScene, settings: SharedSettings) -> Result<Self> {
+        let vs = graphics::SHADERS._2d_vs;
+        let fs = graphics::SHADERS.basic_fs;
+        let program = Program::from_source(facade, vs, fs, None)?;
+        let heatmap_program = Program::from_source(facade, graphics::SHADERS._2d_color_vs, graphics::SHADERS.color_fs, None)?;
+
+        let mut export_path = imgui::ImString::with_capacity(256);
+        if let Some(dir) = &settings.borrow().export_directory {
+            export_path.push_str(&dir.join("distance_field.pgm").to_string_lossy());
+        }
+
+        let mut zero_contour = Shape::new(facade, PrimitiveType::LinesList, [ 1.0, 1.0, 0.0 ])?;
+        zero_contour.size = 2.0;
+
+        Ok(Self {
+            facade,
+            scene,
+            revision: 0,
+            settings,
+            polygon: Vec::new(),
+            method: Method::Exact,
+            resolution: 128,
+            range: 0.5,
+            show_isolines: true,
+            field: None,
+            program,
+            heatmap_program,
+            heatmap_buffer: VertexBuffer::empty(facade, 0)?,
+            boundary: Shape::new(facade, PrimitiveType::LineLoop, [ 1.0, 1.0, 1.0 ])?,
+            vertices: Shape::new(facade, PrimitiveType::Points, [ 1.0, 0.4, 0.4 ])?,
+            zero_contour,
+            isolines: Shape::new(facade, PrimitiveType::LinesList, [ 0.2, 0.2, 0.2 ])?,
+            export_path,
+            exec_time: ExecTimeHistory::default(),
+            point_input: PointInput::new(facade)?,
+        })
+    }
+
+    /// Computes the field of the polygon and regenerates the buffers.
+    fn compute(&mut self) -> Result<()> {
+        self.boundary.set_vertices(&self.polygon)?;
+        self.vertices.set_vertices(&self.polygon)?;
+
+        let bounds = Rect::new(Vec2::new(-1.0, -1.0), Vec2::new(1.0, 1.0));
+        let resolution = self.resolution as usize;
+        let start_time = Instant::now();
+        self.field = match self.method {
+            Method::Exact => DistanceField::exact(&self.polygon, bounds, resolution),
+            Method::Transform => DistanceField::transform(&self.polygon, bounds, resolution),
+        };
+        if self.field.is_some() {
+            self.exec_time.push(Instant::now() - start_time);
+        } else {
+            self.exec_time.clear();
+        }
+        self.update_buffers()
+    }
+
+    /// Regenerates the buffers of the heatmap and of the contours, which depend on the range.
+    fn update_buffers(&mut self) -> Result<()> {
+        let field = match &self.field {
+            Some(field) => field,
+            None => {
+                self.heatmap_buffer = VertexBuffer::empty(self.facade, 0)?;
+                self.zero_contour.clear()?;
+                return self.isolines.clear();
+            },
+        };
+
+        // Two triangles per cell, the colors are interpolated between the nodes
+        let range = self.range;
+        let node = |x: usize, y: usize| Vertex { position: field.position(x, y), color: Self::color(field.value(x, y) / range) };
+        let mut vertices = Vec::with_capacity(field.width * field.height * 6);
+        for y in 0..field.height - 1 {
+            for x in 0..field.width - 1 {
+                let (a, b, c, d) = (node(x, y), node(x + 1, y), node(x + 1, y + 1), node(x, y + 1));
+                vertices.extend_from_slice(&[ a, b, c, a, c, d ]);
+            }
+        }
+        self.heatmap_buffer = VertexBuffer::new(self.facade, &vertices)?;
+
+        self.zero_contour.set_vertices(&field.contour(0.0))?;
+        let isolines = (-5..=5)
+            .filter(|&i| i != 0)
+            .flat_map(|i| field.contour(i as f32 * range / 5.0))
+            .collect::<Vec<_>>();
+        self.isolines.set_vertices(&isolines)
+    }
+
+    /// Returns the color of a distance relative to the range, from white at 0 to blue at 1 and to orange at -1.
+    fn color(t: f32) -> [f32; 3] {
+        let (inside, outside) = ([ 0.1, 0.3, 0.8 ], [ 0.9, 0.45, 0.1 ]);
+        let (far, t) = if t >= 0.0 { (inside, t.min(1.0)) } else { (outside, (-t).min(1.0)) };
+        let mut color = [ 0.0; 3 ];
+        for (channel, far) in color.iter_mut().zip(&far) {
+            *channel = 0.95 + (far - 0.95) * t;
+        }
+        color
+    }
+
+    /// Writes the field to the PGM file at `export_path`, from black at -range to white at +range.
+    fn export_image(&self) -> Result<()> {
+        let path = Path::new(self.export_path.to_str().trim());
+        match &self.field {
+            Some(field) => {
+                let img = GrayImage::from_values(field.width, field.height, &field.values, -self.range, self.range);
+                image::write(path, &img)?;
+                log::info!("Exported the distance field to {}", path.display());
+                self.settings.borrow_mut().export_directory = path.canonicalize().ok()
+                                                                .and_then(|path| path.parent().map(Path::to_owned));
+            },
+            None => log::warn!("No distance field to export"),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,327 @@
+use crate::{
+    Result,
+    graphics::{ self, Shape, Viewport },
+    math::{ Vec2, Segment2, Line, duality },
+    scene::SharedScene,
+    ui::{ PointInput, window::algorithms::{ Drawable, Configurable } },
+};
+
+use glium::{
+    index::PrimitiveType, Frame, Program,
+    backend::Facade,
+};
+use winit::{
+    event::{ Event, WindowEvent, MouseButton, ElementState },
+    window::Window,
+};
+
+/// Half of the length of the drawn lines, long enough to cross the viewport
+const LINE_EXTENT: f32 = 10.0;
+/// Distance from the cursor under which a point or the end of a segment is picked, in normalized coordinates
+const PICK_RADIUS: f32 = 0.04;
+
+/// A primitive of the primal plane that can be dragged.
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum Handle {
+    Point(usize),
+    /// An end of a segment, which defines a line: the first one if the flag is false
+    SegmentEnd(usize, bool),
+}
+
+pub struct DualityRenderer<'f> {
+    /// The primal points are those of the scene, its segments are extended to lines
+    scene: SharedScene,
+    revision: u64,
+    program: Program,
+    points: Shape<'f>,
+    lines: Shape<'f>,
+    /// Ends of the segments, dragged to move the lines
+    handles: Shape<'f>,
+    dual_lines: Shape<'f>,
+    dual_points: Shape<'f>,
+    /// The dragged primitive in the primal plane
+    selection: Shape<'f>,
+    /// The dual of the dragged primitive
+    dual_selection: Shape<'f>,
+    /// First end of the line being drawn
+    start: Shape<'f>,
+    pending: Option<Vec2>,
+    dragged: Option<Handle>,
+    /// Clicks add the ends of lines instead of points
+    add_lines: bool,
+    point_input: PointInput<'f>,
+}
+
+impl<'f> Drawable for DualityRenderer<'f> {
+    fn update(&mut self) -> Result<()> {
+        if self.scene.borrow().revision() != self.revision {
+            self.update_buffers()?;
+        }
+        Ok(())
+    }
+
+    fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        let (primal, dual) = Self::split(viewport);
+        self.point_input.draw(target, &primal)?;
+        self.lines.draw(target, &self.program, &primal)?;
+        self.selection.draw(target, &self.program, &primal)?;
+        self.points.draw(target, &self.program, &primal)?;
+        self.handles.draw(target, &self.program, &primal)?;
+        self.start.draw(target, &self.program, &primal)?;
+
+        self.dual_lines.draw(target, &self.program, &dual)?;
+        self.dual_selection.draw(target, &self.program, &dual)?;
+        self.dual_points.draw(target, &self.program, &dual)
+    }
+
+    fn handle_events(&mut self, window: &Window, event: &Event<()>, io: &imgui::Io, viewport: &Viewport) -> Result<()> {
+        let (primal, _) = Self::split(viewport);
+        if let Event::WindowEvent { event, .. } = event {
+            let coords = primal.window_pos_to_normalized(io.mouse_pos.into(), window);
+            match event {
+                WindowEvent::MouseInput { button: MouseButton::Left, state: ElementState::Pressed, .. } => {
+                    // Ignore clicks over an ImGui window or in the dual plane
+                    if !io.want_capture_mouse && primal.contains(io.mouse_pos.into(), window) {
+                        self.dragged = self.pick(coords);
+                        if self.dragged.is_none() {
+                            self.add(self.point_input.place(coords))?;
+                        }
+                    }
+                },
+                WindowEvent::MouseInput { button: MouseButton::Left, state: ElementState::Released, .. } => {
+                    self.dragged = None;
+                    self.update_selection()?;
+                },
+                WindowEvent::CursorMoved { .. } => {
+                    if let Some(handle) = self.dragged {
+                        self.drag(handle, self.point_input.place(coords));
+                    }
+                },
+                _ => {},
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'f> Configurable for DualityRenderer<'f> {
+    fn name(&self) -> &'static str {
+        "Point-line duality"
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        Some("The point (a, b) is dual to the line y = ax - b, and the line y = mx + c to the point (m, -c). \
+              The transform preserves incidence: the dual lines of collinear points meet at the dual point \
+              of the line through them. It reverses the order: a point above a line is dual to a line above \
+              the dual point. The primal plane is on the left and the dual plane on the right, \
+              drag the points and the ends of the lines to move them.")
+    }
+
+    fn pseudo_code(&self) -> Option<&'static str> {
+        Some(concat!(
+            "dual of the point (a, b) = line y = ax - b\n",
+            "dual of the line y = mx + c = point (m, -c)\n",
+            "p on l  <=>  l* on p*\n",
+            "p above l  <=>  l* above p*",
+        ))
+    }
+
+    fn complexity(&self) -> Option<&'static str> {
+        Some("O(1) per primitive")
+    }
+
+    fn configure(&mut self, ui: &imgui::Ui) -> Result<()> {
+        let scene = self.scene.borrow();
+        let lines = scene.segments().iter().filter_map(Line::from_segment).count();
+        ui.text(imgui::im_str!("{} points, {} lines", scene.points().len(), lines));
+        match self.dragged {
+            Some(Handle::Point(idx)) => {
+                if let Some(p) = scene.points().get(idx) {
+                    ui.text(imgui::im_str!("Point ({:.3}, {:.3})", p.x, p.y));
+                    ui.text(imgui::im_str!("Dual line y = {:.3}x + {:.3}", p.x, -p.y));
+                }
+            },
+            Some(Handle::SegmentEnd(idx, _)) => {
+                if let Some(line) = scene.segments().get(idx).and_then(Line::from_segment) {
+                    ui.text(imgui::im_str!("Line y = {:.3}x + {:.3}", line.slope, line.intercept));
+                    let dual = duality::dual_point(&line);
+                    ui.text(imgui::im_str!("Dual point ({:.3}, {:.3})", dual.x, dual.y));
+                }
+            },
+            None => {},
+        }
+        drop(scene);
+
+        ui.checkbox(imgui::im_str!("Add lines"), &mut self.add_lines);
+        if ui.button(imgui::im_str!("Clear Points"), [0.0, 0.0]) {
+            self.scene.borrow_mut().clear_points();
+        }
+        ui.same_line(0.0);
+        if ui.button(imgui::im_str!("Clear Lines"), [0.0, 0.0]) {
+            self.pending = None;
+            self.start.clear()?;
+            self.scene.borrow_mut().clear_segments();
+        }
+
+        if let Some(point) = self.point_input.configure(ui)? {
+            self.add(point)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'f> DualityRenderer<'f> {
+    pub fn new(facade: &'f dyn Facade, scene: SharedScene) -> Result<Self> {
+        let vs = graphics::SHADERS._2d_vs;
+        let fs = graphics::SHADERS.basic_fs;
+        let program = Program::from_source(facade, vs, fs, None)?;
+
+        let mut selection = Shape::new(facade, PrimitiveType::LinesList, [ 1.0, 0.8, 0.0 ])?;
+        selection.size = 3.0;
+        let mut dual_selection = Shape::new(facade, PrimitiveType::LinesList, [ 1.0, 0.8, 0.0 ])?;
+        dual_selection.size = 3.0;
+
+        Ok(Self {
+            scene,
+            revision: 0,
+            program,
+            points: Shape::new(facade, PrimitiveType::Points, [ 1.0, 1.0, 1.0 ])?,
+            lines: Shape::new(facade, PrimitiveType::LinesList, [ 0.0, 0.2, 1.0 ])?,
+            handles: Shape::new(facade, PrimitiveType::Points, [ 0.0, 0.2, 1.0 ])?,
+            dual_lines: Shape::new(facade, PrimitiveType::LinesList, [ 0.6, 0.6, 0.6 ])?,
+            dual_points: Shape::new(facade, PrimitiveType::Points, [ 0.0, 0.8, 0.0 ])?,
+            selection,
+            dual_selection,
+            start: Shape::new(facade, PrimitiveType::Points, [ 1.0, 0.8, 0.0 ])?,
+            pending: None,
+            dragged: None,
+            add_lines: false,
+            point_input: PointInput::new(facade)?,
+        })
+    }
+
+    /// Returns the left half of the viewport, for the primal plane, and the right half for the dual plane.
+    fn split(viewport: &Viewport) -> (Viewport, Viewport) {
+        let width = viewport.width / 2.0;
+        (Viewport::new(viewport.left, viewport.bottom, width, viewport.height),
+         Viewport::new(viewport.left + width, viewport.bottom, width, viewport.height))
+    }
+
+    /// Returns the points of a line to draw it as `LinesList`.
+    fn line_vertices(line: &Line) -> Vec<Vec2> {
+        let segment = line.segment(-LINE_EXTENT, LINE_EXTENT);
+        vec![ segment.a, segment.b ]
+    }
+
+    /// Returns the points of a cross centered on `p` to draw it as `LinesList`, to highlight a point.
+    fn cross(p: Vec2) -> Vec<Vec2> {
+        let size = PICK_RADIUS;
+        vec![
+            Vec2::new(p.x - size, p.y - size), Vec2::new(p.x + size, p.y + size),
+            Vec2::new(p.x - size, p.y + size), Vec2::new(p.x + size, p.y - size),
+        ]
+    }
+
+    /// Adds a point to the scene, or an end of a line if lines are being added.
+    fn add(&mut self, point: Vec2) -> Result<()> {
+        if !self.add_lines {
+            self.scene.borrow_mut().add_point(point);
+            return Ok(());
+        }
+        match self.pending.take() {
+            Some(start) => {
+                self.start.clear()?;
+                if start.x == point.x {
+                    log::warn!("Ignoring a vertical line, it has no dual");
+                } else {
+                    self.scene.borrow_mut().add_segment(Segment2::new(start, point));
+                }
+            },
+            None => {
+                self.pending = Some(point);
+                self.start.set_vertices(&[ point ])?;
+            },
+        }
+        Ok(())
+    }
+
+    /// Returns the point or the end of a segment closest to `coords`, if it is close enough.
+    fn pick(&self, coords: Vec2) -> Option<Handle> {
+        let scene = self.scene.borrow();
+        let points = scene.points().iter().enumerate().map(|(idx, &p)| (Handle::Point(idx), p));
+        let ends = scene.segments()
+                        .iter()
+                        .enumerate()
+                        .flat_map(|(idx, s)| vec![ (Handle::SegmentEnd(idx, false), s.a), (Handle::SegmentEnd(idx, true), s.b) ]);
+        points.chain(ends)
+            .map(|(handle, p)| (handle, (&p - &coords).length()))
+            .filter(|&(_, distance)| distance < PICK_RADIUS)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(handle, _)| handle)
+    }
+
+    /// Moves the dragged primitive to `coords`, the buffers are updated with the scene.
+    fn drag(&mut self, handle: Handle, coords: Vec2) {
+        let mut scene = self.scene.borrow_mut();
+        match handle {
+            Handle::Point(idx) => {
+                scene.move_point(idx, coords);
+            },
+            Handle::SegmentEnd(idx, end) => {
+                let mut segment = scene.segments()[idx];
+                if end { segment.b = coords; } else { segment.a = coords; }
+                if Line::from_segment(&segment).is_some() { // The line cannot become vertical
+                    scene.move_segment(idx, segment);
+                }
+            },
+        }
+    }
+
+    /// Regenerates the buffers of both planes from the scene.
+    fn update_buffers(&mut self) -> Result<()> {
+        let scene = self.scene.borrow();
+        self.revision = scene.revision();
+        // The dragged primitive may have been removed from the scene
+        let dragged_exists = match self.dragged {
+            Some(Handle::Point(idx)) => idx < scene.points().len(),
+            Some(Handle::SegmentEnd(idx, _)) => idx < scene.segments().len(),
+            None => true,
+        };
+        if !dragged_exists {
+            self.dragged = None;
+        }
+
+        let points = scene.points();
+        let lines = scene.segments().iter().filter_map(Line::from_segment).collect::<Vec<_>>();
+        self.points.set_vertices(points)?;
+        self.lines.set_vertices(&lines.iter().flat_map(Self::line_vertices).collect::<Vec<_>>())?;
+        self.handles.set_vertices(&scene.segments().iter().flat_map(|s| vec![ s.a, s.b ]).collect::<Vec<_>>())?;
+
+        let dual_lines = duality::dual_lines(points).flat_map(|line| Self::line_vertices(&line)).collect::<Vec<_>>();
+        self.dual_lines.set_vertices(&dual_lines)?;
+        self.dual_points.set_vertices(&duality::dual_points(&lines).collect::<Vec<_>>())?;
+        drop(scene);
+        self.update_selection()
+    }
+
+    /// Highlights the dragged primitive and its dual.
+    fn update_selection(&mut self) -> Result<()> {
+        let scene = self.scene.borrow();
+        let (selection, dual_selection) = match self.dragged {
+            Some(Handle::Point(idx)) => {
+                let p = scene.points()[idx];
+                (Self::cross(p), Self::line_vertices(&duality::dual_line(p)))
+            },
+            Some(Handle::SegmentEnd(idx, _)) => match Line::from_segment(&scene.segments()[idx]) {
+                Some(line) => {
+                    let p = duality::dual_point(&line);
+                    (Self::line_vertices(&line), Self::cross(p))
+                },
+                None => (Vec::new(), Vec::new()),
+            },
+            None => (Vec::new(), Vec::new()),
+        };
+        self.selection.set_vertices(&selection)?;
+        self.dual_selection.set_vertices(&dual_selection)
+    }
+}
@@ -0,0 +1,190 @@
+use crate::{
+    Result,
+    algorithms::{ FarthestPointVoronoi, Degeneracy },
+    graphics::{ self, Shape, Viewport },
+    scene::SharedScene,
+    ui::{ PointInput, ExecTimeHistory, window::algorithms::{ Drawable, Configurable } },
+};
+
+use std::time::Instant;
+
+use glium::{
+    index::PrimitiveType, Frame, Program,
+    backend::Facade,
+};
+use winit::{
+    event::{ Event, WindowEvent, MouseButton, ElementState },
+    window::Window,
+};
+
+/// Length of the rays of the diagram, long enough to cross the viewport
+const RAY_LENGTH: f32 = 10.0;
+
+pub struct FarthestPointVoronoiRenderer<'f> {
+    alg: FarthestPointVoronoi,
+    /// The input points are those of the scene
+    scene: SharedScene,
+    /// Revision of the scene the diagram was computed from
+    revision: u64,
+    program: Program,
+    points: Shape<'f>,
+    /// Points on the hull, the only ones that have a cell
+    sites: Shape<'f>,
+    triangles: Shape<'f>,
+    diagram: Shape<'f>,
+    circle: Shape<'f>,
+    show_triangulation: bool,
+    show_diagram: bool,
+    show_circle: bool,
+    exec_time: ExecTimeHistory,
+    point_input: PointInput<'f>,
+}
+
+impl<'f> Drawable for FarthestPointVoronoiRenderer<'f> {
+    fn update(&mut self) -> Result<()> {
+        if self.scene.borrow().revision() != self.revision {
+            self.compute()?;
+        }
+        Ok(())
+    }
+
+    fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        self.point_input.draw(target, viewport)?;
+        if self.show_triangulation {
+            self.triangles.draw(target, &self.program, viewport)?;
+        }
+        if self.show_diagram {
+            self.diagram.draw(target, &self.program, viewport)?;
+        }
+        if self.show_circle {
+            self.circle.draw(target, &self.program, viewport)?;
+        }
+        self.points.draw(target, &self.program, viewport)?;
+        self.sites.draw(target, &self.program, viewport)
+    }
+
+    fn handle_events(&mut self, window: &Window, event: &Event<()>, io: &imgui::Io, viewport: &Viewport) -> Result<()> {
+        if let Event::WindowEvent { event, .. } = event {
+            if let WindowEvent::MouseInput { button, state, .. } = event {
+                if !io.want_capture_mouse && // Ignore clicks when the cursor is over an ImGui window
+                    button == &MouseButton::Left && state == &ElementState::Pressed {
+
+                    // Add a point on click
+                    let coords = viewport.window_pos_to_normalized(io.mouse_pos.into(), window);
+                    self.scene.borrow_mut().add_point(self.point_input.place(coords));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'f> Configurable for FarthestPointVoronoiRenderer<'f> {
+    fn name(&self) -> &'static str {
+        "Farthest-point Voronoi"
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        Some("The cell of a point contains the positions for which it is the farthest input point, \
+              so only the points on the convex hull have a cell and all the cells are unbounded. \
+              The dual farthest-point Delaunay triangulation triangulates the hull so that the circumcircle \
+              of every triangle contains all the points. The center of the minimum enclosing circle \
+              lies on the diagram, at a vertex or in the middle of the two points of an edge.")
+    }
+
+    fn pseudo_code(&self) -> Option<&'static str> {
+        Some(concat!(
+            "polygon = convex hull of the points\n",
+            "while polygon has 3 vertices or more:\n",
+            "    v = vertex whose circumcircle with its\n",
+            "        neighbours is the largest\n",
+            "    add the triangle (prev(v), v, next(v))\n",
+            "    remove v from polygon\n",
+            "link the circumcenters of adjacent triangles",
+        ))
+    }
+
+    fn complexity(&self) -> Option<&'static str> {
+        Some("O(n log n) for the hull, then O(h²) for the triangulation")
+    }
+
+    fn degeneracies(&self) -> &'static [Degeneracy] {
+        &[ Degeneracy::CollinearPoints, Degeneracy::CocircularPoints ]
+    }
+
+    fn configure(&mut self, ui: &imgui::Ui) -> Result<()> {
+        ui.text(imgui::im_str!("{} vertices, {} on the hull", self.alg.points().len(), self.alg.hull().len()));
+        if let Some(circle) = self.alg.minimum_enclosing_circle() {
+            ui.text(imgui::im_str!("Enclosing circle radius: {:.4}", circle.radius));
+        }
+
+        ui.checkbox(imgui::im_str!("Triangulation"), &mut self.show_triangulation);
+        ui.checkbox(imgui::im_str!("Diagram"), &mut self.show_diagram);
+        ui.checkbox(imgui::im_str!("Minimum enclosing circle"), &mut self.show_circle);
+
+        if ui.button(imgui::im_str!("Clear Points"), [0.0, 0.0]) {
+            self.scene.borrow_mut().clear_points();
+        }
+
+        if let Some(point) = self.point_input.configure(ui)? {
+            self.scene.borrow_mut().add_point(point);
+        }
+
+        self.exec_time.configure(ui);
+        Ok(())
+    }
+}
+
+impl<'f> FarthestPointVoronoiRenderer<'f> {
+    pub fn new(facade: &'f dyn Facade, scene: SharedScene) -> Result<Self> {
+        let vs = graphics::SHADERS._2d_vs;
+        let fs = graphics::SHADERS.basic_fs;
+        let program = Program::from_source(facade, vs, fs, None)?;
+
+        Ok(Self {
+            alg: FarthestPointVoronoi::new(),
+            scene,
+            revision: 0,
+            program,
+            points: Shape::new(facade, PrimitiveType::Points, [ 1.0, 1.0, 1.0 ])?,
+            sites: Shape::new(facade, PrimitiveType::Points, [ 0.0, 0.0, 0.8 ])?,
+            triangles: Shape::new(facade, PrimitiveType::LinesList, [ 0.0, 0.2, 1.0 ])?,
+            diagram: Shape::new(facade, PrimitiveType::LinesList, [ 1.0, 0.6, 0.0 ])?,
+            circle: Shape::new(facade, PrimitiveType::LineLoop, [ 0.0, 0.8, 0.0 ])?,
+            show_triangulation: true,
+            show_diagram: true,
+            show_circle: false,
+            exec_time: ExecTimeHistory::default(),
+            point_input: PointInput::new(facade)?,
+        })
+    }
+
+    /// Computes the diagram of the points of the scene and regenerates the buffers.
+    fn compute(&mut self) -> Result<()> {
+        let scene = self.scene.borrow();
+        self.revision = scene.revision();
+
+        let start_time = Instant::now();
+        self.alg.set_points(scene.points().to_vec());
+        drop(scene);
+        if self.alg.points().is_empty() {
+            self.exec_time.clear();
+        } else {
+            self.exec_time.push(Instant::now() - start_time);
+        }
+
+        let points = self.alg.points();
+        self.points.set_vertices(points)?;
+        self.sites.set_vertices(&self.alg.hull().iter().map(|&idx| points[idx]).collect::<Vec<_>>())?;
+        self.triangles.set_vertices(&graphics::shape::triangle_edges(points, self.alg.triangles()))?;
+        let edges = self.alg.edges()
+                            .iter()
+                            .flat_map(|edge| vec![ edge.start, edge.clipped_end(RAY_LENGTH) ])
+                            .collect::<Vec<_>>();
+        self.diagram.set_vertices(&edges)?;
+        match self.alg.minimum_enclosing_circle() {
+            Some(circle) => self.circle.set_vertices(&circle.points(64).collect::<Vec<_>>()),
+            None => self.circle.clear(),
+        }
+    }
+}
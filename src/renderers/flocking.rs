@@ -0,0 +1,305 @@
+use crate::{
+    Result,
+    algorithms::{ Incremental2dTriangulation, repair_delaunay, update_delaunay },
+    graphics::{ self, Shape, Viewport },
+    math::Vec2,
+    ui::{ ExecTimeHistory, window::algorithms::{ Drawable, Configurable } },
+};
+
+use std::time::Instant;
+
+use glium::{
+    index::PrimitiveType, Frame, Program,
+    backend::Facade,
+};
+use winit::{
+    event::{ Event, WindowEvent, MouseButton, ElementState },
+    window::Window,
+};
+
+/// The boids turn back when they get closer than `MARGIN` to the sides of this square
+const BOUNDS: f32 = 0.95;
+const MARGIN: f32 = 0.1;
+/// Acceleration that turns the boids back from the sides
+const WALL_FORCE: f32 = 2.0;
+/// Range of the speeds of the boids
+const MIN_SPEED: f32 = 0.1;
+const MAX_SPEED: f32 = 0.4;
+/// Length of the triangle showing a boid
+const BOID_SIZE: f32 = 0.02;
+/// The frames longer than this are simulated as if they lasted this long, e.g. after the window was moved
+const MAX_TIME_STEP: f32 = 0.1;
+
+struct Boid {
+    position: Vec2,
+    velocity: Vec2,
+}
+
+impl Boid {
+    fn random() -> Self {
+        Self {
+            position: Vec2::random_range(-BOUNDS, BOUNDS, -BOUNDS, BOUNDS),
+            velocity: Vec2::random_range(-MAX_SPEED, MAX_SPEED, -MAX_SPEED, MAX_SPEED),
+        }
+    }
+}
+
+pub struct FlockingRenderer<'f> {
+    boids: Vec<Boid>,
+    /// Delaunay triangulation of the positions of the boids, counter-clockwise, updated as they move
+    indices: Vec<usize>,
+    /// Delaunay neighbours of each boid
+    neighbours: Vec<Vec<usize>>,
+    program: Program,
+    triangulation: Shape<'f>,
+    shapes: Shape<'f>,
+    count: i32,
+    /// Weights of the steering rules: keep away from the neighbours, match their velocity and move to their center
+    separation: f32,
+    alignment: f32,
+    cohesion: f32,
+    /// The Delaunay neighbours farther than this are ignored, like those across the hull of the flock
+    range: f32,
+    speed: f32,
+    paused: bool,
+    show_triangulation: bool,
+    /// Computes the triangulation again every frame instead of flipping the edges of the previous one
+    recompute: bool,
+    last_update: Instant,
+    /// Edge flips of the last update, and number of times the triangulation had to be computed again
+    flips: usize,
+    rebuilds: usize,
+    exec_time: ExecTimeHistory,
+}
+
+impl<'f> Drawable for FlockingRenderer<'f> {
+    fn update(&mut self) -> Result<()> {
+        let now = Instant::now();
+        let dt = (now - self.last_update).as_secs_f32().min(MAX_TIME_STEP);
+        self.last_update = now;
+        if !self.paused {
+            self.step(dt * self.speed);
+        }
+        self.update_triangulation();
+        self.update_buffers()
+    }
+
+    fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        if self.show_triangulation {
+            self.triangulation.draw(target, &self.program, viewport)?;
+        }
+        self.shapes.draw(target, &self.program, viewport)
+    }
+
+    fn handle_events(&mut self, window: &Window, event: &Event<()>, io: &imgui::Io, viewport: &Viewport) -> Result<()> {
+        if let Event::WindowEvent { event, .. } = event {
+            if let WindowEvent::MouseInput { button, state, .. } = event {
+                if !io.want_capture_mouse && // Ignore clicks when the cursor is over an ImGui window
+                    button == &MouseButton::Left && state == &ElementState::Pressed {
+
+                    // Add a boid on click
+                    let mut boid = Boid::random();
+                    boid.position = viewport.window_pos_to_normalized(io.mouse_pos.into(), window);
+                    self.boids.push(boid);
+                    self.count = self.boids.len() as i32;
+                    self.indices.clear();
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'f> Configurable for FlockingRenderer<'f> {
+    fn name(&self) -> &'static str {
+        "Flocking"
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        Some("Boids steer with three rules: separation from their neighbours, alignment with their velocity, \
+              and cohesion towards their center. The neighbours of a boid are its neighbours in the Delaunay triangulation of the flock, \
+              about 6 of them wherever the boid is, instead of the boids within a radius. \
+              Between frames the boids barely move, so the previous triangulation is repaired by flipping its illegal edges. \
+              It is only computed again when a triangle turns over or when its boundary stops being convex.")
+    }
+
+    fn pseudo_code(&self) -> Option<&'static str> {
+        Some(concat!(
+            "move the boids\n",
+            "if a triangle turned over or the hull is not convex:\n",
+            "    triangulation = Delaunay(boids)\n",
+            "else:\n",
+            "    flip the illegal edges of triangulation\n",
+            "for each boid b:\n",
+            "    N = neighbours of b in triangulation\n",
+            "    steer away from the boids of N\n",
+            "    steer towards the mean velocity of N\n",
+            "    steer towards the centroid of N",
+        ))
+    }
+
+    fn complexity(&self) -> Option<&'static str> {
+        Some("O(n log n + f log n) per frame for f flips, instead of computing the triangulation again in O(nh) after an O(n log n) sort")
+    }
+
+    fn configure(&mut self, ui: &imgui::Ui) -> Result<()> {
+        ui.text(imgui::im_str!("{} boids, {} triangles", self.boids.len(), self.indices.len() / 3));
+        ui.text(imgui::im_str!("{} flips, {} rebuilds", self.flips, self.rebuilds));
+
+        if imgui::Slider::new(imgui::im_str!("Boids"), 3..=1000).build(ui, &mut self.count) {
+            self.reset();
+        }
+        imgui::Slider::new(imgui::im_str!("Separation"), 0.0..=2.0).build(ui, &mut self.separation);
+        imgui::Slider::new(imgui::im_str!("Alignment"), 0.0..=2.0).build(ui, &mut self.alignment);
+        imgui::Slider::new(imgui::im_str!("Cohesion"), 0.0..=2.0).build(ui, &mut self.cohesion);
+        imgui::Slider::new(imgui::im_str!("Range"), 0.05..=2.0).build(ui, &mut self.range);
+        imgui::Slider::new(imgui::im_str!("Speed"), 0.0..=5.0).build(ui, &mut self.speed);
+        ui.checkbox(imgui::im_str!("Pause"), &mut self.paused);
+        ui.checkbox(imgui::im_str!("Triangulation"), &mut self.show_triangulation);
+        if ui.checkbox(imgui::im_str!("Recompute every frame"), &mut self.recompute) {
+            self.exec_time.clear();
+        }
+        if ui.button(imgui::im_str!("Reset"), [0.0, 0.0]) {
+            self.reset();
+        }
+
+        self.exec_time.configure(ui);
+        Ok(())
+    }
+}
+
+impl<'f> FlockingRenderer<'f> {
+    pub fn new(facade: &'f dyn Facade) -> Result<Self> {
+        let vs = graphics::SHADERS._2d_vs;
+        let fs = graphics::SHADERS.basic_fs;
+        let program = Program::from_source(facade, vs, fs, None)?;
+
+        let mut renderer = Self {
+            boids: Vec::new(),
+            indices: Vec::new(),
+            neighbours: Vec::new(),
+            program,
+            triangulation: Shape::new(facade, PrimitiveType::LinesList, [ 0.3, 0.3, 0.6 ])?,
+            shapes: Shape::new(facade, PrimitiveType::TrianglesList, [ 1.0, 0.8, 0.2 ])?,
+            count: 200,
+            separation: 1.0,
+            alignment: 0.5,
+            cohesion: 0.3,
+            range: 0.3,
+            speed: 1.0,
+            paused: false,
+            show_triangulation: true,
+            recompute: false,
+            last_update: Instant::now(),
+            flips: 0,
+            rebuilds: 0,
+            exec_time: ExecTimeHistory::default(),
+        };
+        renderer.reset();
+        Ok(renderer)
+    }
+
+    /// Replaces the boids with `count` random ones.
+    fn reset(&mut self) {
+        self.boids = (0..self.count.max(0)).map(|_| Boid::random()).collect();
+        self.indices.clear();
+        self.rebuilds = 0;
+        self.exec_time.clear();
+    }
+
+    /// Repairs the triangulation after the boids moved, or computes it again if it cannot be repaired.
+    fn update_triangulation(&mut self) {
+        let positions = self.boids.iter().map(|boid| boid.position).collect::<Vec<_>>();
+        let start_time = Instant::now();
+        let flips = if self.indices.is_empty() || self.recompute {
+            None
+        } else {
+            update_delaunay(&positions, &mut self.indices)
+        };
+        self.flips = match flips {
+            Some(flips) => flips,
+            None => {
+                self.indices = Incremental2dTriangulation::delaunay(&positions);
+                // Orients the triangles counter-clockwise, as the next update expects them
+                repair_delaunay(&positions, &mut self.indices);
+                self.rebuilds += 1;
+                0
+            },
+        };
+        self.exec_time.push(Instant::now() - start_time);
+
+        self.neighbours = vec![ Vec::new(); self.boids.len() ];
+        for t in self.indices.chunks_exact(3) {
+            for i in 0..3 {
+                let (a, b) = (t[i], t[(i + 1) % 3]);
+                // Each inner edge is seen from both of its triangles, in opposite directions
+                if !self.neighbours[a].contains(&b) {
+                    self.neighbours[a].push(b);
+                    self.neighbours[b].push(a);
+                }
+            }
+        }
+    }
+
+    /// Steers the boids with the rules of the flock and moves them during `dt` seconds.
+    fn step(&mut self, dt: f32) {
+        let accelerations = (0..self.boids.len())
+            .map(|idx| {
+                let boid = &self.boids[idx];
+                let neighbours = self.neighbours.get(idx).map_or(&[][..], |n| &n[..])
+                    .iter()
+                    .map(|&other| &self.boids[other])
+                    .filter(|other| (&other.position - &boid.position).length() < self.range)
+                    .collect::<Vec<_>>();
+
+                let mut acceleration = Vec2::new(0.0, 0.0);
+                if !neighbours.is_empty() {
+                    let n = neighbours.len() as f32;
+                    let (mut separation, mut velocity, mut center) = (Vec2::new(0.0, 0.0), Vec2::new(0.0, 0.0), Vec2::new(0.0, 0.0));
+                    for other in &neighbours {
+                        // Pushed away harder by the closer neighbours
+                        let offset = &boid.position - &other.position;
+                        separation += &(&offset / offset.sqr_length().max(1e-4));
+                        velocity += &other.velocity;
+                        center += &other.position;
+                    }
+                    acceleration += &(0.01 * self.separation * &separation);
+                    acceleration += &(self.alignment * &(&(&velocity / n) - &boid.velocity));
+                    acceleration += &(self.cohesion * &(&(&center / n) - &boid.position));
+                }
+
+                // Turns back near the sides
+                let wall = |x: f32| if x > BOUNDS - MARGIN { -WALL_FORCE } else if x < MARGIN - BOUNDS { WALL_FORCE } else { 0.0 };
+                &acceleration + &Vec2::new(wall(boid.position.x), wall(boid.position.y))
+            })
+            .collect::<Vec<_>>();
+
+        for (boid, acceleration) in self.boids.iter_mut().zip(&accelerations) {
+            boid.velocity += &(dt * acceleration);
+            let speed = boid.velocity.length();
+            if speed > MAX_SPEED {
+                boid.velocity *= MAX_SPEED / speed;
+            } else if speed < MIN_SPEED && speed > 0.0 {
+                boid.velocity *= MIN_SPEED / speed;
+            }
+            boid.position += &(dt * &boid.velocity);
+        }
+    }
+
+    /// Regenerates the edges of the triangulation and the triangles of the boids, pointing where they go.
+    fn update_buffers(&mut self) -> Result<()> {
+        let positions = self.boids.iter().map(|boid| boid.position).collect::<Vec<_>>();
+        self.triangulation.set_vertices(&graphics::shape::triangle_edges(&positions, &self.indices))?;
+
+        let shapes = self.boids
+            .iter()
+            .flat_map(|boid| {
+                let forward = &boid.velocity.normalized() * BOID_SIZE;
+                let side = Vec2::new(-forward.y / 3.0, forward.x / 3.0);
+                let back = &boid.position - &(0.5 * &forward);
+                vec![ &boid.position + &(0.5 * &forward), &back + &side, &back - &side ]
+            })
+            .collect::<Vec<_>>();
+        self.shapes.set_vertices(&shapes)
+    }
+}
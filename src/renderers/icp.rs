@@ -0,0 +1,202 @@
+use crate::{
+    Result,
+    algorithms::Icp,
+    graphics::{ self, Shape, Viewport },
+    math::{ Vec2, Transform2 },
+    scene::SharedScene,
+    ui::{ ExecTimeHistory, window::algorithms::{ Drawable, Configurable } },
+};
+
+use std::time::{ Duration, Instant };
+
+use glium::{
+    index::PrimitiveType, Frame, Program,
+    backend::Facade,
+};
+
+/// Playing stops once an iteration improves the error by less than this
+const TOLERANCE: f32 = 1e-6;
+
+pub struct IcpRenderer<'f> {
+    /// The fixed points are those of the scene
+    scene: SharedScene,
+    revision: u64,
+    icp: Icp,
+    /// Rotation in degrees around the origin, then translation, that make the moving copy of the points
+    angle: f32,
+    offset: [f32; 2],
+    /// While playing, an iteration runs every `interval` seconds
+    playing: bool,
+    interval: f32,
+    last_step: Instant,
+    show_correspondences: bool,
+    program: Program,
+    target: Shape<'f>,
+    source: Shape<'f>,
+    correspondences: Shape<'f>,
+    exec_time: ExecTimeHistory,
+}
+
+impl<'f> Drawable for IcpRenderer<'f> {
+    fn update(&mut self) -> Result<()> {
+        if self.scene.borrow().revision() != self.revision {
+            self.reset()?;
+        }
+
+        if self.playing && Instant::now() - self.last_step >= Duration::from_secs_f32(self.interval) {
+            self.last_step = Instant::now();
+            let previous = self.icp.errors().last().copied();
+            match (self.step()?, previous) {
+                (Some(error), Some(previous)) if previous - error >= TOLERANCE => {},
+                (Some(_), None) => {},
+                _ => self.playing = false, // Converged
+            }
+        }
+        Ok(())
+    }
+
+    fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        if self.show_correspondences {
+            self.correspondences.draw(target, &self.program, viewport)?;
+        }
+        self.target.draw(target, &self.program, viewport)?;
+        self.source.draw(target, &self.program, viewport)
+    }
+}
+
+impl<'f> Configurable for IcpRenderer<'f> {
+    fn name(&self) -> &'static str {
+        "Point set registration (ICP)"
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        Some("Aligns a rotated and translated copy of the points of the scene back onto them with the iterative closest point algorithm. \
+              Each iteration pairs every moving point with the closest fixed point, found with a k-d tree, \
+              then moves the copy by the rotation and translation that minimize the squared distances between the pairs: \
+              the translation matches the centroids and the angle comes from the sums of the dot and cross products of the centered pairs. \
+              The error is the root mean square distance between the pairs. \
+              It only converges to the right alignment when the copy starts close enough, large rotations end in a local minimum.")
+    }
+
+    fn pseudo_code(&self) -> Option<&'static str> {
+        Some(concat!(
+            "repeat:\n",
+            "    for each moving point p:\n",
+            "        q(p) = closest fixed point to p\n",
+            "    P, Q = centroids of the p and of the q(p)\n",
+            "    angle = atan2(sum of (p - P) × (q(p) - Q),\n",
+            "                  sum of (p - P) · (q(p) - Q))\n",
+            "    for each moving point p:\n",
+            "        p = rotate(p - P, angle) + Q",
+        ))
+    }
+
+    fn complexity(&self) -> Option<&'static str> {
+        Some("O(n log n) per iteration for n points, to build the k-d tree and query it once per point")
+    }
+
+    fn configure(&mut self, ui: &imgui::Ui) -> Result<()> {
+        ui.text(imgui::im_str!("{} points, {} iterations", self.icp.target().len(), self.icp.iterations()));
+        match self.icp.errors().last() {
+            Some(error) => ui.text(imgui::im_str!("RMS error: {:.6}", error)),
+            None => ui.text(imgui::im_str!("RMS error: -")),
+        }
+        // The transform found so far, undone, should be the one that made the copy
+        let recovered = self.icp.transform().inverse();
+        ui.text(imgui::im_str!("Recovered rotation: {:.2}°, translation: ({:.3}, {:.3})",
+                               recovered.angle.to_degrees(), recovered.translation.x, recovered.translation.y));
+
+        let rotated = imgui::Slider::new(imgui::im_str!("Rotation (°)"), -90.0..=90.0).build(ui, &mut self.angle);
+        let moved = ui.input_float2(imgui::im_str!("Translation"), &mut self.offset).build();
+        if rotated || moved {
+            self.reset()?;
+        }
+
+        if ui.button(imgui::im_str!("Step"), [0.0, 0.0]) {
+            self.playing = false;
+            self.step()?;
+        }
+        ui.same_line(0.0);
+        ui.checkbox(imgui::im_str!("Play"), &mut self.playing);
+        ui.same_line(0.0);
+        if ui.button(imgui::im_str!("Reset"), [0.0, 0.0]) {
+            self.reset()?;
+        }
+        imgui::Slider::new(imgui::im_str!("Interval (s)"), 0.05..=2.0).build(ui, &mut self.interval);
+        ui.checkbox(imgui::im_str!("Correspondences"), &mut self.show_correspondences);
+
+        if ui.button(imgui::im_str!("Random Points"), [0.0, 0.0]) {
+            self.scene.borrow_mut().add_random_points(200);
+        }
+
+        self.exec_time.configure(ui);
+        Ok(())
+    }
+}
+
+impl<'f> IcpRenderer<'f> {
+    pub fn new(facade: &'f dyn Facade, scene: SharedScene) -> Result<Self> {
+        let vs = graphics::SHADERS._2d_vs;
+        let fs = graphics::SHADERS.basic_fs;
+        let program = Program::from_source(facade, vs, fs, None)?;
+
+        let mut target = Shape::new(facade, PrimitiveType::Points, [ 0.4, 0.6, 1.0 ])?;
+        target.size = 4.0;
+        let mut source = Shape::new(facade, PrimitiveType::Points, [ 1.0, 0.4, 0.4 ])?;
+        source.size = 4.0;
+
+        Ok(Self {
+            scene,
+            revision: 0,
+            icp: Icp::new(Vec::new(), Vec::new()),
+            angle: 20.0,
+            offset: [ 0.1, -0.05 ],
+            playing: false,
+            interval: 0.5,
+            last_step: Instant::now(),
+            show_correspondences: true,
+            program,
+            target,
+            source,
+            correspondences: Shape::new(facade, PrimitiveType::LinesList, [ 0.3, 0.3, 0.3 ])?,
+            exec_time: ExecTimeHistory::default(),
+        })
+    }
+
+    /// Copies the points of the scene and moves the copy to its starting position.
+    fn reset(&mut self) -> Result<()> {
+        let scene = self.scene.borrow();
+        self.revision = scene.revision();
+        let target = scene.points().to_vec();
+        drop(scene);
+
+        let transform = Transform2::new(self.angle.to_radians(), Vec2::from(self.offset));
+        let source = target.iter().map(|&p| transform.apply(p)).collect();
+        self.icp = Icp::new(source, target);
+        self.exec_time.clear();
+
+        self.target.set_vertices(self.icp.target())?;
+        self.source.set_vertices(self.icp.source())?;
+        self.correspondences.clear()
+    }
+
+    /// Runs one iteration and regenerates the buffers, returns the new error.
+    fn step(&mut self) -> Result<Option<f32>> {
+        let start_time = Instant::now();
+        let error = self.icp.step();
+        if error.is_none() {
+            return Ok(None);
+        }
+        self.exec_time.push(Instant::now() - start_time);
+
+        // The pairs were made before the points moved, they are drawn from the new positions
+        let correspondences = self.icp.source()
+            .iter()
+            .zip(self.icp.correspondences())
+            .flat_map(|(&p, &idx)| vec![ p, self.icp.target()[idx] ])
+            .collect::<Vec<_>>();
+        self.correspondences.set_vertices(&correspondences)?;
+        self.source.set_vertices(self.icp.source())?;
+        Ok(error)
+    }
+}
@@ -0,0 +1,715 @@
+use std::time::{ Duration, Instant };
+
+use crate::{
+    Result,
+    algorithms::{ Incremental2dTriangulation, Degeneracy, TriangleQuality, validate_delaunay, repair_delaunay,
+                  triangle_qualities, histogram, greedy_coloring, color_count, locate_triangle, fill_region, DualGraph,
+                  TriangleWalk, TriangleGrid, HistoryDag, Location },
+    graphics::{ self, Shape, ColorMappedShape, Viewport },
+    math::{ Vec2, Segment2, Rect },
+    render::{ layers, DrawList, GliumRenderer },
+    scene::{ self, SharedScene },
+    task::Task,
+    ui::{ PointInput, ExecTimeHistory, Playback, window::algorithms::{ Drawable, Configurable } },
+};
+#[cfg(feature = "cross-validate")]
+use crate::cross_validation;
+
+use glium::{
+    index, Surface, Frame, Program, VertexBuffer, IndexBuffer,
+    backend::Facade,
+};
+use winit::{
+    event::{ Event, WindowEvent, MouseButton, ElementState },
+    window::Window,
+};
+
+/// Largest number of points whose steps can be recorded, every state of the triangulation is copied
+const MAX_RECORDED_POINTS: usize = 300;
+/// Number of bins of the histogram of the quality of the triangles
+const HISTOGRAM_BINS: usize = 30;
+
+/// Quality measure of the triangles shown by the overlay.
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum Metric {
+    MinAngle,
+    AspectRatio,
+    RadiusEdgeRatio,
+}
+
+impl Metric {
+    const ALL: [Metric; 3] = [ Metric::MinAngle, Metric::AspectRatio, Metric::RadiusEdgeRatio ];
+
+    fn name(self) -> &'static imgui::ImStr {
+        match self {
+            Metric::MinAngle => imgui::im_str!("Min angle (°)"),
+            Metric::AspectRatio => imgui::im_str!("Aspect ratio"),
+            Metric::RadiusEdgeRatio => imgui::im_str!("Radius-edge ratio"),
+        }
+    }
+
+    fn value(self, quality: &TriangleQuality) -> f32 {
+        match self {
+            Metric::MinAngle => quality.min_angle.to_degrees(),
+            Metric::AspectRatio => quality.aspect_ratio,
+            Metric::RadiusEdgeRatio => quality.radius_edge_ratio,
+        }
+    }
+
+    /// Fixed range of the color map and of the histogram, so that triangulations can be compared.
+    /// The best triangles are at the high end, drawn in white.
+    fn range(self) -> (f32, f32) {
+        match self {
+            Metric::MinAngle => (0.0, 60.0),
+            Metric::AspectRatio => (4.0, 1.0),
+            Metric::RadiusEdgeRatio => (2.0, 1.0 / 3f32.sqrt()),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Vertex {
+    position: Vec2,
+}
+
+impl Vertex {
+    pub fn new(position: Vec2) -> Self {
+        Self {
+            position,
+        }
+    }
+}
+
+implement_vertex!(Vertex, position);
+
+pub struct Incremental2dTriangulationRenderer<'f> {
+    facade: &'f dyn Facade,
+    alg: Incremental2dTriangulation,
+    /// The input points are those of the scene, the triangulation is written back to it
+    scene: SharedScene,
+    /// Revision of the scene the points were read from
+    revision: u64,
+    program: Program,
+    renderer: GliumRenderer<'f>,
+    points_list: DrawList,
+    triangles_list: DrawList,
+    /// Buffer object that stores all the points, indexed by the illegal edges and the differences
+    points_buffer: VertexBuffer<Vertex>,
+    /// Edges that do not satisfy the Delaunay criterion, drawn in red
+    illegal_buffer: IndexBuffer<u32>,
+    /// Triangles that differ from the Delaunay triangulation of spade, drawn in red
+    #[cfg(feature = "cross-validate")]
+    differences_buffer: IndexBuffer<u32>,
+    /// Quality of each triangle, and the triangles filled with the color of the selected metric
+    qualities: Vec<TriangleQuality>,
+    metric: Metric,
+    show_quality: bool,
+    quality_overlay: ColorMappedShape<'f>,
+    /// Color of each triangle, different from those of its neighbours, and the triangles filled with it
+    colors: Vec<usize>,
+    show_colors: bool,
+    coloring_overlay: ColorMappedShape<'f>,
+    /// Adjacency of the triangles
+    graph: DualGraph,
+    /// When selecting, left clicks select the region of the triangle under the cursor instead of adding points
+    selecting: bool,
+    /// Position of the last selection, selected again when the triangulation changes
+    seed: Option<Vec2>,
+    /// Triangles of the selected region, bounded by the segments of the scene
+    region: Vec<usize>,
+    barriers: Vec<Segment2>,
+    region_overlay: Shape<'f>,
+    barriers_shape: Shape<'f>,
+    /// Number of random queries of the point location benchmark, and its results
+    query_count: i32,
+    location_stats: Vec<LocationStats>,
+    exec_time: ExecTimeHistory,
+    point_input: PointInput<'f>,
+    /// Triangulation running in the background
+    task: Option<Task<Triangulation>>,
+    random_count: i32,
+    /// Recorded steps, drawn instead of the triangulation while they are played back
+    playback: Playback<'f>,
+    /// Whether the recorded steps go on with the edge flipping
+    record_flips: bool,
+}
+
+/// Timings and costs of a point location strategy over the random queries of the benchmark.
+struct LocationStats {
+    name: &'static str,
+    /// Time to build the structure used by the queries
+    build_time: Duration,
+    query_time: Duration,
+    mean_visited: f32,
+    max_visited: usize,
+    found: usize,
+    /// Queries answered with the same triangle as the walk
+    agreeing: usize,
+}
+
+/// Result of a triangulation computed on a worker thread.
+struct Triangulation {
+    /// Input points, sorted by the algorithm
+    positions: Vec<Vec2>,
+    indices: Vec<usize>,
+    exec_time: Duration,
+    /// Triangles that differ from those of spade, only compared for Delaunay triangulations
+    #[cfg(feature = "cross-validate")]
+    differences: Vec<usize>,
+}
+
+impl<'f> Drawable for Incremental2dTriangulationRenderer<'f> {
+    fn update(&mut self) -> Result<()> {
+        // Checked first so that a result computed from outdated points is discarded
+        if self.scene.borrow().revision() != self.revision {
+            self.read_points()?;
+            self.playback.stop()?; // The recorded steps are outdated
+        }
+        self.playback.update()?;
+
+        let result = match &self.task {
+            Some(task) => task.poll(),
+            None => None,
+        };
+        if let Some(triangulation) = result {
+            self.task = None;
+            self.set_triangulation(triangulation)?;
+        }
+        Ok(())
+    }
+
+    fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        self.point_input.draw(target, viewport)?;
+        if self.playback.is_active() {
+            self.playback.draw(target, viewport)?;
+            return self.draw_points(target, viewport);
+        }
+        if self.show_colors {
+            self.coloring_overlay.draw(target, viewport)?;
+        }
+        if self.show_quality {
+            self.quality_overlay.draw(target, viewport)?;
+        }
+        self.region_overlay.draw(target, &self.program, viewport)?;
+        self.draw_triangles(target, viewport)?;
+        self.barriers_shape.draw(target, &self.program, viewport)?;
+        self.draw_illegal_edges(target, viewport)?;
+        #[cfg(feature = "cross-validate")]
+        self.draw_differences(target, viewport)?;
+        self.draw_points(target, viewport)
+    }
+
+    fn handle_events(&mut self, window: &Window, event: &Event<()>, io: &imgui::Io, viewport: &Viewport) -> Result<()> {
+        if let Event::WindowEvent { event, .. } = event {
+            if let WindowEvent::MouseInput { button, state, .. } = event {
+                if !io.want_capture_mouse && // Ignore clicks when the cursor is over an ImGui window
+                    button == &MouseButton::Left && state == &ElementState::Pressed {
+
+                    let coords = viewport.window_pos_to_normalized(io.mouse_pos.into(), window);
+                    if self.selecting {
+                        self.select(Some(coords))?;
+                    } else {
+                        // Add a point on click
+                        self.scene.borrow_mut().add_point(self.point_input.place(coords));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'f> Configurable for Incremental2dTriangulationRenderer<'f> {
+    fn name(&self) -> &'static str {
+        "Incremental 2D triangulation"
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        Some("The points are sorted by x coordinate and inserted one by one. Each new point is connected \
+              to every edge of the current convex hull that it can see, which creates new triangles. \
+              Edge flipping then turns the result into a Delaunay triangulation by flipping every edge \
+              whose opposite vertex lies inside the circumcircle of the adjacent triangle. \
+              The edges that still break this criterion are drawn in red, repairing flips them until none is left. \
+              The quality overlay colors each triangle by its smallest angle, its aspect ratio or its radius-edge ratio, \
+              from dark green for the thinnest triangles to white for equilateral ones. \
+              The coloring gives adjacent triangles different colors: the triangles are taken by decreasing number of neighbours \
+              and each gets the first color none of its neighbours has, which needs at most 4 colors. \
+              When selecting, a click fills the region of the triangle under the cursor: the triangles reachable \
+              through their shared edges without crossing a segment of the scene, drawn in the segment arrangement. \
+              The point location benchmark locates random points with a walk from the previous triangle, \
+              a history DAG built by a randomized incremental Delaunay triangulation, and a uniform grid of the triangles.")
+    }
+
+    fn pseudo_code(&self) -> Option<&'static str> {
+        Some(concat!(
+            "sort the points by x, then y\n",
+            "start with the first non-collinear points\n",
+            "for each remaining point p:\n",
+            "    for each edge (a, b) of the hull:\n",
+            "        if (a, b) is visible from p:\n",
+            "            add the triangle (a, b, p)\n",
+            "    add p to the hull",
+        ))
+    }
+
+    fn complexity(&self) -> Option<&'static str> {
+        Some("O(nh) for the insertions after an O(n log n) sort, edge flipping is O(n²) in the worst case")
+    }
+
+    fn degeneracies(&self) -> &'static [Degeneracy] {
+        &[ Degeneracy::CollinearPoints, Degeneracy::CocircularPoints ]
+    }
+
+    fn configure(&mut self, ui: &imgui::Ui) -> Result<()> {
+        ui.text(imgui::im_str!("{} vertices, {} triangles", self.alg.points().len(), self.alg.triangle_count()));
+        let illegal = self.illegal_buffer.len() / 2;
+        if illegal > 0 {
+            ui.text_colored([ 1.0, 0.0, 0.0, 1.0 ], imgui::im_str!("{} illegal edges", illegal));
+            ui.same_line(0.0);
+            if ui.button(imgui::im_str!("Repair"), [0.0, 0.0]) {
+                self.repair()?;
+            }
+        } else if self.alg.triangle_count() > 0 {
+            ui.text_disabled(imgui::im_str!("Delaunay triangulation"));
+        }
+        #[cfg(feature = "cross-validate")]
+        {
+            let differences = self.differences_buffer.len() / 3;
+            if differences > 0 {
+                ui.text_colored([ 1.0, 0.0, 0.0, 1.0 ], imgui::im_str!("{} triangles differ from spade", differences));
+            }
+        }
+
+        if ui.button(imgui::im_str!("Clear Points"), [0.0, 0.0]) {
+            self.scene.borrow_mut().clear_points();
+        }
+
+        self.configure_quality(ui)?;
+        ui.checkbox(imgui::im_str!("Coloring"), &mut self.show_colors);
+        if !self.colors.is_empty() {
+            ui.same_line(0.0);
+            ui.text(imgui::im_str!("{} colors", color_count(&self.colors)));
+        }
+
+        ui.checkbox(imgui::im_str!("Select regions"), &mut self.selecting);
+        if !self.region.is_empty() {
+            ui.same_line(0.0);
+            ui.text(imgui::im_str!("{} triangles selected, {} barriers", self.region.len(), self.barriers.len()));
+            ui.same_line(0.0);
+            if ui.button(imgui::im_str!("Clear Selection"), [0.0, 0.0]) {
+                self.select(None)?;
+            }
+        }
+
+        if ui.button(imgui::im_str!("Edge Flipping"), [0.0, 0.0]) {
+            self.flip_edges();
+        }
+
+        if !self.playback.is_active() {
+            ui.checkbox(imgui::im_str!("Record edge flipping"), &mut self.record_flips);
+        }
+        if self.playback.configure(ui, self.alg.points().len())? {
+            let (points, snapshots) = Incremental2dTriangulation::record(self.alg.points().to_vec(), self.record_flips);
+            self.playback.start(points, snapshots)?;
+        }
+
+        self.configure_location_benchmark(ui);
+
+        ui.input_int(imgui::im_str!("Count"), &mut self.random_count).build();
+        if ui.button(imgui::im_str!("Random Points"), [0.0, 0.0]) {
+            self.scene.borrow_mut().add_random_points(self.random_count.max(0) as usize);
+        }
+
+        let mut cancel = false;
+        if let Some(task) = &self.task {
+            imgui::ProgressBar::new(task.progress().fraction()).build(ui);
+            cancel = ui.button(imgui::im_str!("Cancel"), [0.0, 0.0]);
+        }
+        if cancel {
+            self.task = None; // Dropping the task cancels it
+        }
+
+        if let Some(point) = self.point_input.configure(ui)? {
+            self.scene.borrow_mut().add_point(point);
+        }
+
+        self.exec_time.configure(ui);
+        Ok(())
+    }
+}
+
+impl<'f> Incremental2dTriangulationRenderer<'f> {
+    pub fn new(facade: &'f dyn Facade, scene: SharedScene) -> Result<Self> {
+        let vs = graphics::SHADERS._2d_vs;
+        let fs = graphics::SHADERS.basic_fs;
+        let program = Program::from_source(facade, vs, fs, None)?;
+
+        let mut barriers_shape = Shape::new(facade, index::PrimitiveType::LinesList, [ 1.0, 1.0, 1.0 ])?;
+        barriers_shape.size = 2.0;
+
+        Ok(Self {
+            facade,
+            alg: Incremental2dTriangulation::new(),
+            scene,
+            revision: 0,
+            program,
+            renderer: GliumRenderer::new(facade)?,
+            points_list: layers::list(),
+            triangles_list: layers::list(),
+            points_buffer: VertexBuffer::empty(facade, 0)?, // Start without any point
+            illegal_buffer: IndexBuffer::empty(facade, index::PrimitiveType::LinesList, 0)?,
+            #[cfg(feature = "cross-validate")]
+            differences_buffer: IndexBuffer::empty(facade, index::PrimitiveType::TrianglesList, 0)?,
+            qualities: Vec::new(),
+            metric: Metric::MinAngle,
+            show_quality: false,
+            quality_overlay: ColorMappedShape::new(facade, index::PrimitiveType::TrianglesList)?,
+            colors: Vec::new(),
+            show_colors: false,
+            coloring_overlay: ColorMappedShape::new(facade, index::PrimitiveType::TrianglesList)?,
+            graph: DualGraph::default(),
+            selecting: false,
+            seed: None,
+            region: Vec::new(),
+            barriers: Vec::new(),
+            region_overlay: Shape::new(facade, index::PrimitiveType::TrianglesList, [ 0.9, 0.6, 0.1 ])?,
+            barriers_shape,
+            query_count: 10000,
+            location_stats: Vec::new(),
+            exec_time: ExecTimeHistory::default(),
+            point_input: PointInput::new(facade)?,
+            task: None,
+            random_count: 1000,
+            playback: Playback::new(facade, MAX_RECORDED_POINTS)?,
+            record_flips: true,
+        })
+    }
+
+    fn draw_points(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        self.renderer.draw_in(&self.points_list, target, viewport.rect(target))
+    }
+
+    fn draw_triangles(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        self.renderer.draw_in(&self.triangles_list, target, viewport.rect(target))
+    }
+
+    fn draw_illegal_edges(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        let uniforms = uniform! {
+            color: [ 1.0_f32, 0.0_f32, 0.0_f32 ],
+        };
+        let mut draw_params = viewport.draw_parameters(target);
+        draw_params.line_width = Some(3.0);
+        target.draw(&self.points_buffer, &self.illegal_buffer, &self.program, &uniforms, &draw_params)?;
+        Ok(())
+    }
+
+    #[cfg(feature = "cross-validate")]
+    fn draw_differences(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        let uniforms = uniform! {
+            color: [ 1.0_f32, 0.0_f32, 0.0_f32 ],
+        };
+        let mut draw_params = viewport.draw_parameters(target);
+        draw_params.polygon_mode = glium::PolygonMode::Line;
+        draw_params.line_width = Some(3.0);
+        target.draw(&self.points_buffer, &self.differences_buffer, &self.program, &uniforms, &draw_params)?;
+        Ok(())
+    }
+
+    pub fn flip_edges(&mut self) {
+        self.start_triangulation(true);
+    }
+
+    /// Replaces the input points with those of the scene and triangulates them.
+    fn read_points(&mut self) -> Result<()> {
+        let scene = self.scene.borrow();
+        self.revision = scene.revision();
+        self.alg.set_points(scene.points().to_vec());
+        self.barriers = scene.segments().to_vec();
+        drop(scene);
+
+        let barriers = self.barriers.iter().flat_map(|segment| vec![ segment.a, segment.b ]).collect::<Vec<_>>();
+        self.barriers_shape.set_vertices(&barriers)?;
+
+        self.update_points_buffer()?;
+        self.triangles_list = layers::list();
+        self.illegal_buffer = IndexBuffer::empty(self.facade, index::PrimitiveType::LinesList, 0)?;
+        self.qualities.clear();
+        self.quality_overlay.clear()?;
+        self.colors.clear();
+        self.coloring_overlay.clear()?;
+        self.graph = DualGraph::default();
+        self.region.clear();
+        self.region_overlay.clear()?;
+        #[cfg(feature = "cross-validate")]
+        {
+            self.differences_buffer = IndexBuffer::empty(self.facade, index::PrimitiveType::TrianglesList, 0)?;
+        }
+        if self.alg.points().is_empty() {
+            self.task = None;
+            self.exec_time.clear();
+        } else {
+            self.start_triangulation(false);
+        }
+        Ok(())
+    }
+
+    /// Triangulates the points on a worker thread, cancelling the previous computation if it is still running.
+    /// The result is retrieved in [`update()`](#method.update).
+    fn start_triangulation(&mut self, flip_edges: bool) {
+        let positions = self.alg.points().to_vec();
+
+        self.task = Some(Task::spawn(move |progress| {
+            let start_time = Instant::now();
+            let (positions, indices) = Incremental2dTriangulation::compute(positions, flip_edges, progress)?;
+            let exec_time = Instant::now() - start_time;
+
+            #[cfg(feature = "cross-validate")]
+            let differences = if flip_edges {
+                cross_validation::delaunay_differences(&positions, &indices)
+            } else {
+                Vec::new()
+            };
+
+            Some(Triangulation {
+                positions,
+                indices,
+                exec_time,
+                #[cfg(feature = "cross-validate")]
+                differences,
+            })
+        }));
+    }
+
+    /// Replaces the displayed triangulation with the result of a worker thread and stores it in the scene.
+    fn set_triangulation(&mut self, triangulation: Triangulation) -> Result<()> {
+        self.exec_time.push(triangulation.exec_time);
+        // The triangulation sorts the points, so the vertex buffer is recreated
+        // for the indices to refer to the right points
+        self.alg.set_triangulation(triangulation.positions, triangulation.indices);
+        self.update_points_buffer()?;
+        self.update_triangles()?;
+
+        #[cfg(feature = "cross-validate")]
+        self.set_differences(&triangulation.differences)?;
+        Ok(())
+    }
+
+    /// Regenerates the buffer of the triangles that differ from those of spade.
+    #[cfg(feature = "cross-validate")]
+    fn set_differences(&mut self, differences: &[usize]) -> Result<()> {
+        if !differences.is_empty() {
+            log::warn!("{} triangles differ from the Delaunay triangulation of spade", differences.len() / 3);
+        }
+        let differences = differences
+                                .iter()
+                                .map(|&idx| idx as u32)
+                                .collect::<Vec<_>>();
+        self.differences_buffer = IndexBuffer::new(self.facade, index::PrimitiveType::TrianglesList, &differences)?;
+        Ok(())
+    }
+
+    /// Flips the illegal edges of the current triangulation until it is a Delaunay triangulation.
+    fn repair(&mut self) -> Result<()> {
+        let start_time = Instant::now();
+        let points = self.alg.points().to_vec();
+        let mut indices = self.alg.indices().to_vec();
+        let flips = repair_delaunay(&points, &mut indices);
+        self.exec_time.push(Instant::now() - start_time);
+        log::info!("Repaired the triangulation with {} flips", flips);
+
+        self.alg.set_triangulation(points, indices);
+        #[cfg(feature = "cross-validate")]
+        self.set_differences(&cross_validation::delaunay_differences(self.alg.points(), self.alg.indices()))?;
+        self.update_triangles()
+    }
+
+    /// Stores the triangles in the scene, regenerates their buffer and finds the illegal edges.
+    fn update_triangles(&mut self) -> Result<()> {
+        self.scene.borrow_mut().set_triangulation(scene::Triangulation {
+            points: self.alg.points().to_vec(),
+            indices: self.alg.indices().to_vec(),
+        });
+
+        self.triangles_list = layers::triangulation(self.alg.points(), self.alg.indices());
+
+        let illegal = validate_delaunay(self.alg.points(), self.alg.indices())
+                                .into_iter()
+                                .flat_map(|(a, b)| vec![ a as u32, b as u32 ])
+                                .collect::<Vec<_>>();
+        self.illegal_buffer = IndexBuffer::new(self.facade, index::PrimitiveType::LinesList, &illegal)?;
+
+        self.qualities = triangle_qualities(self.alg.points(), self.alg.indices());
+        self.update_quality_overlay()?;
+        self.graph = DualGraph::new(self.alg.indices());
+        self.update_coloring()?;
+        self.select(self.seed)
+    }
+
+    /// Selects the region of the triangle containing `seed` and regenerates the buffer of its triangles,
+    /// clears the selection if there is no such triangle.
+    fn select(&mut self, seed: Option<Vec2>) -> Result<()> {
+        self.seed = seed;
+        let points = self.alg.points();
+        let start = seed.and_then(|p| locate_triangle(points, self.alg.indices(), p));
+        self.region = match start {
+            Some(start) => fill_region(points, &self.graph, start, &self.barriers),
+            None => Vec::new(),
+        };
+
+        let vertices = self.region
+                            .iter()
+                            .flat_map(|&t| self.graph.triangle(t).iter().map(|&idx| points[idx]).collect::<Vec<_>>())
+                            .collect::<Vec<_>>();
+        self.region_overlay.set_vertices(&vertices)
+    }
+
+    /// Shows the point location benchmark and its results.
+    fn configure_location_benchmark(&mut self, ui: &imgui::Ui) {
+        if !ui.collapsing_header(imgui::im_str!("Point location benchmark")).build() {
+            return;
+        }
+        ui.input_int(imgui::im_str!("Queries"), &mut self.query_count).step(1000).build();
+        self.query_count = self.query_count.max(1);
+        if ui.button(imgui::im_str!("Run Benchmark"), [0.0, 0.0]) && self.alg.triangle_count() > 0 {
+            self.location_stats = self.benchmark_location(self.query_count as usize);
+        }
+        for stats in &self.location_stats {
+            ui.text(imgui::im_str!("{}: {:.3} µs per query, built in {:.2} ms", stats.name,
+                                   stats.query_time.as_secs_f64() * 1e6 / self.query_count.max(1) as f64,
+                                   stats.build_time.as_secs_f64() * 1e3));
+            ui.text_disabled(imgui::im_str!("    {:.1} visited on average, {} at most, {} found, {} agree with the walk",
+                                            stats.mean_visited, stats.max_visited, stats.found, stats.agreeing));
+        }
+    }
+
+    /// Locates random points of the bounding rectangle of the triangulation with the walk, the history DAG and the grid.
+    /// The DAG locates the points in its own Delaunay triangulation, which is the same one if the current triangulation
+    /// is Delaunay and has no cocircular points.
+    fn benchmark_location(&self, query_count: usize) -> Vec<LocationStats> {
+        let (points, indices) = (self.alg.points(), self.alg.indices());
+        let bounds = match Rect::from_points(points) {
+            Some(bounds) => bounds,
+            None => return Vec::new(),
+        };
+        let queries = (0..query_count)
+                        .map(|_| Vec2::random_range(bounds.min.x, bounds.max.x, bounds.min.y, bounds.max.y))
+                        .collect::<Vec<_>>();
+
+        // Sorted vertices of the located triangle, to compare the answers of structures that number the triangles differently
+        let key = |triangles: &[usize], location: &Location| location.triangle.map(|t| {
+            let mut vertices = [ triangles[3 * t], triangles[3 * t + 1], triangles[3 * t + 2] ];
+            vertices.sort_unstable();
+            vertices
+        });
+        let stats = |name, build_time, query_time, locations: &[Location], triangles: &[usize], reference: &[Option<[usize; 3]>]| LocationStats {
+            name,
+            build_time,
+            query_time,
+            mean_visited: locations.iter().map(|location| location.visited).sum::<usize>() as f32 / locations.len().max(1) as f32,
+            max_visited: locations.iter().map(|location| location.visited).max().unwrap_or(0),
+            found: locations.iter().filter(|location| location.triangle.is_some()).count(),
+            agreeing: locations.iter().zip(reference).filter(|(location, expected)| key(triangles, location) == **expected).count(),
+        };
+
+        // Each walk starts from the triangle found by the previous one
+        let start_time = Instant::now();
+        let walk = TriangleWalk::new(points, indices);
+        let walk_build = Instant::now() - start_time;
+        let start_time = Instant::now();
+        let mut start = 0;
+        let walked = queries.iter()
+                        .map(|&p| {
+                            let location = walk.locate(p, start);
+                            start = location.triangle.unwrap_or(start);
+                            location
+                        })
+                        .collect::<Vec<_>>();
+        let walk_time = Instant::now() - start_time;
+        let reference = walked.iter().map(|location| key(indices, location)).collect::<Vec<_>>();
+
+        let start_time = Instant::now();
+        let dag = HistoryDag::new(points);
+        let dag_build = Instant::now() - start_time;
+        let start_time = Instant::now();
+        let descended = queries.iter().map(|&p| dag.locate(p)).collect::<Vec<_>>();
+        let dag_time = Instant::now() - start_time;
+
+        let start_time = Instant::now();
+        let grid = TriangleGrid::new(points, indices);
+        let grid_build = Instant::now() - start_time;
+        let start_time = Instant::now();
+        let gridded = queries.iter().map(|&p| grid.locate(p)).collect::<Vec<_>>();
+        let grid_time = Instant::now() - start_time;
+
+        vec![
+            stats("Walk", walk_build, walk_time, &walked, indices, &reference),
+            stats("History DAG", dag_build, dag_time, &descended, &dag.indices(), &reference),
+            stats("Grid", grid_build, grid_time, &gridded, indices, &reference),
+        ]
+    }
+
+    /// Colors the triangles so that adjacent ones differ, and regenerates the buffer of the colored triangles.
+    fn update_coloring(&mut self) -> Result<()> {
+        self.colors = greedy_coloring(self.graph.adjacency());
+        let points = self.alg.points();
+        let vertices = self.alg.indices().iter().map(|&idx| points[idx]).collect::<Vec<_>>();
+        let colors = self.colors
+                        .iter()
+                        .flat_map(|&color| vec![ graphics::colormap::category(color); 3 ])
+                        .collect::<Vec<_>>();
+        self.coloring_overlay.set_colored_vertices(&vertices, &colors)
+    }
+
+    /// Shows the metric selection and the histogram of the quality of the triangles.
+    fn configure_quality(&mut self, ui: &imgui::Ui) -> Result<()> {
+        ui.checkbox(imgui::im_str!("Quality overlay"), &mut self.show_quality);
+        let names = Metric::ALL.iter().map(|metric| metric.name()).collect::<Vec<_>>();
+        let mut idx = Metric::ALL.iter().position(|&metric| metric == self.metric).unwrap_or(0);
+        if imgui::ComboBox::new(imgui::im_str!("Metric")).build_simple_string(ui, &mut idx, &names[..]) {
+            self.metric = Metric::ALL[idx];
+            self.update_quality_overlay()?;
+        }
+
+        let values = self.qualities.iter().map(|quality| self.metric.value(quality)).collect::<Vec<_>>();
+        if let Some((min, max)) = graphics::colormap::range(&values) {
+            let mean = values.iter().sum::<f32>() / values.len() as f32;
+            ui.text(imgui::im_str!("Min: {:.3}, mean: {:.3}, max: {:.3}", min, mean, max));
+
+            // The bins go from the worst triangles to the best ones
+            let counts = histogram(&values, HISTOGRAM_BINS, self.metric.range())
+                            .into_iter()
+                            .map(|count| count as f32)
+                            .collect::<Vec<_>>();
+            ui.plot_histogram(imgui::im_str!("##quality_histogram"), &counts)
+                .scale_min(0.0)
+                .graph_size([0.0, 60.0])
+                .build();
+        }
+        Ok(())
+    }
+
+    /// Regenerates the buffer of the triangles colored by the selected metric.
+    fn update_quality_overlay(&mut self) -> Result<()> {
+        let points = self.alg.points();
+        let vertices = self.alg.indices().iter().map(|&idx| points[idx]).collect::<Vec<_>>();
+        let values = self.qualities
+                        .iter()
+                        .flat_map(|quality| {
+                            let value = self.metric.value(quality);
+                            vec![ value; 3 ]
+                        })
+                        .collect::<Vec<_>>();
+        self.quality_overlay.set_vertices(&vertices, &values, self.metric.range())
+    }
+
+    fn update_points_buffer(&mut self) -> Result<()> {
+        let vertices = self.alg.points()
+                                .iter()
+                                .cloned()
+                                .map(Vertex::new)
+                                .collect::<Vec<_>>();
+        self.points_buffer = VertexBuffer::new(self.facade, &vertices)?; // Regenerate the vertex buffer
+        self.points_list = layers::points(self.alg.points());
+        Ok(())
+    }
+}
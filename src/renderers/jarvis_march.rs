@@ -0,0 +1,177 @@
+use crate::{
+    Result,
+    algorithms::{ JarvisMarch, Degeneracy, Record, hull_positions, jarvis_march::JarvisMarchSteps },
+    graphics::Viewport,
+    render::{ layers, DrawList, GliumRenderer },
+    scene::SharedScene,
+    ui::{ PointInput, ExecTimeHistory, Playback, window::algorithms::{ Drawable, Configurable } },
+};
+
+use std::time::Instant;
+
+use glium::{ Frame, backend::Facade };
+use winit::{
+    event::{ Event, WindowEvent, MouseButton, ElementState },
+    window::Window,
+};
+
+/// Largest number of points whose steps can be recorded, every state of the hull is copied
+const MAX_RECORDED_POINTS: usize = 2000;
+
+pub struct JarvisMarchRenderer<'f> {
+    alg: JarvisMarch,
+    /// The input points are the vertices of the geometry of the scene, the hull is written back to it
+    scene: SharedScene,
+    /// Revision of the scene the hull was computed from
+    revision: u64,
+    renderer: GliumRenderer<'f>,
+    /// Draw list of all the points
+    points_list: DrawList,
+    /// Draw list of the hull and its vertices
+    hull_list: DrawList,
+    exec_time: ExecTimeHistory,
+    point_input: PointInput<'f>,
+    /// Recorded steps, drawn instead of the hull while they are played back
+    playback: Playback<'f>,
+}
+
+impl<'f> Drawable for JarvisMarchRenderer<'f> {
+    fn update(&mut self) -> Result<()> {
+        if self.scene.borrow().revision() != self.revision {
+            self.compute_hull()?;
+            self.playback.stop()?; // The recorded steps are outdated
+        }
+        self.playback.update()
+    }
+
+    fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        self.point_input.draw(target, viewport)?;
+        self.draw_points(target, viewport)?;
+        if self.playback.is_active() {
+            return self.playback.draw(target, viewport);
+        }
+        self.draw_hull(target, viewport)
+    }
+
+    fn handle_events(&mut self, window: &Window, event: &Event<()>, io: &imgui::Io, viewport: &Viewport) -> Result<()> {
+        if let Event::WindowEvent { event, .. } = event {
+            if let WindowEvent::MouseInput { button, state, .. } = event {
+                if !io.want_capture_mouse && // Ignore clicks when the cursor is over an ImGui window
+                    button == &MouseButton::Left && state == &ElementState::Pressed {
+
+                    // Add a point on click
+                    let coords = viewport.window_pos_to_normalized(io.mouse_pos.into(), window);
+                    self.scene.borrow_mut().add_point(self.point_input.place(coords));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'f> Configurable for JarvisMarchRenderer<'f> {
+    fn name(&self) -> &'static str {
+        "Jarvis march"
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        Some("Also known as gift wrapping. Starting from the leftmost point, which is always on the hull, \
+              the algorithm repeatedly picks the point such that all the other points lie on the same side \
+              of the line going through the current hull point and that candidate, until it wraps back to the start.")
+    }
+
+    fn pseudo_code(&self) -> Option<&'static str> {
+        Some(concat!(
+            "p = leftmost point\n",
+            "loop:\n",
+            "    add p to the hull\n",
+            "    q = any point other than p\n",
+            "    for each point r:\n",
+            "        if r is to the right of (p, q): q = r\n",
+            "    p = q\n",
+            "    if p is the leftmost point: stop",
+        ))
+    }
+
+    fn complexity(&self) -> Option<&'static str> {
+        Some("O(nh), h being the number of points on the hull")
+    }
+
+    fn degeneracies(&self) -> &'static [Degeneracy] {
+        &[ Degeneracy::CollinearPoints ]
+    }
+
+    fn configure(&mut self, ui: &imgui::Ui) -> Result<()> {
+        ui.text(imgui::im_str!("{} vertices", self.alg.points().len()));
+        ui.text(imgui::im_str!("Area: {:.4}, perimeter: {:.4}", self.alg.area(), self.alg.perimeter()));
+
+        if ui.button(imgui::im_str!("Clear Points"), [0.0, 0.0]) {
+            self.scene.borrow_mut().clear_points();
+        }
+
+        if let Some(point) = self.point_input.configure(ui)? {
+            self.scene.borrow_mut().add_point(point);
+        }
+
+        if self.playback.configure(ui, self.alg.points().len())? {
+            let points = self.alg.points().to_vec();
+            let snapshots = JarvisMarchSteps::new(&points).record();
+            self.playback.start(points, snapshots)?;
+        }
+
+        self.exec_time.configure(ui);
+        Ok(())
+    }
+}
+
+impl<'f> JarvisMarchRenderer<'f> {
+    pub fn new(facade: &'f dyn Facade, scene: SharedScene) -> Result<Self> {
+        Ok(Self {
+            alg: JarvisMarch::new(),
+            scene,
+            revision: 0,
+            renderer: GliumRenderer::new(facade)?,
+            points_list: layers::list(), // Start without any point
+            hull_list: layers::list(), // Same for the hull
+            exec_time: ExecTimeHistory::default(),
+            point_input: PointInput::new(facade)?,
+            playback: Playback::new(facade, MAX_RECORDED_POINTS)?,
+        })
+    }
+
+    fn draw_points(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        self.renderer.draw_in(&self.points_list, target, viewport.rect(target))?;
+        Ok(())
+    }
+
+    fn draw_hull(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        self.renderer.draw_in(&self.hull_list, target, viewport.rect(target))?;
+        Ok(())
+    }
+
+    /// Computes the hull of the points, segments and polygons of the scene and stores it in the scene.
+    fn compute_hull(&mut self) -> Result<()> {
+        let mut scene = self.scene.borrow_mut();
+        self.revision = scene.revision();
+
+        let start_time = Instant::now();
+        self.alg.set_primitives(&scene.primitives());
+        if self.alg.points().is_empty() {
+            self.exec_time.clear();
+        } else {
+            self.exec_time.push(Instant::now() - start_time);
+        }
+
+        scene.set_hull(hull_positions(self.alg.points(), self.alg.hull()));
+        drop(scene);
+        self.update_buffers()
+    }
+
+    /// Regenerates the draw lists from the points and the hull of the algorithm.
+    fn update_buffers(&mut self) -> Result<()> {
+        let points = self.alg.points();
+        self.points_list = layers::points(points);
+        self.hull_list = layers::convex_hull(&hull_positions(points, self.alg.hull()));
+        Ok(())
+    }
+}
@@ -0,0 +1,182 @@
+use crate::{
+    Result,
+    algorithms::{ largest_inscribed_circle, InscribedCircle },
+    graphics::{ self, Shape, Viewport },
+    math::Vec2,
+    scene::SharedScene,
+    ui::{ PointInput, ExecTimeHistory, window::algorithms::{ Drawable, Configurable } },
+};
+
+use std::time::Instant;
+
+use glium::{
+    index::PrimitiveType, Frame, Program,
+    backend::Facade,
+};
+use winit::{
+    event::{ Event, WindowEvent, MouseButton, ElementState },
+    window::Window,
+};
+
+/// The refinement stops when the radius cannot grow by more than this, in normalized coordinates
+const PRECISION: f32 = 1e-4;
+
+pub struct LargestInscribedCircleRenderer<'f> {
+    /// The last polygon of the scene is loaded when it changes
+    scene: SharedScene,
+    revision: u64,
+    /// Vertices of the polygon, clicks add new ones
+    polygon: Vec<Vec2>,
+    result: Option<InscribedCircle>,
+    program: Program,
+    outline: Shape<'f>,
+    vertices: Shape<'f>,
+    circle: Shape<'f>,
+    center: Shape<'f>,
+    exec_time: ExecTimeHistory,
+    point_input: PointInput<'f>,
+}
+
+impl<'f> Drawable for LargestInscribedCircleRenderer<'f> {
+    fn update(&mut self) -> Result<()> {
+        let scene = self.scene.borrow();
+        if scene.revision() != self.revision {
+            self.revision = scene.revision();
+            if let Some(polygon) = scene.polygons().last() {
+                self.polygon = polygon.clone();
+                drop(scene);
+                self.compute()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        self.point_input.draw(target, viewport)?;
+        self.outline.draw(target, &self.program, viewport)?;
+        self.circle.draw(target, &self.program, viewport)?;
+        self.vertices.draw(target, &self.program, viewport)?;
+        self.center.draw(target, &self.program, viewport)
+    }
+
+    fn handle_events(&mut self, window: &Window, event: &Event<()>, io: &imgui::Io, viewport: &Viewport) -> Result<()> {
+        if let Event::WindowEvent { event, .. } = event {
+            if let WindowEvent::MouseInput { button, state, .. } = event {
+                if !io.want_capture_mouse && // Ignore clicks when the cursor is over an ImGui window
+                    button == &MouseButton::Left && state == &ElementState::Pressed {
+
+                    // Add a vertex on click
+                    let coords = viewport.window_pos_to_normalized(io.mouse_pos.into(), window);
+                    self.polygon.push(self.point_input.place(coords));
+                    self.compute()?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'f> Configurable for LargestInscribedCircleRenderer<'f> {
+    fn name(&self) -> &'static str {
+        "Largest inscribed circle"
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        Some("The center of the largest circle inside a polygon is its pole of inaccessibility, \
+              the point farthest from the boundary. The bounding box of the polygon is covered with square cells, \
+              the distance from the center of a cell to the boundary bounds the distances of all its points. \
+              The most promising cells are split first, and the cells that cannot contain a farther point are dropped. \
+              Click to add vertices to the polygon, the last polygon of the scene is loaded when it changes.")
+    }
+
+    fn pseudo_code(&self) -> Option<&'static str> {
+        Some(concat!(
+            "queue = cells covering the bounding box\n",
+            "best = centroid of the polygon\n",
+            "while queue is not empty:\n",
+            "    cell = cell of queue with the largest\n",
+            "           d(center) + half diagonal\n",
+            "    if d(cell center) > d(best):\n",
+            "        best = cell center\n",
+            "    if cell can improve best by more than\n",
+            "       the precision:\n",
+            "        add the 4 quarters of cell to queue",
+        ))
+    }
+
+    fn complexity(&self) -> Option<&'static str> {
+        Some("O(n) per probed cell for n vertices, the number of cells depends on the shape and the precision")
+    }
+
+    fn configure(&mut self, ui: &imgui::Ui) -> Result<()> {
+        ui.text(imgui::im_str!("{} vertices", self.polygon.len()));
+        if let Some(result) = &self.result {
+            let circle = result.circle;
+            ui.text(imgui::im_str!("Center: ({:.4}, {:.4})", circle.center.x, circle.center.y));
+            ui.text(imgui::im_str!("Radius: {:.4}", circle.radius));
+            ui.text(imgui::im_str!("{} cells probed", result.probes));
+        }
+
+        if ui.button(imgui::im_str!("Clear Polygon"), [0.0, 0.0]) {
+            self.polygon.clear();
+            self.compute()?;
+        }
+        ui.same_line(0.0);
+        if ui.button(imgui::im_str!("Add to Scene"), [0.0, 0.0]) && self.polygon.len() >= 3 {
+            self.scene.borrow_mut().add_polygon(self.polygon.clone());
+        }
+
+        if let Some(point) = self.point_input.configure(ui)? {
+            self.polygon.push(point);
+            self.compute()?;
+        }
+
+        self.exec_time.configure(ui);
+        Ok(())
+    }
+}
+
+impl<'f> LargestInscribedCircleRenderer<'f> {
+    pub fn new(facade: &'f dyn Facade, scene: SharedScene) -> Result<Self> {
+        let vs = graphics::SHADERS._2d_vs;
+        let fs = graphics::SHADERS.basic_fs;
+        let program = Program::from_source(facade, vs, fs, None)?;
+
+        Ok(Self {
+            scene,
+            revision: 0,
+            polygon: Vec::new(),
+            result: None,
+            program,
+            outline: Shape::new(facade, PrimitiveType::LineLoop, [ 0.0, 0.2, 1.0 ])?,
+            vertices: Shape::new(facade, PrimitiveType::Points, [ 1.0, 1.0, 1.0 ])?,
+            circle: Shape::new(facade, PrimitiveType::LineLoop, [ 0.0, 0.8, 0.0 ])?,
+            center: Shape::new(facade, PrimitiveType::Points, [ 0.0, 0.8, 0.0 ])?,
+            exec_time: ExecTimeHistory::default(),
+            point_input: PointInput::new(facade)?,
+        })
+    }
+
+    /// Computes the circle of the polygon and regenerates the buffers.
+    fn compute(&mut self) -> Result<()> {
+        let start_time = Instant::now();
+        self.result = largest_inscribed_circle(&self.polygon, PRECISION);
+        match self.result {
+            Some(_) => self.exec_time.push(Instant::now() - start_time),
+            None => self.exec_time.clear(),
+        }
+
+        self.outline.set_vertices(&self.polygon)?;
+        self.vertices.set_vertices(&self.polygon)?;
+        match self.result {
+            Some(result) => {
+                self.circle.set_vertices(&result.circle.points(64).collect::<Vec<_>>())?;
+                self.center.set_vertices(&[ result.circle.center ])
+            },
+            None => {
+                self.circle.clear()?;
+                self.center.clear()
+            },
+        }
+    }
+}
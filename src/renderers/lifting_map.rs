@@ -0,0 +1,310 @@
+use crate::{
+    Result,
+    algorithms::{ Incremental2dTriangulation, Degeneracy, lower_hull, paraboloid_height, shared_triangles, validate_delaunay },
+    graphics::{ self, Shape, Viewport, shape::triangle_edges },
+    math::{ Vec2, float },
+    scene::SharedScene,
+    ui::{ ExecTimeHistory, window::algorithms::{ Drawable, Configurable } },
+};
+
+use std::time::Instant;
+
+use glium::{
+    index::PrimitiveType, Frame, Program,
+    backend::Facade,
+};
+use winit::{
+    event::{ Event, WindowEvent, MouseButton, ElementState },
+    window::Window,
+};
+
+/// Distance of the eye from the origin along the z axis
+const EYE_DISTANCE: f32 = 4.0;
+/// Size on the screen of the unit square, before the perspective
+const SCALE: f32 = 0.6;
+/// Lowered so that the plane and the lifted points are both in view
+const HEIGHT_OFFSET: f32 = 0.5;
+/// Radians of rotation per normalized unit of mouse drag
+const DRAG_SENSITIVITY: f32 = 2.0;
+/// Fraction of the lift covered per second while animating
+const LIFT_SPEED: f32 = 0.4;
+const MAX_TIME_STEP: f32 = 0.1;
+/// Radius of the largest circle of the paraboloid wireframe, and the numbers of circles and meridians
+const PARABOLOID_RADIUS: f32 = 1.0;
+const PARABOLOID_CIRCLES: usize = 4;
+const PARABOLOID_MERIDIANS: usize = 16;
+/// Segments of a circle, and of a meridian between two circles
+const CIRCLE_SEGMENTS: usize = 48;
+const MERIDIAN_SEGMENTS: usize = 4;
+
+pub struct LiftingMapRenderer<'f> {
+    /// The input points are those of the scene
+    scene: SharedScene,
+    /// Revision of the scene the points were read from
+    revision: u64,
+    points: Vec<Vec2>,
+    /// Triangles of the lower hull of the lifted points, and of the Delaunay triangulation of the points
+    lower: Vec<usize>,
+    delaunay: Vec<usize>,
+    /// Number of triangles of the lower hull in the Delaunay triangulation
+    shared: usize,
+    /// Edges of the projected lower hull that are not locally Delaunay
+    illegal: usize,
+    /// Fraction of the height of the paraboloid the points are lifted to, 0 in the plane and 1 on the paraboloid
+    lift: f32,
+    /// While animating, the points go up and down between the plane and the paraboloid
+    animate: bool,
+    rising: bool,
+    last_update: Instant,
+    show_paraboloid: bool,
+    show_delaunay: bool,
+    /// Rotation of the view around the vertical axis and tilt towards the camera, dragged with the mouse
+    yaw: f32,
+    pitch: f32,
+    dragging: Option<Vec2>,
+    program: Program,
+    paraboloid: Shape<'f>,
+    delaunay_edges: Shape<'f>,
+    /// Vertical lines from the points to the lifted points
+    lifts: Shape<'f>,
+    hull_edges: Shape<'f>,
+    plane_points: Shape<'f>,
+    lifted_points: Shape<'f>,
+    exec_time: ExecTimeHistory,
+}
+
+impl<'f> Drawable for LiftingMapRenderer<'f> {
+    fn update(&mut self) -> Result<()> {
+        if self.scene.borrow().revision() != self.revision {
+            self.read_points();
+        }
+
+        let now = Instant::now();
+        let dt = (now - self.last_update).as_secs_f32().min(MAX_TIME_STEP);
+        self.last_update = now;
+        if self.animate {
+            let direction = if self.rising { 1.0 } else { -1.0 };
+            self.lift = crate::math::clamp(self.lift + direction * dt * LIFT_SPEED, 0.0, 1.0);
+            if self.lift >= 1.0 || self.lift <= 0.0 {
+                self.rising = !self.rising;
+            }
+        }
+        self.project()
+    }
+
+    fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        if self.show_paraboloid {
+            self.paraboloid.draw(target, &self.program, viewport)?;
+        }
+        if self.show_delaunay {
+            self.delaunay_edges.draw(target, &self.program, viewport)?;
+        }
+        self.lifts.draw(target, &self.program, viewport)?;
+        self.hull_edges.draw(target, &self.program, viewport)?;
+        self.plane_points.draw(target, &self.program, viewport)?;
+        self.lifted_points.draw(target, &self.program, viewport)
+    }
+
+    fn handle_events(&mut self, window: &Window, event: &Event<()>, io: &imgui::Io, viewport: &Viewport) -> Result<()> {
+        if let Event::WindowEvent { event, .. } = event {
+            let coords = viewport.window_pos_to_normalized(io.mouse_pos.into(), window);
+            match event {
+                WindowEvent::MouseInput { button: MouseButton::Left, state: ElementState::Pressed, .. } => {
+                    if !io.want_capture_mouse { // Ignore clicks when the cursor is over an ImGui window
+                        self.dragging = Some(coords);
+                    }
+                },
+                WindowEvent::MouseInput { button: MouseButton::Left, state: ElementState::Released, .. } => {
+                    self.dragging = None;
+                },
+                WindowEvent::CursorMoved { .. } => {
+                    // Turn the view
+                    if let Some(last) = self.dragging {
+                        let delta = &coords - &last;
+                        self.yaw += delta.x * DRAG_SENSITIVITY;
+                        self.pitch = crate::math::clamp(self.pitch - delta.y * DRAG_SENSITIVITY, -1.5, 1.5);
+                        self.dragging = Some(coords);
+                    }
+                },
+                _ => {},
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'f> Configurable for LiftingMapRenderer<'f> {
+    fn name(&self) -> &'static str {
+        "Lifting map"
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        Some("The points of the scene are lifted onto the paraboloid z = x² + y². \
+              A circle of the plane lifts to the intersection of the paraboloid with a plane, \
+              the points inside the circle lift below that plane and those outside of it above. \
+              A triangle has an empty circumcircle when no lifted point is below the plane of its lifted vertices, \
+              which makes it a facet of the lower convex hull of the lifted points. \
+              Projected back onto the plane, the lower hull is the Delaunay triangulation, \
+              compared here with the one built by the incremental triangulation and edge flipping. \
+              Drag with the mouse to turn the view.")
+    }
+
+    fn pseudo_code(&self) -> Option<&'static str> {
+        Some(concat!(
+            "for each point p = (x, y):\n",
+            "    lift p to (x, y, x² + y²)\n",
+            "hull = 3D convex hull of the lifted points\n",
+            "for each facet f of the hull:\n",
+            "    if the normal of f points down:\n",
+            "        add the projection of f to the triangulation",
+        ))
+    }
+
+    fn complexity(&self) -> Option<&'static str> {
+        Some("O(n log n) with an optimal 3D hull, the beneath-beyond hull used here is O(n²) in the worst case")
+    }
+
+    fn degeneracies(&self) -> &'static [Degeneracy] {
+        &[ Degeneracy::CollinearPoints, Degeneracy::CocircularPoints ]
+    }
+
+    fn configure(&mut self, ui: &imgui::Ui) -> Result<()> {
+        ui.text(imgui::im_str!("{} points, {} triangles in the lower hull", self.points.len(), self.lower.len() / 3));
+        if !self.lower.is_empty() {
+            if self.illegal > 0 {
+                // The lifted points are perturbed to break the ties between cocircular points
+                ui.text(imgui::im_str!("{} illegal edges between nearly cocircular points", self.illegal));
+            } else {
+                ui.text_disabled(imgui::im_str!("The projection is a Delaunay triangulation"));
+            }
+            ui.text(imgui::im_str!("{} of the {} triangles of the incremental triangulation", self.shared, self.delaunay.len() / 3));
+        }
+
+        ui.checkbox(imgui::im_str!("Animate the lift"), &mut self.animate);
+        if imgui::Slider::new(imgui::im_str!("Lift"), 0.0..=1.0).build(ui, &mut self.lift) {
+            self.animate = false;
+        }
+        ui.checkbox(imgui::im_str!("Paraboloid"), &mut self.show_paraboloid);
+        ui.same_line(0.0);
+        ui.checkbox(imgui::im_str!("Delaunay triangulation"), &mut self.show_delaunay);
+
+        if ui.button(imgui::im_str!("Random Points"), [0.0, 0.0]) {
+            self.scene.borrow_mut().add_random_points(100);
+        }
+        ui.same_line(0.0);
+        if ui.button(imgui::im_str!("Clear Points"), [0.0, 0.0]) {
+            self.scene.borrow_mut().clear_points();
+        }
+
+        self.exec_time.configure(ui);
+        Ok(())
+    }
+}
+
+impl<'f> LiftingMapRenderer<'f> {
+    pub fn new(facade: &'f dyn Facade, scene: SharedScene) -> Result<Self> {
+        let vs = graphics::SHADERS._2d_vs;
+        let fs = graphics::SHADERS.basic_fs;
+        let program = Program::from_source(facade, vs, fs, None)?;
+
+        let mut hull_edges = Shape::new(facade, PrimitiveType::LinesList, [ 0.0, 0.8, 0.0 ])?;
+        hull_edges.size = 2.0;
+        let mut plane_points = Shape::new(facade, PrimitiveType::Points, [ 1.0, 1.0, 1.0 ])?;
+        plane_points.size = 5.0;
+        let mut lifted_points = Shape::new(facade, PrimitiveType::Points, [ 1.0, 0.8, 0.2 ])?;
+        lifted_points.size = 6.0;
+
+        Ok(Self {
+            scene,
+            revision: 0,
+            points: Vec::new(),
+            lower: Vec::new(),
+            delaunay: Vec::new(),
+            shared: 0,
+            illegal: 0,
+            lift: 1.0,
+            animate: false,
+            rising: false,
+            last_update: Instant::now(),
+            show_paraboloid: true,
+            show_delaunay: true,
+            yaw: 0.5,
+            pitch: 0.5,
+            dragging: None,
+            program,
+            paraboloid: Shape::new(facade, PrimitiveType::LinesList, [ 0.25, 0.25, 0.3 ])?,
+            delaunay_edges: Shape::new(facade, PrimitiveType::LinesList, [ 0.0, 0.2, 1.0 ])?,
+            lifts: Shape::new(facade, PrimitiveType::LinesList, [ 0.4, 0.4, 0.4 ])?,
+            hull_edges,
+            plane_points,
+            lifted_points,
+            exec_time: ExecTimeHistory::default(),
+        })
+    }
+
+    /// Replaces the points with those of the scene, computes the lower hull of their lifts and compares it to their Delaunay triangulation.
+    fn read_points(&mut self) {
+        let scene = self.scene.borrow();
+        self.revision = scene.revision();
+        self.points = scene.points().to_vec();
+        drop(scene);
+
+        let start_time = Instant::now();
+        self.lower = lower_hull(&self.points);
+        if self.points.is_empty() {
+            self.exec_time.clear();
+        } else {
+            self.exec_time.push(Instant::now() - start_time);
+        }
+
+        self.delaunay = Incremental2dTriangulation::delaunay(&self.points);
+        self.shared = shared_triangles(&self.lower, &self.delaunay);
+        self.illegal = validate_delaunay(&self.points, &self.lower).len();
+    }
+
+    /// Projects the points, lifted to the current height, onto the screen and regenerates the buffers.
+    fn project(&mut self) -> Result<()> {
+        let (sin_yaw, cos_yaw) = (float::sin(self.yaw), float::cos(self.yaw));
+        let (sin_pitch, cos_pitch) = (float::sin(self.pitch), float::cos(self.pitch));
+        let lift = self.lift;
+        // The plane of the points is horizontal, the height goes up the screen
+        let project = |p: Vec2, height: f32| {
+            let (x, y, z) = (p.x, height * lift - HEIGHT_OFFSET, p.y);
+            let (x, z) = (cos_yaw * x + sin_yaw * z, -sin_yaw * x + cos_yaw * z);
+            let (y, z) = (cos_pitch * y - sin_pitch * z, sin_pitch * y + cos_pitch * z);
+            let factor = SCALE * EYE_DISTANCE / (EYE_DISTANCE - z);
+            Vec2::new(x * factor, y * factor)
+        };
+
+        let plane = self.points.iter().map(|&p| project(p, 0.0)).collect::<Vec<_>>();
+        let lifted = self.points.iter().map(|&p| project(p, paraboloid_height(p))).collect::<Vec<_>>();
+        self.plane_points.set_vertices(&plane)?;
+        self.lifted_points.set_vertices(&lifted)?;
+        self.lifts.set_vertices(&plane.iter().zip(&lifted).flat_map(|(&a, &b)| vec![ a, b ]).collect::<Vec<_>>())?;
+        self.hull_edges.set_vertices(&triangle_edges(&lifted, &self.lower))?;
+        self.delaunay_edges.set_vertices(&triangle_edges(&plane, &self.delaunay))?;
+
+        let mut paraboloid = Vec::new();
+        let point = |radius: f32, angle: f32| Vec2::new(radius * float::cos(angle), radius * float::sin(angle));
+        for circle in 1..=PARABOLOID_CIRCLES {
+            let radius = PARABOLOID_RADIUS * circle as f32 / PARABOLOID_CIRCLES as f32;
+            for i in 0..CIRCLE_SEGMENTS {
+                for &j in &[ i, i + 1 ] {
+                    let p = point(radius, j as f32 * 2.0 * std::f32::consts::PI / CIRCLE_SEGMENTS as f32);
+                    paraboloid.push(project(p, paraboloid_height(p)));
+                }
+            }
+        }
+        let segments = PARABOLOID_CIRCLES * MERIDIAN_SEGMENTS;
+        for meridian in 0..PARABOLOID_MERIDIANS {
+            let angle = meridian as f32 * 2.0 * std::f32::consts::PI / PARABOLOID_MERIDIANS as f32;
+            for i in 0..segments {
+                for &j in &[ i, i + 1 ] {
+                    let p = point(PARABOLOID_RADIUS * j as f32 / segments as f32, angle);
+                    paraboloid.push(project(p, paraboloid_height(p)));
+                }
+            }
+        }
+        self.paraboloid.set_vertices(&paraboloid)
+    }
+}
@@ -0,0 +1,247 @@
+use crate::{
+    Result,
+    algorithms::{ lp2d, feasible_region },
+    graphics::{ self, Shape, Viewport },
+    math::{ Vec2, Rect, HalfPlane },
+    ui::{ ExecTimeHistory, window::algorithms::{ Drawable, Configurable } },
+};
+
+use std::time::Instant;
+
+use glium::{
+    index::PrimitiveType, Frame, Program,
+    backend::Facade,
+};
+use winit::{
+    event::{ Event, WindowEvent, MouseButton, ElementState },
+    window::Window,
+};
+
+/// Half of the length of the drawn boundary lines, long enough to cross the viewport
+const LINE_EXTENT: f32 = 10.0;
+/// Distance from the cursor under which the tip of the objective arrow is picked, in normalized coordinates
+const PICK_RADIUS: f32 = 0.04;
+/// Spacing and length of the ticks on the excluded side of the boundary lines
+const TICK_SPACING: f32 = 0.05;
+const TICK_LENGTH: f32 = 0.02;
+/// Length of the sides of the head of the objective arrow
+const ARROW_HEAD: f32 = 0.04;
+/// Number of half-planes added by the Random button
+const RANDOM_COUNT: usize = 8;
+
+pub struct LinearProgrammingRenderer<'f> {
+    constraints: Vec<HalfPlane>,
+    /// The objective is to go as far as possible in the direction from the origin to this point
+    objective: Vec2,
+    dragging: bool,
+    /// First point of the boundary of the half-plane being drawn
+    pending: Option<Vec2>,
+    optimum: Option<Vec2>,
+    /// The program has no solution because the region is empty, as opposed to unbounded
+    infeasible: bool,
+    program: Program,
+    region: Shape<'f>,
+    lines: Shape<'f>,
+    ticks: Shape<'f>,
+    arrow: Shape<'f>,
+    start: Shape<'f>,
+    optimum_point: Shape<'f>,
+    exec_time: ExecTimeHistory,
+}
+
+impl<'f> Drawable for LinearProgrammingRenderer<'f> {
+    fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        self.region.draw(target, &self.program, viewport)?;
+        self.ticks.draw(target, &self.program, viewport)?;
+        self.lines.draw(target, &self.program, viewport)?;
+        self.arrow.draw(target, &self.program, viewport)?;
+        self.start.draw(target, &self.program, viewport)?;
+        self.optimum_point.draw(target, &self.program, viewport)
+    }
+
+    fn handle_events(&mut self, window: &Window, event: &Event<()>, io: &imgui::Io, viewport: &Viewport) -> Result<()> {
+        if let Event::WindowEvent { event, .. } = event {
+            let coords = viewport.window_pos_to_normalized(io.mouse_pos.into(), window);
+            match event {
+                WindowEvent::MouseInput { button: MouseButton::Left, state: ElementState::Pressed, .. } => {
+                    if !io.want_capture_mouse { // Ignore clicks when the cursor is over an ImGui window
+                        // Drag the objective, or add an end of a boundary line
+                        self.dragging = (&coords - &self.objective).length() < PICK_RADIUS;
+                        if !self.dragging {
+                            match self.pending.take() {
+                                Some(start) if start != coords => self.constraints.push(HalfPlane::left_of(start, coords)),
+                                Some(_) => {},
+                                None => self.pending = Some(coords),
+                            }
+                            self.solve()?;
+                        }
+                    }
+                },
+                WindowEvent::MouseInput { button: MouseButton::Left, state: ElementState::Released, .. } => {
+                    self.dragging = false;
+                },
+                WindowEvent::CursorMoved { .. } => {
+                    if self.dragging {
+                        self.objective = coords;
+                        self.solve()?;
+                    }
+                },
+                _ => {},
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'f> Configurable for LinearProgrammingRenderer<'f> {
+    fn name(&self) -> &'static str {
+        "Linear programming"
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        Some("Finds the point of the intersection of half-planes that goes the farthest in the direction of the objective, \
+              with Seidel's randomized incremental algorithm. The half-planes are added in a random order: \
+              the optimum only changes when the new half-plane excludes it, \
+              and the new optimum is then on the boundary line of that half-plane, \
+              where it is found by a 1D program against the previous half-planes. \
+              Click twice to add a half-plane on the left of the line from the first click to the second, \
+              the ticks are on the excluded side. Drag the tip of the arrow to change the objective.")
+    }
+
+    fn pseudo_code(&self) -> Option<&'static str> {
+        Some(concat!(
+            "shuffle the half-planes h1, ..., hn\n",
+            "v = corner of a bounding box in the direction c\n",
+            "for i = 1 to n:\n",
+            "    if v is not in hi:\n",
+            "        v = maximum of c · x on the line of hi,\n",
+            "            subject to h1, ..., hi-1\n",
+            "        if there is none: infeasible\n",
+            "return v",
+        ))
+    }
+
+    fn complexity(&self) -> Option<&'static str> {
+        Some("O(n) expected for n half-planes, the i-th one moves the optimum with probability 2 / i for an O(i) 1D program")
+    }
+
+    fn configure(&mut self, ui: &imgui::Ui) -> Result<()> {
+        ui.text(imgui::im_str!("{} half-planes", self.constraints.len()));
+        match self.optimum {
+            Some(p) => ui.text(imgui::im_str!("Optimum at ({:.3}, {:.3}), value {:.3}", p.x, p.y, self.objective.dot(p))),
+            None if self.infeasible => ui.text(imgui::im_str!("Infeasible: the half-planes do not meet")),
+            None => ui.text(imgui::im_str!("Unbounded in the direction of the objective")),
+        }
+
+        if ui.button(imgui::im_str!("Random"), [0.0, 0.0]) {
+            // Lines around the origin, which stays on their feasible side
+            for _ in 0..RANDOM_COUNT {
+                let normal = Vec2::random_range(-1.0, 1.0, -1.0, 1.0).normalized();
+                let offset = 0.3 + rand::random::<f32>() * 0.5;
+                self.constraints.push(HalfPlane::new(normal, offset));
+            }
+            self.solve()?;
+        }
+        ui.same_line(0.0);
+        if ui.button(imgui::im_str!("Remove last"), [0.0, 0.0]) {
+            self.constraints.pop();
+            self.solve()?;
+        }
+        ui.same_line(0.0);
+        if ui.button(imgui::im_str!("Clear"), [0.0, 0.0]) {
+            self.constraints.clear();
+            self.pending = None;
+            self.solve()?;
+        }
+
+        self.exec_time.configure(ui);
+        Ok(())
+    }
+}
+
+impl<'f> LinearProgrammingRenderer<'f> {
+    pub fn new(facade: &'f dyn Facade) -> Result<Self> {
+        let vs = graphics::SHADERS._2d_vs;
+        let fs = graphics::SHADERS.basic_fs;
+        let program = Program::from_source(facade, vs, fs, None)?;
+
+        let mut lines = Shape::new(facade, PrimitiveType::LinesList, [ 1.0, 1.0, 1.0 ])?;
+        lines.size = 2.0;
+        let mut arrow = Shape::new(facade, PrimitiveType::LinesList, [ 1.0, 0.9, 0.0 ])?;
+        arrow.size = 2.0;
+        let mut optimum_point = Shape::new(facade, PrimitiveType::Points, [ 1.0, 0.2, 0.2 ])?;
+        optimum_point.size = 12.0;
+
+        let mut renderer = Self {
+            constraints: Vec::new(),
+            objective: Vec2::new(0.2, 0.3),
+            dragging: false,
+            pending: None,
+            optimum: None,
+            infeasible: false,
+            program,
+            region: Shape::new(facade, PrimitiveType::TriangleFan, [ 0.1, 0.25, 0.3 ])?,
+            lines,
+            ticks: Shape::new(facade, PrimitiveType::LinesList, [ 0.6, 0.6, 0.6 ])?,
+            arrow,
+            start: Shape::new(facade, PrimitiveType::Points, [ 0.3, 0.8, 1.0 ])?,
+            optimum_point,
+            exec_time: ExecTimeHistory::default(),
+        };
+        renderer.solve()?;
+        Ok(renderer)
+    }
+
+    /// Solves the program and regenerates the buffers.
+    fn solve(&mut self) -> Result<()> {
+        let objective = self.objective;
+        let start_time = Instant::now();
+        self.optimum = lp2d(&self.constraints, objective);
+        self.exec_time.push(Instant::now() - start_time);
+        self.infeasible = self.optimum.is_none() && lp2d(&self.constraints, Vec2::new(0.0, 0.0)).is_none();
+
+        let bounds = Rect::new(Vec2::new(-LINE_EXTENT, -LINE_EXTENT), Vec2::new(LINE_EXTENT, LINE_EXTENT));
+        let region = feasible_region(&self.constraints, &bounds);
+        self.region.set_vertices(&region)?;
+
+        let mut lines = Vec::with_capacity(2 * self.constraints.len());
+        let mut ticks = Vec::new();
+        for half_plane in &self.constraints {
+            let (origin, direction) = match half_plane.boundary_point() {
+                Some(origin) => (origin, half_plane.direction().normalized()),
+                None => continue,
+            };
+            lines.push(&origin - &(&direction * LINE_EXTENT));
+            lines.push(&origin + &(&direction * LINE_EXTENT));
+
+            let outward = &half_plane.normal.normalized() * TICK_LENGTH;
+            let count = (LINE_EXTENT / TICK_SPACING) as i32;
+            for i in -count..=count {
+                let p = &origin + &(&direction * (i as f32 * TICK_SPACING));
+                ticks.push(p);
+                ticks.push(&p + &outward);
+            }
+        }
+        self.lines.set_vertices(&lines)?;
+        self.ticks.set_vertices(&ticks)?;
+
+        // Arrow from the origin to the objective
+        let origin = Vec2::new(0.0, 0.0);
+        let mut arrow = vec![ origin, objective ];
+        if objective != origin {
+            let back = &objective.normalized() * -ARROW_HEAD;
+            let side = &Vec2::new(-back.y, back.x) * 0.5;
+            arrow.extend_from_slice(&[ objective, &(&objective + &back) + &side, objective, &(&objective + &back) - &side ]);
+        }
+        self.arrow.set_vertices(&arrow)?;
+
+        match self.pending {
+            Some(p) => self.start.set_vertices(&[ p ])?,
+            None => self.start.clear()?,
+        }
+        match self.optimum {
+            Some(p) => self.optimum_point.set_vertices(&[ p ]),
+            None => self.optimum_point.clear(),
+        }
+    }
+}
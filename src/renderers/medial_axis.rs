@@ -0,0 +1,156 @@
+use crate::{
+    Result,
+    algorithms::MedialAxis,
+    graphics::Viewport,
+    math::Vec2,
+    render::{ layers, DrawList, GliumRenderer },
+    scene::SharedScene,
+    ui::{ PointInput, ExecTimeHistory, window::algorithms::{ Drawable, Configurable } },
+};
+
+use std::time::Instant;
+
+use glium::{ Frame, backend::Facade };
+use winit::{
+    event::{ Event, WindowEvent, MouseButton, ElementState },
+    window::Window,
+};
+
+pub struct MedialAxisRenderer<'f> {
+    /// The last polygon of the scene is loaded when it changes
+    scene: SharedScene,
+    revision: u64,
+    /// Left clicks add vertices
+    polygon: Vec<Vec2>,
+    /// Largest distance between consecutive samples of the boundary
+    spacing: f32,
+    show_samples: bool,
+    axis: Option<MedialAxis>,
+    renderer: GliumRenderer<'f>,
+    /// Boundary, edges of the axis, samples if they are shown, and vertices
+    list: DrawList,
+    exec_time: ExecTimeHistory,
+    point_input: PointInput<'f>,
+}
+
+impl<'f> Drawable for MedialAxisRenderer<'f> {
+    fn update(&mut self) -> Result<()> {
+        let scene = self.scene.borrow();
+        if scene.revision() != self.revision {
+            self.revision = scene.revision();
+            if let Some(polygon) = scene.polygons().last() {
+                self.polygon = polygon.clone();
+                drop(scene);
+                self.compute()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        self.point_input.draw(target, viewport)?;
+        self.renderer.draw_in(&self.list, target, viewport.rect(target))
+    }
+
+    fn handle_events(&mut self, window: &Window, event: &Event<()>, io: &imgui::Io, viewport: &Viewport) -> Result<()> {
+        if let Event::WindowEvent { event, .. } = event {
+            if let WindowEvent::MouseInput { button, state, .. } = event {
+                if !io.want_capture_mouse && // Ignore clicks when the cursor is over an ImGui window
+                    button == &MouseButton::Left && state == &ElementState::Pressed {
+
+                    // Add a vertex on click
+                    let coords = viewport.window_pos_to_normalized(io.mouse_pos.into(), window);
+                    self.polygon.push(self.point_input.place(coords));
+                    self.compute()?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'f> Configurable for MedialAxisRenderer<'f> {
+    fn name(&self) -> &'static str {
+        "Medial axis"
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        Some("Approximates the medial axis of a simple polygon, the centers of the circles inside it that touch \
+              its boundary at two points or more. The boundary is sampled, and the Voronoi diagram of the samples \
+              is built from the circumcenters of their Delaunay triangulation. The Voronoi edges inside the polygon \
+              that separate samples which are not consecutive on the boundary converge to the axis as the samples get denser. \
+              Left click to add vertices, the last polygon of the scene is loaded when it changes.")
+    }
+
+    fn pseudo_code(&self) -> Option<&'static str> {
+        Some(concat!(
+            "S = vertices and points of the boundary at most h apart\n",
+            "T = Delaunay triangulation of S\n",
+            "for each edge (a, b) of T between triangles t1 and t2:\n",
+            "    if a and b are not consecutive on the boundary\n",
+            "       and both circumcenters are inside the polygon:\n",
+            "        add (center(t1), center(t2)) to the axis",
+        ))
+    }
+
+    fn complexity(&self) -> Option<&'static str> {
+        Some("O(m²) for the m samples, dominated by the edge flipping of the triangulation")
+    }
+
+    fn configure(&mut self, ui: &imgui::Ui) -> Result<()> {
+        let (samples, edges) = self.axis.as_ref().map_or((0, 0), |axis| (axis.samples.len(), axis.edges.len()));
+        ui.text(imgui::im_str!("{} vertices, {} samples, {} edges on the axis", self.polygon.len(), samples, edges));
+        if imgui::Slider::new(imgui::im_str!("Sample spacing"), 0.01..=0.2).build(ui, &mut self.spacing) {
+            self.compute()?;
+        }
+        if ui.checkbox(imgui::im_str!("Samples"), &mut self.show_samples) {
+            self.list = layers::medial_axis(&self.polygon, self.axis.as_ref(), self.show_samples);
+        }
+
+        if ui.button(imgui::im_str!("Clear Polygon"), [0.0, 0.0]) {
+            self.polygon.clear();
+            self.compute()?;
+        }
+        ui.same_line(0.0);
+        if ui.button(imgui::im_str!("Add to Scene"), [0.0, 0.0]) && self.polygon.len() >= 3 {
+            self.scene.borrow_mut().add_polygon(self.polygon.clone());
+        }
+
+        if let Some(point) = self.point_input.configure(ui)? {
+            self.polygon.push(point);
+            self.compute()?;
+        }
+
+        self.exec_time.configure(ui);
+        Ok(())
+    }
+}
+
+impl<'f> MedialAxisRenderer<'f> {
+    pub fn new(facade: &'f dyn Facade, scene: SharedScene) -> Result<Self> {
+        Ok(Self {
+            scene,
+            revision: 0,
+            polygon: Vec::new(),
+            spacing: 0.05,
+            show_samples: false,
+            axis: None,
+            renderer: GliumRenderer::new(facade)?,
+            list: layers::list(),
+            exec_time: ExecTimeHistory::default(),
+            point_input: PointInput::new(facade)?,
+        })
+    }
+
+    /// Computes the axis and regenerates the draw list.
+    fn compute(&mut self) -> Result<()> {
+        let start_time = Instant::now();
+        self.axis = MedialAxis::new(&self.polygon, self.spacing);
+        match &self.axis {
+            Some(_) => self.exec_time.push(Instant::now() - start_time),
+            None => self.exec_time.clear(),
+        }
+        self.list = layers::medial_axis(&self.polygon, self.axis.as_ref(), self.show_samples);
+        Ok(())
+    }
+}
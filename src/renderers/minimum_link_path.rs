@@ -0,0 +1,240 @@
+use crate::{
+    Result,
+    algorithms::{ window_partition, WindowPartition },
+    graphics::{ self, Shape, Viewport },
+    math::Vec2,
+    scene::SharedScene,
+    ui::{ PointInput, ExecTimeHistory, window::algorithms::{ Drawable, Configurable } },
+};
+
+use std::time::Instant;
+
+use glium::{
+    index::PrimitiveType, Frame, Program,
+    backend::Facade,
+};
+use winit::{
+    event::{ Event, WindowEvent, MouseButton, ElementState },
+    window::Window,
+};
+
+/// Colors of the windows, cycled through by number of links so that consecutive regions stand out
+const WINDOW_COLORS: [[f32; 3]; 3] = [ [ 1.0, 0.5, 0.0 ], [ 0.0, 0.6, 1.0 ], [ 0.8, 0.2, 0.8 ] ];
+
+pub struct MinimumLinkPathRenderer<'f> {
+    /// The last polygon of the scene is loaded when it changes
+    scene: SharedScene,
+    revision: u64,
+    /// Vertices of the polygon, left clicks add new ones
+    polygon: Vec<Vec2>,
+    /// Right clicks place the start and the end in turn
+    start: Option<Vec2>,
+    end: Option<Vec2>,
+    place_end: bool,
+    partition: Option<WindowPartition>,
+    path: Option<Vec<Vec2>>,
+    program: Program,
+    outline: Shape<'f>,
+    vertices: Shape<'f>,
+    windows: Vec<Shape<'f>>,
+    path_shape: Shape<'f>,
+    endpoints: Shape<'f>,
+    show_windows: bool,
+    exec_time: ExecTimeHistory,
+    point_input: PointInput<'f>,
+}
+
+impl<'f> Drawable for MinimumLinkPathRenderer<'f> {
+    fn update(&mut self) -> Result<()> {
+        let scene = self.scene.borrow();
+        if scene.revision() != self.revision {
+            self.revision = scene.revision();
+            if let Some(polygon) = scene.polygons().last() {
+                self.polygon = polygon.clone();
+                drop(scene);
+                self.compute()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        self.point_input.draw(target, viewport)?;
+        self.outline.draw(target, &self.program, viewport)?;
+        if self.show_windows {
+            for windows in &self.windows {
+                windows.draw(target, &self.program, viewport)?;
+            }
+        }
+        self.path_shape.draw(target, &self.program, viewport)?;
+        self.vertices.draw(target, &self.program, viewport)?;
+        self.endpoints.draw(target, &self.program, viewport)
+    }
+
+    fn handle_events(&mut self, window: &Window, event: &Event<()>, io: &imgui::Io, viewport: &Viewport) -> Result<()> {
+        if let Event::WindowEvent { event, .. } = event {
+            if let WindowEvent::MouseInput { button, state, .. } = event {
+                // Ignore clicks when the cursor is over an ImGui window
+                if !io.want_capture_mouse && state == &ElementState::Pressed {
+                    let coords = viewport.window_pos_to_normalized(io.mouse_pos.into(), window);
+                    match button {
+                        // Add a vertex on left click
+                        MouseButton::Left => {
+                            self.polygon.push(self.point_input.place(coords));
+                            self.compute()?;
+                        },
+                        MouseButton::Right => self.place_endpoint(coords)?,
+                        _ => {},
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'f> Configurable for MinimumLinkPathRenderer<'f> {
+    fn name(&self) -> &'static str {
+        "Minimum-link path"
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        Some("Finds a path with the fewest segments between two points of a polygon. \
+              The points seen from the start are 1 link away, the edges of this visibility region that cross \
+              the polygon are its windows. Behind each window, the points that see a point of the window \
+              are 1 link farther, and so on: the windows partition the polygon in regions of equal link distance. \
+              The path goes back from the end to the window of its region, then along the lines of the windows. \
+              Left click to add vertices to the polygon, right click to place the start and the end in turn, \
+              the last polygon of the scene is loaded when it changes.")
+    }
+
+    fn pseudo_code(&self) -> Option<&'static str> {
+        Some(concat!(
+            "regions = [visibility polygon of start]\n",
+            "for each region R, by increasing links:\n",
+            "    for each reflex vertex r seen from\n",
+            "          the source of R (start or window):\n",
+            "        p = last point of the source seeing r\n",
+            "        window = ray from p through r, past r\n",
+            "        add the pocket behind window to regions\n",
+            "            with links(R) + 1\n",
+            "path = end, a point of the window of its region\n",
+            "       seen from end, then the points of the\n",
+            "       windows of the parent regions",
+        ))
+    }
+
+    fn complexity(&self) -> Option<&'static str> {
+        Some("O(n³) per region with this brute force visibility, O(n) with triangulations and shortest path trees")
+    }
+
+    fn configure(&mut self, ui: &imgui::Ui) -> Result<()> {
+        let regions = self.partition.as_ref().map_or(0, |partition| partition.regions.len());
+        ui.text(imgui::im_str!("{} vertices, {} regions", self.polygon.len(), regions));
+        match &self.path {
+            Some(path) => ui.text(imgui::im_str!("Link distance: {}", path.len() - 1)),
+            None if self.start.is_some() && self.end.is_some() => ui.text(imgui::im_str!("The start or the end is outside of the polygon")),
+            None => ui.text(imgui::im_str!("Right click to place the start and the end")),
+        }
+
+        ui.checkbox(imgui::im_str!("Windows"), &mut self.show_windows);
+        if ui.button(imgui::im_str!("Clear Polygon"), [0.0, 0.0]) {
+            self.polygon.clear();
+            self.compute()?;
+        }
+        ui.same_line(0.0);
+        if ui.button(imgui::im_str!("Add to Scene"), [0.0, 0.0]) && self.polygon.len() >= 3 {
+            self.scene.borrow_mut().add_polygon(self.polygon.clone());
+        }
+
+        if let Some(point) = self.point_input.configure(ui)? {
+            self.polygon.push(point);
+            self.compute()?;
+        }
+
+        self.exec_time.configure(ui);
+        Ok(())
+    }
+}
+
+impl<'f> MinimumLinkPathRenderer<'f> {
+    pub fn new(facade: &'f dyn Facade, scene: SharedScene) -> Result<Self> {
+        let vs = graphics::SHADERS._2d_vs;
+        let fs = graphics::SHADERS.basic_fs;
+        let program = Program::from_source(facade, vs, fs, None)?;
+
+        let windows = WINDOW_COLORS.iter()
+                                   .map(|&color| Shape::new(facade, PrimitiveType::LinesList, color))
+                                   .collect::<Result<Vec<_>>>()?;
+        let mut path_shape = Shape::new(facade, PrimitiveType::LineStrip, [ 0.0, 1.0, 0.0 ])?;
+        path_shape.size = 2.0;
+
+        Ok(Self {
+            scene,
+            revision: 0,
+            polygon: Vec::new(),
+            start: None,
+            end: None,
+            place_end: false,
+            partition: None,
+            path: None,
+            program,
+            outline: Shape::new(facade, PrimitiveType::LineLoop, [ 1.0, 1.0, 1.0 ])?,
+            vertices: Shape::new(facade, PrimitiveType::Points, [ 1.0, 1.0, 1.0 ])?,
+            windows,
+            path_shape,
+            endpoints: Shape::new(facade, PrimitiveType::Points, [ 0.0, 1.0, 0.0 ])?,
+            show_windows: true,
+            exec_time: ExecTimeHistory::default(),
+            point_input: PointInput::new(facade)?,
+        })
+    }
+
+    /// Moves the start or the end, whichever did not move last.
+    fn place_endpoint(&mut self, p: Vec2) -> Result<()> {
+        if self.place_end {
+            self.end = Some(p);
+            self.place_end = false;
+            self.find_path()
+        } else {
+            self.start = Some(p);
+            self.place_end = true;
+            self.compute()
+        }
+    }
+
+    /// Computes the window partition from the start and regenerates the buffers.
+    fn compute(&mut self) -> Result<()> {
+        self.outline.set_vertices(&self.polygon)?;
+        self.vertices.set_vertices(&self.polygon)?;
+
+        let start_time = Instant::now();
+        self.partition = self.start.and_then(|start| window_partition(&self.polygon, start));
+        match self.partition {
+            Some(_) => self.exec_time.push(Instant::now() - start_time),
+            None => self.exec_time.clear(),
+        }
+
+        for (idx, windows) in self.windows.iter_mut().enumerate() {
+            let vertices = self.partition
+                .iter()
+                .flat_map(|partition| &partition.regions)
+                .filter(|region| region.links % WINDOW_COLORS.len() == idx)
+                .filter_map(|region| region.window)
+                .flat_map(|window| vec![ window.a, window.b ])
+                .collect::<Vec<_>>();
+            windows.set_vertices(&vertices)?;
+        }
+        self.find_path()
+    }
+
+    /// Finds the path from the start to the end in the current partition.
+    fn find_path(&mut self) -> Result<()> {
+        self.path = match (&self.partition, self.end) {
+            (Some(partition), Some(end)) => partition.path_to(end),
+            _ => None,
+        };
+        self.path_shape.set_vertices(self.path.as_deref().unwrap_or(&[]))?;
+        self.endpoints.set_vertices(&self.start.iter().chain(self.end.iter()).copied().collect::<Vec<_>>())
+    }
+}
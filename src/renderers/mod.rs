@@ -0,0 +1,120 @@
+//! Adapters that display the algorithms with glium and configure them with ImGui.
+//! Each one owns an algorithm, runs it again when the input geometry of the scene changes
+//! and keeps GPU buffers in sync with its results.
+
+pub mod jarvis_march;
+pub mod graham_scan;
+
+pub use jarvis_march::JarvisMarchRenderer;
+pub use graham_scan::GrahamScanRenderer;
+
+pub mod incremental_2d_triangulation;
+pub use incremental_2d_triangulation::Incremental2dTriangulationRenderer;
+
+pub mod rotating_calipers;
+pub use rotating_calipers::RotatingCalipersRenderer;
+
+pub mod voronoi;
+pub use voronoi::VoronoiRenderer;
+
+pub mod farthest_point_voronoi;
+pub use farthest_point_voronoi::FarthestPointVoronoiRenderer;
+
+pub mod order_k_voronoi;
+pub use order_k_voronoi::OrderKVoronoiRenderer;
+
+pub mod stippling;
+pub use stippling::StipplingRenderer;
+
+pub mod largest_inscribed_circle;
+pub use largest_inscribed_circle::LargestInscribedCircleRenderer;
+
+pub mod arrangement;
+pub use arrangement::ArrangementRenderer;
+
+pub mod segment_intersections;
+pub use segment_intersections::SegmentIntersectionsRenderer;
+
+pub mod duality;
+pub use duality::DualityRenderer;
+
+pub mod circle_packing;
+pub use circle_packing::CirclePackingRenderer;
+
+pub mod nearest_neighbour;
+pub use nearest_neighbour::NearestNeighbourRenderer;
+
+pub mod bvh;
+pub use bvh::BvhRenderer;
+
+pub mod collisions;
+pub use collisions::CollisionRenderer;
+
+pub mod particles;
+pub use particles::ParticlesRenderer;
+
+pub mod flocking;
+pub use flocking::FlockingRenderer;
+
+pub mod pca;
+pub use pca::PcaRenderer;
+
+pub mod minimum_link_path;
+pub use minimum_link_path::MinimumLinkPathRenderer;
+
+pub mod shortest_path;
+pub use shortest_path::ShortestPathRenderer;
+
+pub mod polygon_triangulation;
+pub use polygon_triangulation::PolygonTriangulationRenderer;
+
+pub mod pathfinding;
+pub use pathfinding::PathfindingRenderer;
+
+pub mod morphing;
+pub use morphing::MorphingRenderer;
+
+pub mod offset;
+pub use offset::OffsetRenderer;
+
+pub mod medial_axis;
+pub use medial_axis::MedialAxisRenderer;
+
+pub mod distance_field;
+pub use distance_field::DistanceFieldRenderer;
+
+pub mod terrain_contours;
+pub use terrain_contours::TerrainContoursRenderer;
+
+pub mod visibility;
+pub use visibility::VisibilityRenderer;
+
+pub mod ray_casting;
+pub use ray_casting::RayCastingRenderer;
+
+pub mod linear_programming;
+pub use linear_programming::LinearProgrammingRenderer;
+
+pub mod icp;
+pub use icp::IcpRenderer;
+
+pub mod clustering;
+pub use clustering::ClusteringRenderer;
+
+pub mod data_depth;
+pub use data_depth::DataDepthRenderer;
+
+pub mod refinement;
+pub use refinement::RefinementRenderer;
+
+pub mod convex_hull_3d;
+pub use convex_hull_3d::ConvexHullRenderer;
+
+pub mod delaunay_3d;
+pub use delaunay_3d::Delaunay3dRenderer;
+
+pub mod lifting_map;
+pub use lifting_map::LiftingMapRenderer;
+
+pub mod convex_hull_4d;
+pub use convex_hull_4d::ConvexHull4dRenderer;
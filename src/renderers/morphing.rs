@@ -0,0 +1,241 @@
+use crate::{
+    Result,
+    algorithms::Morph,
+    graphics::{ self, Shape, Viewport },
+    math::Vec2,
+    scene::SharedScene,
+    ui::{ PointInput, ExecTimeHistory, window::algorithms::{ Drawable, Configurable } },
+};
+
+use std::{ f32::consts::PI, time::{ Duration, Instant } };
+
+use glium::{
+    index::PrimitiveType, Frame, Program,
+    backend::Facade,
+};
+use winit::{
+    event::{ Event, WindowEvent, MouseButton, ElementState },
+    window::Window,
+};
+
+/// Most samples offered by the slider, matching them is quadratic
+const MAX_SAMPLES: i32 = 512;
+
+pub struct MorphingRenderer<'f> {
+    /// The last two polygons of the scene are loaded when it changes
+    scene: SharedScene,
+    revision: u64,
+    /// The source and the target, left clicks add vertices to the one being edited
+    polygons: [Vec<Vec2>; 2],
+    editing: usize,
+    samples: i32,
+    morph: Option<Morph>,
+    /// Position of the morph, from the source at 0 to the target at 1
+    t: f32,
+    /// While playing, `t` goes back and forth once every `duration` seconds
+    playing: bool,
+    duration: f32,
+    animation_start: Instant,
+    show_correspondences: bool,
+    program: Program,
+    source: Shape<'f>,
+    target: Shape<'f>,
+    vertices: Shape<'f>,
+    morphed: Shape<'f>,
+    correspondences: Shape<'f>,
+    exec_time: ExecTimeHistory,
+    point_input: PointInput<'f>,
+}
+
+impl<'f> Drawable for MorphingRenderer<'f> {
+    fn update(&mut self) -> Result<()> {
+        // The scene must not be borrowed anymore when interpolating, hence the block
+        let changed = {
+            let scene = self.scene.borrow();
+            if scene.revision() == self.revision {
+                false
+            } else {
+                self.revision = scene.revision();
+                match scene.polygons() {
+                    [ .., source, target ] => {
+                        self.polygons = [ source.clone(), target.clone() ];
+                        true
+                    },
+                    _ => false,
+                }
+            }
+        };
+        if changed {
+            self.compute()?;
+        }
+
+        if self.playing {
+            let elapsed = (Instant::now() - self.animation_start).as_secs_f32();
+            self.t = (1.0 - (PI * elapsed / self.duration).cos()) / 2.0;
+            self.interpolate()?;
+        }
+        Ok(())
+    }
+
+    fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        self.point_input.draw(target, viewport)?;
+        if self.show_correspondences {
+            self.correspondences.draw(target, &self.program, viewport)?;
+        }
+        self.source.draw(target, &self.program, viewport)?;
+        self.target.draw(target, &self.program, viewport)?;
+        self.morphed.draw(target, &self.program, viewport)?;
+        self.vertices.draw(target, &self.program, viewport)
+    }
+
+    fn handle_events(&mut self, window: &Window, event: &Event<()>, io: &imgui::Io, viewport: &Viewport) -> Result<()> {
+        if let Event::WindowEvent { event, .. } = event {
+            if let WindowEvent::MouseInput { button, state, .. } = event {
+                if !io.want_capture_mouse && // Ignore clicks when the cursor is over an ImGui window
+                    button == &MouseButton::Left && state == &ElementState::Pressed {
+
+                    // Add a vertex to the edited polygon on click
+                    let coords = viewport.window_pos_to_normalized(io.mouse_pos.into(), window);
+                    self.polygons[self.editing].push(self.point_input.place(coords));
+                    self.compute()?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'f> Configurable for MorphingRenderer<'f> {
+    fn name(&self) -> &'static str {
+        "Polygon morphing"
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        Some("Morphs a source polygon into a target polygon. Both boundaries are resampled to the same number of points, \
+              evenly spaced by arc length and counter-clockwise, so that the k-th point of the source matches \
+              the k-th point of the target. The samples of the target are rotated so that matching points are \
+              the closest once both polygons are centered on their centroids, which keeps the morph from twisting. \
+              The intermediate polygons move every point along a straight line to its match. \
+              Left click to add vertices to the edited polygon, the last two polygons of the scene are loaded when it changes.")
+    }
+
+    fn pseudo_code(&self) -> Option<&'static str> {
+        Some(concat!(
+            "A = N points evenly spaced along the source\n",
+            "B = N points evenly spaced along the target\n",
+            "make A and B counter-clockwise\n",
+            "shift = s minimizing the sum over i of\n",
+            "    |(A[i] - centroid(A)) - (B[i + s] - centroid(B))|²\n",
+            "morph(t)[i] = (1 - t) A[i] + t B[i + shift]",
+        ))
+    }
+
+    fn complexity(&self) -> Option<&'static str> {
+        Some("O(n + m) for the resampling, O(N²) to match the N samples")
+    }
+
+    fn configure(&mut self, ui: &imgui::Ui) -> Result<()> {
+        ui.text(imgui::im_str!("Source: {} vertices, target: {} vertices", self.polygons[0].len(), self.polygons[1].len()));
+        let edit_source = ui.radio_button(imgui::im_str!("Edit source"), &mut self.editing, 0);
+        ui.same_line(0.0);
+        let edit_target = ui.radio_button(imgui::im_str!("Edit target"), &mut self.editing, 1);
+        if edit_source || edit_target {
+            self.vertices.set_vertices(&self.polygons[self.editing])?;
+        }
+
+        if imgui::Slider::new(imgui::im_str!("Samples"), 3..=MAX_SAMPLES).build(ui, &mut self.samples) {
+            self.compute()?;
+        }
+        if imgui::Slider::new(imgui::im_str!("t"), 0.0..=1.0).build(ui, &mut self.t) {
+            self.playing = false;
+            self.interpolate()?;
+        }
+        if ui.checkbox(imgui::im_str!("Play"), &mut self.playing) && self.playing {
+            // Starts from the current position, on its way to the target
+            let elapsed = self.duration * (1.0 - 2.0 * self.t).acos() / PI;
+            self.animation_start = Instant::now() - Duration::from_secs_f32(elapsed);
+        }
+        imgui::Slider::new(imgui::im_str!("Duration (s)"), 0.5..=10.0).build(ui, &mut self.duration);
+        ui.checkbox(imgui::im_str!("Correspondences"), &mut self.show_correspondences);
+
+        if ui.button(imgui::im_str!("Clear Polygon"), [0.0, 0.0]) {
+            self.polygons[self.editing].clear();
+            self.compute()?;
+        }
+        ui.same_line(0.0);
+        if ui.button(imgui::im_str!("Add to Scene"), [0.0, 0.0]) && self.polygons[self.editing].len() >= 3 {
+            self.scene.borrow_mut().add_polygon(self.polygons[self.editing].clone());
+        }
+
+        if let Some(point) = self.point_input.configure(ui)? {
+            self.polygons[self.editing].push(point);
+            self.compute()?;
+        }
+
+        self.exec_time.configure(ui);
+        Ok(())
+    }
+}
+
+impl<'f> MorphingRenderer<'f> {
+    pub fn new(facade: &'f dyn Facade, scene: SharedScene) -> Result<Self> {
+        let vs = graphics::SHADERS._2d_vs;
+        let fs = graphics::SHADERS.basic_fs;
+        let program = Program::from_source(facade, vs, fs, None)?;
+
+        let mut morphed = Shape::new(facade, PrimitiveType::LineLoop, [ 1.0, 1.0, 0.0 ])?;
+        morphed.size = 2.0;
+
+        Ok(Self {
+            scene,
+            revision: 0,
+            polygons: [ Vec::new(), Vec::new() ],
+            editing: 0,
+            samples: 64,
+            morph: None,
+            t: 0.5,
+            playing: false,
+            duration: 2.0,
+            animation_start: Instant::now(),
+            show_correspondences: false,
+            program,
+            source: Shape::new(facade, PrimitiveType::LineLoop, [ 1.0, 0.4, 0.4 ])?,
+            target: Shape::new(facade, PrimitiveType::LineLoop, [ 0.4, 0.6, 1.0 ])?,
+            vertices: Shape::new(facade, PrimitiveType::Points, [ 1.0, 1.0, 1.0 ])?,
+            morphed,
+            correspondences: Shape::new(facade, PrimitiveType::LinesList, [ 0.3, 0.3, 0.3 ])?,
+            exec_time: ExecTimeHistory::default(),
+            point_input: PointInput::new(facade)?,
+        })
+    }
+
+    /// Matches the polygons and regenerates the buffers.
+    fn compute(&mut self) -> Result<()> {
+        self.source.set_vertices(&self.polygons[0])?;
+        self.target.set_vertices(&self.polygons[1])?;
+        self.vertices.set_vertices(&self.polygons[self.editing])?;
+
+        let start_time = Instant::now();
+        self.morph = Morph::new(&self.polygons[0], &self.polygons[1], self.samples as usize);
+        match &self.morph {
+            Some(morph) => {
+                self.exec_time.push(Instant::now() - start_time);
+                let lines = morph.from.iter().zip(&morph.to).flat_map(|(&a, &b)| vec![ a, b ]).collect::<Vec<_>>();
+                self.correspondences.set_vertices(&lines)?;
+            },
+            None => {
+                self.exec_time.clear();
+                self.correspondences.clear()?;
+            },
+        }
+        self.interpolate()
+    }
+
+    /// Regenerates the buffer of the polygon at `t`.
+    fn interpolate(&mut self) -> Result<()> {
+        match &self.morph {
+            Some(morph) => self.morphed.set_vertices(&morph.at(self.t)),
+            None => self.morphed.clear(),
+        }
+    }
+}
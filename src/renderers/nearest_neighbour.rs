@@ -0,0 +1,262 @@
+use crate::{
+    Result,
+    graphics::{ self, Shape, Viewport },
+    math::{ Vec2, Rect },
+    scene::SharedScene,
+    spatial::{ self, KdTree },
+    ui::{ PointInput, ExecTimeHistory, window::algorithms::{ Drawable, Configurable } },
+};
+
+use std::time::{ Duration, Instant };
+
+use glium::{
+    index::PrimitiveType, Frame, Program,
+    backend::Facade,
+};
+use winit::{
+    event::{ Event, WindowEvent, MouseButton, ElementState },
+    window::Window,
+};
+
+/// Half the size of the cross drawn at the query
+const CROSS_SIZE: f32 = 0.02;
+
+/// Result of the last query of one of the methods.
+#[derive(Copy, Clone)]
+struct Query {
+    nearest: usize,
+    /// Number of points compared with the query
+    visited: usize,
+    time: Duration,
+}
+
+pub struct NearestNeighbourRenderer<'f> {
+    /// The input points are those of the scene
+    scene: SharedScene,
+    /// Revision of the scene the points were copied from
+    revision: u64,
+    points: Vec<Vec2>,
+    program: Program,
+    vertices: Shape<'f>,
+    /// Splitting lines of the nodes of the k-d tree
+    splits: Shape<'f>,
+    /// Points the k-d tree compared with the last query
+    visited: Shape<'f>,
+    query: Shape<'f>,
+    nearest: Shape<'f>,
+    show_splits: bool,
+    show_visited: bool,
+    brute_force: Option<Query>,
+    kd_tree: Option<Query>,
+    build_time: Option<Duration>,
+    random_count: i32,
+    brute_force_time: ExecTimeHistory,
+    kd_tree_time: ExecTimeHistory,
+    point_input: PointInput<'f>,
+}
+
+impl<'f> Drawable for NearestNeighbourRenderer<'f> {
+    fn update(&mut self) -> Result<()> {
+        if self.scene.borrow().revision() != self.revision {
+            self.compute()?;
+        }
+        Ok(())
+    }
+
+    fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        self.point_input.draw(target, viewport)?;
+        if self.show_splits {
+            self.splits.draw(target, &self.program, viewport)?;
+        }
+        self.vertices.draw(target, &self.program, viewport)?;
+        if self.show_visited {
+            self.visited.draw(target, &self.program, viewport)?;
+        }
+        self.nearest.draw(target, &self.program, viewport)?;
+        self.query.draw(target, &self.program, viewport)
+    }
+
+    fn handle_events(&mut self, window: &Window, event: &Event<()>, io: &imgui::Io, viewport: &Viewport) -> Result<()> {
+        if let Event::WindowEvent { event, .. } = event {
+            match event {
+                WindowEvent::MouseInput { button, state, .. } => {
+                    if !io.want_capture_mouse && // Ignore clicks when the cursor is over an ImGui window
+                        button == &MouseButton::Left && state == &ElementState::Pressed {
+
+                        // Add a point on click
+                        let coords = viewport.window_pos_to_normalized(io.mouse_pos.into(), window);
+                        self.scene.borrow_mut().add_point(self.point_input.place(coords));
+                    }
+                },
+                WindowEvent::CursorMoved { .. } => {
+                    // Both methods look for the point closest to the cursor
+                    let coords = viewport.window_pos_to_normalized(io.mouse_pos.into(), window);
+                    self.race(coords)?;
+                },
+                _ => {},
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'f> Configurable for NearestNeighbourRenderer<'f> {
+    fn name(&self) -> &'static str {
+        "Nearest neighbour race"
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        Some("The point closest to the cursor is looked for twice: by brute force, which compares the cursor \
+              with every point, and with a k-d tree, which splits the plane at the median of the points, \
+              alternately along x and y. The search goes down the side of each split that contains the cursor \
+              first, then skips the other side when the split is farther than the closest point found so far, \
+              so most of the points are never compared. The highlighted points are the ones the tree visited.")
+    }
+
+    fn pseudo_code(&self) -> Option<&'static str> {
+        Some(concat!(
+            "search(node, q):\n",
+            "    if node is empty: return\n",
+            "    if |node.p - q| < |best - q|:\n",
+            "        best = node.p\n",
+            "    d = q[axis] - node.p[axis]\n",
+            "    near, far = children of node,\n",
+            "                near on the side of q\n",
+            "    search(near, q)\n",
+            "    if |d| < |best - q|:\n",
+            "        search(far, q)",
+        ))
+    }
+
+    fn complexity(&self) -> Option<&'static str> {
+        Some("O(n) per query by brute force, O(n log n) to build the k-d tree and about O(log n) per query for evenly spread points")
+    }
+
+    fn configure(&mut self, ui: &imgui::Ui) -> Result<()> {
+        ui.text(imgui::im_str!("{} points", self.points.len()));
+        if let Some(build_time) = self.build_time {
+            ui.text(imgui::im_str!("k-d tree built in {} µs", build_time.as_micros()));
+        }
+        if let (Some(brute_force), Some(kd_tree)) = (self.brute_force, self.kd_tree) {
+            ui.text(imgui::im_str!("Brute force: {} µs, {} points visited", brute_force.time.as_micros(), brute_force.visited));
+            ui.text(imgui::im_str!("k-d tree: {} µs, {} points visited", kd_tree.time.as_micros(), kd_tree.visited));
+            ui.text(imgui::im_str!("The k-d tree visited {:.1}% of the points", 100.0 * kd_tree.visited as f32 / brute_force.visited as f32));
+            if brute_force.nearest != kd_tree.nearest {
+                ui.text(imgui::im_str!("Different points at the same distance"));
+            }
+        }
+
+        ui.checkbox(imgui::im_str!("Splits"), &mut self.show_splits);
+        ui.checkbox(imgui::im_str!("Visited points"), &mut self.show_visited);
+
+        if ui.button(imgui::im_str!("Clear Points"), [0.0, 0.0]) {
+            self.scene.borrow_mut().clear_points();
+        }
+
+        ui.input_int(imgui::im_str!("Count"), &mut self.random_count).build();
+        if ui.button(imgui::im_str!("Random Points"), [0.0, 0.0]) {
+            self.scene.borrow_mut().add_random_points(self.random_count.max(0) as usize);
+        }
+
+        if let Some(point) = self.point_input.configure(ui)? {
+            self.scene.borrow_mut().add_point(point);
+        }
+
+        ui.text(imgui::im_str!("Brute force"));
+        self.brute_force_time.configure(ui);
+        ui.text(imgui::im_str!("k-d tree"));
+        self.kd_tree_time.configure(ui);
+        Ok(())
+    }
+}
+
+impl<'f> NearestNeighbourRenderer<'f> {
+    pub fn new(facade: &'f dyn Facade, scene: SharedScene) -> Result<Self> {
+        let vs = graphics::SHADERS._2d_vs;
+        let fs = graphics::SHADERS.basic_fs;
+        let program = Program::from_source(facade, vs, fs, None)?;
+
+        let mut nearest = Shape::new(facade, PrimitiveType::LinesList, [ 0.0, 1.0, 0.0 ])?;
+        nearest.size = 2.0;
+        let mut visited = Shape::new(facade, PrimitiveType::Points, [ 1.0, 0.5, 0.0 ])?;
+        visited.size = 4.0;
+
+        Ok(Self {
+            scene,
+            revision: 0,
+            points: Vec::new(),
+            program,
+            vertices: Shape::new(facade, PrimitiveType::Points, [ 1.0, 1.0, 1.0 ])?,
+            splits: Shape::new(facade, PrimitiveType::LinesList, [ 0.3, 0.3, 0.5 ])?,
+            visited,
+            query: Shape::new(facade, PrimitiveType::LinesList, [ 1.0, 0.0, 0.0 ])?,
+            nearest,
+            show_splits: true,
+            show_visited: true,
+            brute_force: None,
+            kd_tree: None,
+            build_time: None,
+            random_count: 1000,
+            brute_force_time: ExecTimeHistory::default(),
+            kd_tree_time: ExecTimeHistory::default(),
+            point_input: PointInput::new(facade)?,
+        })
+    }
+
+    /// Copies the points of the scene, draws the splits of their k-d tree and forgets the last query.
+    fn compute(&mut self) -> Result<()> {
+        let scene = self.scene.borrow();
+        self.revision = scene.revision();
+        self.points = scene.points().to_vec();
+        drop(scene);
+
+        let start_time = Instant::now();
+        let tree = KdTree::new(&self.points);
+        self.build_time = if self.points.is_empty() { None } else { Some(Instant::now() - start_time) };
+
+        let bounds = Rect::new(Vec2::new(-1.0, -1.0), Vec2::new(1.0, 1.0));
+        let splits = tree.splits(&bounds).iter().flat_map(|s| vec![ s.a, s.b ]).collect::<Vec<_>>();
+        self.splits.set_vertices(&splits)?;
+        self.vertices.set_vertices(&self.points)?;
+
+        self.brute_force = None;
+        self.kd_tree = None;
+        self.brute_force_time.clear();
+        self.kd_tree_time.clear();
+        self.visited.clear()?;
+        self.nearest.clear()?;
+        self.query.clear()
+    }
+
+    /// Looks for the point closest to `query` with both methods and displays the result.
+    fn race(&mut self, query: Vec2) -> Result<()> {
+        self.query.set_vertices(&[
+            Vec2::new(query.x - CROSS_SIZE, query.y), Vec2::new(query.x + CROSS_SIZE, query.y),
+            Vec2::new(query.x, query.y - CROSS_SIZE), Vec2::new(query.x, query.y + CROSS_SIZE),
+        ])?;
+
+        // Only the query is timed, the tree is built again here because it borrows the points
+        let tree = KdTree::new(&self.points);
+
+        let start_time = Instant::now();
+        let brute_force = spatial::nearest(&self.points, query);
+        let brute_force_time = Instant::now() - start_time;
+
+        let mut visited = Vec::new();
+        let start_time = Instant::now();
+        let kd_tree = tree.nearest_visiting(query, |idx| visited.push(idx));
+        let kd_tree_time = Instant::now() - start_time;
+
+        let (brute_force, kd_tree) = match (brute_force, kd_tree) {
+            (Some(brute_force), Some(kd_tree)) => (brute_force, kd_tree),
+            _ => return Ok(()),
+        };
+        self.brute_force = Some(Query { nearest: brute_force, visited: self.points.len(), time: brute_force_time });
+        self.kd_tree = Some(Query { nearest: kd_tree, visited: visited.len(), time: kd_tree_time });
+        self.brute_force_time.push(brute_force_time);
+        self.kd_tree_time.push(kd_tree_time);
+
+        self.visited.set_vertices(&visited.iter().map(|&idx| self.points[idx]).collect::<Vec<_>>())?;
+        self.nearest.set_vertices(&[ query, self.points[kd_tree] ])
+    }
+}
@@ -0,0 +1,232 @@
+use crate::{
+    Result,
+    algorithms::offset,
+    graphics::Viewport,
+    math::{ Vec2, Polyline, bezier },
+    render::{ layers, DrawList, GliumRenderer },
+    scene::SharedScene,
+    ui::{ PointInput, ExecTimeHistory, window::algorithms::{ Drawable, Configurable } },
+};
+
+use std::time::Instant;
+
+use glium::{ Frame, backend::Facade };
+use winit::{
+    event::{ Event, WindowEvent, MouseButton, ElementState },
+    window::Window,
+};
+
+/// How the clicked points define the curve
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum CurveKind {
+    Polyline,
+    /// The points are the control points of a path of cubic Bézier curves
+    Bezier,
+}
+
+pub struct OffsetRenderer<'f> {
+    /// The last polygon of the scene is loaded as a closed polyline when it changes
+    scene: SharedScene,
+    revision: u64,
+    /// Left clicks add points
+    points: Vec<Vec2>,
+    kind: CurveKind,
+    closed: bool,
+    /// Segments of the flattening of each Bézier curve
+    segments: i32,
+    /// Points of the polyline that replaces the curve when it is resampled by arc length
+    resample_count: i32,
+    /// Largest distance between the curve and the polyline that replaces it when it is simplified
+    tolerance: f32,
+    /// Signed, on the left of the curve when positive
+    distance: f32,
+    /// Offsets on both sides, which outlines the stroke of width twice the distance
+    both_sides: bool,
+    offsets: Vec<offset::OffsetCurve>,
+    renderer: GliumRenderer<'f>,
+    /// Band of the offsets, curve, offset curves and points, with the control polygon of Bézier paths
+    list: DrawList,
+    exec_time: ExecTimeHistory,
+    point_input: PointInput<'f>,
+}
+
+impl<'f> Drawable for OffsetRenderer<'f> {
+    fn update(&mut self) -> Result<()> {
+        let scene = self.scene.borrow();
+        if scene.revision() != self.revision {
+            self.revision = scene.revision();
+            if let Some(polygon) = scene.polygons().last() {
+                self.points = polygon.clone();
+                self.kind = CurveKind::Polyline;
+                self.closed = true;
+                drop(scene);
+                self.compute()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        self.point_input.draw(target, viewport)?;
+        self.renderer.draw_in(&self.list, target, viewport.rect(target))
+    }
+
+    fn handle_events(&mut self, window: &Window, event: &Event<()>, io: &imgui::Io, viewport: &Viewport) -> Result<()> {
+        if let Event::WindowEvent { event, .. } = event {
+            if let WindowEvent::MouseInput { button, state, .. } = event {
+                if !io.want_capture_mouse && // Ignore clicks when the cursor is over an ImGui window
+                    button == &MouseButton::Left && state == &ElementState::Pressed {
+
+                    // Add a point on click
+                    let coords = viewport.window_pos_to_normalized(io.mouse_pos.into(), window);
+                    self.points.push(self.point_input.place(coords));
+                    self.compute()?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'f> Configurable for OffsetRenderer<'f> {
+    fn name(&self) -> &'static str {
+        "Curve offsetting"
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        Some("Offsets a polyline or a path of cubic Bézier curves at a signed distance, on its left when the distance \
+              is positive. Every segment moves along its normal, and round joins fill the gaps at the outer corners. \
+              The raw offset loops back on itself at the inner corners and has cusps where the curve bends more tightly \
+              than the distance: it is cut where it crosses itself, and the pieces closer to the curve than the distance \
+              are removed. Offsetting both sides outlines the stroke of the curve, whose band is filled. \
+              Bézier paths are offset through their flattening, and the curve has to be simple. \
+              Resampling replaces the curve by a polyline with points evenly spaced by arc length, \
+              simplifying by a polyline with fewer points within the tolerance of it (Ramer-Douglas-Peucker). \
+              Left click to add points, the last polygon of the scene is loaded as a closed polyline when it changes.")
+    }
+
+    fn pseudo_code(&self) -> Option<&'static str> {
+        Some(concat!(
+            "raw = []\n",
+            "for each segment (a, b) with normal n:\n",
+            "    raw += [a + d n, b + d n]\n",
+            "    if the next corner is outer:\n",
+            "        raw += arc of radius d around b\n",
+            "cut raw at its self-intersections\n",
+            "keep the pieces at distance >= |d| from the curve\n",
+            "link the kept pieces at their cuts",
+        ))
+    }
+
+    fn complexity(&self) -> Option<&'static str> {
+        Some("O(k² + k n) for k points of the raw offset and n segments, to find the crossings and measure the distances")
+    }
+
+    fn configure(&mut self, ui: &imgui::Ui) -> Result<()> {
+        ui.text(imgui::im_str!("{} points, {} offset curves", self.points.len(), self.offsets.len()));
+        let mut changed = ui.radio_button(imgui::im_str!("Polyline"), &mut self.kind, CurveKind::Polyline);
+        ui.same_line(0.0);
+        changed |= ui.radio_button(imgui::im_str!("Bezier"), &mut self.kind, CurveKind::Bezier);
+        changed |= ui.checkbox(imgui::im_str!("Closed"), &mut self.closed);
+        ui.same_line(0.0);
+        changed |= ui.checkbox(imgui::im_str!("Both sides"), &mut self.both_sides);
+        changed |= imgui::Slider::new(imgui::im_str!("Distance"), -0.5..=0.5).build(ui, &mut self.distance);
+        if self.kind == CurveKind::Bezier {
+            changed |= imgui::Slider::new(imgui::im_str!("Segments per curve"), 1..=64).build(ui, &mut self.segments);
+        }
+        if changed {
+            self.compute()?;
+        }
+
+        imgui::Slider::new(imgui::im_str!("Points"), 2..=256).build(ui, &mut self.resample_count);
+        ui.same_line(0.0);
+        if ui.button(imgui::im_str!("Resample"), [0.0, 0.0]) {
+            // The curve becomes a polyline with points evenly spaced along it
+            let count = self.resample_count as usize;
+            self.points = match self.kind {
+                CurveKind::Polyline => self.polyline().resampled(count).points,
+                CurveKind::Bezier => bezier::resample_path(&self.points, self.closed, count),
+            };
+            self.kind = CurveKind::Polyline;
+            self.compute()?;
+        }
+
+        ui.input_float(imgui::im_str!("Tolerance"), &mut self.tolerance).step(0.005).build();
+        self.tolerance = self.tolerance.max(0.0);
+        ui.same_line(0.0);
+        if ui.button(imgui::im_str!("Simplify"), [0.0, 0.0]) {
+            // The points closer than the tolerance to the simplified polyline are removed
+            self.points = self.polyline().simplified(self.tolerance).points;
+            self.kind = CurveKind::Polyline;
+            self.compute()?;
+        }
+
+        if ui.button(imgui::im_str!("Clear Curve"), [0.0, 0.0]) {
+            self.points.clear();
+            self.compute()?;
+        }
+        ui.same_line(0.0);
+        if ui.button(imgui::im_str!("Add to Scene"), [0.0, 0.0]) {
+            let mut scene = self.scene.borrow_mut();
+            for curve in self.offsets.iter().filter(|curve| curve.closed && curve.points.len() >= 3) {
+                scene.add_polygon(curve.points.clone());
+            }
+        }
+
+        if let Some(point) = self.point_input.configure(ui)? {
+            self.points.push(point);
+            self.compute()?;
+        }
+
+        self.exec_time.configure(ui);
+        Ok(())
+    }
+}
+
+impl<'f> OffsetRenderer<'f> {
+    pub fn new(facade: &'f dyn Facade, scene: SharedScene) -> Result<Self> {
+        Ok(Self {
+            scene,
+            revision: 0,
+            points: Vec::new(),
+            kind: CurveKind::Polyline,
+            closed: false,
+            segments: 16,
+            resample_count: 32,
+            tolerance: 0.01,
+            distance: 0.1,
+            both_sides: false,
+            offsets: Vec::new(),
+            renderer: GliumRenderer::new(facade)?,
+            list: layers::list(),
+            exec_time: ExecTimeHistory::default(),
+            point_input: PointInput::new(facade)?,
+        })
+    }
+
+    /// Returns the polyline of the curve, closed if `closed` is set.
+    fn polyline(&self) -> Polyline {
+        let points = match self.kind {
+            CurveKind::Polyline => self.points.clone(),
+            CurveKind::Bezier => bezier::flatten_path(&self.points, self.closed, self.segments as usize),
+        };
+        Polyline::new(points, self.closed)
+    }
+
+    /// Offsets the curve and regenerates the draw list.
+    fn compute(&mut self) -> Result<()> {
+        let polyline = self.polyline();
+        let distances = if self.both_sides { vec![ self.distance, -self.distance ] } else { vec![ self.distance ] };
+        let start_time = Instant::now();
+        self.offsets = distances.iter().flat_map(|&d| offset::offset(&polyline, d)).collect();
+        if polyline.points.is_empty() {
+            self.exec_time.clear();
+        } else {
+            self.exec_time.push(Instant::now() - start_time);
+        }
+
+        let band = distances.iter().flat_map(|&d| offset::band(&polyline, d)).collect::<Vec<_>>();
+        self.list = layers::offset(&self.points, &polyline, &band, &self.offsets, self.kind == CurveKind::Bezier);
+        Ok(())
+    }
+}
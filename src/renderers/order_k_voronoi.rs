@@ -0,0 +1,222 @@
+use crate::{
+    Result,
+    algorithms::{ OrderKVoronoi, Degeneracy },
+    graphics::{ self, Shape, Viewport },
+    math::Vec2,
+    scene::SharedScene,
+    ui::{ PointInput, ExecTimeHistory, window::algorithms::{ Drawable, Configurable } },
+};
+
+use std::time::Instant;
+
+use glium::{
+    index::{ NoIndices, PrimitiveType }, Surface, Frame, Program, VertexBuffer,
+    backend::Facade,
+};
+use winit::{
+    event::{ Event, WindowEvent, MouseButton, ElementState },
+    window::Window,
+};
+
+/// Highest order offered by the slider, the number of regions grows quickly with it
+const MAX_K: i32 = 10;
+
+#[derive(Copy, Clone, Debug)]
+struct Vertex {
+    position: Vec2,
+    color: [f32; 3],
+}
+
+implement_vertex!(Vertex, position, color);
+
+pub struct OrderKVoronoiRenderer<'f> {
+    facade: &'f dyn Facade,
+    alg: OrderKVoronoi,
+    /// The input points are those of the scene
+    scene: SharedScene,
+    /// Revision of the scene the diagram was computed from
+    revision: u64,
+    k: i32,
+    program: Program,
+    /// Draws the regions with the color of their sites
+    regions_program: Program,
+    regions_buffer: VertexBuffer<Vertex>,
+    points: Shape<'f>,
+    edges: Shape<'f>,
+    show_edges: bool,
+    exec_time: ExecTimeHistory,
+    point_input: PointInput<'f>,
+}
+
+impl<'f> Drawable for OrderKVoronoiRenderer<'f> {
+    fn update(&mut self) -> Result<()> {
+        if self.scene.borrow().revision() != self.revision {
+            self.compute()?;
+        }
+        Ok(())
+    }
+
+    fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        let draw_params = viewport.draw_parameters(target);
+        target.draw(&self.regions_buffer, NoIndices(PrimitiveType::TrianglesList), &self.regions_program,
+                    &glium::uniforms::EmptyUniforms, &draw_params)?;
+
+        self.point_input.draw(target, viewport)?;
+        if self.show_edges {
+            self.edges.draw(target, &self.program, viewport)?;
+        }
+        self.points.draw(target, &self.program, viewport)
+    }
+
+    fn handle_events(&mut self, window: &Window, event: &Event<()>, io: &imgui::Io, viewport: &Viewport) -> Result<()> {
+        if let Event::WindowEvent { event, .. } = event {
+            if let WindowEvent::MouseInput { button, state, .. } = event {
+                if !io.want_capture_mouse && // Ignore clicks when the cursor is over an ImGui window
+                    button == &MouseButton::Left && state == &ElementState::Pressed {
+
+                    // Add a point on click
+                    let coords = viewport.window_pos_to_normalized(io.mouse_pos.into(), window);
+                    self.scene.borrow_mut().add_point(self.point_input.place(coords));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'f> Configurable for OrderKVoronoiRenderer<'f> {
+    fn name(&self) -> &'static str {
+        "Order-k Voronoi"
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        Some("The cell of a set of k input points contains the positions for which they are the k nearest points. \
+              The order-1 diagram is the usual Voronoi diagram and the order-(n-1) diagram is the farthest-point one. \
+              The order-k diagram is built from the order-(k-1) one: each cell is divided by the Voronoi diagram \
+              of the points that are not among its k-1 nearest. The cells are colored by their set of points.")
+    }
+
+    fn pseudo_code(&self) -> Option<&'static str> {
+        Some(concat!(
+            "regions = [ ({}, bounding box) ]\n",
+            "repeat k times:\n",
+            "    for each (sites, polygon) in regions:\n",
+            "        for each point t not in sites:\n",
+            "            cell = polygon clipped to the positions\n",
+            "                   closer to t than to the\n",
+            "                   other points not in sites\n",
+            "            add (sites + {t}, cell) to the next regions",
+        ))
+    }
+
+    fn complexity(&self) -> Option<&'static str> {
+        Some("O(k² n³): O(k n) regions per order, each divided by O(n) clipped cells")
+    }
+
+    fn degeneracies(&self) -> &'static [Degeneracy] {
+        &[ Degeneracy::CollinearPoints, Degeneracy::CocircularPoints ]
+    }
+
+    fn configure(&mut self, ui: &imgui::Ui) -> Result<()> {
+        ui.text(imgui::im_str!("{} vertices, {} cells", self.alg.points().len(), self.alg.cell_count()));
+
+        if imgui::Slider::new(imgui::im_str!("k"), 1..=MAX_K).build(ui, &mut self.k) {
+            self.compute()?;
+        }
+        ui.checkbox(imgui::im_str!("Edges"), &mut self.show_edges);
+
+        if ui.button(imgui::im_str!("Clear Points"), [0.0, 0.0]) {
+            self.scene.borrow_mut().clear_points();
+        }
+
+        if let Some(point) = self.point_input.configure(ui)? {
+            self.scene.borrow_mut().add_point(point);
+        }
+
+        self.exec_time.configure(ui);
+        Ok(())
+    }
+}
+
+impl<'f> OrderKVoronoiRenderer<'f> {
+    pub fn new(facade: &'f dyn Facade, scene: SharedScene) -> Result<Self> {
+        let program = Program::from_source(facade, graphics::SHADERS._2d_vs, graphics::SHADERS.basic_fs, None)?;
+        let regions_program = Program::from_source(facade, graphics::SHADERS._2d_color_vs, graphics::SHADERS.color_fs, None)?;
+
+        Ok(Self {
+            facade,
+            alg: OrderKVoronoi::new(),
+            scene,
+            revision: 0,
+            k: 2,
+            program,
+            regions_program,
+            regions_buffer: VertexBuffer::empty(facade, 0)?,
+            points: Shape::new(facade, PrimitiveType::Points, [ 1.0, 1.0, 1.0 ])?,
+            edges: Shape::new(facade, PrimitiveType::LinesList, [ 0.1, 0.1, 0.1 ])?,
+            show_edges: true,
+            exec_time: ExecTimeHistory::default(),
+            point_input: PointInput::new(facade)?,
+        })
+    }
+
+    /// Computes the diagram of the points of the scene and regenerates the buffers.
+    fn compute(&mut self) -> Result<()> {
+        let scene = self.scene.borrow();
+        self.revision = scene.revision();
+
+        let start_time = Instant::now();
+        self.alg.set_points(scene.points().to_vec(), self.k as usize);
+        drop(scene);
+        if self.alg.points().is_empty() {
+            self.exec_time.clear();
+        } else {
+            self.exec_time.push(Instant::now() - start_time);
+        }
+
+        // Fan triangulation of each convex region
+        let mut vertices = Vec::new();
+        let mut edges = Vec::new();
+        for region in self.alg.regions() {
+            let color = Self::sites_color(&region.sites);
+            let polygon = &region.polygon;
+            for idx in 1..polygon.len() - 1 {
+                vertices.extend_from_slice(&[
+                    Vertex { position: polygon[0], color },
+                    Vertex { position: polygon[idx], color },
+                    Vertex { position: polygon[idx + 1], color },
+                ]);
+            }
+            for (idx, &p) in polygon.iter().enumerate() {
+                edges.extend_from_slice(&[ p, polygon[(idx + 1) % polygon.len()] ]);
+            }
+        }
+        self.regions_buffer = VertexBuffer::new(self.facade, &vertices)?;
+        self.edges.set_vertices(&edges)?;
+        self.points.set_vertices(self.alg.points())
+    }
+
+    /// Returns a color that only depends on the set of sites, so that the regions of a cell have the same color.
+    fn sites_color(sites: &[usize]) -> [f32; 3] {
+        // FNV-1a hash of the sorted indices
+        let hash = sites.iter().fold(0xcbf2_9ce4_8422_2325_u64, |hash, &idx| {
+            (hash ^ idx as u64).wrapping_mul(0x0000_0100_0000_01b3)
+        });
+
+        // Hue from the hash, with a saturation and a value that keep the points and the edges visible
+        let hue = (hash % 360) as f32 / 60.0;
+        let (saturation, value) = (0.45, 0.75);
+        let chroma = value * saturation;
+        let x = chroma * (1.0 - ((hue % 2.0) - 1.0).abs());
+        let (r, g, b) = match hue as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        let m = value - chroma;
+        [ r + m, g + m, b + m ]
+    }
+}
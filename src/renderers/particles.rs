@@ -0,0 +1,319 @@
+use crate::{
+    Result,
+    graphics::{ self, Shape, Viewport },
+    math::Vec2,
+    spatial::{ self, SpatialHash },
+    ui::{ ExecTimeHistory, window::algorithms::{ Drawable, Configurable } },
+};
+
+use std::{ f32::consts::PI, time::Instant };
+
+use glium::{
+    index::PrimitiveType, Frame, Program,
+    backend::Facade,
+};
+use winit::{
+    event::{ Event, WindowEvent, MouseButton, ElementState },
+    window::Window,
+};
+
+/// The particles bounce off the sides of this square
+const BOUNDS: f32 = 0.95;
+/// Range of the radii of the particles, the cells of the hash are as large as the largest particle
+const MIN_RADIUS: f32 = 0.004;
+const MAX_RADIUS: f32 = 0.012;
+const MAX_PARTICLES: i32 = 5000;
+/// Number of segments of the outline of a particle
+const CIRCLE_SEGMENTS: usize = 10;
+/// The frames longer than this are simulated as if they lasted this long, e.g. after the window was moved
+const MAX_TIME_STEP: f32 = 0.1;
+
+/// A disk moving at a constant speed between collisions, its mass is its area.
+struct Particle {
+    position: Vec2,
+    velocity: Vec2,
+    radius: f32,
+}
+
+impl Particle {
+    fn random(position: Vec2) -> Self {
+        Self {
+            position,
+            velocity: Vec2::random_range(-0.3, 0.3, -0.3, 0.3),
+            radius: MIN_RADIUS + rand::random::<f32>() * (MAX_RADIUS - MIN_RADIUS),
+        }
+    }
+
+    /// Moves the particle during `dt` seconds, bouncing off the sides of the bounds.
+    fn step(&mut self, dt: f32, gravity: f32) {
+        self.velocity.y -= gravity * dt;
+        self.position = &self.position + &(dt * &self.velocity);
+        if self.position.x.abs() > BOUNDS - self.radius {
+            self.velocity.x = -self.velocity.x.abs() * self.position.x.signum();
+        }
+        if self.position.y.abs() > BOUNDS - self.radius {
+            self.velocity.y = -self.velocity.y.abs() * self.position.y.signum();
+        }
+    }
+}
+
+pub struct ParticlesRenderer<'f> {
+    particles: Vec<Particle>,
+    hash: SpatialHash,
+    program: Program,
+    outlines: Shape<'f>,
+    colliding: Shape<'f>,
+    cells: Shape<'f>,
+    count: i32,
+    speed: f32,
+    gravity: f32,
+    paused: bool,
+    show_cells: bool,
+    /// Also finds the pairs by testing all of them, to compare the time and check that both find the same pairs
+    compare: bool,
+    pairs_match: bool,
+    last_update: Instant,
+    /// Particles that changed cell during the last frame, and pairs of overlapping particles
+    cell_changes: usize,
+    collisions: usize,
+    hash_time: ExecTimeHistory,
+    naive_time: ExecTimeHistory,
+}
+
+impl<'f> Drawable for ParticlesRenderer<'f> {
+    fn update(&mut self) -> Result<()> {
+        let now = Instant::now();
+        let dt = (now - self.last_update).as_secs_f32().min(MAX_TIME_STEP);
+        self.last_update = now;
+        if !self.paused {
+            for particle in &mut self.particles {
+                particle.step(dt * self.speed, self.gravity);
+            }
+        }
+        self.collide()
+    }
+
+    fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        if self.show_cells {
+            self.cells.draw(target, &self.program, viewport)?;
+        }
+        self.outlines.draw(target, &self.program, viewport)?;
+        self.colliding.draw(target, &self.program, viewport)
+    }
+
+    fn handle_events(&mut self, window: &Window, event: &Event<()>, io: &imgui::Io, viewport: &Viewport) -> Result<()> {
+        if let Event::WindowEvent { event, .. } = event {
+            if let WindowEvent::MouseInput { button, state, .. } = event {
+                if !io.want_capture_mouse && // Ignore clicks when the cursor is over an ImGui window
+                    button == &MouseButton::Left && state == &ElementState::Pressed {
+
+                    // Throw a new particle on click
+                    let coords = viewport.window_pos_to_normalized(io.mouse_pos.into(), window);
+                    self.hash.insert(coords);
+                    self.particles.push(Particle::random(coords));
+                    self.count = self.particles.len() as i32;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'f> Configurable for ParticlesRenderer<'f> {
+    fn name(&self) -> &'static str {
+        "Particle collisions"
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        Some("Thousands of disks bounce off each other, their collisions found with a spatial hash: \
+              a uniform grid with cells as large as the largest disk, whose cells are hashed into a fixed number of buckets. \
+              A disk can only overlap the disks of the 3 × 3 cells around its own, so each disk is compared to a few others \
+              instead of all of them. Between frames, only the disks that changed cell are moved to another bucket. \
+              The pairs can also be found by testing all of them, which takes quadratic time.")
+    }
+
+    fn pseudo_code(&self) -> Option<&'static str> {
+        Some(concat!(
+            "for each disk a:\n",
+            "    if a changed cell:\n",
+            "        move a to the bucket of its cell\n",
+            "for each disk a:\n",
+            "    for each bucket of the cells around a:\n",
+            "        for each disk b > a in the bucket:\n",
+            "            if |a - b| <= a.radius + b.radius:\n",
+            "                bounce a and b",
+        ))
+    }
+
+    fn complexity(&self) -> Option<&'static str> {
+        Some("O(n + k) per frame when the disks are spread out, k the number of overlapping pairs, instead of O(n²) for the pair loop")
+    }
+
+    fn configure(&mut self, ui: &imgui::Ui) -> Result<()> {
+        let n = self.particles.len();
+        ui.text(imgui::im_str!("{} particles, {} pairs", n, n * n.saturating_sub(1) / 2));
+        ui.text(imgui::im_str!("{} collisions, {} cell changes", self.collisions, self.cell_changes));
+
+        if imgui::Slider::new(imgui::im_str!("Particles"), 0..=MAX_PARTICLES).build(ui, &mut self.count) {
+            self.reset();
+        }
+        imgui::Slider::new(imgui::im_str!("Speed"), 0.0..=5.0).build(ui, &mut self.speed);
+        imgui::Slider::new(imgui::im_str!("Gravity"), 0.0..=2.0).build(ui, &mut self.gravity);
+        ui.checkbox(imgui::im_str!("Pause"), &mut self.paused);
+        ui.checkbox(imgui::im_str!("Occupied cells"), &mut self.show_cells);
+        if ui.button(imgui::im_str!("Reset"), [0.0, 0.0]) {
+            self.reset();
+        }
+
+        ui.text(imgui::im_str!("Spatial hash"));
+        self.hash_time.configure(ui);
+        if ui.checkbox(imgui::im_str!("Compare with the pair loop"), &mut self.compare) {
+            self.naive_time.clear();
+        }
+        if self.compare {
+            self.naive_time.configure(ui);
+            if !self.pairs_match {
+                ui.text_colored([ 1.0, 0.3, 0.3, 1.0 ], imgui::im_str!("The pairs differ"));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'f> ParticlesRenderer<'f> {
+    pub fn new(facade: &'f dyn Facade) -> Result<Self> {
+        let vs = graphics::SHADERS._2d_vs;
+        let fs = graphics::SHADERS.basic_fs;
+        let program = Program::from_source(facade, vs, fs, None)?;
+
+        let mut renderer = Self {
+            particles: Vec::new(),
+            hash: SpatialHash::new(2.0 * MAX_RADIUS, 0),
+            program,
+            outlines: Shape::new(facade, PrimitiveType::LinesList, [ 1.0, 1.0, 1.0 ])?,
+            colliding: Shape::new(facade, PrimitiveType::LinesList, [ 1.0, 0.5, 0.0 ])?,
+            cells: Shape::new(facade, PrimitiveType::LinesList, [ 0.3, 0.3, 0.6 ])?,
+            count: 1000,
+            speed: 1.0,
+            gravity: 0.0,
+            paused: false,
+            show_cells: false,
+            compare: false,
+            pairs_match: true,
+            last_update: Instant::now(),
+            cell_changes: 0,
+            collisions: 0,
+            hash_time: ExecTimeHistory::default(),
+            naive_time: ExecTimeHistory::default(),
+        };
+        renderer.reset();
+        Ok(renderer)
+    }
+
+    /// Replaces the particles with `count` random ones, and fills the hash with them.
+    fn reset(&mut self) {
+        self.particles = (0..self.count.max(0))
+            .map(|_| Particle::random(Vec2::random_range(-BOUNDS, BOUNDS, -BOUNDS, BOUNDS)))
+            .collect();
+        self.hash = SpatialHash::new(2.0 * MAX_RADIUS, self.particles.len());
+        for particle in &self.particles {
+            self.hash.insert(particle.position);
+        }
+        self.hash_time.clear();
+        self.naive_time.clear();
+    }
+
+    /// Updates the hash, bounces the overlapping particles off each other and regenerates the buffers.
+    fn collide(&mut self) -> Result<()> {
+        let centers = self.particles.iter().map(|particle| particle.position).collect::<Vec<_>>();
+        let radii = self.particles.iter().map(|particle| particle.radius).collect::<Vec<_>>();
+
+        let start_time = Instant::now();
+        self.cell_changes = centers.iter().enumerate().filter(|&(idx, &p)| self.hash.update(idx, p)).count();
+        let pairs = self.hash.pairs(&centers, &radii);
+        self.hash_time.push(Instant::now() - start_time);
+
+        if self.compare {
+            let start_time = Instant::now();
+            let naive_pairs = spatial::overlapping_circles(&centers, &radii);
+            self.naive_time.push(Instant::now() - start_time);
+            let mut sorted = pairs.clone();
+            sorted.sort_unstable();
+            self.pairs_match = sorted == naive_pairs;
+        }
+        self.collisions = pairs.len();
+
+        let mut colliding = vec![ false; self.particles.len() ];
+        for &(a, b) in &pairs {
+            colliding[a] = true;
+            colliding[b] = true;
+            self.bounce(a, b);
+        }
+
+        let particles = &self.particles;
+        let outlines = |colliding_particles: bool| {
+            particles
+                .iter()
+                .zip(&colliding)
+                .filter(|&(_, &c)| c == colliding_particles)
+                .flat_map(|(particle, _)| {
+                    (0..CIRCLE_SEGMENTS).flat_map(move |i| {
+                        let angle = |i: usize| 2.0 * PI * i as f32 / CIRCLE_SEGMENTS as f32;
+                        let point = |a: f32| Vec2::new(particle.position.x + particle.radius * a.cos(), particle.position.y + particle.radius * a.sin());
+                        vec![ point(angle(i)), point(angle(i + 1)) ]
+                    })
+                })
+                .collect::<Vec<_>>()
+        };
+        self.outlines.set_vertices(&outlines(false))?;
+        self.colliding.set_vertices(&outlines(true))?;
+
+        if self.show_cells {
+            let size = self.hash.cell_size();
+            let mut occupied = centers.iter().map(|&p| self.hash.cell(p)).collect::<Vec<_>>();
+            occupied.sort_unstable();
+            occupied.dedup();
+            let edges = occupied
+                .iter()
+                .flat_map(|&(column, row)| {
+                    let (x, y) = (column as f32 * size, row as f32 * size);
+                    let corners = [ Vec2::new(x, y), Vec2::new(x + size, y), Vec2::new(x + size, y + size), Vec2::new(x, y + size) ];
+                    (0..4).flat_map(move |i| vec![ corners[i], corners[(i + 1) % 4] ])
+                })
+                .collect::<Vec<_>>();
+            self.cells.set_vertices(&edges)?;
+        }
+        Ok(())
+    }
+
+    /// Separates two overlapping particles and exchanges the parts of their velocities along the line between them,
+    /// as in an elastic collision. Particles already moving apart keep their velocities.
+    fn bounce(&mut self, a: usize, b: usize) {
+        let (pa, pb) = (&self.particles[a], &self.particles[b]);
+        let offset = &pb.position - &pa.position;
+        let distance = offset.length();
+        if distance == 0.0 {
+            return;
+        }
+        let normal = &offset / distance;
+        let (mass_a, mass_b) = (pa.radius * pa.radius, pb.radius * pb.radius);
+        let total = mass_a + mass_b;
+
+        // Each particle moves back in proportion to the mass of the other
+        let overlap = pa.radius + pb.radius - distance;
+        let approach = (&pa.velocity - &pb.velocity).dot(normal);
+        let (push_a, push_b) = (overlap * mass_b / total, overlap * mass_a / total);
+        let (impulse_a, impulse_b) = if approach > 0.0 {
+            (2.0 * mass_b / total * approach, 2.0 * mass_a / total * approach)
+        } else {
+            (0.0, 0.0)
+        };
+
+        let particle = &mut self.particles[a];
+        particle.position -= &(push_a * &normal);
+        particle.velocity -= &(impulse_a * &normal);
+        let particle = &mut self.particles[b];
+        particle.position += &(push_b * &normal);
+        particle.velocity += &(impulse_b * &normal);
+    }
+}
@@ -0,0 +1,340 @@
+use crate::{
+    Result,
+    algorithms::{ a_star, theta_star, visibility_graph, OccupancyGrid, GridPath },
+    graphics::{ self, Shape, Viewport },
+    math::{ Vec2, Rect, Segment2, polygon },
+    scene::SharedScene,
+    ui::{ PointInput, window::algorithms::{ Drawable, Configurable } },
+};
+
+use std::time::{ Duration, Instant };
+
+use glium::{
+    index::PrimitiveType, Frame, Program,
+    backend::Facade,
+};
+use winit::{
+    event::{ Event, WindowEvent, MouseButton, ElementState },
+    window::Window,
+};
+
+/// Largest distance between a click and the start or the end to drag it
+const PICK_RADIUS: f32 = 0.04;
+
+/// Path of one of the methods, with the time it took
+struct Run {
+    points: Vec<Vec2>,
+    /// Cells expanded by the grid searches, edges of the visibility graph
+    work: usize,
+    time: Duration,
+}
+
+impl Run {
+    fn length(&self) -> f32 {
+        self.points.windows(2).map(|link| (&link[1] - &link[0]).length()).sum()
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Handle {
+    Start,
+    End,
+}
+
+pub struct PathfindingRenderer<'f> {
+    /// The obstacles are the polygons and the segments of the scene
+    scene: SharedScene,
+    revision: u64,
+    start: Vec2,
+    end: Vec2,
+    dragging: Option<Handle>,
+    /// Vertices of the obstacle being drawn, added to the scene on right click
+    pending: Vec<Vec2>,
+    cell_size: f32,
+    cells: usize,
+    grid_time: Duration,
+    a_star: Option<Run>,
+    theta_star: Option<Run>,
+    visibility: Option<Run>,
+    show_grid: bool,
+    show_visibility_graph: bool,
+    random_count: i32,
+    program: Program,
+    blocked: Shape<'f>,
+    obstacles: Shape<'f>,
+    pending_shape: Shape<'f>,
+    visibility_edges: Shape<'f>,
+    a_star_path: Shape<'f>,
+    theta_star_path: Shape<'f>,
+    visibility_path: Shape<'f>,
+    endpoints: Shape<'f>,
+    point_input: PointInput<'f>,
+}
+
+impl<'f> Drawable for PathfindingRenderer<'f> {
+    fn update(&mut self) -> Result<()> {
+        if self.scene.borrow().revision() != self.revision {
+            self.compute()?;
+        }
+        Ok(())
+    }
+
+    fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        self.point_input.draw(target, viewport)?;
+        if self.show_grid {
+            self.blocked.draw(target, &self.program, viewport)?;
+        }
+        if self.show_visibility_graph {
+            self.visibility_edges.draw(target, &self.program, viewport)?;
+        }
+        self.obstacles.draw(target, &self.program, viewport)?;
+        self.pending_shape.draw(target, &self.program, viewport)?;
+        self.a_star_path.draw(target, &self.program, viewport)?;
+        self.theta_star_path.draw(target, &self.program, viewport)?;
+        self.visibility_path.draw(target, &self.program, viewport)?;
+        self.endpoints.draw(target, &self.program, viewport)
+    }
+
+    fn handle_events(&mut self, window: &Window, event: &Event<()>, io: &imgui::Io, viewport: &Viewport) -> Result<()> {
+        if let Event::WindowEvent { event, .. } = event {
+            let coords = viewport.window_pos_to_normalized(io.mouse_pos.into(), window);
+            match event {
+                WindowEvent::MouseInput { button: MouseButton::Left, state: ElementState::Pressed, .. } => {
+                    if !io.want_capture_mouse { // Ignore clicks when the cursor is over an ImGui window
+                        // Drag the start or the end, or add a vertex to the obstacle being drawn
+                        self.dragging = if (&coords - &self.start).length() < PICK_RADIUS {
+                            Some(Handle::Start)
+                        } else if (&coords - &self.end).length() < PICK_RADIUS {
+                            Some(Handle::End)
+                        } else {
+                            None
+                        };
+                        if self.dragging.is_none() {
+                            self.pending.push(self.point_input.place(coords));
+                            self.pending_shape.set_vertices(&self.pending)?;
+                        }
+                    }
+                },
+                WindowEvent::MouseInput { button: MouseButton::Left, state: ElementState::Released, .. } => {
+                    self.dragging = None;
+                },
+                WindowEvent::MouseInput { button: MouseButton::Right, state: ElementState::Pressed, .. } => {
+                    if !io.want_capture_mouse {
+                        self.close_obstacle()?;
+                    }
+                },
+                WindowEvent::CursorMoved { .. } => {
+                    match self.dragging {
+                        Some(Handle::Start) => self.start = self.point_input.place(coords),
+                        Some(Handle::End) => self.end = self.point_input.place(coords),
+                        None => return Ok(()),
+                    }
+                    self.compute()?;
+                },
+                _ => {},
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'f> Configurable for PathfindingRenderer<'f> {
+    fn name(&self) -> &'static str {
+        "Grid pathfinding"
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        Some("Compares three paths from the start to the end among the obstacles. \
+              The obstacles block the cells of a grid they overlap. A*, in orange, moves between the centers \
+              of neighbouring free cells, so its path only turns by multiples of 45°. \
+              Theta*, in cyan, runs the same search but links each cell to the parent of the cell it comes from \
+              when the grid line between them is free, which straightens the path. \
+              The shortest path, in green, bends at the corners of the obstacles: it is found with Dijkstra's algorithm \
+              in their visibility graph, the pairs of corners that see each other. \
+              Drag the start and the end to move them, left click to add the vertices of an obstacle \
+              and right click to close it. The obstacles are the polygons and the segments of the scene.")
+    }
+
+    fn pseudo_code(&self) -> Option<&'static str> {
+        Some(concat!(
+            "open = { start }, g(start) = 0\n",
+            "while open is not empty:\n",
+            "    s = cell of open with the least g + h\n",
+            "    if s is the end: stop\n",
+            "    for each free neighbour n of s:\n",
+            "        if Theta* and parent(s) sees n:\n",
+            "            g' = g(parent(s)) + |parent(s) n|\n",
+            "            p = parent(s)\n",
+            "        else:\n",
+            "            g' = g(s) + |s n|, p = s\n",
+            "        if g' < g(n):\n",
+            "            g(n) = g', parent(n) = p\n",
+            "            add n to open",
+        ))
+    }
+
+    fn complexity(&self) -> Option<&'static str> {
+        Some("O(c log c) for c cells with A*, times the O(√c) line of sight checks with Theta*, \
+              O(n³) for the visibility graph of n corners")
+    }
+
+    fn configure(&mut self, ui: &imgui::Ui) -> Result<()> {
+        ui.text(imgui::im_str!("{} cells, built in {} µs", self.cells, self.grid_time.as_micros()));
+        for (name, run, unit) in &[
+            ("A*", &self.a_star, "cells"),
+            ("Theta*", &self.theta_star, "cells"),
+            ("Visibility graph", &self.visibility, "edges"),
+        ] {
+            match run {
+                Some(run) => ui.text(imgui::im_str!("{}: length {:.4}, {} µs, {} {}", name, run.length(), run.time.as_micros(), run.work, unit)),
+                None => ui.text(imgui::im_str!("{}: no path", name)),
+            }
+        }
+
+        if imgui::Slider::new(imgui::im_str!("Cell size"), 0.01..=0.2).build(ui, &mut self.cell_size) {
+            self.compute()?;
+        }
+        ui.checkbox(imgui::im_str!("Blocked cells"), &mut self.show_grid);
+        ui.same_line(0.0);
+        ui.checkbox(imgui::im_str!("Visibility graph"), &mut self.show_visibility_graph);
+
+        ui.input_int(imgui::im_str!("Count"), &mut self.random_count).build();
+        if ui.button(imgui::im_str!("Random Obstacles"), [0.0, 0.0]) {
+            let mut scene = self.scene.borrow_mut();
+            for _ in 0..self.random_count.max(0) {
+                // Convex polygons, with their corners at increasing angles around their center
+                let center = Vec2::random_range(-0.8, 0.8, -0.8, 0.8);
+                let corners = 3 + rand::random::<usize>() % 4;
+                scene.add_polygon((0..corners).map(|k| {
+                    let angle = (k as f32 + rand::random::<f32>() * 0.5) * std::f32::consts::PI * 2.0 / corners as f32;
+                    let radius = 0.05 + rand::random::<f32>() * 0.1;
+                    Vec2::new(center.x + radius * angle.cos(), center.y + radius * angle.sin())
+                }).collect());
+            }
+        }
+        ui.same_line(0.0);
+        if ui.button(imgui::im_str!("Clear Obstacles"), [0.0, 0.0]) {
+            self.pending.clear();
+            self.pending_shape.clear()?;
+            let mut scene = self.scene.borrow_mut();
+            scene.clear_polygons();
+            scene.clear_segments();
+        }
+
+        if let Some(point) = self.point_input.configure(ui)? {
+            self.pending.push(point);
+            self.pending_shape.set_vertices(&self.pending)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'f> PathfindingRenderer<'f> {
+    pub fn new(facade: &'f dyn Facade, scene: SharedScene) -> Result<Self> {
+        let vs = graphics::SHADERS._2d_vs;
+        let fs = graphics::SHADERS.basic_fs;
+        let program = Program::from_source(facade, vs, fs, None)?;
+
+        let path = |color| -> Result<Shape<'f>> {
+            let mut shape = Shape::new(facade, PrimitiveType::LineStrip, color)?;
+            shape.size = 2.0;
+            Ok(shape)
+        };
+        let mut endpoints = Shape::new(facade, PrimitiveType::Points, [ 1.0, 1.0, 1.0 ])?;
+        endpoints.size = 12.0;
+
+        Ok(Self {
+            scene,
+            revision: 0,
+            start: Vec2::new(-0.8, -0.8),
+            end: Vec2::new(0.8, 0.8),
+            dragging: None,
+            pending: Vec::new(),
+            cell_size: 0.05,
+            cells: 0,
+            grid_time: Duration::default(),
+            a_star: None,
+            theta_star: None,
+            visibility: None,
+            show_grid: true,
+            show_visibility_graph: false,
+            random_count: 10,
+            program,
+            blocked: Shape::new(facade, PrimitiveType::TrianglesList, [ 0.2, 0.2, 0.25 ])?,
+            obstacles: Shape::new(facade, PrimitiveType::LinesList, [ 1.0, 1.0, 1.0 ])?,
+            pending_shape: Shape::new(facade, PrimitiveType::LineStrip, [ 1.0, 0.8, 0.0 ])?,
+            visibility_edges: Shape::new(facade, PrimitiveType::LinesList, [ 0.25, 0.3, 0.5 ])?,
+            a_star_path: path([ 1.0, 0.5, 0.0 ])?,
+            theta_star_path: path([ 0.0, 0.8, 1.0 ])?,
+            visibility_path: path([ 0.2, 1.0, 0.3 ])?,
+            endpoints,
+            point_input: PointInput::new(facade)?,
+        })
+    }
+
+    /// Adds the obstacle being drawn to the scene, a segment if it has two vertices.
+    fn close_obstacle(&mut self) -> Result<()> {
+        let pending = std::mem::take(&mut self.pending);
+        match pending.len() {
+            0 | 1 => {},
+            2 => self.scene.borrow_mut().add_segment(Segment2::new(pending[0], pending[1])),
+            _ => self.scene.borrow_mut().add_polygon(pending),
+        }
+        self.pending_shape.clear()
+    }
+
+    /// Finds the three paths and regenerates the buffers.
+    fn compute(&mut self) -> Result<()> {
+        let scene = self.scene.borrow();
+        self.revision = scene.revision();
+        let (polygons, segments) = (scene.polygons().to_vec(), scene.segments().to_vec());
+        drop(scene);
+
+        // The grid covers the view
+        let start_time = Instant::now();
+        let grid = OccupancyGrid::new(Rect::new(Vec2::new(-1.0, -1.0), Vec2::new(1.0, 1.0)), self.cell_size, &polygons, &segments);
+        self.grid_time = Instant::now() - start_time;
+        self.cells = grid.blocked.len();
+
+        let (start, end) = (self.start, self.end);
+        let search = |find: fn(&OccupancyGrid, Vec2, Vec2) -> Option<GridPath>| {
+            let start_time = Instant::now();
+            find(&grid, start, end).map(|path| Run { points: path.points, work: path.expanded, time: Instant::now() - start_time })
+        };
+        self.a_star = search(a_star);
+        self.theta_star = search(theta_star);
+
+        let start_time = Instant::now();
+        let graph = visibility_graph(&polygons, &segments, start, end);
+        self.visibility = graph.shortest_path().map(|points| Run { points, work: graph.edges.len(), time: Instant::now() - start_time });
+
+        let blocked = (0..grid.rows)
+            .flat_map(|row| (0..grid.columns).map(move |column| (column, row)))
+            .filter(|&(column, row)| grid.blocked[row * grid.columns + column])
+            .flat_map(|cell| {
+                let [ a, b, c, d ] = grid.cell_rect(cell).corners();
+                vec![ a, b, c, a, c, d ]
+            })
+            .collect::<Vec<_>>();
+        self.blocked.set_vertices(&blocked)?;
+        let edges = polygons
+            .iter()
+            .flat_map(|polygon| polygon::edges(polygon).flat_map(|(a, b)| vec![ a, b ]).collect::<Vec<_>>())
+            .chain(segments.iter().flat_map(|s| vec![ s.a, s.b ]))
+            .collect::<Vec<_>>();
+        self.obstacles.set_vertices(&edges)?;
+        self.visibility_edges.set_vertices(&graph.edges.iter().flat_map(|&(a, b)| vec![ graph.nodes[a], graph.nodes[b] ]).collect::<Vec<_>>())?;
+
+        set_path(&mut self.a_star_path, &self.a_star)?;
+        set_path(&mut self.theta_star_path, &self.theta_star)?;
+        set_path(&mut self.visibility_path, &self.visibility)?;
+        self.endpoints.set_vertices(&[ start, end ])
+    }
+}
+
+fn set_path(shape: &mut Shape, run: &Option<Run>) -> Result<()> {
+    match run {
+        Some(run) => shape.set_vertices(&run.points),
+        None => shape.clear(),
+    }
+}
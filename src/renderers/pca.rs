@@ -0,0 +1,209 @@
+use crate::{
+    Result,
+    graphics::{ self, Shape, Viewport },
+    math::{ Vec2, Circle, pca::{ self, PrincipalAxes } },
+    scene::SharedScene,
+    ui::{ PointInput, ExecTimeHistory, window::algorithms::{ Drawable, Configurable } },
+};
+
+use std::time::Instant;
+
+use glium::{
+    index::PrimitiveType, Frame, Program,
+    backend::Facade,
+};
+use winit::{
+    event::{ Event, WindowEvent, MouseButton, ElementState },
+    window::Window,
+};
+
+/// The axes and the ellipse extend to this many standard deviations from the centroid
+const DEVIATIONS: f32 = 2.0;
+/// Number of vertices of the ellipse
+const ELLIPSE_VERTICES: usize = 64;
+
+pub struct PcaRenderer<'f> {
+    result: Option<PrincipalAxes>,
+    /// The input points are those of the scene
+    scene: SharedScene,
+    /// Revision of the scene the axes were computed from
+    revision: u64,
+    program: Program,
+    points: Shape<'f>,
+    major_axis: Shape<'f>,
+    minor_axis: Shape<'f>,
+    ellipse: Shape<'f>,
+    show_ellipse: bool,
+    random_count: i32,
+    exec_time: ExecTimeHistory,
+    point_input: PointInput<'f>,
+}
+
+impl<'f> Drawable for PcaRenderer<'f> {
+    fn update(&mut self) -> Result<()> {
+        if self.scene.borrow().revision() != self.revision {
+            self.compute()?;
+        }
+        Ok(())
+    }
+
+    fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        self.point_input.draw(target, viewport)?;
+        if self.show_ellipse {
+            self.ellipse.draw(target, &self.program, viewport)?;
+        }
+        self.major_axis.draw(target, &self.program, viewport)?;
+        self.minor_axis.draw(target, &self.program, viewport)?;
+        self.points.draw(target, &self.program, viewport)
+    }
+
+    fn handle_events(&mut self, window: &Window, event: &Event<()>, io: &imgui::Io, viewport: &Viewport) -> Result<()> {
+        if let Event::WindowEvent { event, .. } = event {
+            if let WindowEvent::MouseInput { button, state, .. } = event {
+                if !io.want_capture_mouse && // Ignore clicks when the cursor is over an ImGui window
+                    button == &MouseButton::Left && state == &ElementState::Pressed {
+
+                    // Add a point on click
+                    let coords = viewport.window_pos_to_normalized(io.mouse_pos.into(), window);
+                    self.scene.borrow_mut().add_point(self.point_input.place(coords));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'f> Configurable for PcaRenderer<'f> {
+    fn name(&self) -> &'static str {
+        "Principal component analysis"
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        Some("The principal axes of the points are the eigenvectors of their covariance matrix: \
+              the first one is the direction along which the points spread the most, the second one is orthogonal to it. \
+              The eigenvalues are the variances of the points along the axes, which are drawn from the centroid \
+              out to two standard deviations, the square roots of the eigenvalues. \
+              Aligning the points rotates them around their centroid so that the principal axes become the x and y axes.")
+    }
+
+    fn pseudo_code(&self) -> Option<&'static str> {
+        Some(concat!(
+            "c = average of the points\n",
+            "C = average of (p - c)(p - c)^T\n",
+            "    over the points p\n",
+            "λ1 >= λ2 = eigenvalues of C\n",
+            "u1, u2 = unit eigenvectors of C\n",
+            "draw c ± 2 sqrt(λi) ui",
+        ))
+    }
+
+    fn complexity(&self) -> Option<&'static str> {
+        Some("O(n)")
+    }
+
+    fn configure(&mut self, ui: &imgui::Ui) -> Result<()> {
+        if let Some(result) = self.result {
+            let angle = result.axes[0].y.atan2(result.axes[0].x).to_degrees();
+            ui.text(imgui::im_str!("Centroid: ({:.4}, {:.4})", result.centroid.x, result.centroid.y));
+            ui.text(imgui::im_str!("Variances: {:.6}, {:.6}", result.variances[0], result.variances[1]));
+            ui.text(imgui::im_str!("Major axis at {:.2}°", angle));
+        }
+
+        ui.checkbox(imgui::im_str!("Ellipse"), &mut self.show_ellipse);
+        if ui.button(imgui::im_str!("Align to Principal Axes"), [0.0, 0.0]) {
+            if let Some(result) = self.result {
+                let mut scene = self.scene.borrow_mut();
+                let points = scene.points().iter().map(|&p| result.align(p)).collect();
+                scene.set_points(points);
+            }
+        }
+
+        if ui.button(imgui::im_str!("Clear Points"), [0.0, 0.0]) {
+            self.scene.borrow_mut().clear_points();
+        }
+
+        ui.input_int(imgui::im_str!("Count"), &mut self.random_count).build();
+        if ui.button(imgui::im_str!("Random Points"), [0.0, 0.0]) {
+            // Points spread along a random direction, to give the axes different lengths
+            let (direction, spread) = (Vec2::random_range(-1.0, 1.0, -1.0, 1.0).normalized(), 0.2 + rand::random::<f32>() * 0.6);
+            let normal = Vec2::new(-direction.y, direction.x);
+            self.scene.borrow_mut().add_points((0..self.random_count.max(0)).map(|_| {
+                let offset = Vec2::random_range(-0.8, 0.8, -0.8 * spread, 0.8 * spread);
+                &(offset.x * &direction) + &(offset.y * &normal)
+            }));
+        }
+
+        if let Some(point) = self.point_input.configure(ui)? {
+            self.scene.borrow_mut().add_point(point);
+        }
+
+        self.exec_time.configure(ui);
+        Ok(())
+    }
+}
+
+impl<'f> PcaRenderer<'f> {
+    pub fn new(facade: &'f dyn Facade, scene: SharedScene) -> Result<Self> {
+        let vs = graphics::SHADERS._2d_vs;
+        let fs = graphics::SHADERS.basic_fs;
+        let program = Program::from_source(facade, vs, fs, None)?;
+
+        let mut major_axis = Shape::new(facade, PrimitiveType::LinesList, [ 1.0, 0.0, 0.0 ])?;
+        major_axis.size = 2.0;
+        let mut minor_axis = Shape::new(facade, PrimitiveType::LinesList, [ 0.0, 1.0, 0.0 ])?;
+        minor_axis.size = 2.0;
+
+        Ok(Self {
+            result: None,
+            scene,
+            revision: 0,
+            program,
+            points: Shape::new(facade, PrimitiveType::Points, [ 1.0, 1.0, 1.0 ])?,
+            major_axis,
+            minor_axis,
+            ellipse: Shape::new(facade, PrimitiveType::LineLoop, [ 0.3, 0.3, 0.6 ])?,
+            show_ellipse: true,
+            random_count: 200,
+            exec_time: ExecTimeHistory::default(),
+            point_input: PointInput::new(facade)?,
+        })
+    }
+
+    /// Computes the principal axes of the points of the scene and regenerates the buffers.
+    fn compute(&mut self) -> Result<()> {
+        let scene = self.scene.borrow();
+        self.revision = scene.revision();
+
+        let start_time = Instant::now();
+        self.result = pca::principal_axes(scene.points());
+        if self.result.is_some() {
+            self.exec_time.push(Instant::now() - start_time);
+        } else {
+            self.exec_time.clear();
+        }
+        self.points.set_vertices(scene.points())?;
+        drop(scene);
+
+        let result = match self.result {
+            Some(result) => result,
+            None => {
+                self.major_axis.clear()?;
+                self.minor_axis.clear()?;
+                return self.ellipse.clear();
+            },
+        };
+        let c = result.centroid;
+        let half_axes = [
+            (DEVIATIONS * result.variances[0].sqrt()) * &result.axes[0],
+            (DEVIATIONS * result.variances[1].sqrt()) * &result.axes[1],
+        ];
+        self.major_axis.set_vertices(&[ &c - &half_axes[0], &c + &half_axes[0] ])?;
+        self.minor_axis.set_vertices(&[ &c - &half_axes[1], &c + &half_axes[1] ])?;
+
+        let ellipse = Circle::new(Vec2::new(0.0, 0.0), 1.0)
+                        .points(ELLIPSE_VERTICES)
+                        .map(|p| &c + &(&(p.x * &half_axes[0]) + &(p.y * &half_axes[1])))
+                        .collect::<Vec<_>>();
+        self.ellipse.set_vertices(&ellipse)
+    }
+}
@@ -0,0 +1,345 @@
+use crate::{
+    Result,
+    algorithms::{ triangulate_polygon, monotone_decomposition, triangulate_monotone },
+    graphics::{ self, Shape, ColorMappedShape, Viewport },
+    math::Vec2,
+    render::{ layers, DrawList, GliumRenderer },
+    scene::SharedScene,
+    ui::{ PointInput, ExecTimeHistory, window::algorithms::{ Drawable, Configurable } },
+};
+
+use std::time::{ Duration, Instant };
+
+use glium::{
+    index::PrimitiveType, Frame, Program,
+    backend::Facade,
+};
+use winit::{
+    event::{ Event, WindowEvent, MouseButton, ElementState },
+    window::Window,
+};
+
+/// Longest time between the two clicks of a double click, which closes the polygon being drawn
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+/// Largest distance between the two clicks of a double click
+const DOUBLE_CLICK_RADIUS: f32 = 0.02;
+
+/// How the polygon is triangulated
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Method {
+    /// Cutting off one ear at a time
+    EarClipping,
+    /// Splitting the polygon into y-monotone pieces with a plane sweep, then triangulating each piece
+    Monotone,
+}
+
+impl Method {
+    fn name(self) -> &'static str {
+        match self {
+            Method::EarClipping => "Ear clipping",
+            Method::Monotone => "Monotone pieces",
+        }
+    }
+
+    /// Returns the triangles of the polygon, with the monotone pieces if they are computed.
+    fn triangulate(self, polygon: &[Vec2]) -> (Vec<usize>, Vec<Vec<usize>>) {
+        match self {
+            Method::EarClipping => (triangulate_polygon(polygon), Vec::new()),
+            Method::Monotone => {
+                let pieces = monotone_decomposition(polygon);
+                let triangles = pieces.iter().flat_map(|piece| triangulate_monotone(polygon, piece)).collect();
+                (triangles, pieces)
+            },
+        }
+    }
+}
+
+pub struct PolygonTriangulationRenderer<'f> {
+    /// The last polygon of the scene is loaded when it changes
+    scene: SharedScene,
+    revision: u64,
+    /// Vertices of the polygon being drawn, left clicks add new ones
+    pending: Vec<Vec2>,
+    last_click: Option<Instant>,
+    polygon: Vec<Vec2>,
+    method: Method,
+    /// 3 indices into the vertices of the polygon per counter-clockwise triangle
+    triangles: Vec<usize>,
+    /// Counter-clockwise indices into the vertices of the polygon per monotone piece, empty for the ear clipping
+    pieces: Vec<Vec<usize>>,
+    /// Time taken by the other method, when both are compared
+    compare: bool,
+    other_time: Option<Duration>,
+    program: Program,
+    pending_shape: Shape<'f>,
+    renderer: GliumRenderer<'f>,
+    /// The triangles, filled for the ear clipping, their diagonals and the polygon
+    triangulation: DrawList,
+    /// Diagonals between the monotone pieces, and the pieces filled so that neighbouring pieces have different colors
+    piece_edges: Shape<'f>,
+    filled_pieces: ColorMappedShape<'f>,
+    vertex_count: i32,
+    exec_time: ExecTimeHistory,
+    point_input: PointInput<'f>,
+}
+
+impl<'f> Drawable for PolygonTriangulationRenderer<'f> {
+    fn update(&mut self) -> Result<()> {
+        let scene = self.scene.borrow();
+        if scene.revision() != self.revision {
+            self.revision = scene.revision();
+            if let Some(polygon) = scene.polygons().last() {
+                let polygon = polygon.clone();
+                drop(scene);
+                self.set_polygon(polygon)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        self.point_input.draw(target, viewport)?;
+        if self.method == Method::Monotone {
+            self.filled_pieces.draw(target, viewport)?;
+        }
+        self.renderer.draw_in(&self.triangulation, target, viewport.rect(target))?;
+        self.piece_edges.draw(target, &self.program, viewport)?;
+        self.pending_shape.draw(target, &self.program, viewport)
+    }
+
+    fn handle_events(&mut self, window: &Window, event: &Event<()>, io: &imgui::Io, viewport: &Viewport) -> Result<()> {
+        if let Event::WindowEvent { event, .. } = event {
+            if let WindowEvent::MouseInput { button, state, .. } = event {
+                if !io.want_capture_mouse && // Ignore clicks when the cursor is over an ImGui window
+                    button == &MouseButton::Left && state == &ElementState::Pressed {
+
+                    // Add a vertex on click, or close the polygon on double click
+                    let coords = self.point_input.place(viewport.window_pos_to_normalized(io.mouse_pos.into(), window));
+                    let double_click = self.last_click.is_some_and(|time| Instant::now() - time < DOUBLE_CLICK) &&
+                        self.pending.last().is_some_and(|&last| (&coords - &last).length() < DOUBLE_CLICK_RADIUS);
+                    if double_click {
+                        self.last_click = None;
+                        self.close_polygon()?;
+                    } else {
+                        self.last_click = Some(Instant::now());
+                        self.add_vertex(coords)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'f> Configurable for PolygonTriangulationRenderer<'f> {
+    fn name(&self) -> &'static str {
+        "Polygon triangulation"
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        Some("Triangulates a simple polygon by ear clipping. An ear is a convex vertex whose triangle with its two neighbours \
+              contains no other vertex: the diagonal between its neighbours is inside the polygon. \
+              Every simple polygon with more than 3 vertices has two ears, so cutting them off one at a time \
+              leaves a triangle after n - 3 diagonals. \
+              The monotone pieces are found instead by a sweep line moving down: the split and merge vertices, \
+              where the boundary turns back up or down with the interior on the other side, are joined to a vertex nearby. \
+              Each piece is then triangulated from top to bottom along its two chains. \
+              Click to add vertices, double click or press Close Polygon to triangulate it, \
+              the last polygon of the scene is loaded when it changes.")
+    }
+
+    fn pseudo_code(&self) -> Option<&'static str> {
+        if self.method == Method::Monotone {
+            return Some(concat!(
+                "for each vertex v from top to bottom:\n",
+                "    if v is a split vertex:\n",
+                "        e = edge left of v\n",
+                "        connect v to helper(e)\n",
+                "    if the helper replaced by v\n",
+                "            is a merge vertex:\n",
+                "        connect v to it\n",
+                "    update the edges crossing the sweep line\n",
+                "        and their helpers\n",
+                "for each monotone piece:\n",
+                "    merge its chains from top to bottom\n",
+                "    fan each vertex to the reflex chain\n",
+                "        kept on a stack",
+            ));
+        }
+        Some(concat!(
+            "v = first vertex\n",
+            "while more than 3 vertices remain:\n",
+            "    u, w = neighbours of v\n",
+            "    if v is convex and the triangle uvw\n",
+            "            contains no other vertex:\n",
+            "        add the triangle uvw\n",
+            "        remove v, v = w\n",
+            "    else: v = w\n",
+            "add the last triangle",
+        ))
+    }
+
+    fn complexity(&self) -> Option<&'static str> {
+        match self.method {
+            Method::EarClipping => Some("O(n²) in most cases, O(n³) in the worst case: testing a vertex is linear, and a turn of the polygon may find no ear"),
+            Method::Monotone => Some("O(n log n) for the sweep, which sorts the vertices, then O(n) for the pieces"),
+        }
+    }
+
+    fn configure(&mut self, ui: &imgui::Ui) -> Result<()> {
+        let n = self.polygon.len();
+        let count = self.triangles.len() / 3;
+        ui.text(imgui::im_str!("{} vertices, {} triangles, {} diagonals", n, count, count.saturating_sub(1)));
+        if n >= 3 && count < n - 2 {
+            ui.text(imgui::im_str!("{} triangles missing: the polygon is not simple or repeats vertices", n - 2 - count));
+        }
+        if self.method == Method::Monotone {
+            ui.text(imgui::im_str!("{} monotone pieces", self.pieces.len()));
+        }
+        if !self.pending.is_empty() {
+            ui.text(imgui::im_str!("Drawing: {} vertices", self.pending.len()));
+        }
+
+        let mut changed = ui.radio_button(imgui::im_str!("Ear clipping"), &mut self.method, Method::EarClipping);
+        ui.same_line(0.0);
+        changed |= ui.radio_button(imgui::im_str!("Monotone pieces"), &mut self.method, Method::Monotone);
+        changed |= ui.checkbox(imgui::im_str!("Compare both"), &mut self.compare);
+        if changed {
+            self.triangulate()?;
+        }
+        if let Some(time) = self.other_time {
+            let other = if self.method == Method::Monotone { Method::EarClipping } else { Method::Monotone };
+            ui.text(imgui::im_str!("{}: {:.3} ms", other.name(), time.as_secs_f64() * 1000.0));
+        }
+
+        if ui.button(imgui::im_str!("Close Polygon"), [0.0, 0.0]) {
+            self.close_polygon()?;
+        }
+        ui.same_line(0.0);
+        if ui.button(imgui::im_str!("Clear Polygon"), [0.0, 0.0]) {
+            self.pending.clear();
+            self.pending_shape.clear()?;
+            self.set_polygon(Vec::new())?;
+        }
+        ui.same_line(0.0);
+        if ui.button(imgui::im_str!("Add to Scene"), [0.0, 0.0]) && self.polygon.len() >= 3 {
+            self.scene.borrow_mut().add_polygon(self.polygon.clone());
+        }
+
+        imgui::Slider::new(imgui::im_str!("Vertices"), 3..=1000).build(ui, &mut self.vertex_count);
+        if ui.button(imgui::im_str!("Random Polygon"), [0.0, 0.0]) {
+            // Star-shaped around the origin, with the vertices at increasing angles
+            let mut angles = (0..self.vertex_count).map(|_| rand::random::<f32>() * 2.0 * std::f32::consts::PI).collect::<Vec<_>>();
+            angles.sort_by(|a, b| a.total_cmp(b));
+            let polygon = angles.into_iter()
+                                .map(|angle| {
+                                    let radius = 0.2 + rand::random::<f32>() * 0.6;
+                                    Vec2::new(radius * angle.cos(), radius * angle.sin())
+                                })
+                                .collect();
+            self.set_polygon(polygon)?;
+        }
+
+        if let Some(point) = self.point_input.configure(ui)? {
+            self.add_vertex(point)?;
+        }
+
+        self.exec_time.configure(ui);
+        Ok(())
+    }
+}
+
+impl<'f> PolygonTriangulationRenderer<'f> {
+    pub fn new(facade: &'f dyn Facade, scene: SharedScene) -> Result<Self> {
+        let vs = graphics::SHADERS._2d_vs;
+        let fs = graphics::SHADERS.basic_fs;
+        let program = Program::from_source(facade, vs, fs, None)?;
+
+        Ok(Self {
+            scene,
+            revision: 0,
+            pending: Vec::new(),
+            last_click: None,
+            polygon: Vec::new(),
+            method: Method::EarClipping,
+            triangles: Vec::new(),
+            pieces: Vec::new(),
+            compare: false,
+            other_time: None,
+            program,
+            pending_shape: Shape::new(facade, PrimitiveType::LineStrip, [ 1.0, 0.8, 0.0 ])?,
+            renderer: GliumRenderer::new(facade)?,
+            triangulation: layers::list(),
+            piece_edges: Shape::new(facade, PrimitiveType::LinesList, [ 1.0, 0.5, 0.0 ])?,
+            filled_pieces: ColorMappedShape::new(facade, PrimitiveType::TrianglesList)?,
+            vertex_count: 50,
+            exec_time: ExecTimeHistory::default(),
+            point_input: PointInput::new(facade)?,
+        })
+    }
+
+    fn add_vertex(&mut self, point: Vec2) -> Result<()> {
+        self.pending.push(point);
+        self.pending_shape.set_vertices(&self.pending)
+    }
+
+    /// Replaces the polygon with the one being drawn, if it has at least 3 vertices.
+    fn close_polygon(&mut self) -> Result<()> {
+        if self.pending.len() < 3 {
+            return Ok(());
+        }
+        let polygon = std::mem::take(&mut self.pending);
+        self.pending_shape.clear()?;
+        self.set_polygon(polygon)
+    }
+
+    fn set_polygon(&mut self, polygon: Vec<Vec2>) -> Result<()> {
+        self.polygon = polygon;
+        self.triangulate()
+    }
+
+    /// Triangulates the polygon with the chosen method, times the other one if they are compared, and regenerates the buffers.
+    fn triangulate(&mut self) -> Result<()> {
+        let start_time = Instant::now();
+        let (triangles, pieces) = self.method.triangulate(&self.polygon);
+        if self.polygon.is_empty() {
+            self.exec_time.clear();
+        } else {
+            self.exec_time.push(Instant::now() - start_time);
+        }
+        self.triangles = triangles;
+        self.pieces = pieces;
+
+        self.other_time = if self.compare {
+            let other = if self.method == Method::Monotone { Method::EarClipping } else { Method::Monotone };
+            let start_time = Instant::now();
+            other.triangulate(&self.polygon);
+            Some(Instant::now() - start_time)
+        } else {
+            None
+        };
+
+        self.triangulation = layers::polygon_triangulation(&self.polygon, &self.triangles, self.method == Method::EarClipping);
+
+        // The edges of the pieces between vertices that are not consecutive
+        let n = self.polygon.len();
+        let is_diagonal = |(u, v): (usize, usize)| u < v && v - u != 1 && v - u != n - 1;
+        let piece_edges = self.pieces
+                            .iter()
+                            .flat_map(|piece| (0..piece.len()).map(move |i| (piece[i], piece[(i + 1) % piece.len()])))
+                            .filter(|&edge| is_diagonal(edge))
+                            .flat_map(|(u, v)| vec![ self.polygon[u], self.polygon[v] ])
+                            .collect::<Vec<_>>();
+        let filled = self.triangles.iter().map(|&idx| self.polygon[idx]).collect::<Vec<_>>();
+        self.piece_edges.set_vertices(&piece_edges)?;
+
+        // The triangles of each piece in a faded color, a piece of k vertices has k - 2 triangles following those of the previous pieces
+        let mut colors = Vec::with_capacity(filled.len());
+        for (idx, piece) in self.pieces.iter().enumerate() {
+            let color = graphics::colormap::category(idx);
+            let color = [ color[0] * 0.35, color[1] * 0.35, color[2] * 0.35 ];
+            colors.extend(std::iter::repeat_n(color, 3 * piece.len().saturating_sub(2)));
+        }
+        self.filled_pieces.set_colored_vertices(&filled, &colors)
+    }
+}
@@ -0,0 +1,267 @@
+use crate::{
+    Result,
+    graphics::{ self, Shape, Viewport },
+    math::{ Vec2, Segment2, Circle, Ray2, polygon },
+    scene::SharedScene,
+    ui::{ PointInput, ExecTimeHistory, window::algorithms::{ Drawable, Configurable } },
+};
+
+use std::time::Instant;
+
+use glium::{
+    index::PrimitiveType, Frame, Program,
+    backend::Facade,
+};
+use winit::{
+    event::{ Event, WindowEvent, MouseButton, ElementState },
+    window::Window,
+};
+
+/// Largest distance between a click and a handle of the ray to drag it
+const PICK_RADIUS: f32 = 0.04;
+/// Length of the rays that hit nothing, long enough to cross the viewport
+const RAY_LENGTH: f32 = 10.0;
+/// Number of vertices of the circles drawn around the points
+const CIRCLE_VERTICES: usize = 24;
+
+/// What the ray hits first.
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum Target {
+    Point(usize),
+    Segment(usize),
+    Polygon(usize),
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Handle {
+    Origin,
+    Tip,
+}
+
+pub struct RayCastingRenderer<'f> {
+    /// The ray is cast against the points, segments and polygons of the scene
+    scene: SharedScene,
+    revision: u64,
+    origin: Vec2,
+    /// The ray goes from the origin through the tip
+    tip: Vec2,
+    dragging: Option<Handle>,
+    /// The points are hit as circles of this radius
+    point_radius: f32,
+    /// Target, parameter and position of the first hit
+    hit: Option<(Target, f32, Vec2)>,
+    program: Program,
+    obstacles: Shape<'f>,
+    circles: Shape<'f>,
+    ray: Shape<'f>,
+    handles: Shape<'f>,
+    hit_point: Shape<'f>,
+    exec_time: ExecTimeHistory,
+    point_input: PointInput<'f>,
+}
+
+impl<'f> Drawable for RayCastingRenderer<'f> {
+    fn update(&mut self) -> Result<()> {
+        if self.scene.borrow().revision() != self.revision {
+            self.compute()?;
+        }
+        Ok(())
+    }
+
+    fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        self.point_input.draw(target, viewport)?;
+        self.obstacles.draw(target, &self.program, viewport)?;
+        self.circles.draw(target, &self.program, viewport)?;
+        self.ray.draw(target, &self.program, viewport)?;
+        self.handles.draw(target, &self.program, viewport)?;
+        self.hit_point.draw(target, &self.program, viewport)
+    }
+
+    fn handle_events(&mut self, window: &Window, event: &Event<()>, io: &imgui::Io, viewport: &Viewport) -> Result<()> {
+        if let Event::WindowEvent { event, .. } = event {
+            let coords = viewport.window_pos_to_normalized(io.mouse_pos.into(), window);
+            match event {
+                WindowEvent::MouseInput { button: MouseButton::Left, state: ElementState::Pressed, .. } => {
+                    if !io.want_capture_mouse { // Ignore clicks when the cursor is over an ImGui window
+                        // Drag an end of the ray, or add a point
+                        self.dragging = if (&coords - &self.origin).length() < PICK_RADIUS {
+                            Some(Handle::Origin)
+                        } else if (&coords - &self.tip).length() < PICK_RADIUS {
+                            Some(Handle::Tip)
+                        } else {
+                            None
+                        };
+                        if self.dragging.is_none() {
+                            self.scene.borrow_mut().add_point(self.point_input.place(coords));
+                        }
+                    }
+                },
+                WindowEvent::MouseInput { button: MouseButton::Left, state: ElementState::Released, .. } => {
+                    self.dragging = None;
+                },
+                WindowEvent::CursorMoved { .. } => {
+                    if let Some(handle) = self.dragging {
+                        let coords = self.point_input.place(coords);
+                        match handle {
+                            Handle::Origin => self.origin = coords,
+                            Handle::Tip => self.tip = coords,
+                        }
+                        self.compute()?;
+                    }
+                },
+                _ => {},
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'f> Configurable for RayCastingRenderer<'f> {
+    fn name(&self) -> &'static str {
+        "Ray casting"
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        Some("Casts a ray against the geometry of the scene and stops it at the first hit. \
+              The ray is the half-line of the points origin + t * direction with t >= 0: \
+              a segment is hit where the two parametric equations agree, a polygon at the closest hit of its edges, \
+              and a circle at the smallest non-negative root of a quadratic equation. \
+              The points of the scene are hit as small circles. \
+              Drag the origin or the tip of the ray to aim it, left click to add points.")
+    }
+
+    fn pseudo_code(&self) -> Option<&'static str> {
+        Some(concat!(
+            "closest = infinity\n",
+            "for each segment [a, b]:\n",
+            "    solve o + t d = a + u (b - a)\n",
+            "    if t >= 0 and 0 <= u <= 1:\n",
+            "        closest = min(closest, t)\n",
+            "for each circle (c, r):\n",
+            "    solve |o + t d - c|² = r²\n",
+            "    closest = min(closest, smallest t >= 0)\n",
+            "the first hit is o + closest d",
+        ))
+    }
+
+    fn complexity(&self) -> Option<&'static str> {
+        Some("O(n) for n points and edges")
+    }
+
+    fn configure(&mut self, ui: &imgui::Ui) -> Result<()> {
+        match self.hit {
+            Some((target, t, p)) => {
+                let target = match target {
+                    Target::Point(idx) => format!("point {}", idx),
+                    Target::Segment(idx) => format!("segment {}", idx),
+                    Target::Polygon(idx) => format!("polygon {}", idx),
+                };
+                let distance = t * (&self.tip - &self.origin).length();
+                ui.text(imgui::im_str!("Hits {} at ({:.3}, {:.3}), at a distance of {:.3}", target, p.x, p.y, distance));
+            },
+            None => ui.text(imgui::im_str!("Hits nothing")),
+        }
+
+        if ui.input_float(imgui::im_str!("Point radius"), &mut self.point_radius).step(0.01).build() {
+            self.point_radius = crate::math::clamp(self.point_radius, 0.0, 0.5);
+            self.compute()?;
+        }
+
+        if let Some(point) = self.point_input.configure(ui)? {
+            self.scene.borrow_mut().add_point(point);
+        }
+
+        self.exec_time.configure(ui);
+        Ok(())
+    }
+}
+
+impl<'f> RayCastingRenderer<'f> {
+    pub fn new(facade: &'f dyn Facade, scene: SharedScene) -> Result<Self> {
+        let vs = graphics::SHADERS._2d_vs;
+        let fs = graphics::SHADERS.basic_fs;
+        let program = Program::from_source(facade, vs, fs, None)?;
+
+        let mut obstacles = Shape::new(facade, PrimitiveType::LinesList, [ 1.0, 1.0, 1.0 ])?;
+        obstacles.size = 2.0;
+        let mut ray = Shape::new(facade, PrimitiveType::LinesList, [ 1.0, 0.9, 0.0 ])?;
+        ray.size = 2.0;
+        let mut handles = Shape::new(facade, PrimitiveType::Points, [ 1.0, 0.9, 0.0 ])?;
+        handles.size = 12.0;
+        let mut hit_point = Shape::new(facade, PrimitiveType::Points, [ 1.0, 0.2, 0.2 ])?;
+        hit_point.size = 10.0;
+
+        let mut renderer = Self {
+            scene,
+            revision: 0,
+            origin: Vec2::new(-0.5, 0.0),
+            tip: Vec2::new(-0.25, 0.0),
+            dragging: None,
+            point_radius: 0.02,
+            hit: None,
+            program,
+            obstacles,
+            circles: Shape::new(facade, PrimitiveType::LinesList, [ 0.6, 0.6, 0.6 ])?,
+            ray,
+            handles,
+            hit_point,
+            exec_time: ExecTimeHistory::default(),
+            point_input: PointInput::new(facade)?,
+        };
+        // The ray is drawn before the scene changes
+        renderer.compute()?;
+        Ok(renderer)
+    }
+
+    /// Casts the ray against the scene and regenerates the buffers.
+    fn compute(&mut self) -> Result<()> {
+        let scene = self.scene.borrow();
+        self.revision = scene.revision();
+        let (points, segments, polygons) = (scene.points(), scene.segments(), scene.polygons());
+        let ray = Ray2::through(self.origin, self.tip);
+
+        let start_time = Instant::now();
+        let circles = points.iter().map(|&p| Circle::new(p, self.point_radius)).collect::<Vec<_>>();
+        let hits = circles
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, circle)| ray.intersect_circle(circle).map(|t| (Target::Point(idx), t)))
+            .chain(segments.iter().enumerate().filter_map(|(idx, s)| ray.intersect_segment(s).map(|t| (Target::Segment(idx), t))))
+            .chain(polygons.iter().enumerate().filter_map(|(idx, p)| ray.intersect_polygon(p).map(|t| (Target::Polygon(idx), t))));
+        self.hit = hits
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(target, t)| (target, t, ray.at(t)));
+        self.exec_time.push(Instant::now() - start_time);
+
+        let edges = segments
+            .iter()
+            .copied()
+            .chain(polygons.iter().flat_map(|p| polygon::edges(p).map(|(a, b)| Segment2::new(a, b))))
+            .flat_map(|s| vec![ s.a, s.b ])
+            .collect::<Vec<_>>();
+        self.obstacles.set_vertices(&edges)?;
+        drop(scene);
+
+        let outlines = circles
+            .iter()
+            .flat_map(|circle| {
+                let outline = circle.points(CIRCLE_VERTICES).collect::<Vec<_>>();
+                polygon::edges(&outline).flat_map(|(a, b)| vec![ a, b ]).collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        self.circles.set_vertices(&outlines)?;
+
+        // The ray stops at the first hit
+        let end = match self.hit {
+            Some((_, _, p)) => p,
+            None if self.origin == self.tip => self.tip,
+            None => &self.origin + &(RAY_LENGTH * &ray.direction.normalized()),
+        };
+        self.ray.set_vertices(&[ self.origin, end ])?;
+        self.handles.set_vertices(&[ self.origin, self.tip ])?;
+        match self.hit {
+            Some((_, _, p)) => self.hit_point.set_vertices(&[ p ]),
+            None => self.hit_point.clear(),
+        }
+    }
+}
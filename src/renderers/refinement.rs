@@ -0,0 +1,395 @@
+use crate::{
+    Result,
+    algorithms::{ refine, laplacian_smoothing, angle_based_smoothing, triangle_qualities, histogram, Refinement, SizeField, TriangleQuality },
+    graphics::{ self, Shape, ColorMappedShape, Viewport },
+    math::{ Vec2, Rect, Circle },
+    render::{ layers, DrawList, GliumRenderer },
+    scene::SharedScene,
+    task::Task,
+    ui::{ PointInput, ExecTimeHistory, window::algorithms::{ Drawable, Configurable } },
+};
+
+use std::time::{ Duration, Instant };
+
+use glium::{
+    index::PrimitiveType, Frame, Program,
+    backend::Facade,
+};
+use winit::{
+    event::{ Event, WindowEvent, MouseButton, ElementState },
+    window::Window,
+};
+
+/// Nodes along each side of the background grid, which covers the [-1, 1] square
+const FIELD_RESOLUTION: usize = 64;
+/// Fraction of the way to the brush length covered by each cursor move
+const BRUSH_STRENGTH: f32 = 0.3;
+/// Number of segments of the outline of the brush
+const BRUSH_SEGMENTS: usize = 48;
+/// Largest number of smoothing passes
+const MAX_SMOOTHING_ITERATIONS: i32 = 50;
+/// Number of bins of the histogram of the smallest angles
+const HISTOGRAM_BINS: usize = 30;
+
+/// Smoothing applied to the refined mesh.
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum Smoothing {
+    None,
+    Laplacian,
+    AngleBased,
+}
+
+pub struct RefinementRenderer<'f> {
+    /// The input points are those of the scene
+    scene: SharedScene,
+    revision: u64,
+    points: Vec<Vec2>,
+    field: SizeField,
+    /// Length of the field before painting, restored by the right button
+    default_size: f32,
+    /// Length painted by the left button, and radius of the brush
+    brush_size: f32,
+    brush_radius: f32,
+    /// When painting, left clicks paint the field instead of adding points
+    painting: bool,
+    /// Mouse button held down while painting
+    stroke: Option<MouseButton>,
+    max_points: i32,
+    refinement: Option<Refinement>,
+    smoothing: Smoothing,
+    smoothing_iterations: i32,
+    /// Positions of the refined points after smoothing, and the quality of the triangles
+    smoothed: Vec<Vec2>,
+    qualities: Vec<TriangleQuality>,
+    show_field: bool,
+    program: Program,
+    field_overlay: ColorMappedShape<'f>,
+    renderer: GliumRenderer<'f>,
+    /// Edges of the refined mesh, inserted points and input points
+    mesh: DrawList,
+    brush: Shape<'f>,
+    exec_time: ExecTimeHistory,
+    point_input: PointInput<'f>,
+    /// Refinement running in the background
+    task: Option<Task<Refined>>,
+}
+
+/// Result of a refinement computed on a worker thread.
+struct Refined {
+    refinement: Refinement,
+    exec_time: Duration,
+}
+
+impl<'f> Drawable for RefinementRenderer<'f> {
+    fn update(&mut self) -> Result<()> {
+        if self.scene.borrow().revision() != self.revision {
+            self.read_points()?;
+        }
+
+        let result = match &self.task {
+            Some(task) => task.poll(),
+            None => None,
+        };
+        if let Some(refined) = result {
+            self.task = None;
+            self.exec_time.push(refined.exec_time);
+            self.set_refinement(refined.refinement)?;
+        }
+        Ok(())
+    }
+
+    fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        self.point_input.draw(target, viewport)?;
+        if self.show_field {
+            self.field_overlay.draw(target, viewport)?;
+        }
+        self.renderer.draw_in(&self.mesh, target, viewport.rect(target))?;
+        if self.painting {
+            self.brush.draw(target, &self.program, viewport)?;
+        }
+        Ok(())
+    }
+
+    fn handle_events(&mut self, window: &Window, event: &Event<()>, io: &imgui::Io, viewport: &Viewport) -> Result<()> {
+        if let Event::WindowEvent { event, .. } = event {
+            let coords = viewport.window_pos_to_normalized(io.mouse_pos.into(), window);
+            match event {
+                WindowEvent::MouseInput { button, state: ElementState::Pressed, .. } => {
+                    if !io.want_capture_mouse { // Ignore clicks when the cursor is over an ImGui window
+                        match button {
+                            MouseButton::Left | MouseButton::Right if self.painting => {
+                                self.stroke = Some(*button);
+                                self.paint(coords)?;
+                            },
+                            MouseButton::Left => {
+                                // Add a point on click
+                                self.scene.borrow_mut().add_point(self.point_input.place(coords));
+                            },
+                            _ => {},
+                        }
+                    }
+                },
+                WindowEvent::MouseInput { state: ElementState::Released, .. } => {
+                    // The mesh follows the field once the stroke is over
+                    if self.stroke.take().is_some() {
+                        self.start_refinement();
+                    }
+                },
+                WindowEvent::CursorMoved { .. } => {
+                    let outline = Circle::new(coords, self.brush_radius).points(BRUSH_SEGMENTS).collect::<Vec<_>>();
+                    self.brush.set_vertices(&outline)?;
+                    if self.stroke.is_some() {
+                        self.paint(coords)?;
+                    }
+                },
+                _ => {},
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'f> Configurable for RefinementRenderer<'f> {
+    fn name(&self) -> &'static str {
+        "Delaunay refinement"
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        Some("Refines the Delaunay triangulation of the points of the scene until every triangle is small enough \
+              for the target edge length around it. The lengths are stored on a background grid and painted with a brush: \
+              the left button paints the brush length, the right button restores the default one. \
+              Each round inserts the circumcenters of the triangles whose longest edge is too long for the length at their centroid, \
+              or their centroid when the circumcenter is out of the hull, then triangulates again. \
+              Small lengths give dense regions that grade smoothly into coarse ones. \
+              The refined mesh can then be smoothed: the Laplacian smoothing moves each interior point to the centroid of its neighbours, \
+              the angle-based smoothing moves it to even out the angles around its neighbours. \
+              The field goes from dark green for the smallest lengths to white for the largest.")
+    }
+
+    fn pseudo_code(&self) -> Option<&'static str> {
+        Some(concat!(
+            "T = Delaunay triangulation of the points\n",
+            "repeat:\n",
+            "    for each triangle t of T, largest first:\n",
+            "        if longest edge of t > size(centroid of t):\n",
+            "            p = circumcenter of t, or its centroid\n",
+            "                if it is out of the hull\n",
+            "            if p is far enough from the new points:\n",
+            "                insert p\n",
+            "    if nothing was inserted: stop\n",
+            "    T = Delaunay triangulation of the points",
+        ))
+    }
+
+    fn complexity(&self) -> Option<&'static str> {
+        Some("One triangulation of the n points per round, and one query of the grid per triangle. Each smoothing pass is O(n)")
+    }
+
+    fn configure(&mut self, ui: &imgui::Ui) -> Result<()> {
+        if let Some(refinement) = &self.refinement {
+            ui.text(imgui::im_str!("{} input points, {} inserted in {} rounds, {} triangles",
+                                   self.points.len(), refinement.points.len().saturating_sub(self.points.len()),
+                                   refinement.rounds, refinement.indices.len() / 3));
+        }
+
+        ui.checkbox(imgui::im_str!("Paint the field"), &mut self.painting);
+        imgui::Slider::new(imgui::im_str!("Brush length"), 0.01..=0.5).build(ui, &mut self.brush_size);
+        imgui::Slider::new(imgui::im_str!("Brush radius"), 0.02..=1.0).build(ui, &mut self.brush_radius);
+        if imgui::Slider::new(imgui::im_str!("Default length"), 0.05..=1.0).build(ui, &mut self.default_size) {
+            self.reset_field()?;
+        }
+        ui.input_int(imgui::im_str!("Max points"), &mut self.max_points).step(1000).build();
+        self.max_points = self.max_points.max(3);
+        ui.checkbox(imgui::im_str!("Field"), &mut self.show_field);
+        self.configure_smoothing(ui)?;
+
+        if ui.button(imgui::im_str!("Refine"), [0.0, 0.0]) {
+            self.start_refinement();
+        }
+        ui.same_line(0.0);
+        if ui.button(imgui::im_str!("Reset Field"), [0.0, 0.0]) {
+            self.reset_field()?;
+        }
+        ui.same_line(0.0);
+        if ui.button(imgui::im_str!("Square"), [0.0, 0.0]) {
+            let corners = [ Vec2::new(-0.8, -0.8), Vec2::new(0.8, -0.8), Vec2::new(0.8, 0.8), Vec2::new(-0.8, 0.8) ];
+            self.scene.borrow_mut().add_points(corners.iter().cloned());
+        }
+        ui.same_line(0.0);
+        if ui.button(imgui::im_str!("Clear Points"), [0.0, 0.0]) {
+            self.scene.borrow_mut().clear_points();
+        }
+
+        let mut cancel = false;
+        if let Some(task) = &self.task {
+            imgui::ProgressBar::new(task.progress().fraction()).build(ui);
+            cancel = ui.button(imgui::im_str!("Cancel"), [0.0, 0.0]);
+        }
+        if cancel {
+            self.task = None; // Dropping the task cancels it
+        }
+
+        if let Some(point) = self.point_input.configure(ui)? {
+            self.scene.borrow_mut().add_point(point);
+        }
+
+        self.exec_time.configure(ui);
+        Ok(())
+    }
+}
+
+impl<'f> RefinementRenderer<'f> {
+    pub fn new(facade: &'f dyn Facade, scene: SharedScene) -> Result<Self> {
+        let vs = graphics::SHADERS._2d_vs;
+        let fs = graphics::SHADERS.basic_fs;
+        let program = Program::from_source(facade, vs, fs, None)?;
+
+        let mut brush = Shape::new(facade, PrimitiveType::LineLoop, [ 1.0, 1.0, 1.0 ])?;
+        brush.size = 1.5;
+
+        let default_size = 0.3;
+        let bounds = Rect::new(Vec2::new(-1.0, -1.0), Vec2::new(1.0, 1.0));
+        let mut renderer = Self {
+            scene,
+            revision: 0,
+            points: Vec::new(),
+            field: SizeField::uniform(bounds, FIELD_RESOLUTION, default_size).expect("Empty size field"),
+            default_size,
+            brush_size: 0.03,
+            brush_radius: 0.2,
+            painting: true,
+            stroke: None,
+            max_points: 20000,
+            refinement: None,
+            smoothing: Smoothing::None,
+            smoothing_iterations: 5,
+            smoothed: Vec::new(),
+            qualities: Vec::new(),
+            show_field: true,
+            program,
+            field_overlay: ColorMappedShape::new(facade, PrimitiveType::TrianglesList)?,
+            renderer: GliumRenderer::new(facade)?,
+            mesh: layers::list(),
+            brush,
+            exec_time: ExecTimeHistory::default(),
+            point_input: PointInput::new(facade)?,
+            task: None,
+        };
+        renderer.update_field()?;
+        Ok(renderer)
+    }
+
+    /// Copies the points of the scene and refines their triangulation.
+    fn read_points(&mut self) -> Result<()> {
+        let scene = self.scene.borrow();
+        self.revision = scene.revision();
+        self.points = scene.points().to_vec();
+        drop(scene);
+
+        self.mesh = layers::points(&self.points);
+        self.start_refinement();
+        Ok(())
+    }
+
+    /// Paints the field under the brush with the length of the button held down.
+    fn paint(&mut self, center: Vec2) -> Result<()> {
+        let size = match self.stroke {
+            Some(MouseButton::Right) => self.default_size,
+            _ => self.brush_size,
+        };
+        self.field.paint(center, self.brush_radius, size, BRUSH_STRENGTH);
+        self.update_field()
+    }
+
+    /// Sets the whole field back to the default length.
+    fn reset_field(&mut self) -> Result<()> {
+        for value in &mut self.field.values {
+            *value = self.default_size;
+        }
+        self.update_field()?;
+        self.start_refinement();
+        Ok(())
+    }
+
+    /// Regenerates the buffer of the field, two triangles per cell.
+    fn update_field(&mut self) -> Result<()> {
+        let field = &self.field;
+        let (mut vertices, mut values) = (Vec::new(), Vec::new());
+        for y in 0..field.height - 1 {
+            for x in 0..field.width - 1 {
+                for &(dx, dy) in &[ (0, 0), (1, 0), (1, 1), (0, 0), (1, 1), (0, 1) ] {
+                    vertices.push(field.position(x + dx, y + dy));
+                    values.push(field.value(x + dx, y + dy));
+                }
+            }
+        }
+        let max = field.values.iter().cloned().fold(self.default_size, f32::max);
+        self.field_overlay.set_vertices(&vertices, &values, (0.0, max))
+    }
+
+    /// Refines the triangulation on a worker thread, cancelling the previous computation if it is still running.
+    /// The result is retrieved in [`update()`](#method.update).
+    fn start_refinement(&mut self) {
+        let (points, field, max_points) = (self.points.clone(), self.field.clone(), self.max_points as usize);
+
+        self.task = Some(Task::spawn(move |progress| {
+            let start_time = Instant::now();
+            let refinement = refine(&points, &field, max_points, progress)?;
+            Some(Refined {
+                refinement,
+                exec_time: Instant::now() - start_time,
+            })
+        }));
+    }
+
+    /// Replaces the displayed mesh with the result of a worker thread.
+    fn set_refinement(&mut self, refinement: Refinement) -> Result<()> {
+        self.refinement = Some(refinement);
+        self.update_smoothing()
+    }
+
+    fn configure_smoothing(&mut self, ui: &imgui::Ui) -> Result<()> {
+        let mut changed = ui.radio_button(imgui::im_str!("No smoothing"), &mut self.smoothing, Smoothing::None);
+        ui.same_line(0.0);
+        changed |= ui.radio_button(imgui::im_str!("Laplacian"), &mut self.smoothing, Smoothing::Laplacian);
+        ui.same_line(0.0);
+        changed |= ui.radio_button(imgui::im_str!("Angle-based"), &mut self.smoothing, Smoothing::AngleBased);
+        changed |= imgui::Slider::new(imgui::im_str!("Iterations"), 1..=MAX_SMOOTHING_ITERATIONS).build(ui, &mut self.smoothing_iterations);
+        if changed {
+            self.update_smoothing()?;
+        }
+
+        // Smallest angles in degrees, from the worst triangles to the best ones
+        let angles = self.qualities.iter().map(|quality| quality.min_angle.to_degrees()).collect::<Vec<_>>();
+        if let Some((min, _)) = graphics::colormap::range(&angles) {
+            let mean = angles.iter().sum::<f32>() / angles.len() as f32;
+            ui.text(imgui::im_str!("Smallest angle: {:.2}°, mean: {:.2}°", min, mean));
+            let counts = histogram(&angles, HISTOGRAM_BINS, (0.0, 60.0))
+                            .into_iter()
+                            .map(|count| count as f32)
+                            .collect::<Vec<_>>();
+            ui.plot_histogram(imgui::im_str!("##angle_histogram"), &counts)
+                .scale_min(0.0)
+                .graph_size([0.0, 60.0])
+                .build();
+        }
+        Ok(())
+    }
+
+    /// Smooths the refined mesh and regenerates its draw list and the quality of its triangles.
+    fn update_smoothing(&mut self) -> Result<()> {
+        let refinement = match &self.refinement {
+            Some(refinement) => refinement,
+            None => return Ok(()),
+        };
+        let iterations = self.smoothing_iterations.max(0) as usize;
+        self.smoothed = match self.smoothing {
+            Smoothing::None => refinement.points.clone(),
+            Smoothing::Laplacian => laplacian_smoothing(&refinement.points, &refinement.indices, iterations),
+            Smoothing::AngleBased => angle_based_smoothing(&refinement.points, &refinement.indices, iterations),
+        };
+        self.qualities = triangle_qualities(&self.smoothed, &refinement.indices);
+        self.mesh = layers::refinement(&self.points, &self.smoothed, &refinement.indices);
+        Ok(())
+    }
+}
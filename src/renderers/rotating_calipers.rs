@@ -0,0 +1,282 @@
+use crate::{
+    Result,
+    algorithms::{ RotatingCalipers, Caliper, Degeneracy, primitive_vertices },
+    graphics::{ self, Shape, Viewport },
+    math::Vec2,
+    scene::SharedScene,
+    ui::{ PointInput, ExecTimeHistory, window::algorithms::{ Drawable, Configurable } },
+};
+
+use std::time::{ Duration, Instant };
+
+use glium::{
+    index::PrimitiveType, Frame, Program,
+    backend::Facade,
+};
+use winit::{
+    event::{ Event, WindowEvent, MouseButton, ElementState },
+    window::Window,
+};
+
+/// Length of the caliper lines, long enough to cross the viewport
+const LINE_LENGTH: f32 = 10.0;
+
+pub struct RotatingCalipersRenderer<'f> {
+    alg: RotatingCalipers,
+    /// The input points are the vertices of the geometry of the scene
+    scene: SharedScene,
+    /// Revision of the scene the calipers were computed from
+    revision: u64,
+    program: Program,
+    points: Shape<'f>,
+    hull: Shape<'f>,
+    lines: Shape<'f>,
+    /// The antipodal pairs of the current position
+    pairs: Shape<'f>,
+    rectangle: Shape<'f>,
+    diameter: Shape<'f>,
+    /// Index of the displayed position of the calipers, the edge of the hull they are flush with
+    position: usize,
+    /// The calipers move to the next edge every `interval` while playing
+    playing: bool,
+    interval: f32,
+    last_move: Instant,
+    show_rectangle: bool,
+    show_diameter: bool,
+    exec_time: ExecTimeHistory,
+    point_input: PointInput<'f>,
+}
+
+impl<'f> Drawable for RotatingCalipersRenderer<'f> {
+    fn update(&mut self) -> Result<()> {
+        if self.scene.borrow().revision() != self.revision {
+            self.compute()?;
+        }
+
+        if self.playing && Instant::now() - self.last_move >= Duration::from_secs_f32(self.interval) {
+            self.move_to(self.position + 1)?;
+        }
+        Ok(())
+    }
+
+    fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        self.point_input.draw(target, viewport)?;
+        if self.show_rectangle {
+            self.rectangle.draw(target, &self.program, viewport)?;
+        }
+        if self.show_diameter {
+            self.diameter.draw(target, &self.program, viewport)?;
+        }
+        self.hull.draw(target, &self.program, viewport)?;
+        self.lines.draw(target, &self.program, viewport)?;
+        self.pairs.draw(target, &self.program, viewport)?;
+        self.points.draw(target, &self.program, viewport)
+    }
+
+    fn handle_events(&mut self, window: &Window, event: &Event<()>, io: &imgui::Io, viewport: &Viewport) -> Result<()> {
+        if let Event::WindowEvent { event, .. } = event {
+            if let WindowEvent::MouseInput { button, state, .. } = event {
+                if !io.want_capture_mouse && // Ignore clicks when the cursor is over an ImGui window
+                    button == &MouseButton::Left && state == &ElementState::Pressed {
+
+                    // Add a point on click
+                    let coords = viewport.window_pos_to_normalized(io.mouse_pos.into(), window);
+                    self.scene.borrow_mut().add_point(self.point_input.place(coords));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'f> Configurable for RotatingCalipersRenderer<'f> {
+    fn name(&self) -> &'static str {
+        "Rotating calipers"
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        Some("Two parallel lines are rotated around the convex hull, each one touching it at a vertex. \
+              At each step a line becomes flush with an edge, and the opposite line touches the vertex \
+              the farthest from it: the pairs of vertices touched at the same time are antipodal. \
+              The support function h(u) is the distance from the origin to the line of outward normal u, \
+              the width of the hull in that direction is h(u) + h(-u). Every line only moves forward, \
+              so the width, the diameter and the minimum-area enclosing rectangle are found in one turn.")
+    }
+
+    fn pseudo_code(&self) -> Option<&'static str> {
+        Some(concat!(
+            "hull = convex hull of the points\n",
+            "j = 1\n",
+            "for each edge (p[i], p[i + 1]) of hull:\n",
+            "    u = outward normal of the edge\n",
+            "    while p[j + 1] is farther from the edge\n",
+            "          than p[j]:\n",
+            "        j = j + 1\n",
+            "    (p[i], p[j]) and (p[i + 1], p[j]) are antipodal\n",
+            "    width(u) = h(u) + h(-u)\n",
+            "             = distance from p[j] to the edge",
+        ))
+    }
+
+    fn complexity(&self) -> Option<&'static str> {
+        Some("O(n log n) for the hull, then O(h) for the calipers")
+    }
+
+    fn degeneracies(&self) -> &'static [Degeneracy] {
+        &[ Degeneracy::CollinearPoints ]
+    }
+
+    fn configure(&mut self, ui: &imgui::Ui) -> Result<()> {
+        ui.text(imgui::im_str!("{} vertices, {} on the hull", self.alg.points().len(), self.alg.hull().len()));
+        ui.text(imgui::im_str!("{} antipodal pairs", self.alg.antipodal_pairs().len()));
+        if let Some(caliper) = self.caliper() {
+            let n = self.alg.hull().len();
+            ui.text(imgui::im_str!("Edge {} -> {}, antipode {}", caliper.edge, (caliper.edge + 1) % n, caliper.antipode));
+            ui.text(imgui::im_str!("h(u) = {:.4} for u = ({:.3}, {:.3})", caliper.support, caliper.normal.x, caliper.normal.y));
+            ui.text(imgui::im_str!("Width: {:.4}", caliper.width));
+            ui.text(imgui::im_str!("Rectangle area: {:.4}", caliper.area()));
+        }
+        if let (Some(width), Some(area)) = (self.alg.minimum_width(), self.alg.minimum_area()) {
+            ui.text(imgui::im_str!("Minimum width: {:.4} at edge {}", width.width, width.edge));
+            ui.text(imgui::im_str!("Minimum area: {:.4} at edge {}", area.area(), area.edge));
+        }
+        if let Some((_, length)) = self.alg.diameter() {
+            ui.text(imgui::im_str!("Diameter: {:.4}", length));
+        }
+
+        if ui.button(imgui::im_str!("Previous"), [0.0, 0.0]) {
+            self.move_to(self.position + self.alg.calipers().len().max(1) - 1)?;
+        }
+        ui.same_line(0.0);
+        let label = if self.playing { imgui::im_str!("Pause") } else { imgui::im_str!("Play") };
+        if ui.button(label, [0.0, 0.0]) {
+            self.playing = !self.playing;
+        }
+        ui.same_line(0.0);
+        if ui.button(imgui::im_str!("Next"), [0.0, 0.0]) {
+            self.move_to(self.position + 1)?;
+        }
+        imgui::Slider::new(imgui::im_str!("Step duration (s)"), 0.05..=2.0).build(ui, &mut self.interval);
+
+        if ui.button(imgui::im_str!("Minimum width"), [0.0, 0.0]) {
+            if let Some(edge) = self.alg.minimum_width().map(|caliper| caliper.edge) {
+                self.move_to(edge)?;
+            }
+        }
+        ui.same_line(0.0);
+        if ui.button(imgui::im_str!("Minimum area"), [0.0, 0.0]) {
+            if let Some(edge) = self.alg.minimum_area().map(|caliper| caliper.edge) {
+                self.move_to(edge)?;
+            }
+        }
+
+        ui.checkbox(imgui::im_str!("Enclosing rectangle"), &mut self.show_rectangle);
+        ui.checkbox(imgui::im_str!("Diameter"), &mut self.show_diameter);
+
+        if ui.button(imgui::im_str!("Clear Points"), [0.0, 0.0]) {
+            self.scene.borrow_mut().clear_points();
+        }
+
+        if let Some(point) = self.point_input.configure(ui)? {
+            self.scene.borrow_mut().add_point(point);
+        }
+
+        self.exec_time.configure(ui);
+        Ok(())
+    }
+}
+
+impl<'f> RotatingCalipersRenderer<'f> {
+    pub fn new(facade: &'f dyn Facade, scene: SharedScene) -> Result<Self> {
+        let vs = graphics::SHADERS._2d_vs;
+        let fs = graphics::SHADERS.basic_fs;
+        let program = Program::from_source(facade, vs, fs, None)?;
+
+        Ok(Self {
+            alg: RotatingCalipers::new(),
+            scene,
+            revision: 0,
+            program,
+            points: Shape::new(facade, PrimitiveType::Points, [ 1.0, 1.0, 1.0 ])?,
+            hull: Shape::new(facade, PrimitiveType::LineLoop, [ 0.0, 0.2, 1.0 ])?,
+            lines: Shape::new(facade, PrimitiveType::LinesList, [ 1.0, 0.8, 0.0 ])?,
+            pairs: Shape::new(facade, PrimitiveType::LinesList, [ 1.0, 0.0, 0.0 ])?,
+            rectangle: Shape::new(facade, PrimitiveType::LineLoop, [ 0.5, 0.5, 0.5 ])?,
+            diameter: Shape::new(facade, PrimitiveType::LinesList, [ 0.0, 0.8, 0.0 ])?,
+            position: 0,
+            playing: false,
+            interval: 0.5,
+            last_move: Instant::now(),
+            show_rectangle: true,
+            show_diameter: false,
+            exec_time: ExecTimeHistory::default(),
+            point_input: PointInput::new(facade)?,
+        })
+    }
+
+    /// Returns the displayed position of the calipers.
+    fn caliper(&self) -> Option<&Caliper> {
+        self.alg.calipers().get(self.position)
+    }
+
+    /// Rotates the calipers around the hull of the geometry of the scene and regenerates the buffers.
+    fn compute(&mut self) -> Result<()> {
+        let scene = self.scene.borrow();
+        self.revision = scene.revision();
+
+        let start_time = Instant::now();
+        self.alg.set_points(primitive_vertices(&scene.primitives()));
+        drop(scene);
+        if self.alg.points().is_empty() {
+            self.exec_time.clear();
+        } else {
+            self.exec_time.push(Instant::now() - start_time);
+        }
+
+        let points = self.alg.points();
+        self.points.set_vertices(points)?;
+        self.hull.set_vertices(&self.alg.hull().iter().map(|&idx| points[idx]).collect::<Vec<_>>())?;
+        match self.alg.diameter() {
+            Some(((a, b), _)) => self.diameter.set_vertices(&[ self.vertex(a), self.vertex(b) ])?,
+            None => self.diameter.clear()?,
+        }
+        self.move_to(self.position)
+    }
+
+    /// Returns the vertex at position `pos` in the hull.
+    fn vertex(&self, pos: usize) -> Vec2 {
+        self.alg.points()[self.alg.hull()[pos]]
+    }
+
+    /// Displays the calipers flush with the edge `position`, wrapping around the hull.
+    fn move_to(&mut self, position: usize) -> Result<()> {
+        self.last_move = Instant::now();
+        let count = self.alg.calipers().len();
+        self.position = if count == 0 { 0 } else { position % count };
+
+        let caliper = match self.caliper() {
+            Some(caliper) => *caliper,
+            None => {
+                self.lines.clear()?;
+                self.pairs.clear()?;
+                return self.rectangle.clear();
+            },
+        };
+
+        // Both lines are orthogonal to the normal, one through the edge and one through the antipode
+        let direction = &Vec2::new(-caliper.normal.y, caliper.normal.x) * LINE_LENGTH;
+        let line = |p: Vec2| vec![ &p - &direction, &p + &direction ];
+        let (a, b) = (caliper.edge, (caliper.edge + 1) % self.alg.hull().len());
+        let antipode = self.vertex(caliper.antipode);
+        self.lines.set_vertices(&[ line(self.vertex(a)), line(antipode) ].concat())?;
+
+        let pairs = self.alg.antipodal_pairs()
+                                .iter()
+                                .filter(|&&(p, q)| (p == a || p == b || q == a || q == b) &&
+                                                   (p == caliper.antipode || q == caliper.antipode))
+                                .flat_map(|&(p, q)| vec![ self.vertex(p), self.vertex(q) ])
+                                .collect::<Vec<_>>();
+        self.pairs.set_vertices(&pairs)?;
+        self.rectangle.set_vertices(&caliper.rectangle)
+    }
+}
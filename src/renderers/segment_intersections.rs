@@ -0,0 +1,224 @@
+use crate::{
+    Result,
+    algorithms::{ SegmentIntersection, Degeneracy, bentley_ottmann },
+    graphics::{ self, Shape, Viewport },
+    math::{ Vec2, Segment2 },
+    scene::SharedScene,
+    ui::{ PointInput, ExecTimeHistory, window::algorithms::{ Drawable, Configurable } },
+};
+
+use std::time::{ Duration, Instant };
+
+use glium::{
+    index::PrimitiveType, Frame, Program,
+    backend::Facade,
+};
+use winit::{
+    event::{ Event, WindowEvent, MouseButton, ElementState },
+    window::Window,
+};
+
+pub struct SegmentIntersectionsRenderer<'f> {
+    /// The input segments are those of the scene
+    scene: SharedScene,
+    /// Revision of the scene the intersections were computed from
+    revision: u64,
+    intersections: Vec<SegmentIntersection>,
+    /// Number of pairs of segments meeting, and time taken by testing every pair instead
+    brute_force: Option<(usize, Duration)>,
+    program: Program,
+    segments: Shape<'f>,
+    points: Shape<'f>,
+    /// First end of the segment being drawn
+    start: Shape<'f>,
+    pending: Option<Vec2>,
+    compare: bool,
+    random_count: i32,
+    exec_time: ExecTimeHistory,
+    point_input: PointInput<'f>,
+}
+
+impl<'f> Drawable for SegmentIntersectionsRenderer<'f> {
+    fn update(&mut self) -> Result<()> {
+        if self.scene.borrow().revision() != self.revision {
+            self.compute()?;
+        }
+        Ok(())
+    }
+
+    fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        self.point_input.draw(target, viewport)?;
+        self.segments.draw(target, &self.program, viewport)?;
+        self.points.draw(target, &self.program, viewport)?;
+        self.start.draw(target, &self.program, viewport)
+    }
+
+    fn handle_events(&mut self, window: &Window, event: &Event<()>, io: &imgui::Io, viewport: &Viewport) -> Result<()> {
+        if let Event::WindowEvent { event, .. } = event {
+            if let WindowEvent::MouseInput { button, state, .. } = event {
+                if !io.want_capture_mouse && // Ignore clicks when the cursor is over an ImGui window
+                    button == &MouseButton::Left && state == &ElementState::Pressed {
+
+                    // The first click starts a segment, the second one ends it
+                    let coords = viewport.window_pos_to_normalized(io.mouse_pos.into(), window);
+                    self.add_end(self.point_input.place(coords))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'f> Configurable for SegmentIntersectionsRenderer<'f> {
+    fn name(&self) -> &'static str {
+        "Segment intersections"
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        Some("The Bentley-Ottmann algorithm sweeps a vertical line over the segments from left to right. \
+              The event queue holds the ends of the segments and the intersections found so far, sorted by x. \
+              The status holds the segments crossing the sweep line, sorted from bottom to top: \
+              two segments must be neighbours in the status just before they intersect, \
+              so each event only tests the segments it makes neighbours. \
+              Click twice to add a segment.")
+    }
+
+    fn pseudo_code(&self) -> Option<&'static str> {
+        Some(concat!(
+            "Q = ends of the segments, sorted by x\n",
+            "T = empty status\n",
+            "while Q is not empty:\n",
+            "    p = pop the first event of Q\n",
+            "    U = segments starting at p\n",
+            "    C = segments of T through p\n",
+            "    if |U| + |C| > 1: report p\n",
+            "    remove C from T\n",
+            "    insert U and C not ending at p,\n",
+            "        in their order after p\n",
+            "    for each new pair of neighbours in T:\n",
+            "        if they intersect after p:\n",
+            "            add the intersection to Q",
+        ))
+    }
+
+    fn complexity(&self) -> Option<&'static str> {
+        Some("O((n + k) log n) for n segments and k intersections, instead of O(n²) to test every pair")
+    }
+
+    fn degeneracies(&self) -> &'static [Degeneracy] {
+        &[ Degeneracy::CollinearPoints ]
+    }
+
+    fn configure(&mut self, ui: &imgui::Ui) -> Result<()> {
+        let pairs = self.intersections.iter().map(|i| i.segments.len() * (i.segments.len() - 1) / 2).sum::<usize>();
+        ui.text(imgui::im_str!("{} intersection points, {} intersecting pairs", self.intersections.len(), pairs));
+        let max = self.intersections.iter().map(|i| i.segments.len()).max().unwrap_or(0);
+        if max > 2 {
+            ui.text(imgui::im_str!("Up to {} segments through the same point", max));
+        }
+
+        if ui.checkbox(imgui::im_str!("Compare with brute force"), &mut self.compare) {
+            self.compute()?;
+        }
+        if let Some((count, time)) = self.brute_force {
+            ui.text(imgui::im_str!("Every pair tested: {} pairs in {:.3} ms", count, time.as_secs_f64() * 1000.0));
+        }
+
+        if ui.button(imgui::im_str!("Clear Segments"), [0.0, 0.0]) {
+            self.pending = None;
+            self.start.clear()?;
+            self.scene.borrow_mut().clear_segments();
+        }
+
+        ui.input_int(imgui::im_str!("Count"), &mut self.random_count).build();
+        if ui.button(imgui::im_str!("Random Segments"), [0.0, 0.0]) {
+            let mut scene = self.scene.borrow_mut();
+            for _ in 0..self.random_count.max(0) {
+                let a = Vec2::random_range(-0.8, 0.8, -0.8, 0.8);
+                let b = Vec2::random_range(-0.8, 0.8, -0.8, 0.8);
+                scene.add_segment(Segment2::new(a, b));
+            }
+        }
+
+        if let Some(point) = self.point_input.configure(ui)? {
+            self.add_end(point)?;
+        }
+
+        self.exec_time.configure(ui);
+        Ok(())
+    }
+}
+
+impl<'f> SegmentIntersectionsRenderer<'f> {
+    pub fn new(facade: &'f dyn Facade, scene: SharedScene) -> Result<Self> {
+        let vs = graphics::SHADERS._2d_vs;
+        let fs = graphics::SHADERS.basic_fs;
+        let program = Program::from_source(facade, vs, fs, None)?;
+
+        let mut points = Shape::new(facade, PrimitiveType::Points, [ 1.0, 0.2, 0.2 ])?;
+        points.size = 8.0;
+
+        Ok(Self {
+            scene,
+            revision: 0,
+            intersections: Vec::new(),
+            brute_force: None,
+            program,
+            segments: Shape::new(facade, PrimitiveType::LinesList, [ 0.0, 0.2, 1.0 ])?,
+            points,
+            start: Shape::new(facade, PrimitiveType::Points, [ 1.0, 0.8, 0.0 ])?,
+            pending: None,
+            compare: false,
+            random_count: 50,
+            exec_time: ExecTimeHistory::default(),
+            point_input: PointInput::new(facade)?,
+        })
+    }
+
+    /// Starts a segment at `point`, or ends the pending one and adds it to the scene.
+    fn add_end(&mut self, point: Vec2) -> Result<()> {
+        match self.pending.take() {
+            Some(start) => {
+                self.start.clear()?;
+                self.scene.borrow_mut().add_segment(Segment2::new(start, point));
+            },
+            None => {
+                self.pending = Some(point);
+                self.start.set_vertices(&[ point ])?;
+            },
+        }
+        Ok(())
+    }
+
+    /// Finds the intersections of the segments of the scene and regenerates the buffers.
+    fn compute(&mut self) -> Result<()> {
+        let scene = self.scene.borrow();
+        self.revision = scene.revision();
+        let segments = scene.segments().to_vec();
+        drop(scene);
+
+        let start_time = Instant::now();
+        self.intersections = bentley_ottmann(&segments);
+        if segments.is_empty() {
+            self.exec_time.clear();
+        } else {
+            self.exec_time.push(Instant::now() - start_time);
+        }
+
+        self.brute_force = if self.compare {
+            let start_time = Instant::now();
+            let count = segments.iter()
+                                .enumerate()
+                                .map(|(idx, s)| segments[idx + 1..].iter().filter(|other| s.intersects(other)).count())
+                                .sum();
+            Some((count, Instant::now() - start_time))
+        } else {
+            None
+        };
+
+        let lines = segments.iter().flat_map(|s| vec![ s.a, s.b ]).collect::<Vec<_>>();
+        self.segments.set_vertices(&lines)?;
+        let points = self.intersections.iter().map(|i| i.point).collect::<Vec<_>>();
+        self.points.set_vertices(&points)
+    }
+}
@@ -0,0 +1,225 @@
+use crate::{
+    Result,
+    algorithms::{ shortest_path, ShortestPath },
+    graphics::{ self, Shape, Viewport },
+    math::Vec2,
+    scene::SharedScene,
+    ui::{ PointInput, ExecTimeHistory, window::algorithms::{ Drawable, Configurable } },
+};
+
+use std::time::Instant;
+
+use glium::{
+    index::PrimitiveType, Frame, Program,
+    backend::Facade,
+};
+use winit::{
+    event::{ Event, WindowEvent, MouseButton, ElementState },
+    window::Window,
+};
+
+pub struct ShortestPathRenderer<'f> {
+    /// The last polygon of the scene is loaded when it changes
+    scene: SharedScene,
+    revision: u64,
+    /// Vertices of the polygon, left clicks add new ones
+    polygon: Vec<Vec2>,
+    /// Right clicks place the start and the end in turn
+    start: Option<Vec2>,
+    end: Option<Vec2>,
+    place_end: bool,
+    result: Option<ShortestPath>,
+    program: Program,
+    outline: Shape<'f>,
+    vertices: Shape<'f>,
+    triangles: Shape<'f>,
+    sleeve: Shape<'f>,
+    path: Shape<'f>,
+    endpoints: Shape<'f>,
+    show_triangulation: bool,
+    exec_time: ExecTimeHistory,
+    point_input: PointInput<'f>,
+}
+
+impl<'f> Drawable for ShortestPathRenderer<'f> {
+    fn update(&mut self) -> Result<()> {
+        let scene = self.scene.borrow();
+        if scene.revision() != self.revision {
+            self.revision = scene.revision();
+            if let Some(polygon) = scene.polygons().last() {
+                self.polygon = polygon.clone();
+                drop(scene);
+                self.compute()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        self.point_input.draw(target, viewport)?;
+        self.sleeve.draw(target, &self.program, viewport)?;
+        if self.show_triangulation {
+            self.triangles.draw(target, &self.program, viewport)?;
+        }
+        self.outline.draw(target, &self.program, viewport)?;
+        self.path.draw(target, &self.program, viewport)?;
+        self.vertices.draw(target, &self.program, viewport)?;
+        self.endpoints.draw(target, &self.program, viewport)
+    }
+
+    fn handle_events(&mut self, window: &Window, event: &Event<()>, io: &imgui::Io, viewport: &Viewport) -> Result<()> {
+        if let Event::WindowEvent { event, .. } = event {
+            if let WindowEvent::MouseInput { button, state, .. } = event {
+                // Ignore clicks when the cursor is over an ImGui window
+                if !io.want_capture_mouse && state == &ElementState::Pressed {
+                    let coords = viewport.window_pos_to_normalized(io.mouse_pos.into(), window);
+                    match button {
+                        // Add a vertex on left click
+                        MouseButton::Left => self.polygon.push(self.point_input.place(coords)),
+                        // Move the start or the end, whichever did not move last
+                        MouseButton::Right => {
+                            if self.place_end {
+                                self.end = Some(coords);
+                            } else {
+                                self.start = Some(coords);
+                            }
+                            self.place_end = !self.place_end;
+                        },
+                        _ => return Ok(()),
+                    }
+                    self.compute()?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'f> Configurable for ShortestPathRenderer<'f> {
+    fn name(&self) -> &'static str {
+        "Shortest path in a polygon"
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        Some("Finds the shortest path between two points inside a polygon. The polygon is triangulated by ear clipping, \
+              and the triangles between the two points form a sleeve in the dual tree of the triangulation. \
+              The path crosses the diagonals of the sleeve in order: the funnel algorithm keeps the cone \
+              from the last corner of the path through the diagonals seen so far, and adds a corner \
+              where one side of the cone crosses the other. The corners are reflex vertices of the polygon. \
+              Left click to add vertices to the polygon, right click to place the start and the end in turn, \
+              the last polygon of the scene is loaded when it changes.")
+    }
+
+    fn pseudo_code(&self) -> Option<&'static str> {
+        Some(concat!(
+            "triangulate the polygon\n",
+            "sleeve = path of triangles from start to end\n",
+            "portals = diagonals of the sleeve + [end]\n",
+            "apex = left = right = start\n",
+            "for each portal (l, r):\n",
+            "    if r narrows the funnel:\n",
+            "        if r crosses left: add left to path,\n",
+            "            apex = left, restart after it\n",
+            "        else: right = r\n",
+            "    same for l and the left side\n",
+            "add end to path",
+        ))
+    }
+
+    fn complexity(&self) -> Option<&'static str> {
+        Some("O(n²) for the ear clipping, then O(n) for the sleeve and the funnel")
+    }
+
+    fn configure(&mut self, ui: &imgui::Ui) -> Result<()> {
+        ui.text(imgui::im_str!("{} vertices", self.polygon.len()));
+        match &self.result {
+            Some(result) => {
+                ui.text(imgui::im_str!("Length: {:.4}", result.length()));
+                ui.text(imgui::im_str!("{} triangles in the sleeve, {} corners", result.sleeve.len(), result.path.len() - 2));
+            },
+            None if self.start.is_some() && self.end.is_some() => ui.text(imgui::im_str!("The start or the end is outside of the polygon")),
+            None => ui.text(imgui::im_str!("Right click to place the start and the end")),
+        }
+
+        ui.checkbox(imgui::im_str!("Triangulation"), &mut self.show_triangulation);
+        if ui.button(imgui::im_str!("Clear Polygon"), [0.0, 0.0]) {
+            self.polygon.clear();
+            self.compute()?;
+        }
+        ui.same_line(0.0);
+        if ui.button(imgui::im_str!("Add to Scene"), [0.0, 0.0]) && self.polygon.len() >= 3 {
+            self.scene.borrow_mut().add_polygon(self.polygon.clone());
+        }
+
+        if let Some(point) = self.point_input.configure(ui)? {
+            self.polygon.push(point);
+            self.compute()?;
+        }
+
+        self.exec_time.configure(ui);
+        Ok(())
+    }
+}
+
+impl<'f> ShortestPathRenderer<'f> {
+    pub fn new(facade: &'f dyn Facade, scene: SharedScene) -> Result<Self> {
+        let vs = graphics::SHADERS._2d_vs;
+        let fs = graphics::SHADERS.basic_fs;
+        let program = Program::from_source(facade, vs, fs, None)?;
+
+        let mut path = Shape::new(facade, PrimitiveType::LineStrip, [ 0.0, 1.0, 0.0 ])?;
+        path.size = 2.0;
+
+        Ok(Self {
+            scene,
+            revision: 0,
+            polygon: Vec::new(),
+            start: None,
+            end: None,
+            place_end: false,
+            result: None,
+            program,
+            outline: Shape::new(facade, PrimitiveType::LineLoop, [ 1.0, 1.0, 1.0 ])?,
+            vertices: Shape::new(facade, PrimitiveType::Points, [ 1.0, 1.0, 1.0 ])?,
+            triangles: Shape::new(facade, PrimitiveType::LinesList, [ 0.3, 0.3, 0.6 ])?,
+            sleeve: Shape::new(facade, PrimitiveType::TrianglesList, [ 0.1, 0.25, 0.3 ])?,
+            path,
+            endpoints: Shape::new(facade, PrimitiveType::Points, [ 0.0, 1.0, 0.0 ])?,
+            show_triangulation: true,
+            exec_time: ExecTimeHistory::default(),
+            point_input: PointInput::new(facade)?,
+        })
+    }
+
+    /// Finds the path between the start and the end and regenerates the buffers.
+    fn compute(&mut self) -> Result<()> {
+        self.outline.set_vertices(&self.polygon)?;
+        self.vertices.set_vertices(&self.polygon)?;
+        self.endpoints.set_vertices(&self.start.iter().chain(self.end.iter()).copied().collect::<Vec<_>>())?;
+
+        let start_time = Instant::now();
+        self.result = match (self.start, self.end) {
+            (Some(start), Some(end)) => shortest_path(&self.polygon, start, end),
+            _ => None,
+        };
+        let result = match &self.result {
+            Some(result) => result,
+            None => {
+                self.exec_time.clear();
+                self.triangles.clear()?;
+                self.sleeve.clear()?;
+                return self.path.clear();
+            },
+        };
+        self.exec_time.push(Instant::now() - start_time);
+
+        let polygon = &self.polygon;
+        let sleeve = result.sleeve
+                           .iter()
+                           .flat_map(|&idx| result.triangles[3 * idx..3 * idx + 3].iter().map(|&v| polygon[v]))
+                           .collect::<Vec<_>>();
+        self.triangles.set_vertices(&graphics::shape::triangle_edges(polygon, &result.triangles))?;
+        self.sleeve.set_vertices(&sleeve)?;
+        self.path.set_vertices(&result.path)
+    }
+}
@@ -0,0 +1,196 @@
+use crate::{
+    Result,
+    algorithms::{ DensityMap, Stippling },
+    graphics::{ self, Shape, Viewport },
+    image,
+    math::Vec2,
+    scene::SharedScene,
+    task::Task,
+    ui::{ SharedSettings, ExecTimeHistory, window::algorithms::{ Drawable, Configurable } },
+};
+
+use std::{
+    path::Path,
+    time::{ Duration, Instant },
+};
+
+use glium::{
+    index::PrimitiveType, Frame, Program,
+    backend::Facade,
+};
+
+/// Largest side of the density map, in pixels, the relaxation visits each of them
+const MAX_DENSITY_SIZE: usize = 256;
+
+pub struct StipplingRenderer<'f> {
+    /// The stipples can replace the points of the scene
+    scene: SharedScene,
+    settings: SharedSettings,
+    program: Program,
+    density: Option<DensityMap>,
+    stipples: Shape<'f>,
+    /// Positions of the stipples, kept to be copied to the scene
+    points: Vec<Vec2>,
+    path: imgui::ImString,
+    count: i32,
+    iterations: i32,
+    exec_time: ExecTimeHistory,
+    /// Relaxation running in the background
+    task: Option<Task<Stipples>>,
+}
+
+/// Result of a relaxation computed on a worker thread.
+struct Stipples {
+    points: Vec<Vec2>,
+    exec_time: Duration,
+}
+
+impl<'f> Drawable for StipplingRenderer<'f> {
+    fn update(&mut self) -> Result<()> {
+        let result = match &self.task {
+            Some(task) => task.poll(),
+            None => None,
+        };
+        if let Some(stipples) = result {
+            self.task = None;
+            self.exec_time.push(stipples.exec_time);
+            self.stipples.set_vertices(&stipples.points)?;
+            self.points = stipples.points;
+        }
+        Ok(())
+    }
+
+    fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        self.stipples.draw(target, &self.program, viewport)
+    }
+}
+
+impl<'f> Configurable for StipplingRenderer<'f> {
+    fn name(&self) -> &'static str {
+        "Voronoi stippling"
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        Some("Draws a grayscale image with dots, closer together where the image is darker. \
+              The dots are first sampled according to the darkness of the pixels, then each Lloyd iteration \
+              moves every dot to the centroid of its Voronoi cell, weighted by the darkness of the pixels of the cell. \
+              The dots converge to a centroidal Voronoi tessellation and end up evenly spaced locally. \
+              Images are read from PGM files, other formats can be converted with most image editors.")
+    }
+
+    fn pseudo_code(&self) -> Option<&'static str> {
+        Some(concat!(
+            "sample the points from the density\n",
+            "repeat:\n",
+            "    for each pixel p:\n",
+            "        s = point nearest to p\n",
+            "        sum(s) += density(p) * p\n",
+            "        weight(s) += density(p)\n",
+            "    for each point s:\n",
+            "        s = sum(s) / weight(s)",
+        ))
+    }
+
+    fn complexity(&self) -> Option<&'static str> {
+        Some("O(p) per iteration for p pixels, the nearest point is found in a grid")
+    }
+
+    fn configure(&mut self, ui: &imgui::Ui) -> Result<()> {
+        match &self.density {
+            Some(density) => ui.text(imgui::im_str!("{}x{} pixels, {} stipples", density.width(), density.height(), self.points.len())),
+            None => ui.text(imgui::im_str!("No image loaded")),
+        }
+
+        ui.input_text(imgui::im_str!("Path"), &mut self.path).build();
+        if ui.button(imgui::im_str!("Load Image"), [0.0, 0.0]) {
+            self.load_image()?;
+        }
+
+        ui.input_int(imgui::im_str!("Stipples"), &mut self.count).step(1000).build();
+        ui.input_int(imgui::im_str!("Iterations"), &mut self.iterations).build();
+        self.count = self.count.max(0);
+        self.iterations = self.iterations.max(0);
+        if ui.button(imgui::im_str!("Stipple"), [0.0, 0.0]) {
+            self.start_relaxation();
+        }
+        ui.same_line(0.0);
+        if ui.button(imgui::im_str!("Replace Scene Points"), [0.0, 0.0]) {
+            self.scene.borrow_mut().set_points(self.points.clone());
+        }
+        imgui::Slider::new(imgui::im_str!("Size"), 1.0..=8.0).build(ui, &mut self.stipples.size);
+
+        let mut cancel = false;
+        if let Some(task) = &self.task {
+            imgui::ProgressBar::new(task.progress().fraction()).build(ui);
+            cancel = ui.button(imgui::im_str!("Cancel"), [0.0, 0.0]);
+        }
+        if cancel {
+            self.task = None; // Dropping the task cancels it
+        }
+
+        self.exec_time.configure(ui);
+        Ok(())
+    }
+}
+
+impl<'f> StipplingRenderer<'f> {
+    pub fn new(facade: &'f dyn Facade, scene: SharedScene, settings: SharedSettings) -> Result<Self> {
+        let vs = graphics::SHADERS._2d_vs;
+        let fs = graphics::SHADERS.basic_fs;
+        let program = Program::from_source(facade, vs, fs, None)?;
+
+        let mut path = imgui::ImString::with_capacity(256);
+        if let Some(dir) = &settings.borrow().import_directory {
+            path.push_str(&dir.join("image.pgm").to_string_lossy());
+        }
+
+        let mut stipples = Shape::new(facade, PrimitiveType::Points, [ 1.0, 1.0, 1.0 ])?;
+        stipples.size = 3.0;
+
+        Ok(Self {
+            scene,
+            settings,
+            program,
+            density: None,
+            stipples,
+            points: Vec::new(),
+            path,
+            count: 4000,
+            iterations: 50,
+            exec_time: ExecTimeHistory::default(),
+            task: None,
+        })
+    }
+
+    /// Reads the density map from the image at `path` and stipples it.
+    fn load_image(&mut self) -> Result<()> {
+        let path = Path::new(self.path.to_str().trim());
+        let img = image::read(path)?;
+        log::info!("Read a {}x{} image from {}", img.width, img.height, path.display());
+        self.density = Some(DensityMap::from_luma(img.width, img.height, &img.pixels).downsampled(MAX_DENSITY_SIZE));
+        self.settings.borrow_mut().import_directory = path.canonicalize().ok()
+                                                        .and_then(|path| path.parent().map(Path::to_owned));
+        self.start_relaxation();
+        Ok(())
+    }
+
+    /// Samples new stipples and relaxes them on a worker thread, cancelling the previous computation if it is still running.
+    /// The result is retrieved in [`update()`](#method.update).
+    fn start_relaxation(&mut self) {
+        let density = match &self.density {
+            Some(density) => density.clone(),
+            None => return,
+        };
+        let (count, iterations) = (self.count as usize, self.iterations as usize);
+
+        self.task = Some(Task::spawn(move |progress| {
+            let start_time = Instant::now();
+            let mut stippling = Stippling::new(density, count);
+            stippling.run(iterations, progress)?;
+            Some(Stipples {
+                points: stippling.points().to_vec(),
+                exec_time: Instant::now() - start_time,
+            })
+        }));
+    }
+}
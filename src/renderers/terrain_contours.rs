@@ -0,0 +1,276 @@
+use crate::{
+    Result,
+    algorithms::{ Incremental2dTriangulation, contour_levels, contour_lines::{ self, contour_lines } },
+    graphics::{ self, Shape, ColorMappedShape, Viewport, colormap, label, shape::triangle_edges },
+    math::{ Vec2, polyline },
+    scene::SharedScene,
+    ui::{ PointInput, ExecTimeHistory, window::algorithms::{ Drawable, Configurable } },
+};
+
+use std::time::Instant;
+
+use glium::{ index::PrimitiveType, Frame, Program, backend::Facade };
+use winit::{
+    event::{ Event, WindowEvent, MouseButton, ElementState },
+    window::Window,
+};
+
+/// Height of the characters of the labels, in the [-1, 1] square
+const LABEL_SIZE: f32 = 0.03;
+
+pub struct TerrainContoursRenderer<'f> {
+    /// The points of the scene with their values as heights
+    scene: SharedScene,
+    revision: u64,
+    points: Vec<Vec2>,
+    heights: Vec<f32>,
+    /// Value of the points added by left clicks
+    new_height: f32,
+    /// Height of the terrain under the cursor
+    cursor_height: Option<f32>,
+    random_count: i32,
+    /// Delaunay triangulation of the points
+    indices: Vec<usize>,
+    /// Height between consecutive contours
+    interval: f32,
+    show_labels: bool,
+    show_triangulation: bool,
+    program: Program,
+    /// The triangles with the colors of the heights of their vertices
+    terrain: ColorMappedShape<'f>,
+    vertices: ColorMappedShape<'f>,
+    edges: Shape<'f>,
+    contours: Shape<'f>,
+    labels: Shape<'f>,
+    exec_time: ExecTimeHistory,
+    point_input: PointInput<'f>,
+}
+
+impl<'f> Drawable for TerrainContoursRenderer<'f> {
+    fn update(&mut self) -> Result<()> {
+        let scene = self.scene.borrow();
+        if scene.revision() != self.revision {
+            self.revision = scene.revision();
+            self.points = scene.points().to_vec();
+            self.heights = scene.values().to_vec();
+            drop(scene);
+            self.compute()?;
+        }
+        Ok(())
+    }
+
+    fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        self.terrain.draw(target, viewport)?;
+        self.point_input.draw(target, viewport)?;
+        if self.show_triangulation {
+            self.edges.draw(target, &self.program, viewport)?;
+        }
+        self.contours.draw(target, &self.program, viewport)?;
+        if self.show_labels {
+            self.labels.draw(target, &self.program, viewport)?;
+        }
+        self.vertices.draw(target, viewport)
+    }
+
+    fn handle_events(&mut self, window: &Window, event: &Event<()>, io: &imgui::Io, viewport: &Viewport) -> Result<()> {
+        if let Event::WindowEvent { event, .. } = event {
+            match event {
+                WindowEvent::MouseInput { button, state, .. } => {
+                    if !io.want_capture_mouse && // Ignore clicks when the cursor is over an ImGui window
+                        button == &MouseButton::Left && state == &ElementState::Pressed {
+
+                        // Add a point on click
+                        let coords = viewport.window_pos_to_normalized(io.mouse_pos.into(), window);
+                        self.scene.borrow_mut().add_point_with_value(self.point_input.place(coords), self.new_height);
+                    }
+                },
+                WindowEvent::CursorMoved { .. } => {
+                    let coords = viewport.window_pos_to_normalized(io.mouse_pos.into(), window);
+                    self.cursor_height = contour_lines::interpolate(&self.points, &self.heights, &self.indices, coords);
+                },
+                _ => {},
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'f> Configurable for TerrainContoursRenderer<'f> {
+    fn name(&self) -> &'static str {
+        "Terrain contours"
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        Some("Draws the contour lines of a terrain given by the points of the scene, with their values as heights. \
+              The points are triangulated with the Delaunay triangulation, and the height is interpolated linearly over each triangle: \
+              a contour crosses a triangle along a segment between two of its edges, where the heights of their ends \
+              are on either side of the contour. The segments are linked into lines through the shared edges, \
+              and each line is labelled with its height in the middle. \
+              The triangles are colored from green at the lowest height to white at the highest. \
+              The height under the cursor is interpolated in the triangle that contains it. \
+              Left click to add points at the chosen height, or load the last point cloud of the scene \
+              with its z coordinates as heights. The values can be edited in the Scene window.")
+    }
+
+    fn pseudo_code(&self) -> Option<&'static str> {
+        Some(concat!(
+            "T = Delaunay triangulation of the points\n",
+            "for each level h, multiple of the interval:\n",
+            "    for each triangle of T:\n",
+            "        crossed = edges (a, b) with h between h(a) and h(b)\n",
+            "        if there are 2 crossed edges:\n",
+            "            add the segment between their crossings at h\n",
+            "    link the segments through their crossed edges",
+        ))
+    }
+
+    fn complexity(&self) -> Option<&'static str> {
+        Some("O(t log t) per level for t triangles, after the triangulation")
+    }
+
+    fn configure(&mut self, ui: &imgui::Ui) -> Result<()> {
+        ui.text(imgui::im_str!("{} points, {} triangles", self.points.len(), self.indices.len() / 3));
+        match self.cursor_height {
+            Some(height) => ui.text(imgui::im_str!("Height under the cursor: {:.3}", height)),
+            None => ui.text(imgui::im_str!("Height under the cursor: outside")),
+        }
+        if imgui::Slider::new(imgui::im_str!("Interval"), 0.01..=0.5).build(ui, &mut self.interval) {
+            self.update_contours()?;
+        }
+        ui.checkbox(imgui::im_str!("Labels"), &mut self.show_labels);
+        ui.same_line(0.0);
+        ui.checkbox(imgui::im_str!("Triangulation"), &mut self.show_triangulation);
+        imgui::Slider::new(imgui::im_str!("New point height"), -1.0..=1.0).build(ui, &mut self.new_height);
+
+        ui.input_int(imgui::im_str!("Count"), &mut self.random_count).build();
+        if ui.button(imgui::im_str!("Random Terrain"), [0.0, 0.0]) {
+            self.random_terrain();
+        }
+        ui.same_line(0.0);
+        if ui.button(imgui::im_str!("Load Point Cloud"), [0.0, 0.0]) {
+            self.load_point_cloud();
+        }
+        ui.same_line(0.0);
+        if ui.button(imgui::im_str!("Clear Points"), [0.0, 0.0]) {
+            self.scene.borrow_mut().clear_points();
+        }
+
+        if let Some(point) = self.point_input.configure(ui)? {
+            self.scene.borrow_mut().add_point_with_value(point, self.new_height);
+        }
+
+        self.exec_time.configure(ui);
+        Ok(())
+    }
+}
+
+impl<'f> TerrainContoursRenderer<'f> {
+    pub fn new(facade: &'f dyn Facade, scene: SharedScene) -> Result<Self> {
+        let vs = graphics::SHADERS._2d_vs;
+        let fs = graphics::SHADERS.basic_fs;
+        let program = Program::from_source(facade, vs, fs, None)?;
+
+        let mut vertices = ColorMappedShape::new(facade, PrimitiveType::Points)?;
+        vertices.size = 6.0;
+        let mut contours = Shape::new(facade, PrimitiveType::LinesList, [ 0.35, 0.2, 0.1 ])?;
+        contours.size = 1.5;
+
+        Ok(Self {
+            scene,
+            revision: 0,
+            points: Vec::new(),
+            heights: Vec::new(),
+            new_height: 0.5,
+            cursor_height: None,
+            random_count: 500,
+            indices: Vec::new(),
+            interval: 0.1,
+            show_labels: true,
+            show_triangulation: false,
+            program,
+            terrain: ColorMappedShape::new(facade, PrimitiveType::TrianglesList)?,
+            vertices,
+            edges: Shape::new(facade, PrimitiveType::LinesList, [ 0.5, 0.5, 0.5 ])?,
+            contours,
+            labels: Shape::new(facade, PrimitiveType::LinesList, [ 0.1, 0.1, 0.1 ])?,
+            exec_time: ExecTimeHistory::default(),
+            point_input: PointInput::new(facade)?,
+        })
+    }
+
+    /// Replaces the points of the scene with random ones on a few hills and valleys.
+    fn random_terrain(&mut self) {
+        let hills = (0..5)
+            .map(|_| (Vec2::random_range(-0.8, 0.8, -0.8, 0.8), 0.2 + 0.3 * rand::random::<f32>(), rand::random::<f32>() - 0.3))
+            .collect::<Vec<_>>();
+        let points = (0..self.random_count.max(0)).map(|_| Vec2::random_range(-0.9, 0.9, -0.9, 0.9)).collect::<Vec<_>>();
+        let heights = points
+            .iter()
+            .map(|p| hills.iter().map(|(center, width, height)| height * (-(p - center).sqr_length() / (width * width)).exp()).sum())
+            .collect();
+        self.scene.borrow_mut().set_points_with_values(points, heights);
+    }
+
+    /// Replaces the points of the scene with the last point cloud, its z coordinates becoming the values.
+    fn load_point_cloud(&mut self) {
+        let mut scene = self.scene.borrow_mut();
+        let cloud = match scene.meshes().iter().rev().find(|mesh| mesh.indices.is_empty() && !mesh.vertices.is_empty()) {
+            Some(cloud) => cloud,
+            None => {
+                log::warn!("No point cloud in the scene, import one first");
+                return;
+            },
+        };
+        let points = cloud.vertices.iter().map(|p| Vec2::new(p.0.x, p.0.y)).collect();
+        let heights = cloud.vertices.iter().map(|p| p.0.z).collect();
+        scene.set_points_with_values(points, heights);
+    }
+
+    /// Triangulates the points and regenerates the buffers.
+    fn compute(&mut self) -> Result<()> {
+        let start_time = Instant::now();
+        self.indices = Incremental2dTriangulation::delaunay(&self.points);
+        if self.points.is_empty() {
+            self.exec_time.clear();
+        } else {
+            self.exec_time.push(Instant::now() - start_time);
+        }
+
+        let range = colormap::range(&self.heights).unwrap_or((0.0, 0.0));
+        let corners = self.indices.iter().map(|&idx| self.points[idx]).collect::<Vec<_>>();
+        let heights = self.indices.iter().map(|&idx| self.heights[idx]).collect::<Vec<_>>();
+        self.terrain.set_vertices(&corners, &heights, range)?;
+        self.edges.set_vertices(&triangle_edges(&self.points, &self.indices))?;
+        self.vertices.set_vertices(&self.points, &self.heights, range)?;
+        self.cursor_height = None;
+        self.update_contours()
+    }
+
+    /// Extracts the contours at every multiple of the interval and regenerates their buffers.
+    fn update_contours(&mut self) -> Result<()> {
+        let (min, max) = colormap::range(&self.heights).unwrap_or((0.0, -1.0));
+        let decimals = if self.interval >= 1.0 { 0 } else if self.interval >= 0.1 { 1 } else { 2 };
+
+        let mut lines = Vec::new();
+        let mut labels = Vec::new();
+        for level in contour_levels(min, max, self.interval) {
+            for contour in contour_lines(&self.points, &self.heights, &self.indices, level) {
+                let n = contour.points.len();
+                let edges = if contour.closed { n } else { n - 1 };
+                lines.extend((0..edges).flat_map(|i| vec![ contour.points[i], contour.points[(i + 1) % n] ]));
+
+                // Labels the lines long enough to hold them, in their middle
+                let length = polyline::length(&contour.points, contour.closed);
+                let text = format!("{:.*}", decimals, level);
+                if length > 2.0 * LABEL_SIZE * text.len() as f32 {
+                    if let Some(middle) = polyline::point_at(&contour.points, contour.closed, length / 2.0) {
+                        labels.extend(label::text_lines(&text, middle, LABEL_SIZE));
+                    }
+                }
+            }
+        }
+        self.contours.set_vertices(&lines)?;
+        self.labels.set_vertices(&labels)
+    }
+
+}
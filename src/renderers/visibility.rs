@@ -0,0 +1,272 @@
+use crate::{
+    Result,
+    algorithms::{ visible_points, Sight },
+    graphics::{ self, Shape, Viewport },
+    math::{ Vec2, Segment2 },
+    scene::SharedScene,
+    ui::{ PointInput, ExecTimeHistory, window::algorithms::{ Drawable, Configurable } },
+};
+
+use std::time::Instant;
+
+use glium::{
+    index::PrimitiveType, Frame, Program,
+    backend::Facade,
+};
+use winit::{
+    event::{ Event, WindowEvent, MouseButton, ElementState },
+    window::Window,
+};
+
+/// Largest distance between a click and the query point to drag it
+const PICK_RADIUS: f32 = 0.04;
+
+/// What left clicks add to the scene
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Input {
+    Points,
+    /// Two clicks per obstacle
+    Obstacles,
+}
+
+pub struct VisibilityRenderer<'f> {
+    /// The points and the obstacles are the points and the segments of the scene
+    scene: SharedScene,
+    revision: u64,
+    query: Vec2,
+    dragging: bool,
+    input: Input,
+    sights: Vec<Sight>,
+    program: Program,
+    obstacles: Shape<'f>,
+    visible: Shape<'f>,
+    hidden: Shape<'f>,
+    /// From the query point to the visible points
+    visible_rays: Shape<'f>,
+    /// From the query point to the obstacle hiding each hidden point
+    blocked_rays: Shape<'f>,
+    query_point: Shape<'f>,
+    /// First end of the obstacle being drawn
+    start: Shape<'f>,
+    pending: Option<Vec2>,
+    show_rays: bool,
+    random_count: i32,
+    exec_time: ExecTimeHistory,
+    point_input: PointInput<'f>,
+}
+
+impl<'f> Drawable for VisibilityRenderer<'f> {
+    fn update(&mut self) -> Result<()> {
+        if self.scene.borrow().revision() != self.revision {
+            self.compute()?;
+        }
+        Ok(())
+    }
+
+    fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        self.point_input.draw(target, viewport)?;
+        if self.show_rays {
+            self.visible_rays.draw(target, &self.program, viewport)?;
+            self.blocked_rays.draw(target, &self.program, viewport)?;
+        }
+        self.obstacles.draw(target, &self.program, viewport)?;
+        self.hidden.draw(target, &self.program, viewport)?;
+        self.visible.draw(target, &self.program, viewport)?;
+        self.start.draw(target, &self.program, viewport)?;
+        self.query_point.draw(target, &self.program, viewport)
+    }
+
+    fn handle_events(&mut self, window: &Window, event: &Event<()>, io: &imgui::Io, viewport: &Viewport) -> Result<()> {
+        if let Event::WindowEvent { event, .. } = event {
+            let coords = viewport.window_pos_to_normalized(io.mouse_pos.into(), window);
+            match event {
+                WindowEvent::MouseInput { button: MouseButton::Left, state: ElementState::Pressed, .. } => {
+                    if !io.want_capture_mouse { // Ignore clicks when the cursor is over an ImGui window
+                        // Drag the query point, or add a point or an end of an obstacle
+                        self.dragging = (&coords - &self.query).length() < PICK_RADIUS;
+                        if !self.dragging {
+                            self.add(self.point_input.place(coords))?;
+                        }
+                    }
+                },
+                WindowEvent::MouseInput { button: MouseButton::Left, state: ElementState::Released, .. } => {
+                    self.dragging = false;
+                },
+                WindowEvent::CursorMoved { .. } => {
+                    if self.dragging {
+                        self.query = self.point_input.place(coords);
+                        self.compute()?;
+                    }
+                },
+                _ => {},
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'f> Configurable for VisibilityRenderer<'f> {
+    fn name(&self) -> &'static str {
+        "Visibility sweep"
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        Some("Counts the points visible from the query point, in yellow, among segment obstacles. \
+              A ray turns around the query point and stops at the points and at the ends of the obstacles, \
+              in the order of their angles. It keeps the obstacles it crosses sorted by their distance along it: \
+              an obstacle is inserted when the ray reaches its first end, and removed at its other end. \
+              A point is visible if it is closer than the first obstacle on its ray. \
+              The obstacles are split where they cross each other, so that their order only changes at their ends. \
+              Drag the query point to move it, left click to add points or obstacles, \
+              which are the points and the segments of the scene.")
+    }
+
+    fn pseudo_code(&self) -> Option<&'static str> {
+        Some(concat!(
+            "events = points and ends of the obstacles,\n",
+            "         sorted by angle around q\n",
+            "active = obstacles across the first ray,\n",
+            "         sorted by distance along it\n",
+            "for each event:\n",
+            "    if first end of s: insert s in active\n",
+            "    if point p:\n",
+            "        p is visible if |p - q| <= distance\n",
+            "            to the first of active\n",
+            "    if last end of s: remove s from active",
+        ))
+    }
+
+    fn complexity(&self) -> Option<&'static str> {
+        Some("O((n + m) log (n + m) + m k) for n points, m obstacles and k obstacles on a ray, after splitting the obstacles in O(m²)")
+    }
+
+    fn configure(&mut self, ui: &imgui::Ui) -> Result<()> {
+        let visible = self.sights.iter().filter(|&&sight| sight == Sight::Visible).count();
+        ui.text(imgui::im_str!("{} of {} points visible from ({:.3}, {:.3})", visible, self.sights.len(), self.query.x, self.query.y));
+        ui.checkbox(imgui::im_str!("Rays"), &mut self.show_rays);
+
+        ui.text(imgui::im_str!("Left click adds:"));
+        ui.same_line(0.0);
+        ui.radio_button(imgui::im_str!("Points"), &mut self.input, Input::Points);
+        ui.same_line(0.0);
+        if ui.radio_button(imgui::im_str!("Obstacles"), &mut self.input, Input::Obstacles) {
+            self.pending = None;
+            self.start.clear()?;
+        }
+
+        ui.input_int(imgui::im_str!("Count"), &mut self.random_count).build();
+        if ui.button(imgui::im_str!("Random Obstacles"), [0.0, 0.0]) {
+            let mut scene = self.scene.borrow_mut();
+            for _ in 0..self.random_count.max(0) {
+                let center = Vec2::random_range(-0.8, 0.8, -0.8, 0.8);
+                let half = Vec2::random_range(-0.15, 0.15, -0.15, 0.15);
+                scene.add_segment(Segment2::new(&center - &half, &center + &half));
+            }
+        }
+        ui.same_line(0.0);
+        if ui.button(imgui::im_str!("Clear Obstacles"), [0.0, 0.0]) {
+            self.pending = None;
+            self.start.clear()?;
+            self.scene.borrow_mut().clear_segments();
+        }
+
+        if let Some(point) = self.point_input.configure(ui)? {
+            self.add(point)?;
+        }
+
+        self.exec_time.configure(ui);
+        Ok(())
+    }
+}
+
+impl<'f> VisibilityRenderer<'f> {
+    pub fn new(facade: &'f dyn Facade, scene: SharedScene) -> Result<Self> {
+        let vs = graphics::SHADERS._2d_vs;
+        let fs = graphics::SHADERS.basic_fs;
+        let program = Program::from_source(facade, vs, fs, None)?;
+
+        let mut obstacles = Shape::new(facade, PrimitiveType::LinesList, [ 1.0, 1.0, 1.0 ])?;
+        obstacles.size = 2.0;
+        let mut query_point = Shape::new(facade, PrimitiveType::Points, [ 1.0, 0.9, 0.0 ])?;
+        query_point.size = 14.0;
+
+        Ok(Self {
+            scene,
+            revision: 0,
+            query: Vec2::new(0.0, 0.0),
+            dragging: false,
+            input: Input::Points,
+            sights: Vec::new(),
+            program,
+            obstacles,
+            visible: Shape::new(facade, PrimitiveType::Points, [ 0.2, 0.9, 0.3 ])?,
+            hidden: Shape::new(facade, PrimitiveType::Points, [ 0.4, 0.4, 0.4 ])?,
+            visible_rays: Shape::new(facade, PrimitiveType::LinesList, [ 0.2, 0.6, 0.3 ])?,
+            blocked_rays: Shape::new(facade, PrimitiveType::LinesList, [ 0.6, 0.2, 0.2 ])?,
+            query_point,
+            start: Shape::new(facade, PrimitiveType::Points, [ 1.0, 0.8, 0.0 ])?,
+            pending: None,
+            show_rays: true,
+            random_count: 10,
+            exec_time: ExecTimeHistory::default(),
+            point_input: PointInput::new(facade)?,
+        })
+    }
+
+    /// Adds a point to the scene, or an end of an obstacle depending on the input mode.
+    fn add(&mut self, point: Vec2) -> Result<()> {
+        match self.input {
+            Input::Points => {
+                self.scene.borrow_mut().add_point(point);
+            },
+            Input::Obstacles => match self.pending.take() {
+                Some(start) => {
+                    self.start.clear()?;
+                    self.scene.borrow_mut().add_segment(Segment2::new(start, point));
+                },
+                None => {
+                    self.pending = Some(point);
+                    self.start.set_vertices(&[ point ])?;
+                },
+            },
+        }
+        Ok(())
+    }
+
+    /// Sweeps around the query point and regenerates the buffers.
+    fn compute(&mut self) -> Result<()> {
+        let scene = self.scene.borrow();
+        self.revision = scene.revision();
+        let (points, obstacles) = (scene.points(), scene.segments());
+
+        let start_time = Instant::now();
+        self.sights = visible_points(self.query, points, obstacles);
+        if points.is_empty() {
+            self.exec_time.clear();
+        } else {
+            self.exec_time.push(Instant::now() - start_time);
+        }
+
+        let (mut visible, mut hidden, mut visible_rays, mut blocked_rays) = (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+        for (&p, sight) in points.iter().zip(&self.sights) {
+            match sight {
+                Sight::Visible => {
+                    visible.push(p);
+                    visible_rays.extend_from_slice(&[ self.query, p ]);
+                },
+                Sight::Blocked(hit) => {
+                    hidden.push(p);
+                    blocked_rays.extend_from_slice(&[ self.query, *hit ]);
+                },
+            }
+        }
+        self.obstacles.set_vertices(&obstacles.iter().flat_map(|s| vec![ s.a, s.b ]).collect::<Vec<_>>())?;
+        drop(scene);
+
+        self.visible.set_vertices(&visible)?;
+        self.hidden.set_vertices(&hidden)?;
+        self.visible_rays.set_vertices(&visible_rays)?;
+        self.blocked_rays.set_vertices(&blocked_rays)?;
+        self.query_point.set_vertices(&[ self.query ])
+    }
+}
@@ -0,0 +1,181 @@
+use crate::{
+    Result,
+    algorithms::{ Incremental2dTriangulation, VoronoiCell, Degeneracy, voronoi_diagram, greedy_coloring },
+    graphics::{ self, ColorMappedShape, Viewport },
+    math::{ Vec2, Rect },
+    render::{ layers, DrawList, GliumRenderer },
+    scene::SharedScene,
+    ui::{ PointInput, ExecTimeHistory, window::algorithms::{ Drawable, Configurable } },
+};
+
+use std::time::Instant;
+
+use glium::{
+    index::PrimitiveType, Frame,
+    backend::Facade,
+};
+use winit::{
+    event::{ Event, WindowEvent, MouseButton, ElementState },
+    window::Window,
+};
+
+pub struct VoronoiRenderer<'f> {
+    /// The input points are those of the scene
+    scene: SharedScene,
+    /// Revision of the scene the diagram was computed from
+    revision: u64,
+    points: Vec<Vec2>,
+    cells: Vec<VoronoiCell>,
+    renderer: GliumRenderer<'f>,
+    /// Edges of the cells, clipped to the viewport, and the points
+    diagram: DrawList,
+    /// The dual Delaunay triangulation
+    triangles: DrawList,
+    /// The cells filled so that neighbouring cells have different colors
+    filled_cells: ColorMappedShape<'f>,
+    show_triangulation: bool,
+    show_cells: bool,
+    exec_time: ExecTimeHistory,
+    point_input: PointInput<'f>,
+}
+
+impl<'f> Drawable for VoronoiRenderer<'f> {
+    fn update(&mut self) -> Result<()> {
+        if self.scene.borrow().revision() != self.revision {
+            self.compute()?;
+        }
+        Ok(())
+    }
+
+    fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        if self.show_cells {
+            self.filled_cells.draw(target, viewport)?;
+        }
+        self.point_input.draw(target, viewport)?;
+        if self.show_triangulation {
+            self.renderer.draw_in(&self.triangles, target, viewport.rect(target))?;
+        }
+        self.renderer.draw_in(&self.diagram, target, viewport.rect(target))
+    }
+
+    fn handle_events(&mut self, window: &Window, event: &Event<()>, io: &imgui::Io, viewport: &Viewport) -> Result<()> {
+        if let Event::WindowEvent { event, .. } = event {
+            if let WindowEvent::MouseInput { button, state, .. } = event {
+                if !io.want_capture_mouse && // Ignore clicks when the cursor is over an ImGui window
+                    button == &MouseButton::Left && state == &ElementState::Pressed {
+
+                    // Add a point on click
+                    let coords = viewport.window_pos_to_normalized(io.mouse_pos.into(), window);
+                    self.scene.borrow_mut().add_point(self.point_input.place(coords));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'f> Configurable for VoronoiRenderer<'f> {
+    fn name(&self) -> &'static str {
+        "Voronoi diagram"
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        Some("The cell of a point contains the positions closer to it than to any other input point. \
+              The diagram is the dual of the Delaunay triangulation: the cells of two points share an edge when the points share an edge of the triangulation, \
+              and the vertices of the diagram are the circumcenters of the triangles, equally far from their three vertices. \
+              Each cell is the viewport clipped by the perpendicular bisectors between its point and its Delaunay neighbours. \
+              The points on the convex hull have unbounded cells, cut here by the viewport.")
+    }
+
+    fn pseudo_code(&self) -> Option<&'static str> {
+        Some(concat!(
+            "T = Delaunay triangulation of the points\n",
+            "for each point p:\n",
+            "    cell(p) = viewport\n",
+            "    for each neighbour q of p in T:\n",
+            "        clip cell(p) to the half-plane\n",
+            "            closer to p than to q",
+        ))
+    }
+
+    fn complexity(&self) -> Option<&'static str> {
+        Some("O(n log n) expected for the triangulation, then O(n) for the cells: each point has 6 neighbours on average")
+    }
+
+    fn degeneracies(&self) -> &'static [Degeneracy] {
+        &[ Degeneracy::CollinearPoints, Degeneracy::CocircularPoints ]
+    }
+
+    fn configure(&mut self, ui: &imgui::Ui) -> Result<()> {
+        let bounded = self.cells.iter().filter(|cell| cell.bounded).count();
+        ui.text(imgui::im_str!("{} cells, {} bounded", self.cells.len(), bounded));
+
+        ui.checkbox(imgui::im_str!("Delaunay triangulation"), &mut self.show_triangulation);
+        ui.checkbox(imgui::im_str!("Fill cells"), &mut self.show_cells);
+
+        if ui.button(imgui::im_str!("Clear Points"), [0.0, 0.0]) {
+            self.scene.borrow_mut().clear_points();
+        }
+
+        if let Some(point) = self.point_input.configure(ui)? {
+            self.scene.borrow_mut().add_point(point);
+        }
+
+        self.exec_time.configure(ui);
+        Ok(())
+    }
+}
+
+impl<'f> VoronoiRenderer<'f> {
+    pub fn new(facade: &'f dyn Facade, scene: SharedScene) -> Result<Self> {
+        Ok(Self {
+            scene,
+            revision: 0,
+            points: Vec::new(),
+            cells: Vec::new(),
+            renderer: GliumRenderer::new(facade)?,
+            diagram: layers::list(),
+            triangles: layers::list(),
+            filled_cells: ColorMappedShape::new(facade, PrimitiveType::TrianglesList)?,
+            show_triangulation: false,
+            show_cells: true,
+            exec_time: ExecTimeHistory::default(),
+            point_input: PointInput::new(facade)?,
+        })
+    }
+
+    /// Computes the diagram of the points of the scene inside the viewport and regenerates the buffers.
+    fn compute(&mut self) -> Result<()> {
+        let scene = self.scene.borrow();
+        self.revision = scene.revision();
+        self.points = scene.points().to_vec();
+        drop(scene);
+
+        let bounds = Rect::new(Vec2::new(-1.0, -1.0), Vec2::new(1.0, 1.0));
+        let start_time = Instant::now();
+        self.cells = voronoi_diagram(&self.points, &bounds);
+        if self.points.is_empty() {
+            self.exec_time.clear();
+        } else {
+            self.exec_time.push(Instant::now() - start_time);
+        }
+
+        self.diagram = layers::voronoi(&self.points, &self.cells);
+        let triangles = Incremental2dTriangulation::delaunay(&self.points);
+        self.triangles = layers::triangulation(&self.points, &triangles);
+
+        // Fans from the first vertex of each convex cell, in faded colors under the edges
+        let neighbours = self.cells.iter().map(|cell| cell.neighbours.clone()).collect::<Vec<_>>();
+        let colors = greedy_coloring(&neighbours);
+        let (mut vertices, mut vertex_colors) = (Vec::new(), Vec::new());
+        for (cell, &color) in self.cells.iter().zip(&colors) {
+            let color = graphics::colormap::category(color);
+            let color = [ color[0] * 0.35, color[1] * 0.35, color[2] * 0.35 ];
+            for idx in 1..cell.polygon.len().saturating_sub(1) {
+                vertices.extend_from_slice(&[ cell.polygon[0], cell.polygon[idx], cell.polygon[idx + 1] ]);
+                vertex_colors.extend_from_slice(&[ color; 3 ]);
+            }
+        }
+        self.filled_cells.set_colored_vertices(&vertices, &vertex_colors)
+    }
+}
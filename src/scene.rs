@@ -0,0 +1,377 @@
+//! Input geometry shared by the algorithms, the windows of the viewer and the exporters.
+//!
+//! Renderers read their input from the scene and write their results back to it,
+//! so that points imported once, or clicked in any viewport, are used by every algorithm.
+
+use crate::{
+    algorithms::{ Primitive, Bounded, bounding::{ Box3, Sphere, OrientedBox3 } },
+    math::{ self, Vec2, Vec3, Segment2 },
+    wkt::Geometry,
+};
+
+use std::{ cell::RefCell, cmp::Ordering, rc::Rc };
+
+use cgmath::InnerSpace;
+
+/// A scene shared by the windows of the viewer, which all run on the main thread.
+pub type SharedScene = Rc<RefCell<Scene>>;
+
+/// A 3D model, or a point cloud if `indices` is empty.
+#[derive(Clone, Debug, Default)]
+pub struct Mesh {
+    pub vertices: Vec<Vec3>,
+    /// Indices of the vertices that form the triangles, 3 per triangle
+    pub indices: Vec<usize>,
+}
+
+impl Mesh {
+    /// Returns the normal of each triangle, whose length is twice the area of the triangle.
+    pub fn face_normals(&self) -> Vec<Vec3> {
+        self.indices.chunks(3)
+            .map(|tri| {
+                let (p1, p2, p3) = (self.vertices[tri[0]].0, self.vertices[tri[1]].0, self.vertices[tri[2]].0);
+                (p2 - p1).cross(p3 - p1).into()
+            })
+            .collect()
+    }
+
+    /// Returns the normal of each vertex, the normalized sum of the normals of the triangles sharing it.
+    /// Vertices that are not part of any triangle have a zero normal.
+    pub fn vertex_normals(&self) -> Vec<Vec3> {
+        let mut normals = vec![ cgmath::vec3(0.0, 0.0, 0.0); self.vertices.len() ];
+        for (tri, normal) in self.indices.chunks(3).zip(self.face_normals()) {
+            for &idx in tri {
+                normals[idx] += normal.0;
+            }
+        }
+
+        normals.into_iter()
+            .map(|n| if n.magnitude2() > 0.0 { n.normalize().into() } else { n.into() })
+            .collect()
+    }
+}
+
+impl Bounded for Mesh {
+    type Rect = Box3;
+    type Circle = Sphere;
+    type OrientedBox = OrientedBox3;
+
+    fn bounding_rect(&self) -> Option<Box3> {
+        self.vertices.bounding_rect()
+    }
+
+    fn bounding_circle(&self) -> Option<Sphere> {
+        self.vertices.bounding_circle()
+    }
+
+    fn oriented_bounding_box(&self) -> Option<OrientedBox3> {
+        self.vertices.oriented_bounding_box()
+    }
+}
+
+/// A triangulation of 2D points.
+#[derive(Clone, Debug, Default)]
+pub struct Triangulation {
+    /// Points in the order used by the indices
+    pub points: Vec<Vec2>,
+    /// Indices of the points that form the triangles, 3 per triangle
+    pub indices: Vec<usize>,
+}
+
+/// Input geometry and the last results computed from it.
+/// Every change to the input increments the [`revision()`](#method.revision) and discards the results,
+/// observers compare it with the revision they last read to know when to run again.
+#[derive(Default)]
+pub struct Scene {
+    points: Vec<Vec2>,
+    /// Scalar attribute of each point, e.g. a height or a measurement, 0 unless set
+    values: Vec<f32>,
+    polygons: Vec<Vec<Vec2>>,
+    segments: Vec<Segment2>,
+    meshes: Vec<Mesh>,
+    revision: u64,
+
+    hull: Vec<Vec2>,
+    triangulation: Option<Triangulation>,
+    hull_3d: Option<Mesh>,
+}
+
+impl Scene {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts at 0 for an empty scene.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    pub fn points(&self) -> &[Vec2] {
+        &self.points
+    }
+
+    /// Returns the value of each point, in the order of [`points()`](#method.points).
+    pub fn values(&self) -> &[f32] {
+        &self.values
+    }
+
+    pub fn polygons(&self) -> &[Vec<Vec2>] {
+        &self.polygons
+    }
+
+    pub fn segments(&self) -> &[Segment2] {
+        &self.segments
+    }
+
+    pub fn meshes(&self) -> &[Mesh] {
+        &self.meshes
+    }
+
+    /// Adds a point to the input point set.
+    /// Returns false if the point was a duplicate and has been ignored.
+    pub fn add_point(&mut self, point: Vec2) -> bool {
+        self.add_point_with_value(point, 0.0)
+    }
+
+    /// Adds a point with its value, e.g. a height.
+    /// Returns false if the point was a duplicate and has been ignored.
+    pub fn add_point_with_value(&mut self, point: Vec2, value: f32) -> bool {
+        if self.points.contains(&point) {
+            log::warn!("Ignoring duplicate point ({}, {})", point.x, point.y);
+            return false;
+        }
+        self.points.push(point);
+        self.values.push(value);
+        self.changed();
+        true
+    }
+
+    /// Adds several points at once, duplicates are ignored.
+    pub fn add_points<I: IntoIterator<Item = Vec2>>(&mut self, points: I) {
+        let mut all = std::mem::take(&mut self.points);
+        all.extend(points);
+        let mut values = std::mem::take(&mut self.values);
+        values.resize(all.len(), 0.0);
+        self.set_points_with_values(all, values);
+    }
+
+    /// Adds `n` random points, in the range used for clicked points.
+    pub fn add_random_points(&mut self, n: usize) {
+        let x_min = -0.8; // 0.8 to prevent getting too close to the edges of the window
+        let x_max = -x_min;
+        let y_min = x_min;
+        let y_max = -y_min;
+
+        self.add_points((0..n).map(|_| Vec2::random_range(x_min, x_max, y_min, y_max)));
+    }
+
+    /// Replaces the input point set, e.g. with the content of an imported file.
+    /// Duplicates are ignored.
+    pub fn set_points(&mut self, points: Vec<Vec2>) {
+        let values = vec![ 0.0; points.len() ];
+        self.set_points_with_values(points, values);
+    }
+
+    /// Replaces the input point set and the values of the points, one per point.
+    /// Duplicates are ignored with their values.
+    pub fn set_points_with_values(&mut self, points: Vec<Vec2>, values: Vec<f32>) {
+        assert_eq!(points.len(), values.len(), "There must be one value per point");
+        let count = points.len();
+        let keep = unique(&points);
+        self.points = points.into_iter().zip(&keep).filter(|(_, &keep)| keep).map(|(p, _)| p).collect();
+        self.values = values.into_iter().zip(&keep).filter(|(_, &keep)| keep).map(|(value, _)| value).collect();
+        if self.points.len() < count {
+            log::warn!("Ignoring {} duplicate points", count - self.points.len());
+        }
+        self.changed();
+    }
+
+    /// Changes the value of the point at `idx`.
+    pub fn set_value(&mut self, idx: usize, value: f32) {
+        self.values[idx] = value;
+        self.changed();
+    }
+
+    /// Moves the point at `idx`, e.g. while it is dragged.
+    /// Returns false if another point is at the new position and the point has not been moved.
+    pub fn move_point(&mut self, idx: usize, point: Vec2) -> bool {
+        if self.points.contains(&point) {
+            return false;
+        }
+        self.points[idx] = point;
+        self.changed();
+        true
+    }
+
+    /// Removes the input point set.
+    pub fn clear_points(&mut self) {
+        self.points.clear();
+        self.values.clear();
+        self.changed();
+    }
+
+    pub fn add_polygon(&mut self, polygon: Vec<Vec2>) {
+        self.polygons.push(polygon);
+        self.changed();
+    }
+
+    pub fn clear_polygons(&mut self) {
+        self.polygons.clear();
+        self.changed();
+    }
+
+    pub fn add_segment(&mut self, segment: Segment2) {
+        self.segments.push(segment);
+        self.changed();
+    }
+
+    /// Replaces the segment at `idx`, e.g. while one of its ends is dragged.
+    pub fn move_segment(&mut self, idx: usize, segment: Segment2) {
+        self.segments[idx] = segment;
+        self.changed();
+    }
+
+    pub fn clear_segments(&mut self) {
+        self.segments.clear();
+        self.changed();
+    }
+
+    pub fn add_mesh(&mut self, mesh: Mesh) {
+        self.meshes.push(mesh);
+        self.changed();
+    }
+
+    /// Adds a point to the last mesh or point cloud, or to a new point cloud if there is none.
+    pub fn add_vertex(&mut self, vertex: Vec3) {
+        match self.meshes.last_mut() {
+            Some(mesh) => mesh.vertices.push(vertex),
+            None => self.meshes.push(Mesh { vertices: vec![ vertex ], indices: Vec::new() }),
+        }
+        self.changed();
+    }
+
+    /// Moves the vertex at `idx` among the vertices of all the meshes and point clouds, in order.
+    pub fn move_vertex(&mut self, idx: usize, vertex: Vec3) {
+        if let Some(v) = self.meshes.iter_mut().flat_map(|mesh| mesh.vertices.iter_mut()).nth(idx) {
+            *v = vertex;
+            self.changed();
+        }
+    }
+
+    /// Moves the vertices of the meshes and point clouds with `f`.
+    pub fn transform_meshes<F: Fn(Vec3) -> Vec3>(&mut self, f: F) {
+        for mesh in &mut self.meshes {
+            for vertex in &mut mesh.vertices {
+                *vertex = f(*vertex);
+            }
+        }
+        self.changed();
+    }
+
+    /// Removes the meshes and point clouds.
+    pub fn clear_meshes(&mut self) {
+        self.meshes.clear();
+        self.changed();
+    }
+
+    /// Adds a geometry read from Well-Known Text: points to the point set,
+    /// linestrings as segments and the exterior rings of polygons as polygons.
+    pub fn add_geometry(&mut self, geometry: Geometry) {
+        match geometry {
+            Geometry::Point(point) => self.add_points(point),
+            Geometry::MultiPoint(points) => self.add_points(points),
+            Geometry::LineString(points) => {
+                for segment in points.windows(2) {
+                    self.segments.push(Segment2::new(segment[0], segment[1]));
+                }
+                self.changed();
+            },
+            Geometry::Polygon(rings) => self.add_polygon_rings(rings),
+            Geometry::MultiPolygon(polygons) => {
+                for rings in polygons {
+                    self.add_polygon_rings(rings);
+                }
+            },
+        }
+    }
+
+    fn add_polygon_rings(&mut self, mut rings: Vec<Vec<Vec2>>) {
+        if rings.len() > 1 {
+            log::warn!("Ignoring the {} holes of a polygon", rings.len() - 1);
+        }
+        if !rings.is_empty() {
+            self.add_polygon(rings.swap_remove(0));
+        }
+    }
+
+    /// Removes all the input geometry.
+    pub fn clear(&mut self) {
+        self.points.clear();
+        self.values.clear();
+        self.polygons.clear();
+        self.segments.clear();
+        self.meshes.clear();
+        self.changed();
+    }
+
+    /// Returns the 2D geometry of the scene: its points, segments and polygons, in this order.
+    pub fn primitives(&self) -> Vec<Primitive> {
+        self.points.iter().cloned().map(Primitive::from)
+            .chain(self.segments.iter().cloned().map(Primitive::from))
+            .chain(self.polygons.iter().cloned().map(Primitive::from))
+            .collect()
+    }
+
+    /// Returns the last 2D convex hull computed from the points, segments and polygons.
+    pub fn hull(&self) -> &[Vec2] {
+        &self.hull
+    }
+
+    pub fn set_hull(&mut self, hull: Vec<Vec2>) {
+        self.hull = hull;
+    }
+
+    /// Returns the last triangulation computed from the points.
+    pub fn triangulation(&self) -> Option<&Triangulation> {
+        self.triangulation.as_ref()
+    }
+
+    pub fn set_triangulation(&mut self, triangulation: Triangulation) {
+        self.triangulation = Some(triangulation);
+    }
+
+    /// Returns the last 3D convex hull computed from the vertices of the meshes.
+    pub fn hull_3d(&self) -> Option<&Mesh> {
+        self.hull_3d.as_ref()
+    }
+
+    pub fn set_hull_3d(&mut self, hull: Mesh) {
+        self.hull_3d = Some(hull);
+    }
+
+    /// Notifies the observers and discards the results computed from the previous input.
+    fn changed(&mut self) {
+        self.revision += 1;
+        self.hull.clear();
+        self.triangulation = None;
+        self.hull_3d = None;
+    }
+}
+
+/// Returns whether to keep each point, so that a single one of the points equal to each other is kept.
+/// Equal points have close x coordinates, so only the neighbours in the x order are compared.
+fn unique(points: &[Vec2]) -> Vec<bool> {
+    let mut order = (0..points.len()).collect::<Vec<_>>();
+    order.sort_by(|&a, &b| points[a].x.partial_cmp(&points[b].x).unwrap_or(Ordering::Equal));
+
+    let mut keep = vec![true; points.len()];
+    for (i, &idx) in order.iter().enumerate() {
+        let p = points[idx];
+        keep[idx] = order[..i]
+                        .iter()
+                        .rev()
+                        .take_while(|&&prev| math::cmp_f32(points[prev].x, p.x))
+                        .all(|&prev| !keep[prev] || points[prev] != p);
+    }
+    keep
+}
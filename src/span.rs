@@ -0,0 +1,45 @@
+//! Timing of the phases of the algorithms through the `log` crate,
+//! shown in the console of the viewer and on the standard error of `cgeom -v`.
+
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+/// Starts a [`Span`](span/struct.Span.html) logged with the path of the calling module as target.
+macro_rules! span {
+    ($name:expr) => {
+        $crate::span::Span::enter(module_path!(), $name)
+    };
+}
+
+/// A phase of an algorithm, its duration is logged at the debug level when the span is dropped.
+/// Without the `std` feature there is no clock and only the end of the phase is logged.
+pub struct Span {
+    target: &'static str,
+    name: &'static str,
+    #[cfg(feature = "std")]
+    start: Instant,
+}
+
+impl Span {
+    pub fn enter(target: &'static str, name: &'static str) -> Self {
+        log::trace!(target: target, "{}: started", name);
+        Self {
+            target,
+            name,
+            #[cfg(feature = "std")]
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Drop for Span {
+    #[cfg(feature = "std")]
+    fn drop(&mut self) {
+        log::debug!(target: self.target, "{}: {} µs", self.name, self.start.elapsed().as_micros());
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn drop(&mut self) {
+        log::debug!(target: self.target, "{}: done", self.name);
+    }
+}
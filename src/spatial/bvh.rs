@@ -0,0 +1,297 @@
+use crate::math::{ Vec2, Rect, Segment2, morton };
+
+use alloc::{ vec, vec::Vec };
+
+/// The nodes with at most this many segments may become leaves
+const MAX_LEAF_SIZE: usize = 4;
+/// Cost of visiting a node relative to the cost of testing a segment, for the surface area heuristic
+const TRAVERSAL_COST: f32 = 1.0;
+/// Margin added around the boxes tested against rays, so that the rounding errors of the test
+/// never skip a segment hit near the border of its box
+const MARGIN: f32 = 1e-5;
+
+/// A node of the hierarchy: its box contains the segments `indices[start..start + count]`.
+#[derive(Copy, Clone, Debug)]
+pub struct Node {
+    pub bounds: Rect,
+    pub depth: usize,
+    start: usize,
+    count: usize,
+    /// Positions of the children in the nodes, `None` for a leaf
+    children: Option<(usize, usize)>,
+}
+
+impl Node {
+    /// Returns the number of segments in the box of the node.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    pub fn is_leaf(&self) -> bool {
+        self.children.is_none()
+    }
+}
+
+/// Bounding volume hierarchy over a set of segments, to find the ones hit by a ray or crossing a segment
+/// without testing all of them. Every node is split where the surface area heuristic expects queries
+/// to be the cheapest: the probability of a random line crossing a box is proportional to its perimeter.
+pub struct Bvh<'a> {
+    segments: &'a [Segment2],
+    /// The root is the first node
+    nodes: Vec<Node>,
+    /// Indices of the segments, the segments of a node are contiguous
+    indices: Vec<usize>,
+}
+
+impl<'a> Bvh<'a> {
+    pub fn new(segments: &'a [Segment2]) -> Self {
+        let mut bvh = Self {
+            segments,
+            nodes: Vec::new(),
+            indices: (0..segments.len()).collect(),
+        };
+        if !segments.is_empty() {
+            bvh.build(0, segments.len(), 0);
+        }
+        bvh
+    }
+
+    /// Builds the hierarchy faster than [`new`](#method.new), but with boxes that are more expensive to query:
+    /// the segments are sorted by the Morton codes of the centers of their boxes, and every node is split
+    /// where the highest bit that differs between its codes changes, which halves the largest side of its grid cell.
+    pub fn new_linear(segments: &'a [Segment2]) -> Self {
+        let centers = segments.iter().map(|segment| segment.bounding_rect().center()).collect::<Vec<_>>();
+        let mut bvh = Self {
+            segments,
+            nodes: Vec::new(),
+            indices: morton::order2(&centers),
+        };
+        if let Some(bounds) = Rect::from_points(&centers) {
+            let codes = bvh.indices.iter().map(|&idx| morton::code2(centers[idx], &bounds)).collect::<Vec<_>>();
+            bvh.build_linear(&codes, 0, segments.len(), 0);
+        }
+        bvh
+    }
+
+    /// Returns the nodes, the root first and every node before its children.
+    pub fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+
+    /// Returns the number of levels of the hierarchy.
+    pub fn depth(&self) -> usize {
+        self.nodes.iter().map(|node| node.depth + 1).max().unwrap_or(0)
+    }
+
+    fn bounds(&self, indices: &[usize]) -> Rect {
+        let first = self.segments[indices[0]].tolerant_bounding_rect();
+        indices.iter().fold(first, |bounds, &idx| bounds.union(&self.segments[idx].tolerant_bounding_rect()))
+    }
+
+    /// Adds the node of the segments `indices[start..end]` and builds its subtree, returns its position.
+    fn build(&mut self, start: usize, end: usize, depth: usize) -> usize {
+        let bounds = self.bounds(&self.indices[start..end]);
+        let position = self.nodes.len();
+        self.nodes.push(Node {
+            bounds,
+            depth,
+            start,
+            count: end - start,
+            children: None,
+        });
+
+        let count = end - start;
+        if count <= 1 {
+            return position;
+        }
+
+        // Sorts the segments by the center of their box along each axis, and keeps the cheapest split:
+        // the cost of a child is its number of segments times the probability of visiting it
+        let segments = self.segments;
+        let center = |idx: usize, axis: usize| {
+            let rect = segments[idx].tolerant_bounding_rect();
+            if axis == 0 { rect.min.x + rect.max.x } else { rect.min.y + rect.max.y }
+        };
+        let mut best: Option<(f32, usize, usize)> = None;
+        for axis in 0..2 {
+            let indices = &mut self.indices[start..end];
+            indices.sort_by(|&a, &b| center(a, axis).total_cmp(&center(b, axis)));
+
+            // Perimeters of the boxes of the segments after each position
+            let mut after = vec![ 0.0; count ];
+            let mut rect = segments[indices[count - 1]].tolerant_bounding_rect();
+            for i in (1..count).rev() {
+                rect = rect.union(&segments[indices[i]].tolerant_bounding_rect());
+                after[i] = rect.perimeter();
+            }
+            let mut rect = segments[indices[0]].tolerant_bounding_rect();
+            for i in 1..count {
+                rect = rect.union(&segments[indices[i - 1]].tolerant_bounding_rect());
+                let cost = rect.perimeter() * i as f32 + after[i] * (count - i) as f32;
+                let cheaper = match best {
+                    Some((best_cost, _, _)) => cost < best_cost,
+                    None => true,
+                };
+                if cheaper {
+                    best = Some((cost, axis, i));
+                }
+            }
+        }
+
+        let (cost, axis, split) = best.expect("No split");
+        let leaf_cost = bounds.perimeter() * count as f32;
+        if count <= MAX_LEAF_SIZE && TRAVERSAL_COST * bounds.perimeter() + cost >= leaf_cost {
+            return position;
+        }
+
+        if axis == 0 {
+            let indices = &mut self.indices[start..end];
+            indices.sort_by(|&a, &b| center(a, 0).total_cmp(&center(b, 0)));
+        }
+        let left = self.build(start, start + split, depth + 1);
+        let right = self.build(start + split, end, depth + 1);
+        self.nodes[position].children = Some((left, right));
+        position
+    }
+
+    /// Adds the node of the segments `indices[start..end]`, whose sorted Morton codes are `codes[start..end]`,
+    /// and builds its subtree, returns its position.
+    fn build_linear(&mut self, codes: &[u32], start: usize, end: usize, depth: usize) -> usize {
+        let bounds = self.bounds(&self.indices[start..end]);
+        let position = self.nodes.len();
+        self.nodes.push(Node {
+            bounds,
+            depth,
+            start,
+            count: end - start,
+            children: None,
+        });
+
+        let count = end - start;
+        if count <= MAX_LEAF_SIZE {
+            return position;
+        }
+
+        // The codes share their bits above the highest one that differs, the first ones have it at 0
+        let (first, last) = (codes[start], codes[end - 1]);
+        let split = if first == last {
+            count / 2
+        } else {
+            let bit = 1 << (31 - (first ^ last).leading_zeros());
+            codes[start..end].iter().position(|&code| code & bit != 0).expect("No code with the bit")
+        };
+        let left = self.build_linear(codes, start, start + split, depth + 1);
+        let right = self.build_linear(codes, start + split, end, depth + 1);
+        self.nodes[position].children = Some((left, right));
+        position
+    }
+
+    /// Returns the index of the first segment hit by the ray from `origin` in `direction`
+    /// and the parameter of the hit point, or `None` if the ray misses every segment.
+    /// Gives the same result as [`spatial::raycast`](../fn.raycast.html), except between segments hit at the same point.
+    pub fn raycast(&self, origin: Vec2, direction: Vec2) -> Option<(usize, f32)> {
+        self.raycast_visiting(origin, direction, |_| {})
+    }
+
+    /// Same as [`raycast`](#method.raycast), calling `visit` with the index of every segment tested against the ray.
+    pub fn raycast_visiting<F: FnMut(usize)>(&self, origin: Vec2, direction: Vec2, mut visit: F) -> Option<(usize, f32)> {
+        let mut best: Option<(usize, f32)> = None;
+        let mut stack = match self.nodes.first().and_then(|root| entry(&root.bounds, origin, direction)) {
+            Some(t) => vec![ (0, t) ],
+            None => return None,
+        };
+        while let Some((position, t)) = stack.pop() {
+            // The ray enters the box after the closest hit
+            if let Some((_, best_t)) = best {
+                if t > best_t {
+                    continue;
+                }
+            }
+            let node = &self.nodes[position];
+            match node.children {
+                Some((left, right)) => {
+                    // Pushes the farthest child first, to visit the closest one first
+                    let mut children = [ left, right ]
+                        .iter()
+                        .filter_map(|&child| entry(&self.nodes[child].bounds, origin, direction).map(|t| (child, t)))
+                        .collect::<Vec<_>>();
+                    children.sort_by(|a, b| b.1.total_cmp(&a.1));
+                    stack.extend(children);
+                },
+                None => {
+                    for &idx in &self.indices[node.start..node.start + node.count] {
+                        visit(idx);
+                        let t = match self.segments[idx].ray_intersection(origin, direction) {
+                            Some(t) => t,
+                            None => continue,
+                        };
+                        let closer = match best {
+                            Some((_, best_t)) => t < best_t,
+                            None => true,
+                        };
+                        if closer {
+                            best = Some((idx, t));
+                        }
+                    }
+                },
+            }
+        }
+        best
+    }
+
+    /// Returns the indices of the segments that intersect `segment`, in increasing order.
+    /// Gives the same result as [`spatial::intersecting`](../fn.intersecting.html).
+    pub fn intersecting(&self, segment: &Segment2) -> Vec<usize> {
+        self.intersecting_visiting(segment, |_| {})
+    }
+
+    /// Same as [`intersecting`](#method.intersecting), calling `visit` with the index of every segment
+    /// tested against `segment`.
+    pub fn intersecting_visiting<F: FnMut(usize)>(&self, segment: &Segment2, mut visit: F) -> Vec<usize> {
+        let bounds = segment.tolerant_bounding_rect();
+        let mut found = Vec::new();
+        let mut stack = if self.nodes.is_empty() { Vec::new() } else { vec![ 0 ] };
+        while let Some(position) = stack.pop() {
+            let node = &self.nodes[position];
+            if !node.bounds.overlaps(&bounds) {
+                continue;
+            }
+            match node.children {
+                Some((left, right)) => stack.extend_from_slice(&[ right, left ]),
+                None => {
+                    for &idx in &self.indices[node.start..node.start + node.count] {
+                        visit(idx);
+                        if self.segments[idx].intersects(segment) {
+                            found.push(idx);
+                        }
+                    }
+                },
+            }
+        }
+        found.sort_unstable();
+        found
+    }
+}
+
+/// Returns the parameter where the ray from `origin` in `direction` enters `rect` widened by [`MARGIN`],
+/// 0 if it starts inside, or `None` if it misses it.
+fn entry(rect: &Rect, origin: Vec2, direction: Vec2) -> Option<f32> {
+    let (mut t_min, mut t_max) = (0.0f32, f32::INFINITY);
+    for &(o, d, min, max) in &[ (origin.x, direction.x, rect.min.x - MARGIN, rect.max.x + MARGIN),
+                                     (origin.y, direction.y, rect.min.y - MARGIN, rect.max.y + MARGIN) ] {
+        if d == 0.0 {
+            if o < min || o > max {
+                return None;
+            }
+            continue;
+        }
+        let (t0, t1) = ((min - o) / d, (max - o) / d);
+        t_min = t_min.max(t0.min(t1));
+        t_max = t_max.min(t0.max(t1));
+    }
+    if t_min <= t_max { Some(t_min) } else { None }
+}
@@ -0,0 +1,118 @@
+use crate::math::Vec2;
+
+use alloc::{ vec, vec::Vec };
+
+/// Uniform grid over a set of points, to find the nearest point without checking all of them.
+/// Fast when the points are spread evenly, the grid has about one cell per point.
+pub struct PointGrid<'a> {
+    points: &'a [Vec2],
+    /// Bottom left corner of the grid
+    origin: Vec2,
+    cell_size: f32,
+    columns: usize,
+    rows: usize,
+    /// The indices of the points of cell `c` are `indices[starts[c]..starts[c + 1]]`
+    starts: Vec<usize>,
+    indices: Vec<usize>,
+}
+
+impl<'a> PointGrid<'a> {
+    pub fn new(points: &'a [Vec2]) -> Self {
+        let (mut min, mut max) = (Vec2::new(0.0, 0.0), Vec2::new(0.0, 0.0));
+        if let Some(first) = points.first() {
+            min = *first;
+            max = *first;
+        }
+        for p in points {
+            min = Vec2::new(min.x.min(p.x), min.y.min(p.y));
+            max = Vec2::new(max.x.max(p.x), max.y.max(p.y));
+        }
+
+        let size = &max - &min;
+        let cell_size = crate::math::float::sqrt(size.x * size.y / points.len().max(1) as f32)
+                            .max(size.x.max(size.y) / points.len().max(1) as f32)
+                            .max(1e-6);
+        let columns = (size.x / cell_size) as usize + 1;
+        let rows = (size.y / cell_size) as usize + 1;
+
+        let mut grid = Self {
+            points,
+            origin: min,
+            cell_size,
+            columns,
+            rows,
+            starts: vec![ 0; columns * rows + 1 ],
+            indices: vec![ 0; points.len() ],
+        };
+
+        // Counting sort of the points by cell
+        let cells = points.iter().map(|&p| grid.cell(p)).collect::<Vec<_>>();
+        for &cell in &cells {
+            grid.starts[cell + 1] += 1;
+        }
+        for cell in 0..columns * rows {
+            grid.starts[cell + 1] += grid.starts[cell];
+        }
+        let mut next = grid.starts.clone();
+        for (idx, &cell) in cells.iter().enumerate() {
+            grid.indices[next[cell]] = idx;
+            next[cell] += 1;
+        }
+        grid
+    }
+
+    /// Returns the column and the row of the cell containing `p`, or of the closest cell if it is outside the grid.
+    fn coords(&self, p: Vec2) -> (usize, usize) {
+        let column = ((p.x - self.origin.x) / self.cell_size).max(0.0) as usize;
+        let row = ((p.y - self.origin.y) / self.cell_size).max(0.0) as usize;
+        (column.min(self.columns - 1), row.min(self.rows - 1))
+    }
+
+    fn cell(&self, p: Vec2) -> usize {
+        let (column, row) = self.coords(p);
+        row * self.columns + column
+    }
+
+    /// Returns the index of the point closest to `query`, or `None` if there is no point.
+    /// Gives the same result as [`spatial::nearest`](../fn.nearest.html), except between points at the same distance.
+    pub fn nearest(&self, query: Vec2) -> Option<usize> {
+        if self.points.is_empty() {
+            return None;
+        }
+        let (column, row) = self.coords(query);
+        let mut best: Option<(usize, f32)> = None;
+
+        // Visits the rings of cells around the cell of the query, the points beyond ring `r`
+        // are at least `r` cells away from the query
+        for ring in 0..self.columns.max(self.rows) {
+            if let Some((_, distance)) = best {
+                if distance <= (ring as f32 - 1.0) * self.cell_size {
+                    break;
+                }
+            }
+
+            let (min_column, max_column) = (column as isize - ring as isize, column as isize + ring as isize);
+            let (min_row, max_row) = (row as isize - ring as isize, row as isize + ring as isize);
+            for r in min_row.max(0)..=max_row.min(self.rows as isize - 1) {
+                for c in min_column.max(0)..=max_column.min(self.columns as isize - 1) {
+                    // Only the border of the ring, the inside was visited before
+                    if r != min_row && r != max_row && c != min_column && c != max_column {
+                        continue;
+                    }
+                    let cell = r as usize * self.columns + c as usize;
+                    for &idx in &self.indices[self.starts[cell]..self.starts[cell + 1]] {
+                        let distance = (&self.points[idx] - &query).length();
+                        let closer = match best {
+                            Some((_, best_distance)) => distance < best_distance,
+                            None => true,
+                        };
+                        if closer {
+                            best = Some((idx, distance));
+                        }
+                    }
+                }
+            }
+        }
+        best.map(|(idx, _)| idx)
+    }
+}
@@ -0,0 +1,149 @@
+use crate::math::{ Vec2, Rect, Segment2 };
+
+use alloc::vec::Vec;
+
+/// Balanced 2-d tree over a set of points, to find the nearest point by pruning the cells farther than the best candidate.
+/// Every node splits its cell at the median of its points, along x and y alternately.
+pub struct KdTree<'a> {
+    points: &'a [Vec2],
+    /// Implicit tree: the node of the range `start..end` is the point at the middle of the range,
+    /// the points before it are below its split and the points after it above
+    indices: Vec<usize>,
+}
+
+/// Returns the coordinate the nodes at `depth` split along.
+fn coord(p: Vec2, depth: usize) -> f32 {
+    if splits_x(depth) { p.x } else { p.y }
+}
+
+/// Returns whether the nodes at `depth` split along x, the root does.
+fn splits_x(depth: usize) -> bool {
+    depth % 2 != 1
+}
+
+impl<'a> KdTree<'a> {
+    pub fn new(points: &'a [Vec2]) -> Self {
+        let mut indices = (0..points.len()).collect::<Vec<_>>();
+        Self::build(points, &mut indices, 0);
+        Self {
+            points,
+            indices,
+        }
+    }
+
+    fn build(points: &[Vec2], indices: &mut [usize], depth: usize) {
+        if indices.len() <= 1 {
+            return;
+        }
+        let mid = indices.len() / 2;
+        indices.select_nth_unstable_by(mid, |&a, &b| {
+            coord(points[a], depth).total_cmp(&coord(points[b], depth))
+        });
+        let (below, above) = indices.split_at_mut(mid);
+        Self::build(points, below, depth + 1);
+        Self::build(points, &mut above[1..], depth + 1);
+    }
+
+    /// Returns the index of the point closest to `query`, or `None` if there is no point.
+    /// Gives the same result as [`spatial::nearest`](../fn.nearest.html), except between points at the same distance.
+    pub fn nearest(&self, query: Vec2) -> Option<usize> {
+        self.nearest_visiting(query, |_| {})
+    }
+
+    /// Same as [`nearest`](#method.nearest), calling `visit` with the index of every point compared with the query.
+    pub fn nearest_visiting<F: FnMut(usize)>(&self, query: Vec2, mut visit: F) -> Option<usize> {
+        let mut best = None;
+        self.search(query, 0, self.indices.len(), 0, &mut best, &mut visit);
+        best.map(|(idx, _)| idx)
+    }
+
+    /// Visits the node of the range `start..end`, then the side of its split containing the query,
+    /// and the other side only if the split is closer than the best candidate.
+    fn search<F: FnMut(usize)>(&self, query: Vec2, start: usize, end: usize, depth: usize,
+                               best: &mut Option<(usize, f32)>, visit: &mut F) {
+        if start >= end {
+            return;
+        }
+        let mid = (start + end) / 2;
+        let idx = self.indices[mid];
+        let p = self.points[idx];
+        visit(idx);
+
+        // Whether the squared distance `d` is smaller than the one of the best candidate
+        let closer = |best: &Option<(usize, f32)>, d: f32| match *best {
+            Some((_, best_distance)) => d < best_distance,
+            None => true,
+        };
+
+        let distance = (&p - &query).sqr_length();
+        if closer(best, distance) {
+            *best = Some((idx, distance));
+        }
+
+        let offset = coord(query, depth) - coord(p, depth);
+        let (near, far) = if offset < 0.0 { ((start, mid), (mid + 1, end)) } else { ((mid + 1, end), (start, mid)) };
+        self.search(query, near.0, near.1, depth + 1, best, visit);
+        if closer(best, offset * offset) {
+            self.search(query, far.0, far.1, depth + 1, best, visit);
+        }
+    }
+
+    /// Returns the indices of the points at most `radius` away from `query`, in increasing order.
+    /// Gives the same result as [`spatial::within`](../fn.within.html).
+    pub fn within(&self, query: Vec2, radius: f32) -> Vec<usize> {
+        let mut found = Vec::new();
+        self.search_within(query, radius, 0, self.indices.len(), 0, &mut found);
+        found.sort_unstable();
+        found
+    }
+
+    /// Visits the node of the range `start..end`, then the sides of its split that the disk around the query overlaps.
+    fn search_within(&self, query: Vec2, radius: f32, start: usize, end: usize, depth: usize, found: &mut Vec<usize>) {
+        if start >= end {
+            return;
+        }
+        let mid = (start + end) / 2;
+        let idx = self.indices[mid];
+        let p = self.points[idx];
+        if (&p - &query).sqr_length() <= radius * radius {
+            found.push(idx);
+        }
+
+        let offset = coord(query, depth) - coord(p, depth);
+        if offset <= radius {
+            self.search_within(query, radius, start, mid, depth + 1, found);
+        }
+        if offset >= -radius {
+            self.search_within(query, radius, mid + 1, end, depth + 1, found);
+        }
+    }
+
+    /// Returns the splitting lines of the nodes, clipped to their cells inside `bounds`.
+    pub fn splits(&self, bounds: &Rect) -> Vec<Segment2> {
+        let mut splits = Vec::with_capacity(self.indices.len());
+        self.collect_splits(0, self.indices.len(), 0, *bounds, &mut splits);
+        splits
+    }
+
+    fn collect_splits(&self, start: usize, end: usize, depth: usize, cell: Rect, splits: &mut Vec<Segment2>) {
+        if start >= end {
+            return;
+        }
+        let mid = (start + end) / 2;
+        let p = self.points[self.indices[mid]];
+        let (mut below, mut above) = (cell, cell);
+        if splits_x(depth) {
+            let x = p.x.max(cell.min.x).min(cell.max.x);
+            splits.push(Segment2::new(Vec2::new(x, cell.min.y), Vec2::new(x, cell.max.y)));
+            below.max.x = x;
+            above.min.x = x;
+        } else {
+            let y = p.y.max(cell.min.y).min(cell.max.y);
+            splits.push(Segment2::new(Vec2::new(cell.min.x, y), Vec2::new(cell.max.x, y)));
+            below.max.y = y;
+            above.min.y = y;
+        }
+        self.collect_splits(start, mid, depth + 1, below, splits);
+        self.collect_splits(mid + 1, end, depth + 1, above, splits);
+    }
+}
@@ -0,0 +1,96 @@
+//! Queries over sets of points.
+//! The functions of this module are the brute force references,
+//! they check every point and are meant to validate faster spatial structures.
+
+use crate::math::{ Vec2, Rect, Segment2 };
+
+use alloc::vec::Vec;
+
+pub mod grid;
+pub use grid::PointGrid;
+pub mod kd_tree;
+pub use kd_tree::KdTree;
+pub mod bvh;
+pub use bvh::Bvh;
+pub mod sweep_and_prune;
+pub use sweep_and_prune::SweepAndPrune;
+pub mod spatial_hash;
+pub use spatial_hash::SpatialHash;
+
+/// Returns the index of the point closest to `query`, or `None` if there is no point.
+pub fn nearest(points: &[Vec2], query: Vec2) -> Option<usize> {
+    points
+        .iter()
+        .map(|p| (p - &query).sqr_length())
+        .enumerate()
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(idx, _)| idx)
+}
+
+/// Returns the indices of the points inside `rect`, borders included.
+pub fn in_rect(points: &[Vec2], rect: &Rect) -> Vec<usize> {
+    points
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| rect.contains(**p))
+        .map(|(idx, _)| idx)
+        .collect()
+}
+
+/// Returns the indices of the points at most `radius` away from `query`, in increasing order.
+pub fn within(points: &[Vec2], query: Vec2, radius: f32) -> Vec<usize> {
+    points
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| (*p - &query).sqr_length() <= radius * radius)
+        .map(|(idx, _)| idx)
+        .collect()
+}
+
+/// Returns the index of the first segment hit by the ray from `origin` in `direction`
+/// and the parameter of the hit point, or `None` if the ray misses every segment.
+pub fn raycast(segments: &[Segment2], origin: Vec2, direction: Vec2) -> Option<(usize, f32)> {
+    segments
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, segment)| segment.ray_intersection(origin, direction).map(|t| (idx, t)))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+/// Returns the indices of the segments that intersect `segment`, in increasing order.
+pub fn intersecting(segments: &[Segment2], segment: &Segment2) -> Vec<usize> {
+    segments
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| s.intersects(segment))
+        .map(|(idx, _)| idx)
+        .collect()
+}
+
+/// Returns the pairs of rectangles that overlap, borders included, in lexicographic order.
+pub fn overlapping_pairs(rects: &[Rect]) -> Vec<(usize, usize)> {
+    let mut pairs = Vec::new();
+    for (i, a) in rects.iter().enumerate() {
+        for (j, b) in rects.iter().enumerate().skip(i + 1) {
+            if a.overlaps(b) {
+                pairs.push((i, j));
+            }
+        }
+    }
+    pairs
+}
+
+/// Returns the pairs of circles that overlap, borders included, in lexicographic order,
+/// given the center and the radius of each circle.
+pub fn overlapping_circles(centers: &[Vec2], radii: &[f32]) -> Vec<(usize, usize)> {
+    let mut pairs = Vec::new();
+    for (i, a) in centers.iter().enumerate() {
+        for (j, b) in centers.iter().enumerate().skip(i + 1) {
+            let distance = radii[i] + radii[j];
+            if (a - b).sqr_length() <= distance * distance {
+                pairs.push((i, j));
+            }
+        }
+    }
+    pairs
+}
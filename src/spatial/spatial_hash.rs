@@ -0,0 +1,129 @@
+use crate::math::{ Vec2, float };
+
+use alloc::{ vec, vec::Vec };
+
+/// Uniform grid over moving circles, with the cells hashed into a fixed number of buckets so that the grid is unbounded
+/// and its memory only depends on the number of circles. Each circle is only compared to the circles of the cells
+/// around its own, which finds the overlapping pairs in about linear time when the cells are as large as the circles.
+/// A circle moving within its cell costs nothing to update, one changing cell is moved to another bucket.
+pub struct SpatialHash {
+    cell_size: f32,
+    /// Indices of the circles of all the cells hashed into each bucket
+    buckets: Vec<Vec<usize>>,
+    /// Cell and bucket of each circle
+    cells: Vec<((i32, i32), usize)>,
+}
+
+impl SpatialHash {
+    /// Creates an empty hash with cells of `cell_size`, at least the diameter of the largest circle,
+    /// and enough buckets for `capacity` circles.
+    pub fn new(cell_size: f32, capacity: usize) -> Self {
+        Self {
+            cell_size,
+            buckets: vec![ Vec::new(); (2 * capacity).next_power_of_two() ],
+            cells: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    /// Returns the number of circles.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Returns the column and the row of the cell containing `p`.
+    pub fn cell(&self, p: Vec2) -> (i32, i32) {
+        (float::floor(p.x / self.cell_size) as i32, float::floor(p.y / self.cell_size) as i32)
+    }
+
+    /// Returns the bucket the cell is hashed into, with the large primes of Teschner et al.
+    fn bucket(&self, (column, row): (i32, i32)) -> usize {
+        let hash = (column as u32).wrapping_mul(73_856_093) ^ (row as u32).wrapping_mul(19_349_663);
+        hash as usize & (self.buckets.len() - 1)
+    }
+
+    /// Adds a circle centered on `p` and returns its index, the circles are numbered in the order they are added.
+    pub fn insert(&mut self, p: Vec2) -> usize {
+        let idx = self.cells.len();
+        let cell = self.cell(p);
+        let bucket = self.bucket(cell);
+        self.buckets[bucket].push(idx);
+        self.cells.push((cell, bucket));
+        idx
+    }
+
+    /// Moves the circle `idx` to `p`. Returns true if it changed cell.
+    pub fn update(&mut self, idx: usize, p: Vec2) -> bool {
+        let cell = self.cell(p);
+        let (old_cell, old_bucket) = self.cells[idx];
+        if cell == old_cell {
+            return false;
+        }
+        let bucket = self.bucket(cell);
+        if bucket != old_bucket {
+            let entries = &mut self.buckets[old_bucket];
+            let position = entries.iter().position(|&other| other == idx).expect("Circle missing from its bucket");
+            entries.swap_remove(position);
+            self.buckets[bucket].push(idx);
+        }
+        self.cells[idx] = (cell, bucket);
+        true
+    }
+
+    /// Returns the circles of the cell containing `p` and of the 8 cells around it,
+    /// with some of distant cells hashed into the same buckets.
+    pub fn around(&self, p: Vec2) -> Vec<usize> {
+        let (column, row) = self.cell(p);
+        let mut buckets = Vec::with_capacity(9);
+        for r in row - 1..=row + 1 {
+            for c in column - 1..=column + 1 {
+                buckets.push(self.bucket((c, r)));
+            }
+        }
+        buckets.sort_unstable();
+        buckets.dedup();
+        buckets.into_iter().flat_map(|bucket| self.buckets[bucket].iter().copied()).collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.buckets.iter_mut().for_each(Vec::clear);
+        self.cells.clear();
+    }
+
+    /// Returns the pairs of circles that overlap, borders included, smallest index first,
+    /// given the center and the radius of each circle.
+    /// Gives the same pairs as [`spatial::overlapping_circles`](../fn.overlapping_circles.html), in another order.
+    pub fn pairs(&self, centers: &[Vec2], radii: &[f32]) -> Vec<(usize, usize)> {
+        let mut pairs = Vec::new();
+        let mut neighbours = Vec::with_capacity(9);
+        for (a, &((column, row), _)) in self.cells.iter().enumerate() {
+            // Several cells around may share a bucket, which is only searched once
+            neighbours.clear();
+            for r in row - 1..=row + 1 {
+                for c in column - 1..=column + 1 {
+                    neighbours.push(self.bucket((c, r)));
+                }
+            }
+            neighbours.sort_unstable();
+            neighbours.dedup();
+
+            // The buckets also hold the circles of distant cells, the distance test discards them
+            for &bucket in &neighbours {
+                for &b in self.buckets[bucket].iter().filter(|&&b| b > a) {
+                    let distance = radii[a] + radii[b];
+                    if (&centers[a] - &centers[b]).sqr_length() <= distance * distance {
+                        pairs.push((a, b));
+                    }
+                }
+            }
+        }
+        pairs
+    }
+}
@@ -0,0 +1,47 @@
+use crate::math::Rect;
+
+use alloc::vec::Vec;
+
+/// Sweep and prune over moving boxes: the boxes are sorted by their left side, and only the boxes
+/// whose horizontal extents overlap are compared. The order is kept from one call to the next,
+/// so when the boxes move a little between calls an insertion sort puts it back in order in about linear time.
+#[derive(Default)]
+pub struct SweepAndPrune {
+    /// Indices of the boxes, by increasing left side after a call to [`pairs`](#method.pairs)
+    order: Vec<usize>,
+}
+
+impl SweepAndPrune {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the pairs of boxes that overlap, borders included, smallest index first.
+    /// Gives the same pairs as [`spatial::overlapping_pairs`](../fn.overlapping_pairs.html), in another order.
+    pub fn pairs(&mut self, boxes: &[Rect]) -> Vec<(usize, usize)> {
+        if self.order.len() != boxes.len() {
+            self.order = (0..boxes.len()).collect();
+        }
+
+        // Insertion sort, fast when the order of the previous call is nearly right
+        for i in 1..self.order.len() {
+            let mut j = i;
+            while j > 0 && boxes[self.order[j - 1]].min.x > boxes[self.order[j]].min.x {
+                self.order.swap(j - 1, j);
+                j -= 1;
+            }
+        }
+
+        let mut pairs = Vec::new();
+        for (i, &a) in self.order.iter().enumerate() {
+            // The boxes after `a` in the order start after its left side, the sweep stops at the first one
+            // that starts after its right side
+            for &b in self.order[i + 1..].iter().take_while(|&&b| boxes[b].min.x <= boxes[a].max.x) {
+                if boxes[a].overlaps(&boxes[b]) {
+                    pairs.push((a.min(b), a.max(b)));
+                }
+            }
+        }
+        pairs
+    }
+}
@@ -0,0 +1,108 @@
+use core::sync::atomic::{ AtomicBool, AtomicUsize, Ordering };
+
+#[cfg(feature = "std")]
+use std::{
+    thread,
+    sync::{ Arc, mpsc },
+};
+
+/// Progress of a computation, shared between the thread doing the work and the one that started it.
+#[derive(Default)]
+pub struct Progress {
+    done: AtomicUsize,
+    total: AtomicUsize,
+    cancelled: AtomicBool,
+}
+
+impl Progress {
+    /// Sets the number of steps needed to complete the work and resets the number of steps done.
+    pub fn set_total(&self, total: usize) {
+        self.total.store(total, Ordering::Relaxed);
+        self.done.store(0, Ordering::Relaxed);
+    }
+
+    /// Sets the number of steps done so far.
+    pub fn set_done(&self, done: usize) {
+        self.done.store(done, Ordering::Relaxed);
+    }
+
+    /// Returns the completed fraction of the work, between 0.0 and 1.0.
+    pub fn fraction(&self) -> f32 {
+        let total = self.total.load(Ordering::Relaxed);
+        if total == 0 {
+            return 0.0;
+        }
+        let done = self.done.load(Ordering::Relaxed);
+        (done as f32 / total as f32).min(1.0)
+    }
+
+    /// Asks the computation to stop as soon as possible.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Long computations should check this regularly and return early when it is true.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// A computation running on a worker thread.
+/// The result is sent back over a channel and can be retrieved without blocking with [`poll()`](#method.poll).
+/// Dropping the task cancels it.
+#[cfg(feature = "std")]
+pub struct Task<T> {
+    progress: Arc<Progress>,
+    receiver: mpsc::Receiver<T>,
+}
+
+#[cfg(feature = "std")]
+impl<T: Send + 'static> Task<T> {
+    /// Runs `work` on a new thread. `work` returns `None` when it has been cancelled.
+    pub fn spawn<F>(work: F) -> Self
+    where F: FnOnce(&Progress) -> Option<T> + Send + 'static {
+        let progress = Arc::new(Progress::default());
+        let (sender, receiver) = mpsc::channel();
+
+        let worker_progress = Arc::clone(&progress);
+        thread::spawn(move || {
+            if let Some(result) = work(&worker_progress) {
+                // The task may have been dropped in the meantime, nobody wants the result then
+                let _ = sender.send(result);
+            }
+        });
+
+        Self {
+            progress,
+            receiver,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> Task<T> {
+    /// Returns the result of the computation if it is done.
+    pub fn poll(&self) -> Option<T> {
+        self.receiver.try_recv().ok()
+    }
+
+    /// Blocks until the computation is done, returns `None` if it was cancelled.
+    pub fn wait(self) -> Option<T> {
+        self.receiver.recv().ok()
+    }
+
+    pub fn progress(&self) -> &Progress {
+        &self.progress
+    }
+
+    pub fn cancel(&self) {
+        self.progress.cancel();
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> Drop for Task<T> {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
@@ -1,29 +1,35 @@
-#[cfg(test)]
 use crate::{
-    math::Vec2,
-    algorithms::*,
+    math::{ Vec2, Rect },
+    algorithms::{ *, incremental_2d_triangulation::EdgeFlippingSteps },
+    spatial,
+    task::Progress,
 };
+#[cfg(feature = "std")]
+use crate::task::Task;
+#[cfg(feature = "gui")]
+use crate::ui::ExecTimeHistory;
 
-#[cfg(test)]
 use std::time::Instant;
+#[cfg(feature = "gui")]
+use std::time::Duration;
 
 #[test]
 fn jarvis_march_basic() {
     let points = vec![
         Vec2::new(0.1328125, 0.2265625),
         Vec2::new(-0.123046875, 0.080729164),
-        Vec2::new(0.26953125, 0.45833334), // 3
+        Vec2::new(0.26953125, 0.45833334), // 4
         Vec2::new(0.15429688, 0.390625),
         Vec2::new(0.001953125, 0.2890625),
         Vec2::new(-0.119140625, 0.38802084),
-        Vec2::new(-0.1484375, -0.015625), // 5
+        Vec2::new(-0.1484375, -0.015625), // 2
         Vec2::new(-0.203125, 0.20833333),
-        Vec2::new(0.1953125, 0.020833334), // 4
+        Vec2::new(0.1953125, 0.020833334), // 3
         Vec2::new(0.001953125, 0.1484375),
-        Vec2::new(-0.2421875, 0.47135416), // 2
+        Vec2::new(-0.2421875, 0.47135416), // 5
         Vec2::new(-0.34375, 0.17447917), // 1
     ];
-    let hull_indices = [ 11, 10, 2, 8, 6 ];
+    let hull_indices = [ 11, 6, 8, 2, 10 ];
 
     let timer = Instant::now();
     let hull = JarvisMarch::march(points.iter());
@@ -49,14 +55,7 @@ fn graham_scan_basic() {
         Vec2::new(-0.2421875, 0.47135416), // 2
         Vec2::new(-0.34375, 0.17447917), // 1
     ];
-    // inversed because it uses a stack
-    let hull_expected = vec![
-        points[6], // 5
-        points[8], // 4
-        points[2], // 3
-        points[10], // 2
-        points[11], // 1
-    ];
+    let hull_expected = vec![ 6, 8, 2, 10, 11 ];
 
     let timer = Instant::now();
     let hull = GrahamScan::scan(&points);
@@ -103,3 +102,3138 @@ fn incremental_2d_triangulation() {
     ];
     assert_eq!(indices, expected);
 }
+
+#[test]
+fn convex_hull_2d_headless() {
+    let points = [
+        Vec2::new(-0.5, -0.5),
+        Vec2::new(0.5, -0.5),
+        Vec2::new(0.0, 0.0), // inside
+        Vec2::new(0.5, 0.5),
+        Vec2::new(-0.5, 0.5),
+    ];
+
+    let mut jarvis = JarvisMarch::new();
+    let mut graham = GrahamScan::new();
+    for &point in points.iter() {
+        assert!(jarvis.add_point(point));
+        assert!(graham.add_point(point));
+    }
+    assert!(!jarvis.add_point(points[0]));
+    assert!(!graham.add_point(points[0]));
+
+    assert_eq!(jarvis.points().len(), 5);
+    assert_eq!(jarvis.hull(), &[ 0, 1, 3, 4 ][..]);
+    assert_eq!(graham.hull().len(), 4);
+    assert!(!graham.hull().contains(&2));
+
+    jarvis.clear();
+    assert!(jarvis.points().is_empty());
+    assert!(jarvis.hull().is_empty());
+}
+
+#[test]
+fn incremental_2d_triangulation_headless() {
+    let mut triangulation = Incremental2dTriangulation::new();
+    triangulation.add_point(Vec2::new(-0.5, -0.5));
+    triangulation.add_point(Vec2::new(0.5, -0.5));
+    assert_eq!(triangulation.triangle_count(), 0);
+    triangulation.add_point(Vec2::new(0.0, 0.5));
+    assert_eq!(triangulation.triangle_count(), 1);
+    triangulation.add_point(Vec2::new(0.0, -0.2));
+    assert_eq!(triangulation.triangle_count(), 3);
+    assert!(triangulation.indices().iter().all(|&idx| idx < triangulation.points().len()));
+
+    triangulation.retriangulate(true);
+    assert_eq!(triangulation.triangle_count(), 3);
+}
+
+#[test]
+fn incremental_2d_triangulation_cancelled() {
+    let mut points = vec![
+        Vec2::new(-0.5, -0.5),
+        Vec2::new(0.5, -0.5),
+        Vec2::new(0.0, 0.5),
+        Vec2::new(0.2, 0.1),
+    ];
+
+    let progress = Progress::default();
+    progress.cancel();
+    assert_eq!(Incremental2dTriangulation::triangulate_with_progress(&mut points, &progress), None);
+}
+
+#[test]
+fn incremental_2d_triangulation_duplicates() {
+    // The degenerate triangles of the duplicates used to make the edge flipping loop forever
+    let points = [ (0.0, 2.0), (0.0, 0.0), (1.0, 2.0), (1.0, 2.0), (0.0, 2.0), (1.0, 1.0), (1.0, 1.0) ]
+        .iter()
+        .map(|&(x, y)| Vec2::new(x, y))
+        .collect::<Vec<_>>();
+    let (unique, indices) = Incremental2dTriangulation::compute(points.clone(), true, &Progress::default()).unwrap();
+    assert_eq!(unique.len(), 4);
+    assert_eq!(indices.len(), 3 * 2);
+
+    // Only the first of the coincident points is in triangles
+    let indices = Incremental2dTriangulation::delaunay(&points);
+    assert_eq!(indices.len(), 3 * 2);
+    assert!(indices.iter().all(|idx| [ 0, 1, 2, 5 ].contains(idx)));
+}
+
+#[test]
+fn triangulation_fuzz_input() {
+    // Minimized input of the triangulation fuzz target that made the edge flipping loop forever:
+    // the edge flipping flag, then 2 bytes per point, decoded like the fuzz targets do
+    let data: [u8; 15] = [ 1, 0, 32, 0, 0, 16, 32, 16, 32, 0, 32, 16, 16, 16, 16 ];
+    let points = data[1..]
+        .chunks_exact(2)
+        .map(|c| Vec2::new((c[0] as i8) as f32 / 16.0, (c[1] as i8) as f32 / 16.0))
+        .collect::<Vec<_>>();
+    let count = points.len();
+    let (points, indices) = Incremental2dTriangulation::compute(points, data[0] & 1 == 1, &Progress::default()).unwrap();
+    assert!(points.len() <= count);
+    assert_eq!(indices.len() % 3, 0);
+    assert!(indices.iter().all(|&idx| idx < points.len()));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn task_result() {
+    let task = Task::spawn(|progress| {
+        progress.set_total(4);
+        progress.set_done(2);
+        Some(progress.fraction())
+    });
+    assert_eq!(task.wait(), Some(0.5));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn task_cancel() {
+    let task = Task::spawn(|progress| {
+        while !progress.is_cancelled() {
+            std::thread::yield_now();
+        }
+        None::<()>
+    });
+    task.cancel();
+    assert!(task.progress().is_cancelled());
+    assert_eq!(task.wait(), None);
+}
+
+#[test]
+fn draw_list_tessellation() {
+    use crate::render::{ layers, DrawList, RED, WHITE };
+
+    let mut list = DrawList::new(Rect::new(Vec2::new(0.0, 0.0), Vec2::new(4.0, 2.0)), WHITE);
+    list.points(&[ Vec2::new(2.0, 1.0) ], 2.0, RED)
+        .polyline(&[ Vec2::new(0.0, 0.0), Vec2::new(4.0, 0.0), Vec2::new(4.0, 2.0) ], true, 1.0, RED)
+        .triangles(&[ Vec2::new(0.0, 0.0), Vec2::new(4.0, 0.0), Vec2::new(0.0, 2.0) ], &[ 0, 1, 2 ], RED);
+
+    // A quad per point and per segment, the triangles as they are
+    let vertices = list.tessellate(40, 20);
+    assert_eq!(vertices.len(), 6 + 3 * 6 + 3);
+    assert!(vertices.iter().all(|vertex| vertex.color == RED));
+
+    // The point is a square of 2 pixels in the center
+    let clip = |x: f32, y: f32| [ x / 20.0 - 1.0, y / 10.0 - 1.0 ];
+    assert_eq!(vertices[0].position, clip(19.0, 9.0));
+    assert_eq!(vertices[2].position, clip(21.0, 11.0));
+
+    // The corners of the view are those of clip space
+    assert_eq!(vertices[vertices.len() - 3..].iter().map(|vertex| vertex.position).collect::<Vec<_>>(),
+               vec![ [ -1.0, -1.0 ], [ 1.0, -1.0 ], [ -1.0, 1.0 ] ]);
+
+    // The hull drawn by the viewer is a closed polyline with its vertices
+    let square = [ Vec2::new(-0.5, -0.5), Vec2::new(0.5, -0.5), Vec2::new(0.5, 0.5), Vec2::new(-0.5, 0.5) ];
+    let hull = layers::convex_hull(&square);
+    assert_eq!(hull.view, layers::list().view);
+    assert_eq!(hull.tessellate(100, 100).len(), 4 * 6 + 4 * 6);
+    assert!(layers::points(&[]).tessellate(100, 100).is_empty());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn ppm_image() {
+    use crate::{ image, render::RgbImage };
+
+    let img = RgbImage::new(2, 1, vec![ 255, 0, 0, 10, 20, 30 ]);
+    assert_eq!(img.pixel(1, 0), [ 10, 20, 30 ]);
+    let mut written = Vec::new();
+    image::write_ppm(&mut written, &img).unwrap();
+    assert!(written.starts_with(b"P6"));
+    assert_eq!(image::read_ppm(&written[..]).unwrap(), img);
+    assert!(image::read_ppm("P5 1 1 255\n\x00".as_bytes()).is_err());
+    assert!(image::read_ppm(&b"P6 2 1 255\n\x00\x00\x00"[..]).is_err());
+
+    // Close colors and edges moved by a pixel look the same
+    let stripe = |x0: usize, gray: u8| {
+        let pixels = (0..8 * 8).flat_map(|i| if (x0..x0 + 2).contains(&(i % 8)) { [ gray; 3 ] } else { [ 255; 3 ] }).collect();
+        RgbImage::new(8, 8, pixels)
+    };
+    assert_eq!(stripe(3, 0).difference(&stripe(3, 0), 0.0), 0.0);
+    assert_eq!(stripe(3, 0).difference(&stripe(3, 4), 0.02), 0.0);
+    assert_eq!(stripe(3, 0).difference(&stripe(4, 0), 0.02), 0.0);
+    // Only 3 of the 8 columns have no pixel of the same color next to them in a stripe moved by 3 pixels
+    assert_eq!(stripe(3, 0).difference(&stripe(6, 0), 0.02), 24.0 / 64.0);
+    assert_eq!(stripe(3, 0).difference(&RgbImage::new(1, 1, vec![ 0; 3 ]), 0.02), 1.0);
+}
+
+#[cfg(feature = "wgpu")]
+#[test]
+fn wgpu_offscreen() {
+    use crate::render::{ DrawList, WgpuRenderer, BLUE, WHITE };
+
+    let Some(mut renderer) = WgpuRenderer::headless() else {
+        println!("No wgpu adapter, skipping the offscreen rendering");
+        return;
+    };
+
+    // A blue square in the top right quarter
+    let mut list = DrawList::new(Rect::new(Vec2::new(0.0, 0.0), Vec2::new(2.0, 2.0)), WHITE);
+    let square = [ Vec2::new(1.0, 1.0), Vec2::new(2.0, 1.0), Vec2::new(2.0, 2.0), Vec2::new(1.0, 2.0) ];
+    list.triangles(&square, &[ 0, 1, 2, 0, 2, 3 ], BLUE);
+    let img = renderer.render_to_image(&list, 64, 48).unwrap();
+    assert_eq!((img.width, img.height), (64, 48));
+
+    let blue = BLUE.map(|c| (c * 255.0).round() as u8);
+    let close = |a: [u8; 3], b: [u8; 3]| a.iter().zip(&b).all(|(&a, &b)| (a as i32 - b as i32).abs() <= 2);
+    assert!(close(img.pixel(48, 12), [ blue[0], blue[1], blue[2] ]));
+    assert!(close(img.pixel(16, 12), [ 255; 3 ]));
+    assert!(close(img.pixel(48, 36), [ 255; 3 ]));
+    assert!(close(img.pixel(16, 36), [ 255; 3 ]));
+}
+
+/// Canonical scenes of the algorithms on seeded points, drawn with the layers of their views in the viewer.
+/// The other views are not covered: the boolean operations are not in the viewer, the 3D and 4D views project
+/// their scenes with a camera, the animated and interactive views (e.g. the flocking, the sweeps and the playback)
+/// depend on time or on the mouse, the views of images need their files, and the overlays that are still drawn
+/// with glium shapes (e.g. the filled Voronoi cells, the quality of the triangles and the size field) are left out.
+#[cfg(feature = "wgpu")]
+fn golden_scenes() -> Vec<(&'static str, crate::render::DrawList)> {
+    use crate::{
+        harness::{ Suite, star_polygon },
+        math::Polyline,
+        render::{ layers, DrawList },
+    };
+
+    let points = |count, seed| Suite::Uniform.generate_seeded(count, seed);
+    // The layers drawn one over the other, in the order of the viewer
+    let stack = |lists: Vec<DrawList>| {
+        let mut list = layers::list();
+        list.items.extend(lists.into_iter().flat_map(|list| list.items));
+        list
+    };
+    let mut scenes = Vec::new();
+
+    let hull_points = points(40, 1);
+    let hull = hull_positions(&hull_points, &GrahamScan::scan(&hull_points));
+    scenes.push(("convex_hull", stack(vec![ layers::points(&hull_points), layers::convex_hull(&hull) ])));
+
+    let delaunay_points = points(40, 2);
+    let indices = Incremental2dTriangulation::delaunay(&delaunay_points);
+    scenes.push(("delaunay", stack(vec![ layers::triangulation(&delaunay_points, &indices), layers::points(&delaunay_points) ])));
+
+    let sites = points(20, 3);
+    let cells = voronoi_diagram(&sites, &Rect::new(Vec2::new(-1.0, -1.0), Vec2::new(1.0, 1.0)));
+    scenes.push(("voronoi", layers::voronoi(&sites, &cells)));
+
+    let star = star_polygon(&points(20, 4));
+    scenes.push(("polygon_triangulation", layers::polygon_triangulation(&star, &triangulate_polygon(&star), true)));
+
+    let star = star_polygon(&points(16, 5));
+    scenes.push(("medial_axis", layers::medial_axis(&star, MedialAxis::new(&star, 0.05).as_ref(), false)));
+
+    let star = star_polygon(&points(16, 6));
+    let curve = Polyline::new(star.clone(), true);
+    let offsets = [ 0.1, -0.1 ].iter().flat_map(|&d| offset(&curve, d)).collect::<Vec<_>>();
+    let band = [ 0.1, -0.1 ].iter().flat_map(|&d| offset::band(&curve, d)).collect::<Vec<_>>();
+    scenes.push(("offset", layers::offset(&star, &curve, &band, &offsets, false)));
+
+    let inputs = points(12, 7);
+    let mut field = SizeField::uniform(Rect::new(Vec2::new(-1.0, -1.0), Vec2::new(1.0, 1.0)), 17, 0.3).unwrap();
+    field.paint(Vec2::new(0.3, 0.2), 0.5, 0.08, 1.0);
+    let refinement = refine(&inputs, &field, 2000, &Progress::default()).unwrap();
+    scenes.push(("refinement", layers::refinement(&inputs, &refinement.points, &refinement.indices)));
+
+    scenes
+}
+
+/// Renders the scenes offscreen and compares them with the images of `tests/golden`,
+/// which are written instead with `UPDATE_GOLDEN=1`
+#[cfg(feature = "wgpu")]
+#[test]
+fn golden_images() {
+    use crate::{ image, render::WgpuRenderer };
+    use std::{ fs::File, path::Path };
+
+    /// Colors closer than this look the same
+    const THRESHOLD: f32 = 0.1;
+    /// Largest fraction of the pixels that can look different, e.g. for the antialiasing of another GPU
+    const MAX_DIFFERENCE: f32 = 0.01;
+
+    let Some(mut renderer) = WgpuRenderer::headless() else {
+        println!("No wgpu adapter, skipping the golden images");
+        return;
+    };
+    let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("golden");
+    let update = std::env::var_os("UPDATE_GOLDEN").is_some();
+
+    let mut failures = Vec::new();
+    for (name, list) in golden_scenes() {
+        let img = renderer.render_to_image(&list, 128, 128).unwrap();
+        let path = directory.join(format!("{}.ppm", name));
+        if update {
+            std::fs::create_dir_all(&directory).unwrap();
+            image::write_ppm(File::create(&path).unwrap(), &img).unwrap();
+            continue;
+        }
+
+        let reference = File::open(&path)
+            .map_err(crate::Error::from)
+            .and_then(image::read_ppm)
+            .unwrap_or_else(|err| panic!("Cannot read {}, run the test with UPDATE_GOLDEN=1 to write it: {}", path.display(), err));
+        let difference = img.difference(&reference, THRESHOLD);
+        if difference > MAX_DIFFERENCE {
+            // Written into the temporary directory to compare it with the reference
+            let actual = std::env::temp_dir().join(format!("{}.actual.ppm", name));
+            image::write_ppm(File::create(&actual).unwrap(), &img).unwrap();
+            failures.push(format!("{}: {:.1}% of the pixels differ, rendered into {}", name, 100.0 * difference, actual.display()));
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[cfg(feature = "gui")]
+#[test]
+fn exec_time_history() {
+    let mut history = ExecTimeHistory::default();
+    assert_eq!(history.stats(), None);
+
+    for &micros in &[ 30, 10, 20 ] {
+        history.push(Duration::from_micros(micros));
+    }
+    assert_eq!(history.last(), Some(20.0));
+    assert_eq!(history.stats(), Some((10.0, 20.0, 30.0)));
+
+    for _ in 0..200 {
+        history.push(Duration::from_micros(5));
+    }
+    assert_eq!(history.stats(), Some((5.0, 5.0, 5.0)));
+}
+
+#[test]
+fn jarvis_march_duplicates() {
+    let points = [
+        Vec2::new(0.0, 0.0),
+        Vec2::new(0.0, 0.0),
+        Vec2::new(1.0, 0.0),
+        Vec2::new(1.0, 0.0),
+        Vec2::new(0.0, 1.0),
+    ];
+    let hull = JarvisMarch::march(points.iter());
+    assert!(hull.len() <= points.len());
+    for (i, idx) in hull.iter().enumerate() {
+        assert!(!hull[i + 1..].contains(idx));
+    }
+}
+
+#[test]
+fn boolean_operations() {
+    use crate::math::float;
+
+    let square = |x: f32, y: f32, size: f32| vec![
+        Vec2::new(x, y), Vec2::new(x + size, y), Vec2::new(x + size, y + size), Vec2::new(x, y + size),
+    ];
+    let signed_area = |p: &[Vec2]| (0..p.len()).map(|i| Vec2::shoelace(Vec2::default(), p[i], p[(i + 1) % p.len()])).sum::<f32>() / 2.0;
+    let area = |polygons: &[Vec<Vec2>]| polygons.iter().map(|p| signed_area(p)).sum::<f32>();
+    let check = |a: &[Vec2], b: &[Vec2], expected: [f32; 4]| {
+        for (&operation, &expected) in [ Operation::Union, Operation::Intersection, Operation::Difference, Operation::Xor ].iter().zip(expected.iter()) {
+            let result = polygon_boolean(a, b, operation);
+            assert!(float::abs(area(&result) - expected) < 1e-4, "{:?}: {:?}", operation, result);
+        }
+    };
+
+    // Overlapping squares, in both orientations
+    let (a, b) = (square(0.0, 0.0, 2.0), square(1.0, 1.0, 2.0));
+    check(&a, &b, [ 7.0, 1.0, 3.0, 6.0 ]);
+    check(&a, &b.iter().rev().cloned().collect::<Vec<_>>(), [ 7.0, 1.0, 3.0, 6.0 ]);
+    assert_eq!(polygon_boolean(&a, &b, Operation::Union)[0].len(), 8);
+    let intersection = polygon_boolean(&a, &b, Operation::Intersection);
+    assert_eq!(intersection.len(), 1);
+    assert_eq!(intersection[0].len(), 4);
+    assert!(square(1.0, 1.0, 1.0).iter().all(|p| intersection[0].contains(p)));
+
+    // Sharing an edge, the shared part is dropped from the union and kept in the difference
+    let (left, right) = (square(0.0, 0.0, 1.0), square(1.0, 0.0, 1.0));
+    check(&left, &right, [ 2.0, 0.0, 1.0, 2.0 ]);
+    assert_eq!(polygon_boolean(&left, &right, Operation::Union).iter().map(Vec::len).collect::<Vec<_>>(), vec![ 4 ]);
+    assert!(polygon_boolean(&left, &right, Operation::Intersection).is_empty());
+    let shifted = square(1.0, 0.5, 1.0);
+    check(&left, &shifted, [ 2.0, 0.0, 1.0, 2.0 ]);
+
+    // Same squares
+    check(&left, &left, [ 1.0, 1.0, 0.0, 0.0 ]);
+    assert!(polygon_boolean(&left, &left, Operation::Difference).is_empty());
+
+    // Disjoint, touching at a corner, and one inside the other with a hole left by the difference
+    check(&left, &square(3.0, 0.0, 1.0), [ 2.0, 0.0, 1.0, 2.0 ]);
+    assert_eq!(polygon_boolean(&left, &square(3.0, 0.0, 1.0), Operation::Union).len(), 2);
+    let corner = square(1.0, 1.0, 1.0);
+    check(&left, &corner, [ 2.0, 0.0, 1.0, 2.0 ]);
+    assert_eq!(polygon_boolean(&left, &corner, Operation::Union).len(), 2);
+    let (outer, inner) = (square(0.0, 0.0, 4.0), square(1.0, 1.0, 1.0));
+    check(&outer, &inner, [ 16.0, 1.0, 15.0, 15.0 ]);
+    let holed = polygon_boolean(&outer, &inner, Operation::Difference);
+    assert_eq!(holed.len(), 2);
+    assert_eq!(holed.iter().filter(|p| signed_area(p) < 0.0).count(), 1);
+    check(&inner, &outer, [ 16.0, 1.0, 0.0, 15.0 ]);
+
+    // A vertex of one on an edge of the other, the result is the triangle cut by the sides of the diamond
+    let diamond = vec![ Vec2::new(1.0, -1.0), Vec2::new(2.0, 0.5), Vec2::new(1.0, 2.0), Vec2::new(0.0, 0.5) ];
+    let triangle = vec![ Vec2::new(0.0, 0.0), Vec2::new(2.0, 0.0), Vec2::new(1.0, 2.0) ];
+    let result = polygon_boolean(&triangle, &diamond, Operation::Intersection);
+    assert_eq!(result.len(), 1);
+    assert!(result[0].contains(&Vec2::new(1.0, 2.0)));
+    assert!(polygon_boolean(&[], &triangle, Operation::Intersection).is_empty());
+}
+
+#[test]
+fn general_position() {
+    use crate::algorithms::general_position::{ collinear_triple, cocircular_quadruple };
+
+    // Vertices of a regular hexagon are in convex position, and on the same circle
+    let hexagon = (0..6)
+        .map(|i| {
+            let angle = i as f32 * core::f32::consts::PI / 3.0;
+            Vec2::new(crate::math::float::cos(angle), crate::math::float::sin(angle))
+        })
+        .collect::<Vec<_>>();
+    assert!(is_in_convex_position(&hexagon));
+    assert!(!has_collinear_triple(&hexagon));
+    assert!(has_cocircular_quadruple(&hexagon));
+
+    // A quadrilateral, then a point on the line of two of its vertices
+    let mut points = vec![ Vec2::new(0.0, 0.0), Vec2::new(3.0, 1.0), Vec2::new(1.0, 4.0), Vec2::new(-2.0, 2.5) ];
+    assert!(is_in_convex_position(&points));
+    assert!(!has_collinear_triple(&points));
+    assert!(!has_cocircular_quadruple(&points));
+    points.push(Vec2::new(-4.0, 5.0)); // On the line through (0, 0) and (-2, 2.5)
+    assert_eq!(collinear_triple(&points), Some([ 0, 3, 4 ]));
+    assert!(!is_in_convex_position(&points));
+
+    // A point inside the hull, and a duplicated point
+    assert!(!is_in_convex_position(&[ Vec2::new(0.0, 0.0), Vec2::new(2.0, 0.0), Vec2::new(0.0, 2.0), Vec2::new(0.5, 0.5) ]));
+    let duplicated = [ Vec2::new(0.0, 0.0), Vec2::new(1.0, 1.0), Vec2::new(0.0, 0.0) ];
+    assert!(!is_in_convex_position(&duplicated));
+    assert_eq!(collinear_triple(&duplicated), Some([ 0, 1, 2 ]));
+
+    // Corners of a rectangle, with a collinear point that is not on their circle
+    let rectangle = [ Vec2::new(0.0, 0.0), Vec2::new(0.0, 3.0), Vec2::new(4.0, 0.0), Vec2::new(2.0, 0.0), Vec2::new(4.0, 3.0) ];
+    assert_eq!(cocircular_quadruple(&rectangle), Some([ 0, 1, 2, 4 ]));
+    assert!(!has_cocircular_quadruple(&rectangle[..4]));
+}
+
+#[test]
+fn algorithm_steps() {
+    let points = vec![
+        Vec2::new(-0.5, -0.5),
+        Vec2::new(0.5, -0.4),
+        Vec2::new(0.1, 0.0),
+        Vec2::new(0.6, 0.5),
+        Vec2::new(-0.4, 0.6),
+        Vec2::new(0.0, 0.9),
+        Vec2::new(-0.2, 0.2),
+    ];
+
+    let mut jarvis = JarvisMarch::run(&points);
+    while let Some(step) = jarvis.next() {
+        assert_eq!(step, Step::HullPush(*jarvis.hull().last().unwrap()));
+    }
+    assert_eq!(jarvis.hull(), &JarvisMarch::march(points.iter())[..]);
+
+    // Every point of the stack but the last one makes a left turn
+    let mut graham = GrahamScan::run(&points);
+    while graham.next().is_some() {
+        let hull = graham.hull();
+        for i in 2..hull.len().saturating_sub(1) {
+            assert!(GrahamScan::prod_vec(points[hull[i - 2]], points[hull[i - 1]], points[hull[i]]) >= 0.0);
+        }
+    }
+    assert_eq!(graham.hull(), &GrahamScan::scan(&points)[..]);
+
+    let mut triangulation = Incremental2dTriangulation::run(&points);
+    let mut count = 0;
+    while let Some(step) = triangulation.next() {
+        count += 1;
+        assert_eq!(triangulation.indices().len(), count * 3);
+        match step {
+            Step::Triangle(a, b, c) => assert!(a < points.len() && b < points.len() && c < points.len()),
+            _ => panic!("unexpected step {:?}", step),
+        }
+    }
+    let mut sorted = points.clone();
+    let mut indices = Incremental2dTriangulation::triangulate(&mut sorted);
+    assert_eq!(triangulation.points(), &sorted[..]);
+    assert_eq!(triangulation.indices(), &indices[..]);
+
+    // Flipping an edge does not change the number of triangles
+    let mut flipping = EdgeFlippingSteps::new(&indices, &sorted);
+    while flipping.next().is_some() {
+        assert_eq!(flipping.triangles().count(), indices.len() / 3);
+    }
+    let flipped = flipping.triangles().flat_map(|t| vec![t.0, t.1, t.2]).collect::<Vec<_>>();
+    Incremental2dTriangulation::edge_flipping(&mut indices, &sorted);
+    assert_eq!(flipped, indices);
+}
+
+#[test]
+fn spatial_queries() {
+    let points = vec![
+        Vec2::new(0.0, 0.0),
+        Vec2::new(0.5, 0.5),
+        Vec2::new(-0.5, 0.25),
+        Vec2::new(0.75, -0.5),
+    ];
+
+    assert_eq!(spatial::nearest(&[], Vec2::new(0.0, 0.0)), None);
+    assert_eq!(spatial::nearest(&points, Vec2::new(0.1, -0.1)), Some(0));
+    assert_eq!(spatial::nearest(&points, Vec2::new(0.6, 0.4)), Some(1));
+    assert_eq!(spatial::nearest(&points, Vec2::new(1.0, -1.0)), Some(3));
+
+    let rect = Rect::new(Vec2::new(-0.5, 0.0), Vec2::new(0.5, 0.5));
+    assert_eq!(spatial::in_rect(&points, &rect), vec![0, 1, 2]);
+    let rect = Rect::new(Vec2::new(0.25, -1.0), Vec2::new(1.0, 0.0));
+    assert_eq!(spatial::in_rect(&points, &rect), vec![3]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn io_csv() {
+    let csv = "x,y\n0.5,-1\n\n# comment\n 2, 3.25 \n";
+    let points = crate::io::read_points_csv(csv.as_bytes()).unwrap();
+    assert_eq!(points, vec![ Vec2::new(0.5, -1.0), Vec2::new(2.0, 3.25) ]);
+
+    let mut out = Vec::new();
+    crate::io::write_points_csv(&mut out, &points).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), "0.5,-1\n2,3.25\n");
+
+    assert!(crate::io::read_points_csv("1,2\nx,y\n".as_bytes()).is_err());
+    match crate::io::read_points_csv("1,2\n3\n".as_bytes()) {
+        Err(crate::Error::Parse { line, content, .. }) => {
+            assert_eq!(line, 2);
+            assert_eq!(content, "3");
+        },
+        _ => panic!("expected a parse error"),
+    }
+    match crate::io::read_points_csv("1,2\n3, nan\n".as_bytes()) {
+        Err(crate::Error::NotFinite { line, column, content }) => {
+            assert_eq!((line, column), (2, 2));
+            assert_eq!(content, "nan");
+        },
+        _ => panic!("expected a non finite number error"),
+    }
+    assert!(crate::io::read_points_values_csv("0,1,inf\n".as_bytes()).is_err());
+
+    // The values are optional
+    let (points, values) = crate::io::read_points_values_csv("x,y,value\n0,1,2.5\n1,0\n".as_bytes()).unwrap();
+    assert_eq!(points, vec![ Vec2::new(0.0, 1.0), Vec2::new(1.0, 0.0) ]);
+    assert_eq!(values, vec![ 2.5, 0.0 ]);
+    let mut out = Vec::new();
+    crate::io::write_points_values_csv(&mut out, &points, &values).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), "0,1,2.5\n1,0,0\n");
+    assert!(crate::io::read_points_csv("0,1,2.5\n".as_bytes()).is_err());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn io_triangulation() {
+    let points = [ Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.0), Vec2::new(0.0, 1.0) ];
+    let indices = [ 0, 1, 2 ];
+
+    let mut out = Vec::new();
+    crate::io::write_triangles_csv(&mut out, &points, &indices).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), "0,0,1,0,0,1\n");
+
+    let mut out = Vec::new();
+    crate::io::write_triangulation_json(&mut out, &points, &indices).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(),
+               "{\n  \"points\": [[0, 0], [1, 0], [0, 1]],\n  \"triangles\": [[0, 1, 2]]\n}\n");
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn scene_revision() {
+    let mut scene = crate::scene::Scene::new();
+    assert_eq!(scene.revision(), 0);
+
+    assert!(scene.add_point(Vec2::new(0.0, 0.0)));
+    assert!(!scene.add_point(Vec2::new(0.0, 0.0)));
+    assert_eq!(scene.revision(), 1);
+
+    scene.set_hull(vec![ Vec2::new(0.0, 0.0) ]);
+    assert_eq!(scene.revision(), 1); // Results are not input
+    scene.add_points(vec![ Vec2::new(1.0, 0.0), Vec2::new(0.0, 0.0), Vec2::new(0.5, 1.0), Vec2::new(1.0, 0.0) ]);
+    assert_eq!(scene.points(), &[ Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.0), Vec2::new(0.5, 1.0) ]);
+    assert_eq!(scene.revision(), 2);
+    assert!(scene.hull().is_empty());
+
+    scene.clear();
+    assert!(scene.points().is_empty());
+    assert_eq!(scene.revision(), 3);
+
+    // The values follow their points, duplicates are dropped with theirs
+    scene.set_points_with_values(vec![ Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.0), Vec2::new(0.0, 0.0) ], vec![ 1.0, 2.0, 3.0 ]);
+    assert_eq!(scene.values(), &[ 1.0, 2.0 ]);
+    scene.add_point(Vec2::new(0.0, 1.0));
+    assert!(scene.add_point_with_value(Vec2::new(1.0, 1.0), 4.0));
+    assert_eq!(scene.values(), &[ 1.0, 2.0, 0.0, 4.0 ]);
+    scene.set_value(2, 5.0);
+    assert_eq!(scene.values()[2], 5.0);
+    assert_eq!(scene.revision(), 7);
+    scene.clear_points();
+    assert!(scene.values().is_empty());
+}
+
+#[cfg(feature = "geo")]
+#[test]
+fn geo_types_adapters() {
+    use geo_types::{ Coordinate, MultiPoint, Point, Polygon, LineString };
+
+    let points = MultiPoint(vec![
+        Point::new(0.0_f64, 0.0), Point::new(1.0, 0.0), Point::new(0.25, 0.25),
+        Point::new(1.0, 1.0), Point::new(0.0, 1.0),
+    ]);
+    let hull: Polygon<f64> = crate::geo::convex_hull(&points);
+    let exterior = &hull.exterior().0;
+    assert_eq!(exterior.len(), 5); // Closed ring
+    assert_eq!(exterior.first(), exterior.last());
+    assert!(!exterior.contains(&Coordinate { x: 0.25, y: 0.25 }));
+    let area = exterior.windows(2).map(|w| w[0].x * w[1].y - w[1].x * w[0].y).sum::<f64>();
+    assert!(area > 0.0); // Counter-clockwise
+
+    let square = Polygon::new(LineString::from(vec![ (0.0_f32, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0) ]), vec![]);
+    let triangles = crate::geo::triangulate::<f32, _>(&square, true);
+    assert_eq!(triangles.len(), 2);
+
+    let v: Vec2 = Point::new(0.5_f64, -2.0).into();
+    assert_eq!(v, Vec2::new(0.5, -2.0));
+    let p: Point<f64> = v.into();
+    assert_eq!(p, Point::new(0.5, -2.0));
+
+    // The difference of two squares has an interior, and the pieces of an exclusive or are separate polygons
+    let big = Polygon::new(LineString::from(vec![ (0.0_f64, 0.0), (0.0, 4.0), (4.0, 4.0), (4.0, 0.0) ]), vec![]);
+    let small = Polygon::new(LineString::from(vec![ (1.0_f64, 1.0), (2.0, 1.0), (2.0, 2.0), (1.0, 2.0) ]), vec![]);
+    let holed = crate::geo::boolean(&big, &small, Operation::Difference);
+    assert_eq!(holed.0.len(), 1);
+    assert_eq!(holed.0[0].interiors().len(), 1);
+    let back = crate::geo::boolean(&holed.0[0], &small, Operation::Union);
+    assert_eq!(back.0.len(), 1);
+    assert!(back.0[0].interiors().is_empty());
+    assert_eq!(back.0[0].exterior().0.len(), 5);
+    let shifted = Polygon::new(LineString::from(vec![ (2.0_f64, 2.0), (6.0, 2.0), (6.0, 6.0), (2.0, 6.0) ]), vec![]);
+    assert_eq!(crate::geo::boolean(&big, &shifted, Operation::Xor).0.len(), 2);
+    assert!(crate::geo::boolean(&small, &shifted, Operation::Intersection).0.is_empty());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn wkt() {
+    use crate::wkt::{ self, Geometry };
+
+    let point = wkt::parse("point z (1.5 -2 3)").unwrap();
+    assert_eq!(point, Geometry::Point(Some(Vec2::new(1.5, -2.0))));
+    assert_eq!(point.to_string(), "POINT (1.5 -2)");
+    assert_eq!(wkt::parse("POINT EMPTY").unwrap(), Geometry::Point(None));
+
+    let points = vec![ Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.0), Vec2::new(1.0, 1.0) ];
+    assert_eq!(wkt::parse("MULTIPOINT (0 0, 1 0, 1 1)").unwrap(), Geometry::MultiPoint(points.clone()));
+    assert_eq!(wkt::parse("MULTIPOINT ((0 0), (1 0), (1 1))").unwrap(), Geometry::MultiPoint(points.clone()));
+
+    let polygon = wkt::parse("POLYGON ((0 0, 1 0, 1 1, 0 0))").unwrap();
+    assert_eq!(polygon, Geometry::Polygon(vec![ points.clone() ]));
+    assert_eq!(polygon.to_string(), "POLYGON ((0 0, 1 0, 1 1, 0 0))");
+
+    let triangles = wkt::triangles(&points, &[ 0, 1, 2 ]);
+    assert_eq!(wkt::parse(&triangles.to_string()).unwrap(), triangles);
+
+    assert!(wkt::parse("LINESTRING (0 0, 1)").is_err());
+    assert!(wkt::parse("CIRCLE (0 0)").is_err());
+    assert!(wkt::parse("POINT (0 0) extra").is_err());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn scene_wkt() {
+    let mut scene = crate::scene::Scene::new();
+    scene.add_geometry(crate::wkt::parse("MULTIPOINT (0 0, 1 0)").unwrap());
+    scene.add_geometry(crate::wkt::parse("LINESTRING (0 0, 1 0, 1 1)").unwrap());
+    scene.add_geometry(crate::wkt::parse("POLYGON ((0 0, 2 0, 0 2, 0 0), (0.1 0.1, 0.5 0.1, 0.1 0.5, 0.1 0.1))").unwrap());
+    assert_eq!(scene.points().len(), 2);
+    assert_eq!(scene.segments().len(), 2);
+    assert_eq!(scene.polygons(), &[ vec![ Vec2::new(0.0, 0.0), Vec2::new(2.0, 0.0), Vec2::new(0.0, 2.0) ] ]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn io_mesh() {
+    use crate::{ io::{ self, MeshFormat }, math::Vec3, scene::Mesh };
+
+    let mesh = Mesh {
+        vertices: vec![ Vec3::new(0.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0) ],
+        indices: vec![ 0, 1, 2 ],
+    };
+    let normals = mesh.vertex_normals();
+    assert_eq!((normals[0].0.x, normals[0].0.y, normals[0].0.z), (0.0, 0.0, 1.0));
+
+    let mut out = Vec::new();
+    io::write_mesh_obj(&mut out, &mesh).unwrap();
+    assert!(String::from_utf8(out).unwrap().ends_with("vn 0 0 1\nf 1//1 2//2 3//3\n"));
+
+    let mut out = Vec::new();
+    io::write_mesh_stl(&mut out, &mesh).unwrap();
+    assert_eq!(out.len(), 80 + 4 + 50);
+    assert_eq!(&out[80..84], &1_u32.to_le_bytes());
+
+    let mut out = Vec::new();
+    io::write_mesh_off(&mut out, &mesh).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), "OFF\n3 1 0\n0 0 0\n1 0 0\n0 1 0\n3 0 1 2\n");
+
+    let mut out = Vec::new();
+    io::write_mesh(&mut out, &mesh, MeshFormat::Ply).unwrap();
+    assert!(String::from_utf8(out).unwrap().contains("element face 1\n"));
+
+    assert_eq!(MeshFormat::from_path("hull.STL".as_ref()).unwrap(), MeshFormat::Stl);
+    assert!(MeshFormat::from_path("hull.txt".as_ref()).is_err());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn point_cloud() {
+    use crate::{ math::Vec3, point_cloud };
+
+    let xyz = "x y z r g b\n0 0 0 255 0 0\n1,2,3\n\n# comment\n0.5\t-1 2.5\n";
+    let points = point_cloud::read_xyz(xyz.as_bytes()).unwrap();
+    assert_eq!(points.iter().map(|p| [ p.0.x, p.0.y, p.0.z ]).collect::<Vec<_>>(),
+               vec![ [0.0, 0.0, 0.0], [1.0, 2.0, 3.0], [0.5, -1.0, 2.5] ]);
+    assert!(point_cloud::read_xyz("0 0 0\n1 2\n".as_bytes()).is_err());
+    match point_cloud::read_xyz("0 0 0\n1 inf 2\n".as_bytes()) {
+        Err(crate::Error::NotFinite { line, column, content }) => assert_eq!((line, column, content.as_str()), (2, 2, "inf")),
+        _ => panic!("expected a non finite number error"),
+    }
+
+    // LAS 1.2 header, point format 0, two points with a scale of 0.01
+    let mut las = vec![0_u8; 227];
+    las[0..4].copy_from_slice(b"LASF");
+    las[24] = 1;
+    las[25] = 2;
+    las[94..96].copy_from_slice(&227_u16.to_le_bytes());
+    las[96..100].copy_from_slice(&227_u32.to_le_bytes());
+    las[105..107].copy_from_slice(&20_u16.to_le_bytes());
+    las[107..111].copy_from_slice(&2_u32.to_le_bytes());
+    for axis in 0..3 {
+        las[131 + axis * 8..139 + axis * 8].copy_from_slice(&0.01_f64.to_le_bytes());
+        las[155 + axis * 8..163 + axis * 8].copy_from_slice(&1000.0_f64.to_le_bytes());
+        las[179 + axis * 16..187 + axis * 16].copy_from_slice(&1002.0_f64.to_le_bytes()); // Max
+        las[187 + axis * 16..195 + axis * 16].copy_from_slice(&1000.0_f64.to_le_bytes()); // Min
+    }
+    for raw in &[ 0_i32, 200 ] {
+        let mut record = vec![0_u8; 20];
+        for axis in 0..3 {
+            record[axis * 4..axis * 4 + 4].copy_from_slice(&raw.to_le_bytes());
+        }
+        las.extend(record);
+    }
+    let points = point_cloud::read_las(&las[..]).unwrap();
+    assert_eq!(points.len(), 2);
+    assert_eq!([ points[0].0.x, points[0].0.y, points[0].0.z ], [ -1.0, -1.0, -1.0 ]);
+    assert_eq!([ points[1].0.x, points[1].0.y, points[1].0.z ], [ 1.0, 1.0, 1.0 ]);
+    las[104] = 0x80; // Compressed
+    assert!(point_cloud::read_las(&las[..]).is_err());
+    las[104] = 0;
+    las[131..139].copy_from_slice(&f64::NAN.to_le_bytes());
+    assert!(point_cloud::read_las(&las[..]).is_err());
+
+    let grid = (0..1000).map(|i| Vec3::new((i % 10) as f32, (i / 10 % 10) as f32, (i / 100) as f32)).collect::<Vec<_>>();
+    assert_eq!(point_cloud::decimate(&grid, 1000).len(), 1000);
+    let decimated = point_cloud::decimate(&grid, 200);
+    assert!(decimated.len() <= 200 && decimated.len() >= 100, "{} points", decimated.len());
+
+    let mut points = grid;
+    point_cloud::fit(&mut points, 2.0);
+    assert!(points.iter().all(|p| p.0.x.abs() <= 1.0 && p.0.y.abs() <= 1.0 && p.0.z.abs() <= 1.0));
+    assert_eq!(points[999].0.x, 1.0);
+}
+
+#[cfg(feature = "cross-validate")]
+#[test]
+fn cross_validation() {
+    use crate::{ algorithms::Incremental2dTriangulation, cross_validation, task::Progress };
+
+    // Cocircular points have several Delaunay triangulations, none of them is reported
+    let square = [ Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.0), Vec2::new(1.0, 1.0), Vec2::new(0.0, 1.0) ];
+    assert!(cross_validation::delaunay_differences(&square, &[ 0, 1, 2, 0, 2, 3 ]).is_empty());
+    assert!(cross_validation::delaunay_differences(&square, &[ 0, 1, 3, 1, 2, 3 ]).is_empty());
+    let octagon = (0..8).map(|i| {
+                            let angle = 0.1 + i as f32 * std::f32::consts::FRAC_PI_4;
+                            Vec2::new(angle.cos(), angle.sin())
+                        })
+                        .collect::<Vec<_>>();
+    let (points, indices) = Incremental2dTriangulation::compute(octagon, true, &Progress::default()).unwrap();
+    assert!(cross_validation::delaunay_differences(&points, &indices).is_empty());
+
+    // The circumcircle of each triangle contains the opposite point, the edge should have been flipped.
+    // The triangles of spade cover the same area so they are not reported
+    let points = [ Vec2::new(0.0, 0.0), Vec2::new(1.0, -0.1), Vec2::new(2.0, 0.0), Vec2::new(1.0, 1.0) ];
+    let mut differences = cross_validation::delaunay_differences(&points, &[ 0, 1, 2, 0, 2, 3 ]);
+    differences.sort_unstable();
+    assert_eq!(differences, vec![ 0, 0, 1, 2, 2, 3 ]);
+
+    // Without the second triangle, the missing area is reported
+    let differences = cross_validation::delaunay_differences(&points, &[ 0, 1, 3 ]);
+    assert_eq!(differences, vec![ 1, 2, 3 ]);
+}
+
+#[cfg(feature = "capi")]
+#[test]
+fn capi() {
+    use crate::capi::{ self, CgPoint };
+
+    let points = [
+        CgPoint { x: 0.0, y: 0.0 }, CgPoint { x: 1.0, y: 0.0 }, CgPoint { x: 0.25, y: 0.25 },
+        CgPoint { x: 1.0, y: 1.0 }, CgPoint { x: 0.0, y: 1.0 },
+    ];
+    let mut hull = [ CgPoint { x: 0.0, y: 0.0 }; 5 ];
+    let len = unsafe { capi::convex_hull_2d(points.as_ptr(), points.len(), hull.as_mut_ptr()) };
+    assert_eq!(len, 4);
+    assert!(hull[..len].iter().all(|p| p.x != 0.25));
+
+    let mut triangulation = unsafe { capi::delaunay_2d(points.as_ptr(), points.len(), true) };
+    assert_eq!(triangulation.points_len, 5);
+    assert_eq!(triangulation.indices_len, 3 * 4);
+    unsafe { capi::triangulation_free(&mut triangulation) };
+    assert!(triangulation.points.is_null() && triangulation.indices.is_null());
+
+    // Duplicates are dropped
+    let duplicated = [ points, points ].concat();
+    let mut triangulation = unsafe { capi::delaunay_2d(duplicated.as_ptr(), duplicated.len(), true) };
+    assert_eq!(triangulation.points_len, 5);
+    assert_eq!(triangulation.indices_len, 3 * 4);
+    unsafe { capi::triangulation_free(&mut triangulation) };
+    unsafe { capi::triangulation_free(std::ptr::null_mut()) };
+
+    // A square with a hole left by a smaller one
+    let outer = [ points[0], points[1], points[3], points[4] ];
+    let inner = [ CgPoint { x: 0.25, y: 0.25 }, CgPoint { x: 0.5, y: 0.25 }, CgPoint { x: 0.5, y: 0.5 }, CgPoint { x: 0.25, y: 0.5 } ];
+    let mut polygons = unsafe { capi::polygon_boolean(outer.as_ptr(), outer.len(), inner.as_ptr(), inner.len(), capi::CgOperation::Difference) };
+    assert_eq!(polygons.sizes_len, 2);
+    assert_eq!(polygons.points_len, 8);
+    unsafe { capi::polygons_free(&mut polygons) };
+    assert!(polygons.points.is_null() && polygons.sizes.is_null());
+    let mut polygons = unsafe { capi::polygon_boolean(outer.as_ptr(), outer.len(), inner.as_ptr(), inner.len(), capi::CgOperation::Intersection) };
+    assert_eq!(polygons.sizes_len, 1);
+    assert_eq!(unsafe { *polygons.sizes }, 4);
+    unsafe { capi::polygons_free(&mut polygons) };
+    unsafe { capi::polygons_free(std::ptr::null_mut()) };
+}
+
+#[test]
+fn farthest_point_voronoi() {
+    use crate::math::{ Circle, cmp_f32 };
+
+    let points = vec![
+        Vec2::new(-1.0, 0.0), Vec2::new(0.0, -0.5), Vec2::new(1.0, 0.0),
+        Vec2::new(0.5, 0.5), Vec2::new(0.0, 0.0), Vec2::new(-0.5, 0.5),
+    ];
+    let mut alg = FarthestPointVoronoi::new();
+    alg.set_points(points.clone());
+    assert_eq!(alg.hull().len(), 5);
+    assert!(!alg.hull().contains(&4));
+
+    // Every circumcircle contains all the points
+    assert_eq!(alg.triangles().len(), 3 * 3);
+    for tri in alg.triangles().chunks(3) {
+        let circle = Circle::circumscribed(points[tri[0]], points[tri[1]], points[tri[2]]).unwrap();
+        assert!(points.iter().all(|&p| circle.contains(p)));
+    }
+    // 2 edges between the 3 triangles, one ray per edge of the hull
+    assert_eq!(alg.edges().iter().filter(|e| e.end.is_some()).count(), 2);
+    assert_eq!(alg.edges().iter().filter(|e| e.end.is_none()).count(), 5);
+
+    let circle = alg.minimum_enclosing_circle().unwrap();
+    assert!(cmp_f32(circle.radius, 1.0) && cmp_f32(circle.center.x, 0.0) && cmp_f32(circle.center.y, 0.0));
+
+    alg.set_points(vec![ Vec2::new(0.0, 0.0), Vec2::new(2.0, 0.0), Vec2::new(1.0, 0.0) ]);
+    assert_eq!(alg.hull().len(), 2);
+    assert_eq!(alg.edges().len(), 2);
+    assert_eq!(alg.minimum_enclosing_circle(), Some(Circle::new(Vec2::new(1.0, 0.0), 1.0)));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn farthest_point_voronoi_random() {
+    use crate::math::Circle;
+
+    for _ in 0..20 {
+        let points = (0..50).map(|_| Vec2::random_range(-1.0, 1.0, -1.0, 1.0)).collect::<Vec<_>>();
+        let mut alg = FarthestPointVoronoi::new();
+        alg.set_points(points.clone());
+        assert_eq!(alg.triangles().len(), 3 * (alg.hull().len() - 2));
+        for tri in alg.triangles().chunks(3) {
+            let circle = Circle::circumscribed(points[tri[0]], points[tri[1]], points[tri[2]]).unwrap();
+            assert!(points.iter().all(|&p| circle.contains(p)));
+        }
+
+        // No circle through the hull vertices or on their diameters is smaller and encloses all the points
+        let circle = alg.minimum_enclosing_circle().unwrap();
+        assert!(points.iter().all(|&p| circle.contains(p)));
+        let hull = alg.hull();
+        for (i, &a) in hull.iter().enumerate() {
+            for &b in &hull[i + 1..] {
+                let diametral = Circle::diametral(points[a], points[b]);
+                if points.iter().all(|&p| diametral.contains(p)) {
+                    assert!(circle.radius <= diametral.radius + 1e-5);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn order_k_voronoi() {
+    use crate::math::cmp_f32;
+
+    let bounds = vec![ Vec2::new(-2.0, -2.0), Vec2::new(2.0, -2.0), Vec2::new(2.0, 2.0), Vec2::new(-2.0, 2.0) ];
+    let square = vec![ Vec2::new(-1.0, -1.0), Vec2::new(1.0, -1.0), Vec2::new(1.0, 1.0), Vec2::new(-1.0, 1.0) ];
+
+    // Order 1: the quadrants of the bounds
+    let regions = OrderKVoronoi::diagram(&square, 1, &bounds);
+    assert_eq!(regions.len(), 4);
+    assert!(regions.iter().all(|r| r.sites.len() == 1 && cmp_f32(r.area(), 4.0)));
+
+    // Order 2: the pairs of adjacent corners, the diagonal pairs only meet at the center
+    let regions = OrderKVoronoi::diagram(&square, 2, &bounds);
+    let mut sites = regions.iter().map(|r| r.sites.clone()).collect::<Vec<_>>();
+    sites.sort();
+    sites.dedup();
+    assert_eq!(sites, vec![ vec![ 0, 1 ], vec![ 0, 3 ], vec![ 1, 2 ], vec![ 2, 3 ] ]);
+    assert!(cmp_f32(regions.iter().map(|r| r.area()).sum(), 16.0));
+
+    let mut alg = OrderKVoronoi::new();
+    alg.set_points(square, 10);
+    assert_eq!(alg.k(), 10);
+    assert_eq!(alg.cell_count(), 1);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn order_k_voronoi_random() {
+    // The regions cover the bounds without overlapping, and their sites are the nearest points
+    for k in 1..=4 {
+        let points = (0..12).map(|_| Vec2::random_range(-1.0, 1.0, -1.0, 1.0)).collect::<Vec<_>>();
+        let mut alg = OrderKVoronoi::new();
+        alg.set_points(points.clone(), k);
+        let area = alg.regions().iter().map(|r| r.area()).sum::<f32>();
+        assert!((area - 16.0).abs() < 1e-3);
+
+        for region in alg.regions() {
+            let n = region.polygon.len() as f32;
+            let centroid = &region.polygon.iter().fold(Vec2::new(0.0, 0.0), |acc, p| &acc + p) / n;
+            let mut nearest = (0..points.len()).collect::<Vec<_>>();
+            nearest.sort_by(|&a, &b| (&points[a] - &centroid).length().partial_cmp(&(&points[b] - &centroid).length()).unwrap());
+            nearest.truncate(k);
+            nearest.sort();
+            assert_eq!(region.sites, nearest);
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn point_grid() {
+    use crate::math::cmp_f32;
+
+    assert_eq!(spatial::PointGrid::new(&[]).nearest(Vec2::new(0.0, 0.0)), None);
+
+    // Random, clustered and collinear points, with queries inside and outside of the grid
+    let random = (0..500).map(|_| Vec2::random_range(-1.0, 1.0, -1.0, 1.0)).collect::<Vec<_>>();
+    let clustered = (0..500).map(|i| {
+            let center = if i % 2 == 0 { Vec2::new(0.0, 0.0) } else { Vec2::new(0.5, 0.5) };
+            &center + &Vec2::random_range(-0.01, 0.01, -0.01, 0.01)
+        })
+        .collect::<Vec<_>>();
+    let collinear = (0..100).map(|i| Vec2::new(i as f32 * 0.01, 0.5)).collect::<Vec<_>>();
+    for points in &[ random, clustered, collinear ] {
+        let grid = spatial::PointGrid::new(points);
+        for _ in 0..500 {
+            let query = Vec2::random_range(-2.0, 2.0, -2.0, 2.0);
+            let nearest = grid.nearest(query).unwrap();
+            let expected = spatial::nearest(points, query).unwrap();
+            assert!(cmp_f32((&points[nearest] - &query).length(), (&points[expected] - &query).length()));
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn kd_tree() {
+    use crate::math::{ cmp_f32, Rect };
+
+    assert_eq!(spatial::KdTree::new(&[]).nearest(Vec2::new(0.0, 0.0)), None);
+
+    // Random, clustered and collinear points, with queries inside and outside of their bounds
+    let random = (0..500).map(|_| Vec2::random_range(-1.0, 1.0, -1.0, 1.0)).collect::<Vec<_>>();
+    let clustered = (0..500).map(|i| {
+            let center = if i % 2 == 0 { Vec2::new(0.0, 0.0) } else { Vec2::new(0.5, 0.5) };
+            &center + &Vec2::random_range(-0.01, 0.01, -0.01, 0.01)
+        })
+        .collect::<Vec<_>>();
+    let collinear = (0..100).map(|i| Vec2::new(i as f32 * 0.01, 0.5)).collect::<Vec<_>>();
+    for points in &[ random, clustered, collinear ] {
+        let tree = spatial::KdTree::new(points);
+        assert_eq!(tree.splits(&Rect::new(Vec2::new(-1.0, -1.0), Vec2::new(1.0, 1.0))).len(), points.len());
+        for _ in 0..500 {
+            let query = Vec2::random_range(-2.0, 2.0, -2.0, 2.0);
+            let mut visited = 0;
+            let nearest = tree.nearest_visiting(query, |_| visited += 1).unwrap();
+            let expected = spatial::nearest(points, query).unwrap();
+            assert!(cmp_f32((&points[nearest] - &query).length(), (&points[expected] - &query).length()));
+            assert!(visited >= 1 && visited <= points.len());
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn bvh() {
+    use crate::math::{ cmp_f32, Segment2 };
+
+    assert!(spatial::Bvh::new(&[]).raycast(Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.0)).is_none());
+    assert!(spatial::Bvh::new_linear(&[]).raycast(Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.0)).is_none());
+
+    // Short random segments, long random segments and a stack of parallel segments
+    let random_segment = |length: f32| {
+        let a = Vec2::random_range(-1.0, 1.0, -1.0, 1.0);
+        Segment2::new(a, &a + &Vec2::random_range(-length, length, -length, length))
+    };
+    let short = (0..500).map(|_| random_segment(0.05)).collect::<Vec<_>>();
+    let long = (0..200).map(|_| random_segment(1.0)).collect::<Vec<_>>();
+    let parallel = (0..100)
+        .map(|i| Segment2::new(Vec2::new(-0.5, i as f32 * 0.01), Vec2::new(0.5, i as f32 * 0.01)))
+        .collect::<Vec<_>>();
+    for segments in &[ short, long, parallel ] {
+        // Built with the surface area heuristic, and from the Morton codes
+        for bvh in &[ spatial::Bvh::new(segments), spatial::Bvh::new_linear(segments) ] {
+            let root = bvh.nodes()[0];
+            assert_eq!(root.len(), segments.len());
+            assert!(bvh.nodes().iter().filter(|node| node.is_leaf()).map(|node| node.len()).sum::<usize>() == segments.len());
+
+            for _ in 0..200 {
+                let origin = Vec2::random_range(-1.5, 1.5, -1.5, 1.5);
+                let direction = Vec2::random_range(-1.0, 1.0, -1.0, 1.0);
+                match (bvh.raycast(origin, direction), spatial::raycast(segments, origin, direction)) {
+                    (Some((_, t)), Some((_, expected))) => assert!(cmp_f32(t, expected)),
+                    (None, None) => {},
+                    (hit, expected) => panic!("Hit {:?} instead of {:?}", hit, expected),
+                }
+
+                let query = random_segment(0.5);
+                assert_eq!(bvh.intersecting(&query), spatial::intersecting(segments, &query));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn sweep_and_prune() {
+    use crate::math::Rect;
+
+    let mut sap = spatial::SweepAndPrune::new();
+    assert!(sap.pairs(&[]).is_empty());
+
+    // Boxes moving a little between the calls, and a box shared by all of them
+    let mut boxes = (0..300)
+        .map(|_| {
+            let a = Vec2::random_range(-1.0, 1.0, -1.0, 1.0);
+            Rect::new(a, &a + &Vec2::random_range(0.0, 0.1, 0.0, 0.1))
+        })
+        .collect::<Vec<_>>();
+    boxes.push(Rect::new(Vec2::new(-0.1, -0.1), Vec2::new(0.1, 0.1)));
+    for _ in 0..10 {
+        let mut pairs = sap.pairs(&boxes);
+        pairs.sort_unstable();
+        assert_eq!(pairs, spatial::overlapping_pairs(&boxes));
+
+        for rect in &mut boxes {
+            let offset = Vec2::random_range(-0.05, 0.05, -0.05, 0.05);
+            *rect = Rect::new(&rect.min + &offset, &rect.max + &offset);
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn pgm_image() {
+    use crate::image;
+
+    let ascii = "P2\n# 3x2 gradient\n3 2\n4\n0 1 2\n3 4 4\n";
+    let img = image::read_pgm(ascii.as_bytes()).unwrap();
+    assert_eq!((img.width, img.height), (3, 2));
+    assert_eq!(img.pixels, vec![ 0, 63, 127, 191, 255, 255 ]);
+
+    let mut binary = b"P5 2 2 255\n".to_vec();
+    binary.extend_from_slice(&[ 10, 20, 30, 40 ]);
+    let img = image::read_pgm(&binary[..]).unwrap();
+    assert_eq!(img.pixel(1, 1), 40);
+
+    let mut wide = b"P5 1 1 65535\n".to_vec();
+    wide.extend_from_slice(&[ 0xff, 0xff ]);
+    assert_eq!(image::read_pgm(&wide[..]).unwrap().pixels, vec![ 255 ]);
+
+    assert!(image::read_pgm("P6 1 1 255\n".as_bytes()).is_err());
+    assert!(image::read_pgm(&b"P5 2 2 255\n\x00\x00"[..]).is_err());
+    assert!(image::read_pgm("P2 2 1 255\n0".as_bytes()).is_err());
+
+    // Written images are read back
+    let img = image::GrayImage::from_values(3, 1, &[ -1.0, 0.0, 2.0 ], -1.0, 1.0);
+    assert_eq!(img.pixels, vec![ 0, 128, 255 ]);
+    let mut written = Vec::new();
+    image::write_pgm(&mut written, &img).unwrap();
+    assert_eq!(image::read_pgm(&written[..]).unwrap(), img);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn stippling() {
+    use crate::math::cmp_f32;
+
+    // Left half black, right half white
+    let pixels = (0..64 * 32).map(|i| if i % 64 < 32 { 0 } else { 255 }).collect::<Vec<_>>();
+    let density = DensityMap::from_luma(64, 32, &pixels);
+    assert!(cmp_f32(density.pixel_size(), 2.0 / 64.0));
+    assert!(cmp_f32(density.position(0, 0).x, -1.0 + 1.0 / 64.0));
+    assert!(cmp_f32(density.position(0, 0).y, 0.5 - 1.0 / 64.0));
+
+    let downsampled = density.downsampled(16);
+    assert_eq!((downsampled.width(), downsampled.height()), (16, 8));
+    assert_eq!(downsampled.value(0, 0), 1.0);
+    assert_eq!(downsampled.value(15, 7), 0.0);
+
+    let mut stippling = Stippling::new(density, 100);
+    assert_eq!(stippling.points().len(), 100);
+    assert!(stippling.points().iter().all(|p| p.x <= 0.0 && p.y.abs() <= 0.5));
+
+    // The points spread over the dark half and stay in it
+    stippling.run(30, &Progress::default()).unwrap();
+    assert!(stippling.points().iter().all(|p| p.x <= 0.0 && p.x >= -1.0 && p.y.abs() <= 0.5));
+    let left = stippling.points().iter().filter(|p| p.x < -0.5).count();
+    assert!(left > 30 && left < 70);
+    assert!(stippling.relax() < 0.05);
+
+    assert!(Stippling::new(DensityMap::from_luma(2, 2, &[ 255; 4 ]), 10).points().is_empty());
+}
+
+#[test]
+fn largest_inscribed_circle() {
+    use crate::{ algorithms, math::{ self, cmp_f32 } };
+
+    // The circle of a square touches its 4 sides
+    let square = vec![ Vec2::new(0.0, 0.0), Vec2::new(2.0, 0.0), Vec2::new(2.0, 2.0), Vec2::new(0.0, 2.0) ];
+    let circle = algorithms::largest_inscribed_circle(&square, 1e-4).unwrap().circle;
+    assert!((circle.radius - 1.0).abs() < 1e-4);
+    assert!((circle.center.x - 1.0).abs() < 1e-2 && (circle.center.y - 1.0).abs() < 1e-2);
+
+    // The centroid of a U shape is outside of it, the circle fits in a bottom corner
+    // where it touches the two outer sides and the inner corner
+    let u = vec![
+        Vec2::new(0.0, 0.0), Vec2::new(3.0, 0.0), Vec2::new(3.0, 3.0), Vec2::new(2.0, 3.0),
+        Vec2::new(2.0, 1.0), Vec2::new(1.0, 1.0), Vec2::new(1.0, 3.0), Vec2::new(0.0, 3.0),
+    ];
+    let result = algorithms::largest_inscribed_circle(&u, 1e-4).unwrap();
+    let radius = 2.0_f32.sqrt() / (1.0 + 2.0_f32.sqrt());
+    assert!((result.circle.radius - radius).abs() < 1e-3);
+    assert!(math::polygon::contains(&u, result.circle.center));
+    assert!(result.probes > 0);
+
+    // Triangle with sides 3, 4 and 5, whose inradius is 1
+    let triangle = vec![ Vec2::new(0.0, 0.0), Vec2::new(4.0, 0.0), Vec2::new(0.0, 3.0) ];
+    let circle = algorithms::largest_inscribed_circle(&triangle, 1e-5).unwrap().circle;
+    assert!((circle.radius - 1.0).abs() < 1e-3);
+    assert!(cmp_f32(math::polygon::boundary_distance(&triangle, circle.center), circle.radius));
+
+    assert!(algorithms::largest_inscribed_circle(&triangle[..2], 1e-4).is_none());
+    let flat = vec![ Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.0), Vec2::new(2.0, 0.0) ];
+    assert_eq!(algorithms::largest_inscribed_circle(&flat, 1e-4).unwrap().circle.radius, 0.0);
+}
+
+#[test]
+fn minimum_link_path() {
+    use crate::{ algorithms, math::{ polygon, Segment2 } };
+
+    // The path stays inside the polygon, it may touch the reflex vertices
+    let inside = |polygon: &[Vec2], path: &[Vec2]| {
+        path.windows(2).all(|link| {
+            (0..=20).all(|i| {
+                let t = i as f32 / 20.0;
+                let p = Vec2::new(link[0].x + (link[1].x - link[0].x) * t, link[0].y + (link[1].y - link[0].y) * t);
+                polygon::signed_distance(polygon, p) > -1e-4
+            })
+        })
+    };
+
+    // Every point of a convex polygon is seen from the source
+    let square = vec![ Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.0), Vec2::new(1.0, 1.0), Vec2::new(0.0, 1.0) ];
+    let partition = window_partition(&square, Vec2::new(0.2, 0.2)).unwrap();
+    assert_eq!(partition.regions.len(), 1);
+    assert_eq!(partition.path_to(Vec2::new(0.9, 0.7)).unwrap(), vec![ Vec2::new(0.2, 0.2), Vec2::new(0.9, 0.7) ]);
+
+    // A notch cut in the left side: the bottom arm does not see the top arm, nor the top arm the bottom one
+    // through the same part of the right side, 3 links are needed to go from one to the other
+    let notch = vec![
+        Vec2::new(0.0, 0.0), Vec2::new(3.0, 0.0), Vec2::new(3.0, 3.0), Vec2::new(0.0, 3.0),
+        Vec2::new(0.0, 2.0), Vec2::new(2.0, 2.0), Vec2::new(2.0, 1.0), Vec2::new(0.0, 1.0),
+    ];
+    let (start, end) = (Vec2::new(0.5, 0.5), Vec2::new(0.5, 2.5));
+    let partition = window_partition(&notch, start).unwrap();
+    assert_eq!(partition.link_distance(Vec2::new(2.5, 0.2)), Some(1));
+    assert_eq!(partition.link_distance(Vec2::new(2.8, 2.8)), Some(2));
+    assert_eq!(partition.link_distance(end), Some(3));
+    assert_eq!(partition.link_distance(Vec2::new(-1.0, 0.5)), None);
+
+    let window = partition.regions[1].window.unwrap();
+    assert_eq!(window, Segment2::new(Vec2::new(2.0, 1.0), Vec2::new(3.0, 4.0 / 3.0)));
+
+    let path = partition.path_to(end).unwrap();
+    assert_eq!(path.len(), 4);
+    assert_eq!((path[0], path[3]), (start, end));
+    assert!(inside(&notch, &path));
+
+    // Same distances in clockwise order and back from the end
+    let mut clockwise = notch.clone();
+    clockwise.reverse();
+    let path = algorithms::minimum_link_path(&clockwise, end, start).unwrap();
+    assert_eq!(path.len(), 4);
+    assert!(inside(&notch, &path));
+
+    // An S-shaped corridor, each of its bends takes a link
+    let s_shape = vec![
+        Vec2::new(0.0, 0.0), Vec2::new(4.0, 0.0), Vec2::new(4.0, 3.0), Vec2::new(1.0, 3.0), Vec2::new(1.0, 4.0), Vec2::new(4.0, 4.0),
+        Vec2::new(4.0, 5.0), Vec2::new(0.0, 5.0), Vec2::new(0.0, 2.0), Vec2::new(3.0, 2.0), Vec2::new(3.0, 1.0), Vec2::new(0.0, 1.0),
+    ];
+    let (start, end) = (Vec2::new(0.5, 0.5), Vec2::new(3.5, 4.5));
+    let partition = window_partition(&s_shape, start).unwrap();
+    assert_eq!(partition.link_distance(end), Some(5));
+    assert_eq!(window_partition(&s_shape, end).unwrap().link_distance(start), Some(5));
+    let path = partition.path_to(end).unwrap();
+    assert_eq!(path.len(), 6);
+    assert!(inside(&s_shape, &path));
+
+    assert!(window_partition(&square, Vec2::new(2.0, 0.5)).is_none());
+    assert!(window_partition(&square[..2], Vec2::new(0.5, 0.5)).is_none());
+}
+
+#[test]
+fn shortest_path() {
+    use crate::{ algorithms, math::cmp_f32 };
+
+    // Straight line in a convex polygon, with a vertex in the middle of an edge
+    let square = vec![ Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.0), Vec2::new(2.0, 0.0), Vec2::new(2.0, 2.0), Vec2::new(0.0, 2.0) ];
+    let result = algorithms::shortest_path(&square, Vec2::new(0.5, 0.5), Vec2::new(1.5, 1.5)).unwrap();
+    assert_eq!(result.triangles.len(), 9);
+    assert_eq!(result.path, vec![ Vec2::new(0.5, 0.5), Vec2::new(1.5, 1.5) ]);
+    assert!(cmp_f32(result.length(), 2.0_f32.sqrt()));
+
+    // Around the notch, the path bends at its two reflex vertices
+    let notch = vec![
+        Vec2::new(0.0, 0.0), Vec2::new(3.0, 0.0), Vec2::new(3.0, 3.0), Vec2::new(0.0, 3.0),
+        Vec2::new(0.0, 2.0), Vec2::new(2.0, 2.0), Vec2::new(2.0, 1.0), Vec2::new(0.0, 1.0),
+    ];
+    let (start, end) = (Vec2::new(0.5, 0.5), Vec2::new(0.5, 2.5));
+    let result = algorithms::shortest_path(&notch, start, end).unwrap();
+    assert_eq!(result.path, vec![ start, Vec2::new(2.0, 1.0), Vec2::new(2.0, 2.0), end ]);
+    assert!(cmp_f32(result.length(), 2.0 * 2.5_f32.sqrt() + 1.0));
+    // The sleeve goes from the triangle of the start to the triangle of the end through adjacent triangles
+    assert!(result.sleeve.len() >= 3);
+    for pair in result.sleeve.windows(2) {
+        let (a, b) = (&result.triangles[3 * pair[0]..3 * pair[0] + 3], &result.triangles[3 * pair[1]..3 * pair[1] + 3]);
+        assert_eq!(a.iter().filter(|v| b.contains(v)).count(), 2);
+    }
+
+    // Same path in the other direction and in clockwise order
+    let mut clockwise = notch.clone();
+    clockwise.reverse();
+    let result = algorithms::shortest_path(&clockwise, end, start).unwrap();
+    assert_eq!(result.path, vec![ end, Vec2::new(2.0, 2.0), Vec2::new(2.0, 1.0), start ]);
+
+    assert!(algorithms::shortest_path(&notch, start, Vec2::new(1.0, 1.5)).is_none());
+    assert!(algorithms::shortest_path(&notch[..2], start, end).is_none());
+}
+
+#[test]
+fn morphing() {
+    use crate::math::polygon;
+
+    // The target is the source translated, clockwise and from another vertex: every sample moves by the translation
+    let square = vec![ Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.0), Vec2::new(1.0, 1.0), Vec2::new(0.0, 1.0) ];
+    let target = vec![ Vec2::new(6.0, 5.0), Vec2::new(5.0, 5.0), Vec2::new(5.0, 6.0), Vec2::new(6.0, 6.0) ];
+    let morph = Morph::new(&square, &target, 8).unwrap();
+    assert_eq!(morph.from.len(), 8);
+    assert_eq!(morph.to.len(), 8);
+    assert!(polygon::signed_area(&morph.to) > 0.0);
+    assert_eq!(morph.from[0], Vec2::new(0.0, 0.0));
+    for (a, b) in morph.from.iter().zip(&morph.to) {
+        assert_eq!(b, &(a + &Vec2::new(5.0, 5.0)));
+    }
+    assert_eq!(morph.at(0.0), morph.from);
+    assert_eq!(morph.at(1.0), morph.to);
+    assert_eq!(morph.at(0.5)[2], &morph.from[2] + &Vec2::new(2.5, 2.5));
+
+    assert!(Morph::new(&square, &square[..2], 8).is_none());
+    assert!(Morph::new(&square, &target, 2).is_none());
+}
+
+#[test]
+fn offset_curves() {
+    use crate::math::{ cmp_f32, float, polygon, Polyline };
+
+    let segment = offset(&Polyline::open(vec![ Vec2::new(0.0, 0.0), Vec2::new(2.0, 0.0) ]), 0.5);
+    assert_eq!(segment, vec![ OffsetCurve { points: vec![ Vec2::new(0.0, 0.5), Vec2::new(2.0, 0.5) ], closed: false } ]);
+
+    // Outside of a counter-clockwise square the corners are round, inside the loops at the corners are removed
+    let square = vec![ Vec2::new(0.0, 0.0), Vec2::new(2.0, 0.0), Vec2::new(2.0, 2.0), Vec2::new(0.0, 2.0) ];
+    let closed_square = Polyline::new(square.clone(), true);
+    let outside = offset(&closed_square, -0.5);
+    assert_eq!(outside.len(), 1);
+    assert!(outside[0].closed);
+    assert!(outside[0].points.iter().all(|&p| cmp_f32(polygon::boundary_distance(&square, p), 0.5)));
+    assert!(float::abs(outside[0].length() - (8.0 + core::f32::consts::PI)) < 0.01);
+
+    let inside = offset(&closed_square, 0.5);
+    assert_eq!(inside.len(), 1);
+    assert!(inside[0].closed);
+    assert_eq!(inside[0].points.len(), 4);
+    assert!(inside[0].points.iter().all(|&p| cmp_f32(polygon::boundary_distance(&square, p), 0.5)));
+    assert!(offset(&closed_square, 1.5).is_empty());
+
+    // Two rooms linked by a corridor narrower than the offset
+    let rooms = vec![
+        Vec2::new(0.0, 0.0), Vec2::new(2.0, 0.0), Vec2::new(2.0, 0.8), Vec2::new(4.0, 0.8),
+        Vec2::new(4.0, 0.0), Vec2::new(6.0, 0.0), Vec2::new(6.0, 2.0), Vec2::new(4.0, 2.0),
+        Vec2::new(4.0, 1.2), Vec2::new(2.0, 1.2), Vec2::new(2.0, 2.0), Vec2::new(0.0, 2.0),
+    ];
+    let inside = offset(&Polyline::new(rooms.clone(), true), 0.3);
+    assert_eq!(inside.len(), 2);
+    assert!(polygon::contains(&inside[0].points, Vec2::new(1.0, 1.0)) != polygon::contains(&inside[1].points, Vec2::new(1.0, 1.0)));
+    for curve in &inside {
+        assert!(curve.closed);
+        assert!(curve.points.iter().all(|&p| polygon::signed_distance(&rooms, p) > 0.29));
+    }
+    assert_eq!(offset(&Polyline::new(rooms.clone(), true), 0.1).len(), 1);
+
+    // A clockwise polygon has its inside on the right
+    let mut clockwise = closed_square.clone();
+    clockwise.points.reverse();
+    assert_eq!(offset(&clockwise, -0.5)[0].points.len(), 4);
+
+    // The band covers the area between the polyline and its offset
+    let band = offset::band(&closed_square, -0.5);
+    let area = band.chunks(3).map(|tri| float::abs(Vec2::shoelace(tri[0], tri[1], tri[2])) / 2.0).sum::<f32>();
+    assert!(float::abs(area - (4.0 + core::f32::consts::PI / 4.0)) < 0.01);
+}
+
+#[test]
+fn boolean_operations_with_arcs() {
+    use crate::math::{ float, Arc, Circle, Segment2 };
+    use boolean::{ area, contains, polygon_contour, Edge };
+    use core::f32::consts::PI;
+
+    let disk = |x: f32, y: f32, radius: f32| vec![ Edge::Arc(Arc::new(Circle::new(Vec2::new(x, y), radius), 0.0, 2.0 * PI)) ];
+    let square = [ polygon_contour(&[ Vec2::new(0.0, 0.0), Vec2::new(2.0, 0.0), Vec2::new(2.0, 2.0), Vec2::new(0.0, 2.0) ]) ];
+    let close = |a: f32, b: f32| float::abs(a - b) < 1e-3;
+
+    // A quarter of the disk is in the square
+    let quarter = boolean(&[ disk(0.0, 0.0, 1.0) ], &square, Operation::Intersection);
+    assert_eq!(quarter.len(), 1);
+    assert!(close(area(&quarter), PI / 4.0));
+    assert_eq!(quarter[0].iter().filter(|edge| matches!(edge, Edge::Arc(_))).count(), 1);
+    assert_eq!(quarter[0].iter().filter(|edge| matches!(edge, Edge::Segment(_))).count(), 2);
+    assert!(contains(&quarter, Vec2::new(0.5, 0.5)) && !contains(&quarter, Vec2::new(0.9, 0.9)));
+    assert!(close(area(&boolean(&[ disk(0.0, 0.0, 1.0) ], &square, Operation::Union)), 4.0 + 0.75 * PI));
+    assert!(close(area(&boolean(&square, &[ disk(0.0, 0.0, 1.0) ], Operation::Difference)), 4.0 - PI / 4.0));
+
+    // The lens between two disks of radius 1 whose centers are 1 apart
+    let lens = 2.0 * 0.5f32.acos() - 0.5 * 3.0f32.sqrt();
+    let (a, b) = ([ disk(0.0, 0.0, 1.0) ], [ disk(1.0, 0.0, 1.0) ]);
+    assert!(close(area(&boolean(&a, &b, Operation::Intersection)), lens));
+    assert!(close(area(&boolean(&a, &b, Operation::Union)), 2.0 * PI - lens));
+    assert!(close(area(&boolean(&a, &b, Operation::Difference)), PI - lens));
+    assert!(close(area(&boolean(&a, &b, Operation::Xor)), 2.0 * PI - 2.0 * lens));
+    assert!(boolean(&a, &b, Operation::Intersection)[0].iter().all(|edge| matches!(edge, Edge::Arc(_))));
+
+    // A disk inside the square leaves a round hole, and a stadium made of segments and arcs
+    let holed = boolean(&square, &[ disk(1.0, 1.0, 0.5) ], Operation::Difference);
+    assert_eq!(holed.len(), 2);
+    assert!(close(area(&holed), 4.0 - PI / 4.0));
+    assert!(!contains(&holed, Vec2::new(1.0, 1.0)) && contains(&holed, Vec2::new(0.2, 0.2)));
+    let stadium = [ vec![
+        Edge::Segment(Segment2::new(Vec2::new(0.0, -1.0), Vec2::new(2.0, -1.0))),
+        Edge::Arc(Arc::new(Circle::new(Vec2::new(2.0, 0.0), 1.0), -PI / 2.0, PI)),
+        Edge::Segment(Segment2::new(Vec2::new(2.0, 1.0), Vec2::new(0.0, 1.0))),
+        Edge::Arc(Arc::new(Circle::new(Vec2::new(0.0, 0.0), 1.0), PI / 2.0, PI)),
+    ] ];
+    assert!(close(area(&stadium), 4.0 + PI));
+    assert!(close(area(&boolean(&stadium, &square, Operation::Intersection)), 2.0));
+    assert!(close(area(&boolean(&stadium, &a, Operation::Union)), 4.0 + PI));
+    assert!(close(area(&boolean(&stadium, &a, Operation::Difference)), 4.0));
+}
+
+#[test]
+fn medial_axis() {
+    use crate::math::{ float, polygon };
+
+    let rectangle = vec![ Vec2::new(0.0, 0.0), Vec2::new(2.0, 0.0), Vec2::new(2.0, 1.0), Vec2::new(0.0, 1.0) ];
+    let axis = MedialAxis::new(&rectangle, 0.05).unwrap();
+    assert_eq!(axis.samples.len(), 4 * 21 + 2 * 20);
+    assert_eq!(axis.samples[0], rectangle[0]);
+    assert!(!axis.edges.is_empty());
+    for edge in &axis.edges {
+        let end = edge.end.unwrap();
+        assert!(polygon::contains(&rectangle, edge.start) && polygon::contains(&rectangle, end));
+        // The points of the axis are about as far from the boundary as from the closest sample
+        for &p in &[ edge.start, end ] {
+            assert!(float::abs(polygon::boundary_distance(&rectangle, p) - axis.radius(p)) < 0.05);
+        }
+    }
+    // The middle of the rectangle is on its axis, the middle of its short sides is not
+    let closest = |p: Vec2| axis.edges.iter().map(|edge| (&edge.start - &p).length()).fold(f32::INFINITY, f32::min);
+    assert!(closest(Vec2::new(1.0, 0.5)) < 0.05);
+    assert!(closest(Vec2::new(0.1, 0.5)) > 0.25);
+
+    assert!(MedialAxis::new(&rectangle[..2], 0.05).is_none());
+    assert!(MedialAxis::new(&rectangle, 0.0).is_none());
+
+    // The boundary goes through (1, 1) twice, it is only sampled once
+    let pinched = vec![
+        Vec2::new(0.0, 0.0), Vec2::new(2.0, 0.0), Vec2::new(1.0, 1.0),
+        Vec2::new(2.0, 2.0), Vec2::new(0.0, 2.0), Vec2::new(1.0, 1.0),
+    ];
+    let axis = MedialAxis::new(&pinched, 0.25).unwrap();
+    assert_eq!(axis.samples[0], pinched[0]);
+    assert_eq!(axis.samples.iter().filter(|&&p| p == Vec2::new(1.0, 1.0)).count(), 1);
+    for (i, p) in axis.samples.iter().enumerate() {
+        assert!(!axis.samples[i + 1..].contains(p));
+    }
+}
+
+#[test]
+fn distance_field() {
+    use crate::math::{ float, polygon };
+
+    let square = vec![ Vec2::new(-0.5, -0.5), Vec2::new(0.5, -0.5), Vec2::new(0.5, 0.5), Vec2::new(-0.5, 0.5) ];
+    let bounds = Rect::new(Vec2::new(-1.0, -1.0), Vec2::new(1.0, 0.5));
+    let exact = DistanceField::exact(&square, bounds, 41).unwrap();
+    assert_eq!((exact.width, exact.height), (41, 31));
+    assert_eq!(exact.position(0, 0), Vec2::new(-1.0, 0.5));
+    assert_eq!(exact.position(20, 10), Vec2::new(0.0, 0.0));
+    assert!(float::abs(exact.value(20, 10) - 0.5) < 1e-5);
+    assert!(float::abs(exact.value(0, 30) + core::f32::consts::SQRT_2 / 2.0) < 1e-5);
+
+    // The contour at 0 follows the boundary, the one inside at 0.25 is a smaller square
+    let contour = exact.contour(0.0);
+    assert!(!contour.is_empty());
+    assert!(contour.iter().all(|&p| polygon::boundary_distance(&square, p) < 1e-4));
+    assert!(exact.contour(0.25).iter().all(|&p| float::abs(polygon::boundary_distance(&square, p) - 0.25) < 1e-4));
+
+    // The transform is within a cell of the exact distances
+    let transform = DistanceField::transform(&square, bounds, 41).unwrap();
+    assert!(exact.values.iter().zip(&transform.values).all(|(a, b)| float::abs(a - b) <= exact.cell));
+    assert!(transform.values.iter().any(|&value| value > 0.0) && transform.values.iter().any(|&value| value < 0.0));
+
+    assert!(DistanceField::exact(&square[..2], bounds, 41).is_none());
+    assert!(DistanceField::exact(&square, bounds, 1).is_none());
+}
+
+#[test]
+fn contour_lines() {
+    use crate::math::float;
+
+    assert_eq!(contour_levels(-0.25, 1.0, 0.5), vec![ 0.0, 0.5, 1.0 ]);
+    assert!(contour_levels(0.0, 1.0, 0.0).is_empty());
+
+    // A peak in the middle of a square, the contours around it are closed
+    let points = vec![ Vec2::new(-1.0, -1.0), Vec2::new(1.0, -1.0), Vec2::new(1.0, 1.0), Vec2::new(-1.0, 1.0), Vec2::new(0.0, 0.0) ];
+    let heights = vec![ 0.0, 0.0, 0.0, 0.0, 1.0 ];
+    let indices = Incremental2dTriangulation::delaunay(&points);
+    assert_eq!(indices.len(), 12);
+    let lines = contour_lines::contour_lines(&points, &heights, &indices, 0.5);
+    assert_eq!(lines.len(), 1);
+    assert!(lines[0].closed);
+    assert_eq!(lines[0].points.len(), 4);
+    assert!(lines[0].points.iter().all(|p| float::abs(float::abs(p.x) - 0.5) < 1e-5 && float::abs(float::abs(p.y) - 0.5) < 1e-5));
+    assert!(contour_lines::contour_lines(&points, &heights, &indices, 0.0).is_empty());
+
+    // A slope rising along x, the contour crosses it from one side to the other
+    let heights = vec![ 0.0, 1.0, 1.0, 0.0, 0.5 ];
+    let lines = contour_lines::contour_lines(&points, &heights, &indices, 0.25);
+    assert_eq!(lines.len(), 1);
+    assert!(!lines[0].closed);
+    assert!(lines[0].points.iter().all(|p| float::abs(p.x + 0.5) < 1e-5));
+    let ends = [ lines[0].points[0].y, lines[0].points.last().unwrap().y ];
+    assert!(ends.contains(&-1.0) && ends.contains(&1.0));
+
+    // The heights are linear over each triangle, here over the whole square
+    let height = |p| contour_lines::interpolate(&points, &heights, &indices, p);
+    assert!(float::abs(height(Vec2::new(0.5, 0.2)).unwrap() - 0.75) < 1e-5);
+    assert!(float::abs(height(Vec2::new(-1.0, 0.0)).unwrap()) < 1e-5);
+    assert_eq!(height(Vec2::new(1.5, 0.0)), None);
+}
+
+#[test]
+fn visible_points() {
+    use crate::math::Segment2;
+
+    let query = Vec2::new(0.0, 0.0);
+    let obstacles = vec![
+        Segment2::new(Vec2::new(1.0, -1.0), Vec2::new(1.0, 1.0)),
+        // Across the negative x axis, where the sweep starts
+        Segment2::new(Vec2::new(-1.0, 1.0), Vec2::new(-1.0, -1.0)),
+        // Crossing the first obstacle
+        Segment2::new(Vec2::new(0.5, 0.5), Vec2::new(1.5, 0.5)),
+    ];
+    let points = vec![ Vec2::new(2.0, 0.0), Vec2::new(-2.0, 0.0), Vec2::new(0.5, 0.0), Vec2::new(0.0, 2.0), Vec2::new(2.0, 1.0), Vec2::new(1.0, 0.0) ];
+    let sights = visibility::visible_points(query, &points, &obstacles);
+    assert_eq!(sights[0], Sight::Blocked(Vec2::new(1.0, 0.0)));
+    assert_eq!(sights[1], Sight::Blocked(Vec2::new(-1.0, 0.0)));
+    assert_eq!(sights[2], Sight::Visible);
+    assert_eq!(sights[3], Sight::Visible);
+    assert_eq!(sights[4], Sight::Blocked(Vec2::new(1.0, 0.5)));
+    assert_eq!(sights[5], Sight::Visible); // On an obstacle
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn visible_points_random() {
+    use crate::math::Segment2;
+
+    // Compared with testing the segment to each point against every obstacle
+    for _ in 0..20 {
+        let query = Vec2::random_range(-0.5, 0.5, -0.5, 0.5);
+        let points = (0..50).map(|_| Vec2::random_range(-1.0, 1.0, -1.0, 1.0)).collect::<Vec<_>>();
+        let obstacles = (0..10)
+            .map(|_| Segment2::new(Vec2::random_range(-1.0, 1.0, -1.0, 1.0), Vec2::random_range(-1.0, 1.0, -1.0, 1.0)))
+            .collect::<Vec<_>>();
+        let sights = visibility::visible_points(query, &points, &obstacles);
+        for (p, sight) in points.iter().zip(&sights) {
+            let offset = p - &query;
+            let hit = obstacles.iter().filter_map(|o| o.ray_intersection(query, offset)).fold(f32::INFINITY, f32::min);
+            // Skips the points too close to an obstacle for the comparison to be reliable
+            if (hit - 1.0).abs() < 1e-3 {
+                continue;
+            }
+            assert_eq!(*sight == Sight::Visible, hit > 1.0, "{:?} from {:?}", p, query);
+        }
+    }
+}
+
+#[test]
+fn bounding() {
+    use crate::math::{ Circle, float };
+
+    assert!(<[Vec2]>::bounding_rect(&[]).is_none());
+    let diamond = [Vec2::new(1.0, 0.0), Vec2::new(0.0, 1.0), Vec2::new(-1.0, 0.0), Vec2::new(0.0, -1.0), Vec2::new(0.2, 0.1)];
+    assert_eq!(diamond.bounding_rect(), Some(Rect::new(Vec2::new(-1.0, -1.0), Vec2::new(1.0, 1.0))));
+    assert_eq!(diamond.bounding_circle(), Some(Circle::new(Vec2::new(0.0, 0.0), 1.0)));
+
+    // The oriented box is the diamond itself, half the area of the axis-aligned one
+    let corners = diamond.oriented_bounding_box().unwrap();
+    assert!(float::abs(crate::math::polygon::signed_area(&corners) - 2.0) < 1e-5);
+    assert!(diamond[..4].iter().all(|p| corners.contains(p)));
+    assert_eq!(diamond[..1].oriented_bounding_box(), Some([ diamond[0]; 4 ]));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn bounding_3d() {
+    use crate::math::{ Vec3, float };
+    use cgmath::InnerSpace;
+
+    // A box with sides 4, 1 and 0.5, rotated around the z axis
+    let (cos, sin) = (0.6, 0.8);
+    let points = [ -1.0, 1.0 ].iter()
+        .flat_map(|&x| [ -1.0, 1.0 ].iter().flat_map(move |&y| [ -1.0, 1.0 ].iter().map(move |&z| (x * 2.0, y * 0.5, z * 0.25))))
+        .map(|(x, y, z)| Vec3::new(cos * x - sin * y + 1.0, sin * x + cos * y, z))
+        .collect::<Vec<_>>();
+    let mesh = crate::scene::Mesh { vertices: points.clone(), indices: Vec::new() };
+
+    let bounds = mesh.bounding_rect().unwrap();
+    assert!(float::abs(bounds.max.0.x - (1.0 + 0.6 * 2.0 + 0.8 * 0.5)) < 1e-5);
+    assert!(float::abs(bounds.min.0.z + 0.25) < 1e-5);
+
+    let sphere = mesh.bounding_circle().unwrap();
+    let diagonal = (4.0f32 * 4.0 + 1.0 + 0.25).sqrt() / 2.0;
+    assert!(sphere.radius >= diagonal - 1e-4 && sphere.radius < diagonal * 1.05);
+    assert!(points.iter().all(|p| (p.0 - sphere.center.0).magnitude() <= sphere.radius + 1e-4));
+
+    let obb = mesh.oriented_bounding_box().unwrap();
+    for (&half, &expected) in obb.half_extents.iter().zip(&[ 2.0, 0.5, 0.25 ]) {
+        assert!(float::abs(half - expected) < 1e-4);
+    }
+    assert!(float::abs(obb.center.0.x - 1.0) < 1e-5);
+}
+
+#[test]
+fn arrangement() {
+    use crate::math::Segment2;
+
+    let segment = |ax: f32, ay: f32, bx: f32, by: f32| Segment2::new(Vec2::new(ax, ay), Vec2::new(bx, by));
+    let counts = |arrangement: &Arrangement| (arrangement.vertices().len(), arrangement.edge_count(), arrangement.faces().len());
+
+    // Two crossing segments split each other
+    let cross = Arrangement::new(&[ segment(-1.0, 0.0, 1.0, 0.0), segment(0.0, -1.0, 0.0, 1.0) ]);
+    assert_eq!(counts(&cross), (5, 4, 1));
+
+    // Square with both diagonals: 4 triangles
+    let square = vec![
+        segment(0.0, 0.0, 2.0, 0.0), segment(2.0, 0.0, 2.0, 2.0), segment(2.0, 2.0, 0.0, 2.0),
+        segment(0.0, 2.0, 0.0, 0.0), segment(0.0, 0.0, 2.0, 2.0), segment(2.0, 0.0, 0.0, 2.0),
+    ];
+    let arrangement = Arrangement::new(&square);
+    assert_eq!(counts(&arrangement), (5, 8, 5));
+    let face = arrangement.locate(Vec2::new(1.0, 0.5));
+    assert_ne!(face, 0);
+    let boundary = &arrangement.face_boundaries(face)[0];
+    assert_eq!(boundary.len(), 3);
+    assert!(crate::math::polygon::signed_area(boundary) > 0.0);
+    assert_eq!(arrangement.locate(Vec2::new(3.0, 1.0)), 0);
+
+    // Every half-edge is linked both ways and bounds the face of its cycle
+    for (idx, edge) in arrangement.half_edges().iter().enumerate() {
+        assert_eq!(arrangement.half_edges()[edge.next].prev, idx);
+        assert_eq!(arrangement.half_edges()[edge.twin].twin, idx);
+        assert_eq!(arrangement.half_edges()[edge.next].face, edge.face);
+    }
+
+    // A triangle inside a square is a hole of the face of the square
+    let mut nested = square[..4].to_vec();
+    nested.extend_from_slice(&[ segment(0.5, 0.5, 1.5, 0.5), segment(1.5, 0.5, 1.0, 1.5), segment(1.0, 1.5, 0.5, 0.5) ]);
+    let arrangement = Arrangement::new(&nested);
+    assert_eq!(counts(&arrangement), (7, 7, 3));
+    assert_eq!(arrangement.component_count(), 2);
+    let outer = arrangement.locate(Vec2::new(0.2, 0.2));
+    let inner = arrangement.locate(Vec2::new(1.0, 0.8));
+    assert!(outer != 0 && inner != 0 && outer != inner);
+    assert_eq!(arrangement.faces()[outer].inner.len(), 1);
+    assert_eq!(arrangement.face_boundaries(outer).len(), 2);
+    assert_eq!(arrangement.faces()[0].inner.len(), 1);
+
+    // Overlapping segments share an edge, a segment without length is ignored
+    let overlapping = Arrangement::new(&[ segment(0.0, 0.0, 2.0, 0.0), segment(1.0, 0.0, 3.0, 0.0), segment(1.0, 1.0, 1.0, 1.0) ]);
+    assert_eq!(counts(&overlapping), (4, 3, 1));
+
+    // A segment ending on another one, the T junction splits it
+    let t = Arrangement::new(&[ segment(-1.0, 0.0, 1.0, 0.0), segment(0.0, 0.0, 0.0, 1.0) ]);
+    assert_eq!(counts(&t), (4, 3, 1));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn arrangement_euler() {
+    use crate::math::Segment2;
+
+    // V - E + F = 1 + C for every planar subdivision
+    for _ in 0..20 {
+        let segments = (0..15)
+                        .map(|_| Segment2::new(Vec2::random_range(-1.0, 1.0, -1.0, 1.0), Vec2::random_range(-1.0, 1.0, -1.0, 1.0)))
+                        .collect::<Vec<_>>();
+        let arrangement = Arrangement::new(&segments);
+        let (v, e, f) = (arrangement.vertices().len(), arrangement.edge_count(), arrangement.faces().len());
+        assert_eq!(v + f, e + 1 + arrangement.component_count());
+    }
+}
+
+#[test]
+fn circle_packing() {
+    use crate::math::polygon;
+
+    let square = vec![ Vec2::new(0.0, 0.0), Vec2::new(2.0, 0.0), Vec2::new(2.0, 2.0), Vec2::new(0.0, 2.0) ];
+    let mut packing = CirclePacking::new(square.clone());
+    let first = packing.step(0.0).unwrap();
+    assert!((first.radius - 1.0).abs() < 1e-3);
+    assert!((packing.coverage() - core::f32::consts::PI / 4.0).abs() < 1e-3);
+
+    // The next circles fit in the corners, with a radius of (3 - 2√2)
+    let second = packing.step(0.0).unwrap();
+    assert!((second.radius - (3.0 - 2.0 * 2.0_f32.sqrt())).abs() < 1e-3);
+
+    packing.pack(50, 0.9, 0.01);
+    let circles = packing.circles();
+    assert!(circles.len() <= 50 && circles.len() > 5);
+    assert!(circles.windows(2).all(|pair| pair[0].radius >= pair[1].radius - 1e-3));
+    let tolerance = 1e-3;
+    for (i, a) in circles.iter().enumerate() {
+        assert!(polygon::signed_distance(&square, a.center) >= a.radius - tolerance);
+        for b in &circles[i + 1..] {
+            assert!((&a.center - &b.center).length() >= a.radius + b.radius - tolerance);
+        }
+    }
+
+    // Nothing fits in a polygon without area
+    let mut flat = CirclePacking::new(vec![ Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.0), Vec2::new(2.0, 0.0) ]);
+    assert!(flat.step(0.0).is_none());
+    assert_eq!(flat.coverage(), 0.0);
+}
+
+#[test]
+fn delaunay_validation() {
+    // The circumcircle of each triangle contains the opposite point
+    let points = [ Vec2::new(0.0, 0.0), Vec2::new(1.0, -0.1), Vec2::new(2.0, 0.0), Vec2::new(1.0, 1.0) ];
+    let mut indices = vec![ 0, 1, 2, 0, 2, 3 ];
+    assert_eq!(validate_delaunay(&points, &indices), vec![ (0, 2) ]);
+    assert_eq!(repair_delaunay(&points, &mut indices), 1);
+    assert!(validate_delaunay(&points, &indices).is_empty());
+    assert_eq!(indices.len(), 6);
+    assert!(indices.chunks(3).all(|t| t.contains(&1) && t.contains(&3)));
+
+    // Cocircular points have several Delaunay triangulations, both diagonals of a square are legal
+    let square = [ Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.0), Vec2::new(1.0, 1.0), Vec2::new(0.0, 1.0) ];
+    assert!(validate_delaunay(&square, &[ 0, 1, 2, 0, 2, 3 ]).is_empty());
+    assert!(validate_delaunay(&square, &[ 0, 1, 3, 1, 2, 3 ]).is_empty());
+    assert!(validate_delaunay(&square, &[ 0, 1, 2 ]).is_empty());
+    assert!(validate_delaunay(&square, &[]).is_empty());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn delaunay_repair_random() {
+    use crate::math::Circle;
+
+    let mut points = (0..300).map(|_| Vec2::random_range(-1.0, 1.0, -1.0, 1.0)).collect::<Vec<_>>();
+    let mut indices = Incremental2dTriangulation::triangulate(&mut points);
+    let triangles = indices.len() / 3;
+    assert!(!validate_delaunay(&points, &indices).is_empty());
+
+    repair_delaunay(&points, &mut indices);
+    assert_eq!(indices.len() / 3, triangles);
+    assert!(validate_delaunay(&points, &indices).is_empty());
+    assert_eq!(repair_delaunay(&points, &mut indices), 0);
+
+    // Locally Delaunay edges make the circumcircles empty
+    for t in indices.chunks(3) {
+        if let Some(circle) = Circle::circumscribed(points[t[0]], points[t[1]], points[t[2]]) {
+            assert!(points.iter().all(|p| (p - &circle.center).length() >= circle.radius - 1e-3));
+        }
+    }
+}
+
+#[test]
+fn hull_validation() {
+    let points = [
+        Vec2::new(0.0, 0.0),
+        Vec2::new(1.0, 0.0),
+        Vec2::new(1.0, 1.0),
+        Vec2::new(0.0, 1.0),
+        Vec2::new(0.5, 0.0), // On an edge
+        Vec2::new(0.5, 0.5), // Inside
+        Vec2::new(1.0, 1.0), // Duplicate
+    ];
+    assert!(validate_hull(&points, &[ 0, 1, 2, 3 ]).is_empty());
+    assert_eq!(validate_hull(&points, &[ 0, 3, 2, 1 ]), vec![ HullViolation::Clockwise ]);
+    assert_eq!(validate_hull(&points, &[ 0, 4, 1, 2, 3 ]), vec![ HullViolation::NotConvex(1) ]);
+    assert_eq!(validate_hull(&points, &[ 0, 1, 3 ]), vec![ HullViolation::Outside(2), HullViolation::Outside(6) ]);
+    assert_eq!(validate_hull(&points, &[ 0, 1, 2, 6, 3 ]), vec![ HullViolation::Duplicate(3) ]);
+    assert_eq!(validate_hull(&points, &[ 0, 1, 9 ]), vec![ HullViolation::InvalidIndex(2) ]);
+
+    for points in &[ &points[..], &points[..3] ] {
+        let jarvis = JarvisMarch::march(points.iter());
+        assert!(validate_hull(points, &jarvis).is_empty(), "{:?}", jarvis);
+        let graham = GrahamScan::scan(points);
+        assert!(validate_hull(points, &graham).is_empty(), "{:?}", graham);
+    }
+
+    // Degenerate inputs
+    let empty: [Vec2; 0] = [];
+    assert!(JarvisMarch::march(empty.iter()).is_empty());
+    assert!(GrahamScan::scan(&empty).is_empty());
+    assert!(validate_hull(&empty, &[]).is_empty());
+
+    let same = [ Vec2::new(0.5, 0.5); 3 ];
+    assert_eq!(JarvisMarch::march(same.iter()), vec![ 0 ]);
+    assert_eq!(GrahamScan::scan(&same), vec![ 0 ]);
+    assert!(validate_hull(&same, &[ 0 ]).is_empty());
+    assert_eq!(validate_hull(&same, &[]), vec![ HullViolation::Outside(0), HullViolation::Outside(1), HullViolation::Outside(2) ]);
+
+    let collinear = [ Vec2::new(0.5, 0.5), Vec2::new(0.0, 0.0), Vec2::new(1.0, 1.0), Vec2::new(0.0, 0.0), Vec2::new(0.25, 0.25) ];
+    assert_eq!(JarvisMarch::march(collinear.iter()), vec![ 1, 2 ]);
+    assert_eq!(GrahamScan::scan(&collinear), vec![ 1, 2 ]);
+    assert_eq!(hull_positions(&collinear, &GrahamScan::scan(&collinear)), vec![ collinear[1], collinear[2] ]);
+    assert!(validate_hull(&collinear, &[ 1, 2 ]).is_empty());
+    assert_eq!(validate_hull(&collinear, &[ 1, 0 ]), vec![ HullViolation::Outside(2) ]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn hull_validation_random() {
+    // Points on a grid have many duplicates and collinear points
+    for count in [ 3, 10, 100, 1000 ].iter() {
+        let points = (0..*count).map(|_| Vec2::random_range(-1.0, 1.0, -1.0, 1.0).snapped(0.25)).collect::<Vec<_>>();
+        let jarvis = JarvisMarch::march(points.iter());
+        assert!(validate_hull(&points, &jarvis).is_empty(), "{:?}", jarvis);
+        let graham = GrahamScan::scan(&points);
+        assert!(validate_hull(&points, &graham).is_empty(), "{:?}", graham);
+
+        // Both hulls have the same vertices, from a different start
+        let start = graham.iter().position(|&idx| idx == jarvis[0]).unwrap();
+        assert_eq!(jarvis, [ &graham[start..], &graham[..start] ].concat());
+    }
+}
+
+#[test]
+fn hull_of_primitives() {
+    use crate::math::Segment2;
+
+    let primitives = vec![
+        Primitive::Point(Vec2::new(0.5, 0.5)),
+        Primitive::Segment(Segment2::new(Vec2::new(0.0, 0.0), Vec2::new(2.0, 0.0))),
+        // Not convex, its reflex vertex and the shared one are not on the hull
+        Primitive::Polygon(vec![ Vec2::new(2.0, 0.0), Vec2::new(2.0, 2.0), Vec2::new(1.0, 1.0), Vec2::new(0.0, 2.0) ]),
+    ];
+    let vertices = primitive_vertices(&primitives);
+    assert_eq!(vertices.len(), 6);
+
+    let mut graham = GrahamScan::new();
+    graham.set_primitives(&primitives);
+    assert_eq!(hull_positions(graham.points(), graham.hull()),
+               vec![ Vec2::new(0.0, 0.0), Vec2::new(2.0, 0.0), Vec2::new(2.0, 2.0), Vec2::new(0.0, 2.0) ]);
+
+    let mut jarvis = JarvisMarch::new();
+    jarvis.set_primitives(&primitives);
+    assert!(validate_hull(&vertices, jarvis.hull()).is_empty());
+    assert_eq!(jarvis.hull().len(), 4);
+
+    // A segment alone is its own hull
+    let segment = [ Primitive::from(Segment2::new(Vec2::new(1.0, 1.0), Vec2::new(-1.0, -0.0))) ];
+    assert_eq!(GrahamScan::scan(&primitive_vertices(&segment)), vec![ 1, 0 ]);
+}
+
+#[test]
+fn rotating_calipers_rectangle() {
+    let mut calipers = RotatingCalipers::new();
+    calipers.set_points(vec![
+        Vec2::new(0.0, 0.0),
+        Vec2::new(2.0, 0.0),
+        Vec2::new(1.0, 0.5), // Inside
+        Vec2::new(2.0, 1.0),
+        Vec2::new(0.0, 1.0),
+    ]);
+    assert_eq!(calipers.calipers().len(), 4);
+    assert_eq!(calipers.minimum_width().map(|caliper| caliper.width), Some(1.0));
+    assert_eq!(calipers.minimum_area().map(Caliper::area), Some(2.0));
+    assert_eq!(calipers.diameter().map(|(_, length)| length), Some(5.0_f32.sqrt()));
+
+    // The parallel sides make all the pairs of corners antipodal
+    assert_eq!(calipers.antipodal_pairs(), &[ (0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3) ]);
+
+    let bottom = calipers.calipers()[0];
+    assert_eq!(bottom.normal, Vec2::new(0.0, -1.0));
+    assert_eq!(bottom.support, 0.0);
+    assert_eq!(rotating_calipers::support(calipers.points(), bottom.normal), Some(bottom.support));
+
+    calipers.set_points(vec![ Vec2::new(0.0, 0.0) ]);
+    assert!(calipers.calipers().is_empty());
+    assert!(calipers.diameter().is_none());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn rotating_calipers_random() {
+    for count in [ 3, 10, 100, 1000 ].iter() {
+        let mut calipers = RotatingCalipers::new();
+        calipers.set_points((0..*count).map(|_| Vec2::random_range(-1.0, 1.0, -1.0, 1.0)).collect());
+        let hull = hull_positions(calipers.points(), calipers.hull());
+
+        // The largest distance between two hull vertices is between an antipodal pair
+        let mut diameter = 0.0_f32;
+        for a in &hull {
+            for b in &hull {
+                diameter = diameter.max((b - a).length());
+            }
+        }
+        let (_, length) = calipers.diameter().unwrap();
+        assert!((length - diameter).abs() < 1e-5, "{} != {}", length, diameter);
+
+        // Each caliper is a pair of support lines: all the points are between them
+        for caliper in calipers.calipers() {
+            let support = rotating_calipers::support(calipers.points(), caliper.normal).unwrap();
+            assert!((support - caliper.support).abs() < 1e-5);
+            let opposite = rotating_calipers::support(calipers.points(), -1.0 * &caliper.normal).unwrap();
+            assert!((support + opposite - caliper.width).abs() < 1e-5);
+            assert!(caliper.area() >= calipers.minimum_area().unwrap().area());
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn comparison_harness() {
+    use crate::harness::{ self, Suite };
+
+    assert!(Suite::ALL.iter().all(|suite| suite.generate(50).len() <= 50));
+    assert_eq!(Suite::Onion.generate(24).len(), 24);
+
+    let report = harness::compare(&[ 3, 20, 200 ]);
+    assert_eq!(report.comparisons.len(), Suite::ALL.len() * 3 * (harness::HULLS.len() + harness::TRIANGULATIONS.len()));
+    assert!(report.is_compatible(), "{}", report);
+}
+
+#[test]
+fn convex_hull_nd() {
+    // Corners of the tesseract, its center and points inside it
+    let mut points = (0..16u32).map(|i| (0..4).map(|bit| f64::from((i >> bit) & 1)).collect::<Vec<_>>()).collect::<Vec<_>>();
+    points.push(vec![ 0.5; 4 ]);
+    points.push(vec![ 0.25, 0.75, 0.5, 0.1 ]);
+    let hull = ConvexHullNd::new(&points).unwrap();
+    assert_eq!(hull.dimension(), 4);
+    assert_eq!(hull.vertices(), (0..16).collect::<Vec<_>>());
+    for facet in hull.facets() {
+        assert!(points.iter().all(|p| facet.height(p) <= 1e-9));
+        assert!(facet.vertices.iter().all(|&idx| facet.height(&points[idx]).abs() <= 1e-9));
+    }
+    // The edges join the corners that differ by one coordinate, without the diagonals of the cubic cells
+    let edges = hull.edges();
+    assert_eq!(edges.len(), 32);
+    assert!(edges.iter().all(|&(a, b)| (a ^ b).count_ones() == 1));
+    assert!(hull.contains(&[ 0.9, 0.1, 0.5, 0.5 ]));
+    assert!(!hull.contains(&[ 1.1, 0.5, 0.5, 0.5 ]));
+
+    // A simplex is its own hull, with a facet opposite to each vertex
+    let simplex = [ vec![ 0.0, 0.0, 0.0 ], vec![ 1.0, 0.0, 0.0 ], vec![ 0.0, 1.0, 0.0 ], vec![ 0.0, 0.0, 1.0 ], vec![ 0.1, 0.1, 0.1 ] ];
+    let hull = ConvexHullNd::new(&simplex).unwrap();
+    assert_eq!(hull.facets().len(), 4);
+    assert_eq!(hull.vertices(), vec![ 0, 1, 2, 3 ]);
+    assert_eq!(hull.edges().len(), 6);
+
+    // Points in a hyperplane have no interior
+    let flat = (0..10).map(|i| vec![ f64::from(i), f64::from(i * i), 1.0, 2.0 * f64::from(i) ]).collect::<Vec<_>>();
+    assert!(ConvexHullNd::new(&flat).is_none());
+    assert!(ConvexHullNd::new(&simplex[..3]).is_none());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn linear_programming_2d() {
+    use crate::math::HalfPlane;
+
+    // 0 <= x, 0 <= y, x + y <= 4, x + 3y <= 6
+    let constraints = [
+        HalfPlane::new(Vec2::new(-1.0, 0.0), 0.0),
+        HalfPlane::new(Vec2::new(0.0, -1.0), 0.0),
+        HalfPlane::new(Vec2::new(1.0, 1.0), 4.0),
+        HalfPlane::new(Vec2::new(1.0, 3.0), 6.0),
+    ];
+    let close = |a: Vec2, b: Vec2| (&a - &b).length() < 1e-4;
+    // Parallel to the edge from (3, 1) to (4, 0), any of its points is optimal
+    let optimum = lp2d(&constraints, Vec2::new(1.0, 1.0)).unwrap();
+    assert!((optimum.x + optimum.y - 4.0).abs() < 1e-4 && optimum.x >= 3.0 - 1e-4 && optimum.x <= 4.0 + 1e-4);
+    assert!(close(lp2d(&constraints, Vec2::new(1.0, 2.0)).unwrap(), Vec2::new(3.0, 1.0)));
+    assert!(close(lp2d(&constraints, Vec2::new(2.0, 1.0)).unwrap(), Vec2::new(4.0, 0.0)));
+    assert!(close(lp2d(&constraints, Vec2::new(0.0, 1.0)).unwrap(), Vec2::new(0.0, 2.0)));
+    assert!(close(lp2d(&constraints, Vec2::new(-1.0, -1.0)).unwrap(), Vec2::new(0.0, 0.0)));
+    let feasible = lp2d(&constraints, Vec2::new(0.0, 0.0)).unwrap();
+    assert!(constraints.iter().all(|c| c.signed_distance(feasible) < 1e-4));
+
+    // Unbounded, infeasible, and an objective orthogonal to an unbounded edge
+    assert!(lp2d(&constraints[..2], Vec2::new(1.0, 0.0)).is_none());
+    let infeasible = [ constraints[0], HalfPlane::new(Vec2::new(1.0, 0.0), -1.0) ];
+    assert!(lp2d(&infeasible, Vec2::new(0.0, 1.0)).is_none());
+    assert!(lp2d(&[ HalfPlane::new(Vec2::new(0.0, 0.0), -1.0) ], Vec2::new(0.0, 1.0)).is_none());
+    assert!(close(lp2d(&[ constraints[1], HalfPlane::new(Vec2::new(0.0, 1.0), 2.0) ], Vec2::new(0.0, 1.0)).unwrap(), Vec2::new(0.0, 2.0)));
+
+    // The optimum over random tangents of the unit circle is close to the circle
+    for _ in 0..20 {
+        let constraints = (0..200)
+            .map(|_| Vec2::random_range(-1.0, 1.0, -1.0, 1.0).normalized())
+            .map(|n| HalfPlane::new(n, 1.0))
+            .collect::<Vec<_>>();
+        let objective = Vec2::random_range(-1.0, 1.0, -1.0, 1.0);
+        let optimum = lp2d(&constraints, objective).unwrap();
+        assert!(constraints.iter().all(|c| c.signed_distance(optimum) < 1e-4));
+        // No vertex of the feasible region is better
+        let region = feasible_region(&constraints, &Rect::new(Vec2::new(-2.0, -2.0), Vec2::new(2.0, 2.0)));
+        assert!(region.iter().all(|&p| objective.dot(p) <= objective.dot(optimum) + 1e-4));
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn icp_registration() {
+    use crate::math::float;
+
+    let target = (0..300).map(|_| Vec2::random_range(-1.0, 1.0, -0.5, 0.5)).collect::<Vec<_>>();
+    let (angle, offset) = (0.1f32, Vec2::new(0.05, -0.03));
+    let (sin, cos) = (float::sin(angle), float::cos(angle));
+    let source = target.iter().map(|p| Vec2::new(cos * p.x - sin * p.y + offset.x, sin * p.x + cos * p.y + offset.y)).collect::<Vec<_>>();
+
+    let mut icp = Icp::new(source, target.clone());
+    let error = icp.run(100, 0.0).unwrap();
+    assert!(error < 1e-3, "{}", error);
+    assert!(icp.errors().windows(2).all(|e| e[1] <= e[0] + 1e-5));
+    assert!((icp.transform().angle + angle).abs() < 1e-3);
+    // Every point goes back onto its original
+    assert!(icp.source().iter().zip(&target).all(|(p, q)| (p - q).length() < 1e-3));
+    assert_eq!(icp.correspondences(), &(0..300).collect::<Vec<_>>()[..]);
+
+    assert!(Icp::new(Vec::new(), target).step().is_none());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn kd_tree_within() {
+    let points = (0..500).map(|_| Vec2::random_range(-1.0, 1.0, -1.0, 1.0)).collect::<Vec<_>>();
+    let tree = spatial::KdTree::new(&points);
+    for _ in 0..200 {
+        let query = Vec2::random_range(-1.5, 1.5, -1.5, 1.5);
+        let radius = rand::random::<f32>() * 0.5;
+        assert_eq!(tree.within(query, radius), spatial::within(&points, query, radius));
+    }
+    assert!(spatial::KdTree::new(&[]).within(Vec2::new(0.0, 0.0), 1.0).is_empty());
+}
+
+#[test]
+fn clustering() {
+    // Two squares of 3x3 points far apart, and an isolated point
+    let mut points = Vec::new();
+    for &(cx, cy) in &[ (-0.5, 0.0), (0.5, 0.0) ] {
+        for i in 0..3 {
+            for j in 0..3 {
+                points.push(Vec2::new(cx + i as f32 * 0.05, cy + j as f32 * 0.05));
+            }
+        }
+    }
+    points.push(Vec2::new(0.0, 0.8));
+
+    let clustering = dbscan(&points, 0.06, 3);
+    assert_eq!(clustering.count, 2);
+    assert_eq!(clustering.noise(), vec![ 18 ]);
+    assert_eq!(clustering.clusters(), vec![ (0..9).collect::<Vec<_>>(), (9..18).collect() ]);
+    // The hull of each square is its 4 corners
+    for (hull, first) in clustering.hulls(&points).iter().zip(&[ 0, 9 ]) {
+        let mut corners = hull.clone();
+        corners.sort();
+        assert_eq!(corners, vec![ *first, first + 2, first + 6, first + 8 ]);
+    }
+    assert_eq!(dbscan(&points, 0.01, 2).count, 0);
+
+    let clustering = k_means(&points[..18], 2, 100);
+    assert_eq!(clustering.count, 2);
+    assert!(clustering.noise().is_empty());
+    assert!((0..9).all(|i| clustering.labels[i] == clustering.labels[0]));
+    assert!((9..18).all(|i| clustering.labels[i] == clustering.labels[9]));
+    assert_ne!(clustering.labels[0], clustering.labels[9]);
+
+    // Fewer distinct points than clusters
+    assert_eq!(k_means(&[ Vec2::new(1.0, 1.0); 4 ], 3, 100).count, 1);
+    assert_eq!(k_means(&[], 3, 100), Clustering { labels: Vec::new(), count: 0 });
+}
+
+#[test]
+fn cluster_statistics() {
+    use crate::math::cmp_f32;
+
+    // A unit square with its center, a segment and a single point
+    let points = vec![
+        Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.0), Vec2::new(1.0, 1.0), Vec2::new(0.0, 1.0), Vec2::new(0.5, 0.5),
+        Vec2::new(3.0, 0.0), Vec2::new(3.0, 2.0),
+        Vec2::new(5.0, 5.0),
+    ];
+    let clustering = Clustering { labels: vec![ Some(0), Some(0), Some(0), Some(0), Some(0), Some(1), Some(1), Some(2) ], count: 3 };
+    let statistics = clustering.statistics(&points);
+    assert_eq!(statistics.iter().map(|s| s.size).collect::<Vec<_>>(), vec![ 5, 2, 1 ]);
+
+    let square = &statistics[0];
+    assert_eq!(square.hull.len(), 4);
+    assert!(!square.hull.contains(&4));
+    assert!(cmp_f32(square.area, 1.0));
+    assert_eq!(square.centroid, Some(Vec2::new(0.5, 0.5)));
+    let ((a, b), diameter) = square.diameter.unwrap();
+    assert!(cmp_f32(diameter, 2f32.sqrt()));
+    assert!(a == 0 && b == 2 || a == 2 && b == 0 || a == 1 && b == 3 || a == 3 && b == 1);
+
+    let segment = &statistics[1];
+    assert!(cmp_f32(segment.area, 0.0));
+    assert!(cmp_f32(segment.diameter.unwrap().1, 2.0));
+    assert_eq!(statistics[2].centroid, Some(Vec2::new(5.0, 5.0)));
+    assert_eq!(statistics[2].diameter, None);
+}
+
+#[test]
+fn triangle_quality() {
+    use crate::math::cmp_f32;
+    use core::f32::consts::PI;
+
+    let equilateral = TriangleQuality::new(Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.0), Vec2::new(0.5, 3f32.sqrt() / 2.0));
+    assert!((equilateral.min_angle - PI / 3.0).abs() < 1e-4);
+    assert!((equilateral.aspect_ratio - 1.0).abs() < 1e-4);
+    assert!((equilateral.radius_edge_ratio - 1.0 / 3f32.sqrt()).abs() < 1e-4);
+
+    // Right isosceles triangle: angles of 45°, circumradius half the hypotenuse
+    let right = TriangleQuality::new(Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.0), Vec2::new(0.0, 1.0));
+    assert!((right.min_angle - PI / 4.0).abs() < 1e-4);
+    assert!(cmp_f32(right.radius_edge_ratio, 2f32.sqrt() / 2.0));
+    assert!(right.aspect_ratio > 1.0);
+
+    let flat = TriangleQuality::new(Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.0), Vec2::new(2.0, 0.0));
+    assert_eq!(flat.min_angle, 0.0);
+    assert!(flat.aspect_ratio.is_infinite() && flat.radius_edge_ratio.is_infinite());
+
+    // Flipping the edges of a thin triangulation improves its worst triangle
+    let points = vec![ Vec2::new(0.0, 0.0), Vec2::new(1.0, -0.1), Vec2::new(2.0, 0.0), Vec2::new(1.0, 0.1) ];
+    let mut indices = vec![ 0, 1, 2, 0, 2, 3 ];
+    let worst = |indices: &[usize]| triangle_qualities(&points, indices).iter().map(|q| q.min_angle).fold(f32::INFINITY, f32::min);
+    let before = worst(&indices);
+    repair_delaunay(&points, &mut indices);
+    assert!(worst(&indices) > before);
+
+    assert_eq!(histogram(&[ 0.0, 0.1, 0.5, 0.99, 1.0, 2.0, -1.0 ], 4, (0.0, 1.0)), vec![ 3, 0, 1, 3 ]);
+    assert_eq!(histogram(&[ 0.0, 1.0 ], 2, (1.0, 0.0)), vec![ 1, 1 ]);
+    assert_eq!(histogram(&[ 0.5 ], 0, (0.0, 1.0)), Vec::<usize>::new());
+}
+
+#[test]
+fn delaunay_refinement() {
+    let bounds = Rect::new(Vec2::new(-1.0, -1.0), Vec2::new(1.0, 1.0));
+    assert!(SizeField::uniform(bounds, 1, 0.5).is_none());
+    let mut field = SizeField::uniform(bounds, 21, 0.5).unwrap();
+    assert_eq!((field.width, field.height), (21, 21));
+    assert!((field.size_at(Vec2::new(0.33, -0.71)) - 0.5).abs() < 1e-5);
+    assert!((field.size_at(Vec2::new(5.0, 5.0)) - 0.5).abs() < 1e-5);
+
+    // A fine region on the left of the square
+    field.paint(Vec2::new(-1.0, 0.0), 0.8, 0.1, 1.0);
+    assert!((field.size_at(Vec2::new(-1.0, 0.0)) - 0.1).abs() < 1e-5);
+    assert!((field.size_at(Vec2::new(0.9, 0.0)) - 0.5).abs() < 1e-5);
+
+    let corners = [ Vec2::new(-1.0, -1.0), Vec2::new(1.0, -1.0), Vec2::new(1.0, 1.0), Vec2::new(-1.0, 1.0) ];
+    let refinement = refine(&corners, &field, 5000, &Progress::default()).unwrap();
+    assert_eq!(&refinement.points[..4], &corners[..]);
+    assert!(refinement.rounds > 0 && refinement.points.len() < 5000);
+    assert!(validate_delaunay(&refinement.points, &refinement.indices).is_empty());
+    for t in refinement.indices.chunks(3) {
+        let (a, b, c) = (refinement.points[t[0]], refinement.points[t[1]], refinement.points[t[2]]);
+        let centroid = &(&(&a + &b) + &c) * (1.0 / 3.0);
+        let longest = (&b - &a).length().max((&c - &b).length()).max((&a - &c).length());
+        assert!(longest <= field.size_at(centroid) + 1e-5);
+    }
+    // The insertions keep half the smallest target length from the other vertices
+    for (idx, p) in refinement.points.iter().enumerate() {
+        assert!(refinement.points[idx + 1..].iter().all(|q| (p - q).length() >= 0.05 - 1e-5));
+    }
+    // Graded: more points on the fine side
+    let left = refinement.points.iter().filter(|p| p.x < 0.0).count();
+    assert!(left > 2 * (refinement.points.len() - left));
+
+    let capped = refine(&corners, &field, 50, &Progress::default()).unwrap();
+    assert!(capped.points.len() <= 50);
+    assert!(refine(&[], &field, 50, &Progress::default()).unwrap().indices.is_empty());
+}
+
+#[test]
+fn mesh_smoothing() {
+    // 3x3 grid with the center moved off, triangulated as a fan around it
+    let mut points = (0..9).map(|i| Vec2::new((i % 3) as f32 - 1.0, (i / 3) as f32 - 1.0)).collect::<Vec<_>>();
+    points[4] = Vec2::new(0.6, 0.3);
+    let ring = [ 0, 1, 2, 5, 8, 7, 6, 3 ];
+    let indices = (0..ring.len()).flat_map(|i| [ 4, ring[i], ring[(i + 1) % ring.len()] ]).collect::<Vec<_>>();
+    let worst = |points: &[Vec2]| triangle_qualities(points, &indices)
+                                    .iter()
+                                    .map(|quality| quality.min_angle)
+                                    .fold(f32::INFINITY, f32::min);
+
+    assert_eq!(laplacian_smoothing(&points, &indices, 0), points);
+    let laplacian = laplacian_smoothing(&points, &indices, 1);
+    assert_eq!(&laplacian[..4], &points[..4]);
+    assert_eq!(&laplacian[5..], &points[5..]);
+    assert!(laplacian[4].length() < 1e-6);
+
+    let angle_based = angle_based_smoothing(&points, &indices, 20);
+    assert_eq!(&angle_based[5..], &points[5..]);
+    assert!(angle_based[4].length() < 0.05);
+    assert!(worst(&angle_based) > worst(&points));
+
+    // Smoothing keeps every triangle of a refined mesh the right way round
+    let corners = [ Vec2::new(-1.0, -1.0), Vec2::new(1.0, -1.0), Vec2::new(1.0, 1.0), Vec2::new(-1.0, 1.0) ];
+    let mut field = SizeField::uniform(Rect::new(corners[0], corners[2]), 21, 0.4).unwrap();
+    field.paint(Vec2::new(-1.0, -1.0), 1.0, 0.05, 1.0);
+    let refinement = refine(&corners, &field, 400, &Progress::default()).unwrap();
+    for smoothed in &[ laplacian_smoothing(&refinement.points, &refinement.indices, 10),
+                       angle_based_smoothing(&refinement.points, &refinement.indices, 10) ] {
+        assert_eq!(&smoothed[..4], &corners[..]);
+        for t in refinement.indices.chunks(3) {
+            let before = Vec2::orientation(refinement.points[t[0]], refinement.points[t[1]], refinement.points[t[2]]);
+            let after = Vec2::orientation(smoothed[t[0]], smoothed[t[1]], smoothed[t[2]]);
+            assert!(before * after > 0.0);
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn triangle_coloring() {
+    // Two triangles sharing the edge (0, 2), and a third one touching them only at vertex 2
+    let indices = vec![ 0, 1, 2, 0, 2, 3, 2, 4, 5 ];
+    assert_eq!(DualGraph::new(&indices).adjacency(), &[ vec![ 1 ], vec![ 0 ], vec![] ][..]);
+    let colors = color_triangles(&indices);
+    assert_ne!(colors[0], colors[1]);
+    assert_eq!(colors[2], 0);
+    assert_eq!(color_count(&colors), 2);
+    assert_eq!(color_count(&[]), 0);
+
+    // A triangle with 3 neighbours is colored first, then each neighbour gets the next color
+    let neighbours = vec![ vec![ 1, 2, 3 ], vec![ 0 ], vec![ 0 ], vec![ 0 ] ];
+    assert_eq!(greedy_coloring(&neighbours), vec![ 0, 1, 1, 1 ]);
+
+    let points = (0..500).map(|_| Vec2::random_range(-1.0, 1.0, -1.0, 1.0)).collect::<Vec<_>>();
+    let indices = Incremental2dTriangulation::delaunay(&points);
+    let graph = DualGraph::new(&indices);
+    let neighbours = graph.adjacency();
+    let colors = greedy_coloring(neighbours);
+    assert!(color_count(&colors) <= 4);
+    for (t, list) in neighbours.iter().enumerate() {
+        assert!(list.len() <= 3);
+        assert!(list.iter().all(|&other| colors[other] != colors[t]));
+    }
+}
+
+#[test]
+fn dual_graph() {
+    // A triangle with a neighbour on each edge, one of them with another neighbour, and a separate triangle
+    let indices = vec![ 0, 1, 2, 1, 0, 3, 2, 1, 4, 0, 2, 5, 3, 0, 6, 7, 8, 9 ];
+    let graph = DualGraph::new(&indices);
+    assert_eq!(graph.len(), 6);
+    assert_eq!(graph.triangle(4), [ 3, 0, 6 ]);
+    assert_eq!(graph.neighbours(0), &[ 1, 2, 3 ]);
+    assert_eq!(graph.neighbours(5), &[] as &[usize]);
+    assert_eq!(graph.shared_edge(0, 1), Some((0, 1)));
+    assert_eq!(graph.shared_edge(1, 4), Some((0, 3)));
+    assert_eq!(graph.shared_edge(2, 3), None);
+
+    assert_eq!(graph.bfs(0).collect::<Vec<_>>(), vec![ 0, 1, 2, 3, 4 ]);
+    assert_eq!(graph.dfs(0).collect::<Vec<_>>(), vec![ 0, 1, 4, 2, 3 ]);
+    assert_eq!(graph.bfs(5).collect::<Vec<_>>(), vec![ 5 ]);
+
+    assert_eq!(graph.path(4, 2), Some(vec![ 4, 1, 0, 2 ]));
+    assert_eq!(graph.path(3, 3), Some(vec![ 3 ]));
+    assert_eq!(graph.path(2, 5), None);
+    assert_eq!(graph.components(), (vec![ 0, 0, 0, 0, 0, 1 ], 2));
+    assert!(DualGraph::new(&[]).is_empty());
+}
+
+#[test]
+fn region_fill() {
+    use crate::math::Segment2;
+
+    // 2x2 square cells, each split along its diagonal
+    let points = (0..9).map(|i| Vec2::new((i % 3) as f32, (i / 3) as f32)).collect::<Vec<_>>();
+    let mut indices = Vec::new();
+    for y in 0..2 {
+        for x in 0..2 {
+            let (a, b, c, d) = (y * 3 + x, y * 3 + x + 1, y * 3 + x + 4, y * 3 + x + 3);
+            indices.extend_from_slice(&[ a, b, c, a, c, d ]);
+        }
+    }
+    let graph = DualGraph::new(&indices);
+    assert_eq!(locate_triangle(&points, &indices, Vec2::new(0.2, 0.5)), Some(1));
+    assert_eq!(locate_triangle(&points, &indices, Vec2::new(0.8, 0.3)), Some(0));
+    assert_eq!(locate_triangle(&points, &indices, Vec2::new(2.5, 0.5)), None);
+
+    assert_eq!(graph.flood_fill(1, |_, _| false), vec![ 1 ]);
+    assert_eq!(fill_region(&points, &graph, 1, &[]).len(), 8);
+
+    // A barrier along the middle column of edges, and one that only touches the grid at a vertex
+    let barriers = [ Segment2::new(Vec2::new(1.0, -1.0), Vec2::new(1.0, 3.0)), Segment2::new(Vec2::new(0.0, 0.0), Vec2::new(-1.0, -1.0)) ];
+    let mut left = fill_region(&points, &graph, 1, &barriers);
+    left.sort_unstable();
+    assert_eq!(left, vec![ 0, 1, 4, 5 ]);
+    assert_eq!(fill_region(&points, &graph, 2, &barriers).len(), 4);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn point_location() {
+    let contains = |points: &[Vec2], indices: &[usize], t: usize, p: Vec2| {
+        let (a, b, c) = (points[indices[3 * t]], points[indices[3 * t + 1]], points[indices[3 * t + 2]]);
+        let sides = [ Vec2::orientation(a, b, p), Vec2::orientation(b, c, p), Vec2::orientation(c, a, p) ];
+        sides.iter().all(|&side| side >= -1e-9) || sides.iter().all(|&side| side <= 1e-9)
+    };
+
+    let points = (0..500).map(|_| Vec2::random_range(-1.0, 1.0, -1.0, 1.0)).collect::<Vec<_>>();
+    let indices = Incremental2dTriangulation::delaunay(&points);
+    let dag = HistoryDag::new(&points);
+    let dag_indices = dag.indices();
+    assert_eq!(dag_indices.len(), indices.len());
+    assert!(validate_delaunay(&points, &dag_indices).is_empty());
+    assert!(dag.node_count() > dag_indices.len() / 3);
+
+    let walk = TriangleWalk::new(&points, &indices);
+    let grid = TriangleGrid::new(&points, &indices);
+    let hull = hull_positions(&points, &GrahamScan::scan(&points));
+    for _ in 0..500 {
+        let p = Vec2::random_range(-1.2, 1.2, -1.2, 1.2);
+        let inside = crate::math::polygon::contains(&hull, p);
+        for (location, indices) in &[ (walk.locate(p, 0), &indices), (grid.locate(p), &indices), (dag.locate(p), &dag_indices) ] {
+            assert_eq!(location.triangle.is_some(), inside);
+            if let Some(t) = location.triangle {
+                assert!(contains(&points, indices, t, p));
+                assert!(location.visited > 0);
+            }
+        }
+    }
+
+    // Points on the edges of each other and duplicates
+    let grid_points = (0..30).map(|i| Vec2::new((i % 5) as f32, ((i / 5) % 5) as f32)).collect::<Vec<_>>();
+    let dag = HistoryDag::new(&grid_points);
+    assert_eq!(dag.indices().len(), 3 * 32);
+    assert!(dag.locate(Vec2::new(2.5, 1.5)).triangle.is_some());
+    assert_eq!(dag.locate(Vec2::new(5.0, 1.0)).triangle, None);
+    assert!(HistoryDag::new(&[]).indices().is_empty());
+    assert_eq!(TriangleWalk::new(&points, &[]).locate(Vec2::new(0.0, 0.0), 0).triangle, None);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn convex_hull_3d() {
+    use crate::{ algorithms::{ ConvexHull, IncrementalHull }, math::Vec3 };
+    use cgmath::InnerSpace;
+
+    let sees = |points: &[Vec3], (a, b, c): (u32, u32, u32), p: Vec3| {
+        let (a, b, c) = (points[a as usize].0, points[b as usize].0, points[c as usize].0);
+        (b - a).cross(c - a).dot(p.0 - a) > 1e-5
+    };
+
+    // The corners of a cube, with points inside and a duplicated corner
+    let mut points = (0..8).map(|i| Vec3::new((i & 1) as f32, (i >> 1 & 1) as f32, (i >> 2) as f32)).collect::<Vec<_>>();
+    points.extend((0..50).map(|i| Vec3::new((i % 5) as f32 / 5.0 + 0.1, (i / 5 % 5) as f32 / 5.0 + 0.1, (i % 3) as f32 / 3.0 + 0.1)));
+    points.push(Vec3::new(1.0, 1.0, 1.0));
+    let graph = ConvexHull::hull(&points);
+    assert_eq!((graph.vertices.len(), graph.edges.len(), graph.faces.len()), (8, 18, 12));
+    assert!(graph.vertices.iter().map(|v| points[v.idx as usize].0).all(|p| [ p.x, p.y, p.z ].iter().all(|&x| x == 0.0 || x == 1.0)));
+    assert!(graph.faces.iter().all(|face| points.iter().all(|&p| !sees(&points, face.idx, p))));
+
+    // Points on a sphere, along a Fibonacci spiral, are all vertices of their hull
+    let sphere = (0..200)
+        .map(|i| {
+            let z = 1.0 - (2 * i + 1) as f32 / 200.0;
+            let (r, angle) = ((1.0 - z * z).sqrt(), i as f32 * 2.399_963);
+            Vec3::new(r * angle.cos(), r * angle.sin(), z)
+        })
+        .collect::<Vec<_>>();
+    let graph = ConvexHull::hull(&sphere);
+    assert_eq!(graph.vertices.len(), 200);
+    assert_eq!((graph.edges.len(), graph.faces.len()), (3 * 200 - 6, 2 * 200 - 4));
+    assert!(graph.faces.iter().all(|face| sphere.iter().all(|&p| !sees(&sphere, face.idx, p))));
+
+    // Stepping: each point not inserted yet sees the faces it conflicts with
+    let mut steps = IncrementalHull::new(&sphere).unwrap();
+    assert_eq!((steps.faces().count(), steps.remaining()), (4, 196));
+    while steps.remaining() > 100 {
+        assert!(steps.step().is_some());
+    }
+    assert!(steps.conflicts().count() > 0);
+    for (p, [ a, b, c ]) in steps.conflicts() {
+        assert!(sees(&sphere, (a as u32, b as u32, c as u32), sphere[p]));
+    }
+    while steps.step().is_some() {}
+    assert!(steps.is_done());
+    assert_eq!((steps.faces().count(), steps.conflicts().count()), (2 * 200 - 4, 0));
+
+    let flat = (0..20).map(|i| Vec3::new(i as f32, (i * i % 7) as f32, 0.0)).collect::<Vec<_>>();
+    assert!(IncrementalHull::new(&flat).is_none());
+    assert!(ConvexHull::hull(&flat).faces.is_empty());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn hull_measures() {
+    use crate::{ algorithms::{ ConvexHull, JarvisMarch, hull_area, hull_perimeter }, math::{ Vec3, float } };
+
+    let mut square = GrahamScan::new();
+    square.set_points(vec![ Vec2::new(0.0, 0.0), Vec2::new(2.0, 0.0), Vec2::new(1.0, 0.5), Vec2::new(2.0, 1.0), Vec2::new(0.0, 1.0) ]);
+    assert!(float::abs(square.area() - 2.0) < 1e-6);
+    assert!(float::abs(square.perimeter() - 6.0) < 1e-6);
+    let mut segment = JarvisMarch::new();
+    segment.set_points(vec![ Vec2::new(0.0, 0.0), Vec2::new(1.0, 1.0), Vec2::new(3.0, 3.0) ]);
+    assert_eq!(segment.area(), 0.0);
+    assert!(float::abs(segment.perimeter() - 2.0 * 18.0f32.sqrt()) < 1e-5);
+    assert_eq!((hull_area(&[], &[]), hull_perimeter(&[], &[])), (0.0, 0.0));
+
+    // A box with sides 2, 1 and 3 around (1, 2, 3), with points inside
+    let mut hull = ConvexHull::new();
+    let mut points = (0..8).map(|i| Vec3::new((i & 1) as f32 * 2.0, (i >> 1 & 1) as f32 + 1.5, (i >> 2) as f32 * 3.0 + 1.5)).collect::<Vec<_>>();
+    points.extend(&[ Vec3::new(1.0, 2.0, 3.0), Vec3::new(0.5, 1.8, 2.0) ]);
+    hull.set_points(points);
+    assert!(float::abs(hull.volume() - 6.0) < 1e-4);
+    assert!(float::abs(hull.surface_area() - 22.0) < 1e-4);
+    let centroid = hull.centroid().unwrap();
+    assert!(float::abs(centroid.0.x - 1.0) < 1e-4 && float::abs(centroid.0.y - 2.0) < 1e-4 && float::abs(centroid.0.z - 3.0) < 1e-4);
+
+    hull.clear();
+    assert_eq!((hull.volume(), hull.surface_area(), hull.centroid()), (0.0, 0.0, None));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn point_distributions() {
+    use crate::point_cloud::Distribution;
+    use cgmath::InnerSpace;
+
+    for &distribution in &Distribution::ALL {
+        let points = distribution.generate(500);
+        assert_eq!(points.len(), 500);
+        assert!(points.iter().all(|p| p.0.x.abs() <= 1.0 && p.0.y.abs() <= 1.0 && p.0.z.abs() <= 1.0), "{}", distribution.name());
+    }
+    assert!(Distribution::Sphere.generate(100).iter().all(|p| (p.0.magnitude() - 1.0).abs() < 1e-5));
+    assert!(Distribution::Ball.generate(100).iter().all(|p| p.0.magnitude() <= 1.0));
+    assert!(Distribution::Torus.generate(100).iter().all(|p| {
+        let ring = (p.0.x * p.0.x + p.0.y * p.0.y).sqrt() - 0.7;
+        ring * ring + p.0.z * p.0.z <= 0.09 + 1e-6
+    }));
+
+    // Most of the points of the normal distribution are within two standard deviations
+    let gaussian = Distribution::Gaussian.generate(2000);
+    let close = gaussian.iter().filter(|p| p.0.x.abs() < 0.7).count();
+    assert!(close > 1800 && close < 2000, "{} points", close);
+    assert!(Distribution::Box.generate(0).is_empty());
+}
+
+#[cfg(feature = "gui")]
+#[test]
+fn settings_toml() {
+    use crate::ui::settings::{ Settings, Theme };
+
+    let defaults = Settings::default();
+    let written = toml::to_string_pretty(&defaults).unwrap();
+    assert_eq!(toml::from_str::<Settings>(&written).unwrap(), defaults);
+
+    let mut changed = Settings { theme: Theme::Light, msaa: 4, ..Settings::default() };
+    changed.camera.zoom = 2.5;
+    changed.layout.window_size = [ 800.0, 600.0 ];
+    assert_eq!(toml::from_str::<Settings>(&toml::to_string_pretty(&changed).unwrap()).unwrap(), changed);
+
+    // Missing entries take their default values and unknown ones are ignored, e.g. from other versions
+    let settings: Settings = toml::from_str("msaa = 4\nunknown = true\n\n[camera]\nzoom = 2.5\n[removed]\nvalue = 1\n").unwrap();
+    assert_eq!(settings.msaa, 4);
+    assert_eq!(settings.camera.zoom, 2.5);
+    assert_eq!(settings.camera.rotation, defaults.camera.rotation);
+    assert_eq!(settings.keybindings, defaults.keybindings);
+    assert_eq!(settings.layout, defaults.layout);
+    assert_eq!(toml::from_str::<Settings>("").unwrap(), defaults);
+}
+
+#[cfg(feature = "gui")]
+#[test]
+fn camera_ray() {
+    use crate::{ graphics::OrbitCamera, math::Vec3 };
+    use cgmath::InnerSpace;
+
+    let cam = OrbitCamera::new(10.0, Vec3::new(1.0, 2.0, 3.0), 45.0, 0.1, 100.0);
+    let (origin, direction) = cam.ray(Vec2::new(0.0, 0.0), 1.5);
+    assert!((origin.0 - cam.get_position().0).magnitude() < 1e-5);
+    assert!((direction.0 - cam.get_direction().0).magnitude() < 1e-5);
+
+    // A point along the ray is projected back under the cursor
+    let coords = Vec2::new(0.3, -0.5);
+    let (origin, direction) = cam.ray(coords, 1.5);
+    let p = origin.0 + direction.0 * 7.0;
+    let clip = cam.get_projection_matrix(1.5) * cam.get_view_matrix() * p.extend(1.0);
+    assert!((clip.x / clip.w - coords.x).abs() < 1e-4 && (clip.y / clip.w - coords.y).abs() < 1e-4);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn hull_point_moves() {
+    use crate::{ algorithms::ConvexHull, math::Vec3 };
+
+    let mut points = (0..8).map(|i| Vec3::new((i & 1) as f32, (i >> 1 & 1) as f32, (i >> 2) as f32)).collect::<Vec<_>>();
+    points.push(Vec3::new(0.5, 0.5, 0.5));
+    let mut hull = ConvexHull::new();
+    hull.set_points(points);
+    assert!(hull.contains(Vec3::new(0.2, 0.9, 0.1)) && hull.contains(Vec3::new(1.0, 0.5, 0.5)));
+    assert!(!hull.contains(Vec3::new(1.1, 0.5, 0.5)));
+
+    // Moving the inner point inside leaves the hull as it is, moving it out adds it to the hull
+    assert!(!hull.move_point(8, Vec3::new(0.3, 0.6, 0.2)));
+    assert_eq!(hull.graph().faces.len(), 12);
+    assert!(hull.move_point(8, Vec3::new(0.5, 0.5, 2.0)));
+    assert!(hull.graph().vertices.iter().any(|v| v.idx == 8));
+    assert!((hull.volume() - 4.0 / 3.0).abs() < 1e-4);
+
+    // Moving a corner inwards removes it from the hull
+    assert!(hull.move_point(0, Vec3::new(0.5, 0.5, 0.5)));
+    assert!(hull.graph().vertices.iter().all(|v| v.idx != 0));
+    assert!(hull.contains(Vec3::new(0.5, 0.5, 0.5)));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn delaunay_3d() {
+    use crate::{ algorithms::{ delaunay_3d, TetrahedronQuality, delaunay_3d::signed_volume }, math::Vec3 };
+    use cgmath::InnerSpace;
+
+    let mut points = (0..8).map(|i| Vec3::new((i & 1) as f32, (i >> 1 & 1) as f32, (i >> 2) as f32)).collect::<Vec<_>>();
+    points.push(Vec3::new(0.5, 0.5, 0.5));
+    points.extend((0..40).map(|i| Vec3::new((i * 7 % 11) as f32 / 11.0 + 0.02, (i * 5 % 13) as f32 / 13.0 + 0.03, (i * 3 % 17) as f32 / 17.0 + 0.01)));
+    let tetrahedra = delaunay_3d(&points);
+
+    // The tetrahedra fill the cube without overlapping
+    let volume = |t: &[usize; 4]| signed_volume(points[t[0]], points[t[1]], points[t[2]], points[t[3]]) / 6.0;
+    assert!(tetrahedra.iter().all(|t| volume(t) > 0.0));
+    assert!((tetrahedra.iter().map(volume).sum::<f32>() - 1.0).abs() < 1e-4);
+
+    // No point is inside of the circumscribed sphere of a tetrahedron
+    for t in &tetrahedra {
+        let (a, u, v, w) = (points[t[0]].0, points[t[1]].0 - points[t[0]].0, points[t[2]].0 - points[t[0]].0, points[t[3]].0 - points[t[0]].0);
+        let center = a + (v.cross(w) * u.magnitude2() + w.cross(u) * v.magnitude2() + u.cross(v) * w.magnitude2()) / (2.0 * u.dot(v.cross(w)));
+        let radius = (a - center).magnitude();
+        assert!(points.iter().all(|p| (p.0 - center).magnitude() > radius * (1.0 - 1e-3)));
+    }
+
+    let flat = (0..10).map(|i| Vec3::new(i as f32, (i * i % 7) as f32, 0.0)).collect::<Vec<_>>();
+    assert!(delaunay_3d(&flat).is_empty());
+
+    let regular = TetrahedronQuality::new(Vec3::new(1.0, 1.0, 1.0), Vec3::new(1.0, -1.0, -1.0), Vec3::new(-1.0, 1.0, -1.0), Vec3::new(-1.0, -1.0, 1.0));
+    assert!((regular.min_dihedral - (1.0f32 / 3.0).acos()).abs() < 1e-5);
+    assert!((regular.max_dihedral - regular.min_dihedral).abs() < 1e-5);
+    assert!((regular.radius_edge_ratio - 6.0f32.sqrt() / 4.0).abs() < 1e-5);
+    // A sliver: four nearly coplanar points on a circle, with a good radius-edge ratio
+    let sliver = TetrahedronQuality::new(Vec3::new(0.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0), Vec3::new(1.0, 1.0, 0.01));
+    assert!(sliver.min_dihedral < 0.02 && sliver.max_dihedral > 3.1 && sliver.radius_edge_ratio < 0.75);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn spatial_hash() {
+    let mut hash = spatial::SpatialHash::new(0.04, 500);
+    assert!(hash.pairs(&[], &[]).is_empty());
+
+    // Circles moving between the calls, a few of them changing cell each time
+    let mut centers = (0..500).map(|_| Vec2::random_range(-1.0, 1.0, -1.0, 1.0)).collect::<Vec<_>>();
+    let radii = (0..500).map(|i| 0.005 + (i % 4) as f32 * 0.005).collect::<Vec<_>>();
+    for &p in &centers {
+        hash.insert(p);
+    }
+    assert_eq!(hash.len(), 500);
+    let query = Vec2::new(0.1, 0.2);
+    let around = hash.around(query);
+    assert!(spatial::within(&centers, query, 0.04).iter().all(|idx| around.contains(idx)));
+    for _ in 0..10 {
+        let mut pairs = hash.pairs(&centers, &radii);
+        pairs.sort_unstable();
+        assert_eq!(pairs, spatial::overlapping_circles(&centers, &radii));
+
+        for (idx, p) in centers.iter_mut().enumerate() {
+            let cell = hash.cell(*p);
+            *p = &*p + &Vec2::random_range(-0.03, 0.03, -0.03, 0.03);
+            assert_eq!(hash.update(idx, *p), hash.cell(*p) != cell);
+        }
+    }
+}
+
+#[test]
+fn delaunay_update() {
+    // The top point moves down until the bottom diagonal is illegal, which flips it
+    let mut points = [ Vec2::new(0.0, 0.0), Vec2::new(1.0, -0.1), Vec2::new(2.0, 0.0), Vec2::new(1.0, 20.0) ];
+    let mut indices = vec![ 0, 1, 2, 0, 2, 3 ];
+    assert_eq!(update_delaunay(&points, &mut indices), Some(0));
+    points[3] = Vec2::new(1.0, 2.0);
+    assert_eq!(update_delaunay(&points, &mut indices), Some(1));
+    assert!(validate_delaunay(&points, &indices).is_empty());
+    assert!(indices.chunks(3).all(|t| t.contains(&1) && t.contains(&3)));
+
+    // The top point crossing the bottom edge turns its triangle over
+    points[3] = Vec2::new(1.0, -0.5);
+    let before = indices.clone();
+    assert_eq!(update_delaunay(&points, &mut indices), None);
+    assert_eq!(indices, before);
+
+    // A corner of the square moving inwards makes the boundary concave
+    let mut points = [ Vec2::new(0.0, 0.0), Vec2::new(2.0, 0.0), Vec2::new(2.0, 2.0), Vec2::new(0.0, 2.0), Vec2::new(1.0, 1.5) ];
+    let mut indices = vec![ 0, 1, 4, 1, 2, 4, 2, 3, 4, 3, 0, 4 ];
+    assert_eq!(update_delaunay(&points, &mut indices), Some(0));
+    points[1] = Vec2::new(1.2, 1.3);
+    assert_eq!(update_delaunay(&points, &mut indices), None);
+}
+
+#[test]
+fn data_depth() {
+    use crate::math::polygon;
+
+    // Two nested squares around a center, with a point in the middle of an edge and a duplicate
+    let mut points = Vec::new();
+    for &size in &[ 1.0, 0.5 ] {
+        points.extend_from_slice(&[ Vec2::new(-size, -size), Vec2::new(size, -size), Vec2::new(size, size), Vec2::new(-size, size) ]);
+    }
+    points.extend_from_slice(&[ Vec2::new(0.0, 0.0), Vec2::new(0.0, -1.0), Vec2::new(0.5, 0.5) ]);
+
+    let layers = ConvexLayers::new(&points);
+    assert_eq!(layers.layers, vec![ vec![ 0, 1, 2, 3 ], vec![ 4, 5, 6, 7 ], vec![ 8 ] ]);
+    assert_eq!(layers.depths, vec![ 0, 0, 0, 0, 1, 1, 1, 1, 2, 0, 1 ]);
+
+    let depths = points.iter().map(|&p| tukey_depth(&points, p)).collect::<Vec<_>>();
+    assert_eq!(depths, vec![ 1, 1, 1, 1, 2, 2, 3, 2, 5, 2, 3 ]);
+    assert_eq!(tukey_depth(&points, Vec2::new(2.0, 2.0)), 0);
+
+    // Each region holds the points of its depth or deeper, the first one is the hull
+    let regions = tukey_regions(&points);
+    assert_eq!(regions.len(), 3);
+    assert!((polygon::signed_area(&regions[0]) - 4.0).abs() < 1e-5);
+    for (k, region) in regions.iter().enumerate() {
+        for (&p, &depth) in points.iter().zip(&depths) {
+            assert_eq!(polygon::signed_distance(region, p) > -1e-5, depth > k);
+        }
+    }
+    assert!(tukey_regions(&[ Vec2::new(0.0, 0.0), Vec2::new(1.0, 1.0), Vec2::new(2.0, 2.0) ]).is_empty());
+}
+
+#[test]
+fn graham_scan_nearly_collinear() {
+    // Points within a rounding error of a line, whose angles from the bottommost one round to the same values
+    let points = [
+        Vec2::new(0.21877952, 0.08204261), Vec2::new(0.08404527, 0.031517144),
+        Vec2::new(0.5025455, 0.1884549), Vec2::new(0.25488544, 0.095582366),
+    ];
+    let hull = GrahamScan::scan(&points);
+    let n = hull.len();
+    assert!(n >= 2);
+    for i in 0..n {
+        let (a, b) = (points[hull[i]], points[hull[(i + 1) % n]]);
+        if n > 2 {
+            assert!(Vec2::orientation(points[hull[(i + n - 1) % n]], a, b) > 0.0);
+        }
+        assert!(points.iter().all(|&p| Vec2::orientation(a, b, p) >= 0.0));
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn benchmark_timings() {
+    use crate::harness::{ self, Suite };
+
+    assert_eq!(Suite::from_name("grid"), Some(Suite::Grid));
+    assert_eq!(Suite::from_name("spiral"), None);
+    assert_eq!(Suite::Uniform.generate_seeded(20, 7), Suite::Uniform.generate_seeded(20, 7));
+
+    // The runs of a size are seeded one after the other, the same for every suite
+    let runs = harness::benchmark(&[ Suite::Uniform, Suite::Circle ], &[ 10, 50 ], 2, 7, false);
+    assert_eq!(runs.len(), 2 * 2 * 2 * harness::BENCHMARKS.len());
+    assert_eq!(runs[0].seed, 7);
+    assert_eq!(runs[harness::BENCHMARKS.len()].seed, 8);
+    assert_eq!(runs.last().unwrap().suite, Suite::Circle);
+    assert!(runs.iter().all(|timing| !timing.phases.is_empty() && timing.total() >= timing.phases[0].1));
+
+    // Both polygon triangulations are timed on the same simple polygon
+    let polygon = harness::star_polygon(&Suite::Uniform.generate_seeded(100, 7));
+    assert_eq!(crate::algorithms::triangulate_polygon(&polygon).len(), 3 * 98);
+    assert_eq!(crate::algorithms::triangulate_polygon_monotone(&polygon).len(), 3 * 98);
+
+    let mut out = Vec::new();
+    crate::io::write_timings_csv(&mut out, &runs).unwrap();
+    let csv = String::from_utf8(out).unwrap();
+    assert_eq!(csv.lines().count(), 1 + runs.iter().map(|timing| timing.phases.len()).sum::<usize>());
+    assert_eq!(csv.lines().next(), Some("implementation,distribution,points,seed,phase,time_ns"));
+    assert!(csv.lines().nth(1).unwrap().starts_with("Jarvis march,uniform,10,7,hull,"));
+}
+
+#[test]
+fn contour_tracing() {
+    use crate::algorithms::trace_contours;
+
+    // A square ring and an isolated pixel, which has no area
+    let rows = [
+        "........",
+        ".####...",
+        ".#..#..#",
+        ".#..#...",
+        ".####...",
+        "........",
+    ];
+    let mask = rows.iter().flat_map(|row| row.chars().map(|c| c == '#')).collect::<Vec<_>>();
+    let regions = trace_contours(&mask, 8, 6);
+    assert_eq!(regions.len(), 1);
+    let square = |corners: &[(f32, f32)]| corners.iter().map(|&(x, y)| Vec2::new(x, y)).collect::<Vec<_>>();
+    assert_eq!(regions[0].outer, square(&[ (1.5, 1.5), (4.5, 1.5), (4.5, 4.5), (1.5, 4.5) ]));
+    assert_eq!(regions[0].holes, vec![ square(&[ (2.5, 3.5), (3.5, 3.5), (3.5, 2.5), (2.5, 2.5) ]) ]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn pbm_image() {
+    use crate::image;
+
+    let ascii = "P1\n# 3x2\n3 2\n101\n0 1 0\n";
+    let img = image::read_pbm(ascii.as_bytes()).unwrap();
+    assert_eq!(img.pixels, vec![ 0, 255, 0, 255, 0, 255 ]);
+    assert_eq!(img.threshold(128), vec![ true, false, true, false, true, false ]);
+
+    // The rows of the binary format start on a new byte
+    let binary = b"P4 3 2\n\xa0\x40";
+    assert_eq!(image::read_pbm(&binary[..]).unwrap(), img);
+
+    assert!(image::read_pbm("P1 2 2\n101".as_bytes()).is_err());
+    assert!(image::read_pbm(&b"P4 9 1\n\x00"[..]).is_err());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn convex_hull_3d_random() {
+    use crate::{ algorithms::{ ConvexHull, ConvexHullNd }, math::Vec3, point_cloud::Distribution };
+
+    // The regular icosahedron with edges of length 2, and points inside it
+    let phi = (1.0 + 5f32.sqrt()) / 2.0;
+    let mut icosahedron = Vec::new();
+    for &(a, b) in &[ (1.0, phi), (1.0, -phi), (-1.0, phi), (-1.0, -phi) ] {
+        icosahedron.extend_from_slice(&[ Vec3::new(0.0, a, b), Vec3::new(a, b, 0.0), Vec3::new(b, 0.0, a) ]);
+    }
+    icosahedron.extend((0..20).map(|i| Vec3::new((i % 3) as f32 * 0.3 - 0.3, (i % 5) as f32 * 0.2 - 0.4, (i % 7) as f32 * 0.1 - 0.3)));
+    let graph = ConvexHull::hull(&icosahedron);
+    assert_eq!((graph.vertices.len(), graph.edges.len(), graph.faces.len()), (12, 30, 20));
+    let volume = 5.0 / 12.0 * (3.0 + 5f32.sqrt()) * 8.0;
+    assert!((graph.volume(&icosahedron) - volume).abs() < 1e-3 * volume);
+
+    // The vertices are those of the hull in any dimension, and the triangulated surface is a sphere
+    for &distribution in &Distribution::ALL {
+        let points = distribution.generate(300);
+        let graph = ConvexHull::hull(&points);
+        let (v, e, f) = (graph.vertices.len(), graph.edges.len(), graph.faces.len());
+        assert_eq!(v + f, e + 2, "{:?}", distribution);
+        assert_eq!(f, 2 * v - 4, "{:?}", distribution);
+
+        let mut vertices = graph.vertices.iter().map(|vertex| vertex.idx as usize).collect::<Vec<_>>();
+        vertices.sort_unstable();
+        let coordinates = points.iter().map(|p| vec![ f64::from(p.0.x), f64::from(p.0.y), f64::from(p.0.z) ]).collect::<Vec<_>>();
+        assert_eq!(vertices, ConvexHullNd::new(&coordinates).unwrap().vertices(), "{:?}", distribution);
+    }
+}
+
+#[test]
+fn pathfinding() {
+    use crate::{ algorithms::{ a_star, theta_star, visibility_graph, OccupancyGrid }, math::{ Segment2, polygon } };
+
+    let bounds = Rect::new(Vec2::new(-1.0, -1.0), Vec2::new(1.0, 1.0));
+    let (start, end) = (Vec2::new(-0.5, 0.0), Vec2::new(0.5, 0.0));
+    let length = |path: &[Vec2]| path.windows(2).map(|link| (&link[1] - &link[0]).length()).sum::<f32>();
+
+    // Without obstacles Theta* and the visibility graph go straight, A* along the axes and the diagonals
+    let grid = OccupancyGrid::new(bounds, 0.1, &[], &[]);
+    assert_eq!((grid.columns, grid.rows), (21, 21));
+    assert!(grid.blocked.iter().all(|&blocked| !blocked));
+    assert_eq!(theta_star(&grid, start, end).unwrap().points, vec![ start, end ]);
+    assert_eq!(visibility_graph(&[], &[], start, end).shortest_path(), Some(vec![ start, end ]));
+    let zigzag = a_star(&grid, Vec2::new(-0.85, -0.85), Vec2::new(0.55, 0.05)).unwrap();
+    assert!(zigzag.points.windows(2).all(|link| {
+        let d = &link[1] - &link[0];
+        d.x.abs() < 1e-4 || d.y.abs() < 1e-4 || (d.x.abs() - d.y.abs()).abs() < 1e-4
+    }));
+
+    // A wall between the points, shorter to go around above than below
+    let wall = vec![ Vec2::new(-0.1, -0.8), Vec2::new(0.1, -0.8), Vec2::new(0.1, 0.6), Vec2::new(-0.1, 0.6) ];
+    let grid = OccupancyGrid::new(bounds, 0.05, core::slice::from_ref(&wall), &[]);
+    assert!(grid.is_blocked(20, 20) && !grid.is_blocked(20, 35) && grid.is_blocked(-1, 0));
+    assert!(!grid.line_of_sight(grid.cell(start).unwrap(), grid.cell(end).unwrap()));
+    let exact = visibility_graph(core::slice::from_ref(&wall), &[], start, end).shortest_path().unwrap();
+    assert_eq!(exact, vec![ start, wall[3], wall[2], end ]);
+    let (a, theta) = (a_star(&grid, start, end).unwrap(), theta_star(&grid, start, end).unwrap());
+    assert!(length(&exact) < theta.length() && theta.length() < a.length());
+    assert!(theta.points.len() < a.points.len());
+    assert!(theta.points.iter().all(|&p| !polygon::contains(&wall, p)));
+
+    // A segment closes the gap above the wall
+    let lid = Segment2::new(Vec2::new(0.0, 0.5), Vec2::new(0.0, 1.0));
+    let closed = OccupancyGrid::new(bounds, 0.05, core::slice::from_ref(&wall), &[ lid ]);
+    assert!(visibility_graph(core::slice::from_ref(&wall), &[ lid ], start, end).shortest_path().unwrap()[1].y < 0.0);
+    let below = a_star(&closed, start, end).unwrap();
+    assert!(below.points[1..below.points.len() - 1].iter().all(|p| p.y < 0.0));
+
+    // No path starts inside an obstacle
+    assert!(a_star(&grid, Vec2::new(0.0, 0.0), end).is_none());
+    assert!(visibility_graph(&[ wall ], &[], Vec2::new(0.0, 0.0), end).shortest_path().is_none());
+}
+
+#[test]
+fn recorded_steps() {
+    use crate::algorithms::{ graham_scan::GrahamScanSteps, jarvis_march::JarvisMarchSteps };
+
+    let points = vec![
+        Vec2::new(-0.5, -0.5),
+        Vec2::new(0.5, -0.4),
+        Vec2::new(0.1, 0.0),
+        Vec2::new(0.6, 0.5),
+        Vec2::new(-0.4, 0.6),
+        Vec2::new(0.0, 0.9),
+        Vec2::new(-0.2, 0.2),
+    ];
+    let closed = |mut hull: Vec<usize>| {
+        hull.push(hull[0]);
+        hull
+    };
+
+    // The stack follows the pushes and pops, the candidate edge starts from its top
+    let snapshots = GrahamScanSteps::new(&points).record();
+    assert_eq!(snapshots[0], Snapshot::default());
+    for pair in snapshots.windows(2) {
+        let (before, after) = (&pair[0], &pair[1]);
+        match after.step {
+            Some(Step::HullPop) => assert_eq!(after.hull[..], before.hull[..before.hull.len() - 1]),
+            Some(Step::HullPush(idx)) => assert_eq!(after.hull[before.hull.len()], idx),
+            step => panic!("unexpected step {:?}", step),
+        }
+        if let Some((from, _)) = after.candidate {
+            assert_eq!(after.hull.last(), Some(&from));
+        }
+    }
+    assert_eq!(snapshots.last().unwrap().hull, closed(GrahamScan::scan(&points)));
+    assert_eq!(snapshots.last().unwrap().candidate, None);
+
+    let snapshots = JarvisMarchSteps::new(&points).record();
+    assert_eq!(snapshots.len(), JarvisMarch::march(points.iter()).len() + 1);
+    assert_eq!(snapshots[1].candidate, Some((snapshots[1].hull[0], snapshots[2].hull[1])));
+    assert_eq!(snapshots.last().unwrap().hull, closed(JarvisMarch::march(points.iter())));
+
+    // The triangles are added one at a time, then the flips lead to the Delaunay triangulation
+    let (sorted, snapshots) = Incremental2dTriangulation::record(points.clone(), true);
+    let mut indices = Incremental2dTriangulation::triangulate(&mut points.clone());
+    let added = snapshots.iter().filter(|snapshot| matches!(snapshot.step, Some(Step::Triangle(..)))).count();
+    assert_eq!(added * 3, indices.len());
+    assert_eq!(snapshots[added].triangles, indices);
+    for snapshot in &snapshots[added + 1..] {
+        let (a, b) = snapshot.candidate.unwrap();
+        let sharing = snapshot.triangles.chunks(3).filter(|t| t.contains(&a) && t.contains(&b)).count();
+        assert_eq!(sharing, 2);
+        assert_eq!(snapshot.hull, snapshots[added].hull);
+    }
+    Incremental2dTriangulation::edge_flipping(&mut indices, &sorted);
+    assert_eq!(snapshots.last().unwrap().triangles, indices);
+    assert!(snapshots.len() > added + 1, "these points need flips");
+}
+
+#[test]
+fn lifting_map() {
+    assert_eq!(paraboloid_height(Vec2::new(0.0, 0.5)), 0.25);
+    assert_eq!(paraboloid_height(Vec2::new(-3.0, 4.0)), 25.0);
+
+    let (a, b, c) = (Vec2::new(-0.5, 0.0), Vec2::new(0.5, 0.0), Vec2::new(0.0, 0.5));
+    assert_eq!(lower_hull(&[ a, c, b ]), vec![ 0, 2, 1 ]);
+    assert!(lower_hull(&[ a, b ]).is_empty());
+    assert!(lower_hull(&[ a, b, Vec2::new(1.5, 0.0), Vec2::new(2.5, 0.0) ]).is_empty());
+
+    // A grid is full of cocircular points, any of their triangulations is Delaunay
+    let grid = (0..36).map(|i| Vec2::new((i % 6) as f32 * 0.2 - 0.5, (i / 6) as f32 * 0.2 - 0.5)).collect::<Vec<_>>();
+    let triangles = lower_hull(&grid);
+    assert_eq!(triangles.len() / 3, 50);
+    assert!(validate_delaunay(&grid, &triangles).is_empty());
+    assert!(triangles.chunks(3).all(|t| Vec2::orientation(grid[t[0]], grid[t[1]], grid[t[2]]) > 0.0));
+
+    // Without cocircular points, the lower hull is the Delaunay triangulation
+    let points = vec![ Vec2::new(-0.7, -0.6), Vec2::new(0.8, -0.5), Vec2::new(0.1, 0.9), Vec2::new(0.05, 0.02), Vec2::new(0.3, -0.2) ];
+    let delaunay = Incremental2dTriangulation::delaunay(&points);
+    let triangles = lower_hull(&points);
+    assert_eq!(triangles.len(), delaunay.len());
+    assert_eq!(shared_triangles(&triangles, &delaunay) * 3, delaunay.len());
+    assert_eq!(shared_triangles(&[ 2, 0, 1, 0, 1, 3 ], &[ 1, 2, 0 ]), 1);
+}
+
+#[cfg(feature = "gui")]
+#[test]
+fn stereo_camera() {
+    use crate::{ graphics::{ OrbitCamera, CameraPose, Stereo, StereoMode, Viewport }, math::Vec3 };
+
+    let mut cam = OrbitCamera::new(10.0, Vec3::new(1.0, 2.0, 3.0), 45.0, 0.1, 100.0);
+    let project = |cam: &OrbitCamera, p: cgmath::Vector3<f32>, offset: f32| {
+        let clip = cam.get_eye_projection_matrix(1.5, offset) * cam.get_eye_view_matrix(offset) * p.extend(1.0);
+        Vec2::new(clip.x / clip.w, clip.y / clip.w)
+    };
+    // The target is at the same place in the views of both eyes, points closer to the camera are further apart
+    let target = cam.target.0;
+    let (left, right) = (project(&cam, target, -0.2), project(&cam, target, 0.2));
+    assert!(left.x.abs() < 1e-5 && right.x.abs() < 1e-5);
+    let near = (cam.get_position().0 + target) / 2.0;
+    let (left, right) = (project(&cam, near, -0.2), project(&cam, near, 0.2));
+    assert!(left.x > right.x && (left.y - right.y).abs() < 1e-5);
+    assert_eq!(project(&cam, near, 0.0), cam.project(near.into(), 1.5).unwrap());
+
+    let pose = CameraPose { target: [ 0.5, -1.0, 2.0 ], distance: 4.0, phi: 0.3, theta: 0.2 };
+    let pose: CameraPose = toml::from_str(&toml::to_string_pretty(&pose).unwrap()).unwrap();
+    cam.set_pose(&pose);
+    assert_eq!(cam.pose(), pose);
+    cam.set_pose(&CameraPose { theta: 2.0, distance: 100.0, .. pose });
+    assert!(cam.pose().theta < 1.56 && cam.pose().distance == 50.0);
+
+    let viewport = Viewport::new(0.5, 0.0, 0.5, 1.0);
+    let mut stereo = Stereo::default();
+    assert_eq!(stereo.eyes(&viewport, &cam).len(), 1);
+    stereo.mode = StereoMode::SideBySide;
+    let eyes = stereo.eyes(&viewport, &cam);
+    assert_eq!(eyes[0].viewport, Viewport::new(0.5, 0.0, 0.25, 1.0));
+    assert_eq!(eyes[1].viewport, Viewport::new(0.75, 0.0, 0.25, 1.0));
+    assert!(eyes[0].offset < 0.0 && eyes[0].offset == -eyes[1].offset);
+    stereo.mode = StereoMode::Anaglyph;
+    let eyes = stereo.eyes(&viewport, &cam);
+    assert!(eyes.iter().all(|eye| eye.viewport == viewport) && eyes[1].clear_depth);
+}
+
+#[test]
+fn voronoi_cells() {
+    let bounds = Rect::new(Vec2::new(-1.0, -1.0), Vec2::new(1.0, 1.0));
+    assert!(voronoi_diagram(&[], &bounds).is_empty());
+    let cells = voronoi_diagram(&[ Vec2::new(0.2, 0.3) ], &bounds);
+    assert!((cells[0].area() - 4.0).abs() < 1e-5 && !cells[0].bounded);
+
+    // The cell of the center of a square is the diamond between the middles of its sides
+    let points = [ Vec2::new(-0.5, -0.5), Vec2::new(0.5, -0.5), Vec2::new(0.5, 0.5), Vec2::new(-0.5, 0.5), Vec2::new(0.0, 0.0) ];
+    let cells = voronoi_diagram(&points, &bounds);
+    assert!(cells[4].bounded && cells[..4].iter().all(|cell| !cell.bounded));
+    assert!((cells[4].area() - 0.5).abs() < 1e-5);
+    assert!(cells[4].polygon.contains(&Vec2::new(0.5, 0.0)) && cells[4].polygon.contains(&Vec2::new(0.0, -0.5)));
+    assert_eq!(cells[4].neighbours, vec![ 0, 1, 2, 3 ]);
+    assert!(cells[..4].iter().all(|cell| (cell.area() - 0.875).abs() < 1e-5));
+
+    // Collinear points have strips, a duplicate has no cell
+    let points = [ Vec2::new(0.5, 0.0), Vec2::new(-0.5, 0.0), Vec2::new(0.0, 0.0), Vec2::new(0.5, 0.0) ];
+    let cells = voronoi_diagram(&points, &bounds);
+    assert_eq!(cells.iter().map(|cell| cell.neighbours.len()).collect::<Vec<_>>(), vec![ 1, 1, 2, 0 ]);
+    assert!((cells[2].area() - 1.0).abs() < 1e-5 && (cells[0].area() - 1.5).abs() < 1e-5);
+    assert!(cells[3].polygon.is_empty());
+
+    let mut seed = 7u32;
+    let mut random = || {
+        seed ^= seed << 13;
+        seed ^= seed >> 17;
+        seed ^= seed << 5;
+        (seed % 20000) as f32 / 10000.0 - 1.0
+    };
+    let points = (0..200).map(|_| Vec2::new(random(), random())).collect::<Vec<_>>();
+    let cells = voronoi_diagram(&points, &bounds);
+    assert!((cells.iter().map(VoronoiCell::area).sum::<f32>() - 4.0).abs() < 1e-3);
+    for cell in &cells {
+        assert!(cell.neighbours.iter().all(|&neighbour| cells[neighbour].neighbours.contains(&cell.site)));
+        // The vertices of a cell are at least as close to its site as to any other point
+        let site = points[cell.site];
+        for &v in &cell.polygon {
+            let distance = (&v - &site).length();
+            assert!(points.iter().all(|&p| (&v - &p).length() >= distance - 1e-4));
+        }
+    }
+}
+
+#[test]
+fn segment_intersections() {
+    use crate::math::Segment2;
+
+    let pairs = |segments: &[Segment2]| {
+        let mut pairs = bentley_ottmann(segments).iter().flat_map(|i| i.pairs().collect::<Vec<_>>()).collect::<Vec<_>>();
+        pairs.sort_unstable();
+        pairs.dedup();
+        pairs
+    };
+
+    // Three segments through the same point are reported once, with every pair
+    let star = [
+        Segment2::new(Vec2::new(-1.0, -1.0), Vec2::new(1.0, 1.0)),
+        Segment2::new(Vec2::new(-1.0, 1.0), Vec2::new(1.0, -1.0)),
+        Segment2::new(Vec2::new(0.0, -1.0), Vec2::new(0.0, 1.0)),
+        Segment2::new(Vec2::new(0.2, 0.9), Vec2::new(0.6, 0.9)),
+    ];
+    let intersections = bentley_ottmann(&star);
+    assert_eq!(intersections.len(), 1);
+    assert_eq!(intersections[0].point, Vec2::new(0.0, 0.0));
+    assert_eq!(intersections[0].segments, vec![ 0, 1, 2 ]);
+    assert_eq!(pairs(&star), vec![ (0, 1), (0, 2), (1, 2) ]);
+
+    // Touching ends, a horizontal segment over a vertical one, and a segment without length
+    let segments = [
+        Segment2::new(Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.0)),
+        Segment2::new(Vec2::new(1.0, 0.0), Vec2::new(1.0, 1.0)),
+        Segment2::new(Vec2::new(0.5, -0.5), Vec2::new(0.5, 0.5)),
+        Segment2::new(Vec2::new(0.2, 0.2), Vec2::new(0.2, 0.2)),
+    ];
+    let intersections = bentley_ottmann(&segments);
+    assert_eq!(intersections.iter().map(|i| i.point).collect::<Vec<_>>(), vec![ Vec2::new(0.5, 0.0), Vec2::new(1.0, 0.0) ]);
+    assert_eq!(pairs(&segments), vec![ (0, 1), (0, 2) ]);
+
+    // The sweep finds the same pairs as testing every pair
+    let mut seed = 12345u32;
+    let mut random = || {
+        seed ^= seed << 13;
+        seed ^= seed >> 17;
+        seed ^= seed << 5;
+        (seed % 20000) as f32 / 10000.0 - 1.0
+    };
+    let segments = (0..150).map(|_| {
+            let a = Vec2::new(random(), random());
+            Segment2::new(a, &a + &Vec2::new(random() * 0.4, random() * 0.4))
+        })
+        .collect::<Vec<_>>();
+    let mut expected = Vec::new();
+    for i in 0..segments.len() {
+        for j in i + 1..segments.len() {
+            if segments[i].intersects(&segments[j]) {
+                expected.push((i, j));
+            }
+        }
+    }
+    assert!(expected.len() > 50);
+    assert_eq!(pairs(&segments), expected);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn convex_hull_3d_colors() {
+    use crate::{ algorithms::{ IncrementalHull, convex_hull_3d::Color }, point_cloud::Distribution };
+
+    // Before each step, the next point sees the red faces, a disk bounded by a cycle of purple edges and vertices
+    let mut steps = IncrementalHull::new(&Distribution::Ball.generate(200)).unwrap();
+    while let Some(p) = steps.next_point() {
+        let graph = steps.graph();
+        let red = graph.faces.iter().filter(|face| face.color == Color::Red).count();
+        let purple = graph.edges.iter().filter(|edge| edge.color == Color::Purple).count();
+        let inner = graph.vertices.iter().filter(|vertex| vertex.color == Color::Red).map(|vertex| vertex.idx).collect::<Vec<_>>();
+        assert_eq!(red, steps.conflicts().filter(|&(q, _)| q == p).count());
+        assert_eq!(purple, graph.vertices.iter().filter(|vertex| vertex.color == Color::Purple).count());
+        if red > 0 {
+            assert_eq!(red, 2 * inner.len() + purple - 2);
+        } else {
+            assert_eq!((purple, inner.len()), (0, 0));
+        }
+
+        // The horizon loop follows the purple edges
+        let horizon = steps.horizon();
+        assert_eq!(horizon.len(), purple);
+        for (i, &u) in horizon.iter().enumerate() {
+            let v = horizon[(i + 1) % horizon.len()];
+            let edge = (u.min(v) as u32, u.max(v) as u32);
+            assert!(graph.edges.iter().any(|e| e.idx == edge && e.color == Color::Purple));
+        }
+
+        // The vertices inside the red faces are removed with them, and the point is joined to the horizon
+        assert_eq!(steps.step(), Some(p));
+        let graph = steps.graph();
+        assert!(graph.vertices.iter().all(|vertex| !inner.contains(&vertex.idx)));
+        assert!(horizon.iter().all(|&v| graph.faces.iter().any(|face| face.idx.2 as usize == p && face.idx.0 as usize == v)));
+    }
+    let graph = steps.graph();
+    assert!(graph.faces.iter().all(|face| face.color == Color::White));
+    assert!(graph.edges.iter().all(|edge| edge.color == Color::White));
+    assert!(steps.horizon().is_empty());
+}
+
+#[test]
+fn polygon_triangulation() {
+    use crate::{ algorithms::triangulate_polygon, math::{ cmp_f32, polygon } };
+
+    // n - 2 counter-clockwise triangles inside the polygon and covering it, whatever its orientation
+    let check = |vertices: &[Vec2]| {
+        let triangles = triangulate_polygon(vertices);
+        assert_eq!(triangles.len(), 3 * (vertices.len() - 2));
+        let mut area = 0.0;
+        for t in triangles.chunks(3) {
+            let (a, b, c) = (vertices[t[0]], vertices[t[1]], vertices[t[2]]);
+            assert!(Vec2::orientation(a, b, c) > 0.0);
+            assert!(polygon::contains(vertices, Vec2::new((a.x + b.x + c.x) / 3.0, (a.y + b.y + c.y) / 3.0)));
+            area += polygon::signed_area(&[ a, b, c ]);
+        }
+        assert!(cmp_f32(area, polygon::signed_area(vertices).abs()));
+        triangles
+    };
+    let from = |coordinates: &[(i32, i32)]| coordinates.iter().map(|&(x, y)| Vec2::new(x as f32, y as f32)).collect::<Vec<_>>();
+
+    // Convex, in both orientations
+    let mut octagon = (0..8).map(|k| Vec2::new((k as f32 * core::f32::consts::FRAC_PI_4).cos(), (k as f32 * core::f32::consts::FRAC_PI_4).sin())).collect::<Vec<_>>();
+    check(&octagon);
+    octagon.reverse();
+    check(&octagon);
+
+    // Concave: a comb and a spiral
+    check(&from(&[ (0, 0), (5, 0), (5, 2), (4, 2), (4, 1), (3, 1), (3, 2), (2, 2), (2, 1), (1, 1), (1, 2), (0, 2) ]));
+    let mut spiral = from(&[ (0, 0), (6, 0), (6, 6), (1, 6), (1, 2), (4, 2), (4, 4), (3, 4), (3, 3), (2, 3), (2, 5), (5, 5), (5, 1), (0, 1) ]);
+    check(&spiral);
+    spiral.reverse();
+    check(&spiral);
+
+    // Collinear vertices: the midpoints of the sides of a square, and a triangle with its base split,
+    // only triangulated by the fan from its apex
+    check(&from(&[ (0, 0), (1, 0), (2, 0), (2, 1), (2, 2), (1, 2), (0, 2), (0, 1) ]));
+    let split = from(&[ (0, 0), (1, 0), (2, 0), (3, 0), (4, 0), (2, 3) ]);
+    assert!(check(&split).chunks(3).all(|t| t.contains(&5)));
+
+    // A repeated vertex counts once, and too few vertices have no triangle
+    let repeated = from(&[ (0, 0), (2, 0), (2, 0), (2, 2), (0, 2) ]);
+    assert_eq!(triangulate_polygon(&repeated).len(), 6);
+    assert!(triangulate_polygon(&split[..2]).is_empty());
+}
+
+#[test]
+fn monotone_decomposition() {
+    use crate::{ algorithms::{ self, triangulate_polygon, triangulate_polygon_monotone, is_y_monotone }, math::{ cmp_f32, polygon } };
+
+    // Counter-clockwise y-monotone pieces, triangulated like the ear clipping triangulates the whole polygon
+    let check = |vertices: &[Vec2]| {
+        let pieces = algorithms::monotone_decomposition(vertices);
+        for piece in &pieces {
+            let piece = piece.iter().map(|&idx| vertices[idx]).collect::<Vec<_>>();
+            assert!(is_y_monotone(&piece));
+            assert!(polygon::signed_area(&piece) > 0.0);
+        }
+        let triangles = triangulate_polygon_monotone(vertices);
+        assert_eq!(triangles.len(), triangulate_polygon(vertices).len());
+        let mut area = 0.0;
+        for t in triangles.chunks(3) {
+            let (a, b, c) = (vertices[t[0]], vertices[t[1]], vertices[t[2]]);
+            assert!(Vec2::orientation(a, b, c) > 0.0);
+            area += polygon::signed_area(&[ a, b, c ]);
+        }
+        assert!(cmp_f32(area, polygon::signed_area(vertices).abs()));
+        pieces.len()
+    };
+    let from = |coordinates: &[(i32, i32)]| coordinates.iter().map(|&(x, y)| Vec2::new(x as f32, y as f32)).collect::<Vec<_>>();
+
+    // A convex polygon is a single piece
+    let mut octagon = (0..8).map(|k| Vec2::new((k as f32 * core::f32::consts::FRAC_PI_4).cos(), (k as f32 * core::f32::consts::FRAC_PI_4).sin())).collect::<Vec<_>>();
+    assert_eq!(check(&octagon), 1);
+    octagon.reverse();
+    assert_eq!(check(&octagon), 1);
+
+    // The gaps of a comb are merge vertices with their teeth up, split vertices with their teeth down
+    let comb = from(&[ (0, 0), (5, 0), (5, 2), (4, 2), (4, 1), (3, 1), (3, 2), (2, 2), (2, 1), (1, 1), (1, 2), (0, 2) ]);
+    assert!(!is_y_monotone(&comb));
+    assert_eq!(check(&comb), 3);
+    let upside_down = comb.iter().map(|p| Vec2::new(p.x, -p.y)).collect::<Vec<_>>();
+    assert_eq!(check(&upside_down), 3);
+    let arrow = from(&[ (0, 0), (3, 1), (1, 2), (3, 3), (0, 4) ]);
+    assert!(is_y_monotone(&arrow));
+    assert_eq!(check(&arrow), 1);
+    let mut spiral = from(&[ (0, 0), (6, 0), (6, 6), (1, 6), (1, 2), (4, 2), (4, 4), (3, 4), (3, 3), (2, 3), (2, 5), (5, 5), (5, 1), (0, 1) ]);
+    check(&spiral);
+    spiral.reverse();
+    check(&spiral);
+
+    // Horizontal edges and collinear vertices, and a repeated vertex that counts once
+    assert_eq!(check(&from(&[ (0, 0), (1, 0), (2, 0), (2, 1), (2, 2), (1, 2), (0, 2), (0, 1) ])), 1);
+    check(&from(&[ (0, 0), (4, 0), (4, 2), (3, 2), (2, 1), (1, 2), (0, 2) ]));
+    assert_eq!(triangulate_polygon_monotone(&from(&[ (0, 0), (2, 0), (2, 0), (2, 2), (0, 2) ])).len(), 6);
+
+    // Stars with alternating radii, with many split and merge vertices
+    for n in 3..40 {
+        let star = (0..n).map(|k| {
+                let angle = k as f32 * 2.0 * core::f32::consts::PI / n as f32;
+                let radius = if k % 2 == 0 { 1.0 } else { 0.4 };
+                Vec2::new(radius * angle.cos(), radius * angle.sin())
+            })
+            .collect::<Vec<_>>();
+        check(&star);
+    }
+
+    // Not simple, the boundary goes through (-0.25, -0.25) twice: only part of it is triangulated, like by ear clipping
+    let touching = [ (0.25, 0.5), (-0.25, 0.0), (-0.5, 0.5), (0.25, 0.25), (0.0, -0.25), (-0.25, -0.25), (-0.5, -0.25), (-0.25, -0.25) ]
+        .iter()
+        .map(|&(x, y)| Vec2::new(x, y))
+        .collect::<Vec<_>>();
+    assert!(triangulate_polygon_monotone(&touching).len() <= 3 * (touching.len() - 2));
+}
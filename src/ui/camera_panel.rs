@@ -0,0 +1,82 @@
+use crate::{
+    Result,
+    graphics::{ CameraPose, OrbitCamera, Stereo, StereoMode },
+    ui::SharedSettings,
+};
+
+use std::{ fs, path::Path };
+
+use imgui::{ im_str, ImStr, ImString, Slider, Ui };
+
+/// Stereo rendering and camera pose files of a 3D view, to produce the same pictures again, e.g. for teaching material.
+pub struct CameraPanel {
+    pub stereo: Stereo,
+    /// Path of the TOML file the pose of the camera is exported to and imported from
+    path: ImString,
+    /// Provides the directory of the file, and remembers it
+    settings: SharedSettings,
+}
+
+impl CameraPanel {
+    /// `file_name` is the default name of the pose file, in the last export directory.
+    pub fn new(settings: SharedSettings, file_name: &str) -> Self {
+        let mut path = ImString::with_capacity(256);
+        if let Some(dir) = &settings.borrow().export_directory {
+            path.push_str(&dir.join(file_name).to_string_lossy());
+        }
+        Self {
+            stereo: Stereo::default(),
+            path,
+            settings,
+        }
+    }
+
+    pub fn configure(&mut self, ui: &Ui, cam: &mut OrbitCamera) -> Result<()> {
+        let names = StereoMode::ALL.iter()
+                                   .map(|mode| ImString::new(mode.name()))
+                                   .collect::<Vec<_>>();
+        let names = names.iter()
+                         .map(|name| name.as_ref())
+                         .collect::<Vec<&ImStr>>();
+        let mut idx = StereoMode::ALL.iter().position(|&mode| mode == self.stereo.mode).unwrap_or(0);
+        if imgui::ComboBox::new(im_str!("Stereo")).build_simple_string(ui, &mut idx, &names[..]) {
+            self.stereo.mode = StereoMode::ALL[idx];
+        }
+        if self.stereo.mode != StereoMode::Off {
+            Slider::new(im_str!("Eye separation"), 0.0..=0.1).build(ui, &mut self.stereo.separation);
+            ui.text_disabled(im_str!("Relative to the distance of the camera"));
+        }
+
+        ui.input_text(im_str!("Camera file"), &mut self.path).build();
+        if ui.button(im_str!("Export Camera"), [0.0, 0.0]) {
+            self.export(cam)?;
+        }
+        ui.same_line(0.0);
+        if ui.button(im_str!("Import Camera"), [0.0, 0.0]) {
+            self.import(cam)?;
+        }
+        Ok(())
+    }
+
+    fn export(&self, cam: &OrbitCamera) -> Result<()> {
+        let path = Path::new(self.path.to_str().trim());
+        fs::write(path, toml::to_string_pretty(&cam.pose())?)?;
+        log::info!("Exported the camera to {}", path.display());
+        self.remember_directory(path);
+        Ok(())
+    }
+
+    fn import(&self, cam: &mut OrbitCamera) -> Result<()> {
+        let path = Path::new(self.path.to_str().trim());
+        let pose: CameraPose = toml::from_str(&fs::read_to_string(path)?)?;
+        cam.set_pose(&pose);
+        log::info!("Imported the camera from {}", path.display());
+        self.remember_directory(path);
+        Ok(())
+    }
+
+    fn remember_directory(&self, path: &Path) {
+        self.settings.borrow_mut().export_directory = path.canonicalize().ok()
+                                                        .and_then(|path| path.parent().map(Path::to_owned));
+    }
+}
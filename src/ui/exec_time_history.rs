@@ -0,0 +1,69 @@
+use std::{
+    collections::VecDeque,
+    time::Duration,
+};
+
+use imgui::{ im_str, Ui };
+
+/// Number of runs kept in the history.
+const CAPACITY: usize = 100;
+
+/// Rolling history of the execution times of an algorithm,
+/// displayed as a small plot to show how the cost evolves as points are added.
+#[derive(Default)]
+pub struct ExecTimeHistory {
+    /// Execution times in microseconds, oldest first
+    history: VecDeque<f32>,
+}
+
+impl ExecTimeHistory {
+    /// Records the execution time of a new run, discarding the oldest one if the history is full.
+    pub fn push(&mut self, exec_time: Duration) {
+        if self.history.len() >= CAPACITY {
+            self.history.pop_front();
+        }
+        self.history.push_back(exec_time.as_micros() as f32);
+    }
+
+    pub fn clear(&mut self) {
+        self.history.clear();
+    }
+
+    /// Returns the execution time of the last run in microseconds.
+    pub fn last(&self) -> Option<f32> {
+        self.history.back().copied()
+    }
+
+    /// Returns the minimum, average and maximum execution times in microseconds.
+    pub fn stats(&self) -> Option<(f32, f32, f32)> {
+        if self.history.is_empty() {
+            return None;
+        }
+
+        let min = self.history.iter().cloned().fold(f32::INFINITY, f32::min);
+        let max = self.history.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+        let avg = self.history.iter().sum::<f32>() / self.history.len() as f32;
+        Some((min, avg, max))
+    }
+
+    /// Displays the last execution time, the statistics and the plot of the history.
+    pub fn configure(&self, ui: &Ui) {
+        let last = match self.last() {
+            Some(last) => last,
+            None => return,
+        };
+        ui.text(im_str!("Execution time: {} µs", last));
+
+        if let Some((min, avg, max)) = self.stats() {
+            ui.text(im_str!("Min: {} µs, avg: {:.0} µs, max: {} µs", min, avg, max));
+
+            let (first, second) = self.history.as_slices();
+            let values = [first, second].concat();
+            ui.plot_lines(im_str!("##exec_time_history"), &values)
+                .scale_min(0.0)
+                .scale_max(max)
+                .graph_size([0.0, 40.0])
+                .build();
+        }
+    }
+}
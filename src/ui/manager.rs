@@ -1,11 +1,14 @@
-use super::window::Window;
+use super::{ Toasts, window::Window, settings::{ self, SharedSettings, Theme } };
+use crate::{ Error, Result, graphics::Viewport };
+
+use std::path::PathBuf;
 
 use glium::{ Display, Frame };
 use imgui::{
-    FontConfig, FontSource,
+    im_str, FontConfig, FontSource, ImString, MenuItem,
     Context as ImContext,
 };
-use winit::Event;
+use winit::event::{ Event, WindowEvent, ElementState, KeyboardInput };
 use imgui_glium_renderer::Renderer as ImRenderer;
 use imgui_winit_support::{ HiDpiMode, WinitPlatform };
 
@@ -14,24 +17,116 @@ pub struct Manager<'a> {
     platform: WinitPlatform,
     imgui_renderer: ImRenderer,
     windows: Vec<Box<dyn Window + 'a>>,
+    layout: Layout,
+    toasts: Toasts,
+    settings: SharedSettings,
+    /// The theme currently applied to the ImGui style
+    theme: Theme,
+}
+
+/// Maximum number of viewports displayed at the same time.
+pub const MAX_VIEWPORTS: usize = 4;
+
+/// Splits the window into viewports so that several algorithms can be displayed at the same time.
+/// The last viewport that was clicked has the input focus.
+pub struct Layout {
+    count: usize,
+    focused: usize,
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self {
+            count: 1,
+            focused: 0,
+        }
+    }
+}
+
+impl Layout {
+    /// Returns the number of viewports.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Changes the number of viewports, between 1 and [`MAX_VIEWPORTS`](constant.MAX_VIEWPORTS.html).
+    pub fn set_count(&mut self, count: usize) {
+        self.count = count.clamp(1, MAX_VIEWPORTS);
+        if self.focused >= self.count {
+            self.focused = 0;
+        }
+    }
+
+    /// Returns the index of the viewport that receives the input events.
+    pub fn focused(&self) -> usize {
+        self.focused
+    }
+
+    /// Gives the focus to the next viewport, or the first one after the last.
+    pub fn focus_next(&mut self) {
+        self.focused = (self.focused + 1) % self.count;
+    }
+
+    /// Returns the regions of the window covered by each viewport:
+    /// side by side columns, or a 2x2 grid for 4 viewports.
+    pub fn viewports(&self) -> Vec<Viewport> {
+        if self.count == 4 {
+            return vec![
+                Viewport::new(0.0, 0.5, 0.5, 0.5),
+                Viewport::new(0.5, 0.5, 0.5, 0.5),
+                Viewport::new(0.0, 0.0, 0.5, 0.5),
+                Viewport::new(0.5, 0.0, 0.5, 0.5),
+            ];
+        }
+
+        let width = 1.0 / self.count as f32;
+        (0..self.count)
+            .map(|i| Viewport::new(i as f32 * width, 0.0, width, 1.0))
+            .collect()
+    }
+
+    /// Gives the focus to the viewport that is clicked.
+    fn handle_events(&mut self, window: &winit::window::Window, event: &Event<()>, io: &imgui::Io) {
+        if let Event::WindowEvent { event: WindowEvent::MouseInput { state, .. }, .. } = event {
+            if !io.want_capture_mouse && state == &ElementState::Pressed {
+                let pos = io.mouse_pos.into();
+                if let Some(idx) = self.viewports().iter().position(|v| v.contains(pos, window)) {
+                    self.focused = idx;
+                }
+            }
+        }
+    }
+
+    fn menu(&mut self, ui: &imgui::Ui) {
+        for count in 1..=MAX_VIEWPORTS {
+            let label = if count == 1 {
+                ImString::new("1 viewport")
+            } else {
+                ImString::new(format!("{} viewports", count))
+            };
+            if MenuItem::new(&label).selected(self.count == count).build(ui) {
+                self.set_count(count);
+            }
+        }
+    }
 }
 
 impl<'a> Manager<'a> {
-    pub fn new(display: &Display) -> Self {
+    pub fn new(display: &Display, settings: SharedSettings) -> Result<Self> {
         let mut imgui = ImContext::create();
-        imgui.set_ini_filename(None);
+        imgui.set_ini_filename(ini_path()); // Windows positions and sizes are restored between sessions
         imgui.set_log_filename(None);
 
         if let Some(backend) = super::clipboard::init() {
             imgui.set_clipboard_backend(Box::new(backend));
         } else {
-            eprintln!("Failed to initialize clipboard");
+            log::warn!("Failed to initialize clipboard");
         }
 
         let gl_window = display.gl_window();
         let window = gl_window.window();
         let mut platform = WinitPlatform::init(&mut imgui);
-        platform.attach_window(imgui.io_mut(), &window, HiDpiMode::Rounded);
+        platform.attach_window(imgui.io_mut(), window, HiDpiMode::Rounded);
 
         let hidpi_factor = platform.hidpi_factor();
         let font_size = (13.0 * hidpi_factor) as f32;
@@ -45,40 +140,105 @@ impl<'a> Manager<'a> {
         ]);
         imgui.io_mut().font_global_scale = (1.0 / hidpi_factor) as f32;
 
-        let imgui_renderer = ImRenderer::init(&mut imgui, display)
-                                    .expect("Could not initialize ImGui renderer");
+        let theme = settings.borrow().theme;
+        theme.apply(imgui.style_mut());
 
-        Self {
+        let imgui_renderer = ImRenderer::init(&mut imgui, display)?;
+
+        let mut layout = Layout::default();
+        layout.set_count(settings.borrow().layout.viewports);
+
+        Ok(Self {
             imgui,
             platform,
             imgui_renderer,
             windows: Vec::new(),
-        }
+            layout,
+            toasts: Toasts::default(),
+            settings,
+            theme,
+        })
     }
 
-    pub fn handle_events(&mut self, window: &winit::Window, event: &Event) {
+    pub fn handle_events(&mut self, window: &winit::window::Window, event: &Event<()>) {
         self.platform.handle_event(self.imgui.io_mut(), window, event);
+        // Update the focus first so that a click is handled by the viewport it happened in
+        self.layout.handle_events(window, event, self.imgui.io());
+        if let Event::WindowEvent { event: WindowEvent::KeyboardInput { input, .. }, .. } = event {
+            self.handle_keyboard_input(input);
+        }
 
         for ui_win in self.windows.iter_mut() {
-            ui_win.handle_events(window, event, self.imgui.io());
+            if let Err(err) = ui_win.handle_events(window, event, self.imgui.io(), &self.layout) {
+                self.toasts.push(err.to_string());
+            }
         }
     }
 
-    pub fn draw(&mut self, window: &winit::Window, target: &mut Frame) {
+    /// Updates the ImGui inputs, to be called once the events of the frame have been handled.
+    pub fn prepare_frame(&mut self, window: &winit::window::Window) {
         let io = self.imgui.io_mut();
-        self.platform.prepare_frame(io, window).expect("Could not start ImGui frame");
+        if let Err(err) = self.platform.prepare_frame(io, window) {
+            self.show_error(err.into());
+        }
+    }
+
+    /// Handles the keybindings of the viewports, unless an ImGui widget has the keyboard focus.
+    fn handle_keyboard_input(&mut self, input: &KeyboardInput) {
+        if self.imgui.io().want_capture_keyboard || input.state != ElementState::Pressed {
+            return;
+        }
+        if input.virtual_keycode == Some(self.settings.borrow().keybindings.next_viewport) {
+            self.layout.focus_next();
+        }
+    }
+
+    pub fn draw(&mut self, window: &winit::window::Window, target: &mut Frame) {
+        let theme = self.settings.borrow().theme;
+        if theme != self.theme {
+            theme.apply(self.imgui.style_mut());
+            self.theme = theme;
+        }
+
         let ui = self.imgui.frame();
+        let windows = &mut self.windows;
+        let layout = &mut self.layout;
+        let toasts = &mut self.toasts;
 
-        for ui_win in self.windows.iter_mut() {
-            ui_win.draw(target, &ui, window);
+        ui.main_menu_bar(|| {
+            ui.menu(im_str!("View"), true, || {
+                for ui_win in windows.iter_mut() {
+                    let name = ImString::new(ui_win.name());
+                    MenuItem::new(&name).build_with_ref(&ui, ui_win.opened());
+                }
+            });
+            ui.menu(im_str!("Layout"), true, || {
+                layout.menu(&ui);
+            });
+        });
+        self.settings.borrow_mut().layout.viewports = layout.count();
+
+        for ui_win in windows.iter_mut() {
+            if let Err(err) = ui_win.draw(target, &ui, window, layout) {
+                toasts.push(err.to_string());
+            }
+        }
+        toasts.draw(&ui, window);
+
+        self.platform.prepare_render(&ui, window);
+        if let Err(err) = self.imgui_renderer.render(target, ui.render()) {
+            // Shown on the next frame
+            self.toasts.push(Error::from(err).to_string());
         }
+    }
 
-        self.platform.prepare_render(&ui, &window);
-        self.imgui_renderer.render(target, ui.render()).expect("Could not render ImGui");
+    /// Displays an error as a notification instead of aborting.
+    pub fn show_error(&mut self, err: Error) {
+        self.toasts.push(err.to_string());
     }
 
-    pub fn add_window<W: 'a>(&mut self, window: W)
-    where W: Window {
+    pub fn add_window<W>(&mut self, window: W)
+    where W: Window + 'a {
         self.windows.push(Box::new(window));
     }
 
@@ -86,3 +246,9 @@ impl<'a> Manager<'a> {
         self.imgui.io()
     }
 }
+
+/// Returns the path of the file in which ImGui saves the layout of the windows,
+/// located in the user's configuration directory.
+fn ini_path() -> Option<PathBuf> {
+    Some(settings::config_dir()?.join("imgui.ini"))
+}
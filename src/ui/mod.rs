@@ -1,9 +1,24 @@
 mod clipboard;
 pub mod window;
 pub mod manager;
+pub mod point_input;
+pub mod exec_time_history;
+pub mod playback;
+pub mod camera_panel;
+pub mod toasts;
+pub mod settings;
+
+pub use point_input::PointInput;
+pub use exec_time_history::ExecTimeHistory;
+pub use playback::Playback;
+pub use camera_panel::CameraPanel;
+pub use toasts::Toasts;
+pub use settings::{ Settings, SharedSettings };
+
+use crate::Result;
 
 use glium::Display;
 
-pub fn init(display: &Display) -> manager::Manager {
-    manager::Manager::new(display)
+pub fn init(display: &Display, settings: SharedSettings) -> Result<manager::Manager<'_>> {
+    manager::Manager::new(display, settings)
 }
@@ -0,0 +1,201 @@
+use crate::{
+    Result,
+    algorithms::{ Snapshot, Step },
+    graphics::{ self, Shape, Viewport, shape::triangle_edges },
+    math::Vec2,
+};
+
+use std::time::{ Duration, Instant };
+
+use glium::{ index::PrimitiveType, Frame, Program, backend::Facade };
+use imgui::{ im_str, Slider, Ui };
+
+/// Number of points of the stack listed in the Algorithms window, the bottom of larger stacks is elided.
+const LISTED_POINTS: usize = 16;
+
+/// Plays back the states recorded after each step of an algorithm, forward and backward,
+/// and draws the current one: its triangles, its hull or stack, and the edge being considered.
+pub struct Playback<'f> {
+    program: Program,
+    /// Largest number of points whose steps are recorded, the states take quadratic memory
+    max_points: usize,
+    /// Input points, the states refer to them by index
+    points: Vec<Vec2>,
+    snapshots: Vec<Snapshot>,
+    /// Index of the displayed state
+    current: usize,
+    /// While playing, the next state is displayed every 1 / `speed` seconds
+    playing: bool,
+    speed: f32,
+    last_step: Instant,
+    triangles: Shape<'f>,
+    hull: Shape<'f>,
+    hull_points: Shape<'f>,
+    candidate: Shape<'f>,
+}
+
+impl<'f> Playback<'f> {
+    pub fn new(facade: &'f dyn Facade, max_points: usize) -> Result<Self> {
+        let vs = graphics::SHADERS._2d_vs;
+        let fs = graphics::SHADERS.basic_fs;
+        let program = Program::from_source(facade, vs, fs, None)?;
+
+        let mut hull = Shape::new(facade, PrimitiveType::LineStrip, [ 0.0, 0.8, 0.0 ])?;
+        hull.size = 2.0;
+        let mut candidate = Shape::new(facade, PrimitiveType::LinesList, [ 1.0, 0.3, 0.0 ])?;
+        candidate.size = 3.0;
+
+        Ok(Self {
+            program,
+            max_points,
+            points: Vec::new(),
+            snapshots: Vec::new(),
+            current: 0,
+            playing: false,
+            speed: 5.0,
+            last_step: Instant::now(),
+            triangles: Shape::new(facade, PrimitiveType::LinesList, [ 0.0, 0.2, 1.0 ])?,
+            hull,
+            hull_points: Shape::new(facade, PrimitiveType::Points, [ 0.0, 0.0, 0.8 ])?,
+            candidate,
+        })
+    }
+
+    /// Returns true while recorded steps are displayed, instead of the result of the algorithm.
+    pub fn is_active(&self) -> bool {
+        !self.snapshots.is_empty()
+    }
+
+    /// Starts playing `snapshots` from the first one, their indices refer to `points`.
+    pub fn start(&mut self, points: Vec<Vec2>, snapshots: Vec<Snapshot>) -> Result<()> {
+        self.points = points;
+        self.snapshots = snapshots;
+        self.playing = true;
+        self.last_step = Instant::now();
+        self.seek(0)
+    }
+
+    /// Discards the recorded steps, e.g. when the input points changed.
+    pub fn stop(&mut self) -> Result<()> {
+        self.playing = false;
+        self.points.clear();
+        self.snapshots.clear();
+        self.current = 0;
+        self.triangles.clear()?;
+        self.hull.clear()?;
+        self.hull_points.clear()?;
+        self.candidate.clear()
+    }
+
+    /// Displays the next state if it is time to, stops playing at the last one.
+    pub fn update(&mut self) -> Result<()> {
+        if self.playing && Instant::now() - self.last_step >= Duration::from_secs_f32(1.0 / self.speed) {
+            self.last_step = Instant::now();
+            if self.current + 1 < self.snapshots.len() {
+                self.seek(self.current + 1)?;
+            } else {
+                self.playing = false;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        if !self.is_active() {
+            return Ok(());
+        }
+        self.triangles.draw(target, &self.program, viewport)?;
+        self.hull.draw(target, &self.program, viewport)?;
+        self.candidate.draw(target, &self.program, viewport)?;
+        self.hull_points.draw(target, &self.program, viewport)
+    }
+
+    /// Displays the playback controls, or the button that records the steps of `point_count` points.
+    /// Returns true when that button is pressed: the renderer then records the steps and calls [`start()`](#method.start).
+    pub fn configure(&mut self, ui: &Ui, point_count: usize) -> Result<bool> {
+        if !self.is_active() {
+            if point_count > self.max_points {
+                ui.text_disabled(im_str!("Steps are recorded for up to {} points", self.max_points));
+                return Ok(false);
+            }
+            return Ok(ui.button(im_str!("Record Steps"), [0.0, 0.0]));
+        }
+
+        let last = self.snapshots.len() - 1;
+        if ui.button(im_str!("|<"), [0.0, 0.0]) {
+            self.playing = false;
+            self.seek(0)?;
+        }
+        ui.same_line(0.0);
+        if ui.button(im_str!("Step Back"), [0.0, 0.0]) && self.current > 0 {
+            self.playing = false;
+            self.seek(self.current - 1)?;
+        }
+        ui.same_line(0.0);
+        let label = if self.playing { im_str!("Pause") } else { im_str!("Play") };
+        if ui.button(label, [0.0, 0.0]) {
+            self.playing = !self.playing;
+            if self.playing && self.current == last {
+                self.seek(0)?; // Play again from the start
+            }
+        }
+        ui.same_line(0.0);
+        if ui.button(im_str!("Step"), [0.0, 0.0]) && self.current < last {
+            self.playing = false;
+            self.seek(self.current + 1)?;
+        }
+        ui.same_line(0.0);
+        if ui.button(im_str!(">|"), [0.0, 0.0]) {
+            self.playing = false;
+            self.seek(last)?;
+        }
+        ui.same_line(0.0);
+        if ui.button(im_str!("Stop"), [0.0, 0.0]) {
+            self.stop()?;
+            return Ok(false);
+        }
+        Slider::new(im_str!("Speed (steps/s)"), 0.5..=60.0).build(ui, &mut self.speed);
+
+        let snapshot = &self.snapshots[self.current];
+        ui.text(im_str!("Step {} / {}: {}", self.current, last, Self::describe(snapshot.step)));
+        if !snapshot.hull.is_empty() {
+            let skipped = snapshot.hull.len().saturating_sub(LISTED_POINTS);
+            let listed = snapshot.hull[skipped..].iter().map(|idx| idx.to_string()).collect::<Vec<_>>();
+            let ellipsis = if skipped > 0 { "... " } else { "" };
+            ui.text(im_str!("Hull: {}{}", ellipsis, listed.join(" ")));
+        }
+        if !snapshot.triangles.is_empty() {
+            ui.text(im_str!("{} triangles", snapshot.triangles.len() / 3));
+        }
+        Ok(false)
+    }
+
+    /// Displays the state at `idx` and regenerates the shapes.
+    fn seek(&mut self, idx: usize) -> Result<()> {
+        self.current = idx.min(self.snapshots.len().saturating_sub(1));
+        let snapshot = match self.snapshots.get(self.current) {
+            Some(snapshot) => snapshot,
+            None => return Ok(()),
+        };
+        let points = &self.points;
+
+        self.triangles.set_vertices(&triangle_edges(points, &snapshot.triangles))?;
+        let hull = snapshot.hull.iter().map(|&idx| points[idx]).collect::<Vec<_>>();
+        self.hull.set_vertices(&hull)?;
+        self.hull_points.set_vertices(&hull)?;
+        let candidate = snapshot.candidate
+                            .map(|(a, b)| vec![ points[a], points[b] ])
+                            .unwrap_or_default();
+        self.candidate.set_vertices(&candidate)
+    }
+
+    fn describe(step: Option<Step>) -> String {
+        match step {
+            None => "start".to_string(),
+            Some(Step::HullPush(idx)) => format!("push point {}", idx),
+            Some(Step::HullPop) => "pop the top of the stack".to_string(),
+            Some(Step::Triangle(a, b, c)) => format!("add the triangle ({}, {}, {})", a, b, c),
+            Some(Step::Flip(first, second)) => format!("flip to the triangles {:?} and {:?}", first, second),
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use crate::{
+    Result,
+    graphics::{ Grid, Viewport },
+    math::Vec2,
+};
+
+use glium::{ Frame, backend::Facade };
+use imgui::{ im_str, Ui };
+
+/// Options shared by the 2D algorithms to place points precisely:
+/// snapping clicked points to a grid and adding points at exact coordinates.
+pub struct PointInput<'f> {
+    pub snap_to_grid: bool,
+    grid: Grid<'f>,
+    grid_spacing: f32,
+    coords: [f32; 2],
+}
+
+impl<'f> PointInput<'f> {
+    pub fn new(facade: &'f dyn Facade) -> Result<Self> {
+        let grid_spacing = 0.1;
+
+        Ok(Self {
+            snap_to_grid: false,
+            grid: Grid::new(facade, grid_spacing)?,
+            grid_spacing,
+            coords: [ 0.0, 0.0 ],
+        })
+    }
+
+    /// Returns the position where a clicked point should be placed.
+    pub fn place(&self, point: Vec2) -> Vec2 {
+        if self.snap_to_grid {
+            point.snapped(self.grid.spacing())
+        } else {
+            point
+        }
+    }
+
+    /// Draws the grid if snapping is enabled.
+    pub fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()> {
+        if self.snap_to_grid {
+            self.grid.draw(target, viewport)?;
+        }
+        Ok(())
+    }
+
+    /// Displays the snapping and coordinate entry widgets.
+    /// Returns the point to add when the "Add Point" button is pressed.
+    pub fn configure(&mut self, ui: &Ui) -> Result<Option<Vec2>> {
+        ui.checkbox(im_str!("Snap to grid"), &mut self.snap_to_grid);
+        if self.snap_to_grid && ui.input_float(im_str!("Spacing"), &mut self.grid_spacing).step(0.05).build() {
+            self.grid_spacing = crate::math::clamp(self.grid_spacing, 0.01, 1.0);
+            self.grid.set_spacing(self.grid_spacing)?;
+        }
+
+        ui.input_float2(im_str!("Coordinates"), &mut self.coords).build();
+        if ui.button(im_str!("Add Point"), [0.0, 0.0]) {
+            return Ok(Some(self.coords.into()));
+        }
+        Ok(None)
+    }
+}
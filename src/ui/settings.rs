@@ -0,0 +1,164 @@
+//! Preferences of the viewer, saved as TOML in the user's configuration directory.
+
+use crate::Result;
+
+use std::{ cell::RefCell, fs, path::PathBuf, rc::Rc };
+
+use serde::{ Serialize, Deserialize };
+use winit::event::VirtualKeyCode;
+
+/// Settings shared by the viewer and its windows, which all run on the main thread.
+pub type SharedSettings = Rc<RefCell<Settings>>;
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub theme: Theme,
+    /// Samples per pixel, 0 disables multisampling. Only applied when the viewer starts
+    pub msaa: u16,
+    /// Directory of the last imported file
+    pub import_directory: Option<PathBuf>,
+    /// Directory of the last exported file
+    pub export_directory: Option<PathBuf>,
+    // Tables are written after the values, TOML does not allow values after a table
+    pub keybindings: Keybindings,
+    pub camera: Camera,
+    pub layout: Layout,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    Dark,
+    Light,
+    Classic,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Keybindings {
+    pub quit: VirtualKeyCode,
+    /// Removes the points of the scene
+    pub clear_points: VirtualKeyCode,
+    /// Gives the input focus to the next viewport
+    pub next_viewport: VirtualKeyCode,
+}
+
+/// Sensitivities of the orbit cameras of the 3D algorithms.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Camera {
+    /// Radians per pixel of mouse motion
+    pub rotation: f32,
+    /// Distance per line of mouse wheel scroll
+    pub zoom: f32,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Layout {
+    pub viewports: usize,
+    /// Logical size of the main window
+    pub window_size: [f64; 2],
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            theme: Theme::Dark,
+            msaa: 0,
+            import_directory: None,
+            export_directory: None,
+            keybindings: Keybindings::default(),
+            camera: Camera::default(),
+            layout: Layout::default(),
+        }
+    }
+}
+
+impl Default for Keybindings {
+    fn default() -> Self {
+        Self {
+            quit: VirtualKeyCode::Escape,
+            clear_points: VirtualKeyCode::Delete,
+            next_viewport: VirtualKeyCode::Tab,
+        }
+    }
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self {
+            rotation: 0.01,
+            zoom: 1.0,
+        }
+    }
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self {
+            viewports: 1,
+            window_size: [1024.0, 768.0],
+        }
+    }
+}
+
+impl Theme {
+    pub const ALL: [Theme; 3] = [ Theme::Dark, Theme::Light, Theme::Classic ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Theme::Dark => "Dark",
+            Theme::Light => "Light",
+            Theme::Classic => "Classic",
+        }
+    }
+
+    /// Sets the colors of the ImGui windows.
+    pub fn apply(self, style: &mut imgui::Style) {
+        match self {
+            Theme::Dark => style.use_dark_colors(),
+            Theme::Light => style.use_light_colors(),
+            Theme::Classic => style.use_classic_colors(),
+        };
+    }
+}
+
+impl Settings {
+    /// Reads the settings file, the default settings are returned if it does not exist yet.
+    /// Missing entries take their default value so that files written by older versions still load.
+    pub fn load() -> Result<Self> {
+        let path = match path() {
+            Some(path) if path.exists() => path,
+            _ => return Ok(Self::default()),
+        };
+        let mut settings: Self = toml::from_str(&fs::read_to_string(&path)?)?;
+        log::info!("Loaded settings from {}", path.display());
+
+        if settings.msaa != 0 && !settings.msaa.is_power_of_two() {
+            log::warn!("Ignoring antialiasing with {} samples, it must be a power of two", settings.msaa);
+            settings.msaa = 0;
+        }
+        Ok(settings)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if let Some(path) = path() {
+            fs::write(&path, toml::to_string_pretty(self)?)?;
+            log::info!("Saved settings to {}", path.display());
+        }
+        Ok(())
+    }
+}
+
+/// Returns the directory in which the viewer saves its files, creating it if needed.
+pub fn config_dir() -> Option<PathBuf> {
+    let dir = dirs::config_dir()?.join("computational-geometry");
+    fs::create_dir_all(&dir).ok()?;
+    Some(dir)
+}
+
+fn path() -> Option<PathBuf> {
+    Some(config_dir()?.join("settings.toml"))
+}
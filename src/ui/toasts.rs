@@ -0,0 +1,69 @@
+use crate::graphics;
+
+use std::{
+    collections::VecDeque,
+    time::{ Duration, Instant },
+};
+
+use imgui::{ Ui, Condition, ImString };
+
+/// How long a notification stays on screen.
+const DURATION: Duration = Duration::from_secs(5);
+/// Maximum number of notifications shown at the same time, older ones are discarded first.
+const MAX_TOASTS: usize = 5;
+
+struct Toast {
+    message: String,
+    shown_at: Instant,
+}
+
+/// Error notifications stacked in the bottom right corner of the window, they disappear after a few seconds.
+#[derive(Default)]
+pub struct Toasts {
+    toasts: VecDeque<Toast>,
+}
+
+impl Toasts {
+    /// Shows an error to the user and logs it.
+    /// An error repeated every frame, e.g. a draw failure, only refreshes the last notification.
+    pub fn push(&mut self, message: String) {
+        if let Some(last) = self.toasts.back_mut() {
+            if last.message == message {
+                last.shown_at = Instant::now();
+                return;
+            }
+        }
+
+        log::error!("{}", message);
+        if self.toasts.len() >= MAX_TOASTS {
+            self.toasts.pop_front();
+        }
+        self.toasts.push_back(Toast {
+            message,
+            shown_at: Instant::now(),
+        });
+    }
+
+    pub fn draw(&mut self, ui: &Ui, window: &winit::window::Window) {
+        self.toasts.retain(|toast| toast.shown_at.elapsed() < DURATION);
+
+        let win_size = graphics::window_size(window);
+        let mut y = win_size.height as f32 - 16.0;
+        for (idx, toast) in self.toasts.iter().enumerate() {
+            let name = ImString::new(format!("##toast_{}", idx));
+            imgui::Window::new(&name)
+                        .title_bar(false)
+                        .resizable(false)
+                        .movable(false)
+                        .always_auto_resize(true)
+                        .save_settings(false)
+                        .focus_on_appearing(false)
+                        .position([win_size.width as f32 - 16.0, y], Condition::Always)
+                        .position_pivot([1.0, 1.0])
+                        .build(ui, || {
+                            ui.text_colored([1.0, 0.3, 0.3, 1.0], ImString::new(toast.message.as_str()));
+                            y -= ui.window_size()[1] + 8.0;
+                        });
+        }
+    }
+}
@@ -1,6 +1,13 @@
 use super::Window;
 
-use crate::algorithms::*;
+use crate::{
+    Result,
+    algorithms::{ Degeneracy, general_position },
+    renderers::*,
+    graphics::Viewport,
+    scene::SharedScene,
+    ui::{ SharedSettings, manager::Layout },
+};
 
 use glium::{
     Frame,
@@ -9,8 +16,13 @@ use glium::{
 use imgui::{ im_str, Ui, Io, Condition, ImString, ImStr };
 
 pub trait Drawable {
-    fn draw(&self, target: &mut Frame);
-    fn handle_events(&mut self, _window: &winit::Window, _event: &winit::Event, _io: &Io) { }
+    /// Called every frame before [`draw()`](#tymethod.draw), e.g. to run the algorithm again when the scene changed
+    /// or to retrieve the results of background computations.
+    fn update(&mut self) -> Result<()> { Ok(()) }
+    /// Renders to the region of `target` covered by `viewport`.
+    fn draw(&self, target: &mut Frame, viewport: &Viewport) -> Result<()>;
+    /// Only called for the algorithm of the focused viewport.
+    fn handle_events(&mut self, _window: &winit::window::Window, _event: &winit::event::Event<()>, _io: &Io, _viewport: &Viewport) -> Result<()> { Ok(()) }
 }
 
 pub trait Configurable {
@@ -18,47 +30,225 @@ pub trait Configurable {
     fn name(&self) -> &'static str;
     /// Called by the window to configure the algorithm when it is selected,
     /// use the passed `ui` object to display an algorithm-specific user interface.
-    fn configure(&mut self, _ui: &Ui) { }
+    fn configure(&mut self, _ui: &Ui) -> Result<()> { Ok(()) }
+    /// A short explanation of how the algorithm works, shown in the "About this algorithm" section.
+    fn description(&self) -> Option<&'static str> { None }
+    /// The steps of the algorithm written as pseudo-code.
+    fn pseudo_code(&self) -> Option<&'static str> { None }
+    /// The asymptotic time complexity of the algorithm.
+    fn complexity(&self) -> Option<&'static str> { None }
+    /// The configurations of the points of the scene that the algorithm does not handle, or handles specially,
+    /// the input analysis warns about them.
+    fn degeneracies(&self) -> &'static [Degeneracy] { &[] }
+}
+
+/// Above this number of points, the input analysis does not look for cocircular points, which takes O(n³ log n)
+const MAX_COCIRCULAR_POINTS: usize = 200;
+
+/// Degenerate configurations found in the points of the scene.
+struct InputAnalysis {
+    /// Revision of the scene the points were analyzed from
+    revision: u64,
+    points: usize,
+    convex_position: bool,
+    collinear: Option<[usize; 3]>,
+    /// `None` if there are too many points to look for them
+    cocircular: Option<Option<[usize; 4]>>,
+}
+
+impl InputAnalysis {
+    fn new(scene: &SharedScene) -> Self {
+        let scene = scene.borrow();
+        let points = scene.points();
+        Self {
+            revision: scene.revision(),
+            points: points.len(),
+            convex_position: general_position::is_in_convex_position(points),
+            collinear: general_position::collinear_triple(points),
+            cocircular: if points.len() <= MAX_COCIRCULAR_POINTS {
+                Some(general_position::cocircular_quadruple(points))
+            } else {
+                None
+            },
+        }
+    }
+
+    /// Returns the warning about `degeneracy` if the points have it.
+    fn warning(&self, degeneracy: Degeneracy) -> Option<ImString> {
+        match degeneracy {
+            Degeneracy::CollinearPoints => self.collinear.map(|[ a, b, c ]| {
+                im_str!("Points {}, {} and {} are collinear", a, b, c)
+            }),
+            Degeneracy::CocircularPoints => match self.cocircular {
+                Some(Some([ a, b, c, d ])) => Some(im_str!("Points {}, {}, {} and {} are cocircular", a, b, c, d)),
+                _ => None,
+            },
+        }
+    }
 }
 
 trait A: Drawable + Configurable { }
 impl<T> A for T where T: Drawable + Configurable { }
 
-/// A window containing a dropdown list of all available algorithms.
-/// The currently selected algorithm will be rendered to the window.
+/// A window containing a dropdown list of all available algorithms for each viewport.
+/// The algorithm selected for a viewport will be rendered to it,
+/// and the one of the focused viewport can be configured.
 pub struct Algorithms<'f> {
     algs: Vec<Box<dyn A + 'f>>,
-    selected: usize,
+    /// Index of the algorithm selected for each viewport
+    selected: Vec<usize>,
+    opened: bool,
+    scene: SharedScene,
+    analysis: Option<InputAnalysis>,
 }
 
 impl<'f> Algorithms<'f> {
-    pub fn new(facade: &'f dyn Facade) -> Self {
-        Self {
+    /// All the algorithms run on the geometry of `scene`.
+    pub fn new(facade: &'f dyn Facade, scene: &SharedScene, settings: &SharedSettings) -> Result<Self> {
+        Ok(Self {
             algs: vec![
-                Box::new(JarvisMarch::new(facade)),
-                Box::new(GrahamScan::new(facade)),
-                Box::new(Incremental2dTriangulation::new(facade)),
-                Box::new(ConvexHull::new(facade)),
+                Box::new(JarvisMarchRenderer::new(facade, scene.clone())?),
+                Box::new(GrahamScanRenderer::new(facade, scene.clone())?),
+                Box::new(Incremental2dTriangulationRenderer::new(facade, scene.clone())?),
+                Box::new(RotatingCalipersRenderer::new(facade, scene.clone())?),
+                Box::new(VoronoiRenderer::new(facade, scene.clone())?),
+                Box::new(FarthestPointVoronoiRenderer::new(facade, scene.clone())?),
+                Box::new(OrderKVoronoiRenderer::new(facade, scene.clone())?),
+                Box::new(StipplingRenderer::new(facade, scene.clone(), settings.clone())?),
+                Box::new(LargestInscribedCircleRenderer::new(facade, scene.clone())?),
+                Box::new(ArrangementRenderer::new(facade, scene.clone())?),
+                Box::new(SegmentIntersectionsRenderer::new(facade, scene.clone())?),
+                Box::new(CirclePackingRenderer::new(facade, scene.clone())?),
+                Box::new(DualityRenderer::new(facade, scene.clone())?),
+                Box::new(NearestNeighbourRenderer::new(facade, scene.clone())?),
+                Box::new(BvhRenderer::new(facade, scene.clone())?),
+                Box::new(CollisionRenderer::new(facade)?),
+                Box::new(ParticlesRenderer::new(facade)?),
+                Box::new(FlockingRenderer::new(facade)?),
+                Box::new(PcaRenderer::new(facade, scene.clone())?),
+                Box::new(MinimumLinkPathRenderer::new(facade, scene.clone())?),
+                Box::new(ShortestPathRenderer::new(facade, scene.clone())?),
+                Box::new(PolygonTriangulationRenderer::new(facade, scene.clone())?),
+                Box::new(PathfindingRenderer::new(facade, scene.clone())?),
+                Box::new(MorphingRenderer::new(facade, scene.clone())?),
+                Box::new(OffsetRenderer::new(facade, scene.clone())?),
+                Box::new(MedialAxisRenderer::new(facade, scene.clone())?),
+                Box::new(DistanceFieldRenderer::new(facade, scene.clone(), settings.clone())?),
+                Box::new(TerrainContoursRenderer::new(facade, scene.clone())?),
+                Box::new(VisibilityRenderer::new(facade, scene.clone())?),
+                Box::new(RayCastingRenderer::new(facade, scene.clone())?),
+                Box::new(LinearProgrammingRenderer::new(facade)?),
+                Box::new(IcpRenderer::new(facade, scene.clone())?),
+                Box::new(ClusteringRenderer::new(facade, scene.clone())?),
+                Box::new(DataDepthRenderer::new(facade, scene.clone())?),
+                Box::new(RefinementRenderer::new(facade, scene.clone())?),
+                Box::new(ConvexHullRenderer::new(facade, scene.clone(), settings.clone())?),
+                Box::new(Delaunay3dRenderer::new(facade, scene.clone(), settings.clone())?),
+                Box::new(LiftingMapRenderer::new(facade, scene.clone())?),
+                Box::new(ConvexHull4dRenderer::new(facade)?),
             ],
-            selected: 0,
+            selected: vec![0],
+            opened: true,
+            scene: scene.clone(),
+            analysis: None,
+        })
+    }
+}
+
+impl<'f> Algorithms<'f> {
+    /// Warns about the degenerate configurations of the points of the scene that matter to the selected algorithm,
+    /// and displays a collapsible section with the whole analysis.
+    fn input_analysis(ui: &Ui, alg: &dyn A, analysis: &InputAnalysis) {
+        let degeneracies = alg.degeneracies();
+        let warning_color = [ 1.0, 0.6, 0.0, 1.0 ];
+        for &degeneracy in degeneracies {
+            if let Some(warning) = analysis.warning(degeneracy) {
+                ui.text_colored(warning_color, &warning);
+            }
+        }
+
+        if ui.collapsing_header(im_str!("Input analysis")).build() {
+            ui.text(im_str!("{} points", analysis.points));
+            ui.text(if analysis.convex_position { im_str!("In convex position") } else { im_str!("Not in convex position") });
+            match analysis.collinear {
+                Some([ a, b, c ]) => ui.text(im_str!("Collinear points: {}, {} and {}", a, b, c)),
+                None => ui.text(im_str!("No collinear points")),
+            }
+            match analysis.cocircular {
+                Some(Some([ a, b, c, d ])) => ui.text(im_str!("Cocircular points: {}, {}, {} and {}", a, b, c, d)),
+                Some(None) => ui.text(im_str!("No cocircular points")),
+                None => ui.text_disabled(im_str!("Too many points to look for cocircular ones")),
+            }
+            if degeneracies.is_empty() {
+                ui.text_disabled(im_str!("The algorithm handles every configuration"));
+            } else if degeneracies.iter().all(|&degeneracy| analysis.warning(degeneracy).is_none()) {
+                ui.text(im_str!("No degenerate configuration for this algorithm"));
+            }
+        }
+    }
+
+    /// Displays a collapsible section that explains the selected algorithm.
+    fn about(ui: &Ui, alg: &dyn A) {
+        if alg.description().is_none() && alg.pseudo_code().is_none() && alg.complexity().is_none() {
+            return;
+        }
+
+        if ui.collapsing_header(im_str!("About this algorithm")).build() {
+            if let Some(description) = alg.description() {
+                ui.text_wrapped(&ImString::new(description));
+            }
+            if let Some(pseudo_code) = alg.pseudo_code() {
+                ui.separator();
+                ui.text(ImString::new(pseudo_code));
+            }
+            if let Some(complexity) = alg.complexity() {
+                ui.separator();
+                ui.text(im_str!("Complexity: {}", complexity));
+            }
         }
     }
 }
 
 impl<'f> Window for Algorithms<'f> {
-    fn draw(&mut self, target: &mut glium::Frame, ui: &Ui, _window: &winit::Window) {
+    fn name(&self) -> &'static str {
+        "Algorithms"
+    }
+
+    fn opened(&mut self) -> &mut bool {
+        &mut self.opened
+    }
+
+    fn draw(&mut self, target: &mut glium::Frame, ui: &Ui, _window: &winit::window::Window, layout: &Layout) -> Result<()> {
         if self.algs.is_empty() {
-            return;
+            return Ok(());
         }
 
-        // Draw the selected algorithm
-        self.algs[self.selected].draw(target);
+        // New viewports show the algorithms that are not displayed yet
+        let algs_count = self.algs.len();
+        while self.selected.len() < layout.count() {
+            let next = self.selected.len() % algs_count;
+            self.selected.push(next);
+        }
+        self.selected.truncate(layout.count());
 
+        // Draw the selected algorithms, even when the configuration window is hidden
+        for (viewport, &selected) in layout.viewports().iter().zip(self.selected.iter()) {
+            self.algs[selected].update()?;
+            self.algs[selected].draw(target, viewport)?;
+        }
+        if !self.opened {
+            return Ok(());
+        }
+        let focused = layout.focused();
+
+        let mut result = Ok(());
+        let mut opened = self.opened;
         imgui::Window::new(im_str!("Algorithms"))
+                    .opened(&mut opened)
                     .resizable(true)
                     .size([220.0, 150.0], Condition::FirstUseEver)
-                    .position([16.0, 16.0], Condition::FirstUseEver)
-                    .build(&ui, || {
+                    .position([16.0, 32.0], Condition::FirstUseEver)
+                    .build(ui, || {
                         // Create a vector containing the names of the available algorithms
                         let items = self.algs
                                             .iter()
@@ -67,17 +257,43 @@ impl<'f> Window for Algorithms<'f> {
                         let items = items.iter()
                                             .map(|s| s.as_ref())
                                             .collect::<Vec<&ImStr>>();
-                        imgui::ComboBox::new(im_str!(""))
-                                    .build_simple_string(ui, &mut self.selected, &items[..]);
+                        if self.selected.len() == 1 {
+                            imgui::ComboBox::new(im_str!(""))
+                                        .build_simple_string(ui, &mut self.selected[0], &items[..]);
+                        } else {
+                            for (idx, selected) in self.selected.iter_mut().enumerate() {
+                                let label = ImString::new(format!("Viewport {}", idx + 1));
+                                imgui::ComboBox::new(&label)
+                                            .build_simple_string(ui, selected, &items[..]);
+                            }
+                            ui.text(im_str!("Configuring viewport {} (click a viewport to focus it)", focused + 1));
+                        }
 
                         for _ in 0..3 {
                             ui.spacing();
                         }
-                        self.algs[self.selected].configure(ui);
+                        let selected = self.selected[focused];
+                        result = self.algs[selected].configure(ui);
+
+                        ui.spacing();
+                        let revision = self.scene.borrow().revision();
+                        if self.analysis.as_ref().map(|analysis| analysis.revision) != Some(revision) {
+                            self.analysis = Some(InputAnalysis::new(&self.scene));
+                        }
+                        if let Some(analysis) = &self.analysis {
+                            Self::input_analysis(ui, &*self.algs[selected], analysis);
+                        }
+                        Self::about(ui, &*self.algs[selected]);
                     });
+        self.opened = opened;
+        result
     }
 
-    fn handle_events(&mut self, window: &winit::Window, event: &winit::Event, io: &Io) {
-        self.algs[self.selected].handle_events(window, event, io);
+    fn handle_events(&mut self, window: &winit::window::Window, event: &winit::event::Event<()>, io: &Io, layout: &Layout) -> Result<()> {
+        let focused = layout.focused();
+        if let (Some(&selected), Some(viewport)) = (self.selected.get(focused), layout.viewports().get(focused)) {
+            self.algs[selected].handle_events(window, event, io, viewport)?;
+        }
+        Ok(())
     }
 }
@@ -0,0 +1,166 @@
+use super::Window;
+use crate::{ Result, graphics, ui::manager::Layout };
+
+use std::sync::{ Arc, Mutex };
+
+use imgui::{ im_str, Ui, Condition, ImString, ImStr };
+use log::{ Level, LevelFilter, Log, Metadata, Record };
+
+/// Maximum number of messages kept by the console, older ones are discarded first.
+const MAX_ENTRIES: usize = 1000;
+
+const LEVELS: [Level; 5] = [ Level::Error, Level::Warn, Level::Info, Level::Debug, Level::Trace ];
+
+struct Entry {
+    level: Level,
+    target: String,
+    message: String,
+}
+
+/// A `log` backend that prints messages to the standard output
+/// and stores them so they can be displayed by the console window.
+struct Logger {
+    entries: Arc<Mutex<Vec<Entry>>>,
+}
+
+impl Log for Logger {
+    fn enabled(&self, _metadata: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        println!("[{}] {}: {}", record.level(), record.target(), record.args());
+        if let Ok(mut entries) = self.entries.lock() {
+            if entries.len() >= MAX_ENTRIES {
+                entries.remove(0);
+            }
+            entries.push(Entry {
+                level: record.level(),
+                target: record.target().to_owned(),
+                message: record.args().to_string(),
+            });
+        }
+    }
+
+    fn flush(&self) { }
+}
+
+/// A window that displays the messages sent through the `log` crate.
+pub struct Console {
+    entries: Arc<Mutex<Vec<Entry>>>,
+    /// Index in `LEVELS` of the most verbose level displayed
+    level: usize,
+    auto_scroll: bool,
+    opened: bool,
+}
+
+impl Console {
+    /// Creates the console and installs it as the global logger.
+    /// It should be created as early as possible so that no message is missed.
+    pub fn new() -> Self {
+        let entries = Arc::new(Mutex::new(Vec::new()));
+        let logger = Logger {
+            entries: Arc::clone(&entries),
+        };
+        if log::set_boxed_logger(Box::new(logger)).is_ok() {
+            log::set_max_level(LevelFilter::Debug);
+        }
+
+        Self {
+            entries,
+            level: 2, // Info
+            auto_scroll: true,
+            opened: true,
+        }
+    }
+
+    fn clear(&mut self) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.clear();
+        }
+    }
+
+    fn level_color(level: Level) -> [f32; 4] {
+        match level {
+            Level::Error => [ 1.0, 0.3, 0.3, 1.0 ],
+            Level::Warn => [ 1.0, 0.8, 0.2, 1.0 ],
+            Level::Info => [ 0.9, 0.9, 0.9, 1.0 ],
+            Level::Debug => [ 0.6, 0.6, 0.6, 1.0 ],
+            Level::Trace => [ 0.5, 0.5, 0.7, 1.0 ],
+        }
+    }
+}
+
+impl Default for Console {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Window for Console {
+    fn name(&self) -> &'static str {
+        "Console"
+    }
+
+    fn opened(&mut self) -> &mut bool {
+        &mut self.opened
+    }
+
+    fn draw(&mut self, _target: &mut glium::Frame, ui: &Ui, window: &winit::window::Window, _layout: &Layout) -> Result<()> {
+        if !self.opened {
+            return Ok(());
+        }
+        let win_size = graphics::window_size(window);
+
+        let mut opened = self.opened;
+        let mut clear = false;
+        imgui::Window::new(im_str!("Console"))
+                    .opened(&mut opened)
+                    .resizable(true)
+                    .size([500.0, 180.0], Condition::FirstUseEver)
+                    .position([16.0, win_size.height as f32 - 16.0], Condition::FirstUseEver)
+                    .position_pivot([0.0, 1.0])
+                    .build(ui, || {
+                        let items = LEVELS.iter()
+                                            .map(|level| ImString::new(level.to_string()))
+                                            .collect::<Vec<_>>();
+                        let items = items.iter()
+                                            .map(|s| s.as_ref())
+                                            .collect::<Vec<&ImStr>>();
+                        imgui::ComboBox::new(im_str!("Level"))
+                                    .build_simple_string(ui, &mut self.level, &items[..]);
+                        ui.same_line(0.0);
+                        ui.checkbox(im_str!("Auto-scroll"), &mut self.auto_scroll);
+                        ui.same_line(0.0);
+                        clear = ui.button(im_str!("Clear"), [0.0, 0.0]);
+                        ui.separator();
+
+                        let max_level = LEVELS[self.level];
+                        let auto_scroll = self.auto_scroll;
+                        let entries = &self.entries;
+                        imgui::ChildWindow::new(im_str!("Messages"))
+                                    .horizontal_scrollbar(true)
+                                    .build(ui, || {
+                                        if let Ok(entries) = entries.lock() {
+                                            for entry in entries.iter().filter(|e| e.level <= max_level) {
+                                                ui.text_colored(Self::level_color(entry.level),
+                                                                im_str!("[{}] {}: {}", entry.level, entry.target, entry.message));
+                                            }
+                                        }
+                                        if auto_scroll && ui.scroll_y() >= ui.scroll_max_y() {
+                                            ui.set_scroll_here_y();
+                                        }
+                                    });
+                    });
+        self.opened = opened;
+
+        if clear {
+            self.clear();
+        }
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
 use super::Window;
+use crate::{ Result, graphics, ui::manager::Layout };
 
 use std::time::{ Instant, Duration };
 
@@ -11,6 +12,7 @@ pub struct Debug {
     delta_time: Duration,
     frames_count: u32,
     framerate: u32,
+    opened: bool,
 }
 
 impl Debug {
@@ -41,26 +43,38 @@ impl Default for Debug {
             delta_time: Duration::default(),
             frames_count: 0,
             framerate: 0,
+            opened: true,
         }
     }
 }
 
 impl Window for Debug {
-    fn draw(&mut self, _target: &mut glium::Frame, ui: &Ui, window: &winit::Window) {
-        let win_size = match window.get_inner_size() {
-            Some(dim) => dim,
-            None => return,
-        };
+    fn name(&self) -> &'static str {
+        "Debug"
+    }
+
+    fn opened(&mut self) -> &mut bool {
+        &mut self.opened
+    }
+
+    fn draw(&mut self, _target: &mut glium::Frame, ui: &Ui, window: &winit::window::Window, _layout: &Layout) -> Result<()> {
+        let win_size = graphics::window_size(window);
 
         self.update_framerate();
+        if !self.opened {
+            return Ok(());
+        }
 
+        let framerate = self.framerate;
         imgui::Window::new(im_str!("Debug"))
+                    .opened(&mut self.opened)
                     .resizable(false)
                     .always_auto_resize(true)
-                    .position([win_size.width as f32 - 32.0, 16.0], Condition::FirstUseEver)
+                    .position([win_size.width as f32 - 32.0, 32.0], Condition::FirstUseEver)
                     .position_pivot([1.0, 0.0])
-                    .build(&ui, || {
-                        ui.text(im_str!("Framerate: {} fps", self.framerate));
+                    .build(ui, || {
+                        ui.text(im_str!("Framerate: {} fps", framerate));
                     });
+        Ok(())
     }
 }
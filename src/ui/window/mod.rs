@@ -1,10 +1,27 @@
+use super::manager::Layout;
+use crate::Result;
+
 pub mod debug;
 pub use debug::Debug;
 
 pub mod algorithms;
 pub use algorithms::Algorithms;
 
+pub mod console;
+pub use console::Console;
+
+pub mod scene;
+pub use scene::Scene;
+
+pub mod settings;
+pub use settings::Settings;
+
 pub trait Window {
-    fn draw(&mut self, target: &mut glium::Frame, ui: &imgui::Ui, window: &winit::Window);
-    fn handle_events(&mut self, _window: &winit::Window, _event: &winit::Event, _io: &imgui::Io) { }
+    /// The title of the window, also used in the View menu.
+    fn name(&self) -> &'static str;
+    /// Whether the window is shown, the View menu toggles it.
+    fn opened(&mut self) -> &mut bool;
+    /// Errors are shown to the user as notifications by the manager.
+    fn draw(&mut self, target: &mut glium::Frame, ui: &imgui::Ui, window: &winit::window::Window, layout: &Layout) -> Result<()>;
+    fn handle_events(&mut self, _window: &winit::window::Window, _event: &winit::event::Event<()>, _io: &imgui::Io, _layout: &Layout) -> Result<()> { Ok(()) }
 }
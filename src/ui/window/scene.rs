@@ -0,0 +1,332 @@
+use super::Window;
+use crate::{
+    Result, graphics, image, io, point_cloud,
+    algorithms::{ Bounded, trace_contours },
+    math::{ Vec2, Rect, Polyline },
+    scene::{ Mesh, SharedScene },
+    ui::{ SharedSettings, manager::Layout },
+    wkt::{ self, Geometry },
+};
+
+use std::{
+    fs::File,
+    io::{ BufReader, BufWriter },
+    path::{ Path, PathBuf },
+};
+
+use imgui::{ im_str, Ui, Condition, ImString };
+
+/// A window that shows the content of the scene, imports point sets into it and exports its results.
+pub struct Scene {
+    scene: SharedScene,
+    /// Remembers the directories of the last imported and exported files
+    settings: SharedSettings,
+    /// Path of the file to import or export
+    path: ImString,
+    /// Well-Known Text pasted by the user or copied from the scene
+    wkt: ImString,
+    /// Imported point clouds are decimated to this number of points, 0 keeps all of them
+    max_points: i32,
+    /// Tolerance of the simplification of the shapes traced in images, in pixels
+    simplify: f32,
+    /// Index of the point whose value is edited
+    point: i32,
+    value: f32,
+    opened: bool,
+}
+
+impl Scene {
+    pub fn new(scene: SharedScene, settings: SharedSettings) -> Self {
+        // Start in the directory of the last imported file
+        let mut path = ImString::with_capacity(256);
+        if let Some(dir) = &settings.borrow().import_directory {
+            path.push_str(&dir.join("").to_string_lossy());
+        }
+
+        Self {
+            scene,
+            settings,
+            path,
+            wkt: ImString::with_capacity(4096),
+            max_points: 5000,
+            simplify: 1.0,
+            point: 0,
+            value: 0.0,
+            opened: true,
+        }
+    }
+
+    /// Replaces the points of the scene with those of a CSV file, with their values if it has a third column.
+    fn import_points(&self, path: &str) -> Result<()> {
+        let (points, values) = io::read_points_values_csv(BufReader::new(File::open(path)?))?;
+        log::info!("Read {} points from {}", points.len(), path);
+        // The 2D views show the [-1, 1] square, without a camera to frame the points
+        if let Some(bounds) = points.bounding_rect() {
+            let view = Rect::new(Vec2::new(-1.0, -1.0), Vec2::new(1.0, 1.0));
+            if !view.contains_rect(&bounds) {
+                log::warn!("The points span x from {} to {} and y from {} to {}, beyond the [-1, 1] view",
+                           bounds.min.x, bounds.max.x, bounds.min.y, bounds.max.y);
+            }
+        }
+        self.scene.borrow_mut().set_points_with_values(points, values);
+        self.settings.borrow_mut().import_directory = directory(path);
+        Ok(())
+    }
+
+    /// Replaces the meshes of the scene with the point cloud of a LAS or XYZ file,
+    /// decimated to `max_points` and scaled to fit the view of the 3D algorithms.
+    fn import_point_cloud(&self, path: &str) -> Result<()> {
+        let mut points = point_cloud::read(Path::new(path))?;
+        let count = points.len();
+        if self.max_points > 0 {
+            points = point_cloud::decimate(&points, self.max_points as usize);
+        }
+        log::info!("Read {} points from {}, kept {}", count, path, points.len());
+        point_cloud::fit(&mut points, 2.0);
+
+        let mut scene = self.scene.borrow_mut();
+        scene.clear_meshes();
+        scene.add_mesh(Mesh { vertices: points, indices: Vec::new() });
+        drop(scene);
+        self.settings.borrow_mut().import_directory = directory(path);
+        Ok(())
+    }
+
+    /// Adds the polygons traced around the dark regions of a PGM or PBM image to the scene,
+    /// simplified and scaled to fit the view. Their holes are left out, the scene has none.
+    fn import_image_shapes(&self, path: &str) -> Result<()> {
+        let img = image::read(Path::new(path))?;
+        let regions = trace_contours(&img.threshold(128), img.width, img.height);
+
+        // The image is centered in the view, its longest side spanning 90% of it
+        let center = Vec2::new(img.width as f32 / 2.0, img.height as f32 / 2.0);
+        let scale = 1.8 / img.width.max(img.height).max(1) as f32;
+        let to_view = |ring: Vec<Vec2>| -> Vec<Vec2> {
+            Polyline::new(ring, true).simplified(self.simplify.max(0.0))
+                                     .points
+                                     .iter()
+                                     .map(|p| scale * &(p - &center))
+                                     .collect()
+        };
+        let polygons = regions
+            .into_iter()
+            .map(|region| to_view(region.outer))
+            .filter(|outer| outer.len() >= 3)
+            .map(|outer| vec![ outer ])
+            .collect::<Vec<_>>();
+        log::info!("Traced {} shapes in the {}x{} image {}", polygons.len(), img.width, img.height, path);
+
+        self.scene.borrow_mut().add_geometry(Geometry::MultiPolygon(polygons));
+        self.settings.borrow_mut().import_directory = directory(path);
+        Ok(())
+    }
+
+    /// Writes the points of the scene, with their values if any is set.
+    fn export_points(&self, path: &str) -> Result<()> {
+        let scene = self.scene.borrow();
+        let writer = BufWriter::new(File::create(path)?);
+        if scene.values().iter().any(|&value| value != 0.0) {
+            io::write_points_values_csv(writer, scene.points(), scene.values())
+        } else {
+            io::write_points_csv(writer, scene.points())
+        }
+    }
+
+    fn export_hull(&self, path: &str) -> Result<()> {
+        let scene = self.scene.borrow();
+        io::write_points_csv(BufWriter::new(File::create(path)?), scene.hull())
+    }
+
+    /// Adds the geometry written in the WKT text box to the scene.
+    fn add_wkt(&self) -> Result<()> {
+        let geometry = wkt::parse(self.wkt.to_str())?;
+        self.scene.borrow_mut().add_geometry(geometry);
+        Ok(())
+    }
+
+    /// Shows `geometry` as WKT in the text box and copies it to the clipboard.
+    fn copy_wkt(&mut self, ui: &Ui, geometry: Geometry) {
+        self.wkt = ImString::new(geometry.to_string());
+        self.wkt.reserve(4096); // The text box cannot grow past the capacity
+        ui.set_clipboard_text(&self.wkt);
+    }
+
+    /// Writes the triangulation as JSON if the path ends with `.json`, as CSV otherwise.
+    fn export_triangulation(&self, path: &str) -> Result<()> {
+        let scene = self.scene.borrow();
+        let triangulation = match scene.triangulation() {
+            Some(triangulation) => triangulation,
+            None => {
+                log::warn!("No triangulation to export, run the triangulation first");
+                return Ok(());
+            },
+        };
+
+        let writer = BufWriter::new(File::create(path)?);
+        if path.ends_with(".json") {
+            io::write_triangulation_json(writer, &triangulation.points, &triangulation.indices)
+        } else {
+            io::write_triangles_csv(writer, &triangulation.points, &triangulation.indices)
+        }
+    }
+}
+
+impl Window for Scene {
+    fn name(&self) -> &'static str {
+        "Scene"
+    }
+
+    fn opened(&mut self) -> &mut bool {
+        &mut self.opened
+    }
+
+    fn draw(&mut self, _target: &mut glium::Frame, ui: &Ui, window: &winit::window::Window, _layout: &Layout) -> Result<()> {
+        if !self.opened {
+            return Ok(());
+        }
+        let win_size = graphics::window_size(window);
+
+        let mut opened = self.opened;
+        let mut import = false;
+        let mut import_cloud = false;
+        let mut import_image = false;
+        let mut export_points = false;
+        let mut export_hull = false;
+        let mut export_triangulation = false;
+        let mut clear = false;
+        let mut add_wkt = false;
+        let mut set_value = false;
+        let mut copy = None;
+        let scene = self.scene.clone();
+        let path = &mut self.path;
+        let wkt = &mut self.wkt;
+        let max_points = &mut self.max_points;
+        let simplify = &mut self.simplify;
+        let point = &mut self.point;
+        let value = &mut self.value;
+        imgui::Window::new(im_str!("Scene"))
+                    .opened(&mut opened)
+                    .resizable(false)
+                    .always_auto_resize(true)
+                    .position([win_size.width as f32 - 32.0, 96.0], Condition::FirstUseEver)
+                    .position_pivot([1.0, 0.0])
+                    .build(ui, || {
+                        let scene = scene.borrow();
+                        ui.text(im_str!("{} points, {} polygons, {} segments, {} meshes",
+                                        scene.points().len(), scene.polygons().len(),
+                                        scene.segments().len(), scene.meshes().len()));
+                        ui.text(im_str!("Hull: {} points", scene.hull().len()));
+                        let triangles = scene.triangulation().map_or(0, |t| t.indices.len() / 3);
+                        ui.text(im_str!("Triangulation: {} triangles", triangles));
+                        ui.separator();
+
+                        ui.input_text(im_str!("Path"), path).build();
+                        import = ui.button(im_str!("Import Points"), [0.0, 0.0]);
+                        ui.same_line(0.0);
+                        export_points = ui.button(im_str!("Export Points"), [0.0, 0.0]);
+                        export_hull = ui.button(im_str!("Export Hull"), [0.0, 0.0]);
+                        ui.same_line(0.0);
+                        export_triangulation = ui.button(im_str!("Export Triangulation"), [0.0, 0.0]);
+                        import_cloud = ui.button(im_str!("Import Point Cloud"), [0.0, 0.0]);
+                        ui.same_line(0.0);
+                        ui.set_next_item_width(100.0);
+                        ui.input_int(im_str!("Max points"), max_points).step(1000).build();
+                        *max_points = (*max_points).max(0);
+                        ui.text_disabled(im_str!("LAS or XYZ, replaces the meshes"));
+                        import_image = ui.button(im_str!("Import Image Shapes"), [0.0, 0.0]);
+                        ui.same_line(0.0);
+                        ui.set_next_item_width(100.0);
+                        ui.input_float(im_str!("Simplify"), simplify).build();
+                        *simplify = (*simplify).max(0.0);
+                        ui.text_disabled(im_str!("PGM or PBM, the dark pixels are traced"));
+                        ui.separator();
+
+                        // Values of the points, e.g. heights
+                        match crate::graphics::colormap::range(scene.values()) {
+                            Some((min, max)) => ui.text(im_str!("Values from {} to {}", min, max)),
+                            None => ui.text(im_str!("Values: no points")),
+                        }
+                        ui.set_next_item_width(100.0);
+                        if ui.input_int(im_str!("Point"), point).build() {
+                            *point = (*point).max(0);
+                            if let Some(&current) = scene.values().get(*point as usize) {
+                                *value = current;
+                            }
+                        }
+                        ui.same_line(0.0);
+                        ui.set_next_item_width(100.0);
+                        ui.input_float(im_str!("Value"), value).build();
+                        ui.same_line(0.0);
+                        set_value = ui.button(im_str!("Set Value"), [0.0, 0.0]);
+                        ui.separator();
+
+                        ui.input_text_multiline(im_str!("WKT"), wkt, [300.0, 80.0]).build();
+                        add_wkt = ui.button(im_str!("Add to Scene"), [0.0, 0.0]);
+                        ui.text(im_str!("Copy as WKT:"));
+                        ui.same_line(0.0);
+                        if ui.button(im_str!("Points"), [0.0, 0.0]) {
+                            copy = Some(Geometry::MultiPoint(scene.points().to_vec()));
+                        }
+                        ui.same_line(0.0);
+                        if ui.button(im_str!("Hull"), [0.0, 0.0]) {
+                            let hull = scene.hull();
+                            let rings = if hull.is_empty() { Vec::new() } else { vec![ hull.to_vec() ] };
+                            copy = Some(Geometry::Polygon(rings));
+                        }
+                        ui.same_line(0.0);
+                        if ui.button(im_str!("Triangles"), [0.0, 0.0]) {
+                            copy = scene.triangulation().map(|t| wkt::triangles(&t.points, &t.indices));
+                        }
+                        ui.separator();
+                        clear = ui.button(im_str!("Clear"), [0.0, 0.0]);
+                    });
+        self.opened = opened;
+
+        let path = self.path.to_str().trim().to_owned();
+        if import {
+            self.import_points(&path)?;
+        }
+        if import_cloud {
+            self.import_point_cloud(&path)?;
+        }
+        if import_image {
+            self.import_image_shapes(&path)?;
+        }
+        if export_points || export_hull || export_triangulation {
+            if export_points {
+                self.export_points(&path)?;
+            }
+            if export_hull {
+                self.export_hull(&path)?;
+            }
+            if export_triangulation {
+                self.export_triangulation(&path)?;
+            }
+            self.settings.borrow_mut().export_directory = directory(&path);
+        }
+        if clear {
+            self.scene.borrow_mut().clear();
+        }
+        if add_wkt {
+            self.add_wkt()?;
+        }
+        if set_value {
+            let mut scene = self.scene.borrow_mut();
+            if (self.point as usize) < scene.points().len() {
+                scene.set_value(self.point as usize, self.value);
+            } else {
+                log::warn!("No point {}, there are {}", self.point, scene.points().len());
+            }
+        }
+        if let Some(geometry) = copy {
+            self.copy_wkt(ui, geometry);
+        }
+        Ok(())
+    }
+}
+
+/// Returns the absolute path of the directory containing the file at `path`.
+fn directory(path: &str) -> Option<PathBuf> {
+    let dir = Path::new(path).canonicalize().ok()?.parent()?.to_owned();
+    Some(dir)
+}
@@ -0,0 +1,139 @@
+use super::Window;
+use crate::{
+    Result, graphics,
+    ui::{ Settings as Config, SharedSettings, settings::Theme, manager::Layout },
+};
+
+use imgui::{ im_str, Ui, Condition, ImString, ImStr };
+use winit::event::VirtualKeyCode;
+
+/// Keys that can be bound to an action.
+const KEYS: [VirtualKeyCode; 30] = [
+    VirtualKeyCode::Escape, VirtualKeyCode::Delete, VirtualKeyCode::Back, VirtualKeyCode::Tab,
+    VirtualKeyCode::Space, VirtualKeyCode::Return, VirtualKeyCode::Insert, VirtualKeyCode::Home,
+    VirtualKeyCode::End, VirtualKeyCode::PageUp, VirtualKeyCode::PageDown,
+    VirtualKeyCode::F1, VirtualKeyCode::F2, VirtualKeyCode::F3, VirtualKeyCode::F4, VirtualKeyCode::F5,
+    VirtualKeyCode::F6, VirtualKeyCode::F7, VirtualKeyCode::F8, VirtualKeyCode::F9, VirtualKeyCode::F10,
+    VirtualKeyCode::F11, VirtualKeyCode::F12,
+    VirtualKeyCode::C, VirtualKeyCode::N, VirtualKeyCode::Q, VirtualKeyCode::R, VirtualKeyCode::V,
+    VirtualKeyCode::X, VirtualKeyCode::Z,
+];
+
+/// Samples per pixel that can be chosen for multisampling.
+const MSAA: [u16; 4] = [ 0, 2, 4, 8 ];
+
+/// A window that edits the settings of the viewer, they are saved when it exits or with the Save button.
+pub struct Settings {
+    settings: SharedSettings,
+    opened: bool,
+}
+
+impl Settings {
+    pub fn new(settings: SharedSettings) -> Self {
+        Self {
+            settings,
+            opened: false,
+        }
+    }
+
+    /// Displays a dropdown list of the bindable keys.
+    fn key_combo(ui: &Ui, label: &ImStr, key: &mut VirtualKeyCode) {
+        let items = KEYS.iter()
+                        .map(|key| ImString::new(format!("{:?}", key)))
+                        .collect::<Vec<_>>();
+        let items = items.iter()
+                        .map(|s| s.as_ref())
+                        .collect::<Vec<&ImStr>>();
+
+        let mut idx = KEYS.iter().position(|k| k == key).unwrap_or(0);
+        if imgui::ComboBox::new(label).build_simple_string(ui, &mut idx, &items[..]) {
+            *key = KEYS[idx];
+        }
+    }
+}
+
+impl Window for Settings {
+    fn name(&self) -> &'static str {
+        "Settings"
+    }
+
+    fn opened(&mut self) -> &mut bool {
+        &mut self.opened
+    }
+
+    fn draw(&mut self, _target: &mut glium::Frame, ui: &Ui, window: &winit::window::Window, _layout: &Layout) -> Result<()> {
+        if !self.opened {
+            return Ok(());
+        }
+        let win_size = graphics::window_size(window);
+
+        let mut opened = self.opened;
+        let mut save = false;
+        let mut settings = self.settings.borrow_mut();
+        imgui::Window::new(im_str!("Settings"))
+                    .opened(&mut opened)
+                    .resizable(false)
+                    .always_auto_resize(true)
+                    .position([win_size.width as f32 / 2.0, win_size.height as f32 / 2.0], Condition::FirstUseEver)
+                    .position_pivot([0.5, 0.5])
+                    .build(ui, || {
+                        let themes = Theme::ALL.iter()
+                                            .map(|theme| ImString::new(theme.name()))
+                                            .collect::<Vec<_>>();
+                        let themes = themes.iter()
+                                            .map(|s| s.as_ref())
+                                            .collect::<Vec<&ImStr>>();
+                        let mut theme = Theme::ALL.iter().position(|&t| t == settings.theme).unwrap_or(0);
+                        if imgui::ComboBox::new(im_str!("Theme")).build_simple_string(ui, &mut theme, &themes[..]) {
+                            settings.theme = Theme::ALL[theme];
+                        }
+
+                        let msaa = MSAA.iter()
+                                        .map(|&samples| match samples {
+                                            0 => ImString::new("Off"),
+                                            n => ImString::new(format!("{}x", n)),
+                                        })
+                                        .collect::<Vec<_>>();
+                        let msaa = msaa.iter()
+                                        .map(|s| s.as_ref())
+                                        .collect::<Vec<&ImStr>>();
+                        let mut samples = MSAA.iter().position(|&s| s == settings.msaa).unwrap_or(0);
+                        if imgui::ComboBox::new(im_str!("Antialiasing")).build_simple_string(ui, &mut samples, &msaa[..]) {
+                            settings.msaa = MSAA[samples];
+                        }
+                        ui.text_disabled(im_str!("Antialiasing is applied on restart"));
+
+                        ui.separator();
+                        ui.text(im_str!("3D camera"));
+                        ui.input_float(im_str!("Rotation"), &mut settings.camera.rotation).step(0.001).build();
+                        ui.input_float(im_str!("Zoom"), &mut settings.camera.zoom).step(0.1).build();
+                        settings.camera.rotation = settings.camera.rotation.max(0.0);
+                        settings.camera.zoom = settings.camera.zoom.max(0.0);
+
+                        ui.separator();
+                        ui.text(im_str!("Keybindings"));
+                        Self::key_combo(ui, im_str!("Quit"), &mut settings.keybindings.quit);
+                        Self::key_combo(ui, im_str!("Clear points"), &mut settings.keybindings.clear_points);
+                        Self::key_combo(ui, im_str!("Next viewport"), &mut settings.keybindings.next_viewport);
+
+                        ui.separator();
+                        save = ui.button(im_str!("Save"), [0.0, 0.0]);
+                        ui.same_line(0.0);
+                        if ui.button(im_str!("Reset to Defaults"), [0.0, 0.0]) {
+                            // The window layout and the directories are not preferences
+                            *settings = Config {
+                                import_directory: settings.import_directory.take(),
+                                export_directory: settings.export_directory.take(),
+                                layout: settings.layout.clone(),
+                                ..Config::default()
+                            };
+                        }
+                    });
+        self.opened = opened;
+
+        if save {
+            settings.save()?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,272 @@
+//! Well-Known Text reading and writing, to paste geometries from a database into the viewer and copy results back.
+//!
+//! Points, multipoints, linestrings, polygons and multipolygons are supported.
+//! Z and M coordinates are accepted and dropped. Rings are stored without the closing point,
+//! which is added back when writing.
+
+use crate::{ Error, Result, math::Vec2 };
+
+use std::fmt;
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum Geometry {
+    /// `None` for `POINT EMPTY`
+    Point(Option<Vec2>),
+    MultiPoint(Vec<Vec2>),
+    LineString(Vec<Vec2>),
+    /// The exterior ring followed by the interior rings
+    Polygon(Vec<Vec<Vec2>>),
+    MultiPolygon(Vec<Vec<Vec<Vec2>>>),
+}
+
+impl Geometry {
+    /// Returns all the vertices of the geometry.
+    pub fn points(&self) -> Vec<Vec2> {
+        match self {
+            Geometry::Point(p) => p.iter().cloned().collect(),
+            Geometry::MultiPoint(points) | Geometry::LineString(points) => points.clone(),
+            Geometry::Polygon(rings) => rings.concat(),
+            Geometry::MultiPolygon(polygons) => polygons.iter().flat_map(|rings| rings.concat()).collect(),
+        }
+    }
+}
+
+/// Parses a single WKT geometry, keywords are case insensitive.
+pub fn parse(text: &str) -> Result<Geometry> {
+    let mut parser = Parser { text, pos: 0 };
+    let geometry = parser.geometry()?;
+    parser.skip_whitespace();
+    if parser.pos < text.len() {
+        return Err(parser.error("end of text"));
+    }
+    Ok(geometry)
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    /// Byte offset of the next character to read
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, expected: &str) -> Error {
+        let found = self.text[self.pos..].chars().take(16).collect::<String>();
+        Error::Wkt(format!("expected {} at character {}, found \"{}\"", expected, self.pos + 1, found))
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.text[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.text[self.pos..].chars().next()
+    }
+
+    /// Consumes `c` if it is the next character.
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            return true;
+        }
+        false
+    }
+
+    fn expect(&mut self, c: char) -> Result<()> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("'{}'", c)))
+        }
+    }
+
+    /// Reads a keyword, returned in uppercase.
+    fn word(&mut self) -> String {
+        self.skip_whitespace();
+        let rest = &self.text[self.pos..];
+        let len = rest.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(rest.len());
+        self.pos += len;
+        rest[..len].to_ascii_uppercase()
+    }
+
+    fn geometry(&mut self) -> Result<Geometry> {
+        let start = self.pos;
+        let kind = self.word();
+
+        // Dimension, e.g. "POINT Z (1 2 3)" or "POINT ZM (1 2 3 4)"
+        let before_dim = self.pos;
+        match self.word().as_str() {
+            "Z" | "M" | "ZM" => {},
+            _ => self.pos = before_dim,
+        }
+
+        let before_empty = self.pos;
+        let empty = self.word() == "EMPTY";
+        if !empty {
+            self.pos = before_empty;
+        }
+
+        let geometry = match kind.as_str() {
+            "POINT" if empty => Geometry::Point(None),
+            "POINT" => {
+                self.expect('(')?;
+                let p = self.coords()?;
+                self.expect(')')?;
+                Geometry::Point(Some(p))
+            },
+            "MULTIPOINT" if empty => Geometry::MultiPoint(Vec::new()),
+            "MULTIPOINT" => Geometry::MultiPoint(self.multi_point()?),
+            "LINESTRING" if empty => Geometry::LineString(Vec::new()),
+            "LINESTRING" => Geometry::LineString(self.coords_list()?),
+            "POLYGON" if empty => Geometry::Polygon(Vec::new()),
+            "POLYGON" => Geometry::Polygon(self.polygon()?),
+            "MULTIPOLYGON" if empty => Geometry::MultiPolygon(Vec::new()),
+            "MULTIPOLYGON" => Geometry::MultiPolygon(self.list(Self::polygon)?),
+            _ => {
+                self.pos = start;
+                return Err(self.error("POINT, MULTIPOINT, LINESTRING, POLYGON or MULTIPOLYGON"));
+            },
+        };
+        Ok(geometry)
+    }
+
+    /// Parses `(item, item, ...)`.
+    fn list<T>(&mut self, mut item: impl FnMut(&mut Self) -> Result<T>) -> Result<Vec<T>> {
+        self.expect('(')?;
+        let mut items = vec![ item(self)? ];
+        while self.eat(',') {
+            items.push(item(self)?);
+        }
+        self.expect(')')?;
+        Ok(items)
+    }
+
+    fn number(&mut self) -> Result<f32> {
+        self.skip_whitespace();
+        let rest = &self.text[self.pos..];
+        let len = rest.find(|c: char| !(c.is_ascii_digit() || "+-.eE".contains(c))).unwrap_or(rest.len());
+        match rest[..len].parse() {
+            Ok(x) if len > 0 => {
+                self.pos += len;
+                Ok(x)
+            },
+            _ => Err(self.error("a number")),
+        }
+    }
+
+    /// Parses `x y`, followed by optional z and m coordinates that are ignored.
+    fn coords(&mut self) -> Result<Vec2> {
+        let x = self.number()?;
+        let y = self.number()?;
+        while let Some(c) = self.peek() {
+            if c == ',' || c == ')' {
+                break;
+            }
+            self.number()?;
+        }
+        Ok(Vec2::new(x, y))
+    }
+
+    fn coords_list(&mut self) -> Result<Vec<Vec2>> {
+        self.list(Self::coords)
+    }
+
+    /// Both `MULTIPOINT ((1 2), (3 4))` and `MULTIPOINT (1 2, 3 4)` are valid.
+    fn multi_point(&mut self) -> Result<Vec<Vec2>> {
+        self.list(|parser| {
+            if parser.eat('(') {
+                let p = parser.coords()?;
+                parser.expect(')')?;
+                Ok(p)
+            } else {
+                parser.coords()
+            }
+        })
+    }
+
+    fn polygon(&mut self) -> Result<Vec<Vec<Vec2>>> {
+        self.list(|parser| {
+            let mut ring = parser.coords_list()?;
+            if ring.len() > 1 && ring.first() == ring.last() {
+                ring.pop();
+            }
+            Ok(ring)
+        })
+    }
+}
+
+/// Writes `x y, x y, ...`, closing the ring if `ring` is true.
+fn write_coords(f: &mut fmt::Formatter, points: &[Vec2], ring: bool) -> fmt::Result {
+    let closing = if ring { points.first() } else { None };
+    for (idx, p) in points.iter().chain(closing).enumerate() {
+        if idx > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{} {}", p.x, p.y)?;
+    }
+    Ok(())
+}
+
+fn write_polygon(f: &mut fmt::Formatter, rings: &[Vec<Vec2>]) -> fmt::Result {
+    write!(f, "(")?;
+    for (idx, ring) in rings.iter().enumerate() {
+        if idx > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "(")?;
+        write_coords(f, ring, true)?;
+        write!(f, ")")?;
+    }
+    write!(f, ")")
+}
+
+impl fmt::Display for Geometry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Geometry::Point(None) => write!(f, "POINT EMPTY"),
+            Geometry::Point(Some(p)) => write!(f, "POINT ({} {})", p.x, p.y),
+            Geometry::MultiPoint(points) if points.is_empty() => write!(f, "MULTIPOINT EMPTY"),
+            Geometry::MultiPoint(points) => {
+                write!(f, "MULTIPOINT (")?;
+                for (idx, p) in points.iter().enumerate() {
+                    if idx > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "({} {})", p.x, p.y)?;
+                }
+                write!(f, ")")
+            },
+            Geometry::LineString(points) if points.is_empty() => write!(f, "LINESTRING EMPTY"),
+            Geometry::LineString(points) => {
+                write!(f, "LINESTRING (")?;
+                write_coords(f, points, false)?;
+                write!(f, ")")
+            },
+            Geometry::Polygon(rings) if rings.is_empty() => write!(f, "POLYGON EMPTY"),
+            Geometry::Polygon(rings) => {
+                write!(f, "POLYGON ")?;
+                write_polygon(f, rings)
+            },
+            Geometry::MultiPolygon(polygons) if polygons.is_empty() => write!(f, "MULTIPOLYGON EMPTY"),
+            Geometry::MultiPolygon(polygons) => {
+                write!(f, "MULTIPOLYGON (")?;
+                for (idx, rings) in polygons.iter().enumerate() {
+                    if idx > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write_polygon(f, rings)?;
+                }
+                write!(f, ")")
+            },
+        }
+    }
+}
+
+/// Returns the triangles of a triangulation as a multipolygon, `indices` containing 3 indices into `points` per triangle.
+pub fn triangles(points: &[Vec2], indices: &[usize]) -> Geometry {
+    let triangles = indices.chunks(3)
+                        .map(|tri| vec![ vec![ points[tri[0]], points[tri[1]], points[tri[2]] ] ])
+                        .collect();
+    Geometry::MultiPolygon(triangles)
+}